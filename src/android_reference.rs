@@ -11,6 +11,8 @@ use std::{
     time::{Duration, Instant},
 };
 
+use crate::external_command::resolve_command_path;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AndroidDevice {
     pub serial: String,
@@ -374,13 +376,6 @@ impl AndroidReferenceState {
     }
 }
 
-fn resolve_command_path(env_key: &str, fallback: &str) -> PathBuf {
-    env::var_os(env_key)
-        .filter(|value| !value.is_empty())
-        .map(PathBuf::from)
-        .unwrap_or_else(|| PathBuf::from(fallback))
-}
-
 fn env_number_or_default(env_key: &str, default: u32) -> anyhow::Result<u32> {
     match env::var(env_key) {
         Ok(value) if !value.trim().is_empty() => parse_number_with_suffix(value.trim(), env_key),
@@ -0,0 +1,185 @@
+//! Embedding API: render scenes from another Rust application on a wgpu
+//! device it already owns, without the window, the WS server or a
+//! subprocess.
+//!
+//! ```no_run
+//! use node_forge_render_server::api::{ParamUpdate, RenderServer, SceneDSL};
+//! # use rust_wgpu_fiber::eframe::wgpu;
+//! # fn demo(
+//! #     device: std::sync::Arc<wgpu::Device>,
+//! #     queue: std::sync::Arc<wgpu::Queue>,
+//! #     scene: SceneDSL,
+//! # ) -> anyhow::Result<()> {
+//! let mut server = RenderServer::new(device, queue);
+//! let image = server.render_scene(&scene)?;
+//! image.save("out.png")?;
+//!
+//! // Uniform-only changes reuse the built pipelines.
+//! let image = server.update_params(&[ParamUpdate {
+//!     node_id: "FloatInput_1".to_string(),
+//!     param: "value".to_string(),
+//!     value: serde_json::json!(0.5),
+//! }])?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use rust_wgpu_fiber::eframe::wgpu;
+
+use crate::{
+    app::apply_graph_uniform_updates_parts,
+    asset_store::AssetStore,
+    renderer::{
+        self, ShaderSpaceBuildOptions, ShaderSpaceBuildResult, ShaderSpaceBuilder,
+        ShaderSpacePresentationMode, graph_uniforms::compute_pipeline_signature_for_pass_bindings,
+    },
+};
+
+pub use crate::dsl::SceneDSL;
+pub use crate::renderer::WgslShaderBundle;
+pub use crate::ws::ParamUpdate;
+pub use image::RgbaImage;
+
+/// The scene currently built on the device.
+struct LoadedScene {
+    /// The scene as last rendered, including applied param updates.
+    scene: SceneDSL,
+    build: ShaderSpaceBuildResult,
+    pipeline_signature: [u8; 32],
+}
+
+/// Renders scenes on a caller-provided device. Keeps the last built scene,
+/// so [`RenderServer::update_params`] can skip the rebuild when only uniform
+/// values change, as the editor's `param_update` does.
+pub struct RenderServer {
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    adapter: Option<wgpu::Adapter>,
+    asset_store: Option<AssetStore>,
+    loaded: Option<LoadedScene>,
+}
+
+impl RenderServer {
+    pub fn new(device: Arc<wgpu::Device>, queue: Arc<wgpu::Queue>) -> Self {
+        Self {
+            device,
+            queue,
+            adapter: None,
+            asset_store: None,
+            loaded: None,
+        }
+    }
+
+    /// Lets planning use adapter-specific format and MSAA support.
+    pub fn with_adapter(mut self, adapter: wgpu::Adapter) -> Self {
+        self.adapter = Some(adapter);
+        self
+    }
+
+    /// Images and fonts referenced by scenes by asset id.
+    pub fn with_asset_store(mut self, store: AssetStore) -> Self {
+        self.asset_store = Some(store);
+        self
+    }
+
+    /// Builds `scene`, renders one frame and returns the display-encoded
+    /// (sRGB) output, as headless PNG export writes it.
+    pub fn render_scene(&mut self, scene: &SceneDSL) -> Result<RgbaImage> {
+        self.load(scene.clone())?;
+        self.render()
+    }
+
+    /// Sets node params on the last rendered scene and renders it again.
+    /// Changes that only touch uniform values are written to the existing
+    /// buffers; anything else rebuilds the scene.
+    pub fn update_params(&mut self, updates: &[ParamUpdate]) -> Result<RgbaImage> {
+        let loaded = self
+            .loaded
+            .as_mut()
+            .context("update_params requires a scene; call render_scene first")?;
+        if let Some(missing) = updates
+            .iter()
+            .find(|update| !loaded.scene.nodes.iter().any(|n| n.id == update.node_id))
+        {
+            return Err(anyhow!(
+                "param update references missing node '{}'",
+                missing.node_id
+            ));
+        }
+        for update in updates {
+            if let Some(node) = loaded
+                .scene
+                .nodes
+                .iter_mut()
+                .find(|n| n.id == update.node_id)
+            {
+                node.params
+                    .insert(update.param.clone(), update.value.clone());
+            }
+        }
+
+        let prepared = renderer::prepare_scene(&loaded.scene)?;
+        let signature = compute_pipeline_signature_for_pass_bindings(
+            &prepared.scene,
+            &loaded.build.pass_bindings,
+        );
+        if signature == loaded.pipeline_signature {
+            apply_graph_uniform_updates_parts(
+                &mut loaded.build.pass_bindings,
+                &mut loaded.build.shader_space,
+                &prepared.scene,
+            )?;
+        } else {
+            let scene = loaded.scene.clone();
+            self.load(scene)?;
+        }
+        self.render()
+    }
+
+    /// Generates the WGSL of every pass in `scene` without touching the
+    /// device.
+    pub fn build_wgsl(&self, scene: &SceneDSL) -> Result<Vec<(String, WgslShaderBundle)>> {
+        renderer::build_all_pass_wgsl_bundles_from_scene_with_assets(
+            scene,
+            self.asset_store.as_ref(),
+        )
+    }
+
+    fn load(&mut self, scene: SceneDSL) -> Result<()> {
+        // Drop the previous scene's textures before allocating the next.
+        self.loaded = None;
+        let mut builder = ShaderSpaceBuilder::new(self.device.clone(), self.queue.clone())
+            .with_options(ShaderSpaceBuildOptions {
+                presentation_mode: ShaderSpacePresentationMode::UiSdrDisplayEncode,
+                ..Default::default()
+            });
+        if let Some(adapter) = &self.adapter {
+            builder = builder.with_adapter(adapter.clone());
+        }
+        if let Some(store) = &self.asset_store {
+            builder = builder.with_asset_store(store.clone());
+        }
+        let build = builder.build(&scene)?;
+        self.loaded = Some(LoadedScene {
+            scene,
+            pipeline_signature: build.pipeline_signature,
+            build,
+        });
+        Ok(())
+    }
+
+    fn render(&self) -> Result<RgbaImage> {
+        let loaded = self.loaded.as_ref().context("no scene has been built")?;
+        loaded.build.shader_space.render();
+        let image = loaded
+            .build
+            .shader_space
+            .read_texture_rgba8(loaded.build.export_output_texture.as_str())
+            .map_err(|e| anyhow!("failed to read export texture: {e}"))?;
+        RgbaImage::from_raw(image.width, image.height, image.bytes)
+            .ok_or_else(|| anyhow!("export texture readback has an unexpected size"))
+    }
+}
@@ -1,8 +1,12 @@
 use rust_wgpu_fiber::{ResourceName, eframe::egui, shader_space::PassCaptureMode};
 
 use crate::app::{
+    canvas::state::CanvasFitMode,
     frame::commands::AppCommand,
-    types::{AnalysisTab, DiffMetricMode, QualifierChannel},
+    types::{
+        AnalysisTab, ClippingMode, ClippingStyle, DiffColorSpace, DiffMetricMode, DisplayChannel,
+        DisplayTransform, OnionSkinMode, QualifierChannel,
+    },
 };
 use crate::ui::resource_tree::PassDesignTarget;
 
@@ -15,13 +19,23 @@ pub enum CanvasAction {
     EnterPassDesign(PassDesignTarget),
     ExitPassDesign,
     ToggleHdrClamp,
+    ToggleFalseColor,
+    SetDisplayChannel(DisplayChannel),
+    SetViewExposure(f32),
+    SetViewGamma(f32),
+    SetDisplayTransform(DisplayTransform),
+    SetFalseColorEnabled(bool),
+    SetFalseColorEdge {
+        index: usize,
+        stops: f32,
+    },
     ToggleWireframe,
     TogglePause,
     ResetView {
         current_display_ppi: Option<f32>,
     },
-    CenterAt1x {
-        pixels_per_point: f32,
+    ApplyFitMode {
+        mode: CanvasFitMode,
         current_display_ppi: Option<f32>,
     },
     SetDisplayPpi {
@@ -36,10 +50,15 @@ pub enum CanvasAction {
     ResetReferenceOffset,
     SetReferenceOpacity(f32),
     ToggleReferenceMode,
+    SetOnionSkinMode(OnionSkinMode),
+    SetOnionSkinFlickerHz(f32),
     SetDiffMetricMode(DiffMetricMode),
+    SetDiffColorSpace(DiffColorSpace),
     SetAnalysisTab(AnalysisTab),
     SetClippingShadowThreshold(f32),
     SetClippingHighlightThreshold(f32),
+    SetClippingMode(ClippingMode),
+    SetClippingStyle(ClippingStyle),
     #[allow(dead_code)]
     ToggleQualifier,
     SetQualifierEnabled(bool),
@@ -69,6 +88,10 @@ pub enum CanvasAction {
         y: u32,
         rgba: [f32; 4],
     },
+    PickPixel {
+        x: u32,
+        y: u32,
+    },
     PollClipboardOp {
         now: f64,
     },
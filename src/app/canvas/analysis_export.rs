@@ -0,0 +1,132 @@
+//! Answers `get_analysis` by running the sidebar's scope renderers on demand
+//! and sending their raw data instead of the drawn scope.
+
+use anyhow::{Context, Result};
+use rust_wgpu_fiber::eframe::egui_wgpu;
+
+use crate::{
+    app::types::App,
+    ui,
+    ws::{self, AnalysisKind, AnalysisRequest},
+};
+
+pub fn answer_analysis_request(
+    app: &mut App,
+    render_state: &egui_wgpu::RenderState,
+    request: &AnalysisRequest,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+) -> Result<()> {
+    if request.kind == AnalysisKind::DiffStats {
+        let stats = app.canvas.analysis.diff_stats.context(
+            "no diff stats: load a reference image in diff mode to compare against the output",
+        )?;
+        ws::broadcast_diff_stats_analysis(
+            &app.core.ws_hub,
+            request_id,
+            client_id,
+            &ws::DiffStatsAnalysis {
+                metric: app.canvas.analysis.diff_metric_mode.label().to_string(),
+                color_space: app.canvas.analysis.diff_color_space.label().to_string(),
+                min: stats.min,
+                max: stats.max,
+                avg: stats.avg,
+                rms: stats.rms,
+                p95_abs: stats.p95_abs,
+                sample_count: stats.sample_count,
+                non_finite_count: stats.non_finite_count,
+            },
+        );
+        return Ok(());
+    }
+
+    let texture_name = request
+        .texture
+        .clone()
+        .unwrap_or_else(|| app.core.output_texture_name.as_str().to_string());
+    let texture = app
+        .core
+        .shader_space
+        .textures
+        .get(texture_name.as_str())
+        .with_context(|| format!("unknown texture: {texture_name}"))?;
+    let view = texture
+        .wgpu_texture_view
+        .as_ref()
+        .with_context(|| format!("{texture_name} has no texture view"))?;
+    let size = [
+        texture.wgpu_texture_desc.size.width,
+        texture.wgpu_texture_desc.size.height,
+    ];
+    let device = &render_state.device;
+    let queue = app.core.shader_space.queue.as_ref();
+    let analysis = &mut app.canvas.analysis;
+    let hub = &app.core.ws_hub;
+
+    // Scope renderers are shared with the sidebar, which redraws from its own
+    // source on the next analysis update.
+    match request.kind {
+        AnalysisKind::Histogram => {
+            let renderer = analysis
+                .histogram_renderer
+                .get_or_insert_with(|| ui::histogram::HistogramRenderer::new(device));
+            let range = renderer.update(device, queue, view, size);
+            let bins = renderer
+                .read_bins(device, queue)
+                .context("histogram readback failed")?;
+            ws::broadcast_histogram_analysis(
+                hub,
+                request_id,
+                client_id,
+                &texture_name,
+                range,
+                &bins,
+            );
+        }
+        AnalysisKind::Parade => {
+            let renderer = analysis
+                .parade_renderer
+                .get_or_insert_with(|| ui::parade::ParadeRenderer::new(device));
+            renderer.update(device, queue, view, size);
+            let bins = renderer
+                .read_bins(device, queue)
+                .context("parade readback failed")?;
+            ws::broadcast_scope_bins(
+                hub,
+                request_id,
+                client_id,
+                request.kind,
+                &texture_name,
+                [ui::parade::PLANES, ui::parade::X_BINS, ui::parade::Y_BINS],
+                &bins,
+            )?;
+        }
+        AnalysisKind::Vectorscope => {
+            let renderer = analysis
+                .vectorscope_renderer
+                .get_or_insert_with(|| ui::vectorscope::VectorscopeRenderer::new(device));
+            renderer.update(device, queue, view, size);
+            let bins = renderer
+                .read_bins(device, queue)
+                .context("vectorscope readback failed")?;
+            ws::broadcast_scope_bins(
+                hub,
+                request_id,
+                client_id,
+                request.kind,
+                &texture_name,
+                [
+                    1,
+                    ui::vectorscope::VECTORSCOPE_BINS,
+                    ui::vectorscope::VECTORSCOPE_BINS,
+                ],
+                &bins,
+            )?;
+        }
+        AnalysisKind::DiffStats => unreachable!("diff stats are answered above"),
+    }
+    analysis.last_histogram_request_key = None;
+    analysis.last_parade_request_key = None;
+    analysis.last_vectorscope_request_key = None;
+    Ok(())
+}
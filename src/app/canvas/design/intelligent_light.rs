@@ -1112,6 +1112,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let target = test_target();
         let node = scene
@@ -1195,6 +1196,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let target = test_target();
         let node = scene
@@ -1238,6 +1240,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let target = test_target();
         let node = scene
@@ -1328,6 +1331,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let node = scene
             .nodes
@@ -831,6 +831,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let locked = locked_mesh_gradient_ports(&scene, "mesh");
@@ -1,6 +1,9 @@
 use rust_wgpu_fiber::eframe::{egui, egui_wgpu, wgpu};
 
-use crate::app::{texture_bridge, types::App};
+use crate::app::{
+    texture_bridge,
+    types::{App, DisplayChannel},
+};
 
 use super::pixel_overlay;
 
@@ -13,13 +16,56 @@ fn is_hdr_clamp_effective(
 
 pub struct DisplayFrame {
     pub effective_resolution: [u32; 2],
+    pub using_preview: bool,
     pub compare_output_active: bool,
     pub display_texture_format: Option<wgpu::TextureFormat>,
     pub hdr_clamp_effective: bool,
+    pub false_color_active: bool,
+    pub channel_isolated: bool,
+    pub view_transform_active: bool,
     pub display_attachment: Option<egui::TextureId>,
     pub value_sampling_texture_name: String,
 }
 
+fn display_source_view(app: &App, texture_name: &str) -> Option<(wgpu::TextureView, [u32; 2])> {
+    let texture = app.core.shader_space.textures.get(texture_name)?;
+    let view = texture.wgpu_texture_view.as_ref()?;
+    Some((
+        view.clone(),
+        [
+            texture.wgpu_texture_desc.size.width,
+            texture.wgpu_texture_desc.size.height,
+        ],
+    ))
+}
+
+/// Registers a presentation renderer's output with egui on first use and
+/// re-points the existing id afterwards.
+fn attach_output_view(
+    render_state: &egui_wgpu::RenderState,
+    renderer: &mut egui_wgpu::Renderer,
+    view: &wgpu::TextureView,
+    filter: wgpu::FilterMode,
+    texture_id: &mut Option<egui::TextureId>,
+) -> Option<egui::TextureId> {
+    let sampler = texture_bridge::canvas_sampler_descriptor(filter);
+    if let Some(id) = *texture_id {
+        renderer.update_egui_texture_from_wgpu_texture_with_sampler_options(
+            &render_state.device,
+            view,
+            sampler,
+            id,
+        );
+    } else {
+        *texture_id = Some(renderer.register_native_texture_with_sampler_options(
+            &render_state.device,
+            view,
+            sampler,
+        ));
+    }
+    *texture_id
+}
+
 pub fn flush_deferred_frees(app: &mut App, renderer: &mut egui_wgpu::Renderer) {
     for id in app.canvas.display.deferred_texture_frees.drain(..) {
         renderer.free_texture(&id);
@@ -118,25 +164,122 @@ pub fn build_display_frame(
         app.canvas.display.color_attachment
     };
 
-    if hdr_clamp_effective && !compare_output_active {
-        let hdr_clamp_source = app
-            .core
-            .shader_space
-            .textures
-            .get(display_texture_name.as_str())
-            .and_then(|texture| {
-                texture.wgpu_texture_view.as_ref().map(|view| {
+    // Channel isolation also covers the diff output and replaces every other
+    // presentation pass while active. False color, then the view transform,
+    // read the unclamped source and replace the HDR clamp; none of them
+    // touches the diff.
+    let display_channel = app.canvas.display.display_channel;
+    let channel_isolated = display_channel != DisplayChannel::Rgb;
+    let false_color_active =
+        app.canvas.display.false_color_enabled && !compare_output_active && !channel_isolated;
+    let view_transform = app.canvas.display.view_transform;
+    let view_transform_active = !view_transform.is_identity()
+        && !compare_output_active
+        && !channel_isolated
+        && !false_color_active;
+    if channel_isolated {
+        let source = if compare_output_active {
+            app.canvas
+                .analysis
+                .diff_renderer
+                .as_ref()
+                .map(|diff_renderer| {
                     (
-                        view.clone(),
-                        [
-                            texture.wgpu_texture_desc.size.width,
-                            texture.wgpu_texture_desc.size.height,
-                        ],
+                        diff_renderer.output_view().clone(),
+                        diff_renderer.output_size(),
                     )
                 })
+        } else {
+            display_source_view(app, display_texture_name.as_str())
+        };
+        if let Some((source_view, source_size)) = source {
+            let channel_renderer = app.canvas.display.channel_renderer.get_or_insert_with(|| {
+                crate::ui::channel_isolate::ChannelIsolateRenderer::new(
+                    &render_state.device,
+                    source_size,
+                )
             });
-
-        if let Some((source_view, source_size)) = hdr_clamp_source {
+            channel_renderer.update(
+                &render_state.device,
+                &render_state.queue,
+                &source_view,
+                source_size,
+                display_channel,
+            );
+            display_attachment = attach_output_view(
+                render_state,
+                renderer,
+                channel_renderer.output_view(),
+                app.canvas.display.texture_filter,
+                &mut app.canvas.display.channel_texture_id,
+            )
+            .or(display_attachment);
+        }
+    } else if false_color_active {
+        if let Some((source_view, source_size)) =
+            display_source_view(app, display_texture_name.as_str())
+        {
+            let settings = app.canvas.display.false_color_settings;
+            let false_color_renderer =
+                app.canvas
+                    .display
+                    .false_color_renderer
+                    .get_or_insert_with(|| {
+                        crate::ui::false_color::FalseColorRenderer::new(
+                            &render_state.device,
+                            source_size,
+                        )
+                    });
+            false_color_renderer.update(
+                &render_state.device,
+                &render_state.queue,
+                &source_view,
+                source_size,
+                &settings,
+            );
+            display_attachment = attach_output_view(
+                render_state,
+                renderer,
+                false_color_renderer.output_view(),
+                app.canvas.display.texture_filter,
+                &mut app.canvas.display.false_color_texture_id,
+            )
+            .or(display_attachment);
+        }
+    } else if view_transform_active {
+        if let Some((source_view, source_size)) =
+            display_source_view(app, display_texture_name.as_str())
+        {
+            let view_transform_renderer = app
+                .canvas
+                .display
+                .view_transform_renderer
+                .get_or_insert_with(|| {
+                    crate::ui::display_transform::DisplayTransformRenderer::new(
+                        &render_state.device,
+                        source_size,
+                    )
+                });
+            view_transform_renderer.update(
+                &render_state.device,
+                &render_state.queue,
+                &source_view,
+                source_size,
+                &view_transform,
+            );
+            display_attachment = attach_output_view(
+                render_state,
+                renderer,
+                view_transform_renderer.output_view(),
+                app.canvas.display.texture_filter,
+                &mut app.canvas.display.view_transform_texture_id,
+            )
+            .or(display_attachment);
+        }
+    } else if hdr_clamp_effective && !compare_output_active {
+        if let Some((source_view, source_size)) =
+            display_source_view(app, display_texture_name.as_str())
+        {
             let clamp_renderer = app
                 .canvas
                 .display
@@ -150,35 +293,26 @@ pub fn build_display_frame(
                 &source_view,
                 source_size,
             );
-
-            let sampler =
-                texture_bridge::canvas_sampler_descriptor(app.canvas.display.texture_filter);
-            if let Some(id) = app.canvas.display.hdr_clamp_texture_id {
-                renderer.update_egui_texture_from_wgpu_texture_with_sampler_options(
-                    &render_state.device,
-                    clamp_renderer.output_view(),
-                    sampler,
-                    id,
-                );
-            } else {
-                app.canvas.display.hdr_clamp_texture_id =
-                    Some(renderer.register_native_texture_with_sampler_options(
-                        &render_state.device,
-                        clamp_renderer.output_view(),
-                        sampler,
-                    ));
-            }
-            if let Some(id) = app.canvas.display.hdr_clamp_texture_id {
-                display_attachment = Some(id);
-            }
+            display_attachment = attach_output_view(
+                render_state,
+                renderer,
+                clamp_renderer.output_view(),
+                app.canvas.display.texture_filter,
+                &mut app.canvas.display.hdr_clamp_texture_id,
+            )
+            .or(display_attachment);
         }
     }
 
     DisplayFrame {
         effective_resolution,
+        using_preview,
         compare_output_active,
         display_texture_format,
         hdr_clamp_effective,
+        false_color_active,
+        channel_isolated,
+        view_transform_active,
         display_attachment,
         value_sampling_texture_name: display_texture_name,
     }
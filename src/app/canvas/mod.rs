@@ -1,7 +1,9 @@
 pub mod actions;
+pub mod analysis_export;
 pub mod design;
 pub mod display;
 pub mod draw_capture;
+pub mod onion_skin;
 pub mod ops;
 pub mod pixel_overlay;
 pub mod presenter;
@@ -9,6 +11,7 @@ pub mod reducer;
 pub mod reference;
 pub mod state;
 pub mod viewport;
+pub mod zoom_crop;
 
 use rust_wgpu_fiber::eframe::{egui, egui_wgpu};
 
@@ -34,6 +37,10 @@ pub(super) fn sync_android_reference_frame(
     reference::sync_android_reference_frame(app, ctx, render_state);
 }
 
+pub(super) fn capture_onion_skin_previous(app: &mut App, ctx: &egui::Context) {
+    onion_skin::capture_previous_output(app, ctx);
+}
+
 pub(super) fn clear_reference(app: &mut App) {
     reference::clear_reference(app);
 }
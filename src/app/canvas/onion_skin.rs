@@ -0,0 +1,161 @@
+use rust_wgpu_fiber::eframe::{
+    egui::{self, Color32, Rect},
+    wgpu,
+};
+
+use crate::app::types::{App, OnionSkinMode};
+
+const BLEND_ALPHA: u8 = 128;
+
+pub struct CanvasOnionSkinState {
+    pub mode: OnionSkinMode,
+    pub flicker_hz: f32,
+    /// Output of the scene version that was on screen before the latest update.
+    pub previous: Option<egui::TextureHandle>,
+}
+
+impl Default for CanvasOnionSkinState {
+    fn default() -> Self {
+        Self {
+            mode: OnionSkinMode::Off,
+            flicker_hz: OnionSkinMode::DEFAULT_FLICKER_HZ,
+            previous: None,
+        }
+    }
+}
+
+pub fn set_mode(app: &mut App, mode: OnionSkinMode) {
+    app.canvas.onion_skin.mode = mode;
+    if matches!(mode, OnionSkinMode::Off) {
+        app.canvas.onion_skin.previous = None;
+    }
+}
+
+pub fn set_flicker_hz(app: &mut App, hz: f32) {
+    if hz.is_finite() {
+        app.canvas.onion_skin.flicker_hz =
+            hz.clamp(OnionSkinMode::MIN_FLICKER_HZ, OnionSkinMode::MAX_FLICKER_HZ);
+    }
+}
+
+/// Snapshots the current output texture before a scene update replaces it.
+/// Only runs while onion skinning is enabled; the readback is a full-frame copy.
+pub fn capture_previous_output(app: &mut App, ctx: &egui::Context) {
+    if matches!(app.canvas.onion_skin.mode, OnionSkinMode::Off) {
+        return;
+    }
+    let texture_name = app.core.output_texture_name.as_str();
+    let Some(info) = app.core.shader_space.texture_info(texture_name) else {
+        return;
+    };
+    let size = [info.size.width as usize, info.size.height as usize];
+    let pixels: Option<Vec<Color32>> = match info.format {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => app
+            .core
+            .shader_space
+            .read_texture_rgba8(texture_name)
+            .ok()
+            .map(|image| {
+                image
+                    .bytes
+                    .chunks_exact(4)
+                    .map(|px| Color32::from_rgba_premultiplied(px[0], px[1], px[2], px[3]))
+                    .collect()
+            }),
+        wgpu::TextureFormat::Rgba16Float => app
+            .core
+            .shader_space
+            .read_texture_rgba16f(texture_name)
+            .ok()
+            .map(|image| {
+                image
+                    .channels
+                    .chunks_exact(4)
+                    .map(|px| {
+                        let alpha = px[3].clamp(0.0, 1.0);
+                        Color32::from_rgba_premultiplied(
+                            egui::ecolor::gamma_u8_from_linear_f32(px[0].clamp(0.0, alpha)),
+                            egui::ecolor::gamma_u8_from_linear_f32(px[1].clamp(0.0, alpha)),
+                            egui::ecolor::gamma_u8_from_linear_f32(px[2].clamp(0.0, alpha)),
+                            (alpha * 255.0).round() as u8,
+                        )
+                    })
+                    .collect()
+            }),
+        other => {
+            eprintln!("[onion-skin] unsupported output format for snapshot: {other:?}");
+            None
+        }
+    };
+    let Some(pixels) = pixels.filter(|pixels| pixels.len() == size[0] * size[1]) else {
+        return;
+    };
+
+    let image = egui::ColorImage {
+        size,
+        pixels,
+        source_size: egui::vec2(size[0] as f32, size[1] as f32),
+    };
+    let options = if matches!(app.canvas.display.texture_filter, wgpu::FilterMode::Nearest) {
+        egui::TextureOptions::NEAREST
+    } else {
+        egui::TextureOptions::LINEAR
+    };
+    match app.canvas.onion_skin.previous.as_mut() {
+        Some(handle) => handle.set(image, options),
+        None => {
+            app.canvas.onion_skin.previous =
+                Some(ctx.load_texture("ui.canvas.onion_skin.previous", image, options));
+        }
+    }
+}
+
+/// Whether the flicker cycle currently shows the previous version. One full
+/// A/B cycle takes `1 / hz` seconds.
+pub fn flicker_shows_previous(now: f64, hz: f32) -> bool {
+    if !hz.is_finite() || hz <= 0.0 {
+        return false;
+    }
+    (now * hz as f64 * 2.0).floor() as i64 % 2 == 1
+}
+
+pub fn draw(ui: &egui::Ui, ctx: &egui::Context, app: &App, canvas_rect: Rect, uv: Rect, now: f64) {
+    let Some(previous) = app.canvas.onion_skin.previous.as_ref() else {
+        return;
+    };
+    let tint = match app.canvas.onion_skin.mode {
+        OnionSkinMode::Off => return,
+        OnionSkinMode::Blend => Color32::from_white_alpha(BLEND_ALPHA),
+        OnionSkinMode::Flicker => {
+            let hz = app.canvas.onion_skin.flicker_hz;
+            ctx.request_repaint_after(std::time::Duration::from_secs_f32(0.5 / hz.max(0.001)));
+            if !flicker_shows_previous(now, hz) {
+                return;
+            }
+            Color32::WHITE
+        }
+    };
+    ui.painter().add(
+        egui::epaint::RectShape::filled(canvas_rect, egui::CornerRadius::ZERO, tint)
+            .with_texture(previous.id(), uv),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::flicker_shows_previous;
+
+    #[test]
+    fn flicker_alternates_twice_per_cycle() {
+        assert!(!flicker_shows_previous(0.0, 2.0));
+        assert!(flicker_shows_previous(0.3, 2.0));
+        assert!(!flicker_shows_previous(0.5, 2.0));
+        assert!(flicker_shows_previous(0.8, 2.0));
+    }
+
+    #[test]
+    fn flicker_is_disabled_for_invalid_rate() {
+        assert!(!flicker_shows_previous(0.3, 0.0));
+        assert!(!flicker_shows_previous(0.3, f32::NAN));
+    }
+}
@@ -4,12 +4,13 @@ use std::{
     sync::Arc,
 };
 
+use anyhow::{Context, Result, bail};
 use rust_wgpu_fiber::eframe::{
     egui::{self, Color32, Rect, pos2},
     wgpu,
 };
 
-use crate::app::types::{App, DiffMetricMode, RefImageMode, RefImageState};
+use crate::app::types::{App, DiffColorSpace, DiffMetricMode, RefImageMode, RefImageState};
 
 const PIXEL_OVERLAY_MIN_ZOOM: f32 = 48.0;
 const PIXEL_OVERLAY_REFERENCE_ZOOM: f32 = 100.0;
@@ -167,6 +168,89 @@ pub fn get_or_refresh_cache(
     updated
 }
 
+/// Float RGBA of every texel in `rect`, row-major, read through the same
+/// cache the click-to-sample readout uses. 8-bit textures come back as their
+/// stored (display-encoded) values; float textures as linear premultiplied.
+pub fn read_texture_rect(
+    app: &mut App,
+    texture_name: &str,
+    rect: crate::ws::PixelRect,
+) -> Result<Vec<[f32; 4]>> {
+    let info = app
+        .core
+        .shader_space
+        .texture_info(texture_name)
+        .with_context(|| format!("unknown texture: {texture_name}"))?;
+    let (width, height) = (info.size.width, info.size.height);
+    if u64::from(rect.x) + u64::from(rect.width) > u64::from(width)
+        || u64::from(rect.y) + u64::from(rect.height) > u64::from(height)
+    {
+        bail!("rect is outside the {width}x{height} texture {texture_name}");
+    }
+    let cache = get_or_refresh_cache(app, texture_name, width, height, info.format);
+    match &cache.readback {
+        PixelOverlayReadback::Unavailable => bail!("failed to read back {texture_name}"),
+        PixelOverlayReadback::UnsupportedFormat => {
+            bail!(
+                "cannot sample {texture_name}: unsupported format {:?}",
+                cache.format
+            )
+        }
+        PixelOverlayReadback::Rgba8(_) | PixelOverlayReadback::Rgba16f(_) => {}
+    }
+    (rect.y..rect.y + rect.height)
+        .flat_map(|y| (rect.x..rect.x + rect.width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            sample_overlay_pixel(&cache, x, y)
+                .with_context(|| format!("failed to sample {texture_name} at ({x}, {y})"))
+        })
+        .collect()
+}
+
+/// The whole texture as display-encoded RGBA8 for thumbnails. 8-bit
+/// textures are returned as stored; float textures get the sRGB transfer on
+/// clamped color with alpha kept linear, like the 8-bit export encode.
+pub fn read_texture_display_rgba8(app: &App, texture_name: &str) -> Result<image::RgbaImage> {
+    let info = app
+        .core
+        .shader_space
+        .texture_info(texture_name)
+        .with_context(|| format!("unknown texture: {texture_name}"))?;
+    let (width, height) = (info.size.width, info.size.height);
+    let bytes = match info.format {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => {
+            app.core
+                .shader_space
+                .read_texture_rgba8(texture_name)
+                .map_err(|e| anyhow::anyhow!("failed to read back {texture_name}: {e}"))?
+                .bytes
+        }
+        wgpu::TextureFormat::Rgba16Float => {
+            let image = app
+                .core
+                .shader_space
+                .read_texture_rgba16f(texture_name)
+                .map_err(|e| anyhow::anyhow!("failed to read back {texture_name}: {e}"))?;
+            let quantize = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+            image
+                .channels
+                .chunks_exact(4)
+                .flat_map(|px| {
+                    [
+                        quantize(crate::color::linear_to_srgb(px[0].clamp(0.0, 1.0))),
+                        quantize(crate::color::linear_to_srgb(px[1].clamp(0.0, 1.0))),
+                        quantize(crate::color::linear_to_srgb(px[2].clamp(0.0, 1.0))),
+                        quantize(px[3]),
+                    ]
+                })
+                .collect()
+        }
+        other => bail!("cannot preview {texture_name}: unsupported format {other:?}"),
+    };
+    image::RgbaImage::from_raw(width, height, bytes)
+        .with_context(|| format!("{texture_name} readback has an unexpected size"))
+}
+
 pub fn clear_cache(app: &mut App) {
     app.canvas.display.pixel_overlay_cache = None;
     app.canvas.display.pixel_overlay_last_request_key = None;
@@ -383,12 +467,14 @@ pub fn format_diff_stat_value(value: f32) -> String {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn sample_value_pixel(
     base_cache: &PixelOverlayCache,
     x: u32,
     y: u32,
     reference: Option<ValueSamplingReference<'_>>,
     diff_metric_mode: DiffMetricMode,
+    diff_color_space: DiffColorSpace,
     diff_output_active: bool,
     clamp_output: bool,
 ) -> Option<[f32; 4]> {
@@ -410,7 +496,11 @@ pub fn sample_value_pixel(
         }
         RefImageMode::Diff => {
             if diff_output_active {
-                let mut out = compute_diff_metric_rgba(base_rgba, reference_rgba, diff_metric_mode);
+                let mut out = compute_diff_metric_rgba(
+                    diff_color_space.from_linear_rgba(base_rgba),
+                    diff_color_space.from_linear_rgba(reference_rgba),
+                    diff_metric_mode,
+                );
                 if clamp_output {
                     out = out.map(|v| v.clamp(0.0, 1.0));
                 }
@@ -432,6 +522,7 @@ pub fn draw_pixel_overlay(
     cache: Option<&PixelOverlayCache>,
     reference: Option<ValueSamplingReference<'_>>,
     diff_metric_mode: DiffMetricMode,
+    diff_color_space: DiffColorSpace,
     diff_output_active: bool,
     clamp_output: bool,
 ) {
@@ -516,6 +607,7 @@ pub fn draw_pixel_overlay(
                 y as u32,
                 reference,
                 diff_metric_mode,
+                diff_color_space,
                 diff_output_active,
                 clamp_output,
             ) else {
@@ -554,7 +646,7 @@ pub fn draw_pixel_overlay(
 #[cfg(test)]
 mod tests {
     use super::{
-        DiffMetricMode, PixelOverlayCache, PixelOverlayReadback, RefImageMode,
+        DiffColorSpace, DiffMetricMode, PixelOverlayCache, PixelOverlayReadback, RefImageMode,
         ValueSamplingReference, compose_reference_over_base, compute_diff_metric_rgba,
         format_diff_stat_value, format_overlay_channel, rgba8_to_rgba_f32, sample_rgba8_pixel,
         sample_rgba16f_pixel, sample_rgba16unorm_pixel, sample_value_pixel,
@@ -667,6 +759,7 @@ mod tests {
             0,
             Some(reference),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             false,
             false,
         )
@@ -698,6 +791,7 @@ mod tests {
             0,
             Some(reference),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             false,
             false,
         )
@@ -724,6 +818,7 @@ mod tests {
             0,
             Some(reference),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             false,
             false,
         )
@@ -750,6 +845,7 @@ mod tests {
             0,
             Some(reference),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             true,
             false,
         )
@@ -773,6 +869,7 @@ mod tests {
             0,
             Some(reference),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             true,
             false,
         )
@@ -796,6 +893,7 @@ mod tests {
             0,
             Some(reference),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             false,
             false,
         )
@@ -845,4 +943,33 @@ mod tests {
         assert_eq!(format_diff_stat_value(0.25), "0.2500");
         assert!(format_diff_stat_value(1.0e-5).contains('e'));
     }
+
+    #[test]
+    fn diff_mode_compares_in_selected_color_space() {
+        let cache = make_rgba8_cache(1, 1, vec![0, 0, 0, 255]);
+        let reference = ValueSamplingReference {
+            mode: RefImageMode::Diff,
+            offset_px: [0, 0],
+            size: [1, 1],
+            opacity: 1.0,
+            linear_premul_rgba: &[0.18, 0.18, 0.18, 1.0],
+        };
+        let sample = |space| {
+            sample_value_pixel(
+                &cache,
+                0,
+                0,
+                Some(reference),
+                DiffMetricMode::AE,
+                space,
+                true,
+                false,
+            )
+            .unwrap()
+        };
+        assert_rgba_approx_eq(sample(DiffColorSpace::Linear), [0.18, 0.18, 0.18, 1.0]);
+        let srgb = sample(DiffColorSpace::Srgb);
+        assert!((srgb[0] - 0.4614).abs() < 1e-3, "{srgb:?}");
+        assert_eq!(srgb[3], 1.0);
+    }
 }
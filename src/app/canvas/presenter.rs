@@ -14,21 +14,26 @@ use crate::{
             actions::{CanvasAction, CanvasFrameResult},
             design,
             display::{self, DisplayFrame},
-            ops,
+            onion_skin, ops,
             pixel_overlay::{
                 self, draw_pixel_overlay, format_diff_stat_value,
                 value_sampling_reference_from_state,
             },
-            reducer, reference, viewport,
+            reducer, reference,
+            state::CanvasFitMode,
+            viewport, zoom_crop,
         },
         display_metrics,
         frame::commands::AppCommand,
         input_scope, matrix_render, texture_bridge,
-        types::{App, RefImageMode, RefImageSource, ViewportOperationIndicatorVisual},
+        types::{
+            App, DisplayChannel, OnionSkinMode, RefImageMode, RefImageSource,
+            ViewportOperationIndicatorVisual,
+        },
         window_mode::WindowModeFrame,
     },
     ui::{
-        design_tokens, pass_debug_window,
+        clipping_map, design_tokens, pass_debug_window,
         viewport_indicators::{
             ViewportIndicator, ViewportIndicatorEntry, ViewportIndicatorInteraction,
             ViewportIndicatorKind,
@@ -38,9 +43,16 @@ use crate::{
 
 const ORDER_OPERATION: i32 = 0;
 const ORDER_RENDER_FPS: i32 = 1;
+const ORDER_ZOOM: i32 = 2;
 const ORDER_PAUSE: i32 = 10;
 const ORDER_TEMPORARY_OUTPUT: i32 = 12;
+const ORDER_ONION_SKIN: i32 = 13;
+const ORDER_CHANNEL_DEBUG: i32 = 14;
 const ORDER_HDR: i32 = 15;
+const ORDER_NON_FINITE: i32 = 16;
+const ORDER_FALSE_COLOR: i32 = 17;
+const ORDER_DISPLAY_CHANNEL: i32 = 18;
+const ORDER_VIEW_TRANSFORM: i32 = 19;
 const ORDER_SAMPLING: i32 = 20;
 const ORDER_REF_ALPHA: i32 = 21;
 const ORDER_CLIPPING: i32 = 30;
@@ -48,6 +60,46 @@ const ORDER_QUALIFIER: i32 = 31;
 const ORDER_STATS: i32 = 40;
 const KEY_TOGGLE_SAMPLING: egui::Key = egui::Key::N;
 const KEY_TOGGLE_REFERENCE_ALPHA: egui::Key = egui::Key::P;
+const KEY_TOGGLE_FALSE_COLOR: egui::Key = egui::Key::E;
+const KEYS_DISPLAY_CHANNEL: [egui::Key; 5] = [
+    egui::Key::Num1,
+    egui::Key::Num2,
+    egui::Key::Num3,
+    egui::Key::Num4,
+    egui::Key::Num5,
+];
+
+/// Bare 1-5 pick the display channel; modified digits belong to the fit-mode
+/// and reference-opacity shortcuts.
+fn display_channel_shortcut(input: &egui::InputState) -> Option<DisplayChannel> {
+    if input.modifiers.command || input.modifiers.shift || input.modifiers.alt {
+        return None;
+    }
+    KEYS_DISPLAY_CHANNEL
+        .iter()
+        .zip(DisplayChannel::ALL)
+        .find(|(key, _)| input.key_pressed(**key))
+        .map(|(_, channel)| channel)
+}
+
+/// Shift+digit arrives as the shifted symbol on most layouts, so the physical
+/// key is matched too.
+fn shift_digit_pressed(input: &egui::InputState, digit: egui::Key) -> bool {
+    input.events.iter().any(|event| {
+        matches!(
+            event,
+            egui::Event::Key {
+                key,
+                physical_key,
+                pressed: true,
+                repeat: false,
+                modifiers,
+            } if modifiers.shift
+                && !modifiers.command
+                && (*key == digit || *physical_key == Some(digit))
+        )
+    })
+}
 
 fn with_alpha(color: Color32, alpha: f32) -> Color32 {
     let a = ((color.a() as f32) * alpha.clamp(0.0, 1.0)).round() as u8;
@@ -219,6 +271,16 @@ fn reference_display_texture_id(
     Some(reference.texture.id())
 }
 
+/// Keeps frames coming while zebra stripes crawl, one per stripe step.
+fn request_zebra_repaint(ctx: &egui::Context, app: &App) {
+    if app.canvas.analysis.clip_enabled && app.canvas.analysis.clipping_settings.style.draws_zebra()
+    {
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(
+            1.0 / clipping_map::ZEBRA_SPEED_PX_PER_SEC,
+        ));
+    }
+}
+
 fn draw_display_layers(
     ui: &egui::Ui,
     app: &mut App,
@@ -236,6 +298,15 @@ fn draw_display_layers(
                 .with_texture(tex_id, uv),
         );
     }
+    if !display_frame.using_preview
+        && !display_frame.compare_output_active
+        && !display_frame.hdr_clamp_effective
+        && !display_frame.false_color_active
+        && !display_frame.channel_isolated
+        && !display_frame.view_transform_active
+    {
+        zoom_crop::sync_and_draw(ui, app, render_state, renderer, canvas_rect, image_rect);
+    }
 
     if !display_frame.compare_output_active
         && app.canvas.reference.ref_image.is_some()
@@ -277,6 +348,7 @@ fn draw_display_layers(
     if app.canvas.analysis.clip_enabled
         && let Some(clipping_texture_id) = app.canvas.analysis.clipping_texture_id
     {
+        request_zebra_repaint(ui.ctx(), app);
         ui.painter().add(
             egui::epaint::RectShape::filled(canvas_rect, rounding, Color32::WHITE)
                 .with_texture(clipping_texture_id, uv),
@@ -387,6 +459,31 @@ fn draw_operation_indicators(
         )
     });
 
+    let pixels_per_point = ctx.pixels_per_point();
+    app.canvas
+        .viewport_indicator_manager
+        .register(ViewportIndicatorEntry {
+            interaction: ViewportIndicatorInteraction::HoverOnly,
+            callback_id: None,
+            ..ViewportIndicatorEntry::text_badge_right_aligned_mono(
+                "zoom",
+                ORDER_ZOOM,
+                true,
+                format!(
+                    "{:.0}%",
+                    viewport::device_pixel_zoom(app.canvas.viewport.zoom, pixels_per_point) * 100.0
+                ),
+                format!(
+                    "Effective zoom in device pixels (scale factor {pixels_per_point:.2}x). {}",
+                    CanvasFitMode::ALL
+                        .iter()
+                        .map(|mode| format!("{}: {}", mode.label(), mode.shortcut_label()))
+                        .collect::<Vec<_>>()
+                        .join(" • ")
+                ),
+            )
+        });
+
     app.canvas
         .viewport_indicator_manager
         .register(ViewportIndicatorEntry {
@@ -405,6 +502,43 @@ fn draw_operation_indicators(
             )
         });
 
+    app.canvas
+        .viewport_indicator_manager
+        .register(ViewportIndicatorEntry {
+            interaction: ViewportIndicatorInteraction::HoverOnly,
+            callback_id: None,
+            ..ViewportIndicatorEntry::compact(
+                "onion_skin",
+                ORDER_ONION_SKIN,
+                app.canvas.onion_skin.previous.is_some()
+                    && !matches!(app.canvas.onion_skin.mode, OnionSkinMode::Off),
+                ViewportIndicator {
+                    icon: "OS",
+                    tooltip: "Onion skin: previous scene version shown over the current output",
+                    kind: ViewportIndicatorKind::Text,
+                    strikethrough: false,
+                },
+            )
+        });
+
+    app.canvas
+        .viewport_indicator_manager
+        .register(ViewportIndicatorEntry {
+            interaction: ViewportIndicatorInteraction::HoverOnly,
+            callback_id: None,
+            ..ViewportIndicatorEntry::compact(
+                "channel_debug",
+                ORDER_CHANNEL_DEBUG,
+                app.shell.channel_debug != crate::renderer::ChannelDebugMode::Off,
+                ViewportIndicator {
+                    icon: "CH",
+                    tooltip: "Channel debug: draw passes output a coordinate visualization",
+                    kind: ViewportIndicatorKind::Text,
+                    strikethrough: false,
+                },
+            )
+        });
+
     app.canvas
         .viewport_indicator_manager
         .register(ViewportIndicatorEntry {
@@ -423,6 +557,62 @@ fn draw_operation_indicators(
             )
         });
 
+    app.canvas
+        .viewport_indicator_manager
+        .register(ViewportIndicatorEntry {
+            interaction: ViewportIndicatorInteraction::HoverOnly,
+            callback_id: None,
+            ..ViewportIndicatorEntry::compact(
+                "false_color",
+                ORDER_FALSE_COLOR,
+                display_frame.false_color_active,
+                ViewportIndicator {
+                    icon: "FC",
+                    tooltip: "False color: luminance bands in stops from 18% grey (press E to toggle)",
+                    kind: ViewportIndicatorKind::Text,
+                    strikethrough: false,
+                },
+            )
+        });
+
+    let view_transform = app.canvas.display.view_transform;
+    app.canvas
+        .viewport_indicator_manager
+        .register(ViewportIndicatorEntry {
+            interaction: ViewportIndicatorInteraction::HoverOnly,
+            callback_id: None,
+            ..ViewportIndicatorEntry::text_badge(
+                "view_transform",
+                ORDER_VIEW_TRANSFORM,
+                display_frame.view_transform_active,
+                format!(
+                    "{:+.1} EV  γ{:.2}  {}",
+                    view_transform.exposure_stops,
+                    view_transform.gamma,
+                    view_transform.transform.label()
+                ),
+                "Viewer exposure / gamma / display transform (canvas only; file output is unaffected)",
+            )
+        });
+
+    app.canvas
+        .viewport_indicator_manager
+        .register(ViewportIndicatorEntry {
+            interaction: ViewportIndicatorInteraction::HoverOnly,
+            callback_id: None,
+            ..ViewportIndicatorEntry::compact(
+                "display_channel",
+                ORDER_DISPLAY_CHANNEL,
+                display_frame.channel_isolated,
+                ViewportIndicator {
+                    icon: app.canvas.display.display_channel.short_label(),
+                    tooltip: "Single channel view: 2-4 show R/G/B as grayscale, 5 shows alpha over a checkerboard (press 1 for RGB)",
+                    kind: ViewportIndicatorKind::Text,
+                    strikethrough: false,
+                },
+            )
+        });
+
     app.canvas
         .viewport_indicator_manager
         .register(ViewportIndicatorEntry {
@@ -500,6 +690,22 @@ fn draw_operation_indicators(
             )
         });
 
+    let non_finite_count = app.runtime.non_finite_count.unwrap_or(0);
+    app.canvas
+        .viewport_indicator_manager
+        .register(ViewportIndicatorEntry {
+            interaction: ViewportIndicatorInteraction::HoverOnly,
+            callback_id: None,
+            ..ViewportIndicatorEntry::text_badge(
+                "non_finite",
+                ORDER_NON_FINITE,
+                non_finite_count > 0,
+                format!("NaN {non_finite_count}"),
+                "Safe mode replaced NaN/Inf fragments with the sentinel colour \
+                 (fragments in the last frame, summed over sanitized passes)",
+            )
+        });
+
     if let Some(stats) = app.canvas.analysis.diff_stats {
         app.canvas
             .viewport_indicator_manager
@@ -973,6 +1179,15 @@ fn maybe_sample_clicked_pixel(
     if x >= display_frame.effective_resolution[0] || y >= display_frame.effective_resolution[1] {
         return;
     }
+    if app.shell.pick_ids && !display_frame.using_preview {
+        apply_action(
+            &mut CanvasFrameResult::default(),
+            app,
+            render_state,
+            renderer,
+            CanvasAction::PickPixel { x, y },
+        );
+    }
 
     let mut sample_cache: Option<Arc<pixel_overlay::PixelOverlayCache>> =
         value_sample_cache.cloned().map(Arc::new);
@@ -1004,6 +1219,7 @@ fn maybe_sample_clicked_pixel(
                 .as_ref()
                 .map(value_sampling_reference_from_state),
             app.canvas.analysis.diff_metric_mode,
+            app.canvas.analysis.diff_color_space,
             display_frame.compare_output_active,
             app.canvas.display.hdr_preview_clamp_enabled,
         )
@@ -1034,15 +1250,32 @@ pub fn show_canvas(
     let debug_shortcuts_enabled = input_scope::debug_shortcuts_enabled(app, ctx);
 
     if debug_shortcuts_enabled
-        && ctx.input(|i| i.key_pressed(egui::Key::Num1) && i.modifiers.command)
+        && let Some(mode) = ctx.input(|i| {
+            if !i.modifiers.command || i.modifiers.alt {
+                return None;
+            }
+            if i.key_pressed(egui::Key::Num0) {
+                Some(if i.modifiers.shift {
+                    CanvasFitMode::Fill
+                } else {
+                    CanvasFitMode::Fit
+                })
+            } else if i.key_pressed(egui::Key::Num1) {
+                Some(CanvasFitMode::DevicePixels1x)
+            } else if i.key_pressed(egui::Key::Num2) {
+                Some(CanvasFitMode::DevicePixels2x)
+            } else {
+                None
+            }
+        })
     {
         apply_action(
             &mut frame_result,
             app,
             render_state,
             renderer,
-            CanvasAction::CenterAt1x {
-                pixels_per_point: current_display_metrics.pixels_per_point,
+            CanvasAction::ApplyFitMode {
+                mode,
                 current_display_ppi: current_display_metrics.display_ppi,
             },
         );
@@ -1119,6 +1352,15 @@ pub fn show_canvas(
                 CanvasAction::ToggleClipping,
             );
         }
+        if ctx.input(|i| i.key_pressed(KEY_TOGGLE_FALSE_COLOR)) {
+            apply_action(
+                &mut frame_result,
+                app,
+                render_state,
+                renderer,
+                CanvasAction::ToggleFalseColor,
+            );
+        }
         if ctx.input(|i| i.key_pressed(egui::Key::A)) {
             apply_action(
                 &mut frame_result,
@@ -1128,7 +1370,16 @@ pub fn show_canvas(
                 CanvasAction::ResetReferenceOffset,
             );
         }
-        if ctx.input(|i| i.key_pressed(egui::Key::Num1) && !i.modifiers.command) {
+        if let Some(channel) = ctx.input(display_channel_shortcut) {
+            apply_action(
+                &mut frame_result,
+                app,
+                render_state,
+                renderer,
+                CanvasAction::SetDisplayChannel(channel),
+            );
+        }
+        if ctx.input(|i| shift_digit_pressed(i, egui::Key::Num1)) {
             apply_action(
                 &mut frame_result,
                 app,
@@ -1137,7 +1388,7 @@ pub fn show_canvas(
                 CanvasAction::SetReferenceOpacity(0.0),
             );
         }
-        if ctx.input(|i| i.key_pressed(egui::Key::Num2)) {
+        if ctx.input(|i| shift_digit_pressed(i, egui::Key::Num2)) {
             apply_action(
                 &mut frame_result,
                 app,
@@ -1158,7 +1409,7 @@ pub fn show_canvas(
     }
 
     if normal_canvas_interactions_enabled {
-        reference::maybe_handle_reference_drop(app, ctx, render_state);
+        reference::maybe_handle_canvas_drop(app, ctx, render_state);
     }
 
     let escape_pressed = debug_shortcuts_enabled && ctx.input(|i| i.key_pressed(egui::Key::Escape));
@@ -1402,7 +1653,7 @@ pub fn show_canvas(
         ui.painter().text(
             canvas_rect.center(),
             egui::Align2::CENTER_CENTER,
-            "Drop PNG / JPEG / EXR as Reference",
+            "Drop PNG / JPEG / EXR as Reference, JSON as Scene",
             egui::FontId::new(13.0, egui::FontFamily::Proportional),
             Color32::from_rgba_unmultiplied(214, 228, 255, 240),
         );
@@ -1410,6 +1661,7 @@ pub fn show_canvas(
 
     let context_menu_opened_this_frame =
         normal_canvas_interactions_enabled && response.secondary_clicked();
+    let mut fit_mode_clicked = None;
     if normal_canvas_interactions_enabled {
         response.context_menu(|menu_ui| {
             let copy_clicked = menu_ui.button("复制材质").clicked();
@@ -1433,8 +1685,31 @@ pub fn show_canvas(
                 }
                 menu_ui.close();
             }
+            menu_ui.checkbox(
+                &mut app.canvas.display.zoom_crop.enabled,
+                "Sharp zoom (re-render visible area)",
+            );
+            menu_ui.separator();
+            for mode in CanvasFitMode::ALL {
+                if menu_ui.button(mode.label()).clicked() && !context_menu_opened_this_frame {
+                    fit_mode_clicked = Some(mode);
+                    menu_ui.close();
+                }
+            }
         });
     }
+    if let Some(mode) = fit_mode_clicked {
+        apply_action(
+            &mut frame_result,
+            app,
+            render_state,
+            renderer,
+            CanvasAction::ApplyFitMode {
+                mode,
+                current_display_ppi: current_display_metrics.display_ppi,
+            },
+        );
+    }
 
     let design_active = app.canvas.design.active.is_some();
 
@@ -1615,6 +1890,9 @@ pub fn show_canvas(
             uv,
             display_frame,
         );
+        if !display_frame.compare_output_active {
+            onion_skin::draw(ui, ctx, app, canvas_rect, uv, now);
+        }
 
         let mut value_sample_cache = None;
         let mut design_claims = design::DesignInteractionClaims::default();
@@ -1674,6 +1952,7 @@ pub fn show_canvas(
                     .as_ref()
                     .map(value_sampling_reference_from_state),
                 app.canvas.analysis.diff_metric_mode,
+                app.canvas.analysis.diff_color_space,
                 display_frame.compare_output_active,
                 app.canvas.display.hdr_preview_clamp_enabled,
             );
@@ -1849,6 +2128,7 @@ fn draw_matrix_grid_viewport(ui: &egui::Ui, app: &App, canvas_rect: Rect, image_
     let in_diff_mode = matches!(reference.map(|r| r.mode), Some(RefImageMode::Diff));
     let clip_enabled = app.canvas.analysis.clip_enabled;
     let qualifier_enabled = app.canvas.analysis.qualifier_enabled;
+    request_zebra_repaint(ui.ctx(), app);
 
     for cell in &state.cells {
         let cell_rect =
@@ -2107,6 +2387,7 @@ fn draw_matrix_pixel_overlays(ui: &egui::Ui, app: &mut App, canvas_rect: Rect, i
             cache,
             None,
             crate::app::types::DiffMetricMode::AE,
+            crate::app::types::DiffColorSpace::Linear,
             false,
             false,
         );
@@ -2173,6 +2454,7 @@ fn maybe_sample_matrix_clicked_pixel(
         y,
         None,
         crate::app::types::DiffMetricMode::AE,
+        crate::app::types::DiffColorSpace::Linear,
         false,
         false,
     ) else {
@@ -8,15 +8,18 @@ use rust_wgpu_fiber::{
 use crate::app::{
     canvas::{
         actions::{CanvasAction, CanvasFrameResult},
-        ops, pixel_overlay, reference,
+        onion_skin, ops, pixel_overlay, reference,
         state::{
             CanvasDisplayState, CanvasViewportState, DrawCallCaptureState, PhysicalZoomRequest,
         },
     },
     display_metrics,
     layout_math::clamp_zoom,
-    matrix_render, texture_bridge,
-    types::{App, QualifierChannel, RefImageAlphaMode, RefImageMode, SampledPixel},
+    matrix_render, scene_runtime, texture_bridge,
+    types::{
+        App, PickedPixel, QualifierChannel, RefImageAlphaMode, RefImageMode, SampledPixel,
+        ViewTransformSettings,
+    },
 };
 
 fn set_viewport_display_ppi(viewport: &mut CanvasViewportState, display_ppi: Option<f32>) {
@@ -171,6 +174,40 @@ pub fn apply_action(
                 app.canvas.display.texture_filter,
             );
         }
+        CanvasAction::ToggleFalseColor => {
+            app.canvas.display.false_color_enabled = !app.canvas.display.false_color_enabled;
+        }
+        CanvasAction::SetDisplayChannel(channel) => {
+            app.canvas.display.display_channel = channel;
+        }
+        CanvasAction::SetViewExposure(stops) => {
+            if stops.is_finite() {
+                app.canvas.display.view_transform.exposure_stops = stops.clamp(
+                    ViewTransformSettings::MIN_EXPOSURE_STOPS,
+                    ViewTransformSettings::MAX_EXPOSURE_STOPS,
+                );
+            }
+        }
+        CanvasAction::SetViewGamma(gamma) => {
+            if gamma.is_finite() {
+                app.canvas.display.view_transform.gamma = gamma.clamp(
+                    ViewTransformSettings::MIN_GAMMA,
+                    ViewTransformSettings::MAX_GAMMA,
+                );
+            }
+        }
+        CanvasAction::SetDisplayTransform(transform) => {
+            app.canvas.display.view_transform.transform = transform;
+        }
+        CanvasAction::SetFalseColorEnabled(enabled) => {
+            app.canvas.display.false_color_enabled = enabled;
+        }
+        CanvasAction::SetFalseColorEdge { index, stops } => {
+            app.canvas
+                .display
+                .false_color_settings
+                .set_edge(index, stops);
+        }
         CanvasAction::ToggleWireframe => {
             let requested_enabled = !app.canvas.display.wireframe_enabled;
             let applied = app
@@ -212,17 +249,12 @@ pub fn apply_action(
             app.canvas.viewport.pending_view_reset = true;
             set_viewport_display_ppi(&mut app.canvas.viewport, current_display_ppi);
         }
-        CanvasAction::CenterAt1x {
-            pixels_per_point,
+        CanvasAction::ApplyFitMode {
+            mode,
             current_display_ppi,
         } => {
             set_viewport_display_ppi(&mut app.canvas.viewport, current_display_ppi);
-            if pixels_per_point.is_finite() && pixels_per_point > 0.0 {
-                app.canvas.viewport.pending_center_physical_zoom = Some(PhysicalZoomRequest {
-                    zoom: 1.0 / pixels_per_point,
-                    pixels_per_point,
-                });
-            }
+            app.canvas.viewport.pending_fit_mode = Some(mode);
         }
         CanvasAction::SetDisplayPpi {
             ppi,
@@ -353,6 +385,13 @@ pub fn apply_action(
                 app.canvas.invalidation.mark_pixel_overlay_dirty();
             }
         }
+        CanvasAction::SetDiffColorSpace(space) => {
+            if app.canvas.analysis.diff_color_space != space {
+                app.canvas.analysis.diff_color_space = space;
+                app.canvas.invalidation.mark_diff_dirty();
+                app.canvas.invalidation.mark_pixel_overlay_dirty();
+            }
+        }
         CanvasAction::SetAnalysisTab(tab) => {
             if app.canvas.analysis.analysis_tab != tab {
                 app.canvas.analysis.analysis_tab = tab;
@@ -377,10 +416,28 @@ pub fn apply_action(
                 app.canvas.invalidation.clipping_controls_changed();
             }
         }
+        CanvasAction::SetClippingMode(mode) => {
+            if app.canvas.analysis.clipping_settings.mode != mode {
+                app.canvas.analysis.clipping_settings.mode = mode;
+                app.canvas.invalidation.clipping_controls_changed();
+            }
+        }
+        CanvasAction::SetClippingStyle(style) => {
+            if app.canvas.analysis.clipping_settings.style != style {
+                app.canvas.analysis.clipping_settings.style = style;
+                app.canvas.invalidation.clipping_controls_changed();
+            }
+        }
         CanvasAction::ToggleQualifier => {
             app.canvas.analysis.qualifier_enabled = !app.canvas.analysis.qualifier_enabled;
             app.canvas.invalidation.qualifier_controls_changed();
         }
+        CanvasAction::SetOnionSkinMode(mode) => {
+            onion_skin::set_mode(app, mode);
+        }
+        CanvasAction::SetOnionSkinFlickerHz(hz) => {
+            onion_skin::set_flicker_hz(app, hz);
+        }
         CanvasAction::SetQualifierEnabled(enabled) => {
             if app.canvas.analysis.qualifier_enabled != enabled {
                 app.canvas.analysis.qualifier_enabled = enabled;
@@ -478,6 +535,12 @@ pub fn apply_action(
         CanvasAction::SamplePixel { x, y, rgba } => {
             app.canvas.viewport.last_sampled = Some(SampledPixel { x, y, rgba });
         }
+        CanvasAction::PickPixel { x, y } => {
+            if let Some(target) = scene_runtime::pick_pixel(app, x, y) {
+                crate::ws::broadcast_pick_result(&app.core.ws_hub, x, y, target.as_ref());
+                app.shell.last_pick = Some(PickedPixel { x, y, target });
+            }
+        }
         CanvasAction::PollClipboardOp { now } => {
             ops::poll(&mut app.canvas.async_ops, now);
         }
@@ -493,7 +556,9 @@ mod tests {
         sync_zoom_to_display_ppi, update_pass_capture_mode,
     };
     use crate::app::canvas::state::{CanvasDisplayState, CanvasViewportState};
-    use crate::app::types::{AnalysisTab, ClippingSettings, DiffMetricMode, UiWindowMode};
+    use crate::app::types::{
+        AnalysisTab, ClippingSettings, DiffColorSpace, DiffMetricMode, UiWindowMode,
+    };
     use rust_wgpu_fiber::shader_space::PassCaptureMode;
 
     #[test]
@@ -531,6 +596,7 @@ mod tests {
         let _ = CanvasAction::SetClippingShadowThreshold(0.1);
         let _ = CanvasAction::SetClippingHighlightThreshold(0.9);
         let _ = CanvasAction::SetDiffMetricMode(DiffMetricMode::AE);
+        let _ = CanvasAction::SetDiffColorSpace(DiffColorSpace::Srgb);
         let _ = CanvasAction::SetClipEnabled(true);
         let _ = UiWindowMode::Sidebar;
         let _ = ClippingSettings::default();
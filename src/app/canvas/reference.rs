@@ -1068,33 +1068,68 @@ pub fn paste_shortwire_reference_from_clipboard(
     }))
 }
 
-pub fn maybe_handle_reference_drop(
+fn is_scene_json(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Handles files dropped onto the canvas: images become the manual reference,
+/// a JSON file replaces the current scene. The first accepted file of each
+/// kind wins.
+pub fn maybe_handle_canvas_drop(
     app: &mut App,
     ctx: &egui::Context,
     render_state: &egui_wgpu::RenderState,
 ) {
     let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
+    let mut reference_loaded = false;
+    let mut scene_loaded = false;
     for file in dropped_files {
         let Some(path) = file.path else {
             continue;
         };
-        if !is_supported_reference_image(&path) {
+
+        if !scene_loaded && is_scene_json(&path) {
+            match crate::app::scene_runtime::queue_dropped_scene(app, &path) {
+                Ok(()) => {
+                    eprintln!("[scene-drop] loaded scene from {}", path.display());
+                    scene_loaded = true;
+                    ctx.request_repaint();
+                }
+                Err(error) => {
+                    eprintln!(
+                        "[scene-drop] failed to load scene from {}: {error:#}",
+                        path.display()
+                    );
+                }
+            }
             continue;
         }
 
-        if load_reference_image_from_path(
+        if reference_loaded || !is_supported_reference_image(&path) {
+            continue;
+        }
+
+        match load_reference_image_from_path(
             app,
             ctx,
             render_state,
             &path,
             RefImageSource::Manual,
             app.canvas.reference.alpha_mode,
-        )
-        .is_ok()
-        {
-            app.canvas.reference.desired_override = Some(ReferenceDesiredSource::Manual);
-            app.canvas.reference.last_attempt_key = None;
-            break;
+        ) {
+            Ok(()) => {
+                app.canvas.reference.desired_override = Some(ReferenceDesiredSource::Manual);
+                app.canvas.reference.last_attempt_key = None;
+                reference_loaded = true;
+            }
+            Err(error) => {
+                eprintln!(
+                    "[reference-image] failed to load dropped image {}: {error:#}",
+                    path.display()
+                );
+            }
         }
     }
 }
@@ -1103,7 +1138,8 @@ pub fn maybe_handle_reference_drop(
 mod tests {
     use super::{
         RefImageAlphaMode, apply_reference_alpha_mode_to_linear_rgba, decode_reference_image,
-        is_supported_reference_image, reference_texture_format, validate_android_reference_rgba8,
+        is_scene_json, is_supported_reference_image, reference_texture_format,
+        validate_android_reference_rgba8,
     };
     use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
     use rust_wgpu_fiber::eframe::wgpu;
@@ -1116,6 +1152,14 @@ mod tests {
         assert!(!is_supported_reference_image(Path::new("foo.gif")));
     }
 
+    #[test]
+    fn dropped_json_files_are_treated_as_scenes() {
+        assert!(is_scene_json(Path::new("scene.json")));
+        assert!(is_scene_json(Path::new("SCENE.JSON")));
+        assert!(!is_scene_json(Path::new("scene.nforge")));
+        assert!(!is_supported_reference_image(Path::new("scene.json")));
+    }
+
     #[test]
     fn alpha_mode_conversion_differs_between_premultiplied_and_straight() {
         let rgba = vec![0.8, 0.6, 0.4, 0.5];
@@ -10,20 +10,25 @@ use crate::{
             HistogramRequestKey, ParadeRequestKey, QualifierRequestKey, VectorscopeRequestKey,
         },
         types::{
-            AnalysisTab, ClippingSettings, DiffMetricMode, DiffStats, QualifierSettings,
-            RefImageAlphaMode, RefImageState, SampledPixel, ViewportOperationIndicatorVisual,
+            AnalysisTab, ClippingSettings, DiffColorSpace, DiffMetricMode, DiffStats,
+            DisplayChannel, FalseColorSettings, QualifierSettings, RefImageAlphaMode,
+            RefImageState, SampledPixel, ViewTransformSettings, ViewportOperationIndicatorVisual,
         },
     },
     ui::{self, viewport_indicators::ViewportIndicatorManager},
 };
 
-use super::{design::CanvasDesignState, ops::ClipboardCopyState, pixel_overlay::PixelOverlayCache};
+use super::{
+    design::CanvasDesignState, onion_skin::CanvasOnionSkinState, ops::ClipboardCopyState,
+    pixel_overlay::PixelOverlayCache,
+};
 
 pub struct CanvasState {
     pub viewport: CanvasViewportState,
     pub display: CanvasDisplayState,
     pub analysis: CanvasAnalysisState,
     pub reference: CanvasReferenceState,
+    pub onion_skin: CanvasOnionSkinState,
     pub interactions: CanvasInteractionState,
     pub design: CanvasDesignState,
     pub async_ops: CanvasAsyncOps,
@@ -46,6 +51,7 @@ impl CanvasState {
                 alpha_mode: scene_alpha_mode.unwrap_or_default(),
                 ..Default::default()
             },
+            onion_skin: CanvasOnionSkinState::default(),
             interactions: CanvasInteractionState::default(),
             design: CanvasDesignState::default(),
             async_ops: CanvasAsyncOps::default(),
@@ -55,6 +61,43 @@ impl CanvasState {
     }
 }
 
+/// Explicit canvas zoom presets. Device-pixel modes map one output pixel to a
+/// fixed number of physical monitor pixels, independent of the scale factor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CanvasFitMode {
+    Fit,
+    Fill,
+    DevicePixels1x,
+    DevicePixels2x,
+}
+
+impl CanvasFitMode {
+    pub const ALL: [CanvasFitMode; 4] = [
+        CanvasFitMode::Fit,
+        CanvasFitMode::Fill,
+        CanvasFitMode::DevicePixels1x,
+        CanvasFitMode::DevicePixels2x,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CanvasFitMode::Fit => "Fit",
+            CanvasFitMode::Fill => "Fill",
+            CanvasFitMode::DevicePixels1x => "100% (device pixels)",
+            CanvasFitMode::DevicePixels2x => "200% (device pixels)",
+        }
+    }
+
+    pub fn shortcut_label(self) -> &'static str {
+        match self {
+            CanvasFitMode::Fit => "Cmd+0",
+            CanvasFitMode::Fill => "Cmd+Shift+0",
+            CanvasFitMode::DevicePixels1x => "Cmd+1",
+            CanvasFitMode::DevicePixels2x => "Cmd+2",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct PhysicalZoomRequest {
     pub zoom: f32,
@@ -75,6 +118,7 @@ pub struct CanvasViewportState {
     pub canvas_center_prev: Option<egui::Pos2>,
     pub pending_view_reset: bool,
     pub pending_center_physical_zoom: Option<PhysicalZoomRequest>,
+    pub pending_fit_mode: Option<CanvasFitMode>,
     pub display_ppi: Option<f32>,
     pub last_sampled: Option<SampledPixel>,
 }
@@ -102,6 +146,7 @@ impl Default for CanvasViewportState {
             canvas_center_prev: None,
             pending_view_reset: false,
             pending_center_physical_zoom: None,
+            pending_fit_mode: None,
             display_ppi: None,
             last_sampled: None,
         }
@@ -119,9 +164,20 @@ pub struct CanvasDisplayState {
     pub wireframe_enabled: bool,
     pub hdr_clamp_renderer: Option<ui::hdr_clamp::HdrClampRenderer>,
     pub hdr_clamp_texture_id: Option<egui::TextureId>,
+    pub false_color_enabled: bool,
+    pub false_color_settings: FalseColorSettings,
+    pub false_color_renderer: Option<ui::false_color::FalseColorRenderer>,
+    pub false_color_texture_id: Option<egui::TextureId>,
+    pub display_channel: DisplayChannel,
+    pub channel_renderer: Option<ui::channel_isolate::ChannelIsolateRenderer>,
+    pub channel_texture_id: Option<egui::TextureId>,
+    pub view_transform: ViewTransformSettings,
+    pub view_transform_renderer: Option<ui::display_transform::DisplayTransformRenderer>,
+    pub view_transform_texture_id: Option<egui::TextureId>,
     pub deferred_texture_frees: Vec<egui::TextureId>,
     pub pixel_overlay_cache: Option<Arc<PixelOverlayCache>>,
     pub pixel_overlay_last_request_key: Option<u64>,
+    pub zoom_crop: super::zoom_crop::ZoomCropState,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -143,9 +199,20 @@ impl Default for CanvasDisplayState {
             wireframe_enabled: false,
             hdr_clamp_renderer: None,
             hdr_clamp_texture_id: None,
+            false_color_enabled: false,
+            false_color_settings: FalseColorSettings::default(),
+            false_color_renderer: None,
+            false_color_texture_id: None,
+            display_channel: DisplayChannel::Rgb,
+            channel_renderer: None,
+            channel_texture_id: None,
+            view_transform: ViewTransformSettings::default(),
+            view_transform_renderer: None,
+            view_transform_texture_id: None,
             deferred_texture_frees: Vec::new(),
             pixel_overlay_cache: None,
             pixel_overlay_last_request_key: None,
+            zoom_crop: Default::default(),
         }
     }
 }
@@ -172,6 +239,7 @@ pub struct CanvasAnalysisState {
     pub diff_renderer: Option<ui::diff_renderer::DiffRenderer>,
     pub diff_texture_id: Option<egui::TextureId>,
     pub diff_metric_mode: DiffMetricMode,
+    pub diff_color_space: DiffColorSpace,
     pub diff_stats: Option<DiffStats>,
     pub last_diff_request_key: Option<DiffRequestKey>,
     pub last_diff_stats_request_key: Option<DiffStatsRequestKey>,
@@ -2,6 +2,7 @@ use rust_wgpu_fiber::eframe::egui::{self, Rect};
 
 use crate::{
     app::{
+        canvas::state::{CanvasFitMode, PhysicalZoomRequest},
         display_metrics::{self, CurrentDisplayMetrics},
         layout_math::{clamp_zoom, lerp},
         types::{App, SIDEBAR_ANIM_SECS},
@@ -106,6 +107,22 @@ pub fn prepare_viewport(
     let zoom = clamp_zoom(app.canvas.viewport.zoom, effective_min_zoom);
     app.canvas.viewport.zoom = zoom;
 
+    if let Some(mode) = app.canvas.viewport.pending_fit_mode.take() {
+        let pixels_per_point = current_display_metrics.pixels_per_point;
+        match fit_mode_zoom(mode, canvas_rect.size(), image_size, pixels_per_point) {
+            Some(_) if mode == CanvasFitMode::Fit => {
+                app.canvas.viewport.pending_view_reset = true;
+            }
+            Some(zoom) => {
+                app.canvas.viewport.pending_center_physical_zoom = Some(PhysicalZoomRequest {
+                    zoom,
+                    pixels_per_point,
+                });
+            }
+            None => {}
+        }
+    }
+
     if app.canvas.viewport.pending_view_reset {
         app.canvas.viewport.zoom = fit_zoom;
         app.canvas.viewport.pan = egui::Vec2::ZERO;
@@ -152,6 +169,40 @@ pub fn prepare_viewport(
     }
 }
 
+/// Canvas zoom (points per output pixel) for `mode`. Device-pixel presets divide
+/// by the monitor scale factor so 100% maps one output pixel to one physical pixel.
+pub fn fit_mode_zoom(
+    mode: CanvasFitMode,
+    canvas_size: egui::Vec2,
+    image_size: egui::Vec2,
+    pixels_per_point: f32,
+) -> Option<f32> {
+    if image_size.x <= 0.0 || image_size.y <= 0.0 {
+        return None;
+    }
+    let zoom = match mode {
+        CanvasFitMode::Fit => (canvas_size.x / image_size.x).min(canvas_size.y / image_size.y),
+        CanvasFitMode::Fill => (canvas_size.x / image_size.x).max(canvas_size.y / image_size.y),
+        CanvasFitMode::DevicePixels1x | CanvasFitMode::DevicePixels2x => {
+            if !pixels_per_point.is_finite() || pixels_per_point <= 0.0 {
+                return None;
+            }
+            let device_scale = if mode == CanvasFitMode::DevicePixels2x {
+                2.0
+            } else {
+                1.0
+            };
+            device_scale / pixels_per_point
+        }
+    };
+    (zoom.is_finite() && zoom > 0.0).then_some(zoom)
+}
+
+/// Effective zoom in physical pixels per output pixel, as shown in the canvas readout.
+pub fn device_pixel_zoom(zoom: f32, pixels_per_point: f32) -> f32 {
+    zoom * pixels_per_point
+}
+
 pub fn image_rect(app: &App, canvas_rect: Rect, image_size: egui::Vec2) -> Rect {
     let draw_size = image_size * app.canvas.viewport.zoom;
     let base_min = canvas_rect.center() - draw_size * 0.5;
@@ -183,3 +234,41 @@ fn sync_display_ppi_from_zoom(app: &mut App, current_display_metrics: CurrentDis
 
     app.canvas.viewport.display_ppi = Some(display_metrics::clamp_display_ppi(ppi));
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_wgpu_fiber::eframe::egui;
+
+    use super::{device_pixel_zoom, fit_mode_zoom};
+    use crate::app::canvas::state::CanvasFitMode;
+
+    #[test]
+    fn device_pixel_presets_account_for_scale_factor() {
+        let canvas = egui::vec2(800.0, 600.0);
+        let image = egui::vec2(400.0, 400.0);
+        let zoom_1x = fit_mode_zoom(CanvasFitMode::DevicePixels1x, canvas, image, 2.0).unwrap();
+        let zoom_2x = fit_mode_zoom(CanvasFitMode::DevicePixels2x, canvas, image, 2.0).unwrap();
+        assert_eq!(zoom_1x, 0.5);
+        assert_eq!(zoom_2x, 1.0);
+        assert_eq!(device_pixel_zoom(zoom_1x, 2.0), 1.0);
+        assert_eq!(device_pixel_zoom(zoom_2x, 2.0), 2.0);
+    }
+
+    #[test]
+    fn fit_and_fill_use_min_and_max_axis_ratio() {
+        let canvas = egui::vec2(800.0, 600.0);
+        let image = egui::vec2(400.0, 400.0);
+        assert_eq!(
+            fit_mode_zoom(CanvasFitMode::Fit, canvas, image, 2.0),
+            Some(1.5)
+        );
+        assert_eq!(
+            fit_mode_zoom(CanvasFitMode::Fill, canvas, image, 2.0),
+            Some(2.0)
+        );
+        assert_eq!(
+            fit_mode_zoom(CanvasFitMode::Fit, canvas, egui::Vec2::ZERO, 2.0),
+            None
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Sharp zoom: while zoomed past native resolution, re-render only the
+//! visible window of the output at device-pixel density and draw it over the
+//! magnified full-frame texture.
+//!
+//! The full-frame shader space still drives sampling, analysis and export.
+//! The crop lives in its own shader space, rebuilt when the window, density
+//! bucket or pipeline changes and re-rendered when uniforms or time change.
+//! Scenes the renderer cannot crop fall back to the full-frame texture.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use rust_wgpu_fiber::{
+    ResourceName,
+    eframe::{
+        egui::{self, Color32, Rect, pos2},
+        egui_wgpu,
+    },
+    shader_space::ShaderSpace,
+};
+
+use crate::{
+    app::{scene_runtime, texture_bridge, types::App},
+    renderer::{self, OutputCrop, PassBindings},
+};
+
+/// Largest crop target edge; beyond it the crop density is halved.
+const MAX_CROP_TEXTURE_DIM: u32 = 4096;
+/// Extra window kept around the visible area, as a fraction of its size, so
+/// short pans stay inside the rendered crop.
+const CROP_MARGIN: f32 = 0.25;
+
+#[derive(Default)]
+pub struct ZoomCropState {
+    pub enabled: bool,
+    active: Option<ActiveZoomCrop>,
+    /// Pipeline generation the renderer refused to crop.
+    unsupported_generation: Option<u64>,
+}
+
+struct ActiveZoomCrop {
+    crop: OutputCrop,
+    generation: u64,
+    shader_space: ShaderSpace,
+    passes: Vec<PassBindings>,
+    output_texture: ResourceName,
+    texture_id: Option<egui::TextureId>,
+    rendered_time: Option<f32>,
+}
+
+fn floor_pow2(value: f32) -> f32 {
+    2f32.powf(value.max(1.0).log2().floor())
+}
+
+/// The crop to render for the current view, or `None` when the full-frame
+/// texture is already at or above display density or the whole frame is
+/// visible.
+pub(crate) fn desired_crop(
+    canvas_rect: Rect,
+    image_rect: Rect,
+    resolution: [u32; 2],
+    pixels_per_point: f32,
+) -> Option<OutputCrop> {
+    let frame_w = resolution[0] as f32;
+    let frame_h = resolution[1] as f32;
+    if frame_w < 1.0 || frame_h < 1.0 || image_rect.width() <= 0.0 {
+        return None;
+    }
+    let points_per_texel = image_rect.width() / frame_w;
+    let device_per_texel = points_per_texel * pixels_per_point;
+    if device_per_texel <= 1.0 {
+        return None;
+    }
+    let visible = image_rect.intersect(canvas_rect);
+    if !visible.is_positive() {
+        return None;
+    }
+
+    let x0 = (visible.min.x - image_rect.min.x) / points_per_texel;
+    let y0 = (visible.min.y - image_rect.min.y) / points_per_texel;
+    let x1 = (visible.max.x - image_rect.min.x) / points_per_texel;
+    let y1 = (visible.max.y - image_rect.min.y) / points_per_texel;
+    // Snap to a power-of-two grid tied to the window size so the crop only
+    // changes once the view moves by a noticeable amount.
+    let step_x = floor_pow2((x1 - x0) * CROP_MARGIN);
+    let step_y = floor_pow2((y1 - y0) * CROP_MARGIN);
+    let cx0 = ((x0 / step_x).floor() - 1.0).max(0.0) * step_x;
+    let cy0 = ((y0 / step_y).floor() - 1.0).max(0.0) * step_y;
+    let cx1 = (((x1 / step_x).ceil() + 1.0) * step_x).min(frame_w);
+    let cy1 = (((y1 / step_y).ceil() + 1.0) * step_y).min(frame_h);
+    if cx0 <= 0.0 && cy0 <= 0.0 && cx1 >= frame_w && cy1 >= frame_h {
+        return None;
+    }
+    let size = [cx1 - cx0, cy1 - cy0];
+
+    let mut density = 2f32.powf(device_per_texel.log2().ceil());
+    while density > 1.0 && size[0].max(size[1]) * density > MAX_CROP_TEXTURE_DIM as f32 {
+        density *= 0.5;
+    }
+    if density <= 1.0 {
+        return None;
+    }
+    Some(OutputCrop {
+        origin: [cx0, cy0],
+        size,
+        target_size: [
+            (size[0] * density).ceil() as u32,
+            (size[1] * density).ceil() as u32,
+        ],
+    })
+}
+
+fn release(app: &mut App) {
+    if let Some(active) = app.canvas.display.zoom_crop.active.take()
+        && let Some(id) = active.texture_id
+    {
+        app.canvas.display.deferred_texture_frees.push(id);
+    }
+}
+
+fn build_crop(
+    app: &App,
+    render_state: &egui_wgpu::RenderState,
+    crop: OutputCrop,
+    generation: u64,
+) -> Result<Option<ActiveZoomCrop>> {
+    let Some(scene) = app.runtime.uniform_scene.as_ref() else {
+        return Ok(None);
+    };
+    let result = renderer::ShaderSpaceBuilder::new(
+        Arc::new(render_state.device.clone()),
+        Arc::new(render_state.queue.clone()),
+    )
+    .with_adapter(render_state.adapter.clone())
+    .with_options(renderer::ShaderSpaceBuildOptions {
+        presentation_mode: renderer::ShaderSpacePresentationMode::UiHdrNative,
+        pass_shader_overrides: app.shell.pass_shader_overrides.clone(),
+        pass_debug_overlays: app.shell.pass_debug_overlays.clone(),
+        channel_debug: app.shell.channel_debug,
+        non_finite_sanitize: app.shell.non_finite_sanitize,
+        skip_trusted_wgsl_validation: true,
+        output_crop: Some(crop),
+        ..Default::default()
+    })
+    .with_asset_store(app.core.asset_store.clone())
+    .build(scene)?;
+    let Some(crop) = result.output_crop else {
+        return Ok(None);
+    };
+    Ok(Some(ActiveZoomCrop {
+        crop,
+        generation,
+        shader_space: result.shader_space,
+        passes: result.pass_bindings,
+        output_texture: result.present_output_texture,
+        texture_id: None,
+        rendered_time: None,
+    }))
+}
+
+/// Brings the crop in line with the current view and draws it. Returns
+/// whether a crop was drawn.
+pub(crate) fn sync_and_draw(
+    ui: &egui::Ui,
+    app: &mut App,
+    render_state: &egui_wgpu::RenderState,
+    renderer: &mut egui_wgpu::Renderer,
+    canvas_rect: Rect,
+    image_rect: Rect,
+) -> bool {
+    // The crop is laid out against the output texture, which can differ from
+    // the scene resolution when the RenderTexture overrides its size.
+    let resolution = app
+        .core
+        .shader_space
+        .texture_info(app.core.output_texture_name.as_str())
+        .map(|info| [info.size.width, info.size.height])
+        .unwrap_or(app.core.resolution);
+    let desired = if app.canvas.display.zoom_crop.enabled {
+        desired_crop(
+            canvas_rect,
+            image_rect,
+            resolution,
+            ui.ctx().pixels_per_point(),
+        )
+    } else {
+        None
+    };
+    let generation = app.runtime.pipeline_rebuild_count;
+    let Some(crop) = desired else {
+        release(app);
+        return false;
+    };
+    if app.canvas.display.zoom_crop.unsupported_generation == Some(generation) {
+        return false;
+    }
+
+    let needs_rebuild = app
+        .canvas
+        .display
+        .zoom_crop
+        .active
+        .as_ref()
+        .is_none_or(|active| active.crop != crop || active.generation != generation);
+    if needs_rebuild {
+        release(app);
+        match build_crop(app, render_state, crop, generation) {
+            Ok(Some(active)) => app.canvas.display.zoom_crop.active = Some(active),
+            Ok(None) => {
+                app.canvas.display.zoom_crop.unsupported_generation = Some(generation);
+                return false;
+            }
+            Err(error) => {
+                eprintln!("[zoom-crop] failed to build crop: {error:#}");
+                app.canvas.display.zoom_crop.unsupported_generation = Some(generation);
+                return false;
+            }
+        }
+    }
+
+    let timing = app.runtime.frame_timing();
+    let time = timing.time_secs;
+    let filter = app.canvas.display.texture_filter;
+    let Some(active) = app.canvas.display.zoom_crop.active.as_mut() else {
+        return false;
+    };
+    let uniform_writes = app
+        .runtime
+        .uniform_scene
+        .as_ref()
+        .and_then(|scene| {
+            scene_runtime::apply_graph_uniform_updates_parts(
+                &mut active.passes,
+                &mut active.shader_space,
+                scene,
+            )
+            .ok()
+        })
+        .unwrap_or(0);
+    if uniform_writes > 0 || active.rendered_time != Some(time) {
+        for pass in &mut active.passes {
+            let params = pass.base_params.with_frame_timing(timing);
+            let _ = renderer::update_pass_params(&active.shader_space, pass, &params);
+        }
+        active.shader_space.render();
+        active.rendered_time = Some(time);
+    }
+
+    let Some(view) = active
+        .shader_space
+        .textures
+        .get(active.output_texture.as_str())
+        .and_then(|texture| texture.wgpu_texture_view.as_ref())
+    else {
+        return false;
+    };
+    let sampler = texture_bridge::canvas_sampler_descriptor(filter);
+    let texture_id = match active.texture_id {
+        Some(id) => {
+            renderer.update_egui_texture_from_wgpu_texture_with_sampler_options(
+                &render_state.device,
+                view,
+                sampler,
+                id,
+            );
+            id
+        }
+        None => {
+            let id = renderer.register_native_texture_with_sampler_options(
+                &render_state.device,
+                view,
+                sampler,
+            );
+            active.texture_id = Some(id);
+            id
+        }
+    };
+
+    let scale = image_rect.width() / resolution[0] as f32;
+    let crop_rect = Rect::from_min_size(
+        image_rect.min + egui::vec2(crop.origin[0], crop.origin[1]) * scale,
+        egui::vec2(crop.size[0], crop.size[1]) * scale,
+    );
+    let visible = crop_rect.intersect(canvas_rect);
+    if !visible.is_positive() {
+        return false;
+    }
+    let uv_min = (visible.min - crop_rect.min) / crop_rect.size();
+    let uv_max = (visible.max - crop_rect.min) / crop_rect.size();
+    ui.painter().add(
+        egui::epaint::RectShape::filled(visible, egui::CornerRadius::ZERO, Color32::WHITE)
+            .with_texture(
+                texture_id,
+                Rect::from_min_max(pos2(uv_min.x, uv_min.y), pos2(uv_max.x, uv_max.y)),
+            ),
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_wgpu_fiber::eframe::egui::{Rect, pos2, vec2};
+
+    use super::desired_crop;
+
+    #[test]
+    fn no_crop_at_or_below_native_density() {
+        let canvas = Rect::from_min_size(pos2(0.0, 0.0), vec2(800.0, 600.0));
+        let image = Rect::from_min_size(pos2(0.0, 0.0), vec2(800.0, 600.0));
+        assert!(desired_crop(canvas, image, [1600, 1200], 2.0).is_none());
+        assert!(desired_crop(canvas, image, [7680, 4320], 1.0).is_none());
+    }
+
+    #[test]
+    fn zoomed_view_crops_visible_window_at_device_density() {
+        let canvas = Rect::from_min_size(pos2(0.0, 0.0), vec2(800.0, 600.0));
+        // 8K frame at 4 points per texel, panned into the middle.
+        let image = Rect::from_min_size(pos2(-12000.0, -8000.0), vec2(30720.0, 17280.0));
+        let crop = desired_crop(canvas, image, [7680, 4320], 2.0).expect("crop");
+
+        // Visible texels: x 3000..3200, y 2000..2150.
+        assert!(crop.origin[0] <= 3000.0 && crop.origin[0] + crop.size[0] >= 3200.0);
+        assert!(crop.origin[1] <= 2000.0 && crop.origin[1] + crop.size[1] >= 2150.0);
+        assert!(crop.size[0] < 7680.0 && crop.size[1] < 4320.0);
+        assert_eq!(crop.target_size[0], (crop.size[0] * 8.0).ceil() as u32);
+
+        // A small pan keeps the same snapped crop.
+        let panned = image.translate(vec2(4.0, 3.0));
+        assert_eq!(desired_crop(canvas, panned, [7680, 4320], 2.0), Some(crop));
+    }
+}
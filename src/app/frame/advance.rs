@@ -23,6 +23,7 @@ pub(super) fn run(app: &mut App) -> AdvancePhase {
     let raw_t = app.runtime.start.elapsed().as_secs_f32();
     let delta_t = (raw_t - app.runtime.time_last_raw_secs).max(0.0);
     app.runtime.time_last_raw_secs = raw_t;
+    let prev_time_secs = app.runtime.time_value_secs;
 
     let state_control_selection = app.runtime.state_control_selection.clone();
     let state_control_active = state_control_selection.is_some();
@@ -139,6 +140,13 @@ pub(super) fn run(app: &mut App) -> AdvancePhase {
         app.runtime.time_value_secs += delta_t;
     }
 
+    // One shared frame clock per tick, so every pass sees the same Time outputs.
+    let scene_dt = app.runtime.time_value_secs - prev_time_secs;
+    app.runtime.time_delta_secs = scene_dt.max(0.0);
+    if scene_dt > 0.0 {
+        app.runtime.frame_index = app.runtime.frame_index.wrapping_add(1);
+    }
+
     interaction_bridge::sync_animation_state(
         app,
         animation_current_state_id.as_deref(),
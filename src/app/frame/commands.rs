@@ -37,6 +37,13 @@ pub enum AppCommand {
     },
     ResetPassShaderPatch(String),
     ResetAllPassShaderPatches,
+    SetPassDebugOverlay {
+        pass_name: String,
+        overlay: Option<crate::renderer::PassDebugOverlay>,
+    },
+    SetChannelDebugMode(crate::renderer::ChannelDebugMode),
+    SetNonFiniteSanitize(crate::renderer::NonFiniteSanitizeOptions),
+    SetPickIds(bool),
     UpsertDebugArtifact {
         item: crate::dsl::DebugArtifactItem,
         content_text: String,
@@ -69,6 +76,9 @@ pub fn from_sidebar_action(action: ui::debug_sidebar::SidebarAction) -> AppComma
         ui::debug_sidebar::SidebarAction::OpenPassDesign(target) => {
             AppCommand::OpenPassDesign(target)
         }
+        ui::debug_sidebar::SidebarAction::SetPassDebugOverlay { pass_name, overlay } => {
+            AppCommand::SetPassDebugOverlay { pass_name, overlay }
+        }
         ui::debug_sidebar::SidebarAction::ClearPreview => {
             AppCommand::Canvas(CanvasAction::ClearPreviewTexture)
         }
@@ -87,6 +97,9 @@ pub fn from_sidebar_action(action: ui::debug_sidebar::SidebarAction) -> AppComma
         ui::debug_sidebar::SidebarAction::SetDiffMetricMode(mode) => {
             AppCommand::Canvas(CanvasAction::SetDiffMetricMode(mode))
         }
+        ui::debug_sidebar::SidebarAction::SetDiffColorSpace(space) => {
+            AppCommand::Canvas(CanvasAction::SetDiffColorSpace(space))
+        }
         ui::debug_sidebar::SidebarAction::SetAnalysisTab(tab) => {
             AppCommand::Canvas(CanvasAction::SetAnalysisTab(tab))
         }
@@ -99,6 +112,12 @@ pub fn from_sidebar_action(action: ui::debug_sidebar::SidebarAction) -> AppComma
         ui::debug_sidebar::SidebarAction::SetClippingHighlightThreshold(threshold) => {
             AppCommand::Canvas(CanvasAction::SetClippingHighlightThreshold(threshold))
         }
+        ui::debug_sidebar::SidebarAction::SetClippingMode(mode) => {
+            AppCommand::Canvas(CanvasAction::SetClippingMode(mode))
+        }
+        ui::debug_sidebar::SidebarAction::SetClippingStyle(style) => {
+            AppCommand::Canvas(CanvasAction::SetClippingStyle(style))
+        }
         ui::debug_sidebar::SidebarAction::SetQualifierEnabled(enabled) => {
             AppCommand::Canvas(CanvasAction::SetQualifierEnabled(enabled))
         }
@@ -116,6 +135,37 @@ pub fn from_sidebar_action(action: ui::debug_sidebar::SidebarAction) -> AppComma
             AppCommand::SetMatrixLabelsVisible(visible)
         }
         ui::debug_sidebar::SidebarAction::SetDisplayPpi(ppi) => AppCommand::SetDisplayPpi(ppi),
+        ui::debug_sidebar::SidebarAction::SetOnionSkinMode(mode) => {
+            AppCommand::Canvas(CanvasAction::SetOnionSkinMode(mode))
+        }
+        ui::debug_sidebar::SidebarAction::SetOnionSkinFlickerHz(hz) => {
+            AppCommand::Canvas(CanvasAction::SetOnionSkinFlickerHz(hz))
+        }
+        ui::debug_sidebar::SidebarAction::SetChannelDebugMode(mode) => {
+            AppCommand::SetChannelDebugMode(mode)
+        }
+        ui::debug_sidebar::SidebarAction::SetNonFiniteSanitize(options) => {
+            AppCommand::SetNonFiniteSanitize(options)
+        }
+        ui::debug_sidebar::SidebarAction::SetPickIds(enabled) => AppCommand::SetPickIds(enabled),
+        ui::debug_sidebar::SidebarAction::SetDisplayChannel(channel) => {
+            AppCommand::Canvas(CanvasAction::SetDisplayChannel(channel))
+        }
+        ui::debug_sidebar::SidebarAction::SetViewExposure(stops) => {
+            AppCommand::Canvas(CanvasAction::SetViewExposure(stops))
+        }
+        ui::debug_sidebar::SidebarAction::SetViewGamma(gamma) => {
+            AppCommand::Canvas(CanvasAction::SetViewGamma(gamma))
+        }
+        ui::debug_sidebar::SidebarAction::SetDisplayTransform(transform) => {
+            AppCommand::Canvas(CanvasAction::SetDisplayTransform(transform))
+        }
+        ui::debug_sidebar::SidebarAction::SetFalseColorEnabled(enabled) => {
+            AppCommand::Canvas(CanvasAction::SetFalseColorEnabled(enabled))
+        }
+        ui::debug_sidebar::SidebarAction::SetFalseColorEdge { index, stops } => {
+            AppCommand::Canvas(CanvasAction::SetFalseColorEdge { index, stops })
+        }
     }
 }
 
@@ -343,6 +393,46 @@ pub fn dispatch(
                 }
             }
         }
+        AppCommand::SetPassDebugOverlay { pass_name, overlay } => {
+            match scene_runtime::set_pass_debug_overlay(app, render_state, &pass_name, overlay) {
+                Ok(result) => {
+                    sync_after_shader_rebuild(app, ctx, render_state, renderer, result, now);
+                }
+                Err(err) => {
+                    eprintln!("[pass-debug-overlay] failed to update {pass_name}: {err:#}");
+                }
+            }
+        }
+        AppCommand::SetChannelDebugMode(mode) => {
+            match scene_runtime::set_channel_debug_mode(app, render_state, mode) {
+                Ok(result) => {
+                    sync_after_shader_rebuild(app, ctx, render_state, renderer, result, now);
+                }
+                Err(err) => {
+                    eprintln!("[channel-debug] failed to switch to {mode:?}: {err:#}");
+                }
+            }
+        }
+        AppCommand::SetNonFiniteSanitize(options) => {
+            match scene_runtime::set_non_finite_sanitize(app, render_state, options) {
+                Ok(result) => {
+                    sync_after_shader_rebuild(app, ctx, render_state, renderer, result, now);
+                }
+                Err(err) => {
+                    eprintln!("[sanitize] failed to apply safe mode {options:?}: {err:#}");
+                }
+            }
+        }
+        AppCommand::SetPickIds(enabled) => {
+            match scene_runtime::set_pick_ids(app, render_state, enabled) {
+                Ok(result) => {
+                    sync_after_shader_rebuild(app, ctx, render_state, renderer, result, now);
+                }
+                Err(err) => {
+                    eprintln!("[pick] failed to set pick ids to {enabled}: {err:#}");
+                }
+            }
+        }
         AppCommand::UpsertDebugArtifact { item, content_text } => {
             upsert_debug_artifact(app, item, content_text);
         }
@@ -446,10 +536,9 @@ fn upsert_debug_artifact(app: &mut App, item: crate::dsl::DebugArtifactItem, con
 }
 
 fn render_current_shader_space(app: &mut App, now: f64) {
-    let t = app.runtime.time_value_secs;
+    let timing = app.runtime.frame_timing();
     for pass in &mut app.core.passes {
-        let mut params = pass.base_params;
-        params.time = t;
+        let params = pass.base_params.with_frame_timing(timing);
         let _ = crate::renderer::update_pass_params(&app.core.shader_space, pass, &params);
     }
     let profile = canvas::draw_capture::render_profiled(app, false);
@@ -463,7 +552,7 @@ fn render_current_shader_space(app: &mut App, now: f64) {
 mod tests {
     use super::{AppCommand, from_sidebar_action};
     use crate::{
-        app::{AnalysisTab, DiffMetricMode, canvas::actions::CanvasAction},
+        app::{AnalysisTab, DiffColorSpace, DiffMetricMode, canvas::actions::CanvasAction},
         ui::debug_sidebar::SidebarAction,
     };
     use rust_wgpu_fiber::shader_space::PassCaptureMode;
@@ -500,6 +589,25 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn sidebar_false_color_controls_map_to_canvas_commands() {
+        let enable = from_sidebar_action(SidebarAction::SetFalseColorEnabled(true));
+        assert!(matches!(
+            enable,
+            AppCommand::Canvas(CanvasAction::SetFalseColorEnabled(true))
+        ));
+
+        let edge = from_sidebar_action(SidebarAction::SetFalseColorEdge {
+            index: 4,
+            stops: 1.5,
+        });
+        assert!(matches!(
+            edge,
+            AppCommand::Canvas(CanvasAction::SetFalseColorEdge { index: 4, stops })
+                if stops == 1.5
+        ));
+    }
+
     #[test]
     fn sidebar_reference_picker_maps_to_app_command() {
         let command = from_sidebar_action(SidebarAction::PickReferenceImage);
@@ -528,12 +636,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sidebar_pass_debug_overlay_maps_to_app_command() {
+        let command = from_sidebar_action(SidebarAction::SetPassDebugOverlay {
+            pass_name: "node_2.pass".to_string(),
+            overlay: Some(crate::renderer::PassDebugOverlay::Wireframe),
+        });
+        assert!(matches!(
+            command,
+            AppCommand::SetPassDebugOverlay {
+                pass_name,
+                overlay: Some(crate::renderer::PassDebugOverlay::Wireframe),
+            } if pass_name == "node_2.pass"
+        ));
+    }
+
     #[test]
     fn sidebar_analysis_controls_map_to_canvas_commands() {
         let analysis = from_sidebar_action(SidebarAction::SetAnalysisTab(AnalysisTab::Parade));
         let diff = from_sidebar_action(SidebarAction::SetDiffMetricMode(DiffMetricMode::SE));
+        let space = from_sidebar_action(SidebarAction::SetDiffColorSpace(DiffColorSpace::Srgb));
         assert!(matches!(analysis, AppCommand::Canvas(_)));
         assert!(matches!(diff, AppCommand::Canvas(_)));
+        assert!(matches!(
+            space,
+            AppCommand::Canvas(CanvasAction::SetDiffColorSpace(DiffColorSpace::Srgb))
+        ));
     }
 
     #[test]
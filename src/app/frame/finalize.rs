@@ -23,7 +23,7 @@ fn should_request_immediate_repaint(
 pub(super) fn run(app: &App, ctx: &egui::Context, advance: &AdvancePhase, present: &PresentPhase) {
     ctx.send_viewport_cmd(egui::ViewportCommand::SetTheme(egui::SystemTheme::Dark));
 
-    let title = if let Some(sampled) = app.canvas.viewport.last_sampled {
+    let mut title = if let Some(sampled) = app.canvas.viewport.last_sampled {
         format!(
             "Node Forge Render Server - x={} y={} rgba=({:.3}, {:.3}, {:.3}, {:.3})",
             sampled.x,
@@ -36,6 +36,20 @@ pub(super) fn run(app: &App, ctx: &egui::Context, advance: &AdvancePhase, presen
     } else {
         "Node Forge Render Server".to_string()
     };
+    if let Some(pick) = app.shell.last_pick.as_ref() {
+        match pick.target.as_ref() {
+            Some(target) => {
+                title.push_str(&format!(
+                    " - pick {},{} pass={}",
+                    pick.x, pick.y, target.pass_name
+                ));
+                if let Some(node_id) = target.node_id.as_deref() {
+                    title.push_str(&format!(" node={node_id}"));
+                }
+            }
+            None => title.push_str(&format!(" - pick {},{} none", pick.x, pick.y)),
+        }
+    }
     ctx.send_viewport_cmd(egui::ViewportCommand::Title(title));
 
     if should_request_immediate_repaint(
@@ -0,0 +1,56 @@
+//! Feeds `subscribe_frames` WS clients from the rendered output.
+
+use std::time::Instant;
+
+use rust_wgpu_fiber::eframe::egui;
+
+use crate::{app::types::App, ws};
+
+/// Reads back the export texture once when any subscriber is due and hands
+/// each one a frame encoded to its options. The readback blocks on the GPU,
+/// so it only happens when the render changed and a stream's interval allows.
+pub(super) fn run(app: &mut App, ctx: &egui::Context, render_changed: bool) {
+    let hub = &app.core.ws_hub;
+    if !hub.has_frame_subscribers() {
+        return;
+    }
+    if render_changed {
+        hub.mark_frame_streams_stale();
+    }
+    let now = Instant::now();
+    let due = hub.due_frame_streams(now);
+    if due.is_empty() {
+        // A change inside the stream interval still has to go out once the
+        // interval ends, even if nothing else repaints.
+        if let Some(wait) = hub.next_frame_stream_due_in(now) {
+            ctx.request_repaint_after(wait);
+        }
+        return;
+    }
+
+    if let Some(pass_name) = app.core.export_encode_pass_name.as_ref() {
+        app.core
+            .shader_space
+            .render_pass_by_name(pass_name.as_str());
+    }
+    let export_tex = app.core.export_texture_name.as_str();
+    let image = match app.core.shader_space.read_texture_rgba8(export_tex) {
+        Ok(image) => image,
+        Err(e) => {
+            eprintln!("[frame-stream] failed to read {export_tex}: {e}");
+            return;
+        }
+    };
+    for (client_id, options) in due {
+        hub.publish_frame(client_id, now, |frame_index| {
+            ws::encode_stream_frame(
+                image.bytes.clone(),
+                [image.width, image.height],
+                &options,
+                frame_index,
+            )
+            .map_err(|e| eprintln!("[frame-stream] {e:#}"))
+            .ok()
+        });
+    }
+}
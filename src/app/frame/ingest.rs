@@ -133,6 +133,12 @@ pub(super) fn run(
             .as_ref()
             .map(|trace| trace.enqueued_at.elapsed().as_secs_f64() * 1000.0)
             .unwrap_or(0.0);
+        if matches!(
+            update,
+            crate::ws::SceneUpdate::Parsed { .. } | crate::ws::SceneUpdate::UniformDelta { .. }
+        ) {
+            canvas::capture_onion_skin_previous(app, ctx);
+        }
         let apply_started_at = Instant::now();
         let apply_result = scene_runtime::apply_scene_update(app, ctx, render_state, update);
         let renderer_apply_ms = apply_started_at.elapsed().as_secs_f64() * 1000.0;
@@ -148,6 +154,10 @@ pub(super) fn run(
         }
         let matrix_update = apply_result.matrix_update.clone();
         app.runtime.scene_redraw_pending = true;
+        if !app.runtime.scene_rx.is_empty() {
+            // Updates held back behind a dropped scene.
+            ctx.request_repaint();
+        }
         app.canvas.invalidation.preview_source_changed();
         if apply_result.did_rebuild_shader_space {
             let filter = apply_result
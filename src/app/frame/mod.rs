@@ -1,6 +1,7 @@
 mod advance;
 pub(super) mod commands;
 mod finalize;
+mod frame_stream;
 mod ingest;
 mod interaction_bridge;
 mod present;
@@ -71,6 +72,11 @@ pub(super) fn run(app: &mut App, ui: &mut egui::Ui, frame: &mut eframe::Frame) {
         &advance,
         matrix_poll.added_cells > 0,
     );
+    frame_stream::run(
+        app,
+        &ctx,
+        advance.should_redraw_scene || ingest.did_rebuild_shader_space,
+    );
     let analysis_ms = t2.elapsed().as_secs_f64() * 1000.0;
 
     let t3 = Instant::now();
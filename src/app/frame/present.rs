@@ -81,6 +81,7 @@ pub(super) fn run(
             mode: reference.mode,
             opacity: reference.opacity,
             diff_metric_mode: app.canvas.analysis.diff_metric_mode,
+            diff_color_space: app.canvas.analysis.diff_color_space,
             diff_stats: app.canvas.analysis.diff_stats,
         }
     });
@@ -99,6 +100,15 @@ pub(super) fn run(
     }
     let display_sidebar_state = ui::debug_sidebar::DisplaySidebarState {
         ppi: app.canvas.viewport.effective_display_ppi(),
+        onion_skin_mode: app.canvas.onion_skin.mode,
+        onion_skin_flicker_hz: app.canvas.onion_skin.flicker_hz,
+        channel_debug_mode: app.shell.channel_debug,
+        non_finite_sanitize: app.shell.non_finite_sanitize,
+        pick_ids: app.shell.pick_ids,
+        false_color_enabled: app.canvas.display.false_color_enabled,
+        false_color: app.canvas.display.false_color_settings,
+        display_channel: app.canvas.display.display_channel,
+        view_transform: app.canvas.display.view_transform,
     };
     let pass_capture_sidebar_state = ui::debug_sidebar::PassCaptureSidebarState {
         mode: app.canvas.display.pass_capture_mode,
@@ -217,6 +227,7 @@ pub(super) fn run(
                     },
                     pass_capture_sidebar_state,
                     &app.shell.resource_tree_nodes,
+                    &app.shell.pass_debug_overlays,
                     &mut app.shell.file_tree_state,
                 );
             });
@@ -282,9 +293,9 @@ pub(super) fn run(
                     uniform_scene,
                 );
             }
+            let timing = app.runtime.frame_timing();
             for pass in &mut app.core.passes {
-                let mut params = pass.base_params;
-                params.time = app.runtime.time_value_secs;
+                let params = pass.base_params.with_frame_timing(timing);
                 let _ = crate::renderer::update_pass_params(&app.core.shader_space, pass, &params);
             }
             let profile = canvas::draw_capture::render_profiled(app, false);
@@ -312,9 +323,9 @@ pub(super) fn run(
                     uniform_scene,
                 );
             }
+            let timing = app.runtime.frame_timing();
             for pass in &mut app.core.passes {
-                let mut params = pass.base_params;
-                params.time = app.runtime.time_value_secs;
+                let params = pass.base_params.with_frame_timing(timing);
                 let _ = crate::renderer::update_pass_params(&app.core.shader_space, pass, &params);
             }
             let profile = canvas::draw_capture::render_profiled(app, false);
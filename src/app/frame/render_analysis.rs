@@ -2,10 +2,13 @@ use rust_wgpu_fiber::eframe::{egui_wgpu, wgpu};
 
 use crate::{
     app::{
-        canvas, matrix_render, texture_bridge,
-        types::{AnalysisSourceDomain, App, DiffMetricMode, DiffStats, RefImageMode, TestMode},
+        canvas, matrix_render, scene_runtime, texture_bridge,
+        types::{
+            AnalysisSourceDomain, App, DiffColorSpace, DiffMetricMode, DiffStats, RefImageMode,
+            TestMode,
+        },
     },
-    renderer, ui,
+    renderer, ui, ws,
 };
 
 use super::{
@@ -33,10 +36,9 @@ pub(super) fn run(
     matrix_cells_added: bool,
 ) {
     if advance.should_redraw_scene {
-        let t = app.runtime.time_value_secs;
+        let timing = app.runtime.frame_timing();
         for pass in &mut app.core.passes {
-            let mut params = pass.base_params;
-            params.time = t;
+            let params = pass.base_params.with_frame_timing(timing);
             let _ = renderer::update_pass_params(&app.core.shader_space, pass, &params);
         }
 
@@ -57,8 +59,15 @@ pub(super) fn run(
             .render_texture_fps_tracker
             .record_scene_redraw(ingest.frame_time);
     }
+    refresh_non_finite_count(
+        app,
+        ingest.frame_time,
+        advance.should_redraw_scene,
+        ingest.did_rebuild_shader_space,
+    );
 
     texture_bridge::ensure_output_texture_registered(app, render_state, renderer_guard);
+    let zebra_phase = ui::clipping_map::zebra_phase(ingest.frame_time);
 
     let matrix_active =
         app.shell.test_mode == TestMode::Matrix && !app.shell.matrix_state.cells.is_empty();
@@ -67,7 +76,7 @@ pub(super) fn run(
             let result = matrix_render::render_matrix_dynamic_frame(
                 &mut app.shell.matrix_state,
                 &advance.frame_uniform_values,
-                app.runtime.time_value_secs,
+                app.runtime.frame_timing(),
                 advance.should_redraw_scene,
                 render_state,
                 renderer_guard,
@@ -81,7 +90,7 @@ pub(super) fn run(
                 );
             }
         }
-        run_matrix_analysis(app, render_state, renderer_guard);
+        run_matrix_analysis(app, render_state, renderer_guard, zebra_phase);
         if ingest.did_rebuild_shader_space {
             let _ = render_state
                 .device
@@ -160,6 +169,11 @@ pub(super) fn run(
         } else {
             app.canvas.analysis.diff_metric_mode
         };
+        let effective_color_space = if capture_shortwire_diff {
+            DiffColorSpace::Linear
+        } else {
+            app.canvas.analysis.diff_color_space
+        };
         let effective_clamp_output = effective_diff_clamp_output(
             capture_shortwire_diff,
             app.canvas.display.hdr_preview_clamp_enabled,
@@ -196,6 +210,7 @@ pub(super) fn run(
                 effective_reference_mode,
                 reference.opacity.to_bits(),
                 effective_metric_mode,
+                effective_color_space,
                 effective_clamp_output,
             );
             let stats_key = DiffStatsRequestKey::new(request_key);
@@ -251,6 +266,7 @@ pub(super) fn run(
                     effective_reference_mode,
                     reference.opacity,
                     effective_metric_mode,
+                    effective_color_space,
                     effective_clamp_output,
                     collect_stats,
                 );
@@ -542,8 +558,12 @@ pub(super) fn run(
         }
 
         if app.canvas.analysis.clip_enabled {
-            let request_key =
-                ClippingRequestKey::new(source_key, app.canvas.analysis.clipping_settings, true);
+            let request_key = ClippingRequestKey::new(
+                source_key,
+                app.canvas.analysis.clipping_settings,
+                zebra_phase,
+                true,
+            );
             if app.canvas.analysis.clipping_renderer.is_none() {
                 app.canvas.analysis.clipping_renderer = Some(
                     ui::clipping_map::ClippingMapRenderer::new(&render_state.device, source.size),
@@ -562,8 +582,8 @@ pub(super) fn run(
                     app.core.shader_space.queue.as_ref(),
                     source.view,
                     source.size,
-                    app.canvas.analysis.clipping_settings.shadow_threshold,
-                    app.canvas.analysis.clipping_settings.highlight_threshold,
+                    &app.canvas.analysis.clipping_settings,
+                    zebra_phase,
                 );
 
                 let mut sampler =
@@ -666,10 +686,34 @@ pub(super) fn run(
     }
 }
 
+/// Reads the safe mode counters after each scene redraw, so the total covers
+/// exactly the last rendered frame; broadcasts per-pass counts when it changes.
+fn refresh_non_finite_count(app: &mut App, now: f64, redrawn: bool, rebuilt: bool) {
+    if app.shell.non_finite_sanitize.mode == renderer::NonFiniteSanitizeMode::Off {
+        app.runtime.non_finite_count = None;
+        app.runtime.non_finite_counted_at = None;
+        return;
+    }
+    if app.runtime.non_finite_counted_at.is_some() && !redrawn && !rebuilt {
+        return;
+    }
+
+    app.runtime.non_finite_counted_at = Some(now);
+    let Some(per_pass) = scene_runtime::take_non_finite_counts(app) else {
+        return;
+    };
+    let count = per_pass.iter().map(|(_, count)| count).sum();
+    if Some(count) != app.runtime.non_finite_count {
+        app.runtime.non_finite_count = Some(count);
+        ws::broadcast_non_finite_stats(&app.core.ws_hub, app.shell.non_finite_sanitize, &per_pass);
+    }
+}
+
 fn run_matrix_analysis(
     app: &mut App,
     render_state: &egui_wgpu::RenderState,
     renderer_guard: &mut egui_wgpu::Renderer,
+    zebra_phase: u32,
 ) {
     let reference_mode = app.canvas.reference.ref_image.as_ref().map(|r| r.mode);
     let clip_enabled = app.canvas.analysis.clip_enabled;
@@ -677,6 +721,7 @@ fn run_matrix_analysis(
     let qualifier_enabled = app.canvas.analysis.qualifier_enabled;
     let qualifier_settings = app.canvas.analysis.qualifier_settings;
     let metric_mode = app.canvas.analysis.diff_metric_mode;
+    let color_space = app.canvas.analysis.diff_color_space;
     let hdr_clamp = app.canvas.display.hdr_preview_clamp_enabled;
 
     let diff_dirty = app.canvas.invalidation.diff_dirty();
@@ -706,9 +751,11 @@ fn run_matrix_analysis(
             reference_mode,
             clip_enabled,
             clipping_settings,
+            zebra_phase,
             qualifier_enabled,
             qualifier_settings,
             metric_mode,
+            color_space,
             hdr_clamp,
             diff_dirty,
             clipping_dirty,
@@ -749,9 +796,11 @@ fn update_matrix_cell_analysis(
     reference_mode: Option<RefImageMode>,
     clip_enabled: bool,
     clipping_settings: crate::app::ClippingSettings,
+    zebra_phase: u32,
     qualifier_enabled: bool,
     qualifier_settings: crate::app::QualifierSettings,
     metric_mode: crate::app::DiffMetricMode,
+    color_space: crate::app::DiffColorSpace,
     hdr_clamp: bool,
     diff_dirty: bool,
     clipping_dirty: bool,
@@ -800,6 +849,7 @@ fn update_matrix_cell_analysis(
             reference_mode,
             reference_opacity.to_bits(),
             metric_mode,
+            color_space,
             hdr_clamp,
         );
         let stats_key = DiffStatsRequestKey::new(request_key);
@@ -837,6 +887,7 @@ fn update_matrix_cell_analysis(
                 reference_mode,
                 reference_opacity,
                 metric_mode,
+                color_space,
                 hdr_clamp,
                 collect_stats,
             );
@@ -898,7 +949,8 @@ fn update_matrix_cell_analysis(
             };
         let clip_source_key =
             AnalysisSourceKey::from_hashable(&(cell_coord, clip_size, clip_source_label));
-        let request_key = ClippingRequestKey::new(clip_source_key, clipping_settings, true);
+        let request_key =
+            ClippingRequestKey::new(clip_source_key, clipping_settings, zebra_phase, true);
 
         let needs_new_renderer = cell
             .clipping_renderer
@@ -923,8 +975,8 @@ fn update_matrix_cell_analysis(
                 app.core.shader_space.queue.as_ref(),
                 &clip_view,
                 clip_size,
-                clipping_settings.shadow_threshold,
-                clipping_settings.highlight_threshold,
+                &clipping_settings,
+                zebra_phase,
             );
 
             let mut sampler = texture_bridge::canvas_sampler_descriptor(wgpu::FilterMode::Nearest);
@@ -1031,7 +1083,9 @@ mod tests {
         AnalysisSourceKey, ClippingRequestKey, DiffRequestKey, HistogramRequestKey,
         ParadeRequestKey, RefImageMode, VectorscopeRequestKey, effective_diff_clamp_output,
     };
-    use crate::app::{ClippingSettings, DiffMetricMode};
+    use crate::app::{
+        ClippingMode, ClippingSettings, ClippingStyle, DiffColorSpace, DiffMetricMode,
+    };
 
     #[test]
     fn request_keys_change_with_source_domain() {
@@ -1069,6 +1123,7 @@ mod tests {
             RefImageMode::Diff,
             0.5f32.to_bits(),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             false,
         );
         let key_2 = DiffRequestKey::new(
@@ -1078,6 +1133,7 @@ mod tests {
             RefImageMode::Diff,
             0.5f32.to_bits(),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             false,
         );
         let key_3 = DiffRequestKey::new(
@@ -1087,6 +1143,7 @@ mod tests {
             RefImageMode::Diff,
             0.5f32.to_bits(),
             DiffMetricMode::SE,
+            DiffColorSpace::Linear,
             false,
         );
         let key_4 = DiffRequestKey::new(
@@ -1096,6 +1153,7 @@ mod tests {
             RefImageMode::Diff,
             0.5f32.to_bits(),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             true,
         );
         let key_5 = DiffRequestKey::new(
@@ -1105,6 +1163,7 @@ mod tests {
             RefImageMode::Overlay,
             0.5f32.to_bits(),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
             false,
         );
         let key_6 = DiffRequestKey::new(
@@ -1114,6 +1173,17 @@ mod tests {
             RefImageMode::Diff,
             0.25f32.to_bits(),
             DiffMetricMode::AE,
+            DiffColorSpace::Linear,
+            false,
+        );
+        let key_7 = DiffRequestKey::new(
+            source_key,
+            [64, 64],
+            [0, 0],
+            RefImageMode::Diff,
+            0.5f32.to_bits(),
+            DiffMetricMode::AE,
+            DiffColorSpace::Srgb,
             false,
         );
         assert_ne!(key_1, key_2);
@@ -1121,6 +1191,7 @@ mod tests {
         assert_ne!(key_1, key_4);
         assert_ne!(key_1, key_5);
         assert_ne!(key_1, key_6);
+        assert_ne!(key_1, key_7);
     }
 
     #[test]
@@ -1135,17 +1206,50 @@ mod tests {
     fn clipping_request_key_changes_with_toggle_and_thresholds() {
         let source_key = AnalysisSourceKey::from_hashable(&("output", [1920_u32, 1080_u32]));
         let base_settings = ClippingSettings::default();
-        let base = ClippingRequestKey::new(source_key, base_settings, true);
-        let toggled = ClippingRequestKey::new(source_key, base_settings, false);
+        let base = ClippingRequestKey::new(source_key, base_settings, 0, true);
+        let toggled = ClippingRequestKey::new(source_key, base_settings, 0, false);
         let changed_threshold = ClippingRequestKey::new(
             source_key,
             ClippingSettings {
                 shadow_threshold: 0.05,
                 ..base_settings
             },
+            0,
             true,
         );
         assert_ne!(base, toggled);
         assert_ne!(base, changed_threshold);
     }
+
+    #[test]
+    fn clipping_request_key_tracks_zebra_phase_only_for_stripes() {
+        let source_key = AnalysisSourceKey::from_hashable(&("output", [1920_u32, 1080_u32]));
+        let map = ClippingSettings::default();
+        assert_eq!(
+            ClippingRequestKey::new(source_key, map, 0, true),
+            ClippingRequestKey::new(source_key, map, 5, true)
+        );
+
+        let zebra = ClippingSettings {
+            style: ClippingStyle::Zebra,
+            ..map
+        };
+        assert_ne!(
+            ClippingRequestKey::new(source_key, map, 0, true),
+            ClippingRequestKey::new(source_key, zebra, 0, true)
+        );
+        assert_ne!(
+            ClippingRequestKey::new(source_key, zebra, 0, true),
+            ClippingRequestKey::new(source_key, zebra, 5, true)
+        );
+
+        let luminance = ClippingSettings {
+            mode: ClippingMode::Luminance,
+            ..map
+        };
+        assert_ne!(
+            ClippingRequestKey::new(source_key, map, 0, true),
+            ClippingRequestKey::new(source_key, luminance, 0, true)
+        );
+    }
 }
@@ -4,7 +4,8 @@ use std::{
 };
 
 use crate::app::{
-    ClippingSettings, DiffMetricMode, QualifierSettings, RefImageMode, types::AnalysisSourceDomain,
+    ClippingSettings, DiffColorSpace, DiffMetricMode, QualifierSettings, RefImageMode,
+    types::AnalysisSourceDomain,
 };
 
 fn hash_key<T: Hash + ?Sized>(value: &T) -> u64 {
@@ -45,6 +46,7 @@ impl AnalysisSourceKey {
 pub struct DiffRequestKey(u64);
 
 impl DiffRequestKey {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         source_key: AnalysisSourceKey,
         reference_size: [u32; 2],
@@ -52,6 +54,7 @@ impl DiffRequestKey {
         reference_mode: RefImageMode,
         reference_opacity_bits: u32,
         metric_mode: DiffMetricMode,
+        color_space: DiffColorSpace,
         clamp_output: bool,
     ) -> Self {
         Self(hash_key(&(
@@ -61,6 +64,7 @@ impl DiffRequestKey {
             reference_mode,
             reference_opacity_bits,
             metric_mode,
+            color_space,
             clamp_output,
         )))
     }
@@ -110,12 +114,22 @@ impl VectorscopeRequestKey {
 pub struct ClippingRequestKey(u64);
 
 impl ClippingRequestKey {
-    pub fn new(source_key: AnalysisSourceKey, settings: ClippingSettings, enabled: bool) -> Self {
+    /// `zebra_phase` is only hashed while the style draws stripes, so a
+    /// static map keeps its key across frames.
+    pub fn new(
+        source_key: AnalysisSourceKey,
+        settings: ClippingSettings,
+        zebra_phase: u32,
+        enabled: bool,
+    ) -> Self {
         Self(hash_key(&(
             source_key.raw(),
             enabled,
             settings.shadow_threshold.to_bits(),
             settings.highlight_threshold.to_bits(),
+            settings.mode,
+            settings.style,
+            settings.style.draws_zebra().then_some(zebra_phase),
         )))
     }
 }
@@ -565,6 +565,7 @@ fn build_matrix_cell(
     let mut builder = renderer::ShaderSpaceBuilder::new(device, queue)
         .with_options(renderer::ShaderSpaceBuildOptions {
             presentation_mode: renderer::ShaderSpacePresentationMode::UiHdrNative,
+            skip_trusted_wgsl_validation: true,
             ..Default::default()
         })
         .with_asset_store(asset_store);
@@ -808,7 +809,7 @@ fn apply_frame_uniform_values(
 pub fn render_matrix_dynamic_frame(
     state: &mut MatrixRenderState,
     frame_uniform_values: &HashMap<OverrideKey, serde_json::Value>,
-    time_secs: f32,
+    timing: renderer::FrameTiming,
     render_all_cells: bool,
     render_state: &egui_wgpu::RenderState,
     renderer: &mut egui_wgpu::Renderer,
@@ -830,8 +831,7 @@ pub fn render_matrix_dynamic_frame(
                 &frame_scene,
             )?;
             for pass in &mut cell.pass_bindings {
-                let mut params = pass.base_params;
-                params.time = time_secs;
+                let params = pass.base_params.with_frame_timing(timing);
                 renderer::update_pass_params(&cell.shader_space, pass, &params)?;
             }
             cell.shader_space.render();
@@ -995,6 +995,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
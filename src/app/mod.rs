@@ -10,10 +10,13 @@ mod texture_bridge;
 mod types;
 mod window_mode;
 
+pub(crate) use scene_runtime::apply_graph_uniform_updates_parts;
 pub use types::{
-    AnalysisTab, App, AppInit, ClippingSettings, DiffMetricMode, DiffStats, QualifierChannel,
-    QualifierSettings, RefImageAlphaMode, RefImageMode, ResourcePoolInfo, SampledPixel,
-    ShortwirePastedReferenceImage, ShortwireReferenceImage, StateControlSelection, TestMode,
+    AnalysisTab, App, AppInit, ClippingMode, ClippingSettings, ClippingStyle, DiffColorSpace,
+    DiffMetricMode, DiffStats, DisplayChannel, DisplayTransform, FalseColorSettings,
+    OnionSkinMode, PickedPixel, QualifierChannel, QualifierSettings, RefImageAlphaMode,
+    RefImageMode, ResourcePoolInfo, SampledPixel, ShortwirePastedReferenceImage,
+    ShortwireReferenceImage, StateControlSelection, TestMode, ViewTransformSettings,
 };
 
 use rust_wgpu_fiber::eframe::{self, egui};
@@ -86,7 +86,7 @@ pub(super) fn select_state_control(app: &mut App, selection: StateControlSelecti
     }
 
     app.runtime.state_control_selection = Some(selection);
-    app.runtime.time_value_secs = 0.0;
+    app.runtime.reset_scene_time();
     Ok(())
 }
 
@@ -102,7 +102,7 @@ pub(super) fn clear_state_control(app: &mut App) {
     app.runtime.last_live_overrides = None;
     app.runtime.timeline_pre_hover_overrides = None;
     app.runtime.timeline_preview_was_active = false;
-    app.runtime.time_value_secs = 0.0;
+    app.runtime.reset_scene_time();
 }
 
 fn restore_state_control_after_scene_change(
@@ -184,6 +184,12 @@ fn choose_scene_update_mode(
     }
 }
 
+/// Uniform deltas patch the scene on screen only when it came from the same
+/// client; a delta against another client's scene needs a full rebuild.
+fn uniform_delta_needs_rebuild(scene_client_id: Option<u64>, client_id: Option<u64>) -> bool {
+    scene_client_id != client_id
+}
+
 fn should_reset_viewport_for_scene_resolution(
     source: &ws::ParsedSceneSource,
     previous_resolution: [u32; 2],
@@ -280,6 +286,190 @@ pub fn reset_all_pass_shader_patches(
     })
 }
 
+/// Sets or clears the debug overlay for one pass and rebuilds the ShaderSpace.
+/// The previous overlay set is kept when the rebuild fails.
+pub fn set_pass_debug_overlay(
+    app: &mut App,
+    render_state: &egui_wgpu::RenderState,
+    pass_name: &str,
+    overlay: Option<renderer::PassDebugOverlay>,
+) -> Result<SceneApplyResult> {
+    if app.shell.pass_debug_overlays.get(pass_name).copied() == overlay {
+        return Ok(SceneApplyResult {
+            did_rebuild_shader_space: false,
+            texture_filter_override: None,
+            reset_viewport: false,
+            previous_output_hash: None,
+            matrix_update: MatrixSceneUpdate::None,
+        });
+    }
+
+    ensure_pass_is_live_composited(app, pass_name)?;
+    let scene = latest_scene_for_rebuild(app)?;
+    let previous_overlays = app.shell.pass_debug_overlays.clone();
+    match overlay {
+        Some(overlay) => {
+            app.shell
+                .pass_debug_overlays
+                .insert(pass_name.to_string(), overlay);
+        }
+        None => {
+            app.shell.pass_debug_overlays.remove(pass_name);
+        }
+    }
+    let overrides = app.shell.pass_shader_overrides.clone();
+    let result = match build_shader_space_with_overrides(app, render_state, &scene, &overrides) {
+        Ok(result) => result,
+        Err(e) => {
+            app.shell.pass_debug_overlays = previous_overlays;
+            return Err(e);
+        }
+    };
+    commit_shader_space_rebuild(app, &scene, result, overrides);
+    Ok(SceneApplyResult {
+        did_rebuild_shader_space: true,
+        texture_filter_override: None,
+        reset_viewport: false,
+        previous_output_hash: None,
+        matrix_update: MatrixSceneUpdate::None,
+    })
+}
+
+/// Switches the global channel debug visualization and rebuilds the ShaderSpace.
+pub fn set_channel_debug_mode(
+    app: &mut App,
+    render_state: &egui_wgpu::RenderState,
+    mode: renderer::ChannelDebugMode,
+) -> Result<SceneApplyResult> {
+    if app.shell.channel_debug == mode {
+        return Ok(SceneApplyResult {
+            did_rebuild_shader_space: false,
+            texture_filter_override: None,
+            reset_viewport: false,
+            previous_output_hash: None,
+            matrix_update: MatrixSceneUpdate::None,
+        });
+    }
+
+    let scene = latest_scene_for_rebuild(app)?;
+    let previous_mode = std::mem::replace(&mut app.shell.channel_debug, mode);
+    let overrides = app.shell.pass_shader_overrides.clone();
+    let result = match build_shader_space_with_overrides(app, render_state, &scene, &overrides) {
+        Ok(result) => result,
+        Err(e) => {
+            app.shell.channel_debug = previous_mode;
+            return Err(e);
+        }
+    };
+    commit_shader_space_rebuild(app, &scene, result, overrides);
+    Ok(SceneApplyResult {
+        did_rebuild_shader_space: true,
+        texture_filter_override: None,
+        reset_viewport: false,
+        previous_output_hash: None,
+        matrix_update: MatrixSceneUpdate::None,
+    })
+}
+
+/// Updates the safe mode sanitize options and rebuilds the ShaderSpace.
+pub fn set_non_finite_sanitize(
+    app: &mut App,
+    render_state: &egui_wgpu::RenderState,
+    options: renderer::NonFiniteSanitizeOptions,
+) -> Result<SceneApplyResult> {
+    if app.shell.non_finite_sanitize == options {
+        return Ok(SceneApplyResult {
+            did_rebuild_shader_space: false,
+            texture_filter_override: None,
+            reset_viewport: false,
+            previous_output_hash: None,
+            matrix_update: MatrixSceneUpdate::None,
+        });
+    }
+
+    let scene = latest_scene_for_rebuild(app)?;
+    let previous_options = std::mem::replace(&mut app.shell.non_finite_sanitize, options);
+    let overrides = app.shell.pass_shader_overrides.clone();
+    let result = match build_shader_space_with_overrides(app, render_state, &scene, &overrides) {
+        Ok(result) => result,
+        Err(e) => {
+            app.shell.non_finite_sanitize = previous_options;
+            return Err(e);
+        }
+    };
+    commit_shader_space_rebuild(app, &scene, result, overrides);
+    app.runtime.non_finite_count = None;
+    app.runtime.non_finite_counted_at = None;
+    Ok(SceneApplyResult {
+        did_rebuild_shader_space: true,
+        texture_filter_override: None,
+        reset_viewport: false,
+        previous_output_hash: None,
+        matrix_update: MatrixSceneUpdate::None,
+    })
+}
+
+/// Turns the GPU pick pass on or off and rebuilds the ShaderSpace.
+pub fn set_pick_ids(
+    app: &mut App,
+    render_state: &egui_wgpu::RenderState,
+    enabled: bool,
+) -> Result<SceneApplyResult> {
+    if app.shell.pick_ids == enabled {
+        return Ok(SceneApplyResult {
+            did_rebuild_shader_space: false,
+            texture_filter_override: None,
+            reset_viewport: false,
+            previous_output_hash: None,
+            matrix_update: MatrixSceneUpdate::None,
+        });
+    }
+
+    let scene = latest_scene_for_rebuild(app)?;
+    app.shell.pick_ids = enabled;
+    let overrides = app.shell.pass_shader_overrides.clone();
+    let result = match build_shader_space_with_overrides(app, render_state, &scene, &overrides) {
+        Ok(result) => result,
+        Err(e) => {
+            app.shell.pick_ids = !enabled;
+            return Err(e);
+        }
+    };
+    commit_shader_space_rebuild(app, &scene, result, overrides);
+    if !enabled {
+        app.shell.last_pick = None;
+    }
+    Ok(SceneApplyResult {
+        did_rebuild_shader_space: true,
+        texture_filter_override: None,
+        reset_viewport: false,
+        previous_output_hash: None,
+        matrix_update: MatrixSceneUpdate::None,
+    })
+}
+
+/// Pass under output pixel (`x`, `y`) according to the last rendered pick texture.
+/// Returns `None` when picking is off or the texture cannot be read back.
+pub fn pick_pixel(app: &App, x: u32, y: u32) -> Option<Option<renderer::PickTarget>> {
+    if !app.shell.pick_ids {
+        return None;
+    }
+    let image = app
+        .core
+        .shader_space
+        .read_texture_rgba8(renderer::PICK_TEXTURE_NAME)
+        .ok()?;
+    let target = renderer::pick_id_at(&image.bytes, image.width, image.height, x, y)
+        .and_then(|id| app.shell.pick_targets.get(id as usize - 1).cloned());
+    Some(target)
+}
+
+/// Non-finite fragments each sanitized pass replaced since the last call, read
+/// from the safe mode counters (which are reset by the read).
+pub fn take_non_finite_counts(app: &App) -> Option<Vec<(String, u64)>> {
+    renderer::take_non_finite_counts(&app.core.shader_space)
+}
+
 fn ensure_pass_is_live_composited(app: &App, pass_name: &str) -> Result<()> {
     if !app.core.shader_space.passes.inner.contains_key(pass_name) {
         bail!("shader patch target pass is not registered in the live ShaderSpace: {pass_name}");
@@ -367,6 +557,15 @@ fn build_shader_space_with_overrides(
             debug_dump_wgsl_dir: None,
             pass_shader_overrides: pass_shader_overrides.clone(),
             strict_pass_shader_overrides: true,
+            pass_debug_overlays: app.shell.pass_debug_overlays.clone(),
+            channel_debug: app.shell.channel_debug,
+            non_finite_sanitize: app.shell.non_finite_sanitize,
+            // Interactive rebuilds only need to validate modules that changed.
+            skip_trusted_wgsl_validation: true,
+            dither_sdr_output: false,
+            output_crop: None,
+            pick_ids: app.shell.pick_ids,
+            deterministic: false,
         })
         .with_asset_store(app.core.asset_store.clone())
         .build(scene)
@@ -403,12 +602,16 @@ fn commit_shader_space_rebuild(
     app.core.export_encode_pass_name = result.export_encode_pass_name;
     sync_wireframe_mode(app);
     update_pass_debug_sources(app, result.pass_debug_sources);
+    app.shell.pick_targets = result.pick_targets;
     app.shell.pass_shader_overrides = pass_shader_overrides;
     let live_pass_names: std::collections::HashSet<String> =
         app.shell.pass_debug_sources.keys().cloned().collect();
     app.shell
         .pass_shader_overrides
         .retain(|pass_name, _| live_pass_names.contains(pass_name));
+    app.shell
+        .pass_debug_overlays
+        .retain(|pass_name, _| live_pass_names.contains(pass_name));
     app.runtime.last_pipeline_signature = Some(result.pipeline_signature);
     app.runtime.uniform_scene = renderer::prepare_scene(scene).ok().map(|p| p.scene);
     app.runtime.scene_uses_time = app
@@ -599,7 +802,20 @@ pub(crate) fn apply_uniform_node_param_updates(
     Ok(())
 }
 
-pub fn drain_latest_scene_update(app: &App) -> Option<ws::SceneUpdate> {
+/// Next scene update to apply. A scene dropped onto the canvas is applied on
+/// its own, so it reaches the screen and last-good before any WS updates that
+/// queued meanwhile replace it on the next frame. Otherwise only the newest
+/// queued WS update is kept.
+pub fn drain_latest_scene_update(app: &mut App) -> Option<ws::SceneUpdate> {
+    if let Some(dropped) = app.runtime.dropped_scene_update.take() {
+        let queued = app.runtime.scene_rx.len();
+        if queued > 0 {
+            eprintln!(
+                "[scene-runtime] applying dropped scene; {queued} queued scene update(s) supersede it next frame"
+            );
+        }
+        return Some(dropped);
+    }
     let mut latest: Option<ws::SceneUpdate> = None;
     while let Ok(update) = app.runtime.scene_rx.try_recv() {
         latest = Some(update);
@@ -607,6 +823,18 @@ pub fn drain_latest_scene_update(app: &App) -> Option<ws::SceneUpdate> {
     latest
 }
 
+pub fn queue_dropped_scene(app: &mut App, path: &std::path::Path) -> Result<()> {
+    let scene = crate::dsl::load_scene_from_path(path)?;
+    app.runtime.dropped_scene_update = Some(ws::SceneUpdate::Parsed {
+        scene,
+        request_id: None,
+        client_id: None,
+        source: ws::ParsedSceneSource::SceneUpdate,
+        perf_trace: None,
+    });
+    Ok(())
+}
+
 pub fn apply_scene_resolution_to_window_state(
     current_window_resolution: [u32; 2],
     scene_screen_resolution: Option<[u32; 2]>,
@@ -640,11 +868,14 @@ pub fn apply_scene_update(
         ws::SceneUpdate::UniformDelta {
             updated_nodes,
             request_id,
+            client_id,
             perf_trace: _,
         } => {
-            let scene = match app.runtime.last_good.lock() {
-                Ok(mut guard) => guard.take(),
-                Err(_) => None,
+            // Deltas are relative to the sender's own scene, not whichever
+            // client last put a scene on screen.
+            let scene = match client_id {
+                Some(client_id) => app.core.ws_hub.session_last_good(client_id),
+                None => app.runtime.last_good.lock().ok().and_then(|g| g.clone()),
             };
 
             let Some(mut scene) = scene else {
@@ -652,7 +883,7 @@ pub fn apply_scene_update(
                     "received uniform-only update without a baseline scene; waiting for scene_update"
                         .to_string();
                 eprintln!("[scene-runtime] {message}");
-                broadcast_error(app, request_id, "RESYNC_REQUIRED", message);
+                broadcast_error(app, request_id, client_id, "RESYNC_REQUIRED", message);
                 return SceneApplyResult {
                     did_rebuild_shader_space: false,
                     texture_filter_override: None,
@@ -662,6 +893,34 @@ pub fn apply_scene_update(
                 };
             };
 
+            if uniform_delta_needs_rebuild(app.runtime.scene_client_id, client_id) {
+                if let Err(e) = apply_uniform_node_param_updates(&mut scene, &updated_nodes, false)
+                {
+                    let message = format!("uniform-only update failed: {e:#}");
+                    eprintln!("[scene-runtime] {message}");
+                    broadcast_error(app, request_id, client_id, "UNIFORM_UPDATE_FAILED", message);
+                    return SceneApplyResult {
+                        did_rebuild_shader_space: false,
+                        texture_filter_override: None,
+                        reset_viewport: false,
+                        previous_output_hash: None,
+                        matrix_update: MatrixSceneUpdate::None,
+                    };
+                }
+                return apply_scene_update(
+                    app,
+                    ctx,
+                    render_state,
+                    ws::SceneUpdate::Parsed {
+                        scene,
+                        request_id,
+                        client_id,
+                        source: ws::ParsedSceneSource::SceneDelta,
+                        perf_trace: None,
+                    },
+                );
+            }
+
             let mut cached_uniform_scene = app.runtime.uniform_scene.take();
             let update_result = (|| -> Result<crate::dsl::SceneDSL> {
                 apply_uniform_node_param_updates(&mut scene, &updated_nodes, false)?;
@@ -681,12 +940,12 @@ pub fn apply_scene_update(
             let scene_ref_desired = scene_reference_desired_source(&scene);
             let scene_ref_alpha_mode = scene_reference_image_alpha_mode(&scene);
 
-            if let Ok(mut guard) = app.runtime.last_good.lock() {
-                *guard = Some(scene);
-            }
-
             match update_result {
                 Ok(uniform_scene) => {
+                    app.core.ws_hub.set_session_last_good(client_id, &scene);
+                    if let Ok(mut guard) = app.runtime.last_good.lock() {
+                        *guard = Some(scene);
+                    }
                     app.canvas.reference.scene_desired = scene_ref_desired.clone();
                     app.canvas.reference.scene_alpha_mode = scene_ref_alpha_mode;
                     if let Some(alpha_mode) = scene_ref_alpha_mode {
@@ -719,7 +978,7 @@ pub fn apply_scene_update(
                     app.runtime.timeline_preview_was_active = false;
                     let message = format!("uniform-only update failed: {e:#}");
                     eprintln!("[scene-runtime] {message}");
-                    broadcast_error(app, request_id, "UNIFORM_UPDATE_FAILED", message);
+                    broadcast_error(app, request_id, client_id, "UNIFORM_UPDATE_FAILED", message);
                     SceneApplyResult {
                         did_rebuild_shader_space: false,
                         texture_filter_override: None,
@@ -733,6 +992,7 @@ pub fn apply_scene_update(
         ws::SceneUpdate::Parsed {
             scene,
             request_id,
+            client_id,
             source,
             perf_trace: _,
         } => {
@@ -800,6 +1060,8 @@ pub fn apply_scene_update(
                             );
                             app.runtime.uniform_only_update_count =
                                 app.runtime.uniform_only_update_count.saturating_add(1);
+                            app.core.ws_hub.set_session_last_good(client_id, &scene);
+                            app.runtime.scene_client_id = client_id;
                             if let Ok(mut g) = app.runtime.last_good.lock() {
                                 *g = Some(scene);
                             }
@@ -835,9 +1097,24 @@ pub fn apply_scene_update(
                     debug_dump_wgsl_dir: None,
                     pass_shader_overrides: app.shell.pass_shader_overrides.clone(),
                     strict_pass_shader_overrides: false,
+                    pass_debug_overlays: app.shell.pass_debug_overlays.clone(),
+                    channel_debug: app.shell.channel_debug,
+                    non_finite_sanitize: app.shell.non_finite_sanitize,
+                    skip_trusted_wgsl_validation: true,
+                    dither_sdr_output: false,
+                    output_crop: None,
+                    pick_ids: app.shell.pick_ids,
+                    deterministic: false,
                 })
                 .with_asset_store(app.core.asset_store.clone())
-                .build(&scene)
+                .build_with_progress(&scene, &mut |progress| {
+                    ws::broadcast_render_progress(
+                        &app.core.ws_hub,
+                        request_id.clone(),
+                        client_id,
+                        &progress,
+                    )
+                })
             }));
 
             match build_result {
@@ -851,11 +1128,15 @@ pub fn apply_scene_update(
                     app.core.export_encode_pass_name = result.export_encode_pass_name;
                     sync_wireframe_mode(app);
                     update_pass_debug_sources(app, result.pass_debug_sources);
+                    app.shell.pick_targets = result.pick_targets;
                     let live_pass_names: std::collections::HashSet<String> =
                         app.shell.pass_debug_sources.keys().cloned().collect();
                     app.shell
                         .pass_shader_overrides
                         .retain(|pass_name, _| live_pass_names.contains(pass_name));
+                    app.shell
+                        .pass_debug_overlays
+                        .retain(|pass_name, _| live_pass_names.contains(pass_name));
                     app.runtime.last_pipeline_signature = Some(result.pipeline_signature);
                     app.runtime.uniform_scene = prepared_scene_candidate
                         .or_else(|| renderer::prepare_scene(&scene).ok().map(|p| p.scene));
@@ -875,6 +1156,8 @@ pub fn apply_scene_update(
                     app.runtime.timeline_preview_was_active = false;
                     restore_state_control_after_scene_change(app, previous_state_control_selection);
 
+                    app.core.ws_hub.set_session_last_good(client_id, &scene);
+                    app.runtime.scene_client_id = client_id;
                     if let Ok(mut g) = app.runtime.last_good.lock() {
                         *g = Some(scene);
                     }
@@ -902,7 +1185,7 @@ pub fn apply_scene_update(
                     app.runtime.timeline_pre_hover_overrides = None;
                     app.runtime.timeline_preview_was_active = false;
                     app.runtime.state_control_selection = None;
-                    broadcast_error(app, request_id, "VALIDATION_ERROR", message);
+                    broadcast_error(app, request_id, client_id, "VALIDATION_ERROR", message);
                     apply_error_plane(app, render_state);
                     SceneApplyResult {
                         did_rebuild_shader_space: true,
@@ -926,7 +1209,7 @@ pub fn apply_scene_update(
                     app.runtime.uniform_scene = None;
                     app.runtime.animation_session = None;
                     app.runtime.state_control_selection = None;
-                    broadcast_error(app, request_id, "PANIC", message);
+                    broadcast_error(app, request_id, client_id, "PANIC", message);
                     apply_error_plane(app, render_state);
                     SceneApplyResult {
                         did_rebuild_shader_space: true,
@@ -971,9 +1254,162 @@ pub fn apply_scene_update(
                 matrix_update: MatrixSceneUpdate::None,
             }
         }
+        ws::SceneUpdate::RenderStatsRequest {
+            request_id,
+            client_id,
+        } => {
+            let texture_bytes = app
+                .shell
+                .resource_snapshot
+                .as_ref()
+                .map(|snapshot| {
+                    crate::profile::texture_memory_bytes(&app.core.shader_space, snapshot)
+                })
+                .unwrap_or(0);
+            let gpu_timing = renderer::render_with_gpu_timestamps(&app.core.shader_space);
+            ws::broadcast_render_stats(
+                &app.core.ws_hub,
+                request_id,
+                client_id,
+                app.runtime.latest_render_profile.as_ref(),
+                gpu_timing.as_ref(),
+                texture_bytes,
+            );
+            SceneApplyResult {
+                did_rebuild_shader_space: false,
+                texture_filter_override: None,
+                reset_viewport: false,
+                previous_output_hash: None,
+                matrix_update: MatrixSceneUpdate::None,
+            }
+        }
+        ws::SceneUpdate::PixelRead {
+            request,
+            request_id,
+            client_id,
+        } => {
+            let texture = request
+                .texture
+                .clone()
+                .unwrap_or_else(|| app.core.scene_output_texture_name.as_str().to_string());
+            match super::canvas::pixel_overlay::read_texture_rect(app, &texture, request.rect) {
+                Ok(pixels) => ws::broadcast_pixel_read_result(
+                    &app.core.ws_hub,
+                    request_id,
+                    client_id,
+                    &request,
+                    &texture,
+                    &pixels,
+                ),
+                Err(e) => broadcast_error(
+                    app,
+                    request_id,
+                    client_id,
+                    "PIXEL_READ_FAILED",
+                    format!("{e:#}"),
+                ),
+            }
+            SceneApplyResult {
+                did_rebuild_shader_space: false,
+                texture_filter_override: None,
+                reset_viewport: false,
+                previous_output_hash: None,
+                matrix_update: MatrixSceneUpdate::None,
+            }
+        }
+        ws::SceneUpdate::ResourceQuery {
+            query,
+            request_id,
+            client_id,
+        } => {
+            match query {
+                ws::ResourceQuery::Resources => {
+                    let snapshot = crate::ui::resource_tree::ResourceSnapshot::capture(
+                        &app.core.shader_space,
+                        &app.core.passes,
+                        Some(app.core.output_texture_name.as_str()),
+                        app.runtime.uniform_scene.as_ref(),
+                    );
+                    ws::broadcast_resources(
+                        &app.core.ws_hub,
+                        request_id,
+                        client_id,
+                        &snapshot,
+                        |name| {
+                            app.core.shader_space.texture_info(name).map(|info| {
+                                (
+                                    [info.size.width, info.size.height],
+                                    format!("{:?}", info.format),
+                                )
+                            })
+                        },
+                    );
+                }
+                ws::ResourceQuery::PreviewTexture(request) => {
+                    let preview = super::canvas::pixel_overlay::read_texture_display_rgba8(
+                        app,
+                        &request.name,
+                    )
+                    .and_then(|image| {
+                        ws::broadcast_texture_preview(
+                            &app.core.ws_hub,
+                            request_id.clone(),
+                            client_id,
+                            &request,
+                            image,
+                        )
+                    });
+                    if let Err(e) = preview {
+                        broadcast_error(
+                            app,
+                            request_id,
+                            client_id,
+                            "PIXEL_READ_FAILED",
+                            format!("{e:#}"),
+                        );
+                    }
+                }
+            }
+            SceneApplyResult {
+                did_rebuild_shader_space: false,
+                texture_filter_override: None,
+                reset_viewport: false,
+                previous_output_hash: None,
+                matrix_update: MatrixSceneUpdate::None,
+            }
+        }
+        ws::SceneUpdate::Analysis {
+            request,
+            request_id,
+            client_id,
+        } => {
+            if let Err(e) = super::canvas::analysis_export::answer_analysis_request(
+                app,
+                render_state,
+                &request,
+                request_id.clone(),
+                client_id,
+            ) {
+                broadcast_error(
+                    app,
+                    request_id,
+                    client_id,
+                    "ANALYSIS_FAILED",
+                    format!("{e:#}"),
+                );
+            }
+            SceneApplyResult {
+                did_rebuild_shader_space: false,
+                texture_filter_override: None,
+                reset_viewport: false,
+                previous_output_hash: None,
+                matrix_update: MatrixSceneUpdate::None,
+            }
+        }
         ws::SceneUpdate::ParseError {
             message,
             request_id,
+            client_id,
         } => {
             eprintln!("[error-plane] scene parse error: {message}");
             app.canvas.reference.scene_desired = None;
@@ -983,7 +1419,7 @@ pub fn apply_scene_update(
             app.runtime.last_live_overrides = None;
             app.runtime.timeline_pre_hover_overrides = None;
             app.runtime.timeline_preview_was_active = false;
-            broadcast_error(app, request_id, "PARSE_ERROR", message);
+            broadcast_error(app, request_id, client_id, "PARSE_ERROR", message);
             apply_error_plane(app, render_state);
             SceneApplyResult {
                 did_rebuild_shader_space: true,
@@ -1030,7 +1466,13 @@ fn apply_error_plane(app: &mut App, render_state: &egui_wgpu::RenderState) {
     }
 }
 
-fn broadcast_error(app: &App, request_id: Option<String>, code: &str, message: String) {
+fn broadcast_error(
+    app: &App,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    code: &str,
+    message: String,
+) {
     let msg = protocol::WSMessage {
         msg_type: "error".to_string(),
         timestamp: protocol::now_millis(),
@@ -1041,7 +1483,7 @@ fn broadcast_error(app: &App, request_id: Option<String>, code: &str, message: S
         }),
     };
     if let Ok(text) = serde_json::to_string(&msg) {
-        app.core.ws_hub.broadcast(text);
+        app.core.ws_hub.reply(client_id, text);
     }
 }
 
@@ -1096,6 +1538,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn uniform_delta_rebuilds_when_another_client_owns_the_screen() {
+        assert!(!uniform_delta_needs_rebuild(Some(3), Some(3)));
+        assert!(!uniform_delta_needs_rebuild(None, None));
+        assert!(uniform_delta_needs_rebuild(Some(3), Some(4)));
+        assert!(uniform_delta_needs_rebuild(None, Some(4)));
+        assert!(uniform_delta_needs_rebuild(Some(3), None));
+    }
+
     #[test]
     fn scene_update_preserves_viewport_when_output_resolution_is_unchanged() {
         assert!(!should_reset_viewport_for_scene_resolution(
@@ -1147,6 +1598,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let schema = GraphSchema {
@@ -1176,6 +1628,9 @@ mod tests {
                     1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
                 ],
                 camera_position: [0.0, 0.0, 0.0, 0.0],
+                delta_time: 0.0,
+                frame: 0,
+                _pad1: [0.0; 2],
             },
             graph_binding: Some(GraphBinding {
                 buffer_name: ResourceName::from("params.passA.graph"),
@@ -1216,6 +1671,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let schema = GraphSchema {
@@ -1243,6 +1699,9 @@ mod tests {
                     1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
                 ],
                 camera_position: [0.0, 0.0, 0.0, 0.0],
+                delta_time: 0.0,
+                frame: 0,
+                _pad1: [0.0; 2],
             },
             graph_binding: Some(GraphBinding {
                 buffer_name: ResourceName::from("params.passA.graph"),
@@ -1285,6 +1744,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let schema = GraphSchema {
             fields: vec![GraphField {
@@ -1310,6 +1770,9 @@ mod tests {
                     1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
                 ],
                 camera_position: [0.0, 0.0, 0.0, 0.0],
+                delta_time: 0.0,
+                frame: 0,
+                _pad1: [0.0; 2],
             },
             graph_binding: None,
             last_graph_hash: None,
@@ -1356,6 +1819,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let updated = crate::dsl::Node {
@@ -1399,6 +1863,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let updated = crate::dsl::Node {
@@ -1442,6 +1907,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let updated = crate::dsl::Node {
@@ -29,6 +29,48 @@ pub enum RefImageMode {
     Diff,
 }
 
+/// How the previous scene version's output is shown over the current one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OnionSkinMode {
+    #[default]
+    Off,
+    Blend,
+    Flicker,
+}
+
+impl OnionSkinMode {
+    pub const DEFAULT_FLICKER_HZ: f32 = 2.0;
+    pub const MIN_FLICKER_HZ: f32 = 0.5;
+    pub const MAX_FLICKER_HZ: f32 = 15.0;
+}
+
+/// Which channel the canvas presents. Single colour channels show as
+/// grayscale; alpha composites the image over a checkerboard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DisplayChannel {
+    #[default]
+    Rgb,
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+impl DisplayChannel {
+    /// In keyboard order: keys 1-5 select these.
+    pub const ALL: [Self; 5] = [Self::Rgb, Self::Red, Self::Green, Self::Blue, Self::Alpha];
+
+    pub fn short_label(self) -> &'static str {
+        match self {
+            Self::Rgb => "RGB",
+            Self::Red => "R",
+            Self::Green => "G",
+            Self::Blue => "B",
+            Self::Alpha => "A",
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RefImageSource {
     Manual,
@@ -129,6 +171,46 @@ impl DiffMetricMode {
     }
 }
 
+/// Space both diff operands are converted into before metrics are computed.
+///
+/// Render and reference textures are sampled as linear values; `Srgb`
+/// re-encodes both so deltas match what a viewer sees on an 8-bit display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DiffColorSpace {
+    #[default]
+    Linear,
+    Srgb,
+}
+
+impl DiffColorSpace {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::Srgb => "sRGB",
+        }
+    }
+
+    pub fn shader_code(self) -> u32 {
+        match self {
+            Self::Linear => 0,
+            Self::Srgb => 1,
+        }
+    }
+
+    /// Converts a linear RGBA sample into this space; alpha is unchanged.
+    pub fn from_linear_rgba(self, rgba: [f32; 4]) -> [f32; 4] {
+        match self {
+            Self::Linear => rgba,
+            Self::Srgb => [
+                crate::color::linear_to_srgb(rgba[0]),
+                crate::color::linear_to_srgb(rgba[1]),
+                crate::color::linear_to_srgb(rgba[2]),
+                rgba[3],
+            ],
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum AnalysisTab {
     #[default]
@@ -147,10 +229,41 @@ impl AnalysisTab {
     }
 }
 
+/// What the clipping thresholds are compared against.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ClippingMode {
+    /// Brightest channel for highlights, darkest for shadows: any clipped
+    /// channel is marked.
+    #[default]
+    PerChannel,
+    /// Rec.709 luminance.
+    Luminance,
+}
+
+/// How clipped pixels are shown over the canvas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ClippingStyle {
+    /// Solid tint: orange highlights, blue shadows.
+    #[default]
+    Map,
+    /// Animated diagonal stripes, leaving the image visible between them.
+    Zebra,
+    /// Stripes over the tint.
+    Both,
+}
+
+impl ClippingStyle {
+    pub fn draws_zebra(self) -> bool {
+        matches!(self, Self::Zebra | Self::Both)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ClippingSettings {
     pub shadow_threshold: f32,
     pub highlight_threshold: f32,
+    pub mode: ClippingMode,
+    pub style: ClippingStyle,
 }
 
 impl Default for ClippingSettings {
@@ -158,6 +271,111 @@ impl Default for ClippingSettings {
         Self {
             shadow_threshold: 0.02,
             highlight_threshold: 0.98,
+            mode: ClippingMode::default(),
+            style: ClippingStyle::default(),
+        }
+    }
+}
+
+/// Band edges of the false-color view, in stops of Rec.709 luminance
+/// relative to 18% middle grey. Edges stay in ascending order; pixels below
+/// the first edge fall in the first band, at or above the last in the last.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FalseColorSettings {
+    pub edges: [f32; FalseColorSettings::EDGE_COUNT],
+}
+
+impl FalseColorSettings {
+    pub const EDGE_COUNT: usize = 6;
+    pub const MIN_STOPS: f32 = -10.0;
+    pub const MAX_STOPS: f32 = 8.0;
+    /// Sidebar label of each edge: the band starting at it.
+    pub const EDGE_LABELS: [&'static str; Self::EDGE_COUNT] =
+        ["Under", "Shadow", "Grey", "Above", "Bright", "Clip"];
+
+    /// Moves one edge, clamped between its neighbours. Returns whether it
+    /// changed.
+    pub fn set_edge(&mut self, index: usize, stops: f32) -> bool {
+        if index >= Self::EDGE_COUNT || !stops.is_finite() {
+            return false;
+        }
+        let lo = if index == 0 {
+            Self::MIN_STOPS
+        } else {
+            self.edges[index - 1]
+        };
+        let hi = if index + 1 == Self::EDGE_COUNT {
+            Self::MAX_STOPS
+        } else {
+            self.edges[index + 1]
+        };
+        let stops = stops.clamp(lo, hi);
+        if (self.edges[index] - stops).abs() <= f32::EPSILON {
+            return false;
+        }
+        self.edges[index] = stops;
+        true
+    }
+}
+
+impl Default for FalseColorSettings {
+    fn default() -> Self {
+        Self {
+            // Crushed / under / grey / middle grey / grey / bright / clipped,
+            // where clipped starts at linear 1.0 (log2(1 / 0.18) stops).
+            edges: [-6.0, -4.0, -0.5, 0.5, 2.0, 2.47],
+        }
+    }
+}
+
+/// Transfer function the canvas previews the output through.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DisplayTransform {
+    #[default]
+    Srgb,
+    /// SMPTE ST 2084, with 1.0 as 203-nit reference white.
+    Pq,
+    /// ARIB STD-B67, with 1.0 at the 75% reference-white signal.
+    Hlg,
+}
+
+impl DisplayTransform {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Srgb => "sRGB",
+            Self::Pq => "PQ",
+            Self::Hlg => "HLG",
+        }
+    }
+}
+
+/// Viewer-only exposure, gamma and transfer for the canvas. Never applied to
+/// file output or WS frames.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewTransformSettings {
+    pub exposure_stops: f32,
+    pub gamma: f32,
+    pub transform: DisplayTransform,
+}
+
+impl ViewTransformSettings {
+    pub const MIN_EXPOSURE_STOPS: f32 = -8.0;
+    pub const MAX_EXPOSURE_STOPS: f32 = 8.0;
+    pub const MIN_GAMMA: f32 = 0.25;
+    pub const MAX_GAMMA: f32 = 4.0;
+
+    /// Whether the canvas can show the texture directly.
+    pub fn is_identity(&self) -> bool {
+        self.exposure_stops == 0.0 && self.gamma == 1.0 && self.transform == DisplayTransform::Srgb
+    }
+}
+
+impl Default for ViewTransformSettings {
+    fn default() -> Self {
+        Self {
+            exposure_stops: 0.0,
+            gamma: 1.0,
+            transform: DisplayTransform::Srgb,
         }
     }
 }
@@ -247,6 +465,14 @@ pub struct SampledPixel {
     pub rgba: [f32; 4],
 }
 
+#[derive(Clone, Debug)]
+pub struct PickedPixel {
+    pub x: u32,
+    pub y: u32,
+    /// Top-most pass at the pixel; `None` when no pass covers it.
+    pub target: Option<renderer::PickTarget>,
+}
+
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug)]
 pub enum ViewportOperationIndicator {
@@ -377,7 +603,39 @@ pub(super) struct AppRuntime {
     pub time_updates_enabled_prev_frame: bool,
     pub time_value_secs: f32,
     pub time_last_raw_secs: f32,
+    /// Scene-time step of the last advance tick (Time node `delta`).
+    pub time_delta_secs: f32,
+    /// Advance ticks that moved scene time forward since it was last reset (Time node `frame`).
+    pub frame_index: u32,
     pub latest_render_profile: Option<RenderProfile>,
+    /// WS client whose scene is on screen; `None` for startup, template and
+    /// dropped scenes.
+    pub scene_client_id: Option<u64>,
+    /// Scene loaded from a JSON file dropped onto the canvas. Applied by the
+    /// next ingest pass through the same path as a WS `scene_update`.
+    pub dropped_scene_update: Option<ws::SceneUpdate>,
+    /// NaN/Inf fragments safe mode replaced in the last rendered frame, summed
+    /// over sanitized passes. `None` when safe mode is off or not yet counted.
+    pub non_finite_count: Option<u64>,
+    pub non_finite_counted_at: Option<f64>,
+}
+
+impl AppRuntime {
+    /// Frame clock written into every pass's `Params` for the current frame.
+    pub fn frame_timing(&self) -> renderer::FrameTiming {
+        renderer::FrameTiming {
+            time_secs: self.time_value_secs,
+            delta_secs: self.time_delta_secs,
+            frame: self.frame_index,
+        }
+    }
+
+    /// Reset scene time and the frame clock derived from it.
+    pub fn reset_scene_time(&mut self) {
+        self.time_value_secs = 0.0;
+        self.time_delta_secs = 0.0;
+        self.frame_index = 0;
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -435,6 +693,16 @@ pub(super) struct AppShell {
     pub pass_debug_sources_revision: u64,
     pub pass_debug_windows: crate::ui::pass_debug_window::PassDebugWindowMap,
     pub pass_shader_overrides: std::collections::HashMap<String, String>,
+    /// Debug visualization toggled per pass from the resource tree.
+    pub pass_debug_overlays: std::collections::HashMap<String, renderer::PassDebugOverlay>,
+    /// Global coordinate visualization applied to every draw pass on rebuild.
+    pub channel_debug: renderer::ChannelDebugMode,
+    /// Safe mode sanitize stage applied to pass outputs on rebuild.
+    pub non_finite_sanitize: renderer::NonFiniteSanitizeOptions,
+    /// GPU picking: render pass ids so canvas clicks report the top-most pass.
+    pub pick_ids: bool,
+    pub pick_targets: Vec<renderer::PickTarget>,
+    pub last_pick: Option<PickedPixel>,
     pub pending_shortwire_diff_capture:
         Option<crate::ui::pass_debug_window::ShortwireDiffCaptureRequest>,
     pub debug_artifacts: crate::debug_artifacts::DebugArtifactStore,
@@ -465,9 +733,12 @@ pub struct App {
 
 pub(super) fn scene_uses_time(scene: &crate::dsl::SceneDSL) -> bool {
     scene.nodes.iter().any(|node| {
-        matches!(node.node_type.as_str(), "TimeInput" | "Time")
-            || (node.node_type == "ShaderMaterial"
-                && crate::renderer::node_compiler::shader_material::node_uses_time(node))
+        // FeedbackPass accumulates history, so it must render every frame.
+        matches!(
+            node.node_type.as_str(),
+            "TimeInput" | "Time" | "FeedbackPass"
+        ) || (node.node_type == "ShaderMaterial"
+            && crate::renderer::node_compiler::shader_material::node_uses_time(node))
     })
 }
 
@@ -690,7 +961,13 @@ impl App {
                 time_updates_enabled_prev_frame: true,
                 time_value_secs: 0.0,
                 time_last_raw_secs: 0.0,
+                time_delta_secs: 0.0,
+                frame_index: 0,
                 latest_render_profile: None,
+                scene_client_id: None,
+                dropped_scene_update: None,
+                non_finite_count: None,
+                non_finite_counted_at: None,
             },
             shell: AppShell {
                 window_mode: UiWindowMode::Sidebar,
@@ -708,6 +985,12 @@ impl App {
                 pass_debug_sources_revision: 0,
                 pass_debug_windows: crate::ui::pass_debug_window::PassDebugWindowMap::default(),
                 pass_shader_overrides: std::collections::HashMap::new(),
+                pass_debug_overlays: std::collections::HashMap::new(),
+                channel_debug: renderer::ChannelDebugMode::Off,
+                non_finite_sanitize: renderer::NonFiniteSanitizeOptions::default(),
+                pick_ids: false,
+                pick_targets: Vec::new(),
+                last_pick: None,
                 pending_shortwire_diff_capture: None,
                 debug_artifacts,
                 nforge_path: init.nforge_path,
@@ -770,7 +1053,10 @@ impl App {
 mod tests {
     use std::collections::HashMap;
 
-    use super::{AnalysisTab, ClippingSettings, RenderTextureFpsTracker};
+    use super::{
+        AnalysisTab, ClippingSettings, DisplayTransform, FalseColorSettings,
+        RenderTextureFpsTracker, ViewTransformSettings,
+    };
     use crate::dsl::{Metadata, Node, SceneDSL};
 
     #[test]
@@ -787,6 +1073,40 @@ mod tests {
         assert!((0.75..=1.0).contains(&settings.highlight_threshold));
     }
 
+    #[test]
+    fn false_color_edges_stay_ordered() {
+        let mut settings = FalseColorSettings::default();
+        assert!(settings.edges.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert!(settings.set_edge(2, 1.0));
+        assert_eq!(settings.edges[2], 0.5);
+        assert!(settings.set_edge(0, -20.0));
+        assert_eq!(settings.edges[0], FalseColorSettings::MIN_STOPS);
+        assert!(!settings.set_edge(0, -20.0));
+        assert!(!settings.set_edge(FalseColorSettings::EDGE_COUNT, 0.0));
+        assert!(!settings.set_edge(1, f32::NAN));
+    }
+
+    #[test]
+    fn view_transform_identity_only_at_defaults() {
+        let settings = ViewTransformSettings::default();
+        assert!(settings.is_identity());
+        assert!(
+            !ViewTransformSettings {
+                exposure_stops: 1.0,
+                ..settings
+            }
+            .is_identity()
+        );
+        assert!(
+            !ViewTransformSettings {
+                transform: DisplayTransform::Pq,
+                ..settings
+            }
+            .is_identity()
+        );
+    }
+
     #[test]
     fn render_texture_fps_counts_scene_redraws_within_last_second() {
         let mut tracker = RenderTextureFpsTracker::default();
@@ -853,6 +1173,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
@@ -868,6 +1189,12 @@ mod tests {
         assert!(super::scene_uses_time(&scene));
     }
 
+    #[test]
+    fn scene_uses_time_returns_true_for_feedback_pass() {
+        let scene = scene_with_node_types(&["FeedbackPass"]);
+        assert!(super::scene_uses_time(&scene));
+    }
+
     #[test]
     fn scene_uses_time_returns_false_when_time_nodes_absent() {
         let scene = scene_with_node_types(&["FloatInput", "ColorInput"]);
@@ -1019,6 +1346,7 @@ fn shader_material(in: ShaderMaterialInput) -> vec4f {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let pools = super::extract_resource_pools(&scene);
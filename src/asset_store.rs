@@ -17,6 +17,16 @@ pub struct AssetData {
     pub original_name: String,
 }
 
+/// Asset metadata without its bytes, as listed to WS clients.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetSummary {
+    pub asset_id: String,
+    pub mime_type: String,
+    pub original_name: String,
+    pub byte_length: usize,
+}
+
 /// Thread-safe, clone-friendly in-memory asset cache keyed by `assetId`.
 #[derive(Debug, Clone)]
 pub struct AssetStore {
@@ -102,6 +112,24 @@ impl AssetStore {
             .collect()
     }
 
+    /// Summaries of every stored asset, sorted by id.
+    pub fn summaries(&self) -> Vec<AssetSummary> {
+        let Ok(map) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let mut summaries: Vec<AssetSummary> = map
+            .iter()
+            .map(|(asset_id, data)| AssetSummary {
+                asset_id: asset_id.clone(),
+                mime_type: data.mime_type.clone(),
+                original_name: data.original_name.clone(),
+                byte_length: data.bytes.len(),
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+        summaries
+    }
+
     /// Clear all assets.
     pub fn clear(&self) {
         if let Ok(mut map) = self.inner.lock() {
@@ -254,6 +282,19 @@ mod tests {
         assert_eq!(store.revision(), 5);
     }
 
+    #[test]
+    fn summaries_list_assets_sorted_without_bytes() {
+        let store = AssetStore::new();
+        store.insert("b", sample_asset("b.png"));
+        store.insert("a", sample_asset("a.png"));
+
+        let summaries = store.summaries();
+        let ids: Vec<&str> = summaries.iter().map(|s| s.asset_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(summaries[0].byte_length, 3);
+        assert_eq!(summaries[0].mime_type, "image/png");
+    }
+
     #[test]
     fn nforge_debug_artifacts_load_and_save_round_trip() {
         let path = temp_nforge_path("debug-artifacts");
@@ -4,7 +4,9 @@ fn clamp01(x: f32) -> f32 {
     x.clamp(0.0, 1.0)
 }
 
-fn linear_to_srgb_channel(x: f32) -> f32 {
+/// sRGB transfer function (linear -> encoded). Unclamped: HDR values above 1
+/// follow the power segment and negatives the linear segment.
+pub fn linear_to_srgb(x: f32) -> f32 {
     // https://en.wikipedia.org/wiki/SRGB
     if x <= 0.003_130_8 {
         12.92 * x
@@ -13,6 +15,15 @@ fn linear_to_srgb_channel(x: f32) -> f32 {
     }
 }
 
+/// Inverse of [`linear_to_srgb`] (encoded -> linear).
+pub fn srgb_to_linear(x: f32) -> f32 {
+    if x <= 0.040_45 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
 fn xyz_to_linear_srgb([x, y, z]: [f32; 3]) -> [f32; 3] {
     // D65 standard illuminant
     // https://en.wikipedia.org/wiki/SRGB#From_CIE_XYZ_to_sRGB
@@ -70,9 +81,9 @@ fn lab_to_xyz_d65([l, a, b]: [f32; 3]) -> [f32; 3] {
 pub fn lab(l: f32, a: f32, b: f32) -> Color32 {
     let xyz = lab_to_xyz_d65([l, a, b]);
     let rgb_linear = xyz_to_linear_srgb(xyz);
-    let r = clamp01(linear_to_srgb_channel(rgb_linear[0]));
-    let g = clamp01(linear_to_srgb_channel(rgb_linear[1]));
-    let b = clamp01(linear_to_srgb_channel(rgb_linear[2]));
+    let r = clamp01(linear_to_srgb(rgb_linear[0]));
+    let g = clamp01(linear_to_srgb(rgb_linear[1]));
+    let b = clamp01(linear_to_srgb(rgb_linear[2]));
 
     Color32::from_rgb(
         (r * 255.0).round() as u8,
@@ -99,4 +110,13 @@ mod tests {
         let [r, g, b, _a] = c.to_array();
         assert!(r >= 250 && g >= 250 && b >= 250, "got rgb=({r},{g},{b})");
     }
+
+    #[test]
+    fn srgb_transfer_round_trips() {
+        for x in [0.0, 0.002, 0.04, 0.18, 0.5, 1.0, 4.0] {
+            let back = srgb_to_linear(linear_to_srgb(x));
+            assert!((back - x).abs() <= 1e-5 * x.max(1.0), "{x} -> {back}");
+        }
+        assert!((linear_to_srgb(0.18) - 0.4614).abs() < 1e-3);
+    }
 }
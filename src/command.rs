@@ -6,7 +6,7 @@ use std::{
 };
 
 use anyhow::{Result, anyhow};
-use node_forge_render_server::{app, asset_store, dsl, profile, renderer, ws};
+use node_forge_render_server::{app, asset_store, dsl, profile, renderer, testing, ws};
 use rust_wgpu_fiber::eframe::{self, egui, egui_wgpu, wgpu};
 
 #[derive(Debug, Default, Clone)]
@@ -26,6 +26,63 @@ struct Cli {
     profile_format: Option<String>,
     profile_frames: u32,
     profile_warmup_frames: u32,
+    diff_scenes: Option<(PathBuf, PathBuf)>,
+    format_scene: Option<PathBuf>,
+    explain: bool,
+    device_profile: Option<String>,
+    bless: bool,
+    features: Vec<String>,
+    frames: Option<std::ops::Range<u32>>,
+    fps: Option<f32>,
+    video_codec: Option<String>,
+    video_bitrate: Option<String>,
+    batch: Option<PathBuf>,
+    sweeps: Vec<dsl::ParamSweep>,
+    output_overrides: dsl::OutputOverrides,
+    validate: bool,
+    dump_wgsl: Option<PathBuf>,
+    inspect: bool,
+    deterministic: bool,
+    ws_token: Option<String>,
+    ws_allowed_origins: Vec<String>,
+    ws_timeout_secs: Option<u64>,
+    record_ws: Option<PathBuf>,
+    replay_ws: Option<PathBuf>,
+    stdio: bool,
+}
+
+/// `--batch` manifest: scenes rendered in order on one headless device.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchManifest {
+    jobs: Vec<BatchJob>,
+}
+
+/// One `--batch` render. Relative paths resolve against the manifest's directory.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchJob {
+    /// SceneDSL JSON or `.nforge` file.
+    scene: PathBuf,
+    /// Output file; defaults to the File target's fileName under `--outputdir`
+    /// or the scene's directory.
+    #[serde(default)]
+    output: Option<PathBuf>,
+    /// Feature overrides in `--features` syntax, applied after the global ones.
+    #[serde(default)]
+    features: Vec<String>,
+}
+
+/// Frame rate for `--frames` renders without `--fps`.
+const DEFAULT_SEQUENCE_FPS: f32 = 30.0;
+
+/// Frame range and rate for an image-sequence or video render (`--frames`, `--fps`).
+#[derive(Debug, Clone, PartialEq)]
+struct FrameSequence {
+    frames: std::ops::Range<u32>,
+    fps: f32,
+    /// Encoder settings when the output is `.mp4` or `.webm`.
+    video: renderer::VideoEncodeOptions,
 }
 
 #[derive(Debug, Clone)]
@@ -173,6 +230,7 @@ fn spawn_template_watcher(
                         let _ = scene_tx.try_send(ws::SceneUpdate::Parsed {
                             scene,
                             request_id: None,
+                            client_id: None,
                             source: ws::ParsedSceneSource::SceneDelta,
                             perf_trace: None,
                         });
@@ -185,6 +243,27 @@ fn spawn_template_watcher(
     });
 }
 
+/// Parses `start..end` (exclusive), `start..=end` (inclusive) or a single frame.
+fn parse_frame_range(value: &str) -> Result<std::ops::Range<u32>> {
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<u32>()
+            .map_err(|_| anyhow!("--frames expects <start..end>, got {value:?}"))
+    };
+    let range = if let Some((start, end)) = value.split_once("..=") {
+        parse(start)?..parse(end)?.saturating_add(1)
+    } else if let Some((start, end)) = value.split_once("..") {
+        parse(start)?..parse(end)?
+    } else {
+        let frame = parse(value)?;
+        frame..frame.saturating_add(1)
+    };
+    if range.is_empty() {
+        return Err(anyhow!("--frames range {value:?} is empty"));
+    }
+    Ok(range)
+}
+
 fn parse_cli(args: &[String]) -> Result<Cli> {
     let mut cli = Cli::default();
     let mut i = 0;
@@ -222,6 +301,13 @@ fn parse_cli(args: &[String]) -> Result<Cli> {
                 cli.output = Some(PathBuf::from(v));
                 i += 2;
             }
+            "--dump-wgsl" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --dump-wgsl"));
+                };
+                cli.dump_wgsl = Some(PathBuf::from(v));
+                i += 2;
+            }
             "--dump-wgsl-dir" => {
                 let Some(v) = args.get(i + 1) else {
                     return Err(anyhow!("missing value for --dump-wgsl-dir"));
@@ -289,9 +375,173 @@ fn parse_cli(args: &[String]) -> Result<Cli> {
                     .map_err(|_| anyhow!("--profile-warmup-frames must be an integer"))?;
                 i += 2;
             }
+            "--diff-scenes" => {
+                let (Some(a), Some(b)) = (args.get(i + 1), args.get(i + 2)) else {
+                    return Err(anyhow!("--diff-scenes requires two scene paths"));
+                };
+                cli.diff_scenes = Some((PathBuf::from(a), PathBuf::from(b)));
+                i += 3;
+            }
+            "--resolution" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --resolution"));
+                };
+                cli.output_overrides.resolution = Some(
+                    dsl::parse_resolution_override(v).map_err(|e| anyhow!("--resolution: {e}"))?,
+                );
+                i += 2;
+            }
+            "--format" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --format"));
+                };
+                cli.output_overrides.format =
+                    Some(dsl::parse_format_override(v).map_err(|e| anyhow!("--format: {e}"))?);
+                i += 2;
+            }
+            "--sweep" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --sweep"));
+                };
+                cli.sweeps.push(dsl::ParamSweep::parse(v)?);
+                i += 2;
+            }
+            "--batch" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --batch"));
+                };
+                cli.batch = Some(PathBuf::from(v));
+                i += 2;
+            }
+            "--format-scene" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --format-scene"));
+                };
+                cli.format_scene = Some(PathBuf::from(v));
+                i += 2;
+            }
+            "--explain" => {
+                cli.explain = true;
+                i += 1;
+            }
+            "--validate" => {
+                cli.validate = true;
+                i += 1;
+            }
+            "--inspect" => {
+                cli.inspect = true;
+                i += 1;
+            }
+            "--deterministic" => {
+                cli.deterministic = true;
+                i += 1;
+            }
+            "--device-profile" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --device-profile"));
+                };
+                cli.device_profile = Some(v.clone());
+                i += 2;
+            }
+            "--bless" => {
+                cli.bless = true;
+                i += 1;
+            }
+            "--features" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --features"));
+                };
+                cli.features.push(v.clone());
+                i += 2;
+            }
+            "--frames" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --frames"));
+                };
+                cli.frames = Some(parse_frame_range(v)?);
+                i += 2;
+            }
+            "--fps" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --fps"));
+                };
+                let fps = v
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|fps| fps.is_finite() && *fps > 0.0)
+                    .ok_or_else(|| anyhow!("--fps must be a positive number"))?;
+                cli.fps = Some(fps);
+                i += 2;
+            }
+            "--video-codec" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --video-codec"));
+                };
+                cli.video_codec = Some(v.clone());
+                i += 2;
+            }
+            "--video-bitrate" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --video-bitrate"));
+                };
+                let valid = v
+                    .strip_suffix(['k', 'K', 'm', 'M'])
+                    .unwrap_or(v)
+                    .parse::<f64>()
+                    .is_ok_and(|rate| rate.is_finite() && rate > 0.0);
+                if !valid {
+                    return Err(anyhow!(
+                        "--video-bitrate expects a positive rate like 8M or 2500k, got {v:?}"
+                    ));
+                }
+                cli.video_bitrate = Some(v.clone());
+                i += 2;
+            }
+            "--ws-token" => {
+                let Some(v) = args.get(i + 1).filter(|v| !v.is_empty()) else {
+                    return Err(anyhow!("missing value for --ws-token"));
+                };
+                cli.ws_token = Some(v.clone());
+                i += 2;
+            }
+            "--ws-allow-origin" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --ws-allow-origin"));
+                };
+                cli.ws_allowed_origins.push(v.clone());
+                i += 2;
+            }
+            "--ws-timeout" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --ws-timeout"));
+                };
+                let secs = v.parse::<u64>().map_err(|_| {
+                    anyhow!("--ws-timeout expects whole seconds (0 disables), got {v:?}")
+                })?;
+                cli.ws_timeout_secs = Some(secs);
+                i += 2;
+            }
+            "--record-ws" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --record-ws"));
+                };
+                cli.record_ws = Some(PathBuf::from(v));
+                i += 2;
+            }
+            "--replay-ws" => {
+                let Some(v) = args.get(i + 1) else {
+                    return Err(anyhow!("missing value for --replay-ws"));
+                };
+                cli.replay_ws = Some(PathBuf::from(v));
+                i += 2;
+            }
+            "--stdio" => {
+                cli.stdio = true;
+                i += 1;
+            }
             other => {
                 return Err(anyhow!(
-                    "unknown argument: {other} (supported: --headless, --dsl-json <scene.json>, --nforge <file.nforge>, --render-to-file, --continuous-redraw, --output <abs/path/to/output>, --outputdir <dir>, --dump-wgsl-dir <dir>, --dump-shader-deps <pass-name>, --dump-shader-deps-output <path>, --profile, --profile-output <path|->, --profile-format ndjson, --profile-frames <n>, --profile-warmup-frames <n>)"
+                    "unknown argument: {other} (supported: --headless, --dsl-json <scene.json>, --nforge <file.nforge>, --render-to-file, --continuous-redraw, --output <abs/path/to/output>, --outputdir <dir>, --dump-wgsl <dir>, --dump-wgsl-dir <dir>, --dump-shader-deps <pass-name>, --dump-shader-deps-output <path>, --profile, --profile-output <path|->, --profile-format ndjson, --profile-frames <n>, --profile-warmup-frames <n>, --diff-scenes <a> <b>, --format-scene <scene.json>, --explain, --device-profile <desktop|phone>, --bless, --features <a,!b,c=false>, --frames <start..end>, --fps <n>, --video-codec <ffmpeg encoder>, --video-bitrate <rate>, --batch <manifest.json>, --sweep <node.param=start:end:steps>, --resolution <WxH>, --format <rgba8|rgba8srgb|rgba16f>, --validate, --inspect, --deterministic, --ws-token <secret>, --ws-allow-origin <origin>, --ws-timeout <seconds>, --record-ws <file>, --replay-ws <file>, --stdio)"
                 ));
             }
         }
@@ -307,6 +557,121 @@ fn parse_cli(args: &[String]) -> Result<Cli> {
             "--dump-shader-deps requires --dsl-json <scene.json> or --nforge <file.nforge>"
         ));
     }
+    if cli.explain && cli.dsl_json.is_none() && cli.nforge.is_none() {
+        return Err(anyhow!(
+            "--explain requires --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    }
+    if cli.dump_wgsl.is_some() && cli.dsl_json.is_none() && cli.nforge.is_none() {
+        return Err(anyhow!(
+            "--dump-wgsl requires --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    }
+    if cli.dump_wgsl.is_some()
+        && (cli.headless || cli.explain || cli.validate || cli.batch.is_some())
+    {
+        return Err(anyhow!(
+            "--dump-wgsl only writes shaders; use --dump-wgsl-dir <dir> to dump during a --headless render"
+        ));
+    }
+    if cli.validate && cli.dsl_json.is_none() && cli.nforge.is_none() {
+        return Err(anyhow!(
+            "--validate requires --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    }
+    if cli.validate
+        && (cli.explain
+            || cli.batch.is_some()
+            || cli.frames.is_some()
+            || !cli.sweeps.is_empty()
+            || cli.profile
+            || cli.bless)
+    {
+        return Err(anyhow!(
+            "--validate cannot be combined with --explain, --batch, --frames, --sweep, --profile or --bless"
+        ));
+    }
+    if cli.device_profile.is_some() && !cli.explain {
+        return Err(anyhow!("--device-profile requires --explain"));
+    }
+    if cli.bless && (!cli.headless || (cli.dsl_json.is_none() && cli.nforge.is_none())) {
+        return Err(anyhow!(
+            "--bless requires --headless with --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    }
+    if cli.frames.is_some() && (!cli.headless || (cli.dsl_json.is_none() && cli.nforge.is_none())) {
+        return Err(anyhow!(
+            "--frames requires --headless with --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    }
+    if cli.frames.is_some() && (cli.profile || cli.bless) {
+        return Err(anyhow!(
+            "--frames cannot be combined with --profile or --bless"
+        ));
+    }
+    if cli.fps.is_some() && cli.frames.is_none() {
+        return Err(anyhow!("--fps requires --frames"));
+    }
+    if (cli.video_codec.is_some() || cli.video_bitrate.is_some()) && cli.frames.is_none() {
+        return Err(anyhow!(
+            "--video-codec and --video-bitrate require --frames"
+        ));
+    }
+    if !cli.sweeps.is_empty() && (!cli.headless || (cli.dsl_json.is_none() && cli.nforge.is_none()))
+    {
+        return Err(anyhow!(
+            "--sweep requires --headless with --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    }
+    if !cli.sweeps.is_empty() && (cli.frames.is_some() || cli.profile || cli.bless) {
+        return Err(anyhow!(
+            "--sweep cannot be combined with --frames, --profile or --bless"
+        ));
+    }
+    if !cli.output_overrides.is_empty()
+        && cli.batch.is_none()
+        && (!(cli.headless || cli.inspect) || (cli.dsl_json.is_none() && cli.nforge.is_none()))
+    {
+        return Err(anyhow!(
+            "--resolution and --format require --headless or --inspect with --dsl-json <scene.json> or --nforge <file.nforge>, or --batch"
+        ));
+    }
+    if cli.inspect && cli.dsl_json.is_none() && cli.nforge.is_none() {
+        return Err(anyhow!(
+            "--inspect requires --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    }
+    if cli.inspect && (cli.headless || cli.explain || cli.validate || cli.dump_wgsl.is_some()) {
+        return Err(anyhow!(
+            "--inspect cannot be combined with --headless, --explain, --validate or --dump-wgsl"
+        ));
+    }
+    if cli.batch.is_some()
+        && (cli.dsl_json.is_some()
+            || cli.nforge.is_some()
+            || cli.output.is_some()
+            || cli.render_to_file
+            || cli.frames.is_some()
+            || cli.profile
+            || cli.bless)
+    {
+        return Err(anyhow!(
+            "--batch takes scenes and outputs from the manifest; it cannot be combined with --dsl-json, --nforge, --output, --render-to-file, --frames, --profile or --bless"
+        ));
+    }
+    if cli.deterministic
+        && cli.batch.is_none()
+        && (!cli.headless || (cli.dsl_json.is_none() && cli.nforge.is_none()))
+    {
+        return Err(anyhow!(
+            "--deterministic requires --headless with --dsl-json <scene.json> or --nforge <file.nforge>, or --batch"
+        ));
+    }
+    if cli.deterministic && (cli.frames.is_some() || cli.profile) {
+        return Err(anyhow!(
+            "--deterministic renders a single frame at time 0; it cannot be combined with --frames or --profile"
+        ));
+    }
     if cli.dump_shader_deps_output.is_some() && cli.dump_shader_deps.is_none() {
         return Err(anyhow!(
             "--dump-shader-deps-output requires --dump-shader-deps <pass-name>"
@@ -334,6 +699,16 @@ fn parse_cli(args: &[String]) -> Result<Cli> {
     if cli.profile_format.is_some() && !cli.profile {
         return Err(anyhow!("--profile-format requires --profile"));
     }
+    if cli.stdio && cli.replay_ws.is_some() {
+        return Err(anyhow!(
+            "--replay-ws needs the TCP server and cannot be combined with --stdio"
+        ));
+    }
+    if cli.stdio && cli.profile && cli.profile_output.is_none() {
+        return Err(anyhow!(
+            "--stdio uses stdout for messages; pass --profile-output <path> with --profile"
+        ));
+    }
 
     Ok(cli)
 }
@@ -352,6 +727,28 @@ fn headless_profile_options(cli: &Cli) -> Option<HeadlessProfileOptions> {
     })
 }
 
+fn ws_server_options(cli: &Cli) -> Result<ws::WsServerOptions> {
+    let defaults = ws::WsServerOptions::default();
+    Ok(ws::WsServerOptions {
+        access: ws::WsAccessPolicy {
+            token: cli.ws_token.clone(),
+            allowed_origins: cli.ws_allowed_origins.clone(),
+        },
+        client_timeout: match cli.ws_timeout_secs {
+            None => defaults.client_timeout,
+            Some(0) => None,
+            Some(secs) => Some(std::time::Duration::from_secs(secs)),
+        },
+        record: cli
+            .record_ws
+            .as_deref()
+            .map(ws::WsRecorder::create)
+            .transpose()?,
+        replay: cli.replay_ws.clone(),
+        stdio: cli.stdio,
+    })
+}
+
 fn validate_absolute_output_path(path: &PathBuf) -> Result<()> {
     if !path.is_absolute() {
         return Err(anyhow!(
@@ -399,25 +796,7 @@ fn dump_scene_wgsl(
         return Ok(());
     };
 
-    let bundles = renderer::build_all_pass_wgsl_bundles_from_scene_with_assets(scene, store)?;
-    for (pass_id, bundle) in bundles {
-        write_text_file(
-            dump_dir.join(format!("{pass_id}.vertex.wgsl")),
-            &bundle.vertex,
-        )?;
-        write_text_file(
-            dump_dir.join(format!("{pass_id}.fragment.wgsl")),
-            &bundle.fragment,
-        )?;
-        write_text_file(
-            dump_dir.join(format!("{pass_id}.module.wgsl")),
-            &bundle.module,
-        )?;
-        if let Some(compute) = bundle.compute.as_ref() {
-            write_text_file(dump_dir.join(format!("{pass_id}.compute.wgsl")), compute)?;
-        }
-    }
-
+    renderer::dump_pass_wgsl_bundles(scene, store, dump_dir)?;
     eprintln!("[headless] dumped wgsl: {}", dump_dir.display());
     Ok(())
 }
@@ -542,7 +921,7 @@ fn run_shader_dependency_dump(cli: &Cli) -> Result<()> {
         .as_deref()
         .ok_or_else(|| anyhow!("--dump-shader-deps requires a pass name"))?;
 
-    let (scene, store) = if let Some(nforge_path) = cli.nforge.as_deref() {
+    let (mut scene, store) = if let Some(nforge_path) = cli.nforge.as_deref() {
         asset_store::load_from_nforge(nforge_path)?
     } else if let Some(dsl_json_path) = cli.dsl_json.as_deref() {
         load_scene_from_dsl_json_path(dsl_json_path)?
@@ -551,6 +930,7 @@ fn run_shader_dependency_dump(cli: &Cli) -> Result<()> {
             "--dump-shader-deps requires --dsl-json <scene.json> or --nforge <file.nforge>"
         ));
     };
+    dsl::apply_feature_overrides(&mut scene, &cli.features)?;
 
     let source = pass_debug_source_for_scene(&scene, Some(&store), pass_name)?;
     let json = shader_dependency_dump_json(&source)?;
@@ -565,12 +945,266 @@ fn run_shader_dependency_dump(cli: &Cli) -> Result<()> {
     Ok(())
 }
 
-fn resolve_file_output_path_under(output_dir: &PathBuf, rt: &dsl::FileRenderTarget) -> PathBuf {
+/// Prints a dry-run cost estimate for the scene (texture memory, per-pass
+/// bandwidth, frame-time score) against `--device-profile`, defaulting to desktop.
+fn run_explain(cli: &Cli) -> Result<()> {
+    let (mut scene, store) = if let Some(nforge_path) = cli.nforge.as_deref() {
+        asset_store::load_from_nforge(nforge_path)?
+    } else if let Some(dsl_json_path) = cli.dsl_json.as_deref() {
+        load_scene_from_dsl_json_path(dsl_json_path)?
+    } else {
+        return Err(anyhow!(
+            "--explain requires --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    };
+    dsl::apply_feature_overrides(&mut scene, &cli.features)?;
+    let profile = match cli.device_profile.as_deref() {
+        Some(name) => renderer::DeviceProfile::by_name(name)?,
+        None => renderer::DeviceProfile::desktop(),
+    };
+
+    let estimate = renderer::estimate_scene_cost(&scene, Some(&store), &profile)?;
+    let json = serde_json::to_string_pretty(&estimate)
+        .map_err(|e| anyhow!("failed to serialize cost estimate: {e}"))?;
+    match cli.output.as_ref() {
+        Some(output_path) => write_text_file(output_path.clone(), &json)?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+/// Prints the planner's view of the scene (draw order, pass order, textures,
+/// VRAM estimate) as JSON, or writes it to `--output`.
+fn run_inspect(cli: &Cli) -> Result<()> {
+    let (mut scene, store) = if let Some(nforge_path) = cli.nforge.as_deref() {
+        asset_store::load_from_nforge(nforge_path)?
+    } else if let Some(dsl_json_path) = cli.dsl_json.as_deref() {
+        load_scene_from_dsl_json_path(dsl_json_path)?
+    } else {
+        return Err(anyhow!(
+            "--inspect requires --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    };
+    dsl::apply_feature_overrides(&mut scene, &cli.features)?;
+    dsl::apply_output_overrides(&mut scene, cli.output_overrides)?;
+
+    let inspection = renderer::inspect_scene(&scene, Some(&store))?;
+    let json = serde_json::to_string_pretty(&inspection)
+        .map_err(|e| anyhow!("failed to serialize scene inspection: {e}"))?;
+    match cli.output.as_ref() {
+        Some(output_path) => write_text_file(output_path.clone(), &json)?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+/// Writes every pass's WGSL for the scene into `dir` without rendering.
+fn run_dump_wgsl(cli: &Cli, dir: &std::path::Path) -> Result<()> {
+    let (mut scene, store) = if let Some(nforge_path) = cli.nforge.as_deref() {
+        asset_store::load_from_nforge(nforge_path)?
+    } else if let Some(dsl_json_path) = cli.dsl_json.as_deref() {
+        load_scene_from_dsl_json_path(dsl_json_path)?
+    } else {
+        return Err(anyhow!(
+            "--dump-wgsl requires --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    };
+    dsl::apply_feature_overrides(&mut scene, &cli.features)?;
+    let written = renderer::dump_pass_wgsl_bundles(&scene, Some(&store), dir)?;
+    println!("[wgsl] wrote {} files to {}", written.len(), dir.display());
+    Ok(())
+}
+
+/// Validates the scene without a GPU and prints a JSON report (or writes it to
+/// `--output`). Fails when the report has errors so CI can gate on the exit code.
+fn run_validate(cli: &Cli) -> Result<()> {
+    let loaded = if let Some(nforge_path) = cli.nforge.as_deref() {
+        asset_store::load_from_nforge(nforge_path)
+    } else if let Some(dsl_json_path) = cli.dsl_json.as_deref() {
+        load_scene_from_dsl_json_path(dsl_json_path)
+    } else {
+        return Err(anyhow!(
+            "--validate requires --dsl-json <scene.json> or --nforge <file.nforge>"
+        ));
+    };
+    let report = match loaded.and_then(|(mut scene, store)| {
+        dsl::apply_feature_overrides(&mut scene, &cli.features)?;
+        Ok((scene, store))
+    }) {
+        Ok((scene, store)) => renderer::validate_scene(&scene, Some(&store)),
+        Err(e) => renderer::SceneValidationReport::load_failure(format!("{e:#}")),
+    };
+
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| anyhow!("failed to serialize validation report: {e}"))?;
+    match cli.output.as_ref() {
+        Some(output_path) => write_text_file(output_path.clone(), &json)?,
+        None => println!("{json}"),
+    }
+    if !report.ok {
+        return Err(anyhow!(
+            "scene validation failed with {} error(s)",
+            report.error_count
+        ));
+    }
+    Ok(())
+}
+
+/// Loads a scene for diffing: `.nforge` archives or SceneDSL JSON, with
+/// default params applied so omitted and explicit defaults compare equal.
+fn load_scene_for_diff(path: &std::path::Path) -> Result<dsl::SceneDSL> {
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("nforge"))
+    {
+        return Ok(asset_store::load_from_nforge(path)?.0);
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to read scene {}: {e}", path.display()))?;
+    let mut scene: dsl::SceneDSL = serde_json::from_str(&text)
+        .map_err(|e| anyhow!("invalid SceneDSL json in {}: {e}", path.display()))?;
+    dsl::normalize_scene_defaults(&mut scene)
+        .map_err(|e| anyhow!("failed to apply default params: {e:#}"))?;
+    Ok(scene)
+}
+
+fn run_scene_diff(before: &std::path::Path, after: &std::path::Path) -> Result<()> {
+    let diff = dsl::diff_scenes(&load_scene_for_diff(before)?, &load_scene_for_diff(after)?);
+    let json = serde_json::to_string_pretty(&diff)
+        .map_err(|e| anyhow!("failed to serialize scene diff: {e}"))?;
+    println!("{json}");
+    Ok(())
+}
+
+/// Prints the canonical form of a SceneDSL JSON file, or writes it to
+/// `--output` when given.
+fn run_format_scene(path: &std::path::Path, output: Option<&PathBuf>) -> Result<()> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to read scene {}: {e}", path.display()))?;
+    let formatted = dsl::format_scene_json(&text)
+        .map_err(|e| anyhow!("failed to format {}: {e:#}", path.display()))?;
+    match output {
+        Some(output_path) => write_text_file(output_path.clone(), &formatted)?,
+        None => print!("{formatted}"),
+    }
+    Ok(())
+}
+
+/// Expands the tokens in `rt.file_name` for this render; `scene_path` names
+/// `{scene}` when the scene came from a file.
+fn expand_render_target_file_name(
+    rt: &dsl::FileRenderTarget,
+    scene: &dsl::SceneDSL,
+    scene_path: Option<&std::path::Path>,
+) -> Result<String> {
+    let stem = scene_path
+        .and_then(|p| p.file_stem())
+        .and_then(|s| s.to_str());
+    let tokens = dsl::FileNameTokens::for_scene(scene, stem)?;
+    dsl::expand_file_name(&rt.file_name, &tokens)
+}
+
+fn resolve_file_output_path_under(
+    output_dir: &PathBuf,
+    rt: &dsl::FileRenderTarget,
+    scene: &dsl::SceneDSL,
+    scene_path: &std::path::Path,
+) -> Result<PathBuf> {
     let mut out = output_dir.clone();
-    out.push(&rt.file_name);
-    out
+    out.push(expand_render_target_file_name(rt, scene, Some(scene_path))?);
+    Ok(out)
+}
+
+/// Renders a `--frames` image sequence, or a single video when the output is
+/// `.mp4`/`.webm`. `{frame}` and the other fileName tokens expand in the file
+/// name of `--output`, or in the File target's fileName.
+fn run_headless_sequence(
+    scene: &dsl::SceneDSL,
+    store: &asset_store::AssetStore,
+    scene_path: &std::path::Path,
+    output_dir: Option<PathBuf>,
+    output: Option<PathBuf>,
+    render_to_file: bool,
+    sequence: &FrameSequence,
+) -> Result<()> {
+    let explicit_output = if render_to_file {
+        Some(output.ok_or_else(|| anyhow!("--render-to-file requires --output <absolute path>"))?)
+    } else {
+        output
+    };
+    let (dir, template) = if let Some(out) = explicit_output {
+        validate_absolute_output_path(&out)?;
+        let name = out
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("--output must name a file, got: {}", out.display()))?
+            .to_string();
+        (
+            out.parent().map(ToOwned::to_owned).unwrap_or_default(),
+            name,
+        )
+    } else {
+        let rt = dsl::file_render_target(scene)?.ok_or_else(|| {
+            anyhow!(
+                "--frames requires RenderTarget=File (or pass --render-to-file --output <abs/path>)"
+            )
+        })?;
+        let dir = output_dir.unwrap_or_else(|| {
+            scene_path
+                .parent()
+                .map(ToOwned::to_owned)
+                .unwrap_or_else(|| PathBuf::from("."))
+        });
+        (dir, rt.file_name)
+    };
+    let stem = scene_path.file_stem().and_then(|s| s.to_str());
+    let mut tokens = dsl::FileNameTokens::for_scene(scene, stem)?;
+
+    if renderer::is_video_output_path(std::path::Path::new(&template)) {
+        tokens.frame = u64::from(sequence.frames.start);
+        let path = dir.join(dsl::expand_file_name(&template, &tokens)?);
+        ensure_parent_dir_exists(&path)?;
+        renderer::render_scene_video_headless(
+            scene,
+            Some(store),
+            sequence.frames.clone(),
+            sequence.fps,
+            &path,
+            &sequence.video,
+        )?;
+        println!("[headless] saved: {}", path.display());
+        return Ok(());
+    }
+    if sequence.video != renderer::VideoEncodeOptions::default() {
+        return Err(anyhow!(
+            "--video-codec and --video-bitrate require a .mp4 or .webm output, got {template:?}"
+        ));
+    }
+    if !template.contains("{frame") {
+        return Err(anyhow!(
+            "--frames needs a {{frame}} placeholder in the output file name so frames do not overwrite each other, got {template:?}"
+        ));
+    }
+
+    let written = renderer::render_scene_sequence_headless(
+        scene,
+        Some(store),
+        sequence.frames.clone(),
+        sequence.fps,
+        |frame| {
+            tokens.frame = u64::from(frame);
+            let path = dir.join(dsl::expand_file_name(&template, &tokens)?);
+            ensure_parent_dir_exists(&path)?;
+            Ok(path)
+        },
+    )?;
+    for path in &written {
+        println!("[headless] saved: {}", path.display());
+    }
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_headless_json_render_once(
     dsl_json_path: &std::path::Path,
     output_dir: Option<PathBuf>,
@@ -578,6 +1212,12 @@ fn run_headless_json_render_once(
     dump_wgsl_dir: Option<PathBuf>,
     render_to_file: bool,
     profile: Option<HeadlessProfileOptions>,
+    bless: bool,
+    features: &[String],
+    sequence: Option<&FrameSequence>,
+    sweeps: &[dsl::ParamSweep],
+    output_overrides: dsl::OutputOverrides,
+    deterministic: bool,
 ) -> Result<()> {
     let text = std::fs::read_to_string(dsl_json_path).map_err(|e| {
         anyhow!(
@@ -591,6 +1231,8 @@ fn run_headless_json_render_once(
 
     dsl::normalize_scene_defaults(&mut scene)
         .map_err(|e| anyhow!("failed to apply default params: {e:#}"))?;
+    dsl::apply_feature_overrides(&mut scene, features)?;
+    dsl::apply_output_overrides(&mut scene, output_overrides)?;
 
     // Load assets from the scene directory if the scene has an assets manifest.
     let base_dir = dsl_json_path
@@ -598,6 +1240,17 @@ fn run_headless_json_render_once(
         .unwrap_or_else(|| std::path::Path::new("."));
     let store = asset_store::load_from_scene_dir(&scene, base_dir)?;
     dump_scene_wgsl(&scene, Some(&store), dump_wgsl_dir.as_ref())?;
+    if let Some(sequence) = sequence {
+        return run_headless_sequence(
+            &scene,
+            &store,
+            dsl_json_path,
+            output_dir,
+            output,
+            render_to_file,
+            sequence,
+        );
+    }
 
     let out_path = if render_to_file {
         let out =
@@ -618,11 +1271,14 @@ fn run_headless_json_render_once(
                     .map(ToOwned::to_owned)
                     .unwrap_or_else(|| PathBuf::from("."))
             });
-            resolve_file_output_path_under(&output_dir, &rt)
+            resolve_file_output_path_under(&output_dir, &rt, &scene, dsl_json_path)?
         }
     };
 
     ensure_parent_dir_exists(&out_path)?;
+    if !sweeps.is_empty() {
+        return run_headless_sweep(&scene, &store, &out_path, sweeps, deterministic);
+    }
 
     if let Some(profile) = profile {
         let stdout_profile = profile.output.is_stdout();
@@ -639,12 +1295,20 @@ fn run_headless_json_render_once(
             eprintln!("[headless] profile saved");
         }
     } else {
-        renderer::render_scene_to_file_headless(&scene, &out_path, Some(&store))?;
+        renderer::HeadlessSession::with_deterministic(deterministic)?.render_scene_to_file(
+            &scene,
+            &out_path,
+            Some(&store),
+        )?;
         println!("[headless] saved: {}", out_path.display());
     }
+    if bless {
+        bless_headless_output(dsl_json_path, &out_path)?;
+    }
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_headless_nforge_render_once(
     nforge_path: &std::path::Path,
     output_dir: Option<PathBuf>,
@@ -652,9 +1316,28 @@ fn run_headless_nforge_render_once(
     dump_wgsl_dir: Option<PathBuf>,
     render_to_file: bool,
     profile: Option<HeadlessProfileOptions>,
+    bless: bool,
+    features: &[String],
+    sequence: Option<&FrameSequence>,
+    sweeps: &[dsl::ParamSweep],
+    output_overrides: dsl::OutputOverrides,
+    deterministic: bool,
 ) -> Result<()> {
-    let (scene, store) = asset_store::load_from_nforge(nforge_path)?;
+    let (mut scene, store) = asset_store::load_from_nforge(nforge_path)?;
+    dsl::apply_feature_overrides(&mut scene, features)?;
+    dsl::apply_output_overrides(&mut scene, output_overrides)?;
     dump_scene_wgsl(&scene, Some(&store), dump_wgsl_dir.as_ref())?;
+    if let Some(sequence) = sequence {
+        return run_headless_sequence(
+            &scene,
+            &store,
+            nforge_path,
+            output_dir,
+            output,
+            render_to_file,
+            sequence,
+        );
+    }
 
     let out_path = if render_to_file {
         let out =
@@ -675,11 +1358,14 @@ fn run_headless_nforge_render_once(
                     .map(ToOwned::to_owned)
                     .unwrap_or_else(|| PathBuf::from("."))
             });
-            resolve_file_output_path_under(&output_dir, &rt)
+            resolve_file_output_path_under(&output_dir, &rt, &scene, nforge_path)?
         }
     };
 
     ensure_parent_dir_exists(&out_path)?;
+    if !sweeps.is_empty() {
+        return run_headless_sweep(&scene, &store, &out_path, sweeps, deterministic);
+    }
 
     if let Some(profile) = profile {
         let stdout_profile = profile.output.is_stdout();
@@ -696,21 +1382,187 @@ fn run_headless_nforge_render_once(
             eprintln!("[headless] profile saved");
         }
     } else {
-        renderer::render_scene_to_file_headless(&scene, &out_path, Some(&store))?;
+        renderer::HeadlessSession::with_deterministic(deterministic)?.render_scene_to_file(
+            &scene,
+            &out_path,
+            Some(&store),
+        )?;
+        println!("[headless] saved: {}", out_path.display());
+    }
+    if bless {
+        bless_headless_output(nforge_path, &out_path)?;
+    }
+    Ok(())
+}
+
+/// Copies a headless render over the scene's golden baseline
+/// (`<scene dir>/expected/baseline.<ext>`) and reports the recorded diff.
+/// Renders one output per point of the `--sweep` grid, with the swept values
+/// appended to `base_path`'s file name.
+fn run_headless_sweep(
+    scene: &dsl::SceneDSL,
+    store: &asset_store::AssetStore,
+    base_path: &std::path::Path,
+    sweeps: &[dsl::ParamSweep],
+    deterministic: bool,
+) -> Result<()> {
+    let session = renderer::HeadlessSession::with_deterministic(deterministic)?;
+    for point in dsl::sweep_grid(sweeps) {
+        let mut variant = scene.clone();
+        dsl::apply_sweep_point(&mut variant, sweeps, &point)?;
+        let out_path = dsl::sweep_output_path(base_path, sweeps, &point);
+        session.render_scene_to_file(&variant, &out_path, Some(store))?;
         println!("[headless] saved: {}", out_path.display());
     }
     Ok(())
 }
 
+fn parse_batch_manifest(text: &str, manifest_path: &std::path::Path) -> Result<BatchManifest> {
+    let manifest: BatchManifest = serde_json::from_str(text)
+        .map_err(|e| anyhow!("invalid batch manifest {}: {e}", manifest_path.display()))?;
+    if manifest.jobs.is_empty() {
+        return Err(anyhow!(
+            "batch manifest {} has no jobs",
+            manifest_path.display()
+        ));
+    }
+    Ok(manifest)
+}
+
+fn load_scene_for_batch(
+    scene_path: &std::path::Path,
+) -> Result<(dsl::SceneDSL, asset_store::AssetStore)> {
+    if scene_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("nforge"))
+    {
+        return asset_store::load_from_nforge(scene_path);
+    }
+    load_scene_from_dsl_json_path(scene_path)
+}
+
+fn run_batch_job(
+    session: &renderer::HeadlessSession,
+    job: &BatchJob,
+    base_dir: &std::path::Path,
+    output_dir: Option<&PathBuf>,
+    global_features: &[String],
+    output_overrides: dsl::OutputOverrides,
+) -> Result<PathBuf> {
+    let scene_path = base_dir.join(&job.scene);
+    let (mut scene, store) = load_scene_for_batch(&scene_path)?;
+    dsl::apply_feature_overrides(&mut scene, global_features)?;
+    dsl::apply_feature_overrides(&mut scene, &job.features)?;
+    dsl::apply_output_overrides(&mut scene, output_overrides)?;
+
+    let out_path = match &job.output {
+        Some(output) => base_dir.join(output),
+        None => {
+            let rt = dsl::file_render_target(&scene)?.ok_or_else(|| {
+                anyhow!("scene has no RenderTarget=File; set \"output\" for this job")
+            })?;
+            let dir = output_dir.cloned().unwrap_or_else(|| {
+                scene_path
+                    .parent()
+                    .map(ToOwned::to_owned)
+                    .unwrap_or_else(|| PathBuf::from("."))
+            });
+            resolve_file_output_path_under(&dir, &rt, &scene, &scene_path)?
+        }
+    };
+    ensure_parent_dir_exists(&out_path)?;
+    session.render_scene_to_file(&scene, &out_path, Some(&store))?;
+    Ok(out_path)
+}
+
+/// Renders every job in a `--batch` manifest on one headless device. A failing
+/// job is reported and skipped; the run fails at the end if any job failed.
+fn run_headless_batch(
+    manifest_path: &std::path::Path,
+    output_dir: Option<PathBuf>,
+    features: &[String],
+    output_overrides: dsl::OutputOverrides,
+    deterministic: bool,
+) -> Result<()> {
+    let text = std::fs::read_to_string(manifest_path).map_err(|e| {
+        anyhow!(
+            "failed to read batch manifest {}: {e}",
+            manifest_path.display()
+        )
+    })?;
+    let manifest = parse_batch_manifest(&text, manifest_path)?;
+    let base_dir = manifest_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."));
+
+    let session = renderer::HeadlessSession::with_deterministic(deterministic)?;
+    let mut failed = 0usize;
+    for job in &manifest.jobs {
+        match run_batch_job(
+            &session,
+            job,
+            base_dir,
+            output_dir.as_ref(),
+            features,
+            output_overrides,
+        ) {
+            Ok(out_path) => println!("[headless] saved: {}", out_path.display()),
+            Err(e) => {
+                failed += 1;
+                eprintln!("[batch] failed {}: {e:#}", job.scene.display());
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow!(
+            "{failed} of {} batch jobs failed",
+            manifest.jobs.len()
+        ));
+    }
+    Ok(())
+}
+
+fn bless_headless_output(scene_path: &std::path::Path, out_path: &std::path::Path) -> Result<()> {
+    let extension = out_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("png")
+        .to_ascii_lowercase();
+    let baseline = testing::scene_baseline_path(scene_path, &extension);
+    let record = testing::bless_baseline(out_path, &baseline)?;
+    let detail = match record.diff.as_ref() {
+        Some(diff) => match (diff.mismatched_pixels, diff.max_channel_delta) {
+            (Some(pixels), Some(delta)) => {
+                format!("{pixels} pixels changed, max channel delta {delta}")
+            }
+            _ => format!(
+                "size changed from {}x{} to {}x{}",
+                diff.previous_size[0], diff.previous_size[1], diff.size[0], diff.size[1]
+            ),
+        },
+        None => "new baseline".to_string(),
+    };
+    println!(
+        "[bless] {:?}: {} ({detail})",
+        record.outcome,
+        baseline.display()
+    );
+    Ok(())
+}
+
 fn run_headless_ws_render_once(
     addr: &str,
     output: Option<PathBuf>,
     dump_wgsl_dir: Option<PathBuf>,
     render_to_file: bool,
     profile: Option<HeadlessProfileOptions>,
+    features: &[String],
+    ws_options: ws::WsServerOptions,
 ) -> Result<()> {
     use std::{thread, time::Duration};
 
+    // Profiles and `--stdio` messages own stdout; keep logs on stderr then.
+    let logs_to_stderr = profile.is_some() || ws_options.stdio;
     let (scene_tx, scene_rx) = crossbeam_channel::bounded::<ws::SceneUpdate>(1);
     let app_scene_rx = scene_rx.clone();
     let drop_rx = scene_rx;
@@ -726,6 +1578,7 @@ fn run_headless_ws_render_once(
         hub.clone(),
         last_good,
         asset_store.clone(),
+        ws_options,
         None,
     )?;
 
@@ -737,11 +1590,13 @@ fn run_headless_ws_render_once(
 
         match update {
             ws::SceneUpdate::Parsed {
-                scene,
+                mut scene,
                 request_id,
+                client_id,
                 source: _,
                 perf_trace: _,
             } => {
+                dsl::apply_feature_overrides(&mut scene, features)?;
                 dump_scene_wgsl(&scene, None, dump_wgsl_dir.as_ref())?;
 
                 let out_path = if render_to_file {
@@ -758,7 +1613,7 @@ fn run_headless_ws_render_once(
                         validate_absolute_output_path(&out)?;
                         out
                     } else {
-                        resolve_file_output_path(&rt)
+                        resolve_file_output_path(&rt, &scene)?
                     }
                 };
 
@@ -774,7 +1629,21 @@ fn run_headless_ws_render_once(
                         &mut writer,
                     )
                 } else {
-                    renderer::render_scene_to_file_headless(&scene, &out_path, None)
+                    renderer::HeadlessSession::new().and_then(|session| {
+                        session.render_scene_to_file_with_progress(
+                            &scene,
+                            &out_path,
+                            None,
+                            &mut |progress| {
+                                ws::broadcast_render_progress(
+                                    &hub,
+                                    request_id.clone(),
+                                    client_id,
+                                    &progress,
+                                )
+                            },
+                        )
+                    })
                 };
                 match result {
                     Ok(()) => {
@@ -787,14 +1656,14 @@ fn run_headless_ws_render_once(
                             })),
                         };
                         if let Ok(text) = serde_json::to_string(&msg) {
-                            if profile.is_some() {
+                            if logs_to_stderr {
                                 eprintln!("Rendered to file at {}", out_path.display());
                                 eprintln!("[headless]: {}", text);
                             } else {
                                 println!("Rendered to file at {}", out_path.display());
                                 println!("[headless]: {}", text);
                             }
-                            hub.broadcast(text);
+                            hub.reply(client_id, text);
                         }
                     }
                     Err(e) => {
@@ -808,12 +1677,12 @@ fn run_headless_ws_render_once(
                             }),
                         };
                         if let Ok(text) = serde_json::to_string(&msg) {
-                            if profile.is_some() {
+                            if logs_to_stderr {
                                 eprintln!("[headless]: {}", text);
                             } else {
                                 println!("[headless]: {}", text);
                             }
-                            hub.broadcast(text);
+                            hub.reply(client_id, text);
                         }
                     }
                 }
@@ -823,17 +1692,25 @@ fn run_headless_ws_render_once(
                 return Ok(());
             }
             ws::SceneUpdate::UniformDelta { .. } => {
-                // Headless one-shot render requires a full scene payload.
-                // Ignore uniform deltas and wait for Parsed / ParseError.
+                // Nothing to patch before the one-shot render. Param updates
+                // still reach it: the WS layer applies them to its scene cache,
+                // which the pending scene is materialized from, and to a full
+                // scene already queued here.
             }
             ws::SceneUpdate::DebugArtifactUpsert { .. }
             | ws::SceneUpdate::DebugArtifactBinaryUpsert { .. }
-            | ws::SceneUpdate::DebugArtifactDelete { .. } => {
-                // Debug artifacts do not affect headless render output.
+            | ws::SceneUpdate::DebugArtifactDelete { .. }
+            | ws::SceneUpdate::RenderStatsRequest { .. }
+            | ws::SceneUpdate::PixelRead { .. }
+            | ws::SceneUpdate::ResourceQuery { .. }
+            | ws::SceneUpdate::Analysis { .. } => {
+                // Debug artifacts, stats, pixel reads, resource queries and
+                // analysis requests do not affect headless render output.
             }
             ws::SceneUpdate::ParseError {
                 message,
                 request_id,
+                client_id,
             } => {
                 let msg = node_forge_render_server::protocol::WSMessage {
                     msg_type: "error".to_string(),
@@ -845,7 +1722,7 @@ fn run_headless_ws_render_once(
                     }),
                 };
                 if let Ok(text) = serde_json::to_string(&msg) {
-                    hub.broadcast(text);
+                    hub.reply(client_id, text);
                 }
                 thread::sleep(Duration::from_millis(150));
                 return Ok(());
@@ -854,7 +1731,10 @@ fn run_headless_ws_render_once(
     }
 }
 
-fn resolve_file_output_path(rt: &dsl::FileRenderTarget) -> std::path::PathBuf {
+fn resolve_file_output_path(
+    rt: &dsl::FileRenderTarget,
+    scene: &dsl::SceneDSL,
+) -> Result<std::path::PathBuf> {
     let base = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let dir = rt.directory.trim();
     let mut path = if dir.is_empty() {
@@ -863,8 +1743,8 @@ fn resolve_file_output_path(rt: &dsl::FileRenderTarget) -> std::path::PathBuf {
         let pb = std::path::PathBuf::from(dir);
         if pb.is_absolute() { pb } else { base.join(pb) }
     };
-    path.push(&rt.file_name);
-    path
+    path.push(expand_render_target_file_name(rt, scene, None)?);
+    Ok(path)
 }
 
 fn configure_egui_fonts(ctx: &egui::Context) {
@@ -970,14 +1850,57 @@ fn configure_egui_fonts(ctx: &egui::Context) {
 pub(crate) fn run() -> Result<()> {
     let argv: Vec<String> = std::env::args().skip(1).collect();
     let cli = parse_cli(&argv)?;
+    let ws_options = ws_server_options(&cli)?;
+
+    if let Some((before, after)) = cli.diff_scenes.as_ref() {
+        return run_scene_diff(before, after);
+    }
+
+    if let Some(path) = cli.format_scene.as_deref() {
+        return run_format_scene(path, cli.output.as_ref());
+    }
 
     if cli.dump_shader_deps.is_some() {
         return run_shader_dependency_dump(&cli);
     }
 
+    if cli.explain {
+        return run_explain(&cli);
+    }
+
+    if cli.validate {
+        return run_validate(&cli);
+    }
+
+    if cli.inspect {
+        return run_inspect(&cli);
+    }
+
+    if let Some(dir) = cli.dump_wgsl.as_deref() {
+        return run_dump_wgsl(&cli, dir);
+    }
+
+    if let Some(manifest_path) = cli.batch.as_deref() {
+        return run_headless_batch(
+            manifest_path,
+            cli.output_dir,
+            &cli.features,
+            cli.output_overrides,
+            cli.deterministic,
+        );
+    }
+
     // Script-friendly mode: pass DSL JSON directly.
     if cli.headless {
         let profile_options = headless_profile_options(&cli);
+        let sequence = cli.frames.clone().map(|frames| FrameSequence {
+            frames,
+            fps: cli.fps.unwrap_or(DEFAULT_SEQUENCE_FPS),
+            video: renderer::VideoEncodeOptions {
+                codec: cli.video_codec.clone(),
+                bitrate: cli.video_bitrate.clone(),
+            },
+        });
         if let Some(nforge_path) = cli.nforge.as_deref() {
             return run_headless_nforge_render_once(
                 nforge_path,
@@ -986,6 +1909,12 @@ pub(crate) fn run() -> Result<()> {
                 cli.dump_wgsl_dir,
                 cli.render_to_file,
                 profile_options.clone(),
+                cli.bless,
+                &cli.features,
+                sequence.as_ref(),
+                &cli.sweeps,
+                cli.output_overrides,
+                cli.deterministic,
             );
         }
         if let Some(dsl_json_path) = cli.dsl_json.as_deref() {
@@ -996,16 +1925,24 @@ pub(crate) fn run() -> Result<()> {
                 cli.dump_wgsl_dir,
                 cli.render_to_file,
                 profile_options.clone(),
+                cli.bless,
+                &cli.features,
+                sequence.as_ref(),
+                &cli.sweeps,
+                cli.output_overrides,
+                cli.deterministic,
             );
         }
 
-        // Editor-driven mode: wait for editor to connect over ws and send SceneDSL.
+        // Editor-driven mode: wait for editor to connect over ws (or stdio) and send SceneDSL.
         return run_headless_ws_render_once(
             "127.0.0.1:8080",
             cli.output,
             cli.dump_wgsl_dir,
             cli.render_to_file,
             profile_options,
+            &cli.features,
+            ws_options,
         );
     }
 
@@ -1051,6 +1988,11 @@ pub(crate) fn run() -> Result<()> {
         }
     };
 
+    let mut scene = scene;
+    if let Some(scene) = scene.as_mut() {
+        dsl::apply_feature_overrides(scene, &cli.features)?;
+    }
+
     let resolution_hint = scene
         .as_ref()
         .and_then(dsl::screen_resolution)
@@ -1158,6 +2100,14 @@ pub(crate) fn run() -> Result<()> {
                     debug_dump_wgsl_dir: None,
                     pass_shader_overrides: Default::default(),
                     strict_pass_shader_overrides: false,
+                    pass_debug_overlays: Default::default(),
+                    channel_debug: Default::default(),
+                    non_finite_sanitize: Default::default(),
+                    skip_trusted_wgsl_validation: false,
+                    dither_sdr_output: false,
+                    output_crop: None,
+                    pick_ids: false,
+                    deterministic: false,
                 })
                 .build(&scene)
                 {
@@ -1236,6 +2186,7 @@ pub(crate) fn run() -> Result<()> {
                 hub.clone(),
                 last_good.clone(),
                 asset_store.clone(),
+                ws_options.clone(),
                 Some(ui_wake),
             ) {
                 eprintln!("[ws] failed to start ws server: {e:#}");
@@ -1306,6 +2257,308 @@ mod tests {
         assert_eq!(cli.output_dir.as_ref().unwrap(), &PathBuf::from("out"));
     }
 
+    #[test]
+    fn parse_cli_diff_scenes() {
+        let args = vec![
+            "--diff-scenes".to_string(),
+            "a.json".to_string(),
+            "b.nforge".to_string(),
+        ];
+        let cli = parse_cli(&args).unwrap();
+        assert_eq!(
+            cli.diff_scenes,
+            Some((PathBuf::from("a.json"), PathBuf::from("b.nforge")))
+        );
+
+        let err = parse_cli(&["--diff-scenes".to_string(), "a.json".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("two scene paths"));
+    }
+
+    #[test]
+    fn parse_cli_format_scene() {
+        let args = vec!["--format-scene".to_string(), "scene.json".to_string()];
+        let cli = parse_cli(&args).unwrap();
+        assert_eq!(cli.format_scene, Some(PathBuf::from("scene.json")));
+        assert!(!cli.headless);
+    }
+
+    #[test]
+    fn parse_cli_explain_with_device_profile() {
+        let args = vec![
+            "--explain".to_string(),
+            "--nforge".to_string(),
+            "scene.nforge".to_string(),
+            "--device-profile".to_string(),
+            "phone".to_string(),
+        ];
+        let cli = parse_cli(&args).expect("parse should succeed");
+        assert!(cli.explain);
+        assert_eq!(cli.device_profile.as_deref(), Some("phone"));
+
+        assert!(parse_cli(&["--explain".to_string()]).is_err());
+        let err = parse_cli(&["--device-profile".to_string(), "phone".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("requires --explain"));
+    }
+
+    #[test]
+    fn parse_cli_features_accumulate() {
+        let args = vec![
+            "--headless".to_string(),
+            "--nforge".to_string(),
+            "scene.nforge".to_string(),
+            "--features".to_string(),
+            "mobile,!highQuality".to_string(),
+            "--features".to_string(),
+            "bloom=false".to_string(),
+        ];
+        let cli = parse_cli(&args).expect("parse should succeed");
+        assert_eq!(cli.features, ["mobile,!highQuality", "bloom=false"]);
+
+        let err = parse_cli(&["--features".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("missing value for --features"));
+    }
+
+    #[test]
+    fn parse_cli_frames_and_fps() {
+        let args = vec![
+            "--headless".to_string(),
+            "--dsl-json".to_string(),
+            "scene.json".to_string(),
+            "--frames".to_string(),
+            "10..=12".to_string(),
+            "--fps".to_string(),
+            "24".to_string(),
+        ];
+        let cli = parse_cli(&args).unwrap();
+        assert_eq!(cli.frames, Some(10..13));
+        assert_eq!(cli.fps, Some(24.0));
+
+        assert_eq!(parse_frame_range("0..48").unwrap(), 0..48);
+        assert_eq!(parse_frame_range("7").unwrap(), 7..8);
+        assert!(parse_frame_range("5..5").is_err());
+        assert!(parse_frame_range("a..b").is_err());
+
+        let err = parse_cli(&["--fps".to_string(), "24".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("--fps requires --frames"));
+        let err = parse_cli(&["--frames".to_string(), "0..2".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("--frames requires --headless"));
+    }
+
+    #[test]
+    fn parse_cli_batch_conflicts_with_single_scene_flags() {
+        let cli = parse_cli(&["--batch".to_string(), "jobs.json".to_string()]).unwrap();
+        assert_eq!(cli.batch, Some(PathBuf::from("jobs.json")));
+
+        let err = parse_cli(&[
+            "--batch".to_string(),
+            "jobs.json".to_string(),
+            "--dsl-json".to_string(),
+            "scene.json".to_string(),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("--batch takes scenes"));
+    }
+
+    #[test]
+    fn parse_batch_manifest_reads_jobs() {
+        let path = std::path::Path::new("jobs.json");
+        let manifest = parse_batch_manifest(
+            r#"{"jobs": [
+                {"scene": "a.json", "output": "out/a.png"},
+                {"scene": "b.nforge", "features": ["bloom=false"]}
+            ]}"#,
+            path,
+        )
+        .unwrap();
+        assert_eq!(manifest.jobs.len(), 2);
+        assert_eq!(manifest.jobs[0].output, Some(PathBuf::from("out/a.png")));
+        assert_eq!(manifest.jobs[1].output, None);
+        assert_eq!(manifest.jobs[1].features, vec!["bloom=false".to_string()]);
+
+        assert!(parse_batch_manifest(r#"{"jobs": []}"#, path).is_err());
+        assert!(
+            parse_batch_manifest(r#"{"jobs": [{"scene": "a.json", "out": "x"}]}"#, path).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_cli_resolution_and_format_overrides() {
+        let args = vec![
+            "--headless".to_string(),
+            "--nforge".to_string(),
+            "scene.nforge".to_string(),
+            "--resolution".to_string(),
+            "640x360".to_string(),
+            "--format".to_string(),
+            "rgba8srgb".to_string(),
+        ];
+        let cli = parse_cli(&args).unwrap();
+        assert_eq!(cli.output_overrides.resolution, Some([640, 360]));
+        assert_eq!(
+            cli.output_overrides.format,
+            Some(wgpu::TextureFormat::Rgba8UnormSrgb)
+        );
+
+        let err = parse_cli(&args[3..5]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("--resolution and --format require")
+        );
+        let err = parse_cli(&["--format".to_string(), "rgb10".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("--format:"));
+    }
+
+    #[test]
+    fn parse_cli_dump_wgsl() {
+        let args = vec![
+            "--dump-wgsl".to_string(),
+            "/tmp/wgsl".to_string(),
+            "--nforge".to_string(),
+            "scene.nforge".to_string(),
+        ];
+        let cli = parse_cli(&args).unwrap();
+        assert_eq!(cli.dump_wgsl, Some(PathBuf::from("/tmp/wgsl")));
+
+        let err = parse_cli(&args[..2]).unwrap_err();
+        assert!(err.to_string().contains("--dump-wgsl requires"));
+        let mut headless = args.clone();
+        headless.push("--headless".to_string());
+        let err = parse_cli(&headless).unwrap_err();
+        assert!(err.to_string().contains("--dump-wgsl-dir"));
+    }
+
+    #[test]
+    fn parse_cli_inspect_accepts_output_overrides() {
+        let cli = parse_cli(&[
+            "--inspect".to_string(),
+            "--dsl-json".to_string(),
+            "scene.json".to_string(),
+            "--resolution".to_string(),
+            "320x240".to_string(),
+        ])
+        .unwrap();
+        assert!(cli.inspect);
+        assert_eq!(cli.output_overrides.resolution, Some([320, 240]));
+
+        let err = parse_cli(&["--inspect".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("--inspect requires"));
+    }
+
+    #[test]
+    fn parse_cli_deterministic() {
+        let cli = parse_cli(&[
+            "--headless".to_string(),
+            "--dsl-json".to_string(),
+            "scene.json".to_string(),
+            "--deterministic".to_string(),
+        ])
+        .unwrap();
+        assert!(cli.deterministic);
+
+        let err = parse_cli(&[
+            "--headless".to_string(),
+            "--dsl-json".to_string(),
+            "scene.json".to_string(),
+            "--deterministic".to_string(),
+            "--frames".to_string(),
+            "0..4".to_string(),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("cannot be combined with --frames"));
+
+        let err = parse_cli(&["--deterministic".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("--deterministic requires"));
+    }
+
+    #[test]
+    fn parse_cli_validate() {
+        let cli = parse_cli(&[
+            "--validate".to_string(),
+            "--dsl-json".to_string(),
+            "scene.json".to_string(),
+        ])
+        .unwrap();
+        assert!(cli.validate);
+        assert!(!cli.headless);
+
+        let err = parse_cli(&["--validate".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("--validate requires"));
+    }
+
+    #[test]
+    fn parse_cli_sweeps() {
+        let args = vec![
+            "--headless".to_string(),
+            "--dsl-json".to_string(),
+            "scene.json".to_string(),
+            "--sweep".to_string(),
+            "blur.radius=0:8:3".to_string(),
+            "--sweep".to_string(),
+            "bloom.threshold=0.5:1:2".to_string(),
+        ];
+        let cli = parse_cli(&args).unwrap();
+        assert_eq!(cli.sweeps.len(), 2);
+        assert_eq!(cli.sweeps[0].node_id, "blur");
+        assert_eq!(cli.sweeps[1].param, "threshold");
+
+        let err = parse_cli(&args[3..5]).unwrap_err();
+        assert!(err.to_string().contains("--sweep requires --headless"));
+    }
+
+    #[test]
+    fn parse_cli_video_options() {
+        let mut args = vec![
+            "--headless".to_string(),
+            "--nforge".to_string(),
+            "scene.nforge".to_string(),
+            "--frames".to_string(),
+            "0..60".to_string(),
+            "--video-codec".to_string(),
+            "libx265".to_string(),
+            "--video-bitrate".to_string(),
+            "8M".to_string(),
+        ];
+        let cli = parse_cli(&args).unwrap();
+        assert_eq!(cli.video_codec.as_deref(), Some("libx265"));
+        assert_eq!(cli.video_bitrate.as_deref(), Some("8M"));
+
+        args[8] = "fast".to_string();
+        let err = parse_cli(&args).unwrap_err();
+        assert!(err.to_string().contains("--video-bitrate expects"));
+
+        let err = parse_cli(
+            &args[..3]
+                .iter()
+                .cloned()
+                .chain(args[5..7].iter().cloned())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("require --frames"));
+    }
+
+    #[test]
+    fn parse_cli_bless_requires_headless_scene() {
+        let args = vec![
+            "--headless".to_string(),
+            "--nforge".to_string(),
+            "scene.nforge".to_string(),
+            "--bless".to_string(),
+        ];
+        let cli = parse_cli(&args).expect("parse should succeed");
+        assert!(cli.bless);
+
+        let err = parse_cli(&["--headless".to_string(), "--bless".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("--bless requires --headless"));
+        let err = parse_cli(&[
+            "--nforge".to_string(),
+            "scene.nforge".to_string(),
+            "--bless".to_string(),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("--bless requires --headless"));
+    }
+
     #[test]
     fn parse_cli_headless_json_output_override() {
         let args = vec![
@@ -1455,6 +2708,53 @@ mod tests {
         assert_eq!(cli.profile_warmup_frames, 3);
     }
 
+    #[test]
+    fn parse_cli_ws_server_flags() {
+        let args = vec![
+            "--ws-token".to_string(),
+            "s3cret".to_string(),
+            "--ws-allow-origin".to_string(),
+            "http://localhost:5173".to_string(),
+            "--ws-allow-origin".to_string(),
+            "https://editor.example".to_string(),
+        ];
+        let options = ws_server_options(&parse_cli(&args).unwrap()).unwrap();
+        assert_eq!(options.access.token.as_deref(), Some("s3cret"));
+        assert_eq!(
+            options.access.allowed_origins,
+            ["http://localhost:5173", "https://editor.example"]
+        );
+        assert_eq!(
+            options.client_timeout,
+            ws::WsServerOptions::default().client_timeout
+        );
+
+        let options =
+            ws_server_options(&parse_cli(&["--ws-timeout".to_string(), "0".to_string()]).unwrap())
+                .unwrap();
+        assert_eq!(options.client_timeout, None);
+        assert!(parse_cli(&["--ws-timeout".to_string(), "soon".to_string()]).is_err());
+
+        let err = parse_cli(&["--ws-token".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("missing value for --ws-token"));
+    }
+
+    #[test]
+    fn parse_cli_stdio_flag() {
+        let options = ws_server_options(&parse_cli(&["--stdio".to_string()]).unwrap()).unwrap();
+        assert!(options.stdio);
+        assert!(!ws::WsServerOptions::default().stdio);
+
+        let err = parse_cli(&[
+            "--stdio".to_string(),
+            "--replay-ws".to_string(),
+            "session.ndjson".to_string(),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("cannot be combined with --stdio"));
+        assert!(parse_cli(&["--stdio".to_string(), "--profile".to_string()]).is_err());
+    }
+
     #[test]
     fn parse_cli_profile_frames_requires_profile() {
         let args = vec!["--profile-frames".to_string(), "2".to_string()];
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use anyhow::{Context, Result, anyhow, bail};
 use rust_wgpu_fiber::eframe::wgpu::TextureFormat;
@@ -6,10 +6,59 @@ use serde::{Deserialize, Serialize};
 
 use crate::schema;
 
+mod canonical;
+mod diff;
+mod file_name;
+mod sweep;
+
+pub use canonical::{canonicalize_scene_value, format_scene_json, to_canonical_json};
+pub use diff::{ConnectionSummary, NodeChange, NodeSummary, ParamChange, SceneDiff, diff_scenes};
+pub use file_name::{FileNameTokens, expand_file_name};
+pub use sweep::{ParamSweep, apply_sweep_point, sweep_grid, sweep_output_path};
+
 #[derive(Debug, Clone)]
 pub struct FileRenderTarget {
     pub directory: String,
+    /// May contain `{scene}`/`{date}`/`{frame}`/`{w}`/`{h}`/`{hash}` tokens;
+    /// see [`expand_file_name`].
     pub file_name: String,
+    /// Dither the 8-bit PNG encode to avoid banding in smooth gradients.
+    pub dither: bool,
+    /// Sample depth for PNG/TIFF output (`bitDepth`).
+    pub bit_depth: FileBitDepth,
+    /// JPEG quality, 1–100 (`quality`); `None` when the param is absent.
+    /// WebP output is always lossless and ignores it.
+    pub quality: Option<u8>,
+}
+
+/// `File.quality` when the param is absent.
+pub const DEFAULT_FILE_QUALITY: u8 = 90;
+
+/// `File.bitDepth`: `auto` follows the scene output format (16-bit for
+/// Rgba16Float targets), `8` or `16` force a depth.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FileBitDepth {
+    #[default]
+    Auto,
+    Eight,
+    Sixteen,
+}
+
+impl FileBitDepth {
+    fn from_params(params: &HashMap<String, serde_json::Value>) -> Result<Self> {
+        let Some(raw) = params.get("bitDepth") else {
+            return Ok(Self::Auto);
+        };
+        match raw {
+            serde_json::Value::Null => Ok(Self::Auto),
+            serde_json::Value::String(s) if s.trim().eq_ignore_ascii_case("auto") => Ok(Self::Auto),
+            serde_json::Value::String(s) if s.trim() == "8" => Ok(Self::Eight),
+            serde_json::Value::String(s) if s.trim() == "16" => Ok(Self::Sixteen),
+            v if v.as_u64() == Some(8) => Ok(Self::Eight),
+            v if v.as_u64() == Some(16) => Ok(Self::Sixteen),
+            other => bail!("File.bitDepth must be \"auto\", 8 or 16, got {other}"),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -129,6 +178,15 @@ pub struct SceneDSL {
         skip_serializing_if = "Option::is_none"
     )]
     pub debug_artifacts: Option<DebugArtifacts>,
+    /// Named switches (e.g. `"mobile"`, `"highQuality"`) that nodes reference
+    /// through `enabledWhen`. Resolved during scene prep; see
+    /// [`apply_feature_overrides`] for CLI/WS overrides.
+    #[serde(
+        default,
+        rename = "featureFlags",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub feature_flags: BTreeMap<String, bool>,
 }
 
 /// A reusable subgraph definition referenced by `GroupInstance` nodes.
@@ -206,6 +264,7 @@ pub fn treeshake_unlinked_nodes(scene: &SceneDSL) -> SceneDSL {
         assets: scene.assets.clone(),
         state_machine: scene.state_machine.clone(),
         debug_artifacts: scene.debug_artifacts.clone(),
+        feature_flags: scene.feature_flags.clone(),
     }
 }
 
@@ -216,6 +275,9 @@ pub struct Metadata {
     pub modified: Option<String>,
 }
 
+/// Param key holding a node's top-level `enabledWhen` condition.
+pub const ENABLED_WHEN_PARAM: &str = "__enabled_when";
+
 #[derive(Debug, Clone)]
 pub struct Node {
     pub id: String,
@@ -246,6 +308,8 @@ impl Serialize for Node {
             node_type: &'a str,
             #[serde(skip_serializing_if = "Option::is_none")]
             label: Option<&'a str>,
+            #[serde(rename = "enabledWhen", skip_serializing_if = "Option::is_none")]
+            enabled_when: Option<&'a str>,
             params: HashMap<&'a String, &'a serde_json::Value>,
             inputs: &'a [NodePort],
             outputs: &'a [NodePort],
@@ -259,16 +323,21 @@ impl Serialize for Node {
             .params
             .get("__node_label")
             .and_then(|value| value.as_str());
+        let enabled_when = self
+            .params
+            .get(ENABLED_WHEN_PARAM)
+            .and_then(|value| value.as_str());
         let params = self
             .params
             .iter()
-            .filter(|(key, _)| key.as_str() != "__node_label")
+            .filter(|(key, _)| !matches!(key.as_str(), "__node_label" | ENABLED_WHEN_PARAM))
             .collect();
 
         WireNode {
             id: &self.id,
             node_type: &self.node_type,
             label,
+            enabled_when,
             params,
             inputs: &self.inputs,
             outputs: &self.outputs,
@@ -291,6 +360,8 @@ impl<'de> Deserialize<'de> for Node {
             node_type: String,
             #[serde(default)]
             label: Option<String>,
+            #[serde(default, rename = "enabledWhen")]
+            enabled_when: Option<String>,
             #[serde(default)]
             params: HashMap<String, serde_json::Value>,
             #[serde(default)]
@@ -310,6 +381,14 @@ impl<'de> Deserialize<'de> for Node {
                 params.insert("__node_label".to_string(), serde_json::Value::String(label));
             }
         }
+        if let Some(condition) = wire.enabled_when.map(|c| c.trim().to_string()) {
+            if !condition.is_empty() {
+                params.insert(
+                    ENABLED_WHEN_PARAM.to_string(),
+                    serde_json::Value::String(condition),
+                );
+            }
+        }
 
         Ok(Self {
             id: wire.id,
@@ -446,6 +525,170 @@ pub fn normalize_scene_defaults(scene: &mut SceneDSL) -> Result<()> {
     Ok(())
 }
 
+/// Applies `--features`-style overrides on top of the scene's `featureFlags`.
+///
+/// Each entry is `name` (enable), `!name` (disable) or `name=true|false`;
+/// comma-separated lists are accepted so a single CLI argument can carry several.
+pub fn apply_feature_overrides(scene: &mut SceneDSL, overrides: &[String]) -> Result<()> {
+    for entry in overrides.iter().flat_map(|s| s.split(',')) {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let (name, enabled) = match entry.split_once('=') {
+            Some((name, value)) => match value.trim() {
+                "true" | "1" | "on" => (name.trim(), true),
+                "false" | "0" | "off" => (name.trim(), false),
+                other => bail!("invalid value {other:?} for feature flag {name:?}"),
+            },
+            None => match entry.strip_prefix('!') {
+                Some(name) => (name.trim(), false),
+                None => (entry, true),
+            },
+        };
+        if name.is_empty() {
+            bail!("empty feature flag name in {entry:?}");
+        }
+        scene.feature_flags.insert(name.to_string(), enabled);
+    }
+    Ok(())
+}
+
+/// CLI overrides for the scene output texture (`--resolution`, `--format`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputOverrides {
+    pub resolution: Option<[u32; 2]>,
+    pub format: Option<TextureFormat>,
+}
+
+impl OutputOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.resolution.is_none() && self.format.is_none()
+    }
+}
+
+/// Parses a `WxH` resolution such as `1920x1080`.
+pub fn parse_resolution_override(value: &str) -> Result<[u32; 2]> {
+    let parsed = value
+        .split_once(['x', 'X'])
+        .and_then(|(w, h)| Some([w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?]));
+    match parsed {
+        Some([w, h]) if w > 0 && h > 0 => Ok([w, h]),
+        _ => bail!("resolution must be WxH with positive integers, got {value:?}"),
+    }
+}
+
+/// Parses `rgba8`, `rgba8srgb` or `rgba16f` (full RenderTexture.format names
+/// are accepted too).
+pub fn parse_format_override(value: &str) -> Result<TextureFormat> {
+    let format = match value.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+        "rgba8" | "rgba8unorm" => TextureFormat::Rgba8Unorm,
+        "rgba8srgb" | "rgba8unormsrgb" => TextureFormat::Rgba8UnormSrgb,
+        "rgba16f" | "rgba16float" => TextureFormat::Rgba16Float,
+        _ => bail!("format must be rgba8, rgba8srgb or rgba16f, got {value:?}"),
+    };
+    Ok(format)
+}
+
+/// Rewrites the output RenderTexture (RenderTarget.pass → Composite.target) so the
+/// scene renders at the overridden size and format. Connections driving the
+/// texture's width/height are dropped so the override wins, and a Screen target
+/// is resized to match.
+pub fn apply_output_overrides(scene: &mut SceneDSL, overrides: OutputOverrides) -> Result<()> {
+    if overrides.is_empty() {
+        return Ok(());
+    }
+    let scheme = schema::load_default_scheme()?;
+    let target = scene
+        .nodes
+        .iter()
+        .find(|n| {
+            scheme
+                .nodes
+                .get(&n.node_type)
+                .and_then(|s| s.category.as_deref())
+                == Some("RenderTarget")
+        })
+        .ok_or_else(|| anyhow!("output override: missing RenderTarget node"))?;
+    let target_id = target.id.clone();
+    let composite_id = incoming_connection(scene, &target_id, "pass")
+        .map(|c| c.from.node_id.clone())
+        .ok_or_else(|| anyhow!("output override: RenderTarget.pass has no incoming connection"))?;
+    let texture_id = incoming_connection(scene, &composite_id, "target")
+        .map(|c| c.from.node_id.clone())
+        .ok_or_else(|| anyhow!("output override: Composite.target has no incoming connection"))?;
+
+    let mut resized = vec![texture_id.clone()];
+    if scene
+        .nodes
+        .iter()
+        .any(|n| n.id == target_id && n.node_type == "Screen")
+    {
+        resized.push(target_id);
+    }
+    for node in scene.nodes.iter_mut() {
+        if node.id == texture_id {
+            if node.node_type != "RenderTexture" {
+                bail!(
+                    "output override: Composite.target must come from RenderTexture, got {}",
+                    node.node_type
+                );
+            }
+            if let Some(format) = overrides.format {
+                let name = match format {
+                    TextureFormat::Rgba8UnormSrgb => "rgba8unorm-srgb",
+                    TextureFormat::Rgba16Float => "rgba16float",
+                    _ => "rgba8unorm",
+                };
+                node.params
+                    .insert("format".to_string(), serde_json::Value::from(name));
+            }
+        }
+        if let Some([w, h]) = overrides.resolution
+            && resized.contains(&node.id)
+        {
+            node.params
+                .insert("width".to_string(), serde_json::Value::from(w));
+            node.params
+                .insert("height".to_string(), serde_json::Value::from(h));
+        }
+    }
+    if overrides.resolution.is_some() {
+        scene.connections.retain(|c| {
+            !(resized.contains(&c.to.node_id)
+                && matches!(c.to.port_id.as_str(), "width" | "height"))
+        });
+    }
+    Ok(())
+}
+
+/// Freezes everything in `scene` that varies from frame to frame, for
+/// deterministic renders. FilmGrain is the only node that reseeds from the
+/// frame clock, so it is made static; noise nodes get their effective seed
+/// written inline so scenes relying on the default render identically even
+/// if that default changes.
+pub fn pin_frame_dependent_params(scene: &mut SceneDSL) {
+    let mut film_grain = HashSet::new();
+    for node in scene.nodes.iter_mut() {
+        match node.node_type.as_str() {
+            "FilmGrain" => {
+                node.params
+                    .insert("animated".to_string(), serde_json::Value::Bool(false));
+                film_grain.insert(node.id.clone());
+            }
+            "NoiseTexture" | "Voronoi" | "WhiteNoise" => {
+                let seed = parse_u32(&node.params, "seed").unwrap_or(0);
+                node.params
+                    .insert("seed".to_string(), serde_json::Value::from(seed));
+            }
+            _ => {}
+        }
+    }
+    scene
+        .connections
+        .retain(|c| !(film_grain.contains(&c.to.node_id) && c.to.port_id == "animated"));
+}
+
 fn apply_node_default_params(scene: &mut SceneDSL, scheme: &schema::NodeScheme) {
     fn apply_one(node: &mut Node, scheme: &schema::NodeScheme) {
         let Some(node_scheme) = scheme.nodes.get(&node.node_type) else {
@@ -538,10 +781,28 @@ pub fn file_render_target(scene: &SceneDSL) -> Result<Option<FileRenderTarget>>
         .or_else(|| parse_str(&rt.params, "filename"))
         .unwrap_or("output.png")
         .to_string();
+    let dither = rt
+        .params
+        .get("dither")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let bit_depth = FileBitDepth::from_params(&rt.params)?;
+    let quality = match rt.params.get("quality") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(v) => Some(
+            v.as_f64()
+                .filter(|q| (1.0..=100.0).contains(q))
+                .map(|q| q.round() as u8)
+                .ok_or_else(|| anyhow!("File.quality must be a number in 1..=100, got {v}"))?,
+        ),
+    };
 
     Ok(Some(FileRenderTarget {
         directory,
         file_name,
+        dither,
+        bit_depth,
+        quality,
     }))
 }
 
@@ -861,6 +1122,64 @@ fn eval_data_parse_scalar(
     Ok(out_value.as_f64().unwrap_or(0.0))
 }
 
+/// Default for an unconnected generic `Math` operand (`a`, `b`, `c`) without a
+/// param, chosen so a freshly placed node is well defined (clamp to [0, 1],
+/// lerp halfway, divide by one).
+pub fn math_operand_default(operation: &str, port: &str) -> f64 {
+    match (operation, port) {
+        ("divide" | "power" | "modulo" | "smoothstep", "b") => 1.0,
+        ("clamp", "c") => 1.0,
+        ("lerp", "c") => 0.5,
+        _ => 0.0,
+    }
+}
+
+/// CPU mirror of the generic `Math` node, with WGSL semantics for the edge
+/// cases (truncated modulo, `sign(0) == 0`, `clamp` as `min(max(x, lo), hi)`).
+/// Returns `None` for unknown operations.
+pub fn eval_math_operation(operation: &str, a: f64, b: f64, c: f64) -> Option<f64> {
+    let v = match operation {
+        "add" => a + b,
+        "subtract" => a - b,
+        "multiply" => a * b,
+        "divide" => a / b,
+        "modulo" => a % b,
+        "power" => a.powf(b),
+        "min" => a.min(b),
+        "max" => a.max(b),
+        "step" => {
+            if b < a {
+                0.0
+            } else {
+                1.0
+            }
+        }
+        "atan2" => a.atan2(b),
+        "abs" => a.abs(),
+        "floor" => a.floor(),
+        "ceil" => a.ceil(),
+        "fract" => a - a.floor(),
+        "sqrt" => a.sqrt(),
+        "log" => a.ln(),
+        "exp" => a.exp(),
+        "sign" => {
+            if a == 0.0 {
+                0.0
+            } else {
+                a.signum()
+            }
+        }
+        "clamp" => a.max(b).min(c),
+        "smoothstep" => {
+            let t = ((c - a) / (b - a)).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        }
+        "lerp" => a + (b - a) * c,
+        _ => return None,
+    };
+    Some(v)
+}
+
 fn resolve_input_f64_inner(
     scene: &SceneDSL,
     nodes_by_id: &HashMap<String, Node>,
@@ -1107,10 +1426,30 @@ fn resolve_output_f64_inner(
                     .unwrap_or(2.0);
             base.powf(exp)
         }
-        "MathClosure" => {
+        "Math" => {
+            if out_port != "result" {
+                bail!("unsupported Math output port: {out_port}");
+            }
+            let operation = parse_str(&node.params, "operation")
+                .unwrap_or("add")
+                .trim()
+                .to_ascii_lowercase();
+            let mut operands = [0.0; 3];
+            for (slot, port_id) in operands.iter_mut().zip(["a", "b", "c"]) {
+                *slot =
+                    resolve_input_f64_inner(scene, nodes_by_id, node_id, port_id, cache, visiting)?
+                        .unwrap_or_else(|| math_operand_default(&operation, port_id));
+            }
+            let [a, b, c] = operands;
+            eval_math_operation(&operation, a, b, c).ok_or_else(|| {
+                anyhow!("unsupported Math operation '{operation}' (node={node_id})")
+            })?
+        }
+        "MathClosure" | "CustomWGSL" => {
             bail!(
-                "MathClosure cannot be evaluated on CPU for scalar resolution (node={node_id}). \n\
-This node contains user-provided source code; render-time evaluation must be sandboxed and is not implemented."
+                "{} cannot be evaluated on CPU for scalar resolution (node={node_id}). \n\
+This node contains user-provided source code; render-time evaluation must be sandboxed and is not implemented.",
+                node.node_type
             )
         }
         "ResourcePool" => {
@@ -1174,6 +1513,129 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn math_operation_matches_wgsl_edge_cases() {
+        assert_eq!(eval_math_operation("modulo", -7.0, 3.0, 0.0), Some(-1.0));
+        assert_eq!(eval_math_operation("sign", 0.0, 0.0, 0.0), Some(0.0));
+        assert_eq!(eval_math_operation("step", 0.5, 0.5, 0.0), Some(1.0));
+        assert_eq!(eval_math_operation("clamp", 2.0, 1.0, 0.0), Some(0.0));
+        assert_eq!(eval_math_operation("smoothstep", 0.0, 1.0, 0.5), Some(0.5));
+        assert_eq!(eval_math_operation("lerp", 2.0, 4.0, 0.25), Some(2.5));
+        assert_eq!(eval_math_operation("cbrt", 8.0, 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn enabled_when_round_trips_and_features_override_flags() {
+        let mut scene: SceneDSL = serde_json::from_value(json!({
+            "version": "1.0",
+            "metadata": { "name": "flags" },
+            "featureFlags": { "mobile": false, "highQuality": true },
+            "nodes": [
+                { "id": "bloom", "type": "Bloom", "enabledWhen": " highQuality && !mobile " }
+            ],
+            "connections": [],
+            "outputs": null
+        }))
+        .expect("scene should deserialize");
+
+        assert_eq!(
+            scene.nodes[0].params.get(ENABLED_WHEN_PARAM),
+            Some(&json!("highQuality && !mobile"))
+        );
+        let serialized = serde_json::to_value(&scene.nodes[0]).expect("node should serialize");
+        assert_eq!(
+            serialized.get("enabledWhen"),
+            Some(&json!("highQuality && !mobile"))
+        );
+        assert!(serialized["params"].get(ENABLED_WHEN_PARAM).is_none());
+
+        apply_feature_overrides(
+            &mut scene,
+            &["mobile,!highQuality".to_string(), "debug=true".to_string()],
+        )
+        .unwrap();
+        assert_eq!(scene.feature_flags.get("mobile"), Some(&true));
+        assert_eq!(scene.feature_flags.get("highQuality"), Some(&false));
+        assert_eq!(scene.feature_flags.get("debug"), Some(&true));
+        assert!(apply_feature_overrides(&mut scene, &["x=maybe".to_string()]).is_err());
+        assert!(apply_feature_overrides(&mut scene, &["!".to_string()]).is_err());
+    }
+
+    #[test]
+    fn output_overrides_rewrite_output_render_texture() {
+        let mut scene: SceneDSL = serde_json::from_value(json!({
+            "version": "1.0",
+            "metadata": { "name": "overrides" },
+            "nodes": [
+                { "id": "w", "type": "IntInput", "params": { "value": 512 } },
+                { "id": "tex", "type": "RenderTexture", "params": { "width": 512, "height": 512, "format": "rgba8unorm" } },
+                { "id": "comp", "type": "Composite", "params": {} },
+                { "id": "screen", "type": "Screen", "params": { "width": 512, "height": 512 } }
+            ],
+            "connections": [
+                { "id": "c0", "from": { "nodeId": "w", "portId": "value" }, "to": { "nodeId": "tex", "portId": "width" } },
+                { "id": "c1", "from": { "nodeId": "tex", "portId": "texture" }, "to": { "nodeId": "comp", "portId": "target" } },
+                { "id": "c2", "from": { "nodeId": "comp", "portId": "pass" }, "to": { "nodeId": "screen", "portId": "pass" } }
+            ],
+            "outputs": null
+        }))
+        .expect("scene should deserialize");
+
+        apply_output_overrides(
+            &mut scene,
+            OutputOverrides {
+                resolution: Some(parse_resolution_override("1920x1080").unwrap()),
+                format: Some(parse_format_override("rgba16f").unwrap()),
+            },
+        )
+        .unwrap();
+        let tex = scene.nodes.iter().find(|n| n.id == "tex").unwrap();
+        assert_eq!(tex.params.get("width"), Some(&json!(1920)));
+        assert_eq!(tex.params.get("height"), Some(&json!(1080)));
+        assert_eq!(tex.params.get("format"), Some(&json!("rgba16float")));
+        assert_eq!(screen_resolution(&scene), Some([1920, 1080]));
+        assert!(scene.connections.iter().all(|c| c.id != "c0"));
+
+        assert!(parse_resolution_override("1920").is_err());
+        assert!(parse_resolution_override("0x10").is_err());
+        assert!(parse_format_override("bgra8").is_err());
+    }
+
+    #[test]
+    fn pin_frame_dependent_params_freezes_grain_and_seeds() {
+        let mut scene: SceneDSL = serde_json::from_value(json!({
+            "version": "1.0",
+            "metadata": { "name": "pinned" },
+            "nodes": [
+                { "id": "flag", "type": "BoolInput", "params": { "value": true } },
+                { "id": "grain", "type": "FilmGrain", "params": { "animated": true, "seed": 4 } },
+                { "id": "noise", "type": "NoiseTexture", "params": {} },
+                { "id": "cells", "type": "Voronoi", "params": { "seed": 9 } }
+            ],
+            "connections": [
+                { "id": "c0", "from": { "nodeId": "flag", "portId": "value" }, "to": { "nodeId": "grain", "portId": "animated" } }
+            ],
+            "outputs": null
+        }))
+        .expect("scene should deserialize");
+
+        pin_frame_dependent_params(&mut scene);
+        let params = |id: &str| {
+            scene
+                .nodes
+                .iter()
+                .find(|n| n.id == id)
+                .unwrap()
+                .params
+                .clone()
+        };
+        assert_eq!(params("grain").get("animated"), Some(&json!(false)));
+        assert_eq!(params("grain").get("seed"), Some(&json!(4)));
+        assert_eq!(params("noise").get("seed"), Some(&json!(0)));
+        assert_eq!(params("cells").get("seed"), Some(&json!(9)));
+        assert!(scene.connections.is_empty());
+    }
+
     #[test]
     fn node_title_label_round_trips_as_top_level_metadata() {
         let node: Node = serde_json::from_value(json!({
@@ -1237,4 +1699,19 @@ mod tests {
         assert!(err.contains("unsupported RenderTexture.format"));
         assert!(err.contains("rgb16float"));
     }
+
+    #[test]
+    fn file_bit_depth_accepts_auto_numbers_and_strings() {
+        let parse = |v: serde_json::Value| {
+            FileBitDepth::from_params(&HashMap::from([("bitDepth".to_string(), v)]))
+        };
+        assert_eq!(
+            FileBitDepth::from_params(&HashMap::new()).unwrap(),
+            FileBitDepth::Auto
+        );
+        assert_eq!(parse(json!("auto")).unwrap(), FileBitDepth::Auto);
+        assert_eq!(parse(json!(8)).unwrap(), FileBitDepth::Eight);
+        assert_eq!(parse(json!("16")).unwrap(), FileBitDepth::Sixteen);
+        assert!(parse(json!(12)).is_err());
+    }
 }
@@ -0,0 +1,156 @@
+//! Canonical SceneDSL serialization.
+//!
+//! Object keys are emitted in sorted order (serde_json's default map), nodes
+//! and groups are sorted by id, connections by their endpoints, and numbers
+//! are normalized so `1.0`/`1` and `-0.0`/`0` print identically. Port lists and
+//! other arrays keep their order because it is meaningful to the editor.
+
+use std::cmp::Ordering;
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Number, Value};
+
+use super::SceneDSL;
+
+/// Largest integer magnitude an f64 represents exactly.
+const MAX_EXACT_INT: f64 = 9_007_199_254_740_992.0;
+
+/// Serializes `scene` as canonical, pretty-printed JSON.
+pub fn to_canonical_json(scene: &SceneDSL) -> Result<String> {
+    let value = serde_json::to_value(scene).context("failed to serialize scene")?;
+    to_pretty(canonicalize_scene_value(value))
+}
+
+/// Reformats raw SceneDSL JSON text canonically.
+///
+/// Works on the raw document rather than a parsed [`SceneDSL`], so
+/// editor-only fields (node positions, viewport state) are kept. The text must
+/// still parse as a scene.
+pub fn format_scene_json(text: &str) -> Result<String> {
+    let value: Value = serde_json::from_str(text).context("invalid JSON")?;
+    serde_json::from_value::<SceneDSL>(value.clone()).context("invalid SceneDSL")?;
+    to_pretty(canonicalize_scene_value(value))
+}
+
+/// Applies the canonical ordering and number normalization to a scene value.
+pub fn canonicalize_scene_value(mut value: Value) -> Value {
+    normalize_numbers(&mut value);
+    if let Value::Object(scene) = &mut value {
+        sort_graph(scene);
+        if let Some(Value::Array(groups)) = scene.get_mut("groups") {
+            for group in groups.iter_mut() {
+                if let Value::Object(group) = group {
+                    sort_graph(group);
+                }
+            }
+            groups.sort_by(|a, b| cmp_str_field(a, b, "id"));
+        }
+    }
+    value
+}
+
+fn to_pretty(value: Value) -> Result<String> {
+    let mut text = serde_json::to_string_pretty(&value).context("failed to format scene")?;
+    text.push('\n');
+    Ok(text)
+}
+
+fn sort_graph(graph: &mut Map<String, Value>) {
+    if let Some(Value::Array(nodes)) = graph.get_mut("nodes") {
+        nodes.sort_by(|a, b| cmp_str_field(a, b, "id"));
+    }
+    if let Some(Value::Array(connections)) = graph.get_mut("connections") {
+        connections.sort_by_key(connection_key);
+    }
+}
+
+fn cmp_str_field(a: &Value, b: &Value, key: &str) -> Ordering {
+    let field = |v: &Value| v.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+    field(a).cmp(&field(b))
+}
+
+fn connection_key(conn: &Value) -> [String; 5] {
+    let endpoint = |side: &str, key: &str| {
+        conn.get(side)
+            .and_then(|e| e.get(key))
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string()
+    };
+    [
+        endpoint("from", "nodeId"),
+        endpoint("from", "portId"),
+        endpoint("to", "nodeId"),
+        endpoint("to", "portId"),
+        conn.get("id")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string(),
+    ]
+}
+
+fn normalize_numbers(value: &mut Value) {
+    match value {
+        Value::Number(n) => {
+            if let Some(normalized) = normalize_number(n) {
+                *n = normalized;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize_numbers),
+        Value::Object(map) => map.values_mut().for_each(normalize_numbers),
+        _ => {}
+    }
+}
+
+/// Integral floats become integers; everything else keeps serde_json's
+/// shortest round-trip float formatting.
+fn normalize_number(n: &Number) -> Option<Number> {
+    if n.is_i64() || n.is_u64() {
+        return None;
+    }
+    let f = n.as_f64()?;
+    if f.fract() == 0.0 && f.abs() <= MAX_EXACT_INT {
+        return Some(Number::from(f as i64));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{canonicalize_scene_value, format_scene_json};
+
+    #[test]
+    fn sorts_graph_and_normalizes_numbers() {
+        let value = json!({
+            "nodes": [
+                { "id": "b", "type": "FloatInput", "params": { "value": 2.0 } },
+                { "id": "a", "type": "FloatInput", "params": { "value": -0.0 } }
+            ],
+            "connections": [
+                { "id": "c2", "from": { "nodeId": "b", "portId": "value" }, "to": { "nodeId": "p", "portId": "x" } },
+                { "id": "c1", "from": { "nodeId": "a", "portId": "value" }, "to": { "nodeId": "p", "portId": "y" } }
+            ],
+            "groups": [{ "id": "g2" }, { "id": "g1", "nodes": [{ "id": "z" }, { "id": "y" }] }]
+        });
+        let out = canonicalize_scene_value(value);
+        assert_eq!(out["nodes"][0]["id"], "a");
+        assert_eq!(out["nodes"][0]["params"]["value"].to_string(), "0");
+        assert_eq!(out["nodes"][1]["params"]["value"].to_string(), "2");
+        assert_eq!(out["connections"][0]["id"], "c1");
+        assert_eq!(out["groups"][0]["id"], "g1");
+        assert_eq!(out["groups"][0]["nodes"][0]["id"], "y");
+    }
+
+    #[test]
+    fn formatting_is_idempotent_and_keeps_editor_fields() {
+        let text = r#"{"version":"1.0","metadata":{"name":"s"},"outputs":null,
+            "nodes":[{"id":"n","type":"FloatInput","position":{"x":1.5,"y":2.0},"params":{}}],
+            "connections":[]}"#;
+        let once = format_scene_json(text).unwrap();
+        assert_eq!(format_scene_json(&once).unwrap(), once);
+        assert!(once.contains("\"position\""));
+        assert!(format_scene_json("{\"nodes\": 3}").is_err());
+    }
+}
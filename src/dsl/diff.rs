@@ -0,0 +1,272 @@
+//! Semantic comparison of two scenes.
+//!
+//! Connections are compared by endpoints rather than by id (editor ids are
+//! regenerated freely), and numeric params compare by value so `1` and `1.0`
+//! are not reported as a change.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use super::{Connection, GroupDSL, Node, SceneDSL};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneDiff {
+    pub added_nodes: Vec<NodeSummary>,
+    pub removed_nodes: Vec<NodeSummary>,
+    pub changed_nodes: Vec<NodeChange>,
+    pub added_connections: Vec<ConnectionSummary>,
+    pub removed_connections: Vec<ConnectionSummary>,
+    pub added_groups: Vec<String>,
+    pub removed_groups: Vec<String>,
+    pub changed_groups: Vec<String>,
+}
+
+impl SceneDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_connections.is_empty()
+            && self.removed_connections.is_empty()
+            && self.added_groups.is_empty()
+            && self.removed_groups.is_empty()
+            && self.changed_groups.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeSummary {
+    pub id: String,
+    pub node_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeChange {
+    pub id: String,
+    pub node_type: String,
+    /// Set when the node kept its id but changed type (`[before, after]`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_change: Option<[String; 2]>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<ParamChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wgsl_override_change: Option<[Option<String>; 2]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParamChange {
+    pub key: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionSummary {
+    pub from_node_id: String,
+    pub from_port_id: String,
+    pub to_node_id: String,
+    pub to_port_id: String,
+}
+
+impl From<&Connection> for ConnectionSummary {
+    fn from(conn: &Connection) -> Self {
+        Self {
+            from_node_id: conn.from.node_id.clone(),
+            from_port_id: conn.from.port_id.clone(),
+            to_node_id: conn.to.node_id.clone(),
+            to_port_id: conn.to.port_id.clone(),
+        }
+    }
+}
+
+/// Reports what changed from `before` to `after`. All lists are sorted by id
+/// so the output is stable for review.
+pub fn diff_scenes(before: &SceneDSL, after: &SceneDSL) -> SceneDiff {
+    let mut diff = SceneDiff::default();
+
+    let before_nodes: BTreeMap<&str, &Node> =
+        before.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let after_nodes: BTreeMap<&str, &Node> =
+        after.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+
+    for (id, node) in &after_nodes {
+        match before_nodes.get(id) {
+            None => diff.added_nodes.push(node_summary(node)),
+            Some(old) => {
+                if let Some(change) = diff_node(old, node) {
+                    diff.changed_nodes.push(change);
+                }
+            }
+        }
+    }
+    for (id, node) in &before_nodes {
+        if !after_nodes.contains_key(id) {
+            diff.removed_nodes.push(node_summary(node));
+        }
+    }
+
+    let before_conns: BTreeSet<ConnectionSummary> =
+        before.connections.iter().map(Into::into).collect();
+    let after_conns: BTreeSet<ConnectionSummary> =
+        after.connections.iter().map(Into::into).collect();
+    diff.added_connections = after_conns.difference(&before_conns).cloned().collect();
+    diff.removed_connections = before_conns.difference(&after_conns).cloned().collect();
+
+    let before_groups = groups_by_id(&before.groups);
+    let after_groups = groups_by_id(&after.groups);
+    for (id, group) in &after_groups {
+        match before_groups.get(id) {
+            None => diff.added_groups.push(id.to_string()),
+            Some(old) if old != group => diff.changed_groups.push(id.to_string()),
+            Some(_) => {}
+        }
+    }
+    diff.removed_groups = before_groups
+        .keys()
+        .filter(|id| !after_groups.contains_key(*id))
+        .map(|id| id.to_string())
+        .collect();
+
+    diff
+}
+
+fn node_summary(node: &Node) -> NodeSummary {
+    NodeSummary {
+        id: node.id.clone(),
+        node_type: node.node_type.clone(),
+    }
+}
+
+fn diff_node(before: &Node, after: &Node) -> Option<NodeChange> {
+    let type_change = (before.node_type != after.node_type)
+        .then(|| [before.node_type.clone(), after.node_type.clone()]);
+    let wgsl_override_change = (before.wgsl_override != after.wgsl_override)
+        .then(|| [before.wgsl_override.clone(), after.wgsl_override.clone()]);
+    let params = diff_params(&before.params, &after.params);
+
+    if type_change.is_none() && wgsl_override_change.is_none() && params.is_empty() {
+        return None;
+    }
+    Some(NodeChange {
+        id: after.id.clone(),
+        node_type: after.node_type.clone(),
+        type_change,
+        params,
+        wgsl_override_change,
+    })
+}
+
+fn diff_params(
+    before: &HashMap<String, serde_json::Value>,
+    after: &HashMap<String, serde_json::Value>,
+) -> Vec<ParamChange> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = before.get(key);
+            let new = after.get(key);
+            let unchanged = match (old, new) {
+                (Some(a), Some(b)) => values_equal(a, b),
+                (None, None) => true,
+                _ => false,
+            };
+            (!unchanged).then(|| ParamChange {
+                key: key.clone(),
+                before: old.cloned(),
+                after: new.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// JSON equality that treats numbers by value, recursing into arrays/objects.
+fn values_equal(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(xs), Value::Array(ys)) => {
+            xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| values_equal(x, y))
+        }
+        (Value::Object(xs), Value::Object(ys)) => {
+            xs.len() == ys.len()
+                && xs
+                    .iter()
+                    .all(|(k, x)| ys.get(k).is_some_and(|y| values_equal(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
+fn groups_by_id(groups: &[GroupDSL]) -> BTreeMap<&str, serde_json::Value> {
+    groups
+        .iter()
+        .map(|group| {
+            (
+                group.id.as_str(),
+                serde_json::to_value(group).unwrap_or(serde_json::Value::Null),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::diff_scenes;
+    use crate::dsl::SceneDSL;
+
+    fn scene(value: serde_json::Value) -> SceneDSL {
+        serde_json::from_value(value).expect("scene")
+    }
+
+    fn base() -> serde_json::Value {
+        json!({
+            "version": "1.0",
+            "metadata": { "name": "a" },
+            "nodes": [
+                { "id": "color", "type": "ColorInput", "params": { "value": [1, 0, 0, 1] } },
+                { "id": "pass", "type": "RenderPass", "params": {} }
+            ],
+            "connections": [
+                { "id": "c1", "from": { "nodeId": "color", "portId": "color" },
+                  "to": { "nodeId": "pass", "portId": "material" } }
+            ],
+            "outputs": null
+        })
+    }
+
+    #[test]
+    fn identical_scenes_have_no_diff() {
+        let mut renamed = base();
+        renamed["connections"][0]["id"] = json!("other-id");
+        renamed["nodes"][0]["params"]["value"] = json!([1.0, 0.0, 0.0, 1.0]);
+        assert!(diff_scenes(&scene(base()), &scene(renamed)).is_empty());
+    }
+
+    #[test]
+    fn reports_nodes_params_and_connections() {
+        let mut after = base();
+        after["nodes"][0]["params"]["value"] = json!([0, 1, 0, 1]);
+        after["nodes"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "id": "t", "type": "TimeInput", "params": {} }));
+        after["connections"] = json!([]);
+
+        let diff = diff_scenes(&scene(base()), &scene(after));
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert_eq!(diff.added_nodes[0].id, "t");
+        assert!(diff.removed_nodes.is_empty());
+        assert_eq!(diff.changed_nodes.len(), 1);
+        assert_eq!(diff.changed_nodes[0].params[0].key, "value");
+        assert_eq!(diff.removed_connections.len(), 1);
+        assert_eq!(diff.removed_connections[0].to_port_id, "material");
+        assert!(diff.added_connections.is_empty());
+    }
+}
@@ -0,0 +1,210 @@
+//! `{token}` expansion for `File` render target names.
+//!
+//! `fileName` may contain `{scene}`, `{date}`, `{frame}`, `{w}`, `{h}` and
+//! `{hash}`. Numeric tokens take a zero-padded width (`{frame:04}`), and
+//! `{{`/`}}` produce literal braces. Expansion happens when the output path is
+//! resolved, so a single scene can write dated or numbered files directly.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, anyhow, bail};
+use sha2::{Digest, Sha256};
+
+use super::{SceneDSL, screen_resolution, to_canonical_json};
+
+/// Hex digits of the canonical scene hash kept by `{hash}`.
+const SCENE_HASH_LEN: usize = 8;
+
+/// Values substituted into a `fileName` template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileNameTokens {
+    /// `{scene}`: the scene file stem, or the scene name when unsaved.
+    pub scene: String,
+    /// `{date}`: UTC date as `YYYY-MM-DD`.
+    pub date: String,
+    /// `{frame}`: frame index for sequence exports; 0 for single renders.
+    pub frame: u64,
+    /// `{w}`/`{h}`: output size; `None` when the scene has no Screen size.
+    pub size: Option<[u32; 2]>,
+    /// `{hash}`: short hash of the canonical scene JSON.
+    pub hash: String,
+}
+
+impl FileNameTokens {
+    /// Tokens for a single render of `scene`. `scene_stem` overrides the
+    /// scene's metadata name, e.g. with the file stem it was loaded from.
+    pub fn for_scene(scene: &SceneDSL, scene_stem: Option<&str>) -> Result<Self> {
+        let name = scene_stem.unwrap_or(scene.metadata.name.as_str());
+        let canonical = to_canonical_json(scene)?;
+        let hash = format!("{:x}", Sha256::digest(canonical.as_bytes()));
+        Ok(Self {
+            scene: sanitize_file_name_segment(name),
+            date: utc_date(SystemTime::now()),
+            frame: 0,
+            size: screen_resolution(scene),
+            hash: hash[..SCENE_HASH_LEN].to_string(),
+        })
+    }
+}
+
+/// Expands every `{token}` in `template`. Unknown tokens, unterminated braces
+/// and size tokens without a known size are errors rather than being written
+/// into the file name verbatim.
+pub fn expand_file_name(template: &str, tokens: &FileNameTokens) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find(['{', '}']) {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            bail!("unmatched '}}' in fileName {template:?}");
+        }
+        let Some(end) = tail.find('}') else {
+            bail!("unterminated '{{' in fileName {template:?}");
+        };
+        out.push_str(&expand_token(&tail[1..end], tokens, template)?);
+        rest = &tail[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn expand_token(token: &str, tokens: &FileNameTokens, template: &str) -> Result<String> {
+    let (name, width) = match token.split_once(':') {
+        Some((name, spec)) => match spec.parse::<usize>() {
+            Ok(width) if spec.chars().all(|c| c.is_ascii_digit()) => (name, Some(width)),
+            _ => bail!("invalid width {spec:?} for {{{name}}} in fileName {template:?}"),
+        },
+        None => (token, None),
+    };
+    let size = |axis: usize| {
+        tokens
+            .size
+            .map(|size| u64::from(size[axis]))
+            .ok_or_else(|| anyhow!("{{{name}}} in fileName needs a Screen size"))
+    };
+    let number = match name {
+        "frame" => tokens.frame,
+        "w" => size(0)?,
+        "h" => size(1)?,
+        "scene" | "date" | "hash" => {
+            if width.is_some() {
+                bail!("{{{name}}} does not take a width in fileName {template:?}");
+            }
+            return Ok(match name {
+                "scene" => tokens.scene.clone(),
+                "date" => tokens.date.clone(),
+                _ => tokens.hash.clone(),
+            });
+        }
+        _ => bail!(
+            "unknown token {{{name}}} in fileName {template:?} (supported: scene, date, frame, w, h, hash)"
+        ),
+    };
+    Ok(format!("{number:0width$}", width = width.unwrap_or(0)))
+}
+
+/// Replaces characters that would split or break a path component.
+pub(super) fn sanitize_file_name_segment(name: &str) -> String {
+    let cleaned: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if cleaned.is_empty() || cleaned == "." || cleaned == ".." {
+        "scene".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// `YYYY-MM-DD` in UTC, using the days-to-civil conversion so no calendar
+/// dependency is needed.
+fn utc_date(now: SystemTime) -> String {
+    let secs = now
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{FileNameTokens, expand_file_name, sanitize_file_name_segment, utc_date};
+
+    fn tokens() -> FileNameTokens {
+        FileNameTokens {
+            scene: "bloom".to_string(),
+            date: "2026-03-01".to_string(),
+            frame: 7,
+            size: Some([1920, 1080]),
+            hash: "0123abcd".to_string(),
+        }
+    }
+
+    #[test]
+    fn expands_all_tokens() {
+        let name = expand_file_name("{scene}/{date}_{frame:04}_{w}x{h}_{hash}.png", &tokens());
+        assert_eq!(
+            name.unwrap(),
+            "bloom/2026-03-01_0007_1920x1080_0123abcd.png"
+        );
+        assert_eq!(
+            expand_file_name("plain.png", &tokens()).unwrap(),
+            "plain.png"
+        );
+        assert_eq!(
+            expand_file_name("{{scene}}_{frame}.exr", &tokens()).unwrap(),
+            "{scene}_7.exr"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        for bad in [
+            "{nope}.png",
+            "{frame.png",
+            "frame}.png",
+            "{frame:x4}.png",
+            "{scene:04}.png",
+        ] {
+            assert!(expand_file_name(bad, &tokens()).is_err(), "{bad}");
+        }
+        let no_size = FileNameTokens {
+            size: None,
+            ..tokens()
+        };
+        assert!(expand_file_name("{w}.png", &no_size).is_err());
+    }
+
+    #[test]
+    fn date_and_scene_are_file_name_safe() {
+        assert_eq!(utc_date(UNIX_EPOCH), "1970-01-01");
+        assert_eq!(
+            utc_date(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "2000-02-29"
+        );
+        assert_eq!(sanitize_file_name_segment("a/b:c"), "a_b_c");
+        assert_eq!(sanitize_file_name_segment(" .. "), "scene");
+    }
+}
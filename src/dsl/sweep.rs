@@ -0,0 +1,182 @@
+//! Parameter sweeps: render a grid of scene variants that differ in numeric
+//! node params (`--sweep node.param=start:end:steps`).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow, bail};
+
+use super::SceneDSL;
+use super::file_name::sanitize_file_name_segment;
+
+/// One swept param: `steps` evenly spaced values from `start` to `end`, inclusive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSweep {
+    pub node_id: String,
+    pub param: String,
+    pub start: f64,
+    pub end: f64,
+    pub steps: u32,
+}
+
+impl ParamSweep {
+    /// Parses `node.param=start:end:steps`. The node id is everything before
+    /// the last `.` so ids containing dots still work.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let usage = || anyhow!("--sweep expects node.param=start:end:steps, got {spec:?}");
+        let (target, range) = spec.split_once('=').ok_or_else(usage)?;
+        let (node_id, param) = target.trim().rsplit_once('.').ok_or_else(usage)?;
+        if node_id.is_empty() || param.is_empty() {
+            return Err(usage());
+        }
+        let parts: Vec<&str> = range.split(':').map(str::trim).collect();
+        let [start, end, steps] = parts.as_slice() else {
+            return Err(usage());
+        };
+        let number = |v: &str| {
+            v.parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .ok_or_else(usage)
+        };
+        let steps = steps.parse::<u32>().map_err(|_| usage())?;
+        if steps == 0 {
+            bail!("--sweep {spec:?}: steps must be at least 1");
+        }
+        Ok(Self {
+            node_id: node_id.to_string(),
+            param: param.to_string(),
+            start: number(start)?,
+            end: number(end)?,
+            steps,
+        })
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        if self.steps == 1 {
+            return vec![self.start];
+        }
+        let span = self.end - self.start;
+        let last = f64::from(self.steps - 1);
+        (0..self.steps)
+            .map(|i| self.start + span * f64::from(i) / last)
+            .collect()
+    }
+}
+
+/// Every combination of swept values, first sweep varying slowest.
+pub fn sweep_grid(sweeps: &[ParamSweep]) -> Vec<Vec<f64>> {
+    sweeps.iter().fold(vec![Vec::new()], |grid, sweep| {
+        let values = sweep.values();
+        grid.iter()
+            .flat_map(|point| {
+                values.iter().map(move |v| {
+                    let mut next = point.clone();
+                    next.push(*v);
+                    next
+                })
+            })
+            .collect()
+    })
+}
+
+/// Sets each swept param to its value in `point`. Swept params must already
+/// hold a number (after defaults are applied) so typos fail loudly.
+pub fn apply_sweep_point(scene: &mut SceneDSL, sweeps: &[ParamSweep], point: &[f64]) -> Result<()> {
+    for (sweep, value) in sweeps.iter().zip(point) {
+        let node = scene
+            .nodes
+            .iter_mut()
+            .find(|n| n.id == sweep.node_id)
+            .ok_or_else(|| anyhow!("--sweep: no node with id {:?}", sweep.node_id))?;
+        let slot = node.params.get_mut(&sweep.param).ok_or_else(|| {
+            anyhow!(
+                "--sweep: node {:?} ({}) has no param {:?}",
+                sweep.node_id,
+                node.node_type,
+                sweep.param
+            )
+        })?;
+        if !slot.is_number() {
+            bail!(
+                "--sweep: {}.{} is not numeric (got {slot})",
+                sweep.node_id,
+                sweep.param
+            );
+        }
+        *slot = serde_json::Number::from_f64(*value)
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| {
+                anyhow!(
+                    "--sweep: non-finite value for {}.{}",
+                    sweep.node_id,
+                    sweep.param
+                )
+            })?;
+    }
+    Ok(())
+}
+
+fn format_sweep_value(value: f64) -> String {
+    let text = format!("{value:.4}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// `out/blur.png` → `out/blur_blur1.radius-2.5.png`: the swept values are
+/// appended to the file stem, one `_node.param-value` per sweep.
+pub fn sweep_output_path(base: &Path, sweeps: &[ParamSweep], point: &[f64]) -> PathBuf {
+    let stem = base
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut name = stem;
+    for (sweep, value) in sweeps.iter().zip(point) {
+        name.push('_');
+        name.push_str(&sanitize_file_name_segment(&format!(
+            "{}.{}-{}",
+            sweep.node_id,
+            sweep.param,
+            format_sweep_value(*value)
+        )));
+    }
+    if let Some(ext) = base.extension() {
+        name.push('.');
+        name.push_str(&ext.to_string_lossy());
+    }
+    base.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_expand_sweeps() {
+        let radius = ParamSweep::parse("blur.main.radius=0:8:5").unwrap();
+        assert_eq!(radius.node_id, "blur.main");
+        assert_eq!(radius.param, "radius");
+        assert_eq!(radius.values(), vec![0.0, 2.0, 4.0, 6.0, 8.0]);
+        let threshold = ParamSweep::parse("bloom.threshold=0.5:0.5:1").unwrap();
+        assert_eq!(threshold.values(), vec![0.5]);
+
+        assert!(ParamSweep::parse("radius=0:1:2").is_err());
+        assert!(ParamSweep::parse("blur.radius=0:1").is_err());
+        assert!(ParamSweep::parse("blur.radius=0:1:0").is_err());
+
+        let grid = sweep_grid(&[radius.clone(), ParamSweep::parse("b.t=0:1:2").unwrap()]);
+        assert_eq!(grid.len(), 10);
+        assert_eq!(grid[1], vec![0.0, 1.0]);
+        assert_eq!(grid[2], vec![2.0, 0.0]);
+
+        assert_eq!(
+            sweep_output_path(Path::new("/out/blur.png"), &[radius], &[2.5]),
+            PathBuf::from("/out/blur_blur.main.radius-2.5.png")
+        );
+        assert_eq!(format_sweep_value(1.0 / 3.0), "0.3333");
+        assert_eq!(format_sweep_value(4.0), "4");
+    }
+}
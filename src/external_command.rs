@@ -0,0 +1,12 @@
+//! Lookup for the external tools the server shells out to (`adb`, `ffmpeg`).
+
+use std::{env, path::PathBuf};
+
+/// Path of an external command: `env_key` when set and non-empty, otherwise
+/// `fallback` resolved through `PATH` by `Command`.
+pub(crate) fn resolve_command_path(env_key: &str, fallback: &str) -> PathBuf {
+    env::var_os(env_key)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(fallback))
+}
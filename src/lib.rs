@@ -1,10 +1,12 @@
 pub mod android_reference;
 pub mod animation;
+pub mod api;
 pub mod app;
 pub mod asset_store;
 pub mod color;
 pub mod debug_artifacts;
 pub mod dsl;
+mod external_command;
 pub mod nforge;
 pub mod perf_log;
 pub mod profile;
@@ -12,5 +14,6 @@ pub mod protocol;
 pub mod renderer;
 pub mod schema;
 pub mod state_machine;
+pub mod testing;
 pub mod ui;
 pub mod ws;
@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fs::OpenOptions,
     io::{self, Write},
     path::PathBuf,
@@ -8,12 +8,15 @@ use std::{
 use anyhow::{Context, Result};
 use rust_wgpu_fiber::{
     eframe::wgpu,
-    shader_space::{PassProfileSample, RenderProfile},
+    shader_space::{PassProfileSample, RenderProfile, ShaderSpace},
 };
 use serde::Serialize;
 use serde_json::{Value, json};
 
-use crate::ui::resource_tree::{PassInfo, ResourceSnapshot};
+use crate::{
+    renderer::GpuFrameTiming,
+    ui::resource_tree::{PassInfo, ResourceSnapshot},
+};
 
 pub const PROFILE_SCHEMA_VERSION: u32 = 1;
 
@@ -172,6 +175,7 @@ pub fn scene_info_event(
     output_texture: &str,
     export_texture: &str,
     snapshot: &ResourceSnapshot,
+    texture_bytes: u64,
 ) -> Value {
     json!({
         "event": "scene_info",
@@ -182,6 +186,7 @@ pub fn scene_info_event(
             "outputTexture": output_texture,
             "exportTexture": export_texture,
             "passCount": snapshot.passes.len(),
+            "textureBytes": texture_bytes,
         }
     })
 }
@@ -264,6 +269,101 @@ pub fn run_end_event(run_id: &str, output_path: &str, accumulator: &ProfileAccum
     })
 }
 
+/// Bytes held by every texture a pass renders to or samples. MSAA passes
+/// count their single-sample resolve target, so this undercounts multisampled
+/// color attachments.
+pub fn texture_memory_bytes(shader_space: &ShaderSpace, snapshot: &ResourceSnapshot) -> u64 {
+    let names: BTreeSet<&str> = snapshot
+        .passes
+        .iter()
+        .flat_map(|pass| {
+            pass.target_texture
+                .iter()
+                .chain(&pass.sampled_textures)
+                .map(String::as_str)
+        })
+        .collect();
+    names
+        .into_iter()
+        .filter_map(|name| shader_space.texture_info(name))
+        .map(|info| {
+            let texel = info.format.block_copy_size(None).unwrap_or(4) as u64;
+            info.size.width as u64 * info.size.height as u64 * texel
+        })
+        .sum()
+}
+
+/// Payload of the WS `render_stats` message: the latest frame's timings with
+/// passes ordered by CPU encode time, most expensive first. GPU times and the
+/// submit count come from a separate timestamped frame and are `null` when
+/// the device cannot take timestamps. Without a CPU profile the passes are
+/// ordered by GPU time instead.
+pub fn render_stats_payload(
+    profile: Option<&RenderProfile>,
+    gpu_timing: Option<&GpuFrameTiming>,
+    texture_bytes: u64,
+) -> Value {
+    let Some(profile) = profile else {
+        let Some(timing) = gpu_timing else {
+            return json!({
+                "frame": null,
+                "passes": [],
+                "textureBytes": texture_bytes,
+            });
+        };
+        let mut passes = timing.passes.iter().enumerate().collect::<Vec<_>>();
+        passes.sort_by(|a, b| b.1.gpu_ms.total_cmp(&a.1.gpu_ms).then(a.0.cmp(&b.0)));
+        return json!({
+            "frame": {
+                "cpuEncodeMs": null,
+                "submitMs": null,
+                "queueWaitMs": null,
+                "wallMs": null,
+                "gpuMs": timing.gpu_ms(),
+                "submitCount": timing.submit_count,
+            },
+            "passes": passes
+                .into_iter()
+                .map(|(order_index, pass)| json!({
+                    "passId": pass.pass_name,
+                    "orderIndex": order_index,
+                    "pipelineKind": null,
+                    "cpuEncodeMs": null,
+                    "gpuMs": pass.gpu_ms,
+                }))
+                .collect::<Vec<_>>(),
+            "textureBytes": texture_bytes,
+        });
+    };
+    let mut passes = profile.passes.iter().collect::<Vec<_>>();
+    passes.sort_by(|a, b| {
+        b.cpu_encode_ms
+            .total_cmp(&a.cpu_encode_ms)
+            .then_with(|| a.pass_name.cmp(&b.pass_name))
+    });
+    json!({
+        "frame": {
+            "cpuEncodeMs": profile.frame_cpu_encode_ms,
+            "submitMs": profile.submit_cpu_ms,
+            "queueWaitMs": profile.queue_wait_ms,
+            "wallMs": profile.frame_wall_ms,
+            "gpuMs": gpu_timing.map(GpuFrameTiming::gpu_ms),
+            "submitCount": gpu_timing.map(|timing| timing.submit_count),
+        },
+        "passes": passes
+            .into_iter()
+            .map(|pass| json!({
+                "passId": pass.pass_name,
+                "orderIndex": pass.order_index,
+                "pipelineKind": pass.pipeline_kind.as_str(),
+                "cpuEncodeMs": pass.cpu_encode_ms,
+                "gpuMs": gpu_timing.and_then(|timing| timing.pass_gpu_ms(&pass.pass_name)),
+            }))
+            .collect::<Vec<_>>(),
+        "textureBytes": texture_bytes,
+    })
+}
+
 pub fn pass_info_by_name(snapshot: &ResourceSnapshot) -> BTreeMap<&str, &PassInfo> {
     snapshot
         .passes
@@ -324,3 +424,43 @@ fn percentile_sorted(sorted: &[f64], p: f64) -> f64 {
     let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
     sorted[idx.min(sorted.len() - 1)]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::PassGpuTiming;
+
+    #[test]
+    fn render_stats_payload_reports_gpu_ms_per_pass_and_submit_count() {
+        let timing = GpuFrameTiming {
+            passes: vec![
+                PassGpuTiming {
+                    pass_name: "blur".to_string(),
+                    gpu_ms: 0.5,
+                },
+                PassGpuTiming {
+                    pass_name: "bloom".to_string(),
+                    gpu_ms: 1.5,
+                },
+            ],
+            submit_count: 6,
+        };
+        let payload = render_stats_payload(None, Some(&timing), 64);
+
+        assert_eq!(payload["textureBytes"], 64);
+        assert_eq!(payload["frame"]["gpuMs"], 2.0);
+        assert_eq!(payload["frame"]["submitCount"], 6);
+        assert!(payload["frame"]["cpuEncodeMs"].is_null());
+        let passes = payload["passes"].as_array().unwrap();
+        assert_eq!(passes.len(), 2);
+        assert_eq!(passes[0]["passId"], "bloom");
+        assert_eq!(passes[0]["orderIndex"], 1);
+        assert_eq!(passes[0]["gpuMs"], 1.5);
+        assert_eq!(passes[1]["passId"], "blur");
+        assert_eq!(passes[1]["orderIndex"], 0);
+
+        let empty = render_stats_payload(None, None, 0);
+        assert!(empty["frame"].is_null());
+        assert_eq!(empty["passes"], json!([]));
+    }
+}
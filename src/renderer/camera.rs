@@ -9,6 +9,7 @@ use crate::{
 
 const EPSILON: f32 = 1e-6;
 const CAMERA_MATRIX_COMPARE_EPSILON: f32 = 1e-5;
+const CAMERA_DEFAULT_POSITION: [f32; 3] = [0.0, 0.0, 5.0];
 
 pub fn legacy_projection_camera_matrix(target_size: [f32; 2]) -> [f32; 16] {
     let w = if target_size[0].is_finite() && target_size[0] > 0.0 {
@@ -59,6 +60,15 @@ pub fn resolve_camera_position_for_pass_node(
                     [0.0, 0.0, 0.0],
                 );
             }
+            "Camera" => {
+                return resolve_camera_vec3_input(
+                    scene,
+                    nodes_by_id,
+                    cam_node,
+                    "position",
+                    CAMERA_DEFAULT_POSITION,
+                );
+            }
             _ => {}
         }
     }
@@ -72,11 +82,13 @@ pub fn resolve_effective_camera_for_pass_node(
     target_size: [f32; 2],
 ) -> Result<[f32; 16]> {
     if let Some(conn) = incoming_connection(scene, &node.id, "camera") {
-        return resolve_mat4_output_column_major(
+        // A `Camera` node without an explicit aspect follows the pass target.
+        return resolve_mat4_output_with_aspect(
             scene,
             nodes_by_id,
             &conn.from.node_id,
             &conn.from.port_id,
+            target_aspect(target_size),
         )
         .map_err(|e| {
             anyhow!(
@@ -130,9 +142,29 @@ pub fn resolve_mat4_output_column_major(
     nodes_by_id: &HashMap<String, Node>,
     node_id: &str,
     port_id: &str,
+) -> Result<[f32; 16]> {
+    resolve_mat4_output_with_aspect(scene, nodes_by_id, node_id, port_id, 1.0)
+}
+
+fn resolve_mat4_output_with_aspect(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node_id: &str,
+    port_id: &str,
+    default_aspect: f32,
 ) -> Result<[f32; 16]> {
     let node = find_node(nodes_by_id, node_id)?;
     match node.node_type.as_str() {
+        "Camera" => {
+            if port_id != "camera" {
+                bail!(
+                    "Camera '{}' has no output port '{}' (expected 'camera')",
+                    node_id,
+                    port_id
+                );
+            }
+            resolve_camera_node_matrix(scene, nodes_by_id, node, default_aspect)
+        }
         "PerspectiveCamera" => {
             if port_id != "camera" {
                 bail!(
@@ -171,6 +203,83 @@ pub fn resolve_mat4_output_column_major(
     }
 }
 
+fn target_aspect(target_size: [f32; 2]) -> f32 {
+    let [w, h] = target_size;
+    if w.is_finite() && h.is_finite() && w > 0.0 && h > 0.0 {
+        w / h
+    } else {
+        1.0
+    }
+}
+
+/// Resolve the unified `Camera` node: a look-at view combined with either a perspective
+/// (`fov`, vertical degrees) or orthographic (`orthoHeight`, world units) projection.
+///
+/// `aspect` <= 0 (the default) means "use `default_aspect`", which is the consuming
+/// pass's target aspect when resolved for a RenderPass.
+fn resolve_camera_node_matrix(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    default_aspect: f32,
+) -> Result<[f32; 16]> {
+    let projection = node
+        .params
+        .get("projection")
+        .and_then(|v| v.as_str())
+        .unwrap_or("perspective")
+        .trim()
+        .to_ascii_lowercase();
+
+    let position = resolve_camera_vec3_input(
+        scene,
+        nodes_by_id,
+        node,
+        "position",
+        CAMERA_DEFAULT_POSITION,
+    )?;
+    let target = resolve_camera_vec3_input(scene, nodes_by_id, node, "target", [0.0, 0.0, 0.0])?;
+    let up = resolve_camera_vec3_input(scene, nodes_by_id, node, "up", [0.0, 1.0, 0.0])?;
+
+    let aspect = resolve_camera_scalar_input(scene, nodes_by_id, node, "aspect", 0.0)?;
+    let aspect = if aspect > 0.0 { aspect } else { default_aspect };
+
+    let near = resolve_camera_scalar_input(scene, nodes_by_id, node, "near", 0.1)?;
+    let far = resolve_camera_scalar_input(scene, nodes_by_id, node, "far", 1000.0)?;
+    validate_near_far(node.id.as_str(), near, far)?;
+
+    let projection = match projection.as_str() {
+        "perspective" => {
+            let fov_deg = resolve_camera_scalar_input(scene, nodes_by_id, node, "fov", 60.0)?;
+            if !(fov_deg > 0.0 && fov_deg < 180.0) {
+                bail!(
+                    "{}.fov must be > 0 and < 180 degrees, got {}",
+                    node.id,
+                    fov_deg
+                );
+            }
+            perspective_rh_zo_matrix(fov_deg.to_radians(), aspect, near, far)
+        }
+        "orthographic" | "ortho" => {
+            let height = resolve_camera_scalar_input(scene, nodes_by_id, node, "orthoHeight", 2.0)?;
+            if !(height > 0.0) {
+                bail!("{}.orthoHeight must be > 0, got {}", node.id, height);
+            }
+            let half_h = height * 0.5;
+            let half_w = half_h * aspect;
+            orthographic_rh_zo_matrix(-half_w, half_w, -half_h, half_h, near, far)
+        }
+        other => bail!(
+            "{}.projection must be 'perspective' or 'orthographic', got '{}'",
+            node.id,
+            other
+        ),
+    };
+
+    let view = look_at_view_matrix(position, target, up, node.id.as_str())?;
+    Ok(mat4_mul_col_major(projection, view))
+}
+
 fn resolve_perspective_camera_matrix(
     scene: &SceneDSL,
     nodes_by_id: &HashMap<String, Node>,
@@ -367,7 +476,7 @@ fn ensure_finite_vec3(node_id: &str, key: &str, value: [f32; 3]) -> Result<()> {
     }
 }
 
-fn mat4_mul_col_major(a: [f32; 16], b: [f32; 16]) -> [f32; 16] {
+pub(crate) fn mat4_mul_col_major(a: [f32; 16], b: [f32; 16]) -> [f32; 16] {
     let mut out = [0.0f32; 16];
     for col in 0..4 {
         for row in 0..4 {
@@ -550,6 +659,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
@@ -748,4 +858,78 @@ mod tests {
             1e-7
         ));
     }
+
+    #[test]
+    fn camera_node_perspective_follows_pass_aspect() {
+        let scene = scene(
+            vec![
+                node("rp", "RenderPass", json!({})),
+                node(
+                    "cam",
+                    "Camera",
+                    json!({
+                        "projection": "perspective",
+                        "fov": 90.0,
+                        "position": [0.0, 0.0, 5.0],
+                        "target": [0.0, 0.0, 0.0]
+                    }),
+                ),
+            ],
+            vec![conn("cam", "camera", "rp", "camera")],
+        );
+        let nodes_by_id = nodes_by_id(&scene);
+        let pass = nodes_by_id.get("rp").expect("render pass node");
+
+        let in_pass =
+            resolve_effective_camera_for_pass_node(&scene, &nodes_by_id, pass, [400.0, 200.0])
+                .expect("pass camera");
+        assert!((in_pass[0] - 0.5).abs() < 1e-5, "x scale uses 2:1 aspect");
+        assert!((in_pass[5] - 1.0).abs() < 1e-5);
+
+        // Outside a pass there is no target, so the aspect falls back to 1.
+        let standalone = resolve_mat4_output_column_major(&scene, &nodes_by_id, "cam", "camera")
+            .expect("standalone camera");
+        assert!((standalone[0] - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn camera_node_orthographic_maps_ortho_height_to_clip_space() {
+        let scene = scene(
+            vec![node(
+                "cam",
+                "Camera",
+                json!({
+                    "projection": "orthographic",
+                    "orthoHeight": 4.0,
+                    "aspect": 1.5,
+                    "position": [0.0, 0.0, 5.0]
+                }),
+            )],
+            vec![],
+        );
+        let nodes_by_id = nodes_by_id(&scene);
+        let m = resolve_mat4_output_column_major(&scene, &nodes_by_id, "cam", "camera")
+            .expect("ortho camera");
+        let corner = mat4_mul_vec4_col_major(m, [3.0, 2.0, 0.0, 1.0]);
+        assert!((corner[0] - 1.0).abs() < 1e-5);
+        assert!((corner[1] - 1.0).abs() < 1e-5);
+        assert!(corner[2] >= 0.0 && corner[2] <= 1.0);
+    }
+
+    #[test]
+    fn camera_node_rejects_invalid_params() {
+        for params in [
+            json!({"projection": "fisheye"}),
+            json!({"fov": 180.0}),
+            json!({"projection": "orthographic", "orthoHeight": 0.0}),
+            json!({"near": 10.0, "far": 1.0}),
+        ] {
+            let scene = scene(vec![node("cam", "Camera", params.clone())], vec![]);
+            let nodes_by_id = nodes_by_id(&scene);
+            assert!(
+                resolve_mat4_output_column_major(&scene, &nodes_by_id, "cam", "camera").is_err(),
+                "expected error for {params}"
+            );
+        }
+    }
 }
@@ -383,6 +383,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
@@ -5,12 +5,29 @@ use rust_wgpu_fiber::ResourceName;
 pub const DRAW_PASS_NODE_TYPES: &[&str] = &[
     "RenderPass",
     "BloomNode",
+    "BloomPass",
     "GuassianBlurPass",
     "Downsample",
     "Upsample",
     "GradientBlur",
     "IntelligentLight",
     "MeshGradient",
+    "ComputePass",
+    "FeedbackPass",
+    "DepthOfFieldPass",
+    "ConvolutionPass",
+    "EdgeDetect",
+    "ChromaticAberration",
+    "Vignette",
+    "FilmGrain",
+    "LensDistortion",
+    "LUTPass",
+    "Pixelate",
+    "Posterize",
+    "Dither",
+    "DropShadow",
+    "Displace",
+    "AntiAliasPass",
 ];
 
 pub fn is_draw_pass_node_type(node_type: &str) -> bool {
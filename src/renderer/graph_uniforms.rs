@@ -722,6 +722,18 @@ fn collect_scalar_input_dependencies_for_output_port(
                 );
             }
         }
+        "Math" if out_port == "result" => {
+            for input_port in ["a", "b", "c"] {
+                collect_scalar_input_dependencies_for_input_port(
+                    scene,
+                    nodes_by_id,
+                    node_id,
+                    input_port,
+                    ignored_input_value_node_ids,
+                    visiting_outputs,
+                );
+            }
+        }
         "MathPower" if out_port == "result" => {
             for input_port in ["base", "exponent"] {
                 collect_scalar_input_dependencies_for_input_port(
@@ -1170,6 +1182,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
@@ -1211,6 +1224,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let schema = GraphSchema {
@@ -1261,6 +1275,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let schema = GraphSchema {
@@ -1310,6 +1325,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let schema = GraphSchema {
             fields: vec![
@@ -1398,6 +1414,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let schema = GraphSchema {
@@ -1453,6 +1470,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let pass = PassBindings {
@@ -1471,6 +1489,9 @@ mod tests {
                     1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
                 ],
                 camera_position: [0.0, 0.0, 0.0, 0.0],
+                delta_time: 0.0,
+                frame: 0,
+                _pad1: [0.0; 2],
             },
             graph_binding: Some(GraphBinding {
                 buffer_name: ResourceName::from("params.passA.graph"),
@@ -1560,6 +1581,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let pass = PassBindings {
@@ -1578,6 +1600,9 @@ mod tests {
                     1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
                 ],
                 camera_position: [0.0, 0.0, 0.0, 0.0],
+                delta_time: 0.0,
+                frame: 0,
+                _pad1: [0.0; 2],
             },
             graph_binding: Some(GraphBinding {
                 buffer_name: ResourceName::from("params.passA.graph"),
@@ -1640,6 +1665,9 @@ mod tests {
                     1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
                 ],
                 camera_position: [0.0, 0.0, 0.0, 0.0],
+                delta_time: 0.0,
+                frame: 0,
+                _pad1: [0.0; 2],
             },
             graph_binding: Some(GraphBinding {
                 buffer_name: ResourceName::from("params.passA.graph"),
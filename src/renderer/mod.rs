@@ -6,6 +6,7 @@
 //! - `node_compiler`: Node compilation infrastructure
 //! - `validation`: WGSL validation using naga
 //! - `scene_prep`: Scene preparation and validation
+//! - `scene_validation`: Device-free validation reports for `--validate`
 //! - `render_plan`: Render planning utilities and pass graph helpers
 //! - `geometry_resolver`: Canonical draw-context geometry/coord inference
 //! - `wgsl`: WGSL shader generation
@@ -24,6 +25,7 @@ pub mod node_compiler;
 pub mod pass_debug;
 pub mod render_plan;
 pub mod scene_prep;
+pub mod scene_validation;
 pub mod shader_space;
 pub mod types;
 pub mod utils;
@@ -39,15 +41,28 @@ pub use pass_debug::{
     PassDebugAstNode, PassDebugDependencyNode, PassDebugDependencyTarget, PassDebugSource,
     PassDebugSourceRange,
 };
+pub use render_plan::estimate::{
+    DeviceProfile, PassEstimate, SceneCostEstimate, TextureEstimate, estimate_scene_cost,
+};
+pub use render_plan::inspect::{PassInspection, SceneInspection, inspect_scene};
 pub use scene_prep::{PreparedScene, prepare_scene};
+pub use scene_validation::{
+    DiagnosticSeverity, DiagnosticStage, SceneDiagnostic, SceneValidationReport, validate_scene,
+};
 pub use shader_space::{
-    ShaderSpaceBuildOptions, ShaderSpaceBuildResult, ShaderSpaceBuilder,
-    ShaderSpacePresentationMode, render_scene_to_file_headless,
-    render_scene_to_file_headless_profiled, render_scene_to_png_headless, update_pass_params,
+    GpuFrameTiming, HeadlessSession, NonFiniteSanitizeMode, NonFiniteSanitizeOptions, OutputCrop,
+    PICK_TEXTURE_NAME, PassDebugOverlay, PassGpuTiming, PickTarget, RenderProgress,
+    RenderProgressStage, ShaderSpaceBuildOptions, ShaderSpaceBuildResult, ShaderSpaceBuilder,
+    ShaderSpacePresentationMode, VideoEncodeOptions, deterministic_renderer_config,
+    is_video_output_path, pass_debug_color, pick_id_at, render_scene_sequence_headless,
+    render_scene_to_file_headless, render_scene_to_file_headless_profiled,
+    render_scene_to_png_headless, render_scene_video_headless, render_with_gpu_timestamps,
+    take_non_finite_counts, update_pass_params,
 };
-pub use types::{Params, PassBindings, WgslShaderBundle};
-pub use validation::{validate_wgsl, validate_wgsl_with_context};
+pub use types::{FrameTiming, Params, PassBindings, WgslShaderBundle};
+pub use validation::{validate_wgsl, validate_wgsl_module, validate_wgsl_with_context};
+pub use wgsl_templates::ChannelDebugMode;
 pub use wgsl::{
     build_all_pass_wgsl_bundles_from_scene, build_all_pass_wgsl_bundles_from_scene_with_assets,
-    build_pass_wgsl_bundle,
+    build_pass_wgsl_bundle, dump_pass_wgsl_bundles,
 };
@@ -1,38 +1,49 @@
-//! Compiler for Attribute node (reads vertex attributes).
+//! Compiler for Attribute node (reads vertex and per-instance attributes).
 
 use anyhow::{Result, bail};
 
-use super::super::types::{TypedExpr, ValueType};
+use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
 use crate::dsl::Node;
+use crate::renderer::validation::GlslShaderStage;
 
 /// Compile an Attribute node to WGSL.
 ///
-/// Attribute nodes read vertex attributes by name.
+/// Attribute nodes read vertex attributes by name. Names that are not built-in vertex
+/// attributes resolve against the pass's `instanceAttributes`, which map a name to a baked
+/// DataParse output stored as one vec4 slot per instance.
 ///
 /// # Parameters
-/// - `name`: Attribute name (e.g., "uv", "position", "normal")
-/// - `glslType`: Expected GLSL type (used for validation, not code gen)
+/// - `name`: Attribute name (e.g., "uv", "normal", or a declared instance attribute)
+/// - `glslType`: Expected GLSL type. Ignored for built-ins; narrows the vec4 slot of an
+///   instance attribute (`float`, `vec2`, `vec3`, `vec4`; default `vec4`)
 ///
 /// # Output
-/// - Type: Depends on attribute (uv = vec2f, position/normal = vec3f)
+/// - Type: Depends on attribute (uv = vec2f, normal = vec3f, instance = `glslType`)
 /// - Uses time: false
 ///
 /// # Supported Attributes
 /// - `uv`: Texture coordinates (vec2f) - user-facing bottom-left semantics
 /// - `normal`: Vertex normal (vec3f) - passed through from vertex shader
+/// - any name declared in the pass's `instanceAttributes`
 ///
 /// # Example
 /// ```wgsl
-/// vec2f(in.uv.x, 1.0 - in.uv.y)  // For "uv" attribute
-/// in.normal                         // For "normal" attribute
+/// vec2f(in.uv.x, 1.0 - in.uv.y)             // For "uv" attribute
+/// in.normal                                  // For "normal" attribute
+/// baked_data_parse[(instance_index) * 2u + 1u] // For an instance attribute (vertex stage)
 /// ```
-pub fn compile_attribute(node: &Node, _out_port: Option<&str>) -> Result<TypedExpr> {
-    let name = node
+pub fn compile_attribute(
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    stage: GlslShaderStage,
+) -> Result<TypedExpr> {
+    let raw_name = node
         .params
         .get("name")
         .and_then(|v| v.as_str())
-        .unwrap_or("uv")
-        .to_ascii_lowercase();
+        .unwrap_or("uv");
+    let name = raw_name.to_ascii_lowercase();
 
     match name.as_str() {
         // Common aliases from GLSL graphs (e.g. vUv)
@@ -41,17 +52,112 @@ pub fn compile_attribute(node: &Node, _out_port: Option<&str>) -> Result<TypedEx
             ValueType::Vec2,
         )),
         "normal" => Ok(TypedExpr::new("in.normal".to_string(), ValueType::Vec3)),
-        other => bail!(
-            "unsupported Attribute.name: {} (supported: 'uv', 'normal')",
+        _ => compile_instance_attribute(node, raw_name, ctx, stage),
+    }
+}
+
+/// Whether `node` reads a per-instance attribute from the pass's baked DataParse
+/// buffer, i.e. its name is not a built-in vertex attribute.
+pub(crate) fn reads_instance_attribute(node: &Node) -> bool {
+    let name = node
+        .params
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("uv")
+        .to_ascii_lowercase();
+    !matches!(name.as_str(), "uv" | "vuv" | "v_uv" | "normal")
+}
+
+fn compile_instance_attribute(
+    node: &Node,
+    name: &str,
+    ctx: &mut MaterialCompileContext,
+    stage: GlslShaderStage,
+) -> Result<TypedExpr> {
+    let Some((stride, slot)) = ctx.baked_data_parse_meta.as_ref().and_then(|meta| {
+        meta.slot_for_attribute(name)
+            .map(|slot| (meta.outputs_per_instance, slot))
+    }) else {
+        bail!(
+            "unsupported Attribute.name: {} (supported: 'uv', 'normal', or a name declared in the pass's instanceAttributes)",
+            name
+        );
+    };
+
+    let (swizzle, ty) = match node
+        .params
+        .get("glslType")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("float" | "f32") => (".x", ValueType::F32),
+        Some("vec2" | "vec2f") => (".xy", ValueType::Vec2),
+        Some("vec3" | "vec3f") => (".xyz", ValueType::Vec3),
+        None | Some("vec4" | "vec4f") => ("", ValueType::Vec4),
+        Some(other) => bail!(
+            "Attribute '{}': unsupported glslType for instance attribute: {} (expected float, vec2, vec3 or vec4)",
+            name,
             other
         ),
-    }
+    };
+
+    ctx.uses_instance_index = true;
+
+    let ix = match stage {
+        GlslShaderStage::Vertex => "instance_index",
+        GlslShaderStage::Fragment => "in.instance_index",
+        GlslShaderStage::Compute => "0u",
+    };
+
+    Ok(TypedExpr::new(
+        format!("baked_data_parse[({ix}) * {stride}u + {slot}u]{swizzle}"),
+        ty,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::renderer::types::BakedDataParseMeta;
     use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn compile(node: &Node) -> Result<TypedExpr> {
+        let mut ctx = MaterialCompileContext::default();
+        compile_attribute(node, None, &mut ctx, GlslShaderStage::Fragment)
+    }
+
+    fn instance_attribute_node(name: &str, glsl_type: Option<&str>) -> Node {
+        let mut params = HashMap::from([("name".to_string(), serde_json::json!(name))]);
+        if let Some(t) = glsl_type {
+            params.insert("glslType".to_string(), serde_json::json!(t));
+        }
+        Node {
+            id: "attr1".to_string(),
+            node_type: "Attribute".to_string(),
+            params,
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn ctx_with_instance_attributes() -> MaterialCompileContext {
+        MaterialCompileContext {
+            baked_data_parse_meta: Some(Arc::new(BakedDataParseMeta {
+                pass_id: "pass".to_string(),
+                outputs_per_instance: 3,
+                slot_by_output: HashMap::new(),
+                attribute_slots: HashMap::from([
+                    ("tint".to_string(), 0),
+                    ("offset".to_string(), 2),
+                ]),
+            })),
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn test_attribute_uv() {
@@ -65,7 +171,7 @@ mod tests {
             wgsl_override: None,
         };
 
-        let result = compile_attribute(&node, None).unwrap();
+        let result = compile(&node).unwrap();
         assert_eq!(result.ty, ValueType::Vec2);
         assert_eq!(result.expr, "vec2f(in.uv.x, 1.0 - in.uv.y)");
         assert!(!result.uses_time);
@@ -84,7 +190,7 @@ mod tests {
             wgsl_override: None,
         };
 
-        let result = compile_attribute(&node, None).unwrap();
+        let result = compile(&node).unwrap();
         assert_eq!(result.ty, ValueType::Vec2);
         assert_eq!(result.expr, "vec2f(in.uv.x, 1.0 - in.uv.y)");
     }
@@ -101,7 +207,7 @@ mod tests {
             wgsl_override: None,
         };
 
-        let result = compile_attribute(&node, None).unwrap();
+        let result = compile(&node).unwrap();
         assert_eq!(result.ty, ValueType::Vec2);
         assert_eq!(result.expr, "vec2f(in.uv.x, 1.0 - in.uv.y)");
     }
@@ -118,7 +224,7 @@ mod tests {
             wgsl_override: None,
         };
 
-        let result = compile_attribute(&node, None).unwrap();
+        let result = compile(&node).unwrap();
         assert!(result.expr.contains("1.0 - in.uv.y"));
     }
 
@@ -134,7 +240,7 @@ mod tests {
             wgsl_override: None,
         };
 
-        let result = compile_attribute(&node, None).unwrap();
+        let result = compile(&node).unwrap();
         assert_eq!(result.ty, ValueType::Vec3);
         assert_eq!(result.expr, "in.normal");
         assert!(!result.uses_time);
@@ -152,6 +258,34 @@ mod tests {
             wgsl_override: None,
         };
 
-        assert!(compile_attribute(&node, None).is_err());
+        assert!(compile(&node).is_err());
+    }
+
+    #[test]
+    fn test_attribute_instance_attribute_reads_named_slot_per_stage() {
+        let mut ctx = ctx_with_instance_attributes();
+        let node = instance_attribute_node("tint", None);
+        let frag = compile_attribute(&node, None, &mut ctx, GlslShaderStage::Fragment).unwrap();
+        assert_eq!(frag.ty, ValueType::Vec4);
+        assert_eq!(frag.expr, "baked_data_parse[(in.instance_index) * 3u + 0u]");
+        assert!(ctx.uses_instance_index);
+
+        let node = instance_attribute_node("offset", Some("vec2"));
+        let vert = compile_attribute(&node, None, &mut ctx, GlslShaderStage::Vertex).unwrap();
+        assert_eq!(vert.ty, ValueType::Vec2);
+        assert_eq!(vert.expr, "baked_data_parse[(instance_index) * 3u + 2u].xy");
+    }
+
+    #[test]
+    fn test_attribute_instance_attribute_errors() {
+        let mut ctx = ctx_with_instance_attributes();
+        let undeclared = instance_attribute_node("missing", None);
+        assert!(compile_attribute(&undeclared, None, &mut ctx, GlslShaderStage::Fragment).is_err());
+
+        let bad_type = instance_attribute_node("tint", Some("mat4"));
+        assert!(compile_attribute(&bad_type, None, &mut ctx, GlslShaderStage::Fragment).is_err());
+
+        // Without a pass-level meta there are no instance attributes to resolve.
+        assert!(compile(&instance_attribute_node("tint", None)).is_err());
     }
 }
@@ -0,0 +1,383 @@
+//! ColorCurves color node.
+//!
+//! Per-channel tone curves, the usual grading primitive: a `master` curve is
+//! applied to R, G and B first, then the `red`/`green`/`blue` curves to their
+//! own channel. Alpha passes through.
+//!
+//! Each curve is a list of control points in params, either `[[x, y], ...]` or
+//! `[{ "x": .., "y": .. }, ...]`. Tangents are computed here with the
+//! Fritsch–Carlson monotone scheme and baked into a generated per-node WGSL
+//! helper as piecewise cubic Hermite segments, so the shader does no search or
+//! texture fetch. Outside the first/last point curves extrapolate linearly
+//! along the end tangent, which keeps HDR values above 1 graded.
+//!
+//! A curve with fewer than two points, or absent, is the identity.
+
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+
+use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
+use super::super::utils::{coerce_to_type, fmt_f32, to_vec4_color};
+use crate::dsl::{Node, SceneDSL, incoming_connection, parse_f32};
+
+const HERMITE_LIB_KEY: &str = "color_curves_hermite_lib";
+const HERMITE_WGSL: &str = r#"
+fn cc_hermite(t: f32, x0: f32, x1: f32, y0: f32, y1: f32, m0: f32, m1: f32) -> f32 {
+    let h = x1 - x0;
+    let s = (t - x0) / h;
+    let s2 = s * s;
+    let s3 = s2 * s;
+    return (2.0 * s3 - 3.0 * s2 + 1.0) * y0
+        + (s3 - 2.0 * s2 + s) * h * m0
+        + (-2.0 * s3 + 3.0 * s2) * y1
+        + (s3 - s2) * h * m1;
+}
+"#;
+
+const CHANNELS: [&str; 4] = ["master", "red", "green", "blue"];
+
+/// Monotone cubic interpolant through sorted control points.
+#[derive(Clone, Debug, PartialEq)]
+struct MonotoneCurve {
+    xs: Vec<f32>,
+    ys: Vec<f32>,
+    tangents: Vec<f32>,
+}
+
+impl MonotoneCurve {
+    /// Returns `None` for identity curves (fewer than two distinct points).
+    fn from_points(mut points: Vec<[f32; 2]>) -> Option<Self> {
+        points.retain(|[x, y]| x.is_finite() && y.is_finite());
+        points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        // Duplicate x values would produce zero-width segments; the last wins.
+        let mut deduped: Vec<[f32; 2]> = Vec::with_capacity(points.len());
+        for p in points {
+            match deduped.last_mut() {
+                Some(last) if last[0] == p[0] => *last = p,
+                _ => deduped.push(p),
+            }
+        }
+        if deduped.len() < 2 {
+            return None;
+        }
+
+        let xs: Vec<f32> = deduped.iter().map(|p| p[0]).collect();
+        let ys: Vec<f32> = deduped.iter().map(|p| p[1]).collect();
+        let n = xs.len();
+        let slopes: Vec<f32> = (0..n - 1)
+            .map(|k| (ys[k + 1] - ys[k]) / (xs[k + 1] - xs[k]))
+            .collect();
+
+        let mut tangents = vec![0.0; n];
+        tangents[0] = slopes[0];
+        tangents[n - 1] = slopes[n - 2];
+        for k in 1..n - 1 {
+            tangents[k] = if slopes[k - 1] * slopes[k] <= 0.0 {
+                0.0
+            } else {
+                (slopes[k - 1] + slopes[k]) * 0.5
+            };
+        }
+        for k in 0..n - 1 {
+            if slopes[k] == 0.0 {
+                tangents[k] = 0.0;
+                tangents[k + 1] = 0.0;
+                continue;
+            }
+            let a = tangents[k] / slopes[k];
+            let b = tangents[k + 1] / slopes[k];
+            let s = a * a + b * b;
+            if s > 9.0 {
+                let tau = 3.0 / s.sqrt();
+                tangents[k] = tau * a * slopes[k];
+                tangents[k + 1] = tau * b * slopes[k];
+            }
+        }
+
+        Some(Self { xs, ys, tangents })
+    }
+
+    fn is_identity(&self) -> bool {
+        self.xs.iter().zip(&self.ys).all(|(x, y)| x == y) && self.tangents.iter().all(|m| *m == 1.0)
+    }
+
+    fn wgsl_fn(&self, fn_name: &str) -> String {
+        let n = self.xs.len();
+        let f = fmt_f32;
+        let segment = |k: usize| {
+            format!(
+                "cc_hermite(t, {}, {}, {}, {}, {}, {})",
+                f(self.xs[k]),
+                f(self.xs[k + 1]),
+                f(self.ys[k]),
+                f(self.ys[k + 1]),
+                f(self.tangents[k]),
+                f(self.tangents[k + 1])
+            )
+        };
+
+        let mut body = String::new();
+        body.push_str(&format!(
+            "    if (t <= {}) {{\n        return {} + {} * (t - {});\n    }}\n",
+            f(self.xs[0]),
+            f(self.ys[0]),
+            f(self.tangents[0]),
+            f(self.xs[0])
+        ));
+        for k in 0..n - 1 {
+            body.push_str(&format!(
+                "    if (t <= {}) {{\n        return {};\n    }}\n",
+                f(self.xs[k + 1]),
+                segment(k)
+            ));
+        }
+        body.push_str(&format!(
+            "    return {} + {} * (t - {});\n",
+            f(self.ys[n - 1]),
+            f(self.tangents[n - 1]),
+            f(self.xs[n - 1])
+        ));
+        format!("fn {fn_name}(t: f32) -> f32 {{\n{body}}}\n")
+    }
+
+    #[cfg(test)]
+    fn eval(&self, t: f32) -> f32 {
+        let n = self.xs.len();
+        if t <= self.xs[0] {
+            return self.ys[0] + self.tangents[0] * (t - self.xs[0]);
+        }
+        for k in 0..n - 1 {
+            if t <= self.xs[k + 1] {
+                let h = self.xs[k + 1] - self.xs[k];
+                let s = (t - self.xs[k]) / h;
+                let (s2, s3) = (s * s, s * s * s);
+                return (2.0 * s3 - 3.0 * s2 + 1.0) * self.ys[k]
+                    + (s3 - 2.0 * s2 + s) * h * self.tangents[k]
+                    + (-2.0 * s3 + 3.0 * s2) * self.ys[k + 1]
+                    + (s3 - s2) * h * self.tangents[k + 1];
+            }
+        }
+        self.ys[n - 1] + self.tangents[n - 1] * (t - self.xs[n - 1])
+    }
+}
+
+fn parse_points(value: Option<&serde_json::Value>) -> Vec<[f32; 2]> {
+    let Some(items) = value.and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            if let Some(pair) = item.as_array() {
+                let x = pair.first()?.as_f64()?;
+                let y = pair.get(1)?.as_f64()?;
+                return Some([x as f32, y as f32]);
+            }
+            let obj = item.as_object()?;
+            let x = obj.get("x")?.as_f64()?;
+            let y = obj.get("y")?.as_f64()?;
+            Some([x as f32, y as f32])
+        })
+        .collect()
+}
+
+/// Sanitize a node ID into a WGSL identifier suffix.
+fn sanitize_id_suffix(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Builds the per-node helper `cc_apply_<id>(color, factor)`. Identity
+/// channels are skipped entirely.
+fn build_apply_fn(node: &Node) -> (String, String) {
+    let suffix = sanitize_id_suffix(&node.id);
+    let apply_fn = format!("cc_apply_{suffix}");
+
+    let mut decls = String::new();
+    let mut curve_fns: HashMap<&str, String> = HashMap::new();
+    for channel in CHANNELS {
+        let Some(curve) = MonotoneCurve::from_points(parse_points(node.params.get(channel))) else {
+            continue;
+        };
+        if curve.is_identity() {
+            continue;
+        }
+        let fn_name = format!("cc_{suffix}_{channel}");
+        decls.push_str(&curve.wgsl_fn(&fn_name));
+        curve_fns.insert(channel, fn_name);
+    }
+
+    let apply = |channel: &str, arg: &str| match curve_fns.get(channel) {
+        Some(fn_name) => format!("{fn_name}({arg})"),
+        None => arg.to_string(),
+    };
+    let master = format!(
+        "vec3f({}, {}, {})",
+        apply("master", "color.r"),
+        apply("master", "color.g"),
+        apply("master", "color.b")
+    );
+    let graded = format!(
+        "vec3f({}, {}, {})",
+        apply("red", "m.r"),
+        apply("green", "m.g"),
+        apply("blue", "m.b")
+    );
+    decls.push_str(&format!(
+        "fn {apply_fn}(color: vec4f, factor: f32) -> vec4f {{\n    let m = {master};\n    let graded = {graded};\n    return vec4f(mix(color.rgb, graded, factor), color.a);\n}}\n"
+    ));
+
+    (
+        apply_fn,
+        format!(
+            "\n// ---- ColorCurves helper for {} (generated) ----\n{decls}",
+            node.id
+        ),
+    )
+}
+
+pub fn compile_color_curves<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let color_conn = incoming_connection(scene, &node.id, "color")
+        .ok_or_else(|| anyhow!("ColorCurves missing input color"))?;
+    let color = compile_fn(
+        &color_conn.from.node_id,
+        Some(&color_conn.from.port_id),
+        ctx,
+        cache,
+    )?;
+    let color = to_vec4_color(color);
+
+    let factor = if let Some(conn) = incoming_connection(scene, &node.id, "factor") {
+        let expr = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        coerce_to_type(expr, ValueType::F32)?
+    } else {
+        let v = parse_f32(&node.params, "factor").unwrap_or(1.0);
+        TypedExpr::new(fmt_f32(v), ValueType::F32)
+    };
+
+    ctx.extra_wgsl_decls
+        .entry(HERMITE_LIB_KEY.to_string())
+        .or_insert_with(|| HERMITE_WGSL.to_string());
+    let (apply_fn, decl) = build_apply_fn(node);
+    ctx.extra_wgsl_decls.insert(
+        format!("color_curves::{}", sanitize_id_suffix(&node.id)),
+        decl,
+    );
+
+    Ok(TypedExpr::with_time(
+        format!("{apply_fn}({}, {})", color.expr, factor.expr),
+        ValueType::Vec4,
+        color.uses_time || factor.uses_time,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::node_compiler::test_utils::{test_connection, test_scene};
+
+    fn mock_color_compile_fn(
+        _node_id: &str,
+        _out_port: Option<&str>,
+        _ctx: &mut MaterialCompileContext,
+        _cache: &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr> {
+        Ok(TypedExpr::new(
+            "vec4f(0.25, 0.5, 0.75, 1.0)".to_string(),
+            ValueType::Vec4,
+        ))
+    }
+
+    #[test]
+    fn monotone_curve_interpolates_points_without_overshoot() {
+        let curve =
+            MonotoneCurve::from_points(vec![[0.0, 0.0], [0.5, 0.9], [0.6, 0.95], [1.0, 1.0]])
+                .unwrap();
+        for (x, y) in curve.xs.iter().zip(&curve.ys) {
+            assert!((curve.eval(*x) - y).abs() < 1e-5);
+        }
+        let mut prev = curve.eval(0.0);
+        for i in 1..=100 {
+            let v = curve.eval(i as f32 / 100.0);
+            assert!(v >= prev - 1e-6, "curve must stay monotone");
+            assert!(v <= 1.0 + 1e-6);
+            prev = v;
+        }
+    }
+
+    #[test]
+    fn identity_and_degenerate_curves_are_skipped() {
+        assert!(MonotoneCurve::from_points(vec![[0.5, 0.5]]).is_none());
+        let identity = MonotoneCurve::from_points(vec![[0.0, 0.0], [1.0, 1.0]]).unwrap();
+        assert!(identity.is_identity());
+    }
+
+    #[test]
+    fn compiles_to_valid_wgsl() {
+        let scene = test_scene(
+            Vec::new(),
+            vec![test_connection("color", "value", "curves", "color")],
+        );
+        let node = Node {
+            id: "curves".to_string(),
+            node_type: "ColorCurves".to_string(),
+            params: HashMap::from([
+                (
+                    "master".to_string(),
+                    serde_json::json!([[0.0, 0.05], [0.5, 0.55], [1.0, 1.0]]),
+                ),
+                (
+                    "red".to_string(),
+                    serde_json::json!([{ "x": 0.0, "y": 0.0 }, { "x": 1.0, "y": 0.8 }]),
+                ),
+            ]),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+
+        let result = compile_color_curves(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut ctx,
+            &mut cache,
+            mock_color_compile_fn,
+        )
+        .unwrap();
+
+        assert_eq!(result.ty, ValueType::Vec4);
+        assert!(result.expr.starts_with("cc_apply_curves("));
+        let decls: String = ctx.extra_wgsl_decls.values().cloned().collect();
+        assert!(decls.contains("fn cc_curves_master("));
+        assert!(decls.contains("fn cc_curves_red("));
+        assert!(!decls.contains("fn cc_curves_green("));
+
+        let module = format!(
+            "{decls}\n@fragment\nfn fs_main() -> @location(0) vec4f {{\n    return {};\n}}\n",
+            result.expr
+        );
+        crate::renderer::validate_wgsl(&module).unwrap();
+    }
+}
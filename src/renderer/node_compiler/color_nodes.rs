@@ -1,11 +1,12 @@
-//! Compilers for color manipulation nodes (ColorMix/Blend Color, ColorRamp, HSVAdjust, Luminance).
+//! Compilers for color manipulation nodes (ColorMix/Blend Color, ColorRamp, HSVAdjust, Luminance,
+//! ToneMap, Exposure, Gamma, BrightContrast).
 
 use anyhow::{Result, anyhow, bail};
 use serde_json::Value;
 use std::collections::HashMap;
 
 use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
-use super::super::utils::{fmt_f32, to_vec4_color};
+use super::super::utils::{coerce_to_type, fmt_f32, sanitize_wgsl_ident, to_vec4_color};
 use crate::dsl::{Node, SceneDSL, incoming_connection};
 
 fn parse_json_number_f32(v: &Value) -> Option<f32> {
@@ -65,6 +66,26 @@ fn vec4_const_premul(rgba_straight: [f32; 4]) -> TypedExpr {
     )
 }
 
+/// RGB weights for the Luminance `standard` param (default Rec.709).
+fn luminance_weights(node: &Node) -> Result<&'static str> {
+    let standard = node
+        .params
+        .get("standard")
+        .and_then(|v| v.as_str())
+        .unwrap_or("rec709");
+    Ok(match standard {
+        "rec709" => "vec3f(0.2126, 0.7152, 0.0722)",
+        "rec601" => "vec3f(0.299, 0.587, 0.114)",
+        "rec2020" => "vec3f(0.2627, 0.678, 0.0593)",
+        "average" => "vec3f(0.33333334)",
+        other => bail!("Luminance {}: unsupported standard '{other}'", node.id),
+    })
+}
+
+/// Compile a Luminance node.
+///
+/// Weighted sum of the input RGB using the `standard` param: `rec709`
+/// (default), `rec601`, `rec2020` or `average`. The result is not clamped.
 pub fn compile_luminance<F>(
     scene: &SceneDSL,
     _nodes_by_id: &HashMap<String, Node>,
@@ -94,10 +115,9 @@ where
     )?;
 
     let color_vec4 = to_vec4_color(color);
-    let luma_expr = format!(
-        "clamp(dot(({}).rgb, vec3f(0.2126, 0.7152, 0.0722)), 0.0, 1.0)",
-        color_vec4.expr
-    );
+    let weights = luminance_weights(node)?;
+    // No clamp: HDR inputs keep luminance above 1 for float targets.
+    let luma_expr = format!("dot(({}).rgb, {weights})", color_vec4.expr);
 
     Ok(TypedExpr::with_time(
         luma_expr,
@@ -329,10 +349,46 @@ fn blendLuminance(src: vec4f, dst: vec4f) -> vec4f {
         .insert(COLORMIX_WGSL_LIB_KEY.to_string(), wgsl.to_string());
 }
 
+const COLORMIX_ADDITIVE_WGSL_LIB_KEY: &str = "colormix_additive_blend_lib";
+
+/// Additive compositing modes, emitted only when a ColorMix uses one of them.
+fn ensure_colormix_additive_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx
+        .extra_wgsl_decls
+        .contains_key(COLORMIX_ADDITIVE_WGSL_LIB_KEY)
+    {
+        return;
+    }
+
+    let wgsl = r#"
+// ---- ColorMix (Blend Color) additive helpers (generated) ----
+
+// Additive modes below are clamp-free above 1 so HDR inputs survive; only
+// negative results are floored at 0.
+fn blendAdd(src: vec4f, dst: vec4f) -> vec4f {
+    return vec4f(src.rgb + dst.rgb, src.a + dst.a * (1.0 - src.a));
+}
+
+fn blendSubtract(src: vec4f, dst: vec4f) -> vec4f {
+    let c = src.rgb * (1.0 - dst.a) + dst.rgb * (1.0 - src.a) + max(dst.rgb * src.a - src.rgb * dst.a, vec3f(0.0));
+    return vec4f(c, src.a + dst.a * (1.0 - src.a));
+}
+
+fn blendLinearBurn(src: vec4f, dst: vec4f) -> vec4f {
+    let c = src.rgb * (1.0 - dst.a) + dst.rgb * (1.0 - src.a) + max(src.rgb * dst.a + dst.rgb * src.a - src.a * dst.a, vec3f(0.0));
+    return vec4f(c, src.a + dst.a * (1.0 - src.a));
+}
+"#;
+
+    ctx.extra_wgsl_decls
+        .insert(COLORMIX_ADDITIVE_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
 /// Compile a ColorMix node.
 ///
 /// NOTE: ColorMix has been repurposed to the editor's "Blend Color" node.
-/// It folds N colors left-to-right using a Figma blend mode.
+/// It folds N colors left-to-right using a Figma blend mode, plus the clamp-free
+/// `add`, `subtract` and `linear-burn` compositing modes.
 pub fn compile_color_mix<F>(
     scene: &SceneDSL,
     _nodes_by_id: &HashMap<String, Node>,
@@ -352,13 +408,17 @@ where
 {
     ensure_colormix_wgsl_lib(ctx);
 
+    // Accept editor spellings such as "soft light" / "linear_burn".
     let mode = node
         .params
         .get("mode")
         .and_then(|v| v.as_str())
-        .unwrap_or("normal");
+        .unwrap_or("normal")
+        .trim()
+        .to_ascii_lowercase()
+        .replace([' ', '_'], "-");
 
-    let blend_fn = match mode {
+    let blend_fn = match mode.as_str() {
         "normal" => "blendNormal",
         "darken" => "blendDarken",
         "multiply" => "blendMultiply",
@@ -377,8 +437,14 @@ where
         "saturation" => "blendSaturation",
         "color" => "blendColor",
         "luminosity" => "blendLuminance",
+        "add" | "linear-dodge" => "blendAdd",
+        "subtract" => "blendSubtract",
+        "linear-burn" => "blendLinearBurn",
         _ => "blendNormal",
     };
+    if matches!(blend_fn, "blendAdd" | "blendSubtract" | "blendLinearBurn") {
+        ensure_colormix_additive_wgsl_lib(ctx);
+    }
 
     let mut port_ids: Vec<String> = vec!["color0".to_string(), "color1".to_string()];
     port_ids.extend(node.inputs.iter().map(|p| p.id.clone()));
@@ -432,14 +498,156 @@ where
     Ok(acc)
 }
 
+const COLOR_RAMP_WGSL_LIB_KEY: &str = "color_ramp_lib";
+
+fn ensure_color_ramp_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx.extra_wgsl_decls.contains_key(COLOR_RAMP_WGSL_LIB_KEY) {
+        return;
+    }
+
+    let wgsl = r#"
+// ---- ColorRamp helpers (generated) ----
+
+// Uniform cubic B-spline through four neighbouring stops; approximates rather
+// than interpolates, like Blender's B-Spline ramp.
+fn colorRampBSpline(s: f32, p0: vec4f, p1: vec4f, p2: vec4f, p3: vec4f) -> vec4f {
+    let s2 = s * s;
+    let s3 = s2 * s;
+    let b0 = (1.0 - s) * (1.0 - s) * (1.0 - s);
+    let b1 = 3.0 * s3 - 6.0 * s2 + 4.0;
+    let b2 = -3.0 * s3 + 3.0 * s2 + 3.0 * s + 1.0;
+    return (p0 * b0 + p1 * b1 + p2 * b2 + p3 * s3) / 6.0;
+}
+"#;
+
+    ctx.extra_wgsl_decls
+        .insert(COLOR_RAMP_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RampInterpolation {
+    Linear,
+    Constant,
+    Ease,
+    BSpline,
+}
+
+impl RampInterpolation {
+    fn from_node(node: &Node) -> Result<Self> {
+        let mode = node
+            .params
+            .get("interpolation")
+            .and_then(|v| v.as_str())
+            .unwrap_or("linear");
+        Ok(match mode {
+            "linear" => Self::Linear,
+            "constant" => Self::Constant,
+            "ease" => Self::Ease,
+            "b-spline" | "bspline" | "b_spline" => Self::BSpline,
+            other => bail!("ColorRamp {}: unsupported interpolation '{other}'", node.id),
+        })
+    }
+}
+
+/// Sorted `(position, straight rgba)` stops; black-to-white when absent.
+fn parse_ramp_stops(node: &Node) -> Vec<(f32, [f32; 4])> {
+    let mut stops: Vec<(f32, [f32; 4])> = node
+        .params
+        .get("stops")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|stop| {
+                    let position = stop.get("position").and_then(parse_json_number_f32)?;
+                    let color = stop.get("color").and_then(parse_vec4_like)?;
+                    position.is_finite().then_some((position, color))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if stops.is_empty() {
+        stops = vec![(0.0, [0.0, 0.0, 0.0, 1.0]), (1.0, [1.0, 1.0, 1.0, 1.0])];
+    }
+    // Stable sort keeps coincident stops in editor order (hard edges).
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    stops
+}
+
+fn vec4_literal(c: [f32; 4]) -> String {
+    format!(
+        "vec4f({}, {}, {}, {})",
+        fmt_f32(c[0]),
+        fmt_f32(c[1]),
+        fmt_f32(c[2]),
+        fmt_f32(c[3])
+    )
+}
+
+/// Builds the per-node helper `color_ramp_<id>(t) -> vec4f` returning a
+/// premultiplied color. Stops are interpolated in straight alpha.
+fn build_color_ramp_fn(node: &Node, interpolation: RampInterpolation) -> (String, String) {
+    let fn_name = format!("color_ramp_{}", sanitize_wgsl_ident(&node.id));
+    let stops = parse_ramp_stops(node);
+    let colors: Vec<String> = stops.iter().map(|(_, c)| vec4_literal(*c)).collect();
+    let last = stops.len() - 1;
+
+    let mut body = format!("    var c = {};\n", colors[0]);
+    for i in 0..stops.len() {
+        let x0 = fmt_f32(stops[i].0);
+        if interpolation == RampInterpolation::Constant || i == last {
+            body.push_str(&format!("    if (t >= {x0}) {{ c = {}; }}\n", colors[i]));
+            continue;
+        }
+        let width = stops[i + 1].0 - stops[i].0;
+        if width <= 0.0 {
+            continue;
+        }
+        let s = format!("clamp((t - {x0}) * {}, 0.0, 1.0)", fmt_f32(1.0 / width));
+        let value = match interpolation {
+            RampInterpolation::Linear => format!("mix({}, {}, s)", colors[i], colors[i + 1]),
+            RampInterpolation::Ease => format!(
+                "mix({}, {}, s * s * (3.0 - 2.0 * s))",
+                colors[i],
+                colors[i + 1]
+            ),
+            RampInterpolation::BSpline => format!(
+                "colorRampBSpline(s, {}, {}, {}, {})",
+                colors[i.saturating_sub(1)],
+                colors[i],
+                colors[i + 1],
+                colors[(i + 2).min(last)]
+            ),
+            RampInterpolation::Constant => unreachable!("handled above"),
+        };
+        body.push_str(&format!(
+            "    if (t >= {x0}) {{\n        let s = {s};\n        c = {value};\n    }}\n"
+        ));
+    }
+
+    (
+        fn_name.clone(),
+        format!(
+            "\n// ---- ColorRamp helper for {} (generated) ----\nfn {fn_name}(t: f32) -> vec4f {{\n{body}    return vec4f(c.rgb * c.a, c.a);\n}}\n",
+            node.id
+        ),
+    )
+}
+
 /// Compile a ColorRamp node.
 ///
-/// Maps a scalar value through a color gradient (simplified implementation).
+/// Maps a scalar factor through the `stops` gradient (`[{ position, color }]`,
+/// straight-alpha colors). `interpolation` is `linear` (default), `constant`
+/// (stepped: each stop holds until the next), `ease` (smoothstep between stops)
+/// or `b-spline`. Outside the first/last stop the end colors are held.
+///
+/// # Output
+/// - `color`: premultiplied vec4 (default)
+/// - `alpha`: f32
 pub fn compile_color_ramp<F>(
     scene: &SceneDSL,
     _nodes_by_id: &HashMap<String, Node>,
     node: &Node,
-    _out_port: Option<&str>,
+    out_port: Option<&str>,
     ctx: &mut MaterialCompileContext,
     cache: &mut HashMap<(String, String), TypedExpr>,
     compile_fn: F,
@@ -452,30 +660,45 @@ where
         &mut HashMap<(String, String), TypedExpr>,
     ) -> Result<TypedExpr>,
 {
+    let port = out_port.unwrap_or("color");
+    if port != "color" && port != "alpha" {
+        bail!("ColorRamp: unsupported output port '{port}'");
+    }
+    let interpolation = RampInterpolation::from_node(node)?;
+
     let fac_conn = incoming_connection(scene, &node.id, "fac")
         .or_else(|| incoming_connection(scene, &node.id, "factor"))
-        .or_else(|| incoming_connection(scene, &node.id, "value"))
-        .ok_or_else(|| anyhow!("ColorRamp missing input factor"))?;
-
-    let fac = compile_fn(
-        &fac_conn.from.node_id,
-        Some(&fac_conn.from.port_id),
-        ctx,
-        cache,
-    )?;
+        .or_else(|| incoming_connection(scene, &node.id, "value"));
+    let fac = match fac_conn {
+        Some(conn) => compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?,
+        None => {
+            let v = crate::dsl::parse_f32(&node.params, "factor").unwrap_or(0.5);
+            TypedExpr::new(fmt_f32(v), ValueType::F32)
+        }
+    };
 
     if fac.ty != ValueType::F32 {
         bail!("ColorRamp.factor must be f32, got {:?}", fac.ty);
     }
 
-    // For simplicity, implement a basic grayscale-to-color ramp
-    // In a full implementation, this would read gradient stops from params
-    // For now, just create a simple gradient from black to white
-    Ok(TypedExpr::with_time(
-        format!("vec4f({}, {}, {}, 1.0)", fac.expr, fac.expr, fac.expr),
-        ValueType::Vec4,
-        fac.uses_time,
-    ))
+    if interpolation == RampInterpolation::BSpline {
+        ensure_color_ramp_wgsl_lib(ctx);
+    }
+    let (fn_name, decl) = build_color_ramp_fn(node, interpolation);
+    ctx.extra_wgsl_decls.insert(
+        format!("color_ramp::{}", sanitize_wgsl_ident(&node.id)),
+        decl,
+    );
+
+    let color = format!("{fn_name}({})", fac.expr);
+    if port == "alpha" {
+        return Ok(TypedExpr::with_time(
+            format!("{color}.a"),
+            ValueType::F32,
+            fac.uses_time,
+        ));
+    }
+    Ok(TypedExpr::with_time(color, ValueType::Vec4, fac.uses_time))
 }
 
 /// Compile an HSVAdjust node.
@@ -543,6 +766,557 @@ where
     ))
 }
 
+const TONEMAP_WGSL_LIB_KEY: &str = "tonemap_lib";
+
+fn ensure_tonemap_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx.extra_wgsl_decls.contains_key(TONEMAP_WGSL_LIB_KEY) {
+        return;
+    }
+
+    // All operators take scene-linear HDR and return display-referred *linear*
+    // values in [0, 1]; sRGB encoding still happens at presentation.
+    let wgsl = r#"
+// ---- ToneMap helpers (generated) ----
+
+fn tonemapReinhard(c: vec3f) -> vec3f {
+    return c / (vec3f(1.0) + c);
+}
+
+// Stephen Hill's fit of the ACES RRT + sRGB ODT.
+fn tonemapAcesFitted(c: vec3f) -> vec3f {
+    let input_mat = mat3x3f(
+        vec3f(0.59719, 0.07600, 0.02840),
+        vec3f(0.35458, 0.90834, 0.13383),
+        vec3f(0.04823, 0.01566, 0.83777),
+    );
+    let output_mat = mat3x3f(
+        vec3f(1.60475, -0.10208, -0.00327),
+        vec3f(-0.53108, 1.10813, -0.07276),
+        vec3f(-0.07367, -0.00605, 1.07602),
+    );
+    let v = input_mat * c;
+    let a = v * (v + 0.0245786) - 0.000090537;
+    let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+    return clamp(output_mat * (a / b), vec3f(0.0), vec3f(1.0));
+}
+
+fn tonemapHableCurve(x: vec3f) -> vec3f {
+    let A = 0.15;
+    let B = 0.50;
+    let C = 0.10;
+    let D = 0.20;
+    let E = 0.02;
+    let F = 0.30;
+    return ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F;
+}
+
+// John Hable's Uncharted 2 filmic curve, white point 11.2, exposure bias 2.
+fn tonemapHableFilmic(c: vec3f) -> vec3f {
+    let white_scale = vec3f(1.0) / tonemapHableCurve(vec3f(11.2));
+    return clamp(tonemapHableCurve(c * 2.0) * white_scale, vec3f(0.0), vec3f(1.0));
+}
+
+fn tonemapAgxContrast(x: vec3f) -> vec3f {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    return 15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2
+        + 0.1191 * x - 0.00232;
+}
+
+// Minimal AgX (base look) with a 6th-order contrast approximation.
+fn tonemapAgx(c: vec3f) -> vec3f {
+    let agx_mat = mat3x3f(
+        vec3f(0.842479062253094, 0.0423282422610123, 0.0423756549057051),
+        vec3f(0.0784335999999992, 0.878468636469772, 0.0784336),
+        vec3f(0.0792237451477643, 0.0791661274605434, 0.879142973793104),
+    );
+    let agx_mat_inv = mat3x3f(
+        vec3f(1.19687900512017, -0.0528968517574562, -0.0529716355144438),
+        vec3f(-0.0980208811401368, 1.15190312990417, -0.0980434501171241),
+        vec3f(-0.0990297440797205, -0.0989611768448433, 1.15107367264116),
+    );
+    let min_ev = -12.47393;
+    let max_ev = 4.026069;
+    var v = agx_mat * max(c, vec3f(1e-10));
+    v = clamp(log2(v), vec3f(min_ev), vec3f(max_ev));
+    v = (v - min_ev) / (max_ev - min_ev);
+    v = tonemapAgxContrast(v);
+    v = agx_mat_inv * v;
+    return pow(clamp(v, vec3f(0.0), vec3f(1.0)), vec3f(2.2));
+}
+
+// Colors are premultiplied: tone map the straight color, then re-premultiply.
+fn tonemapPremul(c: vec4f, mode: u32) -> vec4f {
+    let straight = select(c.rgb / c.a, c.rgb, c.a <= 0.0);
+    var mapped: vec3f;
+    switch mode {
+        case 1u: { mapped = tonemapReinhard(straight); }
+        case 2u: { mapped = tonemapHableFilmic(straight); }
+        case 3u: { mapped = tonemapAgx(straight); }
+        default: { mapped = tonemapAcesFitted(straight); }
+    }
+    return vec4f(mapped * select(c.a, 1.0, c.a <= 0.0), c.a);
+}
+"#;
+    ctx.extra_wgsl_decls
+        .insert(TONEMAP_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+/// Tone mapping operator selected by the ToneMap `mode` param.
+fn tonemap_mode_index(mode: &str) -> Option<u32> {
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "aces" | "acesfitted" | "aces_fitted" => Some(0),
+        "reinhard" => Some(1),
+        "filmic" | "hable" => Some(2),
+        "agx" => Some(3),
+        _ => None,
+    }
+}
+
+pub fn compile_tone_map<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let color_conn = incoming_connection(scene, &node.id, "color")
+        .ok_or_else(|| anyhow!("ToneMap missing input color"))?;
+    let color = compile_fn(
+        &color_conn.from.node_id,
+        Some(&color_conn.from.port_id),
+        ctx,
+        cache,
+    )?;
+    let color_vec4 = to_vec4_color(color);
+
+    let mode = node
+        .params
+        .get("mode")
+        .and_then(|v| v.as_str())
+        .unwrap_or("aces");
+    let Some(mode_index) = tonemap_mode_index(mode) else {
+        bail!("ToneMap: unsupported mode '{mode}' (expected aces, reinhard, filmic or agx)");
+    };
+
+    ensure_tonemap_wgsl_lib(ctx);
+    Ok(TypedExpr::with_time(
+        format!("tonemapPremul({}, {mode_index}u)", color_vec4.expr),
+        ValueType::Vec4,
+        color_vec4.uses_time,
+    ))
+}
+
+/// Resolves a scalar adjustment input: the connected value coerced to f32, or
+/// the same-named param, or `default`.
+fn compile_f32_adjust_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    default: f32,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+        let expr = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        return coerce_to_type(expr, ValueType::F32);
+    }
+    let v = node
+        .params
+        .get(port_id)
+        .and_then(parse_json_number_f32)
+        .unwrap_or(default);
+    Ok(TypedExpr::new(fmt_f32(v), ValueType::F32))
+}
+
+const COLOR_ADJUST_WGSL_LIB_KEY: &str = "color_adjust_lib";
+
+fn ensure_color_adjust_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx.extra_wgsl_decls.contains_key(COLOR_ADJUST_WGSL_LIB_KEY) {
+        return;
+    }
+
+    // Gamma and contrast are non-linear, so they run on the straight color and
+    // re-premultiply. Exposure is a plain scale and is premultiplication-safe.
+    let wgsl = r#"
+// ---- Exposure / Gamma / BrightContrast helpers (generated) ----
+
+fn colorAdjustExposure(c: vec4f, stops: f32) -> vec4f {
+    return vec4f(c.rgb * exp2(stops), c.a);
+}
+
+fn colorAdjustGamma(c: vec4f, gamma: f32) -> vec4f {
+    let straight = select(c.rgb / c.a, c.rgb, c.a <= 0.0);
+    let adjusted = pow(max(straight, vec3f(0.0)), vec3f(gamma));
+    return vec4f(adjusted * select(c.a, 1.0, c.a <= 0.0), c.a);
+}
+
+// Same response as Blender's Bright/Contrast node.
+fn colorAdjustBrightContrast(c: vec4f, bright: f32, contrast: f32) -> vec4f {
+    let straight = select(c.rgb / c.a, c.rgb, c.a <= 0.0);
+    let a = 1.0 + contrast;
+    let b = bright - contrast * 0.5;
+    let adjusted = max(a * straight + b, vec3f(0.0));
+    return vec4f(adjusted * select(c.a, 1.0, c.a <= 0.0), c.a);
+}
+"#;
+    ctx.extra_wgsl_decls
+        .insert(COLOR_ADJUST_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compile_color_adjust<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    label: &str,
+    helper: &str,
+    scalar_ports: &[(&str, f32)],
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let color_conn = incoming_connection(scene, &node.id, "color")
+        .ok_or_else(|| anyhow!("{label} missing input color"))?;
+    let color = compile_fn(
+        &color_conn.from.node_id,
+        Some(&color_conn.from.port_id),
+        ctx,
+        cache,
+    )?;
+    let color_vec4 = to_vec4_color(color);
+
+    let mut args = vec![color_vec4.expr];
+    let mut uses_time = color_vec4.uses_time;
+    for (port_id, default) in scalar_ports {
+        let expr =
+            compile_f32_adjust_input(scene, node, port_id, *default, ctx, cache, &compile_fn)?;
+        uses_time |= expr.uses_time;
+        args.push(expr.expr);
+    }
+
+    ensure_color_adjust_wgsl_lib(ctx);
+    Ok(TypedExpr::with_time(
+        format!("{helper}({})", args.join(", ")),
+        ValueType::Vec4,
+        uses_time,
+    ))
+}
+
+/// Exposure: scales the color by `2^exposure` (stops).
+pub fn compile_exposure<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    compile_color_adjust(
+        scene,
+        node,
+        "Exposure",
+        "colorAdjustExposure",
+        &[("exposure", 0.0)],
+        ctx,
+        cache,
+        compile_fn,
+    )
+}
+
+/// Gamma: raises the straight (unpremultiplied) color to `gamma`.
+pub fn compile_gamma<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    compile_color_adjust(
+        scene,
+        node,
+        "Gamma",
+        "colorAdjustGamma",
+        &[("gamma", 1.0)],
+        ctx,
+        cache,
+        compile_fn,
+    )
+}
+
+/// BrightContrast: `max((1 + contrast) * c + bright - contrast / 2, 0)`.
+pub fn compile_bright_contrast<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    compile_color_adjust(
+        scene,
+        node,
+        "BrightContrast",
+        "colorAdjustBrightContrast",
+        &[("bright", 0.0), ("contrast", 0.0)],
+        ctx,
+        cache,
+        compile_fn,
+    )
+}
+
+const COLOR_CHANNELS_WGSL_LIB_KEY: &str = "color_channels_lib";
+
+fn ensure_color_channels_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx
+        .extra_wgsl_decls
+        .contains_key(COLOR_CHANNELS_WGSL_LIB_KEY)
+    {
+        return;
+    }
+
+    // Channels are split from and merged into the straight color; hue, saturation
+    // and lightness of a premultiplied color would depend on its alpha.
+    // Mode: 0 = RGB, 1 = HSV, 2 = HSL. Hue is in [0, 1).
+    let wgsl = r#"
+// ---- SeparateColor / CombineColor helpers (generated) ----
+
+fn colorChannelsRgbToHsv(c: vec3f) -> vec3f {
+    let cmax = max(c.r, max(c.g, c.b));
+    let delta = cmax - min(c.r, min(c.g, c.b));
+    var h = 0.0;
+    if (delta > 0.0) {
+        if (cmax == c.r) {
+            h = (c.g - c.b) / delta;
+        } else if (cmax == c.g) {
+            h = (c.b - c.r) / delta + 2.0;
+        } else {
+            h = (c.r - c.g) / delta + 4.0;
+        }
+        h = fract(h / 6.0);
+    }
+    let s = select(0.0, delta / cmax, cmax > 0.0);
+    return vec3f(h, s, cmax);
+}
+
+fn colorChannelsHsvToRgb(hsv: vec3f) -> vec3f {
+    let k = vec3f(0.0, 4.0, 2.0);
+    let p = abs(fract(vec3f(hsv.x) + k / 6.0) * 6.0 - 3.0);
+    let rgb = clamp(p - 1.0, vec3f(0.0), vec3f(1.0));
+    return hsv.z * mix(vec3f(1.0), rgb, hsv.y);
+}
+
+fn colorChannelsRgbToHsl(c: vec3f) -> vec3f {
+    let hsv = colorChannelsRgbToHsv(c);
+    let l = hsv.z * (1.0 - hsv.y * 0.5);
+    let d = min(l, 1.0 - l);
+    let s = select(0.0, (hsv.z - l) / d, d > 0.0);
+    return vec3f(hsv.x, s, l);
+}
+
+fn colorChannelsHslToRgb(hsl: vec3f) -> vec3f {
+    let v = hsl.z + hsl.y * min(hsl.z, 1.0 - hsl.z);
+    let s = select(0.0, 2.0 * (1.0 - hsl.z / v), v > 0.0);
+    return colorChannelsHsvToRgb(vec3f(hsl.x, s, v));
+}
+
+fn separateColor(c: vec4f, mode: u32) -> vec4f {
+    let straight = select(c.rgb / c.a, c.rgb, c.a <= 0.0);
+    var channels = straight;
+    if (mode == 1u) {
+        channels = colorChannelsRgbToHsv(straight);
+    } else if (mode == 2u) {
+        channels = colorChannelsRgbToHsl(straight);
+    }
+    return vec4f(channels, c.a);
+}
+
+fn combineColor(channels: vec3f, alpha: f32, mode: u32) -> vec4f {
+    var rgb = channels;
+    if (mode == 1u) {
+        rgb = colorChannelsHsvToRgb(channels);
+    } else if (mode == 2u) {
+        rgb = colorChannelsHslToRgb(channels);
+    }
+    return vec4f(rgb * alpha, alpha);
+}
+"#;
+    ctx.extra_wgsl_decls
+        .insert(COLOR_CHANNELS_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+/// Channel layout selected by the SeparateColor / CombineColor `mode` param.
+fn color_channels_mode_index(node: &Node) -> Result<u32> {
+    let mode = node
+        .params
+        .get("mode")
+        .and_then(|v| v.as_str())
+        .unwrap_or("rgb");
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "rgb" => Ok(0),
+        "hsv" => Ok(1),
+        "hsl" => Ok(2),
+        _ => bail!(
+            "{}: unsupported mode '{mode}' (expected rgb, hsv or hsl)",
+            node.node_type
+        ),
+    }
+}
+
+/// SeparateColor: splits a color into three channels (R/G/B, H/S/V or H/S/L
+/// depending on `mode`) plus alpha. Channels are taken from the straight color.
+pub fn compile_separate_color<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("red");
+    let component = match port {
+        "red" => "x",
+        "green" => "y",
+        "blue" => "z",
+        "alpha" => "w",
+        _ => bail!("SeparateColor: unsupported output port '{port}'"),
+    };
+    let mode_index = color_channels_mode_index(node)?;
+
+    let color_conn = incoming_connection(scene, &node.id, "color")
+        .ok_or_else(|| anyhow!("SeparateColor missing input color"))?;
+    let color = compile_fn(
+        &color_conn.from.node_id,
+        Some(&color_conn.from.port_id),
+        ctx,
+        cache,
+    )?;
+    let color_vec4 = to_vec4_color(color);
+
+    if component == "w" {
+        return Ok(TypedExpr::with_time(
+            format!("({}).w", color_vec4.expr),
+            ValueType::F32,
+            color_vec4.uses_time,
+        ));
+    }
+    ensure_color_channels_wgsl_lib(ctx);
+    Ok(TypedExpr::with_time(
+        format!(
+            "separateColor({}, {mode_index}u).{component}",
+            color_vec4.expr
+        ),
+        ValueType::F32,
+        color_vec4.uses_time,
+    ))
+}
+
+/// CombineColor: builds a color from three channels (R/G/B, H/S/V or H/S/L
+/// depending on `mode`) and alpha. The result is premultiplied.
+pub fn compile_combine_color<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let mode_index = color_channels_mode_index(node)?;
+
+    let mut channels = Vec::with_capacity(3);
+    let mut uses_time = false;
+    for port_id in ["red", "green", "blue"] {
+        let expr = compile_f32_adjust_input(scene, node, port_id, 0.0, ctx, cache, &compile_fn)?;
+        uses_time |= expr.uses_time;
+        channels.push(expr.expr);
+    }
+    let alpha = compile_f32_adjust_input(scene, node, "alpha", 1.0, ctx, cache, &compile_fn)?;
+    uses_time |= alpha.uses_time;
+
+    ensure_color_channels_wgsl_lib(ctx);
+    Ok(TypedExpr::with_time(
+        format!(
+            "combineColor(vec3f({}), {}, {mode_index}u)",
+            channels.join(", "),
+            alpha.expr
+        ),
+        ValueType::Vec4,
+        uses_time,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::super::types::ValueType;
@@ -603,7 +1377,70 @@ mod tests {
         assert_eq!(result.ty, ValueType::F32);
         assert!(result.expr.contains("dot("));
         assert!(result.expr.contains("0.2126"));
-        assert!(result.expr.contains("clamp("));
+        assert!(!result.expr.contains("clamp("));
+    }
+
+    #[test]
+    fn test_luminance_standards_select_weights() {
+        use super::super::test_utils::test_connection;
+        let connections = vec![test_connection("color", "value", "lum1", "color")];
+        let scene = test_scene(vec![], connections);
+        for (standard, weights) in [
+            (Some("rec601"), "vec3f(0.299, 0.587, 0.114)"),
+            (Some("rec2020"), "vec3f(0.2627, 0.678, 0.0593)"),
+            (Some("average"), "vec3f(0.33333334)"),
+            (None, "vec3f(0.2126, 0.7152, 0.0722)"),
+        ] {
+            let mut params = HashMap::new();
+            if let Some(standard) = standard {
+                params.insert("standard".to_string(), serde_json::json!(standard));
+            }
+            let node = Node {
+                id: "lum1".to_string(),
+                node_type: "Luminance".to_string(),
+                params,
+                inputs: Vec::new(),
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            };
+            let result = compile_luminance(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+                mock_color_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(
+                result.expr,
+                format!("dot((vec4f(1.0, 0.0, 0.0, 1.0)).rgb, {weights})")
+            );
+        }
+
+        let node = Node {
+            id: "lum1".to_string(),
+            node_type: "Luminance".to_string(),
+            params: HashMap::from([("standard".to_string(), serde_json::json!("rec999"))]),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        assert!(
+            compile_luminance(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+                mock_color_compile_fn,
+            )
+            .is_err()
+        );
     }
 
     #[test]
@@ -736,6 +1573,51 @@ mod tests {
         assert!(result.expr.contains("blendMultiply("));
     }
 
+    #[test]
+    fn test_color_mix_compositing_modes_compile_to_valid_wgsl() {
+        let scene = test_scene(vec![], vec![]);
+        for (mode, blend_fn) in [
+            ("screen", "blendScreen("),
+            ("overlay", "blendOverlay("),
+            ("soft light", "blendSoftLight("),
+            ("hard_light", "blendHardLight("),
+            ("Difference", "blendDifference("),
+            ("add", "blendAdd("),
+            ("subtract", "blendSubtract("),
+            ("linear-burn", "blendLinearBurn("),
+            ("color-dodge", "blendColorDodge("),
+        ] {
+            let node = Node {
+                id: "mix1".to_string(),
+                node_type: "ColorMix".to_string(),
+                params: HashMap::from([("mode".to_string(), serde_json::json!(mode))]),
+                inputs: Vec::new(),
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            };
+            let mut ctx = MaterialCompileContext::default();
+            let result = compile_color_mix(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut ctx,
+                &mut HashMap::new(),
+                mock_color_compile_fn,
+            )
+            .unwrap();
+
+            assert!(result.expr.contains(blend_fn), "{mode}: {}", result.expr);
+            let module = format!(
+                "{}\n@fragment\nfn fs_main() -> @location(0) vec4f {{\n    return {};\n}}\n",
+                ctx.wgsl_decls(),
+                result.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{mode}: {e:#}"));
+        }
+    }
+
     #[test]
     fn test_color_ramp() {
         use super::super::test_utils::test_connection;
@@ -766,7 +1648,96 @@ mod tests {
         .unwrap();
 
         assert_eq!(result.ty, ValueType::Vec4);
-        assert!(result.expr.contains("vec4f"));
+        assert_eq!(result.expr, "color_ramp_ramp1(0.5)");
+        assert!(
+            ctx.wgsl_decls()
+                .contains("fn color_ramp_ramp1(t: f32) -> vec4f")
+        );
+    }
+
+    #[test]
+    fn test_color_ramp_interpolation_modes_compile_to_valid_wgsl() {
+        use super::super::test_utils::test_connection;
+        let connections = vec![test_connection("factor_node", "value", "ramp1", "factor")];
+        let scene = test_scene(vec![], connections);
+
+        for (mode, needle) in [
+            (
+                "linear",
+                "mix(vec4f(1.0, 0.0, 0.0, 1.0), vec4f(0.0, 1.0, 0.0, 0.5), s)",
+            ),
+            (
+                "constant",
+                "if (t >= 0.25) { c = vec4f(0.0, 1.0, 0.0, 0.5); }",
+            ),
+            ("ease", "s * s * (3.0 - 2.0 * s)"),
+            ("b-spline", "colorRampBSpline(s, "),
+        ] {
+            let node = Node {
+                id: "ramp1".to_string(),
+                node_type: "ColorRamp".to_string(),
+                params: serde_json::from_value(serde_json::json!({
+                    "interpolation": mode,
+                    "stops": [
+                        { "position": 0.25, "color": [0.0, 1.0, 0.0, 0.5] },
+                        { "position": -0.5, "color": [1.0, 0.0, 0.0, 1.0] },
+                        { "position": 1.0, "color": { "r": 0.0, "g": 0.0, "b": 1.0 } }
+                    ]
+                }))
+                .unwrap(),
+                inputs: Vec::new(),
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            };
+            let mut ctx = MaterialCompileContext::default();
+            let result = compile_color_ramp(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some("alpha"),
+                &mut ctx,
+                &mut HashMap::new(),
+                mock_f32_compile_fn,
+            )
+            .unwrap();
+
+            assert_eq!(result.ty, ValueType::F32);
+            let decls = ctx.wgsl_decls();
+            assert!(decls.contains(needle), "{mode}: {decls}");
+            let module = format!(
+                "{decls}\n@fragment\nfn fs_main() -> @location(0) vec4f {{\n    return vec4f({});\n}}\n",
+                result.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{mode}: {e:#}"));
+        }
+    }
+
+    #[test]
+    fn test_color_ramp_rejects_unknown_interpolation() {
+        use super::super::test_utils::test_connection;
+        let connections = vec![test_connection("factor_node", "value", "ramp1", "factor")];
+        let scene = test_scene(vec![], connections);
+        let node = Node {
+            id: "ramp1".to_string(),
+            node_type: "ColorRamp".to_string(),
+            params: HashMap::from([("interpolation".to_string(), serde_json::json!("cardinal"))]),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        let err = compile_color_ramp(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut MaterialCompileContext::default(),
+            &mut HashMap::new(),
+            mock_f32_compile_fn,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("cardinal"));
     }
 
     #[test]
@@ -804,4 +1775,298 @@ mod tests {
 
         assert_eq!(result.ty, ValueType::Vec4);
     }
+
+    #[test]
+    fn test_tone_map_modes_compile_to_valid_wgsl() {
+        use super::super::test_utils::test_connection;
+        let connections = vec![test_connection("color_in", "value", "tm1", "color")];
+        let scene = test_scene(vec![], connections);
+        for mode in ["aces", "reinhard", "filmic", "agx"] {
+            let node = Node {
+                id: "tm1".to_string(),
+                node_type: "ToneMap".to_string(),
+                params: HashMap::from([("mode".to_string(), serde_json::json!(mode))]),
+                inputs: Vec::new(),
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            };
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+
+            let result = compile_tone_map(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut ctx,
+                &mut cache,
+                mock_color_compile_fn,
+            )
+            .unwrap();
+
+            assert_eq!(result.ty, ValueType::Vec4);
+            assert!(result.expr.starts_with("tonemapPremul("));
+            let module = format!(
+                "{}\n@fragment\nfn fs_main() -> @location(0) vec4f {{\n    return {};\n}}\n",
+                ctx.wgsl_decls(),
+                result.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{mode}: {e:#}"));
+        }
+    }
+
+    #[test]
+    fn test_tone_map_rejects_unknown_mode() {
+        use super::super::test_utils::test_connection;
+        let connections = vec![test_connection("color_in", "value", "tm1", "color")];
+        let scene = test_scene(vec![], connections);
+        let node = Node {
+            id: "tm1".to_string(),
+            node_type: "ToneMap".to_string(),
+            params: HashMap::from([("mode".to_string(), serde_json::json!("drago"))]),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+
+        let err = compile_tone_map(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut ctx,
+            &mut cache,
+            mock_color_compile_fn,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unsupported mode"));
+    }
+
+    #[test]
+    fn test_color_adjust_nodes_compile_to_valid_wgsl() {
+        use super::super::test_utils::test_connection;
+        let upstream = |node_id: &str,
+                        _port: Option<&str>,
+                        _ctx: &mut MaterialCompileContext,
+                        _cache: &mut HashMap<(String, String), TypedExpr>|
+         -> Result<TypedExpr> {
+            Ok(if node_id == "amount" {
+                TypedExpr::new("0.5".to_string(), ValueType::F32)
+            } else {
+                TypedExpr::new("vec4f(1.0, 0.0, 0.0, 1.0)".to_string(), ValueType::Vec4)
+            })
+        };
+        let connections = vec![
+            test_connection("color_in", "value", "adj", "color"),
+            test_connection("amount", "value", "adj", "contrast"),
+        ];
+        let scene = test_scene(vec![], connections);
+
+        for node_type in ["Exposure", "Gamma", "BrightContrast"] {
+            let node = Node {
+                id: "adj".to_string(),
+                node_type: node_type.to_string(),
+                params: HashMap::from([("gamma".to_string(), serde_json::json!(2.2))]),
+                inputs: Vec::new(),
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            };
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+            let nodes_by_id = HashMap::new();
+
+            let result = match node_type {
+                "Exposure" => compile_exposure(
+                    &scene,
+                    &nodes_by_id,
+                    &node,
+                    None,
+                    &mut ctx,
+                    &mut cache,
+                    upstream,
+                ),
+                "Gamma" => compile_gamma(
+                    &scene,
+                    &nodes_by_id,
+                    &node,
+                    None,
+                    &mut ctx,
+                    &mut cache,
+                    upstream,
+                ),
+                _ => compile_bright_contrast(
+                    &scene,
+                    &nodes_by_id,
+                    &node,
+                    None,
+                    &mut ctx,
+                    &mut cache,
+                    upstream,
+                ),
+            }
+            .unwrap();
+
+            assert_eq!(result.ty, ValueType::Vec4);
+            let module = format!(
+                "{}\n@fragment\nfn fs_main() -> @location(0) vec4f {{\n    return {};\n}}\n",
+                ctx.wgsl_decls(),
+                result.expr
+            );
+            crate::renderer::validate_wgsl(&module)
+                .unwrap_or_else(|e| panic!("{node_type}: {e:#}"));
+        }
+    }
+
+    #[test]
+    fn test_bright_contrast_reads_connected_and_param_inputs() {
+        use super::super::test_utils::test_connection;
+        let connections = vec![
+            test_connection("color_in", "value", "bc", "color"),
+            test_connection("amount", "value", "bc", "contrast"),
+        ];
+        let scene = test_scene(vec![], connections);
+        let node = Node {
+            id: "bc".to_string(),
+            node_type: "BrightContrast".to_string(),
+            params: HashMap::from([("bright".to_string(), serde_json::json!(0.25))]),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+
+        let result = compile_bright_contrast(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut ctx,
+            &mut cache,
+            mock_f32_compile_fn,
+        )
+        .unwrap();
+
+        // The mock returns 0.5 (f32) for every upstream; color is splatted to vec4.
+        assert!(result.expr.starts_with("colorAdjustBrightContrast("));
+        assert!(result.expr.ends_with(", 0.25, 0.5)"));
+    }
+
+    #[test]
+    fn test_separate_and_combine_color_compile_to_valid_wgsl() {
+        use super::super::test_utils::test_connection;
+        let connections = vec![
+            test_connection("color_in", "value", "sep", "color"),
+            test_connection("amount", "value", "comb", "green"),
+        ];
+        let scene = test_scene(vec![], connections);
+        for mode in ["rgb", "hsv", "hsl"] {
+            let separate = Node {
+                id: "sep".to_string(),
+                node_type: "SeparateColor".to_string(),
+                params: HashMap::from([("mode".to_string(), serde_json::json!(mode))]),
+                inputs: Vec::new(),
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            };
+            let combine = Node {
+                id: "comb".to_string(),
+                node_type: "CombineColor".to_string(),
+                params: HashMap::from([
+                    ("mode".to_string(), serde_json::json!(mode)),
+                    ("blue".to_string(), serde_json::json!(0.75)),
+                ]),
+                ..separate.clone()
+            };
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+
+            let mut exprs = Vec::new();
+            for port in ["red", "green", "blue", "alpha"] {
+                let result = compile_separate_color(
+                    &scene,
+                    &HashMap::new(),
+                    &separate,
+                    Some(port),
+                    &mut ctx,
+                    &mut cache,
+                    mock_color_compile_fn,
+                )
+                .unwrap();
+                assert_eq!(result.ty, ValueType::F32);
+                exprs.push(result.expr);
+            }
+            let combined = compile_combine_color(
+                &scene,
+                &HashMap::new(),
+                &combine,
+                None,
+                &mut ctx,
+                &mut cache,
+                mock_f32_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(combined.ty, ValueType::Vec4);
+            assert!(combined.expr.contains("vec3f(0.0, 0.5, 0.75), 1.0"));
+
+            let module = format!(
+                "{}\n@fragment\nfn fs_main() -> @location(0) vec4f {{\n    return {} + vec4f({});\n}}\n",
+                ctx.wgsl_decls(),
+                combined.expr,
+                exprs.join(", ")
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{mode}: {e:#}"));
+        }
+    }
+
+    #[test]
+    fn test_separate_color_rejects_unknown_port_and_mode() {
+        use super::super::test_utils::test_connection;
+        let connections = vec![test_connection("color_in", "value", "sep", "color")];
+        let scene = test_scene(vec![], connections);
+        let mut node = Node {
+            id: "sep".to_string(),
+            node_type: "SeparateColor".to_string(),
+            params: HashMap::new(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+
+        let err = compile_separate_color(
+            &scene,
+            &HashMap::new(),
+            &node,
+            Some("hue"),
+            &mut ctx,
+            &mut cache,
+            mock_color_compile_fn,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unsupported output port"));
+
+        node.params
+            .insert("mode".to_string(), serde_json::json!("cmyk"));
+        let err = compile_separate_color(
+            &scene,
+            &HashMap::new(),
+            &node,
+            Some("red"),
+            &mut ctx,
+            &mut cache,
+            mock_color_compile_fn,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unsupported mode"));
+    }
 }
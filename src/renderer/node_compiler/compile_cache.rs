@@ -0,0 +1,375 @@
+//! Cross-rebuild cache for compiled pass materials.
+//!
+//! Every scene update re-plans the whole graph, but during editor iteration
+//! most material subgraphs are unchanged. Entries are keyed by a content hash
+//! of the material root node and its upstream subgraph, and store the compiled
+//! expression together with the `MaterialCompileContext` it produced, so
+//! texture bindings, graph inputs and helper declarations replay exactly.
+//!
+//! This only covers CPU-side WGSL generation; pipeline caching is separate.
+//! The planner prewarms the cache for every render pass in parallel before
+//! its serial assembly loop.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::Result;
+use rayon::prelude::*;
+
+use super::{attribute, compile_material_expr, template_loader};
+use crate::dsl::{Connection, Node, SceneDSL};
+use crate::renderer::types::{BakedDataParseMeta, MaterialCompileContext, TypedExpr};
+
+/// Upper bound on cached materials; the cache is cleared when it is reached.
+const MAX_ENTRIES: usize = 1024;
+
+struct CachedMaterial {
+    expr: TypedExpr,
+    ctx: MaterialCompileContext,
+}
+
+static CACHE: OnceLock<Mutex<HashMap<u64, CachedMaterial>>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<HashMap<u64, CachedMaterial>> {
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Compiles the fragment material rooted at `node_id.out_port`, reusing the
+/// result of a previous build when the upstream subgraph is unchanged.
+///
+/// `ctx` must be freshly constructed (only the baked DataParse fields set):
+/// on a hit it is replaced by the cached context.
+pub(crate) fn compile_material_expr_cached(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node_id: &str,
+    out_port: &str,
+    ctx: &mut MaterialCompileContext,
+) -> Result<TypedExpr> {
+    let key = material_subgraph_hash(
+        scene,
+        nodes_by_id,
+        node_id,
+        out_port,
+        ctx.baked_data_parse_meta.as_deref(),
+    );
+
+    if let Ok(cache) = cache().lock()
+        && let Some(hit) = cache.get(&key)
+    {
+        let baked_data_parse = ctx.baked_data_parse.take();
+        let baked_data_parse_meta = ctx.baked_data_parse_meta.take();
+        *ctx = MaterialCompileContext {
+            baked_data_parse,
+            baked_data_parse_meta,
+            ..hit.ctx.clone()
+        };
+        return Ok(hit.expr.clone());
+    }
+
+    let mut expr_cache: HashMap<(String, String), TypedExpr> = HashMap::new();
+    let expr = compile_material_expr(
+        scene,
+        nodes_by_id,
+        node_id,
+        Some(out_port),
+        ctx,
+        &mut expr_cache,
+    )?;
+
+    if let Ok(mut cache) = cache().lock() {
+        if cache.len() >= MAX_ENTRIES {
+            cache.clear();
+        }
+        // Baked values are per-instance data that node compilers never read;
+        // only the slot layout in the meta affects WGSL and it is in the key.
+        let cached_ctx = MaterialCompileContext {
+            baked_data_parse: None,
+            baked_data_parse_meta: None,
+            ..ctx.clone()
+        };
+        cache.insert(
+            key,
+            CachedMaterial {
+                expr: expr.clone(),
+                ctx: cached_ctx,
+            },
+        );
+    }
+    Ok(expr)
+}
+
+/// Compiles the materials rooted at `roots` on the rayon pool so the serial
+/// planner hits the cache. Roots whose subgraph reads baked data (`DataParse`
+/// or an instance `Attribute`) need the per-pass baked layout and are left to
+/// the planner, as are compile errors.
+pub(crate) fn prewarm_material_cache(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    roots: &[(String, String)],
+) {
+    roots.par_iter().for_each(|(node_id, out_port)| {
+        let subgraph = upstream_subgraph(scene, node_id);
+        let reads_baked_data = subgraph
+            .iter()
+            .any(|id| nodes_by_id.get(*id).is_some_and(node_reads_baked_data));
+        if reads_baked_data {
+            return;
+        }
+        let key = hash_subgraph(scene, nodes_by_id, &subgraph, node_id, out_port, None);
+        if cache().lock().is_ok_and(|cache| cache.contains_key(&key)) {
+            return;
+        }
+        let mut ctx = MaterialCompileContext::default();
+        let _ = compile_material_expr_cached(scene, nodes_by_id, node_id, out_port, &mut ctx);
+    });
+}
+
+/// Content hash of `root_id.out_port` and everything upstream of it.
+pub(crate) fn material_subgraph_hash(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    root_id: &str,
+    out_port: &str,
+    baked_meta: Option<&BakedDataParseMeta>,
+) -> u64 {
+    let subgraph = upstream_subgraph(scene, root_id);
+    hash_subgraph(scene, nodes_by_id, &subgraph, root_id, out_port, baked_meta)
+}
+
+fn upstream_subgraph<'a>(scene: &'a SceneDSL, root_id: &'a str) -> BTreeSet<&'a str> {
+    let mut incoming: HashMap<&str, Vec<&Connection>> = HashMap::new();
+    for conn in &scene.connections {
+        incoming
+            .entry(conn.to.node_id.as_str())
+            .or_default()
+            .push(conn);
+    }
+
+    let mut subgraph: BTreeSet<&str> = BTreeSet::new();
+    let mut stack = vec![root_id];
+    while let Some(id) = stack.pop() {
+        if !subgraph.insert(id) {
+            continue;
+        }
+        for conn in incoming.get(id).into_iter().flatten() {
+            stack.push(conn.from.node_id.as_str());
+        }
+    }
+    subgraph
+}
+
+/// Connections are hashed for every node in the subgraph, including outgoing
+/// edges to nodes outside it, because fan-out decides whether a value is
+/// hoisted into a named temporary. The baked DataParse layout only matters
+/// when a node in the subgraph reads baked data.
+fn hash_subgraph(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    subgraph: &BTreeSet<&str>,
+    root_id: &str,
+    out_port: &str,
+    baked_meta: Option<&BakedDataParseMeta>,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    template_loader::generation().hash(&mut hasher);
+    root_id.hash(&mut hasher);
+    out_port.hash(&mut hasher);
+
+    let mut reads_baked_data = false;
+    for id in subgraph {
+        id.hash(&mut hasher);
+        match nodes_by_id.get(*id) {
+            Some(node) => {
+                reads_baked_data |= node_reads_baked_data(node);
+                hash_node(node, &mut hasher);
+            }
+            None => "<missing>".hash(&mut hasher),
+        }
+    }
+
+    let mut edges: Vec<(&str, &str, &str, &str)> = scene
+        .connections
+        .iter()
+        .filter(|c| {
+            subgraph.contains(c.to.node_id.as_str()) || subgraph.contains(c.from.node_id.as_str())
+        })
+        .map(|c| {
+            (
+                c.from.node_id.as_str(),
+                c.from.port_id.as_str(),
+                c.to.node_id.as_str(),
+                c.to.port_id.as_str(),
+            )
+        })
+        .collect();
+    edges.sort_unstable();
+    edges.hash(&mut hasher);
+
+    if !scene.groups.is_empty() {
+        serde_json::to_string(&scene.groups)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+    }
+
+    if reads_baked_data && let Some(meta) = baked_meta {
+        meta.pass_id.hash(&mut hasher);
+        meta.outputs_per_instance.hash(&mut hasher);
+        let mut slots: Vec<_> = meta.slot_by_output.iter().collect();
+        slots.sort_unstable();
+        slots.hash(&mut hasher);
+        let mut attribute_slots: Vec<_> = meta.attribute_slots.iter().collect();
+        attribute_slots.sort_unstable();
+        attribute_slots.hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+/// `DataParse` reads its baked slots and instance `Attribute`s read the pass's
+/// `instanceAttributes` slots, so both compile differently per pass layout.
+fn node_reads_baked_data(node: &Node) -> bool {
+    match node.node_type.as_str() {
+        "DataParse" => true,
+        "Attribute" => attribute::reads_instance_attribute(node),
+        _ => false,
+    }
+}
+
+fn hash_node(node: &Node, hasher: &mut DefaultHasher) {
+    node.node_type.hash(hasher);
+    let mut params: Vec<(&String, String)> = node
+        .params
+        .iter()
+        .map(|(key, value)| (key, value.to_string()))
+        .collect();
+    params.sort_unstable();
+    params.hash(hasher);
+    format!("{:?}", node.inputs).hash(hasher);
+    format!("{:?}", node.outputs).hash(hasher);
+    format!("{:?}", node.input_bindings).hash(hasher);
+    node.wgsl_override.hash(hasher);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::{compile_material_expr_cached, material_subgraph_hash};
+    use crate::dsl::{Node, SceneDSL};
+    use crate::renderer::node_compiler::test_utils::{test_connection, test_scene};
+    use crate::renderer::types::{BakedDataParseMeta, MaterialCompileContext};
+
+    fn node(id: &str, node_type: &str, params: serde_json::Value) -> Node {
+        Node {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            params: serde_json::from_value(params).unwrap(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn scene_with(color: serde_json::Value, other_value: f64) -> SceneDSL {
+        test_scene(
+            vec![
+                node("color", "ColorInput", json!({ "value": color })),
+                node("other", "FloatInput", json!({ "value": other_value })),
+                node("pass", "RenderPass", json!({})),
+            ],
+            vec![test_connection("color", "color", "pass", "material")],
+        )
+    }
+
+    fn nodes_by_id(scene: &SceneDSL) -> HashMap<String, Node> {
+        scene
+            .nodes
+            .iter()
+            .map(|n| (n.id.clone(), n.clone()))
+            .collect()
+    }
+
+    fn hash(scene: &SceneDSL) -> u64 {
+        material_subgraph_hash(scene, &nodes_by_id(scene), "color", "color", None)
+    }
+
+    #[test]
+    fn unrelated_edits_keep_the_key() {
+        let base = scene_with(json!([1.0, 0.0, 0.0, 1.0]), 1.0);
+        let edited = scene_with(json!([1.0, 0.0, 0.0, 1.0]), 2.0);
+        assert_eq!(hash(&base), hash(&edited));
+    }
+
+    #[test]
+    fn upstream_edits_change_the_key() {
+        let base = scene_with(json!([1.0, 0.0, 0.0, 1.0]), 1.0);
+        let edited = scene_with(json!([0.0, 1.0, 0.0, 1.0]), 1.0);
+        assert_ne!(hash(&base), hash(&edited));
+
+        let mut rewired = base.clone();
+        rewired
+            .connections
+            .push(test_connection("color", "color", "other", "value"));
+        assert_ne!(hash(&base), hash(&rewired));
+    }
+
+    #[test]
+    fn cache_hit_replays_compile_context() {
+        let scene = scene_with(json!([0.25, 0.5, 0.75, 1.0]), 3.0);
+        let nodes = nodes_by_id(&scene);
+
+        let mut first_ctx = MaterialCompileContext::default();
+        let first =
+            compile_material_expr_cached(&scene, &nodes, "color", "color", &mut first_ctx).unwrap();
+        let mut second_ctx = MaterialCompileContext::default();
+        let second =
+            compile_material_expr_cached(&scene, &nodes, "color", "color", &mut second_ctx)
+                .unwrap();
+
+        assert_eq!(first.expr, second.expr);
+        assert_eq!(first_ctx.wgsl_decls(), second_ctx.wgsl_decls());
+        assert_eq!(first_ctx.graph_input_kinds, second_ctx.graph_input_kinds);
+        assert_eq!(
+            first_ctx.build_fragment_body(&first.expr),
+            second_ctx.build_fragment_body(&second.expr)
+        );
+    }
+
+    #[test]
+    fn instance_attribute_layout_is_part_of_the_key() {
+        let scene = test_scene(
+            vec![
+                node("tint", "Attribute", json!({ "name": "tint" })),
+                node("pass", "RenderPass", json!({})),
+            ],
+            vec![test_connection("tint", "value", "pass", "material")],
+        );
+        let nodes = nodes_by_id(&scene);
+        let ctx_with_slot = |slot: u32| MaterialCompileContext {
+            baked_data_parse_meta: Some(Arc::new(BakedDataParseMeta {
+                pass_id: "pass".to_string(),
+                outputs_per_instance: 3,
+                slot_by_output: HashMap::new(),
+                attribute_slots: HashMap::from([("tint".to_string(), slot)]),
+            })),
+            ..Default::default()
+        };
+
+        let mut first_ctx = ctx_with_slot(0);
+        let first =
+            compile_material_expr_cached(&scene, &nodes, "tint", "value", &mut first_ctx).unwrap();
+        let mut second_ctx = ctx_with_slot(2);
+        let second =
+            compile_material_expr_cached(&scene, &nodes, "tint", "value", &mut second_ctx).unwrap();
+
+        assert!(first.expr.ends_with("* 3u + 0u]"), "{}", first.expr);
+        assert!(second.expr.ends_with("* 3u + 2u]"), "{}", second.expr);
+    }
+}
@@ -0,0 +1,358 @@
+//! Compiler for CustomWGSL node (user-provided WGSL function bodies).
+//!
+//! Unlike MathClosure, the snippet in `params.source` is already WGSL: it becomes
+//! the body of a generated helper function whose parameters are `uv` plus the
+//! node's declared inputs, and whose return type is `params.outputType`.
+//! Texture-typed inputs expand to a `texture_2d<f32>` / `sampler` parameter pair,
+//! giving snippets direct sampling access that MathClosure cannot offer.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::dsl::{Node, NodePort, SceneDSL, incoming_connection};
+use crate::renderer::geometry_resolver::is_pass_like_node_type;
+use crate::renderer::types::{MaterialCompileContext, PassTextureRef, TypedExpr, ValueType};
+use crate::renderer::utils::{coerce_to_type, sanitize_wgsl_ident};
+use crate::renderer::validation::{GlslShaderStage, validate_wgsl, validate_wgsl_module};
+
+enum CustomWgslInput {
+    Value(ValueType),
+    Texture,
+}
+
+fn map_input_type(port: &NodePort) -> Result<CustomWgslInput> {
+    match port.port_type.as_deref() {
+        Some("texture" | "sampledTexture" | "pass") => Ok(CustomWgslInput::Texture),
+        other => super::value_type_from_port_type(other)
+            .map(CustomWgslInput::Value)
+            .ok_or_else(|| anyhow!("unsupported CustomWGSL input type: {}", other.unwrap_or(""))),
+    }
+}
+
+fn parse_output_type(node: &Node) -> Result<ValueType> {
+    let raw = node
+        .params
+        .get("outputType")
+        .and_then(|v| v.as_str())
+        .unwrap_or("vec4");
+    match super::value_type_from_port_type(Some(raw)) {
+        Some(ValueType::Mat4) | None => {
+            bail!(
+                "unsupported CustomWGSL outputType: {raw} (node={})",
+                node.id
+            )
+        }
+        Some(ty) => Ok(ty),
+    }
+}
+
+fn input_param_name(port: &NodePort) -> String {
+    // Snippets reference the human-readable port name, not generated port ids.
+    match port.name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => sanitize_wgsl_ident(name),
+        _ => sanitize_wgsl_ident(&port.id),
+    }
+}
+
+/// Resolve the texture/sampler globals for a texture-typed input.
+fn resolve_texture_input(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    port: &NodePort,
+    ctx: &mut MaterialCompileContext,
+) -> Result<(String, String)> {
+    let conn = incoming_connection(scene, &node.id, &port.id)
+        .ok_or_else(|| anyhow!("CustomWGSL texture input '{}' is not connected", port.id))?;
+    let upstream = nodes_by_id
+        .get(&conn.from.node_id)
+        .ok_or_else(|| anyhow!("CustomWGSL: upstream node not found: {}", conn.from.node_id))?;
+
+    match upstream.node_type.as_str() {
+        "ImageTexture" if conn.from.port_id == "texture" => {
+            ctx.register_image_texture(&upstream.id);
+            Ok((
+                MaterialCompileContext::tex_var_name(&upstream.id),
+                MaterialCompileContext::sampler_var_name(&upstream.id),
+            ))
+        }
+        "PassTexture" if conn.from.port_id == "texture" => {
+            let pass_conn = incoming_connection(scene, &upstream.id, "pass")
+                .ok_or_else(|| anyhow!("PassTexture.pass input is not connected"))?;
+            let pass = nodes_by_id
+                .get(&pass_conn.from.node_id)
+                .ok_or_else(|| anyhow!("PassTexture upstream pass not found"))?;
+            if !is_pass_like_node_type(&pass.node_type) {
+                bail!(
+                    "PassTexture.pass must be connected to a pass node, got {}",
+                    pass.node_type
+                );
+            }
+            let texture_ref = PassTextureRef::through_pass_texture(
+                &upstream.id,
+                &pass.id,
+                &pass_conn.from.port_id,
+            );
+            ctx.register_pass_texture_ref(texture_ref);
+            Ok((
+                MaterialCompileContext::pass_tex_var_name(&upstream.id),
+                MaterialCompileContext::pass_sampler_var_name(&upstream.id),
+            ))
+        }
+        ty if is_pass_like_node_type(ty) => {
+            let texture_ref = PassTextureRef::direct(&conn.from.node_id, &conn.from.port_id);
+            ctx.register_pass_texture_ref(texture_ref.clone());
+            Ok((
+                MaterialCompileContext::pass_tex_var_name(&texture_ref.binding_id),
+                MaterialCompileContext::pass_sampler_var_name(&texture_ref.binding_id),
+            ))
+        }
+        other => bail!(
+            "CustomWGSL texture input '{}' expects a pass, ImageTexture.texture or PassTexture.texture, got {other}",
+            port.id
+        ),
+    }
+}
+
+/// Parse and fully validate the generated helper with naga.
+///
+/// The snippet must stay inside its function: a body that closes the function
+/// early to declare bindings, entry points or extra functions is rejected.
+fn validate_custom_wgsl_function(node: &Node, fn_decl: &str) -> Result<()> {
+    let module = validate_wgsl(fn_decl)
+        .with_context(|| format!("CustomWGSL source is invalid (node={})", node.id))?;
+    if !module.entry_points.is_empty()
+        || !module.global_variables.is_empty()
+        || module.functions.len() != 1
+    {
+        bail!(
+            "CustomWGSL source must be a single function body; top-level declarations are not allowed (node={})",
+            node.id
+        );
+    }
+    validate_wgsl_module(fn_decl)
+        .with_context(|| format!("CustomWGSL source is invalid (node={})", node.id))
+}
+
+/// Compile a CustomWGSL node into a helper function call bound to a local.
+///
+/// The helper signature is `fn <name>(uv: vec2f, <inputs...>) -> <outputType>`;
+/// value inputs keep their declared type and texture inputs become
+/// `<name>: texture_2d<f32>, <name>_sampler: sampler`.
+pub fn compile_custom_wgsl<F>(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+    stage: GlslShaderStage,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let source = node
+        .params
+        .get("source")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("CustomWGSL missing params.source (node={})", node.id))?;
+    let ret_ty = parse_output_type(node)?;
+
+    let mut fn_params = vec!["uv: vec2f".to_string()];
+    let mut call_args = vec![match stage {
+        GlslShaderStage::Vertex => "uv".to_string(),
+        _ => "in.uv".to_string(),
+    }];
+    let mut uses_time = false;
+
+    for port in &node.inputs {
+        let param_name = input_param_name(port);
+        match map_input_type(port)? {
+            CustomWgslInput::Texture => {
+                let (tex_var, samp_var) =
+                    resolve_texture_input(scene, nodes_by_id, node, port, ctx)?;
+                fn_params.push(format!("{param_name}: texture_2d<f32>"));
+                fn_params.push(format!("{param_name}_sampler: sampler"));
+                call_args.push(tex_var);
+                call_args.push(samp_var);
+            }
+            CustomWgslInput::Value(ty) => {
+                let arg = if let Some(conn) = incoming_connection(scene, &node.id, &port.id) {
+                    let compiled =
+                        compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+                    coerce_to_type(compiled, ty)?
+                } else {
+                    super::default_expr_for_type(ty)
+                };
+                uses_time |= arg.uses_time;
+                fn_params.push(format!("{param_name}: {}", ty.wgsl()));
+                call_args.push(arg.expr);
+            }
+        }
+    }
+
+    let fn_base = format!("cw_{}", super::readable_symbol_base(node, "output"));
+    let fn_name = ctx.allocate_local_name(&format!("fn:{}", node.id), &fn_base);
+    let body = source
+        .lines()
+        .map(|line| format!("    {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let fn_decl = format!(
+        "fn {fn_name}({}) -> {} {{\n{body}\n}}\n",
+        fn_params.join(", "),
+        ret_ty.wgsl()
+    );
+    validate_custom_wgsl_function(node, &fn_decl)?;
+    ctx.extra_wgsl_decls.insert(fn_name.clone(), fn_decl);
+
+    let output_var = super::readable_node_temp_name(ctx, "fs", node, "output", "out");
+    ctx.inline_stmts.push(format!(
+        "    let {output_var} = {fn_name}({});",
+        call_args.join(", ")
+    ));
+
+    Ok(TypedExpr::with_time(output_var, ret_ty, uses_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::node_compiler::test_utils::{test_connection, test_scene};
+    use serde_json::json;
+
+    fn node(id: &str, node_type: &str, params: serde_json::Value, inputs: Vec<NodePort>) -> Node {
+        Node {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            params: serde_json::from_value(params).unwrap(),
+            inputs,
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn port(id: &str, name: &str, ty: &str) -> NodePort {
+        NodePort {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            port_type: Some(ty.to_string()),
+            array_length: None,
+        }
+    }
+
+    fn mock_compile(
+        _node_id: &str,
+        _out_port: Option<&str>,
+        _ctx: &mut MaterialCompileContext,
+        _cache: &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr> {
+        Ok(TypedExpr::with_time("params.time", ValueType::F32, true))
+    }
+
+    fn compile(
+        nodes: Vec<Node>,
+        connections: Vec<crate::dsl::Connection>,
+    ) -> Result<(MaterialCompileContext, TypedExpr)> {
+        let scene = test_scene(nodes, connections);
+        let nodes_by_id: HashMap<String, Node> = scene
+            .nodes
+            .iter()
+            .cloned()
+            .map(|n| (n.id.clone(), n))
+            .collect();
+        let node = nodes_by_id.get("cw").unwrap().clone();
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+        let result = compile_custom_wgsl(
+            &scene,
+            &nodes_by_id,
+            &node,
+            Some("output"),
+            &mut ctx,
+            &mut cache,
+            mock_compile,
+            GlslShaderStage::Fragment,
+        )?;
+        Ok((ctx, result))
+    }
+
+    #[test]
+    fn test_custom_wgsl_declares_value_and_texture_parameters() {
+        let nodes = vec![
+            node("rp", "RenderPass", json!({}), Vec::new()),
+            node(
+                "cw",
+                "CustomWGSL",
+                json!({
+                    "outputType": "vec4",
+                    "source": "let c = textureSample(src, src_sampler, uv);\nreturn c * gain * amount;"
+                }),
+                vec![
+                    port("dynamic_1", "gain", "float"),
+                    port("dynamic_2", "amount", "float"),
+                    port("dynamic_3", "src", "pass"),
+                ],
+            ),
+        ];
+        let (ctx, result) = compile(
+            nodes,
+            vec![
+                test_connection("t", "time", "cw", "dynamic_1"),
+                test_connection("rp", "pass", "cw", "dynamic_3"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(result.ty, ValueType::Vec4);
+        assert!(result.uses_time);
+        let decl = ctx
+            .extra_wgsl_decls
+            .values()
+            .find(|decl| decl.starts_with("fn cw_"))
+            .expect("helper declared");
+        assert!(decl.contains(
+            "(uv: vec2f, gain: f32, amount: f32, src: texture_2d<f32>, src_sampler: sampler) -> vec4f"
+        ));
+        assert_eq!(ctx.pass_textures.len(), 1);
+
+        let stmt = ctx.inline_stmts.join("\n");
+        assert!(stmt.contains(&format!("let {} = cw_", result.expr)));
+        assert!(stmt.contains("(in.uv, params.time, 0.0, pass_tex_"));
+    }
+
+    #[test]
+    fn test_custom_wgsl_rejects_invalid_source() {
+        let nodes = vec![node(
+            "cw",
+            "CustomWGSL",
+            json!({"outputType": "float", "source": "return vec2f(uv);"}),
+            Vec::new(),
+        )];
+        let err = compile(nodes, Vec::new()).unwrap_err();
+        assert!(format!("{err:#}").contains("node=cw"));
+    }
+
+    #[test]
+    fn test_custom_wgsl_rejects_top_level_declarations() {
+        let nodes = vec![node(
+            "cw",
+            "CustomWGSL",
+            json!({
+                "outputType": "float",
+                "source": "return 0.0;\n}\n@group(3) @binding(0) var<uniform> leak: vec4f;\nfn extra() {"
+            }),
+            Vec::new(),
+        )];
+        let err = compile(nodes, Vec::new()).unwrap_err();
+        assert!(format!("{err:#}").contains("single function body"));
+    }
+}
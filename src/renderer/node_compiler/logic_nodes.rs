@@ -0,0 +1,389 @@
+//! Compilers for comparison and boolean logic nodes (Compare, BooleanMath, Switch).
+
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+
+use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
+use super::super::utils::{coerce_for_binary, coerce_to_type, fmt_f32};
+use crate::dsl::{Node, SceneDSL, incoming_connection, parse_f32, parse_str};
+
+/// Connected input, or the same-named numeric param, or `default`, as f32.
+fn compile_value_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    default: f32,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+        return compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache);
+    }
+    let v = parse_f32(&node.params, port_id).unwrap_or(default);
+    Ok(TypedExpr::new(fmt_f32(v), ValueType::F32))
+}
+
+/// Connected input as a bool (numeric scalars test `!= 0`), or the same-named
+/// bool param, or false.
+fn compile_bool_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let Some(conn) = incoming_connection(scene, &node.id, port_id) else {
+        let v = node
+            .params
+            .get(port_id)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        return Ok(TypedExpr::new(v.to_string(), ValueType::Bool));
+    };
+    let x = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+    let expr = match x.ty {
+        ValueType::Bool => return Ok(x),
+        ValueType::F32 => format!("({} != 0.0)", x.expr),
+        ValueType::I32 => format!("({} != 0)", x.expr),
+        ValueType::U32 => format!("({} != 0u)", x.expr),
+        other => bail!(
+            "{}: input '{port_id}' must be a bool or scalar, got {other:?}",
+            node.node_type
+        ),
+    };
+    Ok(TypedExpr::with_time(expr, ValueType::Bool, x.uses_time))
+}
+
+/// Compile a Compare node to WGSL.
+///
+/// Compares `a` and `b` with `operation` (`greater`, `less`, `greater_equal`,
+/// `less_equal`, `equal`, `not_equal`). Equality is within `epsilon` (default
+/// 1e-5). Vector operands compare component-wise and only pass when every
+/// component does.
+pub fn compile_compare<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let operation = parse_str(&node.params, "operation")
+        .unwrap_or("greater")
+        .trim()
+        .to_ascii_lowercase();
+    let op = match operation.as_str() {
+        "greater" | ">" => ">",
+        "less" | "<" => "<",
+        "greater_equal" | ">=" => ">=",
+        "less_equal" | "<=" => "<=",
+        "equal" | "==" => "==",
+        "not_equal" | "!=" => "!=",
+        _ => bail!("Compare: unsupported operation '{operation}'"),
+    };
+
+    let a = compile_value_input(scene, node, "a", 0.0, ctx, cache, &compile_fn)?;
+    let b = compile_value_input(scene, node, "b", 0.0, ctx, cache, &compile_fn)?;
+    let (a, b, ty) = coerce_for_binary(a, b)?;
+    let (a, b, ty) = if matches!(ty, ValueType::I32 | ValueType::U32 | ValueType::Bool) {
+        (
+            coerce_to_type(a, ValueType::F32)?,
+            coerce_to_type(b, ValueType::F32)?,
+            ValueType::F32,
+        )
+    } else {
+        (a, b, ty)
+    };
+    if !matches!(
+        ty,
+        ValueType::F32 | ValueType::Vec2 | ValueType::Vec3 | ValueType::Vec4
+    ) {
+        bail!("Compare: cannot compare values of type {ty:?}");
+    }
+    let uses_time = a.uses_time || b.uses_time;
+
+    let test = match op {
+        "==" | "!=" => {
+            let epsilon = parse_f32(&node.params, "epsilon").unwrap_or(1e-5).max(0.0);
+            let epsilon = coerce_to_type(TypedExpr::new(fmt_f32(epsilon), ValueType::F32), ty)?;
+            let cmp = if op == "==" { "<=" } else { ">" };
+            format!("(abs({} - {}) {cmp} {})", a.expr, b.expr, epsilon.expr)
+        }
+        _ => format!("({} {op} {})", a.expr, b.expr),
+    };
+    let expr = match (ty, op) {
+        (ValueType::F32, _) => test,
+        // Not-equal passes when any component differs.
+        (_, "!=") => format!("any{test}"),
+        _ => format!("all{test}"),
+    };
+    Ok(TypedExpr::with_time(expr, ValueType::Bool, uses_time))
+}
+
+/// Compile a BooleanMath node to WGSL.
+///
+/// Combines bool inputs `a` and `b` with `operation` (`and`, `or`, `xor`, or
+/// `not`, which only reads `a`).
+pub fn compile_boolean_math<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let operation = parse_str(&node.params, "operation")
+        .unwrap_or("and")
+        .trim()
+        .to_ascii_lowercase();
+    let op = match operation.as_str() {
+        "and" => "&&",
+        "or" => "||",
+        "xor" => "!=",
+        "not" => "!",
+        _ => bail!("BooleanMath: unsupported operation '{operation}'"),
+    };
+
+    let a = compile_bool_input(scene, node, "a", ctx, cache, &compile_fn)?;
+    if op == "!" {
+        return Ok(TypedExpr::with_time(
+            format!("(!{})", a.expr),
+            ValueType::Bool,
+            a.uses_time,
+        ));
+    }
+    let b = compile_bool_input(scene, node, "b", ctx, cache, &compile_fn)?;
+    Ok(TypedExpr::with_time(
+        format!("({} {op} {})", a.expr, b.expr),
+        ValueType::Bool,
+        a.uses_time || b.uses_time,
+    ))
+}
+
+/// Compile a Switch node to WGSL.
+///
+/// Picks `ifTrue` or `ifFalse` by the bool `condition` via `select()`. Both
+/// branches are evaluated; values are coerced like the binary math nodes.
+pub fn compile_switch<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let condition = compile_bool_input(scene, node, "condition", ctx, cache, &compile_fn)?;
+    let if_true = compile_value_input(scene, node, "ifTrue", 1.0, ctx, cache, &compile_fn)?;
+    let if_false = compile_value_input(scene, node, "ifFalse", 0.0, ctx, cache, &compile_fn)?;
+    let (if_true, if_false, ty) = coerce_for_binary(if_true, if_false)?;
+
+    Ok(TypedExpr::with_time(
+        format!(
+            "select({}, {}, {})",
+            if_false.expr, if_true.expr, condition.expr
+        ),
+        ty,
+        condition.uses_time || if_true.uses_time || if_false.uses_time,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::{test_connection, test_scene};
+    use super::*;
+
+    fn logic_node(node_type: &str, params: serde_json::Value) -> Node {
+        Node {
+            id: "logic".to_string(),
+            node_type: node_type.to_string(),
+            params: serde_json::from_value(params).unwrap(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn mock_compile_fn(
+        node_id: &str,
+        _port: Option<&str>,
+        _ctx: &mut MaterialCompileContext,
+        _cache: &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr> {
+        Ok(match node_id {
+            "color" => TypedExpr::new("vec4f(1.0, 0.5, 0.0, 1.0)", ValueType::Vec4),
+            "flag" => TypedExpr::new("true", ValueType::Bool),
+            _ => TypedExpr::new("0.5", ValueType::F32),
+        })
+    }
+
+    fn validate(expr: &TypedExpr) {
+        let value = match expr.ty {
+            ValueType::Bool => format!("select(0.0, 1.0, {})", expr.expr),
+            ValueType::Vec4 => format!("({}).x", expr.expr),
+            _ => expr.expr.clone(),
+        };
+        let module = format!(
+            "@fragment\nfn fs_main() -> @location(0) vec4f {{\n    return vec4f({value});\n}}\n"
+        );
+        crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+    }
+
+    #[test]
+    fn compare_operations_compile_for_scalars_and_vectors() {
+        let scene = test_scene(
+            vec![],
+            vec![
+                test_connection("color", "value", "logic", "a"),
+                test_connection("scalar", "value", "logic", "b"),
+            ],
+        );
+        for operation in [
+            "greater",
+            "less",
+            "greater_equal",
+            "less_equal",
+            "equal",
+            "not_equal",
+        ] {
+            let node = logic_node(
+                "Compare",
+                serde_json::json!({ "operation": operation, "epsilon": 0.01 }),
+            );
+            let result = compile_compare(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(result.ty, ValueType::Bool);
+            validate(&result);
+        }
+
+        let scalar_scene = test_scene(vec![], vec![]);
+        let node = logic_node(
+            "Compare",
+            serde_json::json!({ "operation": "equal", "a": 1.0, "b": 1.5, "epsilon": 0.5 }),
+        );
+        let result = compile_compare(
+            &scalar_scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut MaterialCompileContext::default(),
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(result.expr, "(abs(1.0 - 1.5) <= 0.5)");
+    }
+
+    #[test]
+    fn boolean_math_and_switch_compile() {
+        let scene = test_scene(
+            vec![],
+            vec![
+                test_connection("flag", "value", "logic", "a"),
+                test_connection("scalar", "value", "logic", "b"),
+                test_connection("flag", "value", "logic", "condition"),
+                test_connection("color", "value", "logic", "ifTrue"),
+            ],
+        );
+        for operation in ["and", "or", "xor", "not"] {
+            let node = logic_node("BooleanMath", serde_json::json!({ "operation": operation }));
+            let result = compile_boolean_math(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(result.ty, ValueType::Bool);
+            validate(&result);
+        }
+
+        let node = logic_node("Switch", serde_json::json!({ "ifFalse": 0.25 }));
+        let result = compile_switch(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut MaterialCompileContext::default(),
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(result.ty, ValueType::Vec4);
+        assert_eq!(
+            result.expr,
+            "select(vec4f(0.25), vec4f(1.0, 0.5, 0.0, 1.0), true)"
+        );
+        validate(&result);
+
+        let node = logic_node("BooleanMath", serde_json::json!({ "operation": "nand" }));
+        assert!(
+            compile_boolean_math(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,11 +1,11 @@
-//! Compilers for math operation nodes (MathAdd, MathSubtract, MathMultiply, MathDivide, Lerp, MathClamp, MathMax, MathPower).
+//! Compilers for math operation nodes (Math, MathAdd, MathSubtract, MathMultiply, MathDivide, Lerp, MathClamp, MathMax, MathPower).
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use std::collections::HashMap;
 
 use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
 use super::super::utils::{coerce_for_binary, coerce_to_type};
-use crate::dsl::{Node, SceneDSL, incoming_connection, parse_f32};
+use crate::dsl::{Node, SceneDSL, incoming_connection, math_operand_default, parse_f32};
 
 fn wgsl_f32_literal(v: f32) -> String {
     if v.is_finite() {
@@ -463,6 +463,125 @@ where
     Ok(acc_expr)
 }
 
+/// Operand count and WGSL template of a generic Math `operation`; `{a}`, `{b}`
+/// and `{c}` are replaced by the coerced operands.
+fn math_operation(operation: &str) -> Option<(usize, &'static str)> {
+    let op = match operation {
+        "add" => (2, "({a} + {b})"),
+        "subtract" => (2, "({a} - {b})"),
+        "multiply" => (2, "({a} * {b})"),
+        "divide" => (2, "({a} / {b})"),
+        // Truncated remainder, like C `fmod`: the result takes the sign of `a`.
+        "modulo" => (2, "({a} % {b})"),
+        "power" => (2, "pow({a}, {b})"),
+        "min" => (2, "min({a}, {b})"),
+        "max" => (2, "max({a}, {b})"),
+        "step" => (2, "step({a}, {b})"),
+        "atan2" => (2, "atan2({a}, {b})"),
+        "abs" => (1, "abs({a})"),
+        "floor" => (1, "floor({a})"),
+        "ceil" => (1, "ceil({a})"),
+        "fract" => (1, "fract({a})"),
+        "sqrt" => (1, "sqrt({a})"),
+        "log" => (1, "log({a})"),
+        "exp" => (1, "exp({a})"),
+        "sign" => (1, "sign({a})"),
+        "clamp" => (3, "clamp({a}, {b}, {c})"),
+        "smoothstep" => (3, "smoothstep({a}, {b}, {c})"),
+        "lerp" => (3, "mix({a}, {b}, {c})"),
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Compile a generic Math node to WGSL.
+///
+/// The `operation` param selects the function (`add`, `subtract`, `multiply`,
+/// `divide`, `modulo`, `power`, `min`, `max`, `step`, `atan2`, `abs`, `floor`,
+/// `ceil`, `fract`, `sqrt`, `log`, `exp`, `sign`, `clamp`, `smoothstep`,
+/// `lerp`). Operands come from ports `a`, `b` and `c`, falling back to the
+/// same-named params. Argument order follows WGSL: `step(edge, x)`,
+/// `atan2(y, x)`, `clamp(x, lo, hi)`, `smoothstep(lo, hi, x)`, `mix(a, b, t)`.
+/// Vector and scalar operands are coerced like the binary math nodes.
+pub fn compile_math<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let operation = node
+        .params
+        .get("operation")
+        .and_then(|v| v.as_str())
+        .unwrap_or("add")
+        .trim()
+        .to_ascii_lowercase();
+    let Some((arity, template)) = math_operation(&operation) else {
+        bail!("Math: unsupported operation '{operation}'");
+    };
+
+    let mut operands = Vec::with_capacity(arity);
+    for port_id in ["a", "b", "c"].into_iter().take(arity) {
+        let expr = if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+            compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?
+        } else {
+            let v = parse_f32(&node.params, port_id)
+                .unwrap_or_else(|| math_operand_default(&operation, port_id) as f32);
+            TypedExpr::new(wgsl_f32_literal(v), ValueType::F32)
+        };
+        operands.push(expr);
+    }
+
+    // Widest operand type wins; scalars (including lerp's t) are splatted to it.
+    let mut ty = ValueType::F32;
+    for operand in &operands {
+        let probe = TypedExpr::new(String::new(), ty);
+        let (_, _, widened) = coerce_for_binary(probe, operand.clone())?;
+        ty = widened;
+    }
+    let uses_time = operands.iter().any(|operand| operand.uses_time);
+    let args = operands
+        .into_iter()
+        .map(|operand| coerce_to_type(operand, ty).map(|operand| operand.expr))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(TypedExpr::with_time(
+        fill_math_template(template, &args),
+        ty,
+        uses_time,
+    ))
+}
+
+/// Substitutes `{a}`/`{b}`/`{c}` in one pass so operand text is never rescanned.
+fn fill_math_template(template: &str, args: &[String]) -> String {
+    let mut out =
+        String::with_capacity(template.len() + args.iter().map(String::len).sum::<usize>());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let index = match &rest[start..start + 3] {
+            "{a}" => 0,
+            "{b}" => 1,
+            _ => 2,
+        };
+        out.push_str(&args[index]);
+        rest = &rest[start + 3..];
+    }
+    out.push_str(rest);
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::super::types::ValueType;
@@ -488,6 +607,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
@@ -1095,4 +1215,110 @@ mod tests {
             "mix(vec4f(1.0, 0.0, 0.0, 1.0), vec4f(0.0, 0.0, 1.0, 1.0), vec4f(0.25))"
         );
     }
+
+    fn math_node(operation: &str) -> Node {
+        Node {
+            id: "math".to_string(),
+            node_type: "Math".to_string(),
+            params: HashMap::from([("operation".to_string(), serde_json::json!(operation))]),
+            inputs: vec![],
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    #[test]
+    fn test_math_operations_compile_to_valid_wgsl() {
+        use super::super::test_utils::{test_connection, test_scene};
+        // a: vec4 color, b: f32, c unconnected (param default).
+        let scene = test_scene(
+            vec![],
+            vec![
+                test_connection("color_a", "value", "math", "a"),
+                test_connection("t", "value", "math", "b"),
+            ],
+        );
+        for operation in [
+            "add",
+            "subtract",
+            "multiply",
+            "divide",
+            "modulo",
+            "power",
+            "min",
+            "max",
+            "step",
+            "atan2",
+            "abs",
+            "floor",
+            "ceil",
+            "fract",
+            "sqrt",
+            "log",
+            "exp",
+            "sign",
+            "clamp",
+            "smoothstep",
+            "lerp",
+        ] {
+            let node = math_node(operation);
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+            let result = compile_math(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut ctx,
+                &mut cache,
+                mock_compile_fn,
+            )
+            .unwrap_or_else(|e| panic!("{operation}: {e:#}"));
+            assert_eq!(result.ty, ValueType::Vec4, "{operation}");
+
+            let module = format!(
+                "@fragment\nfn fs_main() -> @location(0) vec4f {{\n    return {};\n}}\n",
+                result.expr
+            );
+            crate::renderer::validate_wgsl(&module)
+                .unwrap_or_else(|e| panic!("{operation}: {e:#}\n{module}"));
+        }
+    }
+
+    #[test]
+    fn test_math_unconnected_operands_use_params_and_defaults() {
+        use super::super::test_utils::{test_connection, test_scene};
+        let scene = test_scene(vec![], vec![test_connection("value", "value", "math", "a")]);
+        let mut node = math_node("clamp");
+        node.params.insert("b".to_string(), serde_json::json!(0.25));
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+
+        let result = compile_math(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut ctx,
+            &mut cache,
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(result.ty, ValueType::F32);
+        assert_eq!(result.expr, "clamp(0.5, 0.25, 1.0)");
+
+        let node = math_node("cbrt");
+        let err = compile_math(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut ctx,
+            &mut cache,
+            mock_compile_fn,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unsupported operation"));
+    }
 }
@@ -1,21 +1,29 @@
 //! Node compiler infrastructure and trait definition.
 
 pub mod attribute;
+pub mod color_curves;
 pub mod color_nodes;
+pub(crate) mod compile_cache;
+pub mod custom_wgsl;
 pub mod data_parse;
 pub mod geometry_nodes;
 pub mod glass_material;
 pub mod hyperos_glass_material;
 pub mod input_nodes;
+pub mod logic_nodes;
 pub mod luminance_curve;
 pub mod math_closure;
 pub mod math_nodes;
+pub mod noise_nodes;
+pub mod principled_material;
 pub mod remap_nodes;
 pub mod sdf_nodes;
 pub mod shader_material;
+pub mod shading_nodes;
 pub mod template_loader;
 pub mod texture_nodes;
 pub mod trigonometry_nodes;
+pub mod uv_nodes;
 pub mod vector_nodes;
 
 use anyhow::{Result, bail};
@@ -218,7 +226,7 @@ fn expanded_instance_id(node_id: &str) -> Option<&str> {
     node_id.split_once('/').map(|(instance_id, _)| instance_id)
 }
 
-fn value_type_from_port_type(port_type: Option<&str>) -> Option<ValueType> {
+pub(super) fn value_type_from_port_type(port_type: Option<&str>) -> Option<ValueType> {
     match port_type.unwrap_or("float") {
         "float" | "f32" | "number" => Some(ValueType::F32),
         "int" | "i32" => Some(ValueType::I32),
@@ -232,7 +240,7 @@ fn value_type_from_port_type(port_type: Option<&str>) -> Option<ValueType> {
     }
 }
 
-fn default_expr_for_type(ty: ValueType) -> TypedExpr {
+pub(super) fn default_expr_for_type(ty: ValueType) -> TypedExpr {
     match ty {
         ValueType::F32 => TypedExpr::new("0.0", ValueType::F32),
         ValueType::I32 => TypedExpr::new("0", ValueType::I32),
@@ -275,6 +283,7 @@ fn is_pure_group_node_type(node_type: &str) -> bool {
             | "Lerp"
             | "MathClosure"
             | "Remap"
+            | "MapRange"
             | "Sin"
             | "Cos"
             | "Tan"
@@ -287,6 +296,8 @@ fn is_pure_group_node_type(node_type: &str) -> bool {
             | "CrossProduct"
             | "Normalize"
             | "Refract"
+            | "VectorRotate"
+            | "VectorTransform"
             | "ColorMix"
             | "ColorRamp"
             | "HSVAdjust"
@@ -470,6 +481,7 @@ fn try_compile_pure_group_call(
         assets: scene.assets.clone(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     };
     let helper_nodes_by_id: HashMap<String, Node> = helper_scene
         .nodes
@@ -878,9 +890,18 @@ fn compile_expr(
         )?,
 
         // Attribute node
-        "Attribute" => attribute::compile_attribute(node, out_port)?,
+        "Attribute" => attribute::compile_attribute(node, out_port, ctx, stage)?,
 
         // Math nodes
+        "Math" => math_nodes::compile_math(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
         "MathAdd" => math_nodes::compile_math_add(
             scene,
             nodes_by_id,
@@ -947,6 +968,35 @@ fn compile_expr(
         "Lerp" => {
             math_nodes::compile_lerp(scene, nodes_by_id, node, out_port, ctx, cache, compile_fn)?
         }
+
+        // Logic nodes
+        "Compare" => logic_nodes::compile_compare(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "BooleanMath" => logic_nodes::compile_boolean_math(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "Switch" => logic_nodes::compile_switch(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
         "MathClosure" => math_closure::compile_math_closure(
             scene,
             nodes_by_id,
@@ -957,10 +1007,29 @@ fn compile_expr(
             compile_fn,
             stage,
         )?,
+        "CustomWGSL" => custom_wgsl::compile_custom_wgsl(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+            stage,
+        )?,
 
         "Remap" => {
             remap_nodes::compile_remap(scene, nodes_by_id, node, out_port, ctx, cache, compile_fn)?
         }
+        "MapRange" => remap_nodes::compile_map_range(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
 
         // Texture nodes
         "ImageTexture" => texture_nodes::compile_image_texture(
@@ -972,6 +1041,42 @@ fn compile_expr(
             cache,
             compile_fn,
         )?,
+        "GradientTexture" => texture_nodes::compile_gradient_texture(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "CheckerTexture" => texture_nodes::compile_checker_texture(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "BrickTexture" => texture_nodes::compile_brick_texture(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "WaveTexture" => texture_nodes::compile_wave_texture(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
         "PassTexture" => texture_nodes::compile_pass_texture(
             scene,
             nodes_by_id,
@@ -981,6 +1086,33 @@ fn compile_expr(
             cache,
             compile_fn,
         )?,
+        "NoiseTexture" => noise_nodes::compile_noise_texture(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "Voronoi" => noise_nodes::compile_voronoi(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "WhiteNoise" => noise_nodes::compile_white_noise(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
         "Matcap" => texture_nodes::compile_matcap(
             scene,
             nodes_by_id,
@@ -990,6 +1122,25 @@ fn compile_expr(
             cache,
             compile_fn,
         )?,
+        "EnvironmentTexture" => texture_nodes::compile_environment_texture(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
+        "Text" => texture_nodes::compile_text(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
 
         // Material nodes
         "GlassMaterial" => glass_material::compile_glass_material(
@@ -1010,6 +1161,15 @@ fn compile_expr(
             cache,
             compile_fn,
         )?,
+        "PrincipledMaterial" => principled_material::compile_principled_material(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
         "ShaderMaterial" => shader_material::compile_shader_material(
             scene,
             nodes_by_id,
@@ -1134,8 +1294,110 @@ fn compile_expr(
             compile_fn,
         )?,
 
+        "VectorRotate" => vector_nodes::compile_vector_rotate(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
+        "VectorTransform" => vector_nodes::compile_vector_transform(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
         "ViewVector" => vector_nodes::compile_view_vector(node, out_port, ctx)?,
 
+        // Shading nodes
+        "Fresnel" => shading_nodes::compile_fresnel(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
+        "LayerWeight" => shading_nodes::compile_layer_weight(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
+        "NormalMap" => shading_nodes::compile_normal_map(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
+        "Bump" => shading_nodes::compile_bump(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
+        "ScreenDerivative" => shading_nodes::compile_screen_derivative(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
+        "Facing" => shading_nodes::compile_facing(node, out_port, ctx)?,
+
+        // UV utility nodes
+        "UVTransform" => uv_nodes::compile_uv_transform(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "PolarCoordinates" => uv_nodes::compile_polar_coordinates(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "PixelateUV" => uv_nodes::compile_pixelate_uv(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
         // Color nodes
         "ColorMix" => color_nodes::compile_color_mix(
             scene,
@@ -1173,6 +1435,60 @@ fn compile_expr(
             cache,
             compile_fn,
         )?,
+        "ToneMap" => color_nodes::compile_tone_map(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "Exposure" => color_nodes::compile_exposure(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "Gamma" => color_nodes::compile_gamma(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "BrightContrast" => color_nodes::compile_bright_contrast(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "SeparateColor" => color_nodes::compile_separate_color(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+        "CombineColor" => color_nodes::compile_combine_color(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
         "LuminanceCurve" => luminance_curve::compile_luminance_curve(
             scene,
             nodes_by_id,
@@ -1182,6 +1498,15 @@ fn compile_expr(
             cache,
             compile_fn,
         )?,
+        "ColorCurves" => color_curves::compile_color_curves(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
 
         // SDF nodes
         "Sdf2D" => {
@@ -1198,6 +1523,26 @@ fn compile_expr(
             compile_fn,
         )?,
 
+        "SdfBoolean" => sdf_nodes::compile_sdf_boolean(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
+        "SdfTransform" => sdf_nodes::compile_sdf_transform(
+            scene,
+            nodes_by_id,
+            node,
+            out_port,
+            ctx,
+            cache,
+            compile_fn,
+        )?,
+
         "DataParse" => data_parse::compile_data_parse(
             scene,
             nodes_by_id,
@@ -1247,6 +1592,7 @@ pub mod test_utils {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
@@ -1270,6 +1616,7 @@ pub mod test_utils {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
@@ -1344,6 +1691,7 @@ mod readability_tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
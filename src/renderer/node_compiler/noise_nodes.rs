@@ -0,0 +1,786 @@
+//! Compilers for the procedural noise family (NoiseTexture, Voronoi, WhiteNoise).
+//!
+//! Procedural 2D noise evaluated per fragment, so looks that used to need a
+//! baked ImageTexture stay live in the graph. All three nodes share the PCG
+//! hash helpers in `NOISE_COMMON_WGSL`.
+//!
+//! NoiseTexture params / inputs:
+//! - `noiseType`: `"perlin"` (default), `"simplex"` or `"value"`
+//! - `uv`: optional coordinate input, defaults to `in.uv`
+//! - `scale`: coordinate multiplier (default 5)
+//! - `detail`: extra fBm octaves; `floor(detail) + 1` octaves, clamped to 1..16
+//! - `roughness`: amplitude gain per octave (default 0.5)
+//! - `seed`: integer offset into the hash lattice (param only)
+//!
+//! Outputs `factor` (f32 in 0..1) and `color` (three decorrelated channels,
+//! alpha 1). Helpers are emitted once per algorithm into `extra_wgsl_decls`.
+//!
+//! Voronoi params / inputs:
+//! - `feature`: `"f1"` (default), `"f2"` or `"smoothF1"`
+//! - `uv`, `scale`, `seed`: as for NoiseTexture
+//! - `randomness`: jitter of the feature points, 0 gives a square grid (default 1)
+//! - `smoothness`: blend width for `smoothF1` (default 0.25)
+//!
+//! Outputs `distance` (in scaled units), `color` (random per cell) and
+//! `position` (the selected feature point, back in `uv` space).
+//!
+//! WhiteNoise params / inputs:
+//! - `value`: f32, vec2, vec3, vec4, i32 or u32 key (default `in.uv`); the raw
+//!   bits are hashed, so wiring the Index node gives a stable value per instance
+//! - `seed`: integer mixed into the key (param only)
+//!
+//! Outputs `value` (f32 in 0..1) and `color` (three decorrelated channels,
+//! alpha 1), hashed with the common `nf_pcg4d`.
+
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+
+use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
+use super::super::utils::{coerce_to_type, fmt_f32};
+use crate::dsl::{Node, SceneDSL, incoming_connection, parse_f32, parse_str, parse_u32};
+
+const MAX_OCTAVES: u32 = 16;
+
+const NOISE_COMMON_WGSL_KEY: &str = "noise_common_lib";
+const NOISE_COMMON_WGSL: &str = r#"
+fn nf_pcg2d(v_in: vec2u) -> vec2u {
+    var v = v_in * 1664525u + 1013904223u;
+    v.x = v.x + v.y * 1664525u;
+    v.y = v.y + v.x * 1664525u;
+    v = v ^ (v >> vec2u(16u));
+    v.x = v.x + v.y * 1664525u;
+    v.y = v.y + v.x * 1664525u;
+    v = v ^ (v >> vec2u(16u));
+    return v;
+}
+
+fn nf_hash22(p: vec2f) -> vec2f {
+    let h = nf_pcg2d(bitcast<vec2u>(vec2i(floor(p))));
+    return vec2f(h) * (1.0 / 4294967295.0);
+}
+
+fn nf_grad2(i: vec2f, f: vec2f) -> f32 {
+    let angle = nf_hash22(i).x * 6.283185307179586;
+    return dot(vec2f(cos(angle), sin(angle)), f);
+}
+
+fn nf_pcg4d(v_in: vec4u) -> vec4u {
+    var v = v_in * 1664525u + 1013904223u;
+    v.x = v.x + v.y * v.w;
+    v.y = v.y + v.z * v.x;
+    v.z = v.z + v.x * v.y;
+    v.w = v.w + v.y * v.z;
+    v = v ^ (v >> vec4u(16u));
+    v.x = v.x + v.y * v.w;
+    v.y = v.y + v.z * v.x;
+    v.z = v.z + v.x * v.y;
+    v.w = v.w + v.y * v.z;
+    return v;
+}
+"#;
+
+const VORONOI_WGSL_KEY: &str = "voronoi_lib";
+const VORONOI_WGSL: &str = r#"
+fn nf_voronoi_point(cell: vec2f, randomness: f32) -> vec2f {
+    return cell + nf_hash22(cell) * randomness;
+}
+
+// xy: feature point, z: distance.
+fn nf_voronoi_f1(p: vec2f, randomness: f32) -> vec3f {
+    let base = floor(p);
+    var best = vec3f(0.0, 0.0, 1e9);
+    for (var y = -1; y <= 1; y = y + 1) {
+        for (var x = -1; x <= 1; x = x + 1) {
+            let pt = nf_voronoi_point(base + vec2f(f32(x), f32(y)), randomness);
+            let d = length(pt - p);
+            if (d < best.z) {
+                best = vec3f(pt, d);
+            }
+        }
+    }
+    return best;
+}
+
+fn nf_voronoi_f2(p: vec2f, randomness: f32) -> vec3f {
+    let base = floor(p);
+    var first = vec3f(0.0, 0.0, 1e9);
+    var second = vec3f(0.0, 0.0, 1e9);
+    for (var y = -1; y <= 1; y = y + 1) {
+        for (var x = -1; x <= 1; x = x + 1) {
+            let pt = nf_voronoi_point(base + vec2f(f32(x), f32(y)), randomness);
+            let d = length(pt - p);
+            if (d < first.z) {
+                second = first;
+                first = vec3f(pt, d);
+            } else if (d < second.z) {
+                second = vec3f(pt, d);
+            }
+        }
+    }
+    return second;
+}
+
+fn nf_voronoi_smooth_f1(p: vec2f, randomness: f32, smoothness: f32) -> vec3f {
+    let base = floor(p);
+    let k = max(smoothness, 1e-5);
+    var dist = 8.0;
+    var position = vec2f(0.0);
+    for (var y = -2; y <= 2; y = y + 1) {
+        for (var x = -2; x <= 2; x = x + 1) {
+            let pt = nf_voronoi_point(base + vec2f(f32(x), f32(y)), randomness);
+            let d = length(pt - p);
+            let h = smoothstep(0.0, 1.0, 0.5 + 0.5 * (dist - d) / k);
+            let correction = h * (1.0 - h) * k / (1.0 + 3.0 * k);
+            dist = mix(dist, d, h) - correction;
+            position = mix(position, pt, h);
+        }
+    }
+    return vec3f(position, dist);
+}
+
+fn nf_voronoi_cell_color(pt: vec2f) -> vec4f {
+    let cell = floor(pt);
+    return vec4f(nf_hash22(cell), nf_hash22(cell + vec2f(17.0, 59.0)).x, 1.0);
+}
+"#;
+
+const WHITE_NOISE_WGSL_KEY: &str = "white_noise_lib";
+const WHITE_NOISE_WGSL: &str = r#"
+fn nf_white_noise(key: vec4u) -> vec3f {
+    return vec3f(nf_pcg4d(key).xyz) * (1.0 / 4294967295.0);
+}
+"#;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum VoronoiFeature {
+    F1,
+    F2,
+    SmoothF1,
+}
+
+impl VoronoiFeature {
+    fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "f2" => Self::F2,
+            "smoothf1" | "smooth_f1" | "smooth-f1" => Self::SmoothF1,
+            _ => Self::F1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum NoiseType {
+    Perlin,
+    Simplex,
+    Value,
+}
+
+impl NoiseType {
+    fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "simplex" => Self::Simplex,
+            "value" => Self::Value,
+            _ => Self::Perlin,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Perlin => "perlin",
+            Self::Simplex => "simplex",
+            Self::Value => "value",
+        }
+    }
+
+    /// Single-octave noise remapped to 0..1.
+    fn base_wgsl(self) -> &'static str {
+        match self {
+            Self::Perlin => {
+                r#"
+fn nf_perlin2(p: vec2f) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    let a = nf_grad2(i, f);
+    let b = nf_grad2(i + vec2f(1.0, 0.0), f - vec2f(1.0, 0.0));
+    let c = nf_grad2(i + vec2f(0.0, 1.0), f - vec2f(0.0, 1.0));
+    let d = nf_grad2(i + vec2f(1.0, 1.0), f - vec2f(1.0, 1.0));
+    // Unit gradients bound the lattice blend to +-sqrt(0.5).
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y) * 0.70710678 + 0.5;
+}
+"#
+            }
+            Self::Simplex => {
+                r#"
+fn nf_simplex2(p: vec2f) -> f32 {
+    let k1 = 0.366025403784;
+    let k2 = 0.211324865405;
+    let i = floor(p + (p.x + p.y) * k1);
+    let a = p - i + (i.x + i.y) * k2;
+    let o = select(vec2f(0.0, 1.0), vec2f(1.0, 0.0), a.x > a.y);
+    let b = a - o + k2;
+    let c = a - 1.0 + 2.0 * k2;
+    let h = max(vec3f(0.5) - vec3f(dot(a, a), dot(b, b), dot(c, c)), vec3f(0.0));
+    let n = h * h * h * h * vec3f(nf_grad2(i, a), nf_grad2(i + o, b), nf_grad2(i + vec2f(1.0), c));
+    return clamp(dot(n, vec3f(70.0)) * 0.5 + 0.5, 0.0, 1.0);
+}
+"#
+            }
+            Self::Value => {
+                r#"
+fn nf_value2(p: vec2f) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = nf_hash22(i).x;
+    let b = nf_hash22(i + vec2f(1.0, 0.0)).x;
+    let c = nf_hash22(i + vec2f(0.0, 1.0)).x;
+    let d = nf_hash22(i + vec2f(1.0, 1.0)).x;
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+"#
+            }
+        }
+    }
+
+    fn fbm_wgsl(self) -> String {
+        let name = self.name();
+        format!(
+            r#"
+fn nf_fbm_{name}(p: vec2f, octaves: f32, roughness: f32) -> f32 {{
+    var sum = 0.0;
+    var amplitude = 1.0;
+    var norm = 0.0;
+    var frequency = 1.0;
+    for (var i = 0u; i < {MAX_OCTAVES}u; i = i + 1u) {{
+        if (f32(i) >= octaves) {{
+            break;
+        }}
+        sum = sum + amplitude * nf_{name}2(p * frequency);
+        norm = norm + amplitude;
+        amplitude = amplitude * roughness;
+        frequency = frequency * 2.0;
+    }}
+    return sum / max(norm, 1e-6);
+}}
+"#
+        )
+    }
+}
+
+fn ensure_noise_common_wgsl(ctx: &mut MaterialCompileContext) {
+    ctx.extra_wgsl_decls
+        .entry(NOISE_COMMON_WGSL_KEY.to_string())
+        .or_insert_with(|| NOISE_COMMON_WGSL.to_string());
+}
+
+fn ensure_noise_wgsl_lib(ctx: &mut MaterialCompileContext, noise_type: NoiseType) {
+    ensure_noise_common_wgsl(ctx);
+    ctx.extra_wgsl_decls
+        .entry(format!("noise_{}_lib", noise_type.name()))
+        .or_insert_with(|| format!("{}{}", noise_type.base_wgsl(), noise_type.fbm_wgsl()));
+}
+
+fn resolve_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    ty: ValueType,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<Option<TypedExpr>>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let Some(conn) = incoming_connection(scene, &node.id, port_id) else {
+        return Ok(None);
+    };
+    let expr = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+    coerce_to_type(expr, ty).map(Some)
+}
+
+/// Maps a seed to a lattice offset far enough apart that neighbouring seeds
+/// do not visibly correlate.
+fn seed_offset(seed: u32) -> [f32; 2] {
+    let s = seed as f32;
+    [(s * 127.1) % 4096.0, (s * 311.7) % 4096.0]
+}
+
+pub fn compile_noise_texture<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("factor");
+    if port != "factor" && port != "color" {
+        bail!("NoiseTexture: unsupported output port '{port}'");
+    }
+
+    let noise_type = NoiseType::parse(parse_str(&node.params, "noiseType").unwrap_or("perlin"));
+    ensure_noise_wgsl_lib(ctx, noise_type);
+
+    let uv = resolve_input(scene, node, "uv", ValueType::Vec2, ctx, cache, &compile_fn)?
+        .unwrap_or_else(|| TypedExpr::new("in.uv", ValueType::Vec2));
+    let scale = resolve_input(
+        scene,
+        node,
+        "scale",
+        ValueType::F32,
+        ctx,
+        cache,
+        &compile_fn,
+    )?
+    .unwrap_or_else(|| {
+        let v = parse_f32(&node.params, "scale").unwrap_or(5.0);
+        TypedExpr::new(fmt_f32(v), ValueType::F32)
+    });
+    let detail = resolve_input(
+        scene,
+        node,
+        "detail",
+        ValueType::F32,
+        ctx,
+        cache,
+        &compile_fn,
+    )?
+    .unwrap_or_else(|| {
+        let v = parse_f32(&node.params, "detail").unwrap_or(2.0);
+        TypedExpr::new(fmt_f32(v), ValueType::F32)
+    });
+    let roughness = resolve_input(
+        scene,
+        node,
+        "roughness",
+        ValueType::F32,
+        ctx,
+        cache,
+        &compile_fn,
+    )?
+    .unwrap_or_else(|| {
+        let v = parse_f32(&node.params, "roughness").unwrap_or(0.5);
+        TypedExpr::new(fmt_f32(v), ValueType::F32)
+    });
+    let [seed_x, seed_y] = seed_offset(parse_u32(&node.params, "seed").unwrap_or(0));
+
+    let uses_time = uv.uses_time || scale.uses_time || detail.uses_time || roughness.uses_time;
+    let coord = format!(
+        "(({}) * ({}) + vec2f({}, {}))",
+        uv.expr,
+        scale.expr,
+        fmt_f32(seed_x),
+        fmt_f32(seed_y)
+    );
+    let octaves = format!(
+        "clamp(floor({}) + 1.0, 1.0, {}.0)",
+        detail.expr, MAX_OCTAVES
+    );
+    let roughness = format!("clamp({}, 0.0, 1.0)", roughness.expr);
+    let fbm = |offset: &str| {
+        format!(
+            "nf_fbm_{}({coord}{offset}, {octaves}, {roughness})",
+            noise_type.name()
+        )
+    };
+
+    let expr = if port == "factor" {
+        TypedExpr::with_time(fbm(""), ValueType::F32, uses_time)
+    } else {
+        TypedExpr::with_time(
+            format!(
+                "vec4f({}, {}, {}, 1.0)",
+                fbm(""),
+                fbm(" + vec2f(19.19, 7.31)"),
+                fbm(" + vec2f(-33.71, 51.97)")
+            ),
+            ValueType::Vec4,
+            uses_time,
+        )
+    };
+    Ok(expr)
+}
+
+/// Connected input coerced to f32, falling back to the `port_id` param.
+fn resolve_f32_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    default: f32,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    Ok(
+        resolve_input(scene, node, port_id, ValueType::F32, ctx, cache, compile_fn)?
+            .unwrap_or_else(|| {
+                let v = parse_f32(&node.params, port_id).unwrap_or(default);
+                TypedExpr::new(fmt_f32(v), ValueType::F32)
+            }),
+    )
+}
+
+pub fn compile_voronoi<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("distance");
+    if !matches!(port, "distance" | "color" | "position") {
+        bail!("Voronoi: unsupported output port '{port}'");
+    }
+
+    ensure_noise_common_wgsl(ctx);
+    ctx.extra_wgsl_decls
+        .entry(VORONOI_WGSL_KEY.to_string())
+        .or_insert_with(|| VORONOI_WGSL.to_string());
+
+    let feature = VoronoiFeature::parse(parse_str(&node.params, "feature").unwrap_or("f1"));
+    let uv = resolve_input(scene, node, "uv", ValueType::Vec2, ctx, cache, &compile_fn)?
+        .unwrap_or_else(|| TypedExpr::new("in.uv", ValueType::Vec2));
+    let scale = resolve_f32_input(scene, node, "scale", 5.0, ctx, cache, &compile_fn)?;
+    let randomness = resolve_f32_input(scene, node, "randomness", 1.0, ctx, cache, &compile_fn)?;
+    let smoothness = if feature == VoronoiFeature::SmoothF1 {
+        Some(resolve_f32_input(
+            scene,
+            node,
+            "smoothness",
+            0.25,
+            ctx,
+            cache,
+            &compile_fn,
+        )?)
+    } else {
+        None
+    };
+    let [seed_x, seed_y] = seed_offset(parse_u32(&node.params, "seed").unwrap_or(0));
+
+    let uses_time = uv.uses_time
+        || scale.uses_time
+        || randomness.uses_time
+        || smoothness.as_ref().is_some_and(|s| s.uses_time);
+    let seed = format!("vec2f({}, {})", fmt_f32(seed_x), fmt_f32(seed_y));
+    let coord = format!("(({}) * ({}) + {seed})", uv.expr, scale.expr);
+    let randomness = format!("clamp({}, 0.0, 1.0)", randomness.expr);
+    let cell = match (feature, smoothness) {
+        (VoronoiFeature::SmoothF1, Some(smoothness)) => format!(
+            "nf_voronoi_smooth_f1({coord}, {randomness}, {})",
+            smoothness.expr
+        ),
+        (VoronoiFeature::F2, _) => format!("nf_voronoi_f2({coord}, {randomness})"),
+        _ => format!("nf_voronoi_f1({coord}, {randomness})"),
+    };
+
+    let expr = match port {
+        "distance" => TypedExpr::with_time(format!("{cell}.z"), ValueType::F32, uses_time),
+        "color" => TypedExpr::with_time(
+            format!("nf_voronoi_cell_color({cell}.xy)"),
+            ValueType::Vec4,
+            uses_time,
+        ),
+        _ => TypedExpr::with_time(
+            format!("(({cell}.xy - {seed}) / ({}))", scale.expr),
+            ValueType::Vec2,
+            uses_time,
+        ),
+    };
+    Ok(expr)
+}
+
+pub fn compile_white_noise<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("value");
+    if port != "value" && port != "color" {
+        bail!("WhiteNoise: unsupported output port '{port}'");
+    }
+
+    ensure_noise_common_wgsl(ctx);
+    ctx.extra_wgsl_decls
+        .entry(WHITE_NOISE_WGSL_KEY.to_string())
+        .or_insert_with(|| WHITE_NOISE_WGSL.to_string());
+
+    let value = match incoming_connection(scene, &node.id, "value") {
+        Some(conn) => compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?,
+        None => TypedExpr::new("in.uv", ValueType::Vec2),
+    };
+    let seed = format!("{}u", parse_u32(&node.params, "seed").unwrap_or(0));
+    let v = &value.expr;
+    let key = match value.ty {
+        ValueType::U32 => format!("vec4u({v}, 0u, 0u, {seed})"),
+        ValueType::I32 => format!("vec4u(bitcast<u32>({v}), 0u, 0u, {seed})"),
+        ValueType::F32 => format!("vec4u(bitcast<u32>({v}), 0u, 0u, {seed})"),
+        ValueType::Vec2 => format!("vec4u(bitcast<vec2u>({v}), 0u, {seed})"),
+        ValueType::Vec3 => format!("vec4u(bitcast<vec3u>({v}), {seed})"),
+        ValueType::Vec4 => format!("(bitcast<vec4u>({v}) ^ vec4u(0u, 0u, 0u, {seed}))"),
+        other => bail!("WhiteNoise.value must be a scalar or vector, got {other:?}"),
+    };
+
+    let hash = format!("nf_white_noise({key})");
+    let expr = if port == "value" {
+        TypedExpr::with_time(format!("{hash}.x"), ValueType::F32, value.uses_time)
+    } else {
+        TypedExpr::with_time(
+            format!("vec4f({hash}, 1.0)"),
+            ValueType::Vec4,
+            value.uses_time,
+        )
+    };
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::node_compiler::test_utils::test_scene;
+    use anyhow::bail;
+
+    fn noise_node(noise_type: &str) -> Node {
+        Node {
+            id: "noise".to_string(),
+            node_type: "NoiseTexture".to_string(),
+            params: HashMap::from([
+                ("noiseType".to_string(), serde_json::json!(noise_type)),
+                ("scale".to_string(), serde_json::json!(8.0)),
+                ("detail".to_string(), serde_json::json!(3.0)),
+                ("seed".to_string(), serde_json::json!(7)),
+            ]),
+            inputs: vec![],
+            input_bindings: vec![],
+            outputs: vec![],
+            wgsl_override: None,
+        }
+    }
+
+    fn no_upstream(
+        node_id: &str,
+        _port: Option<&str>,
+        _ctx: &mut MaterialCompileContext,
+        _cache: &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr> {
+        bail!("unexpected upstream node {node_id}")
+    }
+
+    #[test]
+    fn every_noise_type_compiles_to_valid_wgsl() {
+        for noise_type in ["perlin", "simplex", "value"] {
+            let node = noise_node(noise_type);
+            let scene = test_scene(vec![node.clone()], vec![]);
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+            let factor = compile_noise_texture(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some("factor"),
+                &mut ctx,
+                &mut cache,
+                no_upstream,
+            )
+            .unwrap();
+            let color = compile_noise_texture(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some("color"),
+                &mut ctx,
+                &mut cache,
+                no_upstream,
+            )
+            .unwrap();
+            assert_eq!(factor.ty, ValueType::F32);
+            assert_eq!(color.ty, ValueType::Vec4);
+            assert!(factor.expr.contains(&format!("nf_fbm_{noise_type}(")));
+
+            let decls: String = ctx.extra_wgsl_decls.values().cloned().collect();
+            let module = format!(
+                "struct VSOut {{ @builtin(position) position: vec4f, @location(0) uv: vec2f }};\n{decls}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    return {} * {};\n}}\n",
+                color.expr, factor.expr
+            );
+            crate::renderer::validate_wgsl(&module)
+                .unwrap_or_else(|e| panic!("{noise_type}: {e:#}"));
+        }
+    }
+
+    #[test]
+    fn helpers_are_emitted_once_per_algorithm() {
+        let node = noise_node("value");
+        let scene = test_scene(vec![node.clone()], vec![]);
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+        for _ in 0..2 {
+            compile_noise_texture(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut ctx,
+                &mut cache,
+                no_upstream,
+            )
+            .unwrap();
+        }
+        assert_eq!(ctx.extra_wgsl_decls.len(), 2);
+    }
+
+    #[test]
+    fn every_voronoi_feature_compiles_to_valid_wgsl() {
+        for feature in ["f1", "f2", "smoothF1"] {
+            let mut node = noise_node("perlin");
+            node.id = "voronoi".to_string();
+            node.node_type = "Voronoi".to_string();
+            node.params
+                .insert("feature".to_string(), serde_json::json!(feature));
+            let scene = test_scene(vec![node.clone()], vec![]);
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+            let mut compile = |port: &str| {
+                compile_voronoi(
+                    &scene,
+                    &HashMap::new(),
+                    &node,
+                    Some(port),
+                    &mut ctx,
+                    &mut cache,
+                    no_upstream,
+                )
+                .unwrap()
+            };
+            let distance = compile("distance");
+            let color = compile("color");
+            let position = compile("position");
+            assert_eq!(distance.ty, ValueType::F32);
+            assert_eq!(color.ty, ValueType::Vec4);
+            assert_eq!(position.ty, ValueType::Vec2);
+
+            assert!(ctx.extra_wgsl_decls.contains_key(NOISE_COMMON_WGSL_KEY));
+            let decls: String = ctx.extra_wgsl_decls.values().cloned().collect();
+            let module = format!(
+                "struct VSOut {{ @builtin(position) position: vec4f, @location(0) uv: vec2f }};\n{decls}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    return {} * {} + vec4f({}, 0.0, 0.0);\n}}\n",
+                color.expr, distance.expr, position.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{feature}: {e:#}"));
+        }
+    }
+
+    #[test]
+    fn white_noise_hashes_every_key_type_to_valid_wgsl() {
+        use crate::renderer::node_compiler::test_utils::test_connection;
+        let node = Node {
+            id: "white".to_string(),
+            node_type: "WhiteNoise".to_string(),
+            params: HashMap::from([("seed".to_string(), serde_json::json!(3))]),
+            inputs: vec![],
+            input_bindings: vec![],
+            outputs: vec![],
+            wgsl_override: None,
+        };
+        let scene = test_scene(
+            vec![node.clone()],
+            vec![test_connection("key", "value", "white", "value")],
+        );
+        for (ty, expr) in [
+            (ValueType::U32, "instance_index"),
+            (ValueType::I32, "i32(instance_index)"),
+            (ValueType::F32, "in.uv.x"),
+            (ValueType::Vec2, "in.uv"),
+            (ValueType::Vec3, "vec3f(in.uv, 1.0)"),
+            (ValueType::Vec4, "vec4f(in.uv, 1.0, 2.0)"),
+        ] {
+            let upstream = |_: &str,
+                            _: Option<&str>,
+                            _: &mut MaterialCompileContext,
+                            _: &mut HashMap<(String, String), TypedExpr>|
+             -> Result<TypedExpr> { Ok(TypedExpr::new(expr, ty)) };
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+            let value = compile_white_noise(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut ctx,
+                &mut cache,
+                upstream,
+            )
+            .unwrap();
+            let color = compile_white_noise(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some("color"),
+                &mut ctx,
+                &mut cache,
+                upstream,
+            )
+            .unwrap();
+            assert_eq!(value.ty, ValueType::F32);
+            assert_eq!(color.ty, ValueType::Vec4);
+            assert_eq!(ctx.extra_wgsl_decls.len(), 2);
+            assert!(value.expr.contains("3u"), "{ty:?}: {}", value.expr);
+
+            let decls: String = ctx.extra_wgsl_decls.values().cloned().collect();
+            let module = format!(
+                "struct VSOut {{ @builtin(position) position: vec4f, @location(0) uv: vec2f, @location(1) @interpolate(flat) instance_index: u32 }};\n{decls}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    let instance_index = in.instance_index;\n    return {} * {};\n}}\n",
+                color.expr, value.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{ty:?}: {e:#}"));
+        }
+    }
+
+    #[test]
+    fn seeds_shift_the_lattice() {
+        assert_eq!(seed_offset(0), [0.0, 0.0]);
+        assert_ne!(seed_offset(1), seed_offset(2));
+    }
+}
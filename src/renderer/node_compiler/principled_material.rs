@@ -0,0 +1,336 @@
+//! Principled (metallic-roughness) material node.
+//!
+//! Evaluates a Cook-Torrance BRDF (GGX distribution, height-correlated Smith visibility,
+//! Schlick Fresnel) plus a Lambert diffuse lobe for one directional light and a flat ambient
+//! term, and returns a premultiplied `vec4f` like the other material nodes. Inputs follow the
+//! glTF metallic-roughness model so authored materials map one-to-one.
+//!
+//! Lighting conventions:
+//! - `lightDirection` points from the surface towards the light; `lightIntensity` is scaled so
+//!   a white diffuse surface facing the light reaches `lightIntensity * lightColor`.
+//! - The normal and view direction resolve like the shading nodes (see `shading_nodes`), so
+//!   `NormalMap` / `Bump` outputs plug straight into `normal`.
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
+use super::super::utils::{coerce_to_type, fmt_f32};
+use super::shading_nodes::{resolve_surface_normal, resolve_view_direction};
+use super::vector_nodes::{resolve_f32_input, resolve_vec3_input};
+use crate::dsl::{Node, SceneDSL, incoming_connection};
+
+const PRINCIPLED_WGSL_LIB_KEY: &str = "principled_material_lib";
+
+const PRINCIPLED_WGSL_LIB: &str = r#"
+// ---- PrincipledMaterial helpers (generated) ----
+
+const PRINCIPLED_PI: f32 = 3.14159265359;
+
+fn principled_shade(
+    base: vec4f,
+    metallic: f32,
+    roughness: f32,
+    emission: vec3f,
+    normal: vec3f,
+    view: vec3f,
+    light_dir: vec3f,
+    light: vec3f,
+    ambient: vec3f,
+    alpha: f32,
+) -> vec4f {
+    let v = normalize(view);
+    let n0 = normalize(normal);
+    // Double-sided: shade back faces with the flipped normal.
+    let n = select(n0, -n0, dot(n0, v) < 0.0);
+    let l = normalize(light_dir);
+    let h = normalize(v + l);
+    let m = clamp(metallic, 0.0, 1.0);
+    let r = clamp(roughness, 0.0, 1.0);
+    let a = max(r * r, 0.002);
+    let a2 = a * a;
+
+    let n_v = max(dot(n, v), 1e-4);
+    let n_l = clamp(dot(n, l), 0.0, 1.0);
+    let n_h = clamp(dot(n, h), 0.0, 1.0);
+    let v_h = clamp(dot(v, h), 0.0, 1.0);
+
+    let f0 = mix(vec3f(0.04), base.rgb, m);
+    let f = f0 + (vec3f(1.0) - f0) * pow(1.0 - v_h, 5.0);
+    let d_den = n_h * n_h * (a2 - 1.0) + 1.0;
+    let d = a2 / (PRINCIPLED_PI * d_den * d_den);
+    let vis = 0.5 / max(
+        n_l * sqrt(n_v * n_v * (1.0 - a2) + a2) + n_v * sqrt(n_l * n_l * (1.0 - a2) + a2),
+        1e-5,
+    );
+    let specular = d * vis * f;
+    let diffuse = (vec3f(1.0) - f) * (1.0 - m) * base.rgb / PRINCIPLED_PI;
+    let direct = (diffuse + specular) * light * (n_l * PRINCIPLED_PI);
+    let indirect = ambient * ((1.0 - m) * base.rgb + f0);
+
+    let out_alpha = clamp(base.a * alpha, 0.0, 1.0);
+    return vec4f((direct + indirect + emission) * out_alpha, out_alpha);
+}
+"#;
+
+fn ensure_principled_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    ctx.extra_wgsl_decls
+        .entry(PRINCIPLED_WGSL_LIB_KEY.to_string())
+        .or_insert_with(|| PRINCIPLED_WGSL_LIB.to_string());
+}
+
+/// `baseColor` as an rgba `vec4f`; vec3 inputs are treated as opaque.
+fn resolve_base_color<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    if let Some(conn) = incoming_connection(scene, &node.id, "baseColor") {
+        let expr = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        if expr.ty == ValueType::Vec3 {
+            return Ok(TypedExpr::with_time(
+                format!("vec4f({}, 1.0)", expr.expr),
+                ValueType::Vec4,
+                expr.uses_time,
+            ));
+        }
+        return coerce_to_type(expr, ValueType::Vec4);
+    }
+
+    let mut rgba = [0.8, 0.8, 0.8, 1.0];
+    if let Some(arr) = node.params.get("baseColor").and_then(|v| v.as_array()) {
+        for (slot, v) in rgba.iter_mut().zip(arr) {
+            if let Some(v) = v.as_f64() {
+                *slot = v as f32;
+            }
+        }
+    }
+    Ok(TypedExpr::new(
+        format!(
+            "vec4f({}, {}, {}, {})",
+            fmt_f32(rgba[0]),
+            fmt_f32(rgba[1]),
+            fmt_f32(rgba[2]),
+            fmt_f32(rgba[3])
+        ),
+        ValueType::Vec4,
+    ))
+}
+
+/// Compile a PrincipledMaterial node to a premultiplied `vec4f` color.
+pub fn compile_principled_material<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    _out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let base = resolve_base_color(scene, node, ctx, cache, &compile_fn)?;
+    let metallic = resolve_f32_input(scene, node, "metallic", 0.0, ctx, cache, &compile_fn)?;
+    let roughness = resolve_f32_input(scene, node, "roughness", 0.5, ctx, cache, &compile_fn)?;
+    let emission = resolve_vec3_input(
+        scene,
+        node,
+        "emission",
+        [0.0, 0.0, 0.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let emission_strength = resolve_f32_input(
+        scene,
+        node,
+        "emissionStrength",
+        1.0,
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let alpha = resolve_f32_input(scene, node, "alpha", 1.0, ctx, cache, &compile_fn)?;
+    let normal = resolve_surface_normal(scene, node, ctx, cache, &compile_fn)?;
+    let view = resolve_view_direction(scene, node, ctx, cache, &compile_fn)?;
+    let light_dir = resolve_vec3_input(
+        scene,
+        node,
+        "lightDirection",
+        [0.3, 0.5, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let light_color = resolve_vec3_input(
+        scene,
+        node,
+        "lightColor",
+        [1.0, 1.0, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let light_intensity =
+        resolve_f32_input(scene, node, "lightIntensity", 1.0, ctx, cache, &compile_fn)?;
+    let ambient = resolve_vec3_input(
+        scene,
+        node,
+        "ambientColor",
+        [0.03, 0.03, 0.03],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+
+    let uses_time = [
+        &base,
+        &metallic,
+        &roughness,
+        &emission,
+        &emission_strength,
+        &alpha,
+        &normal,
+        &view,
+        &light_dir,
+        &light_color,
+        &light_intensity,
+        &ambient,
+    ]
+    .iter()
+    .any(|expr| expr.uses_time);
+
+    ensure_principled_wgsl_lib(ctx);
+    Ok(TypedExpr::with_time(
+        format!(
+            "principled_shade({}, {}, {}, ({}) * ({}), {}, {}, {}, ({}) * ({}), {}, {})",
+            base.expr,
+            metallic.expr,
+            roughness.expr,
+            emission.expr,
+            emission_strength.expr,
+            normal.expr,
+            view.expr,
+            light_dir.expr,
+            light_color.expr,
+            light_intensity.expr,
+            ambient.expr,
+            alpha.expr
+        ),
+        ValueType::Vec4,
+        uses_time,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::node_compiler::test_utils::{test_connection, test_scene};
+
+    fn mock_compile_fn(
+        node_id: &str,
+        _out_port: Option<&str>,
+        _ctx: &mut MaterialCompileContext,
+        _cache: &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr> {
+        Ok(match node_id {
+            "albedo" => TypedExpr::new("vec3f(0.9, 0.2, 0.1)".to_string(), ValueType::Vec3),
+            "rough" => TypedExpr::with_time("fract(params.time)".to_string(), ValueType::F32, true),
+            _ => TypedExpr::new("vec3f(0.0, 0.0, 1.0)".to_string(), ValueType::Vec3),
+        })
+    }
+
+    fn principled_node(params: serde_json::Value) -> Node {
+        Node {
+            id: "pbr".to_string(),
+            node_type: "PrincipledMaterial".to_string(),
+            params: serde_json::from_value(params).unwrap(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn assert_validates(ctx: &MaterialCompileContext, expr: &TypedExpr) {
+        let module = format!(
+            "struct VSOut {{ @location(0) uv: vec2f, @location(2) local_px: vec3f, @location(5) normal: vec3f, @location(6) world_pos: vec3f }};\nstruct Params {{ time: f32, camera_position: vec4f }};\n@group(0) @binding(0) var<uniform> params: Params;\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    return {};\n}}\n",
+            ctx.wgsl_decls_with_normals(true),
+            expr.expr
+        );
+        crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+    }
+
+    #[test]
+    fn principled_defaults_compile_to_valid_wgsl() {
+        let node = principled_node(serde_json::json!({
+            "baseColor": [1.0, 0.5, 0.25, 0.5],
+            "metallic": 1.0
+        }));
+        let scene = test_scene(vec![node.clone()], vec![]);
+        let mut ctx = MaterialCompileContext::default();
+        let out = compile_principled_material(
+            &scene,
+            &HashMap::new(),
+            &node,
+            Some("material"),
+            &mut ctx,
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(out.ty, ValueType::Vec4);
+        assert!(!out.uses_time);
+        assert!(ctx.needs_surface_normal);
+        assert!(
+            out.expr
+                .starts_with("principled_shade(vec4f(1.0, 0.5, 0.25, 0.5), 1.0,")
+        );
+        assert_validates(&ctx, &out);
+    }
+
+    #[test]
+    fn principled_connected_inputs_and_camera_view() {
+        let node = principled_node(serde_json::json!({ "viewSource": "camera" }));
+        let scene = test_scene(
+            vec![node.clone()],
+            vec![
+                test_connection("albedo", "value", "pbr", "baseColor"),
+                test_connection("rough", "value", "pbr", "roughness"),
+                test_connection("nm", "normal", "pbr", "normal"),
+            ],
+        );
+        let mut ctx = MaterialCompileContext::default();
+        let out = compile_principled_material(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut ctx,
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert!(out.uses_time);
+        assert!(ctx.needs_view_vector);
+        assert!(!ctx.needs_surface_normal);
+        assert!(out.expr.contains("vec4f(vec3f(0.9, 0.2, 0.1), 1.0)"));
+        assert_validates(&ctx, &out);
+    }
+}
@@ -1,4 +1,4 @@
-//! Compiler for Remap node.
+//! Compilers for Remap and MapRange nodes.
 //!
 //! The Remap node shapes a scalar signal `t` based on `mode`.
 //!
@@ -6,12 +6,16 @@
 //! - smoothstep(edge0, edge1, t)
 //! - linearMap: clamp((t - from)/(to-from), 0..1)
 //! - iq_* variants from https://iquilezles.org/articles/functions/
+//!
+//! MapRange maps `value` from [fromMin, fromMax] to [toMin, toMax] with
+//! `interpolation` (linear, stepped, smoothstep, smootherstep) and an optional
+//! `clamp` of the result, per component for vector inputs.
 
 use anyhow::{Result, bail};
 use std::collections::HashMap;
 
 use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
-use super::super::utils::coerce_to_type;
+use super::super::utils::{coerce_for_binary, coerce_to_type};
 use crate::dsl::{Node, SceneDSL, incoming_connection, parse_f32, parse_str};
 
 fn wgsl_f32_literal(v: f32) -> String {
@@ -316,6 +320,137 @@ where
     Ok(expr)
 }
 
+const MAP_RANGE_WGSL_LIB_KEY_PREFIX: &str = "map_range_lib";
+
+/// Emits `mapRange_<ty>` once per value type. Stepped uses `steps + 1` bands
+/// so the last step lands on `toMax`; a degenerate source range maps to `toMin`.
+fn ensure_map_range_wgsl_lib(ctx: &mut MaterialCompileContext, ty: ValueType) -> String {
+    let ty_name = ty.wgsl();
+    let fn_name = format!("mapRange_{ty_name}");
+    let key = format!("{MAP_RANGE_WGSL_LIB_KEY_PREFIX}_{ty_name}");
+    if ctx.extra_wgsl_decls.contains_key(&key) {
+        return fn_name;
+    }
+
+    let wgsl = format!(
+        r#"
+// ---- MapRange helper (generated) ----
+fn {fn_name}(value: {t}, from_min: {t}, from_max: {t}, to_min: {t}, to_max: {t}, steps: f32, mode: u32, clamp_result: bool) -> {t} {{
+    let span = from_max - from_min;
+    let degenerate = abs(span) < {t}(1e-6);
+    var f = select((value - from_min) / select(span, {t}(1.0), degenerate), {t}(0.0), degenerate);
+    if (mode == 1u) {{
+        f = select(floor(f * (steps + 1.0)) / steps, {t}(0.0), steps <= 0.0);
+    }} else if (mode == 2u) {{
+        f = smoothstep({t}(0.0), {t}(1.0), f);
+    }} else if (mode == 3u) {{
+        f = clamp(f, {t}(0.0), {t}(1.0));
+        f = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    }}
+    let result = to_min + f * (to_max - to_min);
+    if (clamp_result) {{
+        return clamp(result, min(to_min, to_max), max(to_min, to_max));
+    }}
+    return result;
+}}
+"#,
+        t = ty_name,
+    );
+    ctx.extra_wgsl_decls.insert(key, wgsl);
+    fn_name
+}
+
+fn map_range_mode_index(node: &Node) -> Result<u32> {
+    let mode = parse_str(&node.params, "interpolation")
+        .unwrap_or("linear")
+        .trim();
+    Ok(match mode {
+        "linear" => 0,
+        "stepped" => 1,
+        "smoothstep" => 2,
+        "smootherstep" => 3,
+        other => bail!("MapRange: unsupported interpolation '{other}'"),
+    })
+}
+
+pub fn compile_map_range<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("result");
+    if port != "result" {
+        bail!("MapRange: unsupported output port '{port}'");
+    }
+
+    let mode = map_range_mode_index(node)?;
+    let clamp_result = node
+        .params
+        .get("clamp")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+
+    let mut operands = Vec::with_capacity(5);
+    for (port_id, default) in [
+        ("value", 0.5),
+        ("fromMin", 0.0),
+        ("fromMax", 1.0),
+        ("toMin", 0.0),
+        ("toMax", 1.0),
+    ] {
+        let expr = if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+            compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?
+        } else {
+            let v = parse_f32(&node.params, port_id).unwrap_or(default);
+            TypedExpr::new(wgsl_f32_literal(v), ValueType::F32)
+        };
+        operands.push(expr);
+    }
+    let steps = resolve_f32_input(scene, node, "steps", 4.0, ctx, cache, &compile_fn)?;
+
+    // Widest operand type wins; integer scalars are mapped as f32.
+    let mut ty = ValueType::F32;
+    for operand in &operands {
+        let probe = TypedExpr::new(String::new(), ty);
+        let (_, _, widened) = coerce_for_binary(probe, operand.clone())?;
+        ty = widened;
+    }
+    match ty {
+        ValueType::F32 | ValueType::Vec2 | ValueType::Vec3 | ValueType::Vec4 => {}
+        ValueType::I32 | ValueType::U32 | ValueType::Bool => ty = ValueType::F32,
+        other => bail!("MapRange: unsupported value type {other:?}"),
+    }
+
+    let uses_time = steps.uses_time || operands.iter().any(|operand| operand.uses_time);
+    let args = operands
+        .into_iter()
+        .map(|operand| coerce_to_type(operand, ty).map(|operand| operand.expr))
+        .collect::<Result<Vec<_>>>()?;
+    let fn_name = ensure_map_range_wgsl_lib(ctx, ty);
+
+    Ok(TypedExpr::with_time(
+        format!(
+            "{fn_name}({}, {}, {mode}u, {clamp_result})",
+            args.join(", "),
+            steps.expr
+        ),
+        ty,
+        uses_time,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -337,6 +472,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
@@ -348,6 +484,7 @@ mod tests {
     ) -> Result<TypedExpr> {
         match node_id {
             "t_src" => Ok(TypedExpr::new("x", ValueType::F32)),
+            "v_src" => Ok(TypedExpr::new("vec3f(0.2, 0.5, 0.9)", ValueType::Vec3)),
             _ => bail!("unknown node"),
         }
     }
@@ -449,4 +586,102 @@ mod tests {
         assert!(out.expr.contains("clamp("));
         assert!(out.expr.contains("x"));
     }
+
+    fn map_range_node(params: HashMap<String, serde_json::Value>) -> Node {
+        Node {
+            id: "map".to_string(),
+            node_type: "MapRange".to_string(),
+            params,
+            inputs: vec![],
+            input_bindings: vec![],
+            outputs: vec![],
+            wgsl_override: None,
+        }
+    }
+
+    #[test]
+    fn map_range_modes_validate_for_float_and_vector() {
+        for interpolation in ["linear", "stepped", "smoothstep", "smootherstep"] {
+            for connect_vector in [false, true] {
+                let map = map_range_node(HashMap::from([
+                    (
+                        "interpolation".to_string(),
+                        serde_json::json!(interpolation),
+                    ),
+                    ("toMin".to_string(), serde_json::json!(-1.0)),
+                    ("toMax".to_string(), serde_json::json!(2.0)),
+                    ("clamp".to_string(), serde_json::json!(false)),
+                ]));
+                let connections = if connect_vector {
+                    vec![Connection {
+                        id: "c1".to_string(),
+                        from: Endpoint {
+                            node_id: "v_src".to_string(),
+                            port_id: "value".to_string(),
+                        },
+                        to: Endpoint {
+                            node_id: "map".to_string(),
+                            port_id: "value".to_string(),
+                        },
+                    }]
+                } else {
+                    vec![]
+                };
+                let scene = test_scene(vec![map.clone()], connections);
+                let nodes_by_id = HashMap::from([(map.id.clone(), map.clone())]);
+                let mut ctx = MaterialCompileContext::default();
+                let mut cache = HashMap::new();
+
+                let out = compile_map_range(
+                    &scene,
+                    &nodes_by_id,
+                    &map,
+                    None,
+                    &mut ctx,
+                    &mut cache,
+                    mock_compile_fn,
+                )
+                .unwrap();
+                let expected = if connect_vector {
+                    ValueType::Vec3
+                } else {
+                    ValueType::F32
+                };
+                assert_eq!(out.ty, expected);
+                assert!(out.expr.ends_with("u, false)"), "{}", out.expr);
+
+                let module = format!(
+                    "{}\n@fragment\nfn fs_main() -> @location(0) vec4f {{\n    let v = {};\n    return vec4f(0.0);\n}}\n",
+                    ctx.wgsl_decls(),
+                    out.expr
+                );
+                crate::renderer::validate_wgsl(&module)
+                    .unwrap_or_else(|e| panic!("{interpolation}: {e:#}"));
+            }
+        }
+    }
+
+    #[test]
+    fn map_range_rejects_unknown_interpolation() {
+        let map = map_range_node(HashMap::from([(
+            "interpolation".to_string(),
+            serde_json::json!("cubic"),
+        )]));
+        let scene = test_scene(vec![map.clone()], vec![]);
+        let nodes_by_id = HashMap::from([(map.id.clone(), map.clone())]);
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+
+        let err = compile_map_range(
+            &scene,
+            &nodes_by_id,
+            &map,
+            None,
+            &mut ctx,
+            &mut cache,
+            mock_compile_fn,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("cubic"));
+    }
 }
@@ -17,6 +17,131 @@ const SDF2D_BEVEL_SMOOTH5_FN: &str = "sdf2d_bevel_smooth5";
 const SDF2D_BEVEL_SMOOTH7_FN: &str = "sdf2d_bevel_smooth7";
 const SDF2D_BEVEL_NORMAL_FN: &str = "sdf2d_bevel_normal";
 const SDF2D_BEVEL_EPS_FN: &str = "sdf2d_bevel_eps";
+const SDF_BOOLEAN_WGSL_LIB_KEY: &str = "sdf_boolean_lib";
+
+// Polynomial smooth min/max (Quilez). `k` is the blend width in distance units; k <= 0 degrades
+// to the hard operation.
+const SDF_BOOLEAN_WGSL_LIB: &str = r#"
+// ---- SDF boolean helpers (generated) ----
+fn sdf_smooth_union(a: f32, b: f32, k: f32) -> f32 {
+    let kk = max(k, 1e-6);
+    let h = clamp(0.5 + 0.5 * (b - a) / kk, 0.0, 1.0);
+    return mix(b, a, h) - kk * h * (1.0 - h);
+}
+
+fn sdf_smooth_subtract(a: f32, b: f32, k: f32) -> f32 {
+    let kk = max(k, 1e-6);
+    let h = clamp(0.5 - 0.5 * (a + b) / kk, 0.0, 1.0);
+    return mix(a, -b, h) + kk * h * (1.0 - h);
+}
+
+fn sdf_smooth_intersect(a: f32, b: f32, k: f32) -> f32 {
+    let kk = max(k, 1e-6);
+    let h = clamp(0.5 - 0.5 * (b - a) / kk, 0.0, 1.0);
+    return mix(b, a, h) + kk * h * (1.0 - h);
+}
+"#;
+
+const SDF_TRANSFORM_WGSL_LIB_KEY: &str = "sdf_transform_lib";
+
+const SDF_TRANSFORM_WGSL_LIB: &str = r#"
+// ---- SdfTransform helpers (generated) ----
+fn sdf_transform_point(
+    p: vec2f,
+    pivot: vec2f,
+    offset: vec2f,
+    angle: f32,
+    scale: vec2f,
+    mirror: vec2f,
+    spacing: vec2f,
+) -> vec2f {
+    var d = p - pivot - offset;
+    // Inverse rotation: rotate the sample point by -angle.
+    let c = cos(angle);
+    let s = sin(angle);
+    d = vec2f(c * d.x + s * d.y, c * d.y - s * d.x);
+    d = d / select(scale, vec2f(1e-6), abs(scale) < vec2f(1e-6));
+    d = select(d, abs(d), mirror > vec2f(0.5));
+    let cell = d - spacing * round(d / max(spacing, vec2f(1e-6)));
+    d = select(d, cell, spacing > vec2f(0.0));
+    return pivot + d;
+}
+
+fn sdf_transform_scale(scale: vec2f) -> f32 {
+    return max(min(abs(scale.x), abs(scale.y)), 1e-6);
+}
+"#;
+
+const SDF2D_SHAPES_WGSL_LIB_KEY: &str = "sdf2d_shapes_lib";
+
+// Extra Sdf2D primitives (after Quilez's 2D distance functions). Kept out of `sdf2d.wgsl` so
+// scenes that only use circles/rects emit the same helper block as before.
+const SDF2D_SHAPES_WGSL_LIB: &str = r#"
+// ---- 2D SDF shape helpers (generated) ----
+// Regular polygon with `n` sides and circumradius `r`; a vertex points along +Y.
+fn sdf2d_polygon(p: vec2f, r: f32, n: f32) -> f32 {
+    let an = 3.14159265 / max(floor(n), 3.0);
+    let acs = vec2f(cos(an), sin(an));
+    let a = atan2(p.x, p.y);
+    let bn = a - 2.0 * an * floor(a / (2.0 * an)) - an;
+    var q = length(p) * vec2f(cos(bn), abs(sin(bn)));
+    q -= r * acs;
+    q.y += clamp(-q.y, 0.0, r * acs.y);
+    return length(q) * sign(q.x);
+}
+
+// Star with `n` points, outer radius `r` and inner (valley) radius `ri`; a point faces +Y.
+fn sdf2d_star(p: vec2f, r: f32, ri: f32, n: f32) -> f32 {
+    let an = 3.14159265 / max(floor(n), 2.0);
+    let a = atan2(p.x, p.y);
+    let bn = a - 2.0 * an * floor(a / (2.0 * an)) - an;
+    // Fold into one half-sector: tip on +X, valley at angle `an`.
+    let q = length(p) * vec2f(cos(bn), abs(sin(bn)));
+    let tip = vec2f(r, 0.0);
+    let e = ri * vec2f(cos(an), sin(an)) - tip;
+    let w = q - tip;
+    let h = clamp(dot(w, e) / max(dot(e, e), 1e-6), 0.0, 1.0);
+    let d = length(w - e * h);
+    return select(d, -d, e.x * w.y - e.y * w.x > 0.0);
+}
+
+// Circular arc of radius `r` and half-thickness `t`, spanning `aperture` radians centered on +Y.
+fn sdf2d_arc(p: vec2f, aperture: f32, r: f32, t: f32) -> f32 {
+    let sc = vec2f(sin(aperture * 0.5), cos(aperture * 0.5));
+    let q = vec2f(abs(p.x), p.y);
+    let d = select(abs(length(q) - r), length(q - sc * r), sc.y * q.x > sc.x * q.y);
+    return d - t;
+}
+
+// Pie slice of radius `r` spanning `aperture` radians centered on +Y.
+fn sdf2d_pie(p: vec2f, aperture: f32, r: f32) -> f32 {
+    let c = vec2f(sin(aperture * 0.5), cos(aperture * 0.5));
+    let q = vec2f(abs(p.x), p.y);
+    let l = length(q) - r;
+    let m = length(q - c * clamp(dot(q, c), 0.0, r));
+    return max(l, m * sign(c.y * q.x - c.x * q.y));
+}
+
+// Segment from `a` to `b` inflated by `r` (a capsule; r = 0 gives a hairline).
+fn sdf2d_segment(p: vec2f, a: vec2f, b: vec2f, r: f32) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = clamp(dot(pa, ba) / max(dot(ba, ba), 1e-6), 0.0, 1.0);
+    return length(pa - ba * h) - r;
+}
+
+// Heart whose height is `2 * r`, centered on the origin with the tip pointing down.
+fn sdf2d_heart(p: vec2f, r: f32) -> f32 {
+    let s = max(2.0 * r, 1e-6);
+    let q = vec2f(abs(p.x), p.y) / s + vec2f(0.0, 0.5);
+    if (q.x + q.y > 1.0) {
+        return (length(q - vec2f(0.25, 0.75)) - 0.35355339) * s;
+    }
+    let d0 = q - vec2f(0.0, 1.0);
+    let d1 = q - vec2f(0.5 * max(q.x + q.y, 0.0));
+    return sqrt(min(dot(d0, d0), dot(d1, d1))) * sign(q.x - q.y) * s;
+}
+"#;
 
 struct Sdf2DLib {
     round_rect_fn: String,
@@ -84,6 +209,12 @@ pub(crate) fn ensure_default_sdf2d_wgsl_lib(ctx: &mut MaterialCompileContext) {
         .insert(SDF2D_WGSL_LIB_KEY.to_string(), block);
 }
 
+fn ensure_sdf2d_shapes_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    ctx.extra_wgsl_decls
+        .entry(SDF2D_SHAPES_WGSL_LIB_KEY.to_string())
+        .or_insert_with(|| SDF2D_SHAPES_WGSL_LIB.to_string());
+}
+
 fn ensure_sdf2d_bevel_wgsl_lib(ctx: &mut MaterialCompileContext, node: &Node) -> Sdf2DBevelLib {
     let path = override_path(node);
     let template =
@@ -463,6 +594,164 @@ where
             )
             .inline())
         }
+        "polygon" | "star" => {
+            // `radius` is the circumradius; `sides` is the vertex (or point) count.
+            let r = resolve_input_expr_f32(scene, node, "radius", ctx, cache, &compile_fn)?;
+            let sides = resolve_input_expr_f32_or_default(
+                scene,
+                node,
+                "sides",
+                5.0,
+                ctx,
+                cache,
+                &compile_fn,
+            )?;
+            ensure_sdf2d_shapes_wgsl_lib(ctx);
+            if shape == "polygon" {
+                return Ok(TypedExpr::with_time(
+                    format!("sdf2d_polygon({}, {}, {})", p.expr, r.expr, sides.expr),
+                    ValueType::F32,
+                    p.uses_time || r.uses_time || sides.uses_time,
+                )
+                .inline());
+            }
+            // `innerRadius` is the valley radius in the same units as `radius`.
+            let inner = match incoming_connection(scene, &node.id, "innerRadius") {
+                Some(_) => {
+                    resolve_input_expr_f32(scene, node, "innerRadius", ctx, cache, &compile_fn)?
+                }
+                None => match node
+                    .params
+                    .get("innerRadius")
+                    .and_then(parse_json_number_f32)
+                {
+                    Some(v) => TypedExpr::new(format!("{v}"), ValueType::F32),
+                    None => TypedExpr::with_time(
+                        format!("({} * 0.5)", r.expr),
+                        ValueType::F32,
+                        r.uses_time,
+                    ),
+                },
+            };
+            Ok(TypedExpr::with_time(
+                format!(
+                    "sdf2d_star({}, {}, {}, {})",
+                    p.expr, r.expr, inner.expr, sides.expr
+                ),
+                ValueType::F32,
+                p.uses_time || r.uses_time || inner.uses_time || sides.uses_time,
+            )
+            .inline())
+        }
+        "arc" | "pie" => {
+            // `angle` is the full aperture in degrees, centered on +Y.
+            let r = resolve_input_expr_f32(scene, node, "radius", ctx, cache, &compile_fn)?;
+            let angle = resolve_input_expr_f32_or_default(
+                scene,
+                node,
+                "angle",
+                90.0,
+                ctx,
+                cache,
+                &compile_fn,
+            )?;
+            ensure_sdf2d_shapes_wgsl_lib(ctx);
+            if shape == "pie" {
+                return Ok(TypedExpr::with_time(
+                    format!("sdf2d_pie({}, radians({}), {})", p.expr, angle.expr, r.expr),
+                    ValueType::F32,
+                    p.uses_time || r.uses_time || angle.uses_time,
+                )
+                .inline());
+            }
+            // `thickness` is the full stroke width.
+            let thickness = resolve_input_expr_f32_or_default(
+                scene,
+                node,
+                "thickness",
+                0.1,
+                ctx,
+                cache,
+                &compile_fn,
+            )?;
+            Ok(TypedExpr::with_time(
+                format!(
+                    "sdf2d_arc({}, radians({}), {}, ({} * 0.5))",
+                    p.expr, angle.expr, r.expr, thickness.expr
+                ),
+                ValueType::F32,
+                p.uses_time || r.uses_time || angle.uses_time || thickness.uses_time,
+            )
+            .inline())
+        }
+        "capsule" | "segment" => {
+            // `start` / `end` are relative to `position`; `radius` inflates the segment.
+            let start = resolve_input_expr_vec2_or_default(
+                scene,
+                node,
+                "start",
+                "vec2f(-0.5, 0.0)",
+                ctx,
+                cache,
+                &compile_fn,
+            )?;
+            let end = resolve_input_expr_vec2_or_default(
+                scene,
+                node,
+                "end",
+                "vec2f(0.5, 0.0)",
+                ctx,
+                cache,
+                &compile_fn,
+            )?;
+            let r = if shape == "segment" {
+                TypedExpr::new("0.0", ValueType::F32)
+            } else {
+                resolve_input_expr_f32(scene, node, "radius", ctx, cache, &compile_fn)?
+            };
+            ensure_sdf2d_shapes_wgsl_lib(ctx);
+            Ok(TypedExpr::with_time(
+                format!(
+                    "sdf2d_segment({}, {}, {}, {})",
+                    p.expr, start.expr, end.expr, r.expr
+                ),
+                ValueType::F32,
+                p.uses_time || start.uses_time || end.uses_time || r.uses_time,
+            )
+            .inline())
+        }
+        "ring" => {
+            // Annulus centered on `radius` with full width `thickness`.
+            let r = resolve_input_expr_f32(scene, node, "radius", ctx, cache, &compile_fn)?;
+            let thickness = resolve_input_expr_f32_or_default(
+                scene,
+                node,
+                "thickness",
+                0.1,
+                ctx,
+                cache,
+                &compile_fn,
+            )?;
+            Ok(TypedExpr::with_time(
+                format!(
+                    "(abs(length({}) - {}) - {} * 0.5)",
+                    p.expr, r.expr, thickness.expr
+                ),
+                ValueType::F32,
+                p.uses_time || r.uses_time || thickness.uses_time,
+            )
+            .inline())
+        }
+        "heart" => {
+            let r = resolve_input_expr_f32(scene, node, "radius", ctx, cache, &compile_fn)?;
+            ensure_sdf2d_shapes_wgsl_lib(ctx);
+            Ok(TypedExpr::with_time(
+                format!("sdf2d_heart({}, {})", p.expr, r.expr),
+                ValueType::F32,
+                p.uses_time || r.uses_time,
+            )
+            .inline())
+        }
         // Treat unknown values as circle for resilience.
         _ => {
             let r = resolve_input_expr_f32(scene, node, "radius", ctx, cache, &compile_fn)?;
@@ -621,10 +910,328 @@ where
     Ok(TypedExpr::with_time(normal_var, ValueType::Vec3, uses_time))
 }
 
+/// Compile an SdfBoolean node combining two distances `a` and `b`.
+///
+/// `operation`: `union` (default), `subtract` (a minus b), `intersect`, or the smooth variants
+/// `smooth_union` / `smooth_subtract` / `smooth_intersect`, which round the seam over width `k`.
+pub fn compile_sdf_boolean<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let out = out_port.unwrap_or("distance");
+    if out != "distance" {
+        bail!("SdfBoolean unsupported output port: {out}");
+    }
+
+    let operation = node
+        .params
+        .get("operation")
+        .and_then(|v| v.as_str())
+        .unwrap_or("union");
+    let smooth_fn = match operation {
+        "union" | "subtract" | "intersect" => None,
+        "smooth_union" => Some("sdf_smooth_union"),
+        "smooth_subtract" => Some("sdf_smooth_subtract"),
+        "smooth_intersect" => Some("sdf_smooth_intersect"),
+        other => bail!("SdfBoolean unsupported operation: {other}"),
+    };
+
+    let a = resolve_input_expr_f32(scene, node, "a", ctx, cache, &compile_fn)?;
+    let b = resolve_input_expr_f32(scene, node, "b", ctx, cache, &compile_fn)?;
+    let mut uses_time = a.uses_time || b.uses_time;
+
+    let expr = if let Some(smooth_fn) = smooth_fn {
+        let k = resolve_input_expr_f32_or_default(scene, node, "k", 0.0, ctx, cache, &compile_fn)?;
+        uses_time |= k.uses_time;
+        ctx.extra_wgsl_decls
+            .entry(SDF_BOOLEAN_WGSL_LIB_KEY.to_string())
+            .or_insert_with(|| SDF_BOOLEAN_WGSL_LIB.to_string());
+        format!("{smooth_fn}({}, {}, {})", a.expr, b.expr, k.expr)
+    } else {
+        match operation {
+            "union" => format!("min({}, {})", a.expr, b.expr),
+            "subtract" => format!("max({}, -({}))", a.expr, b.expr),
+            _ => format!("max({}, {})", a.expr, b.expr),
+        }
+    };
+
+    Ok(TypedExpr::with_time(expr, ValueType::F32, uses_time).inline())
+}
+
+/// Compile an SdfTransform node that moves the sample domain of an upstream distance `sdf`.
+///
+/// The upstream expression is re-evaluated with `in.local_px.xy` replaced by the transformed
+/// point (the same substitution Sdf2DBevel uses for its finite differences), so any Sdf2D /
+/// SdfBoolean chain can be placed, animated or instanced without touching its own inputs.
+/// Around `pivot`, the point is offset, rotated (`rotation`, degrees), scaled, mirrored
+/// (`mirror`: `none` / `x` / `y` / `xy`) and finally repeated every `repeat` units per axis
+/// (0 disables an axis). Distances are rescaled by the smaller scale axis, which keeps them
+/// exact for uniform scale and a conservative bound otherwise.
+pub fn compile_sdf_transform<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let out = out_port.unwrap_or("distance");
+    if out != "distance" {
+        bail!("SdfTransform unsupported output port: {out}");
+    }
+
+    let mirror = match node
+        .params
+        .get("mirror")
+        .and_then(|v| v.as_str())
+        .unwrap_or("none")
+    {
+        "none" => "vec2f(0.0, 0.0)",
+        "x" => "vec2f(1.0, 0.0)",
+        "y" => "vec2f(0.0, 1.0)",
+        "xy" => "vec2f(1.0, 1.0)",
+        other => bail!("SdfTransform unsupported mirror: {other}"),
+    };
+
+    // Keep the upstream distance inline so `in.local_px.xy` is still present to substitute.
+    ctx.auto_temp_suppression_depth += 1;
+    let sdf_result = resolve_input_expr_f32(scene, node, "sdf", ctx, cache, &compile_fn);
+    ctx.auto_temp_suppression_depth = ctx.auto_temp_suppression_depth.saturating_sub(1);
+    let sdf = sdf_result?;
+    if !sdf.expr.contains("in.local_px.xy") {
+        bail!(
+            "{}.sdf must be a local-space distance (e.g. from Sdf2D)",
+            node.id
+        );
+    }
+
+    let pivot = resolve_input_expr_vec2_or_default(
+        scene,
+        node,
+        "pivot",
+        "vec2f(0.0, 0.0)",
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let offset = resolve_input_expr_vec2_or_default(
+        scene,
+        node,
+        "offset",
+        "vec2f(0.0, 0.0)",
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let rotation =
+        resolve_input_expr_f32_or_default(scene, node, "rotation", 0.0, ctx, cache, &compile_fn)?;
+    let scale = resolve_input_expr_vec2_or_default(
+        scene,
+        node,
+        "scale",
+        "vec2f(1.0, 1.0)",
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let repeat = resolve_input_expr_vec2_or_default(
+        scene,
+        node,
+        "repeat",
+        "vec2f(0.0, 0.0)",
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+
+    ctx.extra_wgsl_decls
+        .entry(SDF_TRANSFORM_WGSL_LIB_KEY.to_string())
+        .or_insert_with(|| SDF_TRANSFORM_WGSL_LIB.to_string());
+
+    let point = format!(
+        "sdf_transform_point(in.local_px.xy, {}, {}, radians({}), {}, {mirror}, {})",
+        pivot.expr, offset.expr, rotation.expr, scale.expr, repeat.expr
+    );
+    let uses_time = [&sdf, &pivot, &offset, &rotation, &scale, &repeat]
+        .iter()
+        .any(|expr| expr.uses_time);
+    Ok(TypedExpr::with_time(
+        format!(
+            "(({}) * sdf_transform_scale({}))",
+            sdf.expr.replace("in.local_px.xy", &point),
+            scale.expr
+        ),
+        ValueType::F32,
+        uses_time,
+    )
+    .inline())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::renderer::node_compiler::test_utils::test_scene;
+    use crate::renderer::node_compiler::test_utils::{test_connection, test_scene};
+
+    fn sdf_boolean_scene(operation: &str) -> (SceneDSL, HashMap<String, Node>) {
+        let circle = |id: &str, x: f32| Node {
+            id: id.to_string(),
+            node_type: "Sdf2D".to_string(),
+            params: HashMap::from([
+                ("shape".to_string(), serde_json::json!("circle")),
+                ("position".to_string(), serde_json::json!([x, 0.0])),
+                ("radius".to_string(), serde_json::json!(4.0)),
+            ]),
+            inputs: vec![],
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        let boolean = Node {
+            id: "bool".to_string(),
+            node_type: "SdfBoolean".to_string(),
+            params: HashMap::from([
+                ("operation".to_string(), serde_json::json!(operation)),
+                ("k".to_string(), serde_json::json!(2.0)),
+            ]),
+            inputs: vec![],
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        let nodes = vec![circle("c1", 0.0), circle("c2", 3.0), boolean];
+        let scene = test_scene(
+            nodes.clone(),
+            vec![
+                test_connection("c1", "distance", "bool", "a"),
+                test_connection("c2", "distance", "bool", "b"),
+            ],
+        );
+        let nodes_by_id = nodes.into_iter().map(|n| (n.id.clone(), n)).collect();
+        (scene, nodes_by_id)
+    }
+
+    #[test]
+    fn sdf_boolean_hard_and_smooth_operations() {
+        for (operation, needle, needs_lib) in [
+            ("union", "min(", false),
+            ("subtract", "max(", false),
+            ("intersect", "max(", false),
+            ("smooth_union", "sdf_smooth_union(", true),
+            ("smooth_subtract", "sdf_smooth_subtract(", true),
+            ("smooth_intersect", "sdf_smooth_intersect(", true),
+        ] {
+            let (scene, nodes_by_id) = sdf_boolean_scene(operation);
+            let mut ctx = MaterialCompileContext::default();
+            let expr = crate::renderer::node_compiler::compile_material_expr(
+                &scene,
+                &nodes_by_id,
+                "bool",
+                Some("distance"),
+                &mut ctx,
+                &mut HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(expr.ty, ValueType::F32);
+            assert!(expr.expr.starts_with(needle), "{operation}: {}", expr.expr);
+            assert_eq!(
+                ctx.extra_wgsl_decls.contains_key(SDF_BOOLEAN_WGSL_LIB_KEY),
+                needs_lib
+            );
+            let module = format!(
+                "struct VSOut {{ @location(2) local_px: vec3f }};\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    return vec4f({});\n}}\n",
+                ctx.wgsl_decls(),
+                expr.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+        }
+
+        let (scene, nodes_by_id) = sdf_boolean_scene("xor");
+        assert!(
+            crate::renderer::node_compiler::compile_material_expr(
+                &scene,
+                &nodes_by_id,
+                "bool",
+                None,
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn sdf_transform_rewrites_the_sample_point() {
+        let (mut scene, mut nodes_by_id) = sdf_boolean_scene("union");
+        let transform = Node {
+            id: "xform".to_string(),
+            node_type: "SdfTransform".to_string(),
+            params: HashMap::from([
+                ("rotation".to_string(), serde_json::json!(45.0)),
+                ("scale".to_string(), serde_json::json!([2.0, 1.0])),
+                ("repeat".to_string(), serde_json::json!([16.0, 0.0])),
+                ("mirror".to_string(), serde_json::json!("y")),
+            ]),
+            inputs: vec![],
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        scene.nodes.push(transform.clone());
+        scene
+            .connections
+            .push(test_connection("bool", "distance", "xform", "sdf"));
+        nodes_by_id.insert(transform.id.clone(), transform);
+
+        let mut ctx = MaterialCompileContext::default();
+        let expr = crate::renderer::node_compiler::compile_material_expr(
+            &scene,
+            &nodes_by_id,
+            "xform",
+            Some("distance"),
+            &mut ctx,
+            &mut HashMap::new(),
+        )
+        .unwrap();
+
+        assert_eq!(expr.ty, ValueType::F32);
+        assert_eq!(
+            expr.expr
+                .matches("sdf_transform_point(in.local_px.xy")
+                .count(),
+            2
+        );
+        assert!(expr.expr.contains("vec2f(0.0, 1.0)"));
+        assert!(expr.expr.ends_with("sdf_transform_scale(vec2f(2, 1)))"));
+        let module = format!(
+            "struct VSOut {{ @location(2) local_px: vec3f }};\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    return vec4f({});\n}}\n",
+            ctx.wgsl_decls(),
+            expr.expr
+        );
+        crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+    }
 
     #[test]
     fn sdf2d_circle_from_params() {
@@ -746,6 +1353,61 @@ mod tests {
         assert!(lib.contains("fn sdf2d_smooth_round_rect"));
     }
 
+    #[test]
+    fn sdf2d_extra_shapes_compile_to_valid_wgsl() {
+        for (shape, needle, needs_lib) in [
+            ("polygon", "sdf2d_polygon(", true),
+            ("star", "sdf2d_star(", true),
+            ("arc", "sdf2d_arc(", true),
+            ("pie", "sdf2d_pie(", true),
+            ("capsule", "sdf2d_segment(", true),
+            ("segment", "sdf2d_segment(", true),
+            ("ring", "(abs(length(", false),
+            ("heart", "sdf2d_heart(", true),
+        ] {
+            let node = Node {
+                id: "sdf".to_string(),
+                node_type: "Sdf2D".to_string(),
+                params: HashMap::from([
+                    ("shape".to_string(), serde_json::json!(shape)),
+                    ("position".to_string(), serde_json::json!([1.0, 2.0])),
+                    ("radius".to_string(), serde_json::json!(8.0)),
+                    ("sides".to_string(), serde_json::json!(6)),
+                    ("thickness".to_string(), serde_json::json!(2.0)),
+                ]),
+                inputs: vec![],
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            };
+            let scene = test_scene(vec![node.clone()], vec![]);
+            let nodes_by_id = HashMap::from([(node.id.clone(), node)]);
+            let mut ctx = MaterialCompileContext::default();
+            let expr = crate::renderer::node_compiler::compile_material_expr(
+                &scene,
+                &nodes_by_id,
+                "sdf",
+                Some("distance"),
+                &mut ctx,
+                &mut HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(expr.ty, ValueType::F32);
+            assert!(expr.expr.starts_with(needle), "{shape}: {}", expr.expr);
+            assert_eq!(
+                ctx.extra_wgsl_decls.contains_key(SDF2D_SHAPES_WGSL_LIB_KEY),
+                needs_lib
+            );
+            let module = format!(
+                "struct VSOut {{ @location(2) local_px: vec3f }};\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    return vec4f({});\n}}\n",
+                ctx.wgsl_decls(),
+                expr.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+        }
+    }
+
     #[test]
     fn sdf2d_bevel_depth_emits_helper() {
         let node = Node {
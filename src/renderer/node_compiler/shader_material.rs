@@ -742,6 +742,7 @@ fn shader_material(in: ShaderMaterialInput) -> vec4f {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let nodes_by_id = HashMap::from([(node.id.clone(), node.clone())]);
         let mut ctx = MaterialCompileContext::default();
@@ -814,6 +815,7 @@ fn shader_material(in: ShaderMaterialInput) -> vec4f {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let nodes_by_id = scene
             .nodes
@@ -936,6 +938,7 @@ fn shader_material(
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let nodes_by_id = scene
             .nodes
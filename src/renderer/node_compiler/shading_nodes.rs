@@ -0,0 +1,674 @@
+//! Compilers for surface shading nodes: view-dependent weights (Fresnel, LayerWeight), normal
+//! perturbation (NormalMap, Bump) and fragment builtins (ScreenDerivative, Facing).
+//!
+//! Every node starts from a surface normal: the `normal` input when connected, otherwise the
+//! interpolated geometry normal (see `surfaceNormal` in the shader builder). Fresnel and
+//! LayerWeight compare it against a view direction, which is either the fixed `viewDirection`
+//! input (default +Z, i.e. towards an orthographic 2D camera) or the per-fragment camera vector
+//! when `viewSource` is `"camera"`.
+//!
+//! Meshes carry no tangents, so NormalMap and Bump build their surface frame from screen-space
+//! derivatives of the geometry-local position (`in.local_px`) and, for NormalMap, the UVs.
+//! Tangent-space maps follow the OpenGL convention (green = +V); set `greenChannel` to
+//! `"directX"` for maps authored with green pointing down.
+//!
+//! ScreenDerivative and Facing only exist in the fragment stage. ScreenDerivative exposes
+//! `dpdx`/`dpdy`/`fwidth` of any float or vector expression, which is what anti-aliased
+//! procedural edges need (`smoothstep(-w, w, d)` with `w = fwidth(d)`). Facing reads the
+//! `@builtin(front_facing)` flag, which the shader builder adds to `fs_main` on demand.
+
+use anyhow::{Result, anyhow, bail};
+use std::collections::HashMap;
+
+use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
+use super::super::utils::coerce_to_type;
+use super::vector_nodes::{compile_view_vector, resolve_f32_input, resolve_vec3_input};
+use crate::dsl::{Node, SceneDSL, incoming_connection, parse_str};
+
+const SHADING_WGSL_LIB_KEY: &str = "shading_weights_lib";
+
+fn ensure_shading_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    ctx.extra_wgsl_decls
+        .entry(SHADING_WGSL_LIB_KEY.to_string())
+        .or_insert_with(|| SHADING_WGSL_LIB.to_string());
+}
+
+const SHADING_WGSL_LIB: &str = r#"
+// |cos| between normal and view; back faces are treated as facing the viewer.
+fn shading_facingCos(n: vec3f, v: vec3f) -> f32 {
+    return abs(dot(normalize(n), normalize(v)));
+}
+
+// Unpolarized dielectric Fresnel reflectance; 1.0 on total internal reflection.
+fn shading_fresnelDielectric(cos_i: f32, eta: f32) -> f32 {
+    let c = abs(cos_i);
+    var g = eta * eta - 1.0 + c * c;
+    if (g <= 0.0) {
+        return 1.0;
+    }
+    g = sqrt(g);
+    let a = (g - c) / (g + c);
+    let b = (c * (g + c) - 1.0) / (c * (g - c) + 1.0);
+    return 0.5 * a * a * (1.0 + b * b);
+}
+
+// Facing falloff: blend 0.5 is linear in cos, lower blends tighten it towards grazing angles.
+fn shading_layerFacing(cos_i: f32, blend: f32) -> f32 {
+    let b = clamp(blend, 0.0, 0.99999);
+    let k = select(0.5 / (1.0 - b), 2.0 * b, b < 0.5);
+    return 1.0 - pow(max(cos_i, 1e-6), k);
+}
+
+// Tangent frame from position/UV derivatives (no vertex tangents needed).
+fn shading_cotangentFrame(n: vec3f, p: vec3f, uv: vec2f) -> mat3x3f {
+    let dp1 = dpdx(p);
+    let dp2 = dpdy(p);
+    let duv1 = dpdx(uv);
+    let duv2 = dpdy(uv);
+    let dp2perp = cross(dp2, n);
+    let dp1perp = cross(n, dp1);
+    let t = dp2perp * duv1.x + dp1perp * duv2.x;
+    let b = dp2perp * duv1.y + dp1perp * duv2.y;
+    let inv_max = inverseSqrt(max(max(dot(t, t), dot(b, b)), 1e-20));
+    return mat3x3f(t * inv_max, b * inv_max, n);
+}
+
+// Decodes a [0, 1] tangent-space normal and moves `n` towards it by `strength`.
+fn shading_normalMap(n: vec3f, p: vec3f, uv: vec2f, rgb: vec3f, strength: f32) -> vec3f {
+    let base = normalize(n);
+    let ts = mix(vec3f(0.0, 0.0, 1.0), rgb * 2.0 - 1.0, max(strength, 0.0));
+    let mapped = shading_cotangentFrame(base, p, uv) * ts;
+    let len = length(mapped);
+    return select(base, mapped / max(len, 1e-12), len > 1e-12);
+}
+
+// Bump from height derivatives (surface gradient); negative distance inverts.
+fn shading_bump(n: vec3f, p: vec3f, height: f32, strength: f32, distance: f32) -> vec3f {
+    let base = normalize(n);
+    let dp_dx = dpdx(p);
+    let dp_dy = dpdy(p);
+    let r1 = cross(dp_dy, base);
+    let r2 = cross(base, dp_dx);
+    let det = dot(dp_dx, r1);
+    let grad = dpdx(height) * r1 + dpdy(height) * r2;
+    let bumped = abs(det) * base - distance * sign(det) * grad;
+    let len = length(bumped);
+    let perturbed = select(base, bumped / max(len, 1e-12), len > 1e-12);
+    return normalize(mix(base, perturbed, clamp(strength, 0.0, 1.0)));
+}
+"#;
+
+/// The node's `normal` input, or the interpolated geometry normal.
+pub(super) fn resolve_surface_normal<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    if let Some(conn) = incoming_connection(scene, &node.id, "normal") {
+        let expr = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        return coerce_to_type(expr, ValueType::Vec3);
+    }
+    ctx.needs_surface_normal = true;
+    Ok(TypedExpr::new("surfaceNormal(in)", ValueType::Vec3))
+}
+
+/// The fixed `viewDirection` input, or the camera vector when `viewSource` is `"camera"`.
+pub(super) fn resolve_view_direction<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    match parse_str(&node.params, "viewSource").unwrap_or("fixed") {
+        "fixed" => resolve_vec3_input(
+            scene,
+            node,
+            "viewDirection",
+            [0.0, 0.0, 1.0],
+            ctx,
+            cache,
+            compile_fn,
+        ),
+        "camera" => compile_view_vector(node, None, ctx),
+        other => bail!("{}: unsupported viewSource '{other}'", node.node_type),
+    }
+}
+
+/// Facing cosine between the node's normal and view direction.
+fn compile_facing_cos<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let normal = resolve_surface_normal(scene, node, ctx, cache, compile_fn)?;
+    let view = resolve_view_direction(scene, node, ctx, cache, compile_fn)?;
+
+    ensure_shading_wgsl_lib(ctx);
+    Ok(TypedExpr::with_time(
+        format!("shading_facingCos({}, {})", normal.expr, view.expr),
+        ValueType::F32,
+        normal.uses_time || view.uses_time,
+    ))
+}
+
+/// Compile a Fresnel node.
+///
+/// Outputs the dielectric Fresnel reflectance for the `ior` input at the current facing angle.
+pub fn compile_fresnel<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("fac");
+    if port != "fac" {
+        bail!("Fresnel: unsupported output port '{port}'");
+    }
+
+    let cos = compile_facing_cos(scene, node, ctx, cache, &compile_fn)?;
+    let ior = resolve_f32_input(scene, node, "ior", 1.45, ctx, cache, &compile_fn)?;
+    Ok(TypedExpr::with_time(
+        format!(
+            "shading_fresnelDielectric({}, max({}, 1e-5))",
+            cos.expr, ior.expr
+        ),
+        ValueType::F32,
+        cos.uses_time || ior.uses_time,
+    ))
+}
+
+/// Compile a LayerWeight node.
+///
+/// Outputs:
+/// - `fresnel`: dielectric Fresnel with `eta = 1 / (1 - blend)`.
+/// - `facing`: 0 when facing the viewer, rising to 1 at grazing angles; `blend` shapes the curve.
+pub fn compile_layer_weight<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("fresnel");
+    let cos = compile_facing_cos(scene, node, ctx, cache, &compile_fn)?;
+    let blend = resolve_f32_input(scene, node, "blend", 0.5, ctx, cache, &compile_fn)?;
+    let uses_time = cos.uses_time || blend.uses_time;
+    let expr = match port {
+        "fresnel" => format!(
+            "shading_fresnelDielectric({}, 1.0 / max(1.0 - ({}), 1e-5))",
+            cos.expr, blend.expr
+        ),
+        "facing" => format!("shading_layerFacing({}, {})", cos.expr, blend.expr),
+        other => bail!("LayerWeight: unsupported output port '{other}'"),
+    };
+    Ok(TypedExpr::with_time(expr, ValueType::F32, uses_time))
+}
+
+/// Compile a NormalMap node.
+///
+/// Perturbs the surface normal with the tangent-space normal texel on the `color` input
+/// (default: flat `(0.5, 0.5, 1.0)`), blended in by `strength`.
+pub fn compile_normal_map<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("normal");
+    if port != "normal" {
+        bail!("NormalMap: unsupported output port '{port}'");
+    }
+
+    let normal = resolve_surface_normal(scene, node, ctx, cache, &compile_fn)?;
+    let color = resolve_vec3_input(
+        scene,
+        node,
+        "color",
+        [0.5, 0.5, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let strength = resolve_f32_input(scene, node, "strength", 1.0, ctx, cache, &compile_fn)?;
+    let rgb = match parse_str(&node.params, "greenChannel").unwrap_or("openGL") {
+        "openGL" => color.expr.clone(),
+        "directX" => format!(
+            "(({}) * vec3f(1.0, -1.0, 1.0) + vec3f(0.0, 1.0, 0.0))",
+            color.expr
+        ),
+        other => bail!("NormalMap: unsupported greenChannel '{other}'"),
+    };
+
+    ensure_shading_wgsl_lib(ctx);
+    Ok(TypedExpr::with_time(
+        format!(
+            "shading_normalMap({}, in.local_px, in.uv, {rgb}, {})",
+            normal.expr, strength.expr
+        ),
+        ValueType::Vec3,
+        normal.uses_time || color.uses_time || strength.uses_time,
+    ))
+}
+
+/// Compile a Bump node.
+///
+/// Tilts the surface normal along the screen-space gradient of the `height` input. `distance`
+/// scales the height (in geometry pixels), `strength` blends towards the bumped normal and the
+/// `invert` param flips the relief.
+pub fn compile_bump<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("normal");
+    if port != "normal" {
+        bail!("Bump: unsupported output port '{port}'");
+    }
+    let invert = node
+        .params
+        .get("invert")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let normal = resolve_surface_normal(scene, node, ctx, cache, &compile_fn)?;
+    let height = resolve_f32_input(scene, node, "height", 0.0, ctx, cache, &compile_fn)?;
+    let strength = resolve_f32_input(scene, node, "strength", 1.0, ctx, cache, &compile_fn)?;
+    let distance = resolve_f32_input(scene, node, "distance", 1.0, ctx, cache, &compile_fn)?;
+    let distance_expr = if invert {
+        format!("-({})", distance.expr)
+    } else {
+        distance.expr.clone()
+    };
+
+    ensure_shading_wgsl_lib(ctx);
+    Ok(TypedExpr::with_time(
+        format!(
+            "shading_bump({}, in.local_px, {}, {}, {distance_expr})",
+            normal.expr, height.expr, strength.expr
+        ),
+        ValueType::Vec3,
+        normal.uses_time || height.uses_time || strength.uses_time || distance.uses_time,
+    ))
+}
+
+/// Compile a ScreenDerivative node.
+///
+/// Outputs `ddx`, `ddy` and `fwidth` (default) of the `value` input, with the input's type.
+/// The `precision` param picks the `coarse` or `fine` builtin variants; the default lets the
+/// implementation choose.
+pub fn compile_screen_derivative<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("fwidth");
+    let builtin = match port {
+        "ddx" => "dpdx",
+        "ddy" => "dpdy",
+        "fwidth" => "fwidth",
+        other => bail!("ScreenDerivative: unsupported output port '{other}'"),
+    };
+    let suffix = match parse_str(&node.params, "precision").unwrap_or("default") {
+        "default" => "",
+        "coarse" => "Coarse",
+        "fine" => "Fine",
+        other => bail!("ScreenDerivative: unsupported precision '{other}'"),
+    };
+
+    let conn = incoming_connection(scene, &node.id, "value")
+        .ok_or_else(|| anyhow!("ScreenDerivative missing input value"))?;
+    let value = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+    if !matches!(
+        value.ty,
+        ValueType::F32 | ValueType::Vec2 | ValueType::Vec3 | ValueType::Vec4
+    ) {
+        bail!(
+            "ScreenDerivative.value must be a float or vector, got {:?}",
+            value.ty
+        );
+    }
+
+    Ok(TypedExpr::with_time(
+        format!("{builtin}{suffix}({})", value.expr),
+        value.ty,
+        value.uses_time,
+    ))
+}
+
+/// Compile a Facing node.
+///
+/// `facing` is 1.0 on front faces and 0.0 on back faces; `sign` is +1.0 / -1.0, handy for
+/// flipping a normal on two-sided geometry.
+pub fn compile_facing(
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+) -> Result<TypedExpr> {
+    let expr = match out_port.unwrap_or("facing") {
+        "facing" => "select(0.0, 1.0, front_facing)",
+        "sign" => "select(-1.0, 1.0, front_facing)",
+        other => bail!("Facing {}: unsupported output port '{other}'", node.id),
+    };
+    ctx.uses_front_facing = true;
+    Ok(TypedExpr::new(expr, ValueType::F32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::node_compiler::test_utils::{test_connection, test_scene};
+
+    fn mock_compile_fn(
+        node_id: &str,
+        _out_port: Option<&str>,
+        _ctx: &mut MaterialCompileContext,
+        _cache: &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr> {
+        Ok(match node_id {
+            "blend" => TypedExpr::with_time("fract(params.time)".to_string(), ValueType::F32, true),
+            _ => TypedExpr::new("vec2f(0.3, 0.4)".to_string(), ValueType::Vec2),
+        })
+    }
+
+    fn shading_node(id: &str, node_type: &str, params: serde_json::Value) -> Node {
+        Node {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            params: serde_json::from_value(params).unwrap(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn assert_expr_validates(ctx: &MaterialCompileContext, expr: &TypedExpr, has_normals: bool) {
+        let normal_field = if has_normals {
+            "@location(5) normal: vec3f, "
+        } else {
+            ""
+        };
+        let module = format!(
+            "struct VSOut {{ @location(0) uv: vec2f, @location(2) local_px: vec3f, {normal_field}@location(6) world_pos: vec3f }};\nstruct Params {{ time: f32, camera_position: vec4f }};\n@group(0) @binding(0) var<uniform> params: Params;\n{}\n@fragment\nfn fs_main({}) -> @location(0) vec4f {{\n    let v = {};\n    return vec4f(v);\n}}\n",
+            ctx.wgsl_decls_with_normals(has_normals),
+            ctx.fragment_entry_params(),
+            expr.expr
+        );
+        crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+    }
+
+    #[test]
+    fn fresnel_defaults_to_surface_normal_and_fixed_view() {
+        let node = shading_node("fr", "Fresnel", serde_json::json!({ "ior": 1.33 }));
+        let scene = test_scene(vec![node.clone()], vec![]);
+        let mut ctx = MaterialCompileContext::default();
+        let out = compile_fresnel(
+            &scene,
+            &HashMap::new(),
+            &node,
+            Some("fac"),
+            &mut ctx,
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(out.ty, ValueType::F32);
+        assert!(ctx.needs_surface_normal);
+        assert!(!ctx.needs_view_vector);
+        assert!(out.expr.contains("surfaceNormal(in)"));
+        assert!(out.expr.contains("vec3f(0.0, 0.0, 1.0)"));
+        assert_expr_validates(&ctx, &out, true);
+        assert_expr_validates(&ctx, &out, false);
+
+        assert!(
+            compile_fresnel(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some("facing"),
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn layer_weight_outputs_with_connected_normal_and_camera_view() {
+        let node = shading_node(
+            "lw",
+            "LayerWeight",
+            serde_json::json!({ "viewSource": "camera" }),
+        );
+        let scene = test_scene(
+            vec![node.clone()],
+            vec![
+                test_connection("n", "value", "lw", "normal"),
+                test_connection("blend", "value", "lw", "blend"),
+            ],
+        );
+        for port in ["fresnel", "facing"] {
+            let mut ctx = MaterialCompileContext::default();
+            let out = compile_layer_weight(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some(port),
+                &mut ctx,
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(out.ty, ValueType::F32);
+            assert!(out.uses_time);
+            assert!(ctx.needs_view_vector);
+            assert!(!ctx.needs_surface_normal);
+            assert_expr_validates(&ctx, &out, false);
+        }
+
+        let bad = shading_node(
+            "lw",
+            "LayerWeight",
+            serde_json::json!({ "viewSource": "light" }),
+        );
+        let scene = test_scene(vec![bad.clone()], vec![]);
+        assert!(
+            compile_layer_weight(
+                &scene,
+                &HashMap::new(),
+                &bad,
+                None,
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn normal_map_and_bump_perturb_surface_normal() {
+        let node = shading_node(
+            "nm",
+            "NormalMap",
+            serde_json::json!({ "greenChannel": "directX" }),
+        );
+        let scene = test_scene(
+            vec![node.clone()],
+            vec![test_connection("tex", "color", "nm", "color")],
+        );
+        let mut ctx = MaterialCompileContext::default();
+        let out = compile_normal_map(
+            &scene,
+            &HashMap::new(),
+            &node,
+            None,
+            &mut ctx,
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(out.ty, ValueType::Vec3);
+        assert!(ctx.needs_surface_normal);
+        assert!(out.expr.contains("vec3f(1.0, -1.0, 1.0)"));
+        assert_expr_validates(&ctx, &out, true);
+
+        let node = shading_node("bump", "Bump", serde_json::json!({ "invert": true }));
+        let scene = test_scene(
+            vec![node.clone()],
+            vec![
+                test_connection("blend", "value", "bump", "height"),
+                test_connection("n", "value", "bump", "normal"),
+            ],
+        );
+        let mut ctx = MaterialCompileContext::default();
+        let out = compile_bump(
+            &scene,
+            &HashMap::new(),
+            &node,
+            Some("normal"),
+            &mut ctx,
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(out.ty, ValueType::Vec3);
+        assert!(out.uses_time);
+        assert!(!ctx.needs_surface_normal);
+        assert!(out.expr.ends_with(", -(1.0))"));
+        assert_expr_validates(&ctx, &out, false);
+    }
+
+    #[test]
+    fn screen_derivative_keeps_the_input_type() {
+        let node = shading_node(
+            "d",
+            "ScreenDerivative",
+            serde_json::json!({ "precision": "fine" }),
+        );
+        let scene = test_scene(
+            vec![node.clone()],
+            vec![test_connection("uv", "value", "d", "value")],
+        );
+        for (port, builtin) in [
+            ("ddx", "dpdxFine("),
+            ("ddy", "dpdyFine("),
+            ("fwidth", "fwidthFine("),
+        ] {
+            let mut ctx = MaterialCompileContext::default();
+            let out = compile_screen_derivative(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some(port),
+                &mut ctx,
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(out.ty, ValueType::Vec2);
+            assert!(out.expr.starts_with(builtin), "{port}: {}", out.expr);
+            let out = TypedExpr::new(format!("vec4f({}, 0.0, 1.0)", out.expr), ValueType::Vec4);
+            assert_expr_validates(&ctx, &out, false);
+        }
+    }
+
+    #[test]
+    fn facing_requests_the_front_facing_builtin() {
+        let node = shading_node("f", "Facing", serde_json::json!({}));
+        let mut ctx = MaterialCompileContext::default();
+        let facing = compile_facing(&node, None, &mut ctx).unwrap();
+        let sign = compile_facing(&node, Some("sign"), &mut ctx).unwrap();
+        assert!(ctx.uses_front_facing);
+        assert!(compile_facing(&node, Some("normal"), &mut ctx).is_err());
+
+        let out = TypedExpr::new(format!("{} * {}", facing.expr, sign.expr), ValueType::F32);
+        assert_expr_validates(&ctx, &out, false);
+    }
+}
@@ -1,4 +1,5 @@
-//! Compilers for texture nodes (ImageTexture, CheckerTexture, GradientTexture, NoiseTexture, Matcap).
+//! Compilers for texture nodes (ImageTexture, CheckerTexture, GradientTexture, NoiseTexture, Matcap,
+//! EnvironmentTexture, Text).
 
 use anyhow::{Result, bail};
 use std::collections::HashMap;
@@ -6,7 +7,13 @@ use std::collections::HashMap;
 use super::super::types::{MaterialCompileContext, PassTextureRef, TypedExpr, ValueType};
 use crate::dsl::{Node, SceneDSL, incoming_connection};
 use crate::renderer::geometry_resolver::is_pass_like_node_type;
+use crate::renderer::shader_space::sampler::{
+    MipmapFilter, mip_atlas_address_mode, mip_atlas_binding_id, mipmap_filter_from_node_params,
+    sampler_kind_from_node_params,
+};
+use crate::renderer::shader_space::text_atlas::TextEncoding;
 use crate::renderer::utils::{coerce_to_type, fmt_f32};
+use crate::renderer::wgsl_templates::mip_atlas::mip_atlas_sampling_wgsl;
 
 /// Stable key for the aspect-correction WGSL helpers in `extra_wgsl_decls`.
 const ASPECT_CORRECT_WGSL_LIB_KEY: &str = "aspect_correct_uv_lib";
@@ -20,6 +27,89 @@ fn texture_temp_name(
     super::readable_node_temp_name(ctx, "fs", node, out_port, suffix)
 }
 
+/// Connected input coerced to the type implied by `default` (f32, vec2 or vec4), otherwise
+/// the `port_id` param (a number or array, padded from `default`).
+fn resolve_param_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    default: &[f32],
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let (ty, ctor) = match default.len() {
+        1 => (ValueType::F32, ""),
+        2 => (ValueType::Vec2, "vec2f"),
+        4 => (ValueType::Vec4, "vec4f"),
+        n => bail!("unsupported default arity {n} for {}.{port_id}", node.id),
+    };
+    if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+        let raw = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        return coerce_to_type(raw, ty);
+    }
+
+    let mut values = default.to_vec();
+    match node.params.get(port_id) {
+        Some(serde_json::Value::Array(arr)) => {
+            for (slot, v) in values.iter_mut().zip(arr) {
+                if let Some(v) = v.as_f64() {
+                    *slot = v as f32;
+                }
+            }
+        }
+        Some(v) => {
+            if let Some(v) = v.as_f64() {
+                values.fill(v as f32);
+            }
+        }
+        None => {}
+    }
+    let parts: Vec<String> = values.iter().map(|v| fmt_f32(*v)).collect();
+    if ty == ValueType::F32 {
+        return Ok(TypedExpr::new(parts[0].clone(), ty));
+    }
+    Ok(TypedExpr::new(format!("{ctor}({})", parts.join(", ")), ty))
+}
+
+/// The xy of the `vector` input for procedural textures, defaulting to `in.uv`.
+fn resolve_texture_coord<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let Some(conn) = incoming_connection(scene, &node.id, "vector") else {
+        return Ok(TypedExpr::new("in.uv".to_string(), ValueType::Vec2));
+    };
+    let raw = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+    match raw.ty {
+        ValueType::Vec3 | ValueType::Vec4 => Ok(TypedExpr::with_time(
+            format!("({}).xy", raw.expr),
+            ValueType::Vec2,
+            raw.uses_time,
+        )),
+        _ => coerce_to_type(raw, ValueType::Vec2),
+    }
+}
+
 /// Ensure the aspect-correction UV helper functions are emitted exactly once.
 ///
 /// Both helpers rescale `uv` around (0.5, 0.5) so the texture preserves its natural pixel
@@ -56,6 +146,23 @@ fn aspect_correct_uv_fill(uv: vec2f, img_dim: vec2f, geo_dim: vec2f) -> vec2f {
         .insert(ASPECT_CORRECT_WGSL_LIB_KEY.to_string(), wgsl.to_string());
 }
 
+/// Stable key for the mip atlas sampling helpers in `extra_wgsl_decls`.
+const MIP_ATLAS_WGSL_LIB_KEY: &str = "mip_atlas_lib";
+
+fn ensure_mip_atlas_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    ctx.extra_wgsl_decls
+        .entry(MIP_ATLAS_WGSL_LIB_KEY.to_string())
+        .or_insert_with(|| mip_atlas_sampling_wgsl(false));
+}
+
+/// Swap the mip atlas helpers for their derivative-free variant, which samples
+/// the base level. Compute stages call this before emitting their decls.
+pub(crate) fn use_compute_mip_atlas_sampling(ctx: &mut MaterialCompileContext) {
+    if let Some(lib) = ctx.extra_wgsl_decls.get_mut(MIP_ATLAS_WGSL_LIB_KEY) {
+        *lib = mip_atlas_sampling_wgsl(true);
+    }
+}
+
 /// Compile an ImageTexture node.
 ///
 /// Samples a texture at a given UV coordinate and returns the color or alpha channel.
@@ -72,6 +179,10 @@ fn aspect_correct_uv_fill(uv: vec2f, img_dim: vec2f, geo_dim: vec2f) -> vec2f {
 /// - `"off"`  (default): legacy behavior, sample UV directly.
 /// - `"fit"`:  object-fit: contain — preserves natural aspect, image fully visible.
 /// - `"fill"`: object-fit: cover   — preserves natural aspect, fills the geometry.
+///
+/// `mipmapFilter` (`"linear"` | `"nearest"`) samples the image's mip atlas through
+/// `nf_mip_atlas_sample` (see `wgsl_templates::mip_atlas`); the `texture` output always
+/// exposes the base image.
 pub fn compile_image_texture<F>(
     scene: &SceneDSL,
     _nodes_by_id: &HashMap<String, Node>,
@@ -89,18 +200,25 @@ where
         &mut HashMap<(String, String), TypedExpr>,
     ) -> Result<TypedExpr>,
 {
-    // WGSL is emitted to actually sample a bound texture. The runtime will bind the
-    // texture + sampler; for headless tests we only need valid WGSL.
-    let _image_index = ctx.register_image_texture(&node.id);
     let port = out_port.unwrap_or("color");
 
     if !matches!(port, "color" | "alpha" | "texture") {
         bail!("unsupported ImageTexture output port: {port}");
     }
 
+    // WGSL is emitted to actually sample a bound texture. The runtime will bind the
+    // texture + sampler; for headless tests we only need valid WGSL.
     if port == "texture" {
+        ctx.register_image_texture(&node.id);
         return Ok(TypedExpr::new(node.id.clone(), ValueType::Texture2D));
     }
+    // Mip-filtered sampling reads the atlas; the `texture` output keeps the base image.
+    let mip_filter = mipmap_filter_from_node_params(&node.params);
+    let binding_id = match mip_filter {
+        Some(_) => mip_atlas_binding_id(&node.id),
+        None => node.id.clone(),
+    };
+    ctx.register_image_texture(&binding_id);
 
     // If an explicit UV input is provided, respect it; otherwise default to the fragment input uv.
     let uv_expr: TypedExpr = if let Some(conn) = incoming_connection(scene, &node.id, "uv") {
@@ -113,27 +231,33 @@ where
         bail!("ImageTexture.uv must be vector2, got {:?}", uv_expr.ty);
     }
 
-    let tex_var = MaterialCompileContext::tex_var_name(&node.id);
-    let samp_var = MaterialCompileContext::sampler_var_name(&node.id);
+    let tex_var = MaterialCompileContext::tex_var_name(&binding_id);
+    let samp_var = MaterialCompileContext::sampler_var_name(&binding_id);
 
     let aspect_mode = node
         .params
         .get("aspectCorrection")
         .and_then(|v| v.as_str())
         .unwrap_or("off");
+    let image_dim = if mip_filter.is_some() {
+        ensure_mip_atlas_wgsl_lib(ctx);
+        format!("vec2f(nf_mip_atlas_base_size({tex_var}))")
+    } else {
+        format!("vec2f(textureDimensions({tex_var}))")
+    };
 
     let sample_uv = match aspect_mode {
         "fit" => {
             ensure_aspect_correct_wgsl_lib(ctx);
             format!(
-                "aspect_correct_uv_fit(({}), vec2f(textureDimensions({tex_var})), in.geo_size_px)",
+                "aspect_correct_uv_fit(({}), {image_dim}, in.geo_size_px)",
                 uv_expr.expr
             )
         }
         "fill" => {
             ensure_aspect_correct_wgsl_lib(ctx);
             format!(
-                "aspect_correct_uv_fill(({}), vec2f(textureDimensions({tex_var})), in.geo_size_px)",
+                "aspect_correct_uv_fill(({}), {image_dim}, in.geo_size_px)",
                 uv_expr.expr
             )
         }
@@ -155,7 +279,14 @@ where
     };
 
     // UVs here are already in the renderer's GL-like convention: (0,0) bottom-left.
-    let sample_expr = format!("textureSample({tex_var}, {samp_var}, {sample_uv})");
+    let sample_expr = match mip_filter {
+        Some(filter) => format!(
+            "nf_mip_atlas_sample({tex_var}, {samp_var}, {sample_uv}, {}u, {})",
+            mip_atlas_address_mode(sampler_kind_from_node_params(&node.params)),
+            filter == MipmapFilter::Linear
+        ),
+        None => format!("textureSample({tex_var}, {samp_var}, {sample_uv})"),
+    };
     let sample_var = texture_temp_name(ctx, node, port, "sample");
 
     match port {
@@ -189,6 +320,506 @@ where
     }
 }
 
+// ---------------------------------------------------------------------------
+// GradientTexture
+// ---------------------------------------------------------------------------
+
+/// Stable key for the gradient WGSL helper library in `extra_wgsl_decls`.
+const GRADIENT_WGSL_LIB_KEY: &str = "gradient_texture_lib";
+
+fn ensure_gradient_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx.extra_wgsl_decls.contains_key(GRADIENT_WGSL_LIB_KEY) {
+        return;
+    }
+
+    // `d` is the coordinate relative to the center, `dir` the unit direction of `angle`.
+    let wgsl = r#"
+fn gradient_linear(d: vec2f, dir: vec2f, radius: f32) -> f32 {
+    return clamp(dot(d, dir) / max(2.0 * radius, 1e-6) + 0.5, 0.0, 1.0);
+}
+fn gradient_radial(d: vec2f, radius: f32) -> f32 {
+    return clamp(length(d) / max(radius, 1e-6), 0.0, 1.0);
+}
+fn gradient_angular(d: vec2f, dir: vec2f) -> f32 {
+    // Counter-clockwise sweep starting at `dir`.
+    let local = vec2f(dot(d, dir), dir.x * d.y - dir.y * d.x);
+    return fract(atan2(local.y, local.x) * 0.15915494 + 1.0);
+}
+fn gradient_diamond(d: vec2f, dir: vec2f, radius: f32) -> f32 {
+    let local = vec2f(dot(d, dir), dir.x * d.y - dir.y * d.x);
+    return clamp((abs(local.x) + abs(local.y)) / max(radius, 1e-6), 0.0, 1.0);
+}
+"#;
+
+    ctx.extra_wgsl_decls
+        .insert(GRADIENT_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+/// Compile a GradientTexture node.
+///
+/// Produces a 0..1 factor from the xy of `vector` (default `in.uv`), ready for ColorRamp.
+///
+/// # Params / inputs
+/// - `gradientType`: `linear` (default), `radial`, `angular` or `diamond`
+/// - `center`: vec2 gradient origin (default `(0.5, 0.5)`)
+/// - `angle`: f32 direction in degrees, counter-clockwise from +X (default 0); orients the
+///   linear ramp, the angular seam and the diamond
+/// - `radius`: f32 extent (default 0.5); linear spans `center ± radius`, radial and diamond
+///   reach 1 at `radius`
+///
+/// # Output
+/// - `factor`: f32 in 0..1
+/// - `color`: vec4 grey `(factor, factor, factor, 1)`
+pub fn compile_gradient_texture<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("factor");
+    if port != "factor" && port != "color" {
+        bail!("GradientTexture: unsupported output port '{port}'");
+    }
+    let gradient_type = node
+        .params
+        .get("gradientType")
+        .and_then(|v| v.as_str())
+        .unwrap_or("linear");
+    if !matches!(gradient_type, "linear" | "radial" | "angular" | "diamond") {
+        bail!("GradientTexture: unsupported gradientType '{gradient_type}'");
+    }
+
+    let coord = resolve_texture_coord(scene, node, ctx, cache, &compile_fn)?;
+    let center = resolve_param_input(scene, node, "center", &[0.5, 0.5], ctx, cache, &compile_fn)?;
+    let angle = resolve_param_input(scene, node, "angle", &[0.0], ctx, cache, &compile_fn)?;
+    let radius = resolve_param_input(scene, node, "radius", &[0.5], ctx, cache, &compile_fn)?;
+
+    ensure_gradient_wgsl_lib(ctx);
+    let d_var = texture_temp_name(ctx, node, port, "d");
+    super::push_readable_let(
+        ctx,
+        format!("GradientTexture {} offset", node.id),
+        &d_var,
+        &format!("({}) - ({})", coord.expr, center.expr),
+    );
+    let dir = format!("vec2f(cos(radians({0})), sin(radians({0})))", angle.expr);
+    let factor = match gradient_type {
+        "radial" => format!("gradient_radial({d_var}, {})", radius.expr),
+        "angular" => format!("gradient_angular({d_var}, {dir})"),
+        "diamond" => format!("gradient_diamond({d_var}, {dir}, {})", radius.expr),
+        _ => format!("gradient_linear({d_var}, {dir}, {})", radius.expr),
+    };
+
+    let uses_time = coord.uses_time || center.uses_time || angle.uses_time || radius.uses_time;
+    if port == "color" {
+        return Ok(TypedExpr::with_time(
+            format!("vec4f(vec3f({factor}), 1.0)"),
+            ValueType::Vec4,
+            uses_time,
+        ));
+    }
+    Ok(TypedExpr::with_time(factor, ValueType::F32, uses_time))
+}
+
+// ---------------------------------------------------------------------------
+// CheckerTexture / BrickTexture / WaveTexture
+// ---------------------------------------------------------------------------
+
+/// Stable key for the procedural pattern WGSL helpers in `extra_wgsl_decls`.
+const PATTERN_WGSL_LIB_KEY: &str = "pattern_texture_lib";
+
+fn ensure_pattern_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx.extra_wgsl_decls.contains_key(PATTERN_WGSL_LIB_KEY) {
+        return;
+    }
+
+    let wgsl = r#"
+fn pattern_hash21(p: vec2f) -> f32 {
+    let q = fract(p * vec2f(0.1031, 0.1030));
+    let r = q + dot(q, q.yx + 33.33);
+    return fract((r.x + r.y) * r.x);
+}
+fn pattern_value_noise(p: vec2f) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = pattern_hash21(i);
+    let b = pattern_hash21(i + vec2f(1.0, 0.0));
+    let c = pattern_hash21(i + vec2f(0.0, 1.0));
+    let d = pattern_hash21(i + vec2f(1.0, 1.0));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+fn pattern_checker(p: vec2f) -> f32 {
+    let c = floor(p);
+    return abs(c.x + c.y - 2.0 * floor((c.x + c.y) * 0.5));
+}
+// x: mortar factor (1 in the joint), y: per-brick random value.
+fn pattern_brick(
+    p: vec2f,
+    brick_width: f32,
+    row_height: f32,
+    offset: f32,
+    offset_frequency: f32,
+    mortar_size: f32,
+    mortar_smooth: f32,
+) -> vec2f {
+    let w = max(brick_width, 1e-6);
+    let h = max(row_height, 1e-6);
+    let row = floor(p.y / h);
+    let freq = max(floor(offset_frequency), 1.0);
+    let shifted = row - freq * floor(row / freq) == 0.0;
+    let x = p.x + select(0.0, offset * w, shifted);
+    let col = floor(x / w);
+    let local = vec2f(x - col * w, p.y - row * h);
+    let edge = min(min(local.x, w - local.x), min(local.y, h - local.y));
+    var mortar = 0.0;
+    if (edge < mortar_size) {
+        mortar = select(
+            smoothstep(0.0, mortar_smooth, 1.0 - edge / max(mortar_size, 1e-6)),
+            1.0,
+            mortar_smooth <= 0.0,
+        );
+    }
+    return vec2f(mortar, pattern_hash21(vec2f(col, row) + vec2f(0.5, 17.5)));
+}
+// profile: 0 sine, 1 saw, 2 triangle.
+fn pattern_wave(n_in: f32, profile: u32) -> f32 {
+    if (profile == 1u) {
+        return fract(n_in * 0.15915494);
+    }
+    if (profile == 2u) {
+        let n = n_in * 0.15915494;
+        return abs(n - floor(n + 0.5)) * 2.0;
+    }
+    return 0.5 + 0.5 * sin(n_in - 1.5707963);
+}
+"#;
+
+    ctx.extra_wgsl_decls
+        .insert(PATTERN_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+fn mix_pattern_colors(
+    color1: &TypedExpr,
+    color2: &TypedExpr,
+    factor: &str,
+    uses_time: bool,
+) -> TypedExpr {
+    TypedExpr::with_time(
+        format!("mix({}, {}, {factor})", color1.expr, color2.expr),
+        ValueType::Vec4,
+        uses_time || color1.uses_time || color2.uses_time,
+    )
+}
+
+/// Compile a CheckerTexture node.
+///
+/// # Inputs
+/// - `vector`: coordinate, xy used (default `in.uv`)
+/// - `scale`: f32 cells per unit (default 5)
+/// - `offset`: vec2 added to the scaled coordinate (default 0)
+/// - `color1` / `color2`: vec4 cell colors (default black / white)
+///
+/// # Output
+/// - `factor`: 0 on `color1` cells, 1 on `color2` cells
+/// - `color`: vec4 cell color
+pub fn compile_checker_texture<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("color");
+    if port != "factor" && port != "color" {
+        bail!("CheckerTexture: unsupported output port '{port}'");
+    }
+
+    let coord = resolve_texture_coord(scene, node, ctx, cache, &compile_fn)?;
+    let scale = resolve_param_input(scene, node, "scale", &[5.0], ctx, cache, &compile_fn)?;
+    let offset = resolve_param_input(scene, node, "offset", &[0.0, 0.0], ctx, cache, &compile_fn)?;
+    let uses_time = coord.uses_time || scale.uses_time || offset.uses_time;
+
+    ensure_pattern_wgsl_lib(ctx);
+    let factor_var = texture_temp_name(ctx, node, port, "factor");
+    super::push_readable_let(
+        ctx,
+        format!("CheckerTexture {}", node.id),
+        &factor_var,
+        &format!(
+            "pattern_checker(({}) * ({}) + ({}))",
+            coord.expr, scale.expr, offset.expr
+        ),
+    );
+    if port == "factor" {
+        return Ok(TypedExpr::with_time(factor_var, ValueType::F32, uses_time));
+    }
+
+    let color1 = resolve_param_input(
+        scene,
+        node,
+        "color1",
+        &[0.0, 0.0, 0.0, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let color2 = resolve_param_input(
+        scene,
+        node,
+        "color2",
+        &[1.0, 1.0, 1.0, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    Ok(mix_pattern_colors(&color1, &color2, &factor_var, uses_time))
+}
+
+/// Compile a BrickTexture node (running bond, Blender-style).
+///
+/// # Inputs
+/// - `vector`: coordinate, xy used (default `in.uv`)
+/// - `scale`: f32 coordinate multiplier (default 5)
+/// - `brickWidth` / `rowHeight`: f32 brick size in scaled units (default 0.5 / 0.25)
+/// - `offset`: f32 row shift as a fraction of `brickWidth` (default 0.5)
+/// - `offsetFrequency`: shift every n-th row (param only, default 2)
+/// - `mortarSize`: f32 joint half-width in scaled units (default 0.02)
+/// - `mortarSmooth`: f32 softness of the joint edge, 0 is hard (default 0.1)
+/// - `color1` / `color2`: vec4 brick colors, each brick picks a random mix
+/// - `mortar`: vec4 joint color
+///
+/// # Output
+/// - `factor`: mortar factor, 1 in the joints
+/// - `color`: vec4 brick / mortar color
+pub fn compile_brick_texture<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("color");
+    if port != "factor" && port != "color" {
+        bail!("BrickTexture: unsupported output port '{port}'");
+    }
+
+    let coord = resolve_texture_coord(scene, node, ctx, cache, &compile_fn)?;
+    let scale = resolve_param_input(scene, node, "scale", &[5.0], ctx, cache, &compile_fn)?;
+    let brick_width =
+        resolve_param_input(scene, node, "brickWidth", &[0.5], ctx, cache, &compile_fn)?;
+    let row_height =
+        resolve_param_input(scene, node, "rowHeight", &[0.25], ctx, cache, &compile_fn)?;
+    let offset = resolve_param_input(scene, node, "offset", &[0.5], ctx, cache, &compile_fn)?;
+    let mortar_size =
+        resolve_param_input(scene, node, "mortarSize", &[0.02], ctx, cache, &compile_fn)?;
+    let mortar_smooth =
+        resolve_param_input(scene, node, "mortarSmooth", &[0.1], ctx, cache, &compile_fn)?;
+    let offset_frequency = crate::dsl::parse_f32(&node.params, "offsetFrequency").unwrap_or(2.0);
+    let uses_time = coord.uses_time
+        || [
+            &scale,
+            &brick_width,
+            &row_height,
+            &offset,
+            &mortar_size,
+            &mortar_smooth,
+        ]
+        .iter()
+        .any(|expr| expr.uses_time);
+
+    ensure_pattern_wgsl_lib(ctx);
+    let brick_var = texture_temp_name(ctx, node, port, "brick");
+    super::push_readable_let(
+        ctx,
+        format!("BrickTexture {}", node.id),
+        &brick_var,
+        &format!(
+            "pattern_brick(({}) * ({}), {}, {}, {}, {}, {}, {})",
+            coord.expr,
+            scale.expr,
+            brick_width.expr,
+            row_height.expr,
+            offset.expr,
+            fmt_f32(offset_frequency),
+            mortar_size.expr,
+            mortar_smooth.expr
+        ),
+    );
+    if port == "factor" {
+        return Ok(TypedExpr::with_time(
+            format!("{brick_var}.x"),
+            ValueType::F32,
+            uses_time,
+        ));
+    }
+
+    let color1 = resolve_param_input(
+        scene,
+        node,
+        "color1",
+        &[0.8, 0.8, 0.8, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let color2 = resolve_param_input(
+        scene,
+        node,
+        "color2",
+        &[0.2, 0.2, 0.2, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let mortar = resolve_param_input(
+        scene,
+        node,
+        "mortar",
+        &[0.0, 0.0, 0.0, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let brick = mix_pattern_colors(&color1, &color2, &format!("{brick_var}.y"), uses_time);
+    Ok(TypedExpr::with_time(
+        format!("mix({}, {}, {brick_var}.x)", brick.expr, mortar.expr),
+        ValueType::Vec4,
+        brick.uses_time || mortar.uses_time,
+    ))
+}
+
+/// Compile a WaveTexture node (Blender-style bands / rings).
+///
+/// # Params / inputs
+/// - `waveType`: `bands` (default) or `rings`
+/// - `direction`: bands along `x` (default), `y` or `diagonal`
+/// - `profile`: `sine` (default), `saw` or `triangle`
+/// - `vector`: coordinate, xy used (default `in.uv`)
+/// - `scale`: f32 coordinate multiplier (default 5)
+/// - `distortion`: f32 amount of value-noise warp added to the phase (default 0)
+/// - `detailScale`: f32 frequency of the distortion noise (default 1)
+/// - `phase`: f32 phase offset in radians (default 0)
+///
+/// # Output
+/// - `factor`: f32 in 0..1
+/// - `color`: vec4 grey `(factor, factor, factor, 1)`
+pub fn compile_wave_texture<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("factor");
+    if port != "factor" && port != "color" {
+        bail!("WaveTexture: unsupported output port '{port}'");
+    }
+    let param_str = |key: &str, default: &'static str| -> String {
+        node.params
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or(default)
+            .to_string()
+    };
+    let wave_type = param_str("waveType", "bands");
+    let direction = param_str("direction", "x");
+    let profile = match param_str("profile", "sine").as_str() {
+        "sine" => 0,
+        "saw" => 1,
+        "triangle" => 2,
+        other => bail!("WaveTexture: unsupported profile '{other}'"),
+    };
+
+    let coord = resolve_texture_coord(scene, node, ctx, cache, &compile_fn)?;
+    let scale = resolve_param_input(scene, node, "scale", &[5.0], ctx, cache, &compile_fn)?;
+    let distortion =
+        resolve_param_input(scene, node, "distortion", &[0.0], ctx, cache, &compile_fn)?;
+    let detail_scale =
+        resolve_param_input(scene, node, "detailScale", &[1.0], ctx, cache, &compile_fn)?;
+    let phase = resolve_param_input(scene, node, "phase", &[0.0], ctx, cache, &compile_fn)?;
+    let uses_time = [&coord, &scale, &distortion, &detail_scale, &phase]
+        .iter()
+        .any(|expr| expr.uses_time);
+
+    ensure_pattern_wgsl_lib(ctx);
+    let p_var = texture_temp_name(ctx, node, port, "p");
+    super::push_readable_let(
+        ctx,
+        format!("WaveTexture {} coordinate", node.id),
+        &p_var,
+        &format!("({}) * ({})", coord.expr, scale.expr),
+    );
+    let base = match (wave_type.as_str(), direction.as_str()) {
+        ("bands", "x") => format!("{p_var}.x * 20.0"),
+        ("bands", "y") => format!("{p_var}.y * 20.0"),
+        ("bands", "diagonal") => format!("({p_var}.x + {p_var}.y) * 10.0"),
+        ("rings", _) => format!("length({p_var}) * 20.0"),
+        ("bands", other) => bail!("WaveTexture: unsupported direction '{other}'"),
+        (other, _) => bail!("WaveTexture: unsupported waveType '{other}'"),
+    };
+    let phase_var = texture_temp_name(ctx, node, port, "phase");
+    super::push_readable_let(
+        ctx,
+        format!("WaveTexture {} phase", node.id),
+        &phase_var,
+        &format!(
+            "{base} + ({}) * (pattern_value_noise({p_var} * ({})) * 2.0 - 1.0) + ({})",
+            distortion.expr, detail_scale.expr, phase.expr
+        ),
+    );
+    let factor = format!("pattern_wave({phase_var}, {profile}u)");
+    if port == "color" {
+        return Ok(TypedExpr::with_time(
+            format!("vec4f(vec3f({factor}), 1.0)"),
+            ValueType::Vec4,
+            uses_time,
+        ));
+    }
+    Ok(TypedExpr::with_time(factor, ValueType::F32, uses_time))
+}
+
 // ---------------------------------------------------------------------------
 // Matcap
 // ---------------------------------------------------------------------------
@@ -317,6 +948,247 @@ where
     ))
 }
 
+// ---------------------------------------------------------------------------
+// EnvironmentTexture
+// ---------------------------------------------------------------------------
+
+/// Stable key for the equirectangular lookup helper in `extra_wgsl_decls`.
+const ENVIRONMENT_WGSL_LIB_KEY: &str = "environment_equirect_lib";
+
+/// Ensure the equirectangular direction-to-UV helper is emitted exactly once.
+fn ensure_environment_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx.extra_wgsl_decls.contains_key(ENVIRONMENT_WGSL_LIB_KEY) {
+        return;
+    }
+
+    // Y-up, -Z at the image centre. v follows the renderer's bottom-left UV origin, so +Y
+    // samples the top row of the image.
+    let wgsl = r#"
+fn env_equirect_uv(dir: vec3f, rotation: f32) -> vec2f {
+    let d = dir / max(length(dir), 1e-12);
+    let c = cos(rotation);
+    let s = sin(rotation);
+    let r = vec3f(c * d.x + s * d.z, d.y, c * d.z - s * d.x);
+    let u = atan2(r.x, -r.z) * 0.15915494 + 0.5;
+    let v = asin(clamp(r.y, -1.0, 1.0)) * 0.31830989 + 0.5;
+    return vec2f(fract(u), v);
+}
+"#;
+
+    ctx.extra_wgsl_decls
+        .insert(ENVIRONMENT_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+/// Compile an EnvironmentTexture node.
+///
+/// Samples an equirectangular (lat-long) image by direction. The image is bound like
+/// ImageTexture (`assetId` / `dataUrl` / `path`).
+///
+/// # Inputs
+/// - `vector`: vec3 lookup direction (optional; defaults to the +Z view reflected about the
+///   surface normal, i.e. a mirror reflection)
+/// - `rotation`: f32 rotation around the up axis in degrees (default 0)
+/// - `intensity`: f32 multiplier applied to rgb (default 1)
+///
+/// # Output
+/// - `color`: vec4 sampled environment color
+pub fn compile_environment_texture<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("color");
+    if port != "color" {
+        bail!("EnvironmentTexture: unsupported output port '{port}'");
+    }
+
+    let _image_index = ctx.register_image_texture(&node.id);
+    ensure_environment_wgsl_lib(ctx);
+
+    let direction = if let Some(conn) = incoming_connection(scene, &node.id, "vector") {
+        let raw = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        coerce_to_type(raw, ValueType::Vec3)?
+    } else {
+        ctx.needs_surface_normal = true;
+        TypedExpr::new(
+            "reflect(vec3f(0.0, 0.0, -1.0), surfaceNormal(in))",
+            ValueType::Vec3,
+        )
+    };
+    let mut scalar_input = |port_id: &str, default: f32| -> Result<TypedExpr> {
+        if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+            let raw = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+            return coerce_to_type(raw, ValueType::F32);
+        }
+        let v = crate::dsl::parse_f32(&node.params, port_id).unwrap_or(default);
+        Ok(TypedExpr::new(fmt_f32(v), ValueType::F32))
+    };
+    let rotation = scalar_input("rotation", 0.0)?;
+    let intensity = scalar_input("intensity", 1.0)?;
+
+    let tex_var = MaterialCompileContext::tex_var_name(&node.id);
+    let samp_var = MaterialCompileContext::sampler_var_name(&node.id);
+    let sample_var = texture_temp_name(ctx, node, port, "sample");
+    // Explicit LOD: the atan2 seam would otherwise produce huge derivatives.
+    super::push_readable_let(
+        ctx,
+        format!("EnvironmentTexture {}.color", node.id),
+        &sample_var,
+        &format!(
+            "textureSampleLevel({tex_var}, {samp_var}, env_equirect_uv({}, radians({})), 0.0)",
+            direction.expr, rotation.expr
+        ),
+    );
+
+    Ok(TypedExpr::with_time(
+        format!(
+            "vec4f(({sample_var}).rgb * ({}), ({sample_var}).a)",
+            intensity.expr
+        ),
+        ValueType::Vec4,
+        direction.uses_time || rotation.uses_time || intensity.uses_time,
+    ))
+}
+
+/// Compile a Text node.
+///
+/// The string is rasterized at plan time (see `shader_space::text_atlas`) and bound like
+/// ImageTexture; this samples it and turns the atlas back into coverage.
+///
+/// # Inputs
+/// - `uv`: vec2 sample coordinate (optional; defaults to `in.uv`)
+/// - `color`: vec4 straight-alpha text color (default opaque white)
+///
+/// # Params
+/// - `mode`: `sdf` (default) or `bitmap`
+/// - `aspectCorrection`: `fit` (default), `fill` or `off`
+///
+/// # Output
+/// - `color`: vec4 premultiplied text color
+/// - `alpha`: f32 coverage
+pub fn compile_text<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("color");
+    if !matches!(port, "color" | "alpha") {
+        bail!("Text: unsupported output port '{port}'");
+    }
+    let encoding = TextEncoding::from_node(node)?;
+
+    let _image_index = ctx.register_image_texture(&node.id);
+    let uv = if let Some(conn) = incoming_connection(scene, &node.id, "uv") {
+        let raw = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        coerce_to_type(raw, ValueType::Vec2)?
+    } else {
+        TypedExpr::new("in.uv".to_string(), ValueType::Vec2)
+    };
+
+    let tex_var = MaterialCompileContext::tex_var_name(&node.id);
+    let samp_var = MaterialCompileContext::sampler_var_name(&node.id);
+    let sample_uv = match node
+        .params
+        .get("aspectCorrection")
+        .and_then(|v| v.as_str())
+        .unwrap_or("fit")
+    {
+        mode @ ("fit" | "fill") => {
+            ensure_aspect_correct_wgsl_lib(ctx);
+            let uv_var = texture_temp_name(ctx, node, port, "uv");
+            super::push_readable_let(
+                ctx,
+                format!("Text {} aspect-correct uv", node.id),
+                &uv_var,
+                &format!(
+                    "aspect_correct_uv_{mode}(({}), vec2f(textureDimensions({tex_var})), in.geo_size_px)",
+                    uv.expr
+                ),
+            );
+            uv_var
+        }
+        _ => format!("({})", uv.expr),
+    };
+
+    let sample_var = texture_temp_name(ctx, node, port, "sample");
+    super::push_readable_let(
+        ctx,
+        format!("Text {}.{port}", node.id),
+        &sample_var,
+        &format!("textureSample({tex_var}, {samp_var}, {sample_uv})"),
+    );
+    let coverage = match encoding {
+        TextEncoding::Bitmap => {
+            format!("({sample_var}).a")
+        }
+        // The atlas stores 0.5 on the glyph edge; one screen pixel of antialiasing.
+        TextEncoding::Sdf => format!(
+            "clamp((({sample_var}).a - 0.5) / max(fwidth(({sample_var}).a), 1e-4) + 0.5, 0.0, 1.0)"
+        ),
+    };
+    let coverage_var = texture_temp_name(ctx, node, port, "coverage");
+    super::push_readable_let(
+        ctx,
+        format!("Text {} coverage", node.id),
+        &coverage_var,
+        &coverage,
+    );
+
+    if port == "alpha" {
+        return Ok(TypedExpr::with_time(
+            coverage_var,
+            ValueType::F32,
+            uv.uses_time,
+        ));
+    }
+
+    let color = resolve_param_input(
+        scene,
+        node,
+        "color",
+        &[1.0, 1.0, 1.0, 1.0],
+        ctx,
+        cache,
+        &compile_fn,
+    )?;
+    let color_var = texture_temp_name(ctx, node, port, "tint");
+    super::push_readable_let(
+        ctx,
+        format!("Text {} color", node.id),
+        &color_var,
+        &color.expr,
+    );
+    Ok(TypedExpr::with_time(
+        format!("vec4f({color_var}.rgb * {color_var}.a, {color_var}.a) * {coverage_var}"),
+        ValueType::Vec4,
+        uv.uses_time || color.uses_time,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::test_utils::test_scene;
@@ -517,6 +1389,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_image_texture_mipmap_filter_samples_the_atlas() {
+        let mut scene = aspect_scene(Some("fit"));
+        let params = &mut scene.nodes[0].params;
+        params.insert("mipmapFilter".to_string(), serde_json::json!("linear"));
+        params.insert("addressModeU".to_string(), serde_json::json!("repeat"));
+        let nodes_by_id: HashMap<String, Node> = scene
+            .nodes
+            .iter()
+            .cloned()
+            .map(|n| (n.id.clone(), n))
+            .collect();
+        let node = &scene.nodes[0];
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+
+        compile_image_texture(
+            &scene,
+            &nodes_by_id,
+            node,
+            Some("color"),
+            &mut ctx,
+            &mut cache,
+            mock_compile_fn,
+        )
+        .unwrap();
+
+        let stmts = ctx.inline_stmts.join("\n");
+        assert!(stmts.contains("vec2f(nf_mip_atlas_base_size(img_tex_img1_mips))"));
+        assert!(stmts.contains("nf_mip_atlas_sample(img_tex_img1_mips, img_samp_img1_mips, "));
+        assert!(stmts.contains(", 1u, true)"));
+        assert!(!stmts.contains("textureSample("));
+        assert!(ctx.extra_wgsl_decls.contains_key(MIP_ATLAS_WGSL_LIB_KEY));
+
+        assert_eq!(ctx.image_textures, ["img1#mips"]);
+
+        // The texture output still exposes the base image.
+        compile_image_texture(
+            &scene,
+            &nodes_by_id,
+            node,
+            Some("texture"),
+            &mut ctx,
+            &mut cache,
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(ctx.image_textures, ["img1#mips", "img1"]);
+    }
+
     #[test]
     fn test_image_texture_aspect_fit() {
         let scene = aspect_scene(Some("fit"));
@@ -855,4 +1777,251 @@ mod pass_texture_tests {
         assert_eq!(result.ty, ValueType::F32);
         assert!(result.expr.contains(".w"));
     }
+
+    #[test]
+    fn test_gradient_texture_types_compile_to_valid_wgsl() {
+        for (gradient_type, needle) in [
+            ("linear", "gradient_linear("),
+            ("radial", "gradient_radial("),
+            ("angular", "gradient_angular("),
+            ("diamond", "gradient_diamond("),
+        ] {
+            let scene = test_scene(
+                vec![Node {
+                    id: "grad".to_string(),
+                    node_type: "GradientTexture".to_string(),
+                    params: serde_json::from_value(serde_json::json!({
+                        "gradientType": gradient_type,
+                        "center": [0.25, 0.75],
+                        "angle": 30.0
+                    }))
+                    .unwrap(),
+                    inputs: Vec::new(),
+                    input_bindings: Vec::new(),
+                    outputs: Vec::new(),
+                    wgsl_override: None,
+                }],
+                Vec::new(),
+            );
+            let mut ctx = MaterialCompileContext::default();
+            let result = compile_gradient_texture(
+                &scene,
+                &HashMap::new(),
+                &scene.nodes[0],
+                Some("color"),
+                &mut ctx,
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .unwrap();
+
+            assert_eq!(result.ty, ValueType::Vec4);
+            assert!(
+                result.expr.contains(needle),
+                "{gradient_type}: {}",
+                result.expr
+            );
+            let stmts = ctx.inline_stmts.join("\n");
+            assert!(stmts.contains("(in.uv) - (vec2f(0.25, 0.75))"));
+            let module = format!(
+                "struct VSOut {{ @location(0) uv: vec2f }};\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n{stmts}\n    return {};\n}}\n",
+                ctx.wgsl_decls(),
+                result.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+        }
+    }
+
+    #[test]
+    fn test_pattern_textures_compile_to_valid_wgsl() {
+        type PatternCompiler = fn(
+            &SceneDSL,
+            &HashMap<String, Node>,
+            &Node,
+            Option<&str>,
+            &mut MaterialCompileContext,
+            &mut HashMap<(String, String), TypedExpr>,
+            fn(
+                &str,
+                Option<&str>,
+                &mut MaterialCompileContext,
+                &mut HashMap<(String, String), TypedExpr>,
+            ) -> Result<TypedExpr>,
+        ) -> Result<TypedExpr>;
+        let cases: [(&str, serde_json::Value, PatternCompiler, &str); 4] = [
+            (
+                "CheckerTexture",
+                serde_json::json!({ "scale": 8.0, "offset": [0.5, 0.0] }),
+                compile_checker_texture,
+                "pattern_checker(",
+            ),
+            (
+                "BrickTexture",
+                serde_json::json!({ "mortarSize": 0.05, "offsetFrequency": 3 }),
+                compile_brick_texture,
+                "pattern_brick(",
+            ),
+            (
+                "WaveTexture",
+                serde_json::json!({ "profile": "triangle", "distortion": 2.0 }),
+                compile_wave_texture,
+                "pattern_value_noise(",
+            ),
+            (
+                "WaveTexture",
+                serde_json::json!({ "waveType": "rings", "profile": "saw" }),
+                compile_wave_texture,
+                "length(",
+            ),
+        ];
+        for (node_type, params, compile, needle) in cases {
+            let scene = test_scene(
+                vec![Node {
+                    id: "pattern".to_string(),
+                    node_type: node_type.to_string(),
+                    params: serde_json::from_value(params).unwrap(),
+                    inputs: Vec::new(),
+                    input_bindings: Vec::new(),
+                    outputs: Vec::new(),
+                    wgsl_override: None,
+                }],
+                Vec::new(),
+            );
+            let mut ctx = MaterialCompileContext::default();
+            let result = compile(
+                &scene,
+                &HashMap::new(),
+                &scene.nodes[0],
+                Some("color"),
+                &mut ctx,
+                &mut HashMap::new(),
+                mock_compile_fn,
+            )
+            .unwrap();
+
+            assert_eq!(result.ty, ValueType::Vec4);
+            let stmts = ctx.inline_stmts.join("\n");
+            assert!(stmts.contains(needle), "{node_type}: {stmts}");
+            let module = format!(
+                "struct VSOut {{ @location(0) uv: vec2f }};\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n{stmts}\n    return {};\n}}\n",
+                ctx.wgsl_decls(),
+                result.expr
+            );
+            crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+        }
+    }
+
+    #[test]
+    fn test_environment_texture_defaults_to_mirror_reflection() {
+        let scene = test_scene(
+            vec![Node {
+                id: "env".to_string(),
+                node_type: "EnvironmentTexture".to_string(),
+                params: serde_json::from_value(serde_json::json!({
+                    "rotation": 90.0,
+                    "intensity": 2.0
+                }))
+                .unwrap(),
+                inputs: Vec::new(),
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            }],
+            Vec::new(),
+        );
+        let node = &scene.nodes[0];
+        let mut ctx = MaterialCompileContext::default();
+
+        let result = compile_environment_texture(
+            &scene,
+            &HashMap::new(),
+            node,
+            None,
+            &mut ctx,
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+
+        assert_eq!(result.ty, ValueType::Vec4);
+        assert_eq!(ctx.image_textures, ["env"]);
+        assert!(ctx.needs_surface_normal);
+        assert!(result.expr.contains("* (2.0)"));
+        let stmts = ctx.inline_stmts.join("\n");
+        assert!(stmts.contains(
+            "env_equirect_uv(reflect(vec3f(0.0, 0.0, -1.0), surfaceNormal(in)), radians(90.0))"
+        ));
+
+        let module = format!(
+            "struct VSOut {{ @location(0) uv: vec2f, @location(2) local_px: vec3f }};\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n{stmts}\n    return {};\n}}\n",
+            ctx.wgsl_decls_with_normals(false),
+            result.expr
+        );
+        crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+    }
+
+    #[test]
+    fn test_text_sdf_coverage_is_tinted_and_premultiplied() {
+        let scene = test_scene(
+            vec![Node {
+                id: "label".to_string(),
+                node_type: "Text".to_string(),
+                params: serde_json::from_value(serde_json::json!({
+                    "text": "42",
+                    "color": [1.0, 0.5, 0.0, 0.5]
+                }))
+                .unwrap(),
+                inputs: Vec::new(),
+                input_bindings: Vec::new(),
+                outputs: Vec::new(),
+                wgsl_override: None,
+            }],
+            Vec::new(),
+        );
+        let node = &scene.nodes[0];
+        let mut ctx = MaterialCompileContext::default();
+
+        let result = compile_text(
+            &scene,
+            &HashMap::new(),
+            node,
+            None,
+            &mut ctx,
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+
+        assert_eq!(result.ty, ValueType::Vec4);
+        assert_eq!(ctx.image_textures, ["label"]);
+        let stmts = ctx.inline_stmts.join("\n");
+        assert!(stmts.contains("aspect_correct_uv_fit("));
+        assert!(stmts.contains("fwidth("));
+        assert!(stmts.contains("vec4f(1.0, 0.5, 0.0, 0.5)"));
+
+        let module = format!(
+            "struct VSOut {{ @location(0) uv: vec2f, @location(2) local_px: vec3f, @location(4) geo_size_px: vec2f }};\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n{stmts}\n    return {};\n}}\n",
+            ctx.wgsl_decls_with_normals(false),
+            result.expr
+        );
+        crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+
+        let mut bitmap = node.clone();
+        bitmap
+            .params
+            .insert("mode".to_string(), serde_json::json!("bitmap"));
+        let mut ctx = MaterialCompileContext::default();
+        let alpha = compile_text(
+            &scene,
+            &HashMap::new(),
+            &bitmap,
+            Some("alpha"),
+            &mut ctx,
+            &mut HashMap::new(),
+            mock_compile_fn,
+        )
+        .unwrap();
+        assert_eq!(alpha.ty, ValueType::F32);
+        assert!(!ctx.inline_stmts.join("\n").contains("fwidth("));
+    }
 }
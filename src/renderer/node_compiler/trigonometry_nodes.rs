@@ -1,9 +1,9 @@
 //! Compilers for trigonometry nodes and Time node.
 
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use std::collections::HashMap;
 
-use super::super::types::{MaterialCompileContext, TypedExpr};
+use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
 use super::super::utils::coerce_for_binary;
 use crate::dsl::{Node, SceneDSL, incoming_connection};
 
@@ -136,25 +136,40 @@ where
 
 /// Compile a Time node.
 ///
-/// Returns the current time value from the uniform parameters.
+/// Reads the per-frame clock from the uniform parameters. All passes of a frame share
+/// the same values, so graphs should pick an output port here rather than re-deriving
+/// frame counters or oscillators per node.
+///
+/// # Output ports
+/// - `seconds` (default, alias `time`): scene time in seconds (f32)
+/// - `frame`: frame index driven by the app / headless frame counter (u32)
+/// - `delta`: seconds since the previous frame (f32)
+/// - `sin` / `cos`: `sin(seconds)` / `cos(seconds)` (f32)
 pub fn compile_time(
     _scene: &SceneDSL,
     _nodes_by_id: &HashMap<String, Node>,
-    _node: &Node,
-    _out_port: Option<&str>,
+    node: &Node,
+    out_port: Option<&str>,
     _ctx: &mut MaterialCompileContext,
     _cache: &mut HashMap<(String, String), TypedExpr>,
 ) -> Result<TypedExpr> {
-    Ok(TypedExpr::with_time(
-        "params.time".to_string(),
-        super::super::types::ValueType::F32,
-        true,
-    ))
+    let (expr, ty) = match out_port.unwrap_or("seconds") {
+        "seconds" | "time" => ("params.time", ValueType::F32),
+        "frame" => ("params.frame", ValueType::U32),
+        "delta" => ("params.delta_time", ValueType::F32),
+        "sin" => ("sin(params.time)", ValueType::F32),
+        "cos" => ("cos(params.time)", ValueType::F32),
+        other => bail!(
+            "Time {}: unsupported output port '{}' (expected seconds, frame, delta, sin or cos)",
+            node.id,
+            other
+        ),
+    };
+    Ok(TypedExpr::with_time(expr.to_string(), ty, true))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::super::types::ValueType;
     use super::super::test_utils::{test_connection, test_scene};
     use super::*;
 
@@ -376,4 +391,55 @@ mod tests {
         assert_eq!(result.expr, "params.time");
         assert!(result.uses_time); // Time node always depends on time
     }
+
+    #[test]
+    fn test_time_output_ports() {
+        let scene = test_scene(vec![], vec![]);
+        let nodes_by_id = HashMap::new();
+        let node = Node {
+            id: "time1".to_string(),
+            node_type: "Time".to_string(),
+            params: HashMap::new(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        let mut ctx = MaterialCompileContext::default();
+        let mut cache = HashMap::new();
+
+        let cases = [
+            ("seconds", "params.time", ValueType::F32),
+            ("frame", "params.frame", ValueType::U32),
+            ("delta", "params.delta_time", ValueType::F32),
+            ("sin", "sin(params.time)", ValueType::F32),
+            ("cos", "cos(params.time)", ValueType::F32),
+        ];
+        for (port, expr, ty) in cases {
+            let result = compile_time(
+                &scene,
+                &nodes_by_id,
+                &node,
+                Some(port),
+                &mut ctx,
+                &mut cache,
+            )
+            .unwrap();
+            assert_eq!(result.expr, expr, "port {port}");
+            assert_eq!(result.ty, ty, "port {port}");
+            assert!(result.uses_time, "port {port}");
+        }
+
+        assert!(
+            compile_time(
+                &scene,
+                &nodes_by_id,
+                &node,
+                Some("tan"),
+                &mut ctx,
+                &mut cache
+            )
+            .is_err()
+        );
+    }
 }
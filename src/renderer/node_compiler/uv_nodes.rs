@@ -0,0 +1,412 @@
+//! Compilers for UV utility nodes (UVTransform, PolarCoordinates, PixelateUV).
+//!
+//! All three take an optional `uv` input (default `in.uv`) and output the
+//! remapped coordinate on `uv`, ready to feed a texture or procedural node.
+//! Scalar and vec2 inputs may be connected or given as params.
+//!
+//! UVTransform: rotate by `rotation` degrees around `pivot`, scale by `tile`
+//! around the same pivot, then add `offset`. `wrap` is `none` (default),
+//! `repeat` (fract) or `mirror` (ping-pong).
+//!
+//! PolarCoordinates: `mode` is `toPolar` (default) or `toCartesian`. Polar
+//! coordinates are `(radius, angle)` around `center`, with `radius` 1 on the
+//! circle inscribed in the unit square (times `radialScale`) and `angle` in
+//! 0..1 counter-clockwise from +X (times `lengthScale`). `toCartesian` is the
+//! exact inverse.
+//!
+//! PixelateUV: snaps to a grid of `cells` per unit; `align` picks the cell
+//! `center` (default) or its lower-left `corner`.
+
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+
+use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
+use super::super::utils::{coerce_to_type, fmt_f32};
+use crate::dsl::{Node, SceneDSL, incoming_connection, parse_str};
+
+const UV_WGSL_LIB_KEY: &str = "uv_utils_lib";
+const UV_WGSL_LIB: &str = r#"
+fn uv_transform(uv: vec2f, tile: vec2f, offset: vec2f, angle: f32, pivot: vec2f) -> vec2f {
+    let c = cos(angle);
+    let s = sin(angle);
+    let p = uv - pivot;
+    let r = vec2f(c * p.x - s * p.y, s * p.x + c * p.y);
+    return r * tile + pivot + offset;
+}
+
+fn uv_mirror_repeat(uv: vec2f) -> vec2f {
+    return 1.0 - abs(1.0 - 2.0 * fract(uv * 0.5));
+}
+
+fn uv_to_polar(uv: vec2f, center: vec2f, radial_scale: f32, length_scale: f32) -> vec2f {
+    let d = uv - center;
+    let angle = fract(atan2(d.y, d.x) * 0.15915494309189535);
+    return vec2f(length(d) * 2.0 * radial_scale, angle * length_scale);
+}
+
+fn uv_from_polar(polar: vec2f, center: vec2f, radial_scale: f32, length_scale: f32) -> vec2f {
+    let radius = polar.x / (2.0 * radial_scale);
+    let angle = polar.y / length_scale * 6.283185307179586;
+    return center + radius * vec2f(cos(angle), sin(angle));
+}
+"#;
+
+fn ensure_uv_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    ctx.extra_wgsl_decls
+        .entry(UV_WGSL_LIB_KEY.to_string())
+        .or_insert_with(|| UV_WGSL_LIB.to_string());
+}
+
+/// Connected input coerced to f32 / vec2 (by the length of `default`), otherwise the `port_id`
+/// param as a number or array.
+fn resolve_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    default: &[f32],
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let ty = if default.len() == 1 {
+        ValueType::F32
+    } else {
+        ValueType::Vec2
+    };
+    if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+        let expr = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        if ty == ValueType::Vec2 && matches!(expr.ty, ValueType::Vec3 | ValueType::Vec4) {
+            return Ok(TypedExpr::with_time(
+                format!("({}).xy", expr.expr),
+                ValueType::Vec2,
+                expr.uses_time,
+            ));
+        }
+        return coerce_to_type(expr, ty);
+    }
+
+    let mut values = default.to_vec();
+    match node.params.get(port_id) {
+        Some(serde_json::Value::Array(arr)) => {
+            for (slot, v) in values.iter_mut().zip(arr) {
+                if let Some(v) = v.as_f64() {
+                    *slot = v as f32;
+                }
+            }
+        }
+        Some(serde_json::Value::Object(obj)) => {
+            for (slot, key) in values.iter_mut().zip(["x", "y"]) {
+                if let Some(v) = obj.get(key).and_then(|v| v.as_f64()) {
+                    *slot = v as f32;
+                }
+            }
+        }
+        Some(v) => {
+            if let Some(v) = v.as_f64() {
+                values.fill(v as f32);
+            }
+        }
+        None => {}
+    }
+    let expr = if ty == ValueType::F32 {
+        fmt_f32(values[0])
+    } else {
+        format!("vec2f({}, {})", fmt_f32(values[0]), fmt_f32(values[1]))
+    };
+    Ok(TypedExpr::new(expr, ty))
+}
+
+fn resolve_uv<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    if incoming_connection(scene, &node.id, "uv").is_none() {
+        return Ok(TypedExpr::new("in.uv", ValueType::Vec2));
+    }
+    resolve_input(scene, node, "uv", &[0.0, 0.0], ctx, cache, compile_fn)
+}
+
+fn check_uv_port(node: &Node, out_port: Option<&str>) -> Result<()> {
+    match out_port.unwrap_or("uv") {
+        "uv" => Ok(()),
+        other => bail!("{}: unsupported output port '{other}'", node.node_type),
+    }
+}
+
+pub fn compile_uv_transform<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    check_uv_port(node, out_port)?;
+    let wrap = parse_str(&node.params, "wrap").unwrap_or("none");
+    if !matches!(wrap, "none" | "repeat" | "mirror") {
+        bail!("UVTransform: unsupported wrap '{wrap}'");
+    }
+
+    let uv = resolve_uv(scene, node, ctx, cache, &compile_fn)?;
+    let tile = resolve_input(scene, node, "tile", &[1.0, 1.0], ctx, cache, &compile_fn)?;
+    let offset = resolve_input(scene, node, "offset", &[0.0, 0.0], ctx, cache, &compile_fn)?;
+    let rotation = resolve_input(scene, node, "rotation", &[0.0], ctx, cache, &compile_fn)?;
+    let pivot = resolve_input(scene, node, "pivot", &[0.5, 0.5], ctx, cache, &compile_fn)?;
+
+    ensure_uv_wgsl_lib(ctx);
+    let transformed = format!(
+        "uv_transform({}, {}, {}, radians({}), {})",
+        uv.expr, tile.expr, offset.expr, rotation.expr, pivot.expr
+    );
+    let expr = match wrap {
+        "repeat" => format!("fract({transformed})"),
+        "mirror" => format!("uv_mirror_repeat({transformed})"),
+        _ => transformed,
+    };
+    let uses_time = [&uv, &tile, &offset, &rotation, &pivot]
+        .iter()
+        .any(|e| e.uses_time);
+    Ok(TypedExpr::with_time(expr, ValueType::Vec2, uses_time))
+}
+
+pub fn compile_polar_coordinates<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    check_uv_port(node, out_port)?;
+    let helper = match parse_str(&node.params, "mode").unwrap_or("toPolar") {
+        "toPolar" => "uv_to_polar",
+        "toCartesian" => "uv_from_polar",
+        other => bail!("PolarCoordinates: unsupported mode '{other}'"),
+    };
+
+    let uv = resolve_uv(scene, node, ctx, cache, &compile_fn)?;
+    let center = resolve_input(scene, node, "center", &[0.5, 0.5], ctx, cache, &compile_fn)?;
+    let radial = resolve_input(scene, node, "radialScale", &[1.0], ctx, cache, &compile_fn)?;
+    let length = resolve_input(scene, node, "lengthScale", &[1.0], ctx, cache, &compile_fn)?;
+
+    ensure_uv_wgsl_lib(ctx);
+    let uses_time = [&uv, &center, &radial, &length].iter().any(|e| e.uses_time);
+    Ok(TypedExpr::with_time(
+        format!(
+            "{helper}({}, {}, {}, {})",
+            uv.expr, center.expr, radial.expr, length.expr
+        ),
+        ValueType::Vec2,
+        uses_time,
+    ))
+}
+
+pub fn compile_pixelate_uv<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    check_uv_port(node, out_port)?;
+    let bias = match parse_str(&node.params, "align").unwrap_or("center") {
+        "center" => " + 0.5",
+        "corner" => "",
+        other => bail!("PixelateUV: unsupported align '{other}'"),
+    };
+
+    let uv = resolve_uv(scene, node, ctx, cache, &compile_fn)?;
+    let cells = resolve_input(scene, node, "cells", &[32.0, 32.0], ctx, cache, &compile_fn)?;
+    let cells_expr = format!("max({}, vec2f(1e-4))", cells.expr);
+    Ok(TypedExpr::with_time(
+        format!("((floor({} * {cells_expr}){bias}) / {cells_expr})", uv.expr),
+        ValueType::Vec2,
+        uv.uses_time || cells.uses_time,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::node_compiler::test_utils::{test_connection, test_scene};
+
+    fn mock_compile_fn(
+        node_id: &str,
+        _out_port: Option<&str>,
+        _ctx: &mut MaterialCompileContext,
+        _cache: &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr> {
+        Ok(match node_id {
+            "angle" => TypedExpr::with_time("params.time".to_string(), ValueType::F32, true),
+            _ => TypedExpr::new("vec3f(in.uv, 0.0)".to_string(), ValueType::Vec3),
+        })
+    }
+
+    fn uv_node(node_type: &str, params: serde_json::Value) -> Node {
+        Node {
+            id: "uvn".to_string(),
+            node_type: node_type.to_string(),
+            params: serde_json::from_value(params).unwrap(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn compile_uv_node(
+        scene: &SceneDSL,
+        node: &Node,
+        ctx: &mut MaterialCompileContext,
+    ) -> Result<TypedExpr> {
+        let nodes_by_id = HashMap::new();
+        let mut cache = HashMap::new();
+        match node.node_type.as_str() {
+            "UVTransform" => compile_uv_transform(
+                scene,
+                &nodes_by_id,
+                node,
+                None,
+                ctx,
+                &mut cache,
+                mock_compile_fn,
+            ),
+            "PolarCoordinates" => compile_polar_coordinates(
+                scene,
+                &nodes_by_id,
+                node,
+                None,
+                ctx,
+                &mut cache,
+                mock_compile_fn,
+            ),
+            _ => compile_pixelate_uv(
+                scene,
+                &nodes_by_id,
+                node,
+                None,
+                ctx,
+                &mut cache,
+                mock_compile_fn,
+            ),
+        }
+    }
+
+    #[test]
+    fn uv_utility_nodes_compile_to_valid_wgsl() {
+        let connections = vec![
+            test_connection("coord", "value", "uvn", "uv"),
+            test_connection("angle", "value", "uvn", "rotation"),
+        ];
+        let cases = [
+            (
+                uv_node(
+                    "UVTransform",
+                    serde_json::json!({ "tile": [2.0, 3.0], "offset": { "x": 0.25 }, "wrap": "mirror" }),
+                ),
+                "uv_mirror_repeat(uv_transform((vec3f(in.uv, 0.0)).xy, vec2f(2.0, 3.0), vec2f(0.25, 0.0), radians(params.time), vec2f(0.5, 0.5)))",
+            ),
+            (
+                uv_node(
+                    "PolarCoordinates",
+                    serde_json::json!({ "radialScale": 2.0 }),
+                ),
+                "uv_to_polar((vec3f(in.uv, 0.0)).xy, vec2f(0.5, 0.5), 2.0, 1.0)",
+            ),
+            (
+                uv_node(
+                    "PolarCoordinates",
+                    serde_json::json!({ "mode": "toCartesian" }),
+                ),
+                "uv_from_polar((vec3f(in.uv, 0.0)).xy, vec2f(0.5, 0.5), 1.0, 1.0)",
+            ),
+            (
+                uv_node("PixelateUV", serde_json::json!({ "cells": 16 })),
+                "((floor((vec3f(in.uv, 0.0)).xy * max(vec2f(16.0, 16.0), vec2f(1e-4))) + 0.5) / max(vec2f(16.0, 16.0), vec2f(1e-4)))",
+            ),
+        ];
+        for (node, expected) in cases {
+            let scene = test_scene(vec![node.clone()], connections.clone());
+            let mut ctx = MaterialCompileContext::default();
+            let out = compile_uv_node(&scene, &node, &mut ctx).unwrap();
+            assert_eq!(out.ty, ValueType::Vec2);
+            assert_eq!(out.expr, expected);
+            assert_eq!(out.uses_time, node.node_type == "UVTransform");
+
+            let module = format!(
+                "struct VSOut {{ @location(0) uv: vec2f }};\nstruct Params {{ time: f32 }};\n@group(0) @binding(0) var<uniform> params: Params;\n{}\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    return vec4f({}, 0.0, 1.0);\n}}\n",
+                ctx.wgsl_decls(),
+                out.expr
+            );
+            crate::renderer::validate_wgsl(&module)
+                .unwrap_or_else(|e| panic!("{}: {e:#}", node.node_type));
+        }
+    }
+
+    #[test]
+    fn uv_utility_nodes_reject_unknown_modes() {
+        let scene = test_scene(vec![], vec![]);
+        for node in [
+            uv_node("UVTransform", serde_json::json!({ "wrap": "clamp" })),
+            uv_node(
+                "PolarCoordinates",
+                serde_json::json!({ "mode": "spherical" }),
+            ),
+            uv_node("PixelateUV", serde_json::json!({ "align": "top" })),
+        ] {
+            let mut ctx = MaterialCompileContext::default();
+            assert!(
+                compile_uv_node(&scene, &node, &mut ctx).is_err(),
+                "{}",
+                node.node_type
+            );
+        }
+    }
+}
@@ -1,11 +1,18 @@
-//! Compilers for vector math nodes (VectorMath, CrossProduct, DotProduct, Normalize, Refract).
+//! Compilers for vector math nodes (VectorMath, CrossProduct, DotProduct, Normalize, Refract,
+//! VectorRotate, VectorTransform).
+//!
+//! Angles are in degrees and euler rotations apply X, then Y, then Z, matching the
+//! component mode of TransformGeometry.
 
 use anyhow::{Result, anyhow, bail};
 use std::collections::HashMap;
 
 use super::super::types::{MaterialCompileContext, TypedExpr, ValueType};
-use super::super::utils::coerce_to_type;
-use crate::dsl::{Node, SceneDSL, incoming_connection};
+use super::super::utils::{coerce_to_type, fmt_f32};
+use crate::dsl::{Node, SceneDSL, incoming_connection, parse_f32, parse_str};
+
+const VECTOR_ROTATE_WGSL_LIB_KEY: &str = "vector_rotate_lib";
+const VECTOR_TRANSFORM_WGSL_LIB_KEY: &str = "vector_transform_lib";
 
 /// Compile a DotProduct node.
 ///
@@ -365,6 +372,390 @@ where
     }
 }
 
+/// Read a vec3 param given as `{x, y, z}`, `[x, y, z]` or a scalar to splat.
+fn param_vec3(node: &Node, key: &str, default: [f32; 3]) -> [f32; 3] {
+    let mut out = default;
+    match node.params.get(key) {
+        Some(serde_json::Value::Object(obj)) => {
+            for (slot, axis) in out.iter_mut().zip(["x", "y", "z"]) {
+                if let Some(v) = obj.get(axis).and_then(|v| v.as_f64()) {
+                    *slot = v as f32;
+                }
+            }
+        }
+        Some(serde_json::Value::Array(arr)) => {
+            for (slot, v) in out.iter_mut().zip(arr) {
+                if let Some(v) = v.as_f64() {
+                    *slot = v as f32;
+                }
+            }
+        }
+        Some(v) => {
+            if let Some(v) = v.as_f64() {
+                out = [v as f32; 3];
+            }
+        }
+        None => {}
+    }
+    out
+}
+
+fn vec3_literal(v: [f32; 3]) -> String {
+    format!(
+        "vec3f({}, {}, {})",
+        fmt_f32(v[0]),
+        fmt_f32(v[1]),
+        fmt_f32(v[2])
+    )
+}
+
+/// Connected input coerced to vec3, otherwise the node param with the same id.
+pub(super) fn resolve_vec3_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    default: [f32; 3],
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+        let expr = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        return coerce_to_type(expr, ValueType::Vec3);
+    }
+    Ok(TypedExpr::new(
+        vec3_literal(param_vec3(node, port_id, default)),
+        ValueType::Vec3,
+    ))
+}
+
+/// Connected input coerced to f32, otherwise the node param with the same id.
+pub(super) fn resolve_f32_input<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    port_id: &str,
+    default: f32,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    if let Some(conn) = incoming_connection(scene, &node.id, port_id) {
+        let expr = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        return coerce_to_type(expr, ValueType::F32);
+    }
+    let v = parse_f32(&node.params, port_id).unwrap_or(default);
+    Ok(TypedExpr::new(fmt_f32(v), ValueType::F32))
+}
+
+/// The `vector` input as written, or the `vector` param as a vec3 literal.
+fn resolve_vector_operand<F>(
+    scene: &SceneDSL,
+    node: &Node,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: &F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    if let Some(conn) = incoming_connection(scene, &node.id, "vector") {
+        return compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache);
+    }
+    Ok(TypedExpr::new(
+        vec3_literal(param_vec3(node, "vector", [0.0; 3])),
+        ValueType::Vec3,
+    ))
+}
+
+fn ensure_vector_rotate_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    if ctx
+        .extra_wgsl_decls
+        .contains_key(VECTOR_ROTATE_WGSL_LIB_KEY)
+    {
+        return;
+    }
+
+    let wgsl = r#"
+// ---- VectorRotate helpers (generated) ----
+fn vectorRotate_axisAngle(v: vec3f, center: vec3f, axis: vec3f, angle_deg: f32) -> vec3f {
+    let len = length(axis);
+    if (len < 1e-6) {
+        return v;
+    }
+    let k = axis / len;
+    let p = v - center;
+    let a = radians(angle_deg);
+    let c = cos(a);
+    let s = sin(a);
+    // Rodrigues' rotation formula.
+    return center + p * c + cross(k, p) * s + k * dot(k, p) * (1.0 - c);
+}
+
+// Rz * Ry * Rx: X is applied first, matching TransformGeometry.
+fn vectorRotate_eulerMatrix(rotate_deg: vec3f) -> mat3x3f {
+    let r = radians(rotate_deg);
+    let cx = cos(r.x);
+    let sx = sin(r.x);
+    let cy = cos(r.y);
+    let sy = sin(r.y);
+    let cz = cos(r.z);
+    let sz = sin(r.z);
+    let rx = mat3x3f(1.0, 0.0, 0.0, 0.0, cx, sx, 0.0, -sx, cx);
+    let ry = mat3x3f(cy, 0.0, -sy, 0.0, 1.0, 0.0, sy, 0.0, cy);
+    let rz = mat3x3f(cz, sz, 0.0, -sz, cz, 0.0, 0.0, 0.0, 1.0);
+    return rz * ry * rx;
+}
+
+fn vectorRotate_euler(v: vec3f, center: vec3f, rotate_deg: vec3f, invert: bool) -> vec3f {
+    let m = vectorRotate_eulerMatrix(rotate_deg);
+    let p = v - center;
+    return center + select(m * p, transpose(m) * p, invert);
+}
+"#;
+    ctx.extra_wgsl_decls
+        .insert(VECTOR_ROTATE_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+fn ensure_vector_transform_wgsl_lib(ctx: &mut MaterialCompileContext) {
+    // The TRS builder reuses the euler matrix from the rotate helpers.
+    ensure_vector_rotate_wgsl_lib(ctx);
+    if ctx
+        .extra_wgsl_decls
+        .contains_key(VECTOR_TRANSFORM_WGSL_LIB_KEY)
+    {
+        return;
+    }
+
+    let wgsl = r#"
+// ---- VectorTransform helpers (generated) ----
+// T * R * S with R = Rz * Ry * Rx.
+fn vectorTransform_trs(translate: vec3f, rotate_deg: vec3f, scale: vec3f) -> mat4x4f {
+    let r = vectorRotate_eulerMatrix(rotate_deg);
+    return mat4x4f(
+        vec4f(r[0] * scale.x, 0.0),
+        vec4f(r[1] * scale.y, 0.0),
+        vec4f(r[2] * scale.z, 0.0),
+        vec4f(translate, 1.0),
+    );
+}
+
+// Normals use the inverse transpose of the upper 3x3 (cofactor matrix, sign-corrected).
+fn vectorTransform_normal(m: mat4x4f, n: vec3f) -> vec3f {
+    let c0 = m[0].xyz;
+    let c1 = m[1].xyz;
+    let c2 = m[2].xyz;
+    let cofactor = mat3x3f(cross(c1, c2), cross(c2, c0), cross(c0, c1));
+    let out = cofactor * n * sign(dot(c0, cross(c1, c2)));
+    return out / max(length(out), 1e-6);
+}
+"#;
+    ctx.extra_wgsl_decls
+        .insert(VECTOR_TRANSFORM_WGSL_LIB_KEY.to_string(), wgsl.to_string());
+}
+
+/// Compile a VectorRotate node.
+///
+/// Rotates `vector` around `center` according to `mode`:
+/// - `axisAngle`: `angle` degrees around `axis`
+/// - `euler`: `rotation` degrees around X, then Y, then Z
+/// - `angle2d`: `angle` degrees in the XY plane (around +Z)
+///
+/// `invert` applies the opposite rotation. vector2 inputs come back as vector2;
+/// vector4 inputs are rotated as vector3.
+pub fn compile_vector_rotate<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("vector");
+    if port != "vector" {
+        bail!("VectorRotate: unsupported output port '{port}'");
+    }
+
+    let mode = parse_str(&node.params, "mode")
+        .unwrap_or("axisAngle")
+        .trim();
+    let invert = node
+        .params
+        .get("invert")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let vector = resolve_vector_operand(scene, node, ctx, cache, &compile_fn)?;
+    let out_ty = match vector.ty {
+        ValueType::Vec2 => ValueType::Vec2,
+        ValueType::Vec3 | ValueType::Vec4 => ValueType::Vec3,
+        other => bail!("VectorRotate requires a vector input, got {other:?}"),
+    };
+    let v = coerce_to_type(vector, ValueType::Vec3)?;
+    let center = resolve_vec3_input(scene, node, "center", [0.0; 3], ctx, cache, &compile_fn)?;
+
+    let (expr, uses_time) = match mode {
+        "axisAngle" | "angle2d" => {
+            let axis = if mode == "angle2d" {
+                TypedExpr::new("vec3f(0.0, 0.0, 1.0)", ValueType::Vec3)
+            } else {
+                resolve_vec3_input(
+                    scene,
+                    node,
+                    "axis",
+                    [0.0, 0.0, 1.0],
+                    ctx,
+                    cache,
+                    &compile_fn,
+                )?
+            };
+            let angle = resolve_f32_input(scene, node, "angle", 0.0, ctx, cache, &compile_fn)?;
+            let angle_expr = if invert {
+                format!("-({})", angle.expr)
+            } else {
+                angle.expr.clone()
+            };
+            (
+                format!(
+                    "vectorRotate_axisAngle({}, {}, {}, {angle_expr})",
+                    v.expr, center.expr, axis.expr
+                ),
+                v.uses_time || center.uses_time || axis.uses_time || angle.uses_time,
+            )
+        }
+        "euler" => {
+            let rotation =
+                resolve_vec3_input(scene, node, "rotation", [0.0; 3], ctx, cache, &compile_fn)?;
+            (
+                format!(
+                    "vectorRotate_euler({}, {}, {}, {invert})",
+                    v.expr, center.expr, rotation.expr
+                ),
+                v.uses_time || center.uses_time || rotation.uses_time,
+            )
+        }
+        other => bail!("VectorRotate: unsupported mode '{other}'"),
+    };
+    ensure_vector_rotate_wgsl_lib(ctx);
+
+    let expr = if out_ty == ValueType::Vec2 {
+        format!("({expr}).xy")
+    } else {
+        expr
+    };
+    Ok(TypedExpr::with_time(expr, out_ty, uses_time))
+}
+
+/// Compile a VectorTransform node.
+///
+/// Output `matrix` is the mat4 built from `translate`, `rotate` (degrees) and `scale`,
+/// or the connected `matrix` input when present. Output `vector` applies it to `vector`
+/// as a `point` (with translation), `direction` (without) or `normal` (inverse transpose,
+/// renormalized) per the `type` param. vector2 inputs are transformed in the XY plane;
+/// vector4 inputs are multiplied as-is and ignore `type`.
+pub fn compile_vector_transform<F>(
+    scene: &SceneDSL,
+    _nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    out_port: Option<&str>,
+    ctx: &mut MaterialCompileContext,
+    cache: &mut HashMap<(String, String), TypedExpr>,
+    compile_fn: F,
+) -> Result<TypedExpr>
+where
+    F: Fn(
+        &str,
+        Option<&str>,
+        &mut MaterialCompileContext,
+        &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr>,
+{
+    let port = out_port.unwrap_or("vector");
+    if port != "vector" && port != "matrix" {
+        bail!("VectorTransform: unsupported output port '{port}'");
+    }
+
+    let matrix = if let Some(conn) = incoming_connection(scene, &node.id, "matrix") {
+        let m = compile_fn(&conn.from.node_id, Some(&conn.from.port_id), ctx, cache)?;
+        if m.ty != ValueType::Mat4 {
+            bail!("VectorTransform.matrix requires mat4, got {:?}", m.ty);
+        }
+        m
+    } else {
+        let t = resolve_vec3_input(scene, node, "translate", [0.0; 3], ctx, cache, &compile_fn)?;
+        let r = resolve_vec3_input(scene, node, "rotate", [0.0; 3], ctx, cache, &compile_fn)?;
+        let s = resolve_vec3_input(scene, node, "scale", [1.0; 3], ctx, cache, &compile_fn)?;
+        ensure_vector_transform_wgsl_lib(ctx);
+        TypedExpr::with_time(
+            format!("vectorTransform_trs({}, {}, {})", t.expr, r.expr, s.expr),
+            ValueType::Mat4,
+            t.uses_time || r.uses_time || s.uses_time,
+        )
+    };
+    if port == "matrix" {
+        return Ok(matrix);
+    }
+
+    let kind = parse_str(&node.params, "type").unwrap_or("point").trim();
+    if !matches!(kind, "point" | "direction" | "normal") {
+        bail!("VectorTransform: unsupported type '{kind}'");
+    }
+
+    let vector = resolve_vector_operand(scene, node, ctx, cache, &compile_fn)?;
+    let uses_time = matrix.uses_time || vector.uses_time;
+    let out_ty = vector.ty;
+    let expr = match out_ty {
+        ValueType::Vec4 => format!("({} * {})", matrix.expr, vector.expr),
+        ValueType::Vec2 | ValueType::Vec3 => {
+            let v = coerce_to_type(vector, ValueType::Vec3)?;
+            let transformed = match kind {
+                "point" => format!("({} * vec4f({}, 1.0)).xyz", matrix.expr, v.expr),
+                "direction" => format!("({} * vec4f({}, 0.0)).xyz", matrix.expr, v.expr),
+                _ => {
+                    ensure_vector_transform_wgsl_lib(ctx);
+                    format!("vectorTransform_normal({}, {})", matrix.expr, v.expr)
+                }
+            };
+            if out_ty == ValueType::Vec2 {
+                format!("({transformed}).xy")
+            } else {
+                transformed
+            }
+        }
+        other => bail!("VectorTransform requires a vector input, got {other:?}"),
+    };
+    Ok(TypedExpr::with_time(expr, out_ty, uses_time))
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::super::types::ValueType;
@@ -523,4 +914,148 @@ mod tests {
         assert_eq!(result.ty, ValueType::Vec3);
         assert!(result.expr.contains("+"));
     }
+
+    fn mock_typed_compile_fn(
+        node_id: &str,
+        _out_port: Option<&str>,
+        _ctx: &mut MaterialCompileContext,
+        _cache: &mut HashMap<(String, String), TypedExpr>,
+    ) -> Result<TypedExpr> {
+        Ok(match node_id {
+            "uv" => TypedExpr::new("in.uv".to_string(), ValueType::Vec2),
+            "angle" => TypedExpr::with_time("params.time".to_string(), ValueType::F32, true),
+            _ => TypedExpr::new("vec3f(1.0, 0.0, 0.0)".to_string(), ValueType::Vec3),
+        })
+    }
+
+    fn vector_node(id: &str, node_type: &str, params: serde_json::Value) -> Node {
+        Node {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            params: serde_json::from_value(params).unwrap(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn assert_expr_validates(ctx: &MaterialCompileContext, expr: &TypedExpr) {
+        let module = format!(
+            "{}\nstruct VSOut {{ @location(0) uv: vec2f }};\nstruct Params {{ time: f32 }};\n@group(0) @binding(0) var<uniform> params: Params;\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {{\n    let v = {};\n    return vec4f(0.0);\n}}\n",
+            ctx.wgsl_decls(),
+            expr.expr
+        );
+        crate::renderer::validate_wgsl(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+    }
+
+    #[test]
+    fn vector_rotate_modes_keep_input_dimension() {
+        use super::super::test_utils::test_connection;
+        for (mode, connect_uv, expected) in [
+            ("angle2d", true, ValueType::Vec2),
+            ("axisAngle", false, ValueType::Vec3),
+            ("euler", true, ValueType::Vec2),
+        ] {
+            let node = vector_node(
+                "rot",
+                "VectorRotate",
+                serde_json::json!({
+                    "mode": mode,
+                    "invert": true,
+                    "center": { "x": 0.5, "y": 0.5, "z": 0.0 },
+                    "rotation": { "x": 0.0, "y": 0.0, "z": 90.0 }
+                }),
+            );
+            let mut connections = vec![test_connection("angle", "value", "rot", "angle")];
+            if connect_uv {
+                connections.push(test_connection("uv", "value", "rot", "vector"));
+            }
+            let scene = test_scene(vec![node.clone()], connections);
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+
+            let out = compile_vector_rotate(
+                &scene,
+                &HashMap::new(),
+                &node,
+                None,
+                &mut ctx,
+                &mut cache,
+                mock_typed_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(out.ty, expected, "{mode}");
+            assert_eq!(out.uses_time, mode != "euler", "{mode}");
+            assert_expr_validates(&ctx, &out);
+        }
+    }
+
+    #[test]
+    fn vector_transform_outputs_matrix_and_transformed_vector() {
+        use super::super::test_utils::test_connection;
+        for kind in ["point", "direction", "normal"] {
+            let node = vector_node(
+                "xf",
+                "VectorTransform",
+                serde_json::json!({
+                    "type": kind,
+                    "translate": { "x": 1.0, "y": 2.0, "z": 0.0 },
+                    "rotate": { "x": 0.0, "y": 0.0, "z": 45.0 },
+                    "scale": { "x": 2.0, "y": 2.0, "z": 1.0 }
+                }),
+            );
+            let scene = test_scene(
+                vec![node.clone()],
+                vec![test_connection("pos", "value", "xf", "vector")],
+            );
+            let mut ctx = MaterialCompileContext::default();
+            let mut cache = HashMap::new();
+
+            let vector = compile_vector_transform(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some("vector"),
+                &mut ctx,
+                &mut cache,
+                mock_typed_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(vector.ty, ValueType::Vec3);
+            assert_expr_validates(&ctx, &vector);
+
+            let matrix = compile_vector_transform(
+                &scene,
+                &HashMap::new(),
+                &node,
+                Some("matrix"),
+                &mut ctx,
+                &mut cache,
+                mock_typed_compile_fn,
+            )
+            .unwrap();
+            assert_eq!(matrix.ty, ValueType::Mat4);
+            assert!(matrix.expr.starts_with("vectorTransform_trs("));
+        }
+
+        let bad = vector_node(
+            "xf",
+            "VectorTransform",
+            serde_json::json!({ "type": "plane" }),
+        );
+        let scene = test_scene(vec![bad.clone()], vec![]);
+        assert!(
+            compile_vector_transform(
+                &scene,
+                &HashMap::new(),
+                &bad,
+                None,
+                &mut MaterialCompileContext::default(),
+                &mut HashMap::new(),
+                mock_typed_compile_fn,
+            )
+            .is_err()
+        );
+    }
 }
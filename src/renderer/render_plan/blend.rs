@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
 use anyhow::{Context, Result, anyhow, bail};
-use rust_wgpu_fiber::eframe::wgpu::{self, BlendState};
+use rust_wgpu_fiber::eframe::wgpu::{self, BlendState, Color};
 
 use crate::dsl::parse_str;
 
+use super::pass_assemblers::mesh_gradient::parse_color_from_params;
+
 fn normalize_blend_token(s: &str) -> String {
     s.trim().to_ascii_lowercase().replace('_', "-")
 }
@@ -179,6 +181,67 @@ pub(crate) fn parse_render_pass_blend_state(
     Ok(state)
 }
 
+/// Color-attachment load behavior requested by a pass node's `loadOp` / `clearColor`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum PassLoadOp {
+    /// `loadOp: "auto"` (default): clear on the first write to a target in the
+    /// frame, load for every later pass drawing into it.
+    Auto { clear_color: Color },
+    /// `loadOp: "clear"`: always clear, discarding earlier passes into the same target.
+    Clear { clear_color: Color },
+    /// `loadOp: "load"`: always keep the target's contents, including the
+    /// previous frame's on the first write (accumulation).
+    Load,
+}
+
+impl Default for PassLoadOp {
+    fn default() -> Self {
+        Self::Auto {
+            clear_color: Color::TRANSPARENT,
+        }
+    }
+}
+
+impl PassLoadOp {
+    /// Resolve to a wgpu load op; `first_write` is whether no earlier pass in the
+    /// frame drew into the same target.
+    pub(crate) fn resolve(self, first_write: bool) -> wgpu::LoadOp<Color> {
+        match self {
+            Self::Auto { clear_color } if first_write => wgpu::LoadOp::Clear(clear_color),
+            Self::Auto { .. } | Self::Load => wgpu::LoadOp::Load,
+            Self::Clear { clear_color } => wgpu::LoadOp::Clear(clear_color),
+        }
+    }
+}
+
+/// Parse `loadOp` (`"auto" | "clear" | "load"`) and `clearColor` (straight RGBA,
+/// premultiplied here to match pass targets).
+pub(crate) fn parse_render_pass_load_op(
+    params: &HashMap<String, serde_json::Value>,
+) -> Result<PassLoadOp> {
+    let clear_color = match params.get("clearColor") {
+        None => Color::TRANSPARENT,
+        Some(raw) => {
+            let [r, g, b, a] = parse_color_from_params(params, "clearColor")
+                .ok_or_else(|| anyhow!("unsupported clearColor: {raw}"))?;
+            let a = a.clamp(0.0, 1.0);
+            Color {
+                r: (r * a) as f64,
+                g: (g * a) as f64,
+                b: (b * a) as f64,
+                a: a as f64,
+            }
+        }
+    };
+    let load_op = parse_str(params, "loadOp").map(normalize_blend_token);
+    Ok(match load_op.as_deref() {
+        None | Some("" | "auto") => PassLoadOp::Auto { clear_color },
+        Some("clear") => PassLoadOp::Clear { clear_color },
+        Some("load") => PassLoadOp::Load,
+        Some(other) => bail!("unsupported loadOp: {other} (expected auto, clear or load)"),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,4 +345,38 @@ mod tests {
         };
         assert_blend_state_eq(got, expected);
     }
+
+    #[test]
+    fn load_op_defaults_to_clearing_the_first_write() {
+        let params: HashMap<String, serde_json::Value> = HashMap::new();
+        let got = parse_render_pass_load_op(&params).expect("parse default load op");
+        assert_eq!(got, PassLoadOp::default());
+        assert_eq!(got.resolve(true), wgpu::LoadOp::Clear(Color::TRANSPARENT));
+        assert_eq!(got.resolve(false), wgpu::LoadOp::Load);
+    }
+
+    #[test]
+    fn clear_color_is_premultiplied_and_explicit_ops_ignore_order() {
+        let params: HashMap<String, serde_json::Value> = HashMap::from([
+            ("loadOp".to_string(), json!("clear")),
+            ("clearColor".to_string(), json!([1.0, 0.5, 0.0, 0.5])),
+        ]);
+        let got = parse_render_pass_load_op(&params).expect("parse clear load op");
+        let expected = Color {
+            r: 0.5,
+            g: 0.25,
+            b: 0.0,
+            a: 0.5,
+        };
+        assert_eq!(got.resolve(false), wgpu::LoadOp::Clear(expected));
+
+        let params: HashMap<String, serde_json::Value> =
+            HashMap::from([("loadOp".to_string(), json!("load"))]);
+        let got = parse_render_pass_load_op(&params).expect("parse load op");
+        assert_eq!(got.resolve(true), wgpu::LoadOp::Load);
+
+        let params: HashMap<String, serde_json::Value> =
+            HashMap::from([("loadOp".to_string(), json!("discard"))]);
+        assert!(parse_render_pass_load_op(&params).is_err());
+    }
 }
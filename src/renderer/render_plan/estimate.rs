@@ -0,0 +1,310 @@
+//! Dry-run cost estimation for a planned scene.
+//!
+//! Runs the planner against a device profile (limits only, no GPU) and turns
+//! the resulting resource plan into per-texture allocations and per-pass
+//! bandwidth/fill estimates. The numbers are deliberately coarse: the goal is
+//! to flag scenes that are clearly too heavy for a device class, not to
+//! predict frame times.
+//!
+//! Per pass we count one full write of the target (plus the MSAA resolve), one
+//! full read of every bound texture, and a read of the target when the pass
+//! blends or loads. Fragment work is `pixels * samples`, weighted by shader
+//! size as a stand-in for ALU cost.
+
+use anyhow::{Result, bail};
+use rust_wgpu_fiber::eframe::wgpu::{self, BlendState, TextureFormat};
+use serde::Serialize;
+
+use super::{
+    planner::RenderPlanner,
+    types::{PlanBuildOptions, PlanningGpuCaps, RenderPlan},
+};
+use crate::{asset_store::AssetStore, dsl::SceneDSL, renderer::ShaderSpacePresentationMode};
+
+/// One frame at 60 Hz; `cost_score` is expressed relative to it.
+const FRAME_BUDGET_MS: f64 = 1000.0 / 60.0;
+/// Shader source bytes that count as one extra unit of per-pixel work.
+const SHADER_BYTES_PER_ALU_UNIT: f64 = 4096.0;
+
+/// Coarse description of a device class used for estimation.
+#[derive(Clone, Debug)]
+pub struct DeviceProfile {
+    pub name: String,
+    pub limits: wgpu::Limits,
+    /// GPU memory the scene may reasonably use.
+    pub memory_budget_bytes: u64,
+    pub bandwidth_bytes_per_sec: f64,
+    pub fill_rate_pixels_per_sec: f64,
+}
+
+impl DeviceProfile {
+    pub fn desktop() -> Self {
+        Self {
+            name: "desktop".to_string(),
+            limits: wgpu::Limits::default(),
+            memory_budget_bytes: 4 << 30,
+            bandwidth_bytes_per_sec: 200e9,
+            fill_rate_pixels_per_sec: 50e9,
+        }
+    }
+
+    pub fn phone() -> Self {
+        Self {
+            name: "phone".to_string(),
+            limits: wgpu::Limits::downlevel_defaults(),
+            memory_budget_bytes: 512 << 20,
+            bandwidth_bytes_per_sec: 25e9,
+            fill_rate_pixels_per_sec: 4e9,
+        }
+    }
+
+    pub fn by_name(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "desktop" => Ok(Self::desktop()),
+            "phone" | "mobile" => Ok(Self::phone()),
+            other => bail!("unknown device profile {other:?} (expected desktop or phone)"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextureEstimate {
+    pub name: String,
+    pub size: [u32; 2],
+    pub format: String,
+    pub sample_count: u32,
+    pub bytes: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassEstimate {
+    pub pass_name: String,
+    pub target: String,
+    pub pixels: u64,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    pub estimated_ms: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneCostEstimate {
+    pub profile: String,
+    pub resolution: [u32; 2],
+    pub textures: Vec<TextureEstimate>,
+    pub passes: Vec<PassEstimate>,
+    pub texture_bytes: u64,
+    pub buffer_bytes: u64,
+    pub bandwidth_bytes: u64,
+    pub estimated_frame_ms: f64,
+    /// Estimated frame time as a fraction of a 60 Hz frame; above 1.0 the
+    /// scene is unlikely to run in real time on this device class.
+    pub cost_score: f64,
+    pub warnings: Vec<String>,
+}
+
+/// Plans `scene` for `profile` without a GPU and estimates its cost.
+pub fn estimate_scene_cost(
+    scene: &SceneDSL,
+    asset_store: Option<&AssetStore>,
+    profile: &DeviceProfile,
+) -> Result<SceneCostEstimate> {
+    let plan = RenderPlanner::new(PlanBuildOptions {
+        gpu_caps: PlanningGpuCaps {
+            features: wgpu::Features::empty(),
+            limits: profile.limits.clone(),
+        },
+        presentation_mode: ShaderSpacePresentationMode::SceneLinear,
+        debug_dump_wgsl_dir: None,
+        channel_debug: Default::default(),
+        dither_sdr_output: false,
+    })
+    .plan(scene, asset_store, None)?;
+    Ok(estimate_plan_cost(&plan, profile))
+}
+
+pub(crate) fn estimate_plan_cost(plan: &RenderPlan, profile: &DeviceProfile) -> SceneCostEstimate {
+    let resources = &plan.resources;
+    let mut warnings = Vec::new();
+
+    let mut textures: Vec<TextureEstimate> = resources
+        .textures
+        .iter()
+        .map(|decl| TextureEstimate {
+            name: decl.name.as_str().to_string(),
+            size: decl.size,
+            format: format!("{:?}", decl.format),
+            sample_count: decl.sample_count,
+            bytes: texture_bytes(decl.size, decl.format, decl.sample_count),
+        })
+        .collect();
+    textures.extend(resources.image_textures.iter().map(|spec| {
+        let size = [spec.image.width(), spec.image.height()];
+        TextureEstimate {
+            name: spec.name.as_str().to_string(),
+            size,
+            format: "Rgba8Unorm".to_string(),
+            sample_count: 1,
+            bytes: texture_bytes(size, TextureFormat::Rgba8Unorm, 1),
+        }
+    }));
+
+    let max_dim = profile.limits.max_texture_dimension_2d;
+    for tex in &textures {
+        if tex.size[0] > max_dim || tex.size[1] > max_dim {
+            warnings.push(format!(
+                "texture {} is {}x{}, above the {} limit of {max_dim}",
+                tex.name, tex.size[0], tex.size[1], profile.name
+            ));
+        }
+    }
+
+    let bytes_of = |name: &str| {
+        textures
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| (t.bytes, t.size[0] as u64 * t.size[1] as u64))
+            .unwrap_or((0, 0))
+    };
+    let pass_ms = |pixels: u64, bytes: u64, alu: f64| {
+        (bytes as f64 / profile.bandwidth_bytes_per_sec
+            + pixels as f64 * alu / profile.fill_rate_pixels_per_sec)
+            * 1000.0
+    };
+
+    let mut passes = Vec::new();
+    for spec in &resources.render_pass_specs {
+        let (target_bytes, target_pixels) = bytes_of(spec.target_texture.as_str());
+        let pixels = target_pixels * spec.sample_count.max(1) as u64;
+        let mut bytes_written = target_bytes;
+        if let Some(resolve) = spec.resolve_target.as_ref() {
+            bytes_written += bytes_of(resolve.as_str()).0;
+        }
+        let mut bytes_read: u64 = spec
+            .texture_bindings
+            .iter()
+            .map(|binding| bytes_of(binding.texture.as_str()).0)
+            .sum();
+        let reads_target = spec.blend_state != BlendState::REPLACE
+            || matches!(spec.color_load_op, wgpu::LoadOp::Load);
+        if reads_target {
+            bytes_read += target_bytes;
+        }
+        let alu = 1.0 + spec.shader_wgsl.len() as f64 / SHADER_BYTES_PER_ALU_UNIT;
+        passes.push(PassEstimate {
+            pass_name: spec.name.as_str().to_string(),
+            target: spec.target_texture.as_str().to_string(),
+            pixels,
+            bytes_read,
+            bytes_written,
+            estimated_ms: pass_ms(pixels, bytes_read + bytes_written, alu),
+        });
+    }
+    let fullscreen_copies = resources
+        .image_prepasses
+        .iter()
+        .map(|p| (&p.pass_name, &p.src_texture, &p.dst_texture))
+        .chain(
+            resources
+                .depth_resolve_passes
+                .iter()
+                .map(|p| (&p.pass_name, &p.depth_texture, &p.dst_texture)),
+        );
+    for (pass_name, src, dst) in fullscreen_copies {
+        let (dst_bytes, pixels) = bytes_of(dst.as_str());
+        let src_bytes = bytes_of(src.as_str()).0;
+        passes.push(PassEstimate {
+            pass_name: pass_name.as_str().to_string(),
+            target: dst.as_str().to_string(),
+            pixels,
+            bytes_read: src_bytes,
+            bytes_written: dst_bytes,
+            estimated_ms: pass_ms(pixels, src_bytes + dst_bytes, 1.0),
+        });
+    }
+
+    let texture_bytes: u64 = textures.iter().map(|t| t.bytes).sum();
+    let buffer_bytes: u64 = resources
+        .geometry_buffers
+        .iter()
+        .chain(&resources.instance_buffers)
+        .map(|(_, bytes)| bytes.len() as u64)
+        .sum();
+    let bandwidth_bytes = passes.iter().map(|p| p.bytes_read + p.bytes_written).sum();
+    let estimated_frame_ms: f64 = passes.iter().map(|p| p.estimated_ms).sum();
+    let cost_score = estimated_frame_ms / FRAME_BUDGET_MS;
+
+    if texture_bytes + buffer_bytes > profile.memory_budget_bytes {
+        warnings.push(format!(
+            "GPU memory {:.1} MiB exceeds the {} budget of {:.1} MiB",
+            mib(texture_bytes + buffer_bytes),
+            profile.name,
+            mib(profile.memory_budget_bytes)
+        ));
+    }
+    if cost_score > 1.0 {
+        warnings.push(format!(
+            "estimated {estimated_frame_ms:.1} ms per frame is over the 60 Hz budget on {}",
+            profile.name
+        ));
+    }
+
+    SceneCostEstimate {
+        profile: profile.name.clone(),
+        resolution: plan.resolution,
+        textures,
+        passes,
+        texture_bytes,
+        buffer_bytes,
+        bandwidth_bytes,
+        estimated_frame_ms,
+        cost_score,
+        warnings,
+    }
+}
+
+fn texture_bytes(size: [u32; 2], format: TextureFormat, sample_count: u32) -> u64 {
+    let texel = format.block_copy_size(None).unwrap_or(4) as u64;
+    size[0] as u64 * size[1] as u64 * texel * sample_count.max(1) as u64
+}
+
+fn mib(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anyhow::Result;
+
+    use super::{DeviceProfile, estimate_scene_cost, texture_bytes};
+    use crate::asset_store;
+    use rust_wgpu_fiber::eframe::wgpu::TextureFormat;
+
+    #[test]
+    fn texture_bytes_account_for_format_and_samples() {
+        assert_eq!(texture_bytes([4, 2], TextureFormat::Rgba8Unorm, 1), 32);
+        assert_eq!(texture_bytes([4, 2], TextureFormat::Rgba16Float, 4), 256);
+    }
+
+    #[test]
+    fn phone_profile_is_costlier_than_desktop() -> Result<()> {
+        let archive = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/render/editor-examples/graph-rectangle/scene.nforge");
+        let (scene, store) = asset_store::load_from_nforge(&archive)?;
+        let store = (!scene.assets.is_empty()).then_some(&store);
+
+        let desktop = estimate_scene_cost(&scene, store, &DeviceProfile::desktop())?;
+        let phone = estimate_scene_cost(&scene, store, &DeviceProfile::phone())?;
+
+        assert!(!desktop.passes.is_empty());
+        assert!(desktop.texture_bytes > 0);
+        assert_eq!(desktop.texture_bytes, phone.texture_bytes);
+        assert!(phone.estimated_frame_ms > desktop.estimated_frame_ms);
+        assert!(DeviceProfile::by_name("toaster").is_err());
+        Ok(())
+    }
+}
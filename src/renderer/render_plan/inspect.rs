@@ -0,0 +1,211 @@
+//! Structured scene summary for `--inspect`.
+//!
+//! Plans the scene without a GPU (like [`super::estimate`]) and reports what
+//! the planner decided: resolution, composite draw order, pass execution
+//! order, texture allocations and which pass outputs are sampled downstream.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Result;
+use rust_wgpu_fiber::eframe::wgpu;
+use serde::Serialize;
+
+use super::{
+    estimate::{DeviceProfile, TextureEstimate, estimate_plan_cost},
+    pass_graph::sampled_pass_node_ids_from_roots,
+    planner::RenderPlanner,
+    types::{PlanBuildOptions, PlanningGpuCaps, RenderPlan},
+};
+use crate::{asset_store::AssetStore, dsl::SceneDSL, renderer::ShaderSpacePresentationMode};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassInspection {
+    pub pass_name: String,
+    /// Scene node the pass was assembled from.
+    pub node_id: String,
+    /// `render` or `compute`.
+    pub kind: &'static str,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolve_target: Option<String>,
+    pub sample_count: u32,
+    /// The pass node's output is sampled by another pass rather than only
+    /// composited.
+    pub sampled_output: bool,
+    /// Passes that bind this pass's target (or resolve target) as a texture.
+    pub sampled_by: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneInspection {
+    pub resolution: [u32; 2],
+    pub output_texture_node_id: String,
+    pub scene_output_texture: String,
+    pub export_output_texture: String,
+    /// Composite layer node ids, back to front.
+    pub composite_draw_order: Vec<String>,
+    /// Pass names in the order they are encoded each frame.
+    pub pass_order: Vec<String>,
+    pub passes: Vec<PassInspection>,
+    pub textures: Vec<TextureEstimate>,
+    pub texture_bytes: u64,
+    pub buffer_bytes: u64,
+    /// Textures plus geometry/instance buffers; excludes uniforms and driver overhead.
+    pub estimated_vram_bytes: u64,
+}
+
+/// Plans `scene` without a GPU and summarizes the result.
+pub fn inspect_scene(
+    scene: &SceneDSL,
+    asset_store: Option<&AssetStore>,
+) -> Result<SceneInspection> {
+    let profile = DeviceProfile::desktop();
+    let plan = RenderPlanner::new(PlanBuildOptions {
+        gpu_caps: PlanningGpuCaps {
+            features: wgpu::Features::empty(),
+            limits: profile.limits.clone(),
+        },
+        presentation_mode: ShaderSpacePresentationMode::SceneLinear,
+        debug_dump_wgsl_dir: None,
+        channel_debug: Default::default(),
+        dither_sdr_output: false,
+    })
+    .plan(scene, asset_store, None)?;
+    inspect_plan(&plan, &profile)
+}
+
+fn inspect_plan(plan: &RenderPlan, profile: &DeviceProfile) -> Result<SceneInspection> {
+    let prepared = &plan.prepared;
+    let resources = &plan.resources;
+    let sampled_nodes: HashSet<String> = sampled_pass_node_ids_from_roots(
+        &prepared.scene,
+        &prepared.nodes_by_id,
+        &prepared.composite_layers_in_draw_order,
+    )?;
+
+    // texture name -> passes binding it
+    let mut samplers_by_texture: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let bindings = resources
+        .render_pass_specs
+        .iter()
+        .map(|spec| (&spec.name, &spec.texture_bindings))
+        .chain(
+            resources
+                .compute_pass_specs
+                .iter()
+                .map(|spec| (&spec.name, &spec.texture_bindings)),
+        );
+    for (pass_name, texture_bindings) in bindings {
+        for binding in texture_bindings {
+            samplers_by_texture
+                .entry(binding.texture.as_str())
+                .or_default()
+                .push(pass_name.as_str().to_string());
+        }
+    }
+    let sampled_by = |textures: &[Option<&str>]| -> Vec<String> {
+        let mut out: Vec<String> = textures
+            .iter()
+            .flatten()
+            .filter_map(|name| samplers_by_texture.get(name))
+            .flatten()
+            .cloned()
+            .collect();
+        out.sort();
+        out.dedup();
+        out
+    };
+
+    let mut passes: Vec<PassInspection> = resources
+        .render_pass_specs
+        .iter()
+        .map(|spec| PassInspection {
+            pass_name: spec.name.as_str().to_string(),
+            node_id: spec.pass_id.clone(),
+            kind: "render",
+            target: spec.target_texture.as_str().to_string(),
+            resolve_target: spec.resolve_target.as_ref().map(|t| t.as_str().to_string()),
+            sample_count: spec.sample_count,
+            sampled_output: sampled_nodes.contains(&spec.pass_id),
+            sampled_by: sampled_by(&[
+                Some(spec.target_texture.as_str()),
+                spec.resolve_target.as_ref().map(|t| t.as_str()),
+            ]),
+        })
+        .collect();
+    passes.extend(
+        resources
+            .compute_pass_specs
+            .iter()
+            .map(|spec| PassInspection {
+                pass_name: spec.name.as_str().to_string(),
+                node_id: spec.pass_id.clone(),
+                kind: "compute",
+                target: spec.output_texture.as_str().to_string(),
+                resolve_target: None,
+                sample_count: 1,
+                sampled_output: sampled_nodes.contains(&spec.pass_id),
+                sampled_by: sampled_by(&[Some(spec.output_texture.as_str())]),
+            }),
+    );
+
+    let cost = estimate_plan_cost(plan, profile);
+    Ok(SceneInspection {
+        resolution: plan.resolution,
+        output_texture_node_id: prepared.output_texture_node_id.clone(),
+        scene_output_texture: plan.scene_output_texture.as_str().to_string(),
+        export_output_texture: plan.export_output_texture.as_str().to_string(),
+        composite_draw_order: prepared.composite_layers_in_draw_order.clone(),
+        pass_order: resources
+            .composite_passes
+            .iter()
+            .map(|name| name.as_str().to_string())
+            .collect(),
+        passes,
+        estimated_vram_bytes: cost.texture_bytes + cost.buffer_bytes,
+        textures: cost.textures,
+        texture_bytes: cost.texture_bytes,
+        buffer_bytes: cost.buffer_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anyhow::Result;
+
+    use super::inspect_scene;
+    use crate::asset_store;
+
+    #[test]
+    fn inspection_lists_passes_in_render_order() -> Result<()> {
+        let archive = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/render/editor-examples/graph-rectangle/scene.nforge");
+        let (scene, store) = asset_store::load_from_nforge(&archive)?;
+        let store = (!scene.assets.is_empty()).then_some(&store);
+
+        let inspection = inspect_scene(&scene, store)?;
+        assert!(!inspection.composite_draw_order.is_empty());
+        assert!(!inspection.passes.is_empty());
+        assert!(
+            inspection
+                .passes
+                .iter()
+                .any(|pass| inspection.pass_order.contains(&pass.pass_name))
+        );
+        assert!(
+            inspection
+                .textures
+                .iter()
+                .any(|t| t.name == inspection.scene_output_texture)
+        );
+        assert_eq!(
+            inspection.estimated_vram_bytes,
+            inspection.texture_bytes + inspection.buffer_bytes
+        );
+        Ok(())
+    }
+}
@@ -163,3 +163,35 @@ pub(crate) fn parse_kernel_source_js_like(source: &str) -> Result<Kernel2D> {
         values,
     })
 }
+
+/// Split a rank-1 kernel into `(column, row)` factors with `k[y][x] == column[y] * row[x]`.
+///
+/// Returns `None` for all-zero kernels or when any entry deviates from the outer
+/// product by more than a small fraction of the largest magnitude.
+pub(crate) fn separable_kernel_factors(kernel: &Kernel2D) -> Option<(Vec<f32>, Vec<f32>)> {
+    let w = kernel.width as usize;
+    let h = kernel.height as usize;
+    if kernel.values.len() != w * h {
+        return None;
+    }
+    let (pivot, pivot_value) = kernel
+        .values
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))?;
+    if pivot_value == 0.0 {
+        return None;
+    }
+    let (pivot_y, pivot_x) = (pivot / w, pivot % w);
+    let row: Vec<f32> = kernel.values[pivot_y * w..(pivot_y + 1) * w].to_vec();
+    let column: Vec<f32> = (0..h)
+        .map(|y| kernel.values[y * w + pivot_x] / pivot_value)
+        .collect();
+
+    let tolerance = pivot_value.abs() * 1e-5;
+    let is_outer_product = (0..h).all(|y| {
+        (0..w).all(|x| (kernel.values[y * w + x] - column[y] * row[x]).abs() <= tolerance)
+    });
+    is_outer_product.then_some((column, row))
+}
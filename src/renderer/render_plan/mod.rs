@@ -1,5 +1,7 @@
 pub mod blend;
+pub mod estimate;
 pub mod geometry;
+pub mod inspect;
 pub mod kernel;
 pub(crate) mod pass_assemblers;
 pub mod pass_graph;
@@ -7,11 +9,13 @@ pub mod pass_handlers;
 pub(crate) mod pass_spec;
 pub mod planner;
 pub(crate) mod resource_naming;
+pub(crate) mod stencil;
 pub mod types;
+pub(crate) mod viewport;
 
-pub(crate) use blend::parse_render_pass_blend_state;
+pub(crate) use blend::{parse_render_pass_blend_state, parse_render_pass_load_op};
 pub(crate) use geometry::{load_gltf_geometry_pixel_space, resolve_geometry_for_render_pass};
-pub(crate) use kernel::parse_kernel_source_js_like;
+pub(crate) use kernel::{parse_kernel_source_js_like, separable_kernel_factors};
 pub(crate) use pass_graph::{
     compute_pass_render_order, forward_root_dependencies_from_roots, resolve_pass_texture_bindings,
     sampled_pass_node_ids_from_roots,
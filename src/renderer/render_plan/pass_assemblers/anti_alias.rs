@@ -0,0 +1,275 @@
+//! AntiAliasPass assembler.
+//!
+//! Post-process FXAA (after Lottes' FXAA 3.11 quality path) over the `pass` input,
+//! typically a Composite, for targets whose format cannot be multisampled or where
+//! `select_effective_msaa_sample_count` drops the requested MSAA. Luma is taken from
+//! the premultiplied color as stored, so edges against transparency are smoothed too.
+//!
+//! Only `method: "fxaa"` is implemented. SMAA needs its precomputed area and search
+//! lookup textures, which the asset pipeline does not ship, so it is rejected.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::{
+    dsl::{Node, SceneDSL},
+    renderer::{
+        utils::{cpu_num_f32, fmt_f32},
+        wgsl::build_fullscreen_textured_bundle,
+    },
+};
+
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::assemble_fullscreen_filter;
+
+/// FXAA quality preset (`AntiAliasPass.quality`), mirroring FXAA 3.11's 10/12/29/39.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum FxaaQuality {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Extreme,
+}
+
+impl FxaaQuality {
+    fn from_params(node: &Node) -> Result<Self> {
+        let Some(raw) = node.params.get("quality") else {
+            return Ok(Self::default());
+        };
+        let name = raw
+            .as_str()
+            .ok_or_else(|| anyhow!("quality must be a string, got {raw}"))?;
+        Ok(match name.trim().to_ascii_lowercase().as_str() {
+            "low" => Self::Low,
+            "" | "medium" => Self::Medium,
+            "high" => Self::High,
+            "extreme" | "ultra" => Self::Extreme,
+            other => bail!("unknown quality '{other}', expected low, medium, high or extreme"),
+        })
+    }
+
+    /// `(edge threshold, minimum edge threshold)` as in FXAA 3.11.
+    pub(crate) fn thresholds(self) -> (f32, f32) {
+        match self {
+            Self::Low => (0.25, 0.0833),
+            Self::Medium => (0.166, 0.0833),
+            Self::High => (0.125, 0.0625),
+            Self::Extreme => (0.063, 0.0312),
+        }
+    }
+
+    /// Pixel step per edge-search iteration, in order.
+    pub(crate) fn search_steps(self) -> &'static [f32] {
+        match self {
+            Self::Low => &[1.5, 3.0, 12.0],
+            Self::Medium => &[1.0, 1.5, 2.0, 4.0, 12.0],
+            Self::High => &[1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 4.0, 8.0],
+            Self::Extreme => &[1.0, 1.0, 1.0, 1.0, 1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 4.0, 8.0],
+        }
+    }
+}
+
+/// Resolved AntiAliasPass params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct AntiAliasSettings {
+    pub quality: FxaaQuality,
+    /// Sub-pixel aliasing removal, 0 (sharp) ..= 1 (soft); FXAA's default is 0.75.
+    pub subpixel: f32,
+}
+
+/// Reject anything but FXAA; see the module docs for why SMAA is not available.
+fn check_method(node: &Node) -> Result<()> {
+    let Some(raw) = node.params.get("method") else {
+        return Ok(());
+    };
+    let method = raw
+        .as_str()
+        .ok_or_else(|| anyhow!("method must be a string, got {raw}"))?;
+    match method.trim().to_ascii_lowercase().as_str() {
+        "" | "fxaa" => Ok(()),
+        "smaa" => bail!("AntiAliasPass method 'smaa' is not supported yet, use 'fxaa'"),
+        other => bail!("unknown method '{other}', expected fxaa"),
+    }
+}
+
+pub(crate) fn anti_alias_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<AntiAliasSettings> {
+    check_method(node)?;
+    Ok(AntiAliasSettings {
+        quality: FxaaQuality::from_params(node)?,
+        subpixel: cpu_num_f32(scene, nodes_by_id, node, "subpixel", 0.75)?.clamp(0.0, 1.0),
+    })
+}
+
+pub(crate) fn build_anti_alias_wgsl(settings: &AntiAliasSettings) -> String {
+    let (threshold, threshold_min) = settings.quality.thresholds();
+    let steps = settings.quality.search_steps();
+    let step_list = steps
+        .iter()
+        .map(|s| fmt_f32(*s))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Every lookup uses textureSampleLevel: the early-out and the edge search make
+    // control flow non-uniform.
+    let body = format!(
+        r#"
+ let px = 1.0 / vec2f(textureDimensions(src_tex));
+ let uv = in.uv;
+ let lw = vec3f(0.299, 0.587, 0.114);
+ let rgba_m = textureSampleLevel(src_tex, src_samp, uv, 0.0);
+ let luma_m = dot(rgba_m.rgb, lw);
+ let luma_n = dot(textureSampleLevel(src_tex, src_samp, uv + vec2f(0.0, -1.0) * px, 0.0).rgb, lw);
+ let luma_s = dot(textureSampleLevel(src_tex, src_samp, uv + vec2f(0.0, 1.0) * px, 0.0).rgb, lw);
+ let luma_w = dot(textureSampleLevel(src_tex, src_samp, uv + vec2f(-1.0, 0.0) * px, 0.0).rgb, lw);
+ let luma_e = dot(textureSampleLevel(src_tex, src_samp, uv + vec2f(1.0, 0.0) * px, 0.0).rgb, lw);
+ let range_max = max(luma_m, max(max(luma_n, luma_s), max(luma_w, luma_e)));
+ let range_min = min(luma_m, min(min(luma_n, luma_s), min(luma_w, luma_e)));
+ let range = range_max - range_min;
+ if (range < max({threshold_min}, range_max * {threshold})) {{
+     return rgba_m;
+ }}
+
+ let luma_nw = dot(textureSampleLevel(src_tex, src_samp, uv + vec2f(-1.0, -1.0) * px, 0.0).rgb, lw);
+ let luma_ne = dot(textureSampleLevel(src_tex, src_samp, uv + vec2f(1.0, -1.0) * px, 0.0).rgb, lw);
+ let luma_sw = dot(textureSampleLevel(src_tex, src_samp, uv + vec2f(-1.0, 1.0) * px, 0.0).rgb, lw);
+ let luma_se = dot(textureSampleLevel(src_tex, src_samp, uv + vec2f(1.0, 1.0) * px, 0.0).rgb, lw);
+
+ // Sub-pixel blend from the 3x3 low-pass contrast.
+ let sub_avg = (2.0 * (luma_n + luma_s + luma_w + luma_e) + luma_nw + luma_ne + luma_sw + luma_se) / 12.0;
+ let sub = smoothstep(0.0, 1.0, clamp(abs(sub_avg - luma_m) / range, 0.0, 1.0));
+ let subpix = sub * sub * {subpixel};
+
+ // Edge orientation: a horizontal edge is searched along x and blended along y.
+ let edge_h = abs(luma_nw + luma_sw - 2.0 * luma_w)
+     + 2.0 * abs(luma_n + luma_s - 2.0 * luma_m)
+     + abs(luma_ne + luma_se - 2.0 * luma_e);
+ let edge_v = abs(luma_nw + luma_ne - 2.0 * luma_n)
+     + 2.0 * abs(luma_w + luma_e - 2.0 * luma_m)
+     + abs(luma_sw + luma_se - 2.0 * luma_s);
+ let horizontal = edge_h >= edge_v;
+ var step_len = select(px.x, px.y, horizontal);
+ let luma_neg = select(luma_w, luma_n, horizontal);
+ let luma_pos = select(luma_e, luma_s, horizontal);
+ let gradient_neg = abs(luma_neg - luma_m);
+ let gradient_pos = abs(luma_pos - luma_m);
+ let pair_neg = gradient_neg >= gradient_pos;
+ if (pair_neg) {{
+     step_len = -step_len;
+ }}
+ let luma_local = 0.5 * (select(luma_pos, luma_neg, pair_neg) + luma_m);
+ let gradient_scaled = 0.25 * max(gradient_neg, gradient_pos);
+
+ var pos_b = uv;
+ if (horizontal) {{
+     pos_b.y = pos_b.y + step_len * 0.5;
+ }} else {{
+     pos_b.x = pos_b.x + step_len * 0.5;
+ }}
+ let dir = select(vec2f(0.0, px.y), vec2f(px.x, 0.0), horizontal);
+ let steps = array<f32, {step_count}>({step_list});
+ var pos_n = pos_b - dir * steps[0];
+ var pos_p = pos_b + dir * steps[0];
+ var end_n = dot(textureSampleLevel(src_tex, src_samp, pos_n, 0.0).rgb, lw) - luma_local;
+ var end_p = dot(textureSampleLevel(src_tex, src_samp, pos_p, 0.0).rgb, lw) - luma_local;
+ var done_n = abs(end_n) >= gradient_scaled;
+ var done_p = abs(end_p) >= gradient_scaled;
+ for (var i: u32 = 1u; i < {step_count}u; i = i + 1u) {{
+     if (done_n && done_p) {{
+         break;
+     }}
+     if (!done_n) {{
+         pos_n = pos_n - dir * steps[i];
+         end_n = dot(textureSampleLevel(src_tex, src_samp, pos_n, 0.0).rgb, lw) - luma_local;
+         done_n = abs(end_n) >= gradient_scaled;
+     }}
+     if (!done_p) {{
+         pos_p = pos_p + dir * steps[i];
+         end_p = dot(textureSampleLevel(src_tex, src_samp, pos_p, 0.0).rgb, lw) - luma_local;
+         done_p = abs(end_p) >= gradient_scaled;
+     }}
+ }}
+
+ // Blend toward the nearer span end, only when it ends on the far side of the edge.
+ let dst_n = select(uv.y - pos_n.y, uv.x - pos_n.x, horizontal);
+ let dst_p = select(pos_p.y - uv.y, pos_p.x - uv.x, horizontal);
+ let m_below = luma_m - luma_local < 0.0;
+ let good_span = select((end_p < 0.0) != m_below, (end_n < 0.0) != m_below, dst_n < dst_p);
+ let span_offset = 0.5 - min(dst_n, dst_p) / max(dst_n + dst_p, 1e-6);
+ let offset = max(select(0.0, span_offset, good_span), subpix);
+ var final_uv = uv;
+ if (horizontal) {{
+     final_uv.y = final_uv.y + offset * step_len;
+ }} else {{
+     final_uv.x = final_uv.x + offset * step_len;
+ }}
+ return textureSampleLevel(src_tex, src_samp, final_uv, 0.0);
+"#,
+        threshold = fmt_f32(threshold),
+        threshold_min = fmt_f32(threshold_min),
+        subpixel = fmt_f32(settings.subpixel),
+        step_count = steps.len(),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Assemble an `"AntiAliasPass"` layer.
+pub(crate) fn assemble_anti_alias_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = anti_alias_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_anti_alias_wgsl(&settings);
+    assemble_fullscreen_filter(sc, bs, layer_id, layer_node, "aa", shader_wgsl)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    #[test]
+    fn fxaa_shaders_validate_for_every_preset() {
+        for quality in [
+            FxaaQuality::Low,
+            FxaaQuality::Medium,
+            FxaaQuality::High,
+            FxaaQuality::Extreme,
+        ] {
+            let wgsl = build_anti_alias_wgsl(&AntiAliasSettings {
+                quality,
+                subpixel: 0.75,
+            });
+            validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+            let steps = quality.search_steps().len();
+            assert!(wgsl.contains(&format!("array<f32, {steps}>(")));
+        }
+    }
+
+    #[test]
+    fn only_fxaa_is_accepted() {
+        let node = |method: &str| Node {
+            id: "aa".to_string(),
+            node_type: "AntiAliasPass".to_string(),
+            params: HashMap::from([("method".to_string(), json!(method))]),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        };
+        assert!(check_method(&node("FXAA")).is_ok());
+        let err = check_method(&node("smaa")).unwrap_err();
+        assert!(err.to_string().contains("not supported"));
+        assert!(check_method(&node("taa")).is_err());
+    }
+}
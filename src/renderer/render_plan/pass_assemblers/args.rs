@@ -26,7 +26,10 @@ use crate::{
     },
 };
 
-use super::super::pass_spec::{DepthResolvePass, RenderPassSpec, TextureDecl};
+use super::super::blend::PassLoadOp;
+use super::super::pass_spec::{ComputePassSpec, DepthResolvePass, RenderPassSpec, TextureDecl};
+use super::super::stencil::PassStencilAttachment;
+use super::super::viewport::PassRect;
 
 /// Immutable context shared by all pass assemblers.
 pub(crate) struct SceneContext<'a> {
@@ -38,6 +41,8 @@ pub(crate) struct SceneContext<'a> {
     /// The planning device capabilities.
     pub device: &'a crate::renderer::render_plan::types::PlanningDevice,
     pub adapter: Option<&'a wgpu::Adapter>,
+    /// Global coordinate visualization injected into every draw pass.
+    pub channel_debug: crate::renderer::ChannelDebugMode,
 }
 
 impl<'a> SceneContext<'a> {
@@ -73,11 +78,15 @@ pub(crate) struct BuilderState<'b> {
     pub instance_buffers: &'b mut Vec<(ResourceName, Arc<[u8]>)>,
     pub textures: &'b mut Vec<TextureDecl>,
     pub render_pass_specs: &'b mut Vec<RenderPassSpec>,
+    pub compute_pass_specs: &'b mut Vec<ComputePassSpec>,
     pub composite_passes: &'b mut Vec<ResourceName>,
     pub depth_resolve_passes: &'b mut Vec<DepthResolvePass>,
 
     pub pass_cull_mode_by_name: &'b mut HashMap<ResourceName, Option<wgpu::Face>>,
     pub pass_depth_attachment_by_name: &'b mut HashMap<ResourceName, ResourceName>,
+    pub pass_stencil_by_name: &'b mut HashMap<ResourceName, PassStencilAttachment>,
+    pub pass_load_op_by_name: &'b mut HashMap<ResourceName, PassLoadOp>,
+    pub pass_clip_rect_by_name: &'b mut HashMap<ResourceName, PassRect>,
     pub pass_output_registry: &'b mut PassOutputRegistry,
     pub sampled_pass_ids: &'b HashSet<String>,
 
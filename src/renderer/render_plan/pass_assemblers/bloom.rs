@@ -1,8 +1,11 @@
 //! Bloom pass assembler.
 //!
-//! Handles the `"BloomNode"` node type. Applies bloom effect by extracting bright
-//! areas, downsampling through a MIP chain, applying Gaussian blur at each level,
-//! and additively combining back up to the original resolution.
+//! Handles the `"BloomNode"` and `"BloomPass"` node types. Applies bloom effect by
+//! extracting bright areas, downsampling through a MIP chain, applying Gaussian blur
+//! at each level, and additively combining back up to the original resolution.
+//!
+//! Both node types share the pipeline and differ only in how params are read
+//! (see [`bloom_settings`]) and in the output port name.
 
 use anyhow::{Context, Result, anyhow};
 use rust_wgpu_fiber::{
@@ -22,7 +25,9 @@ use crate::{
             build_vertical_blur_bundle_with_tap_count, clamp_min_1, gaussian_kernel_8,
             gaussian_mip_level_and_sigma_p,
         },
-        wgsl_bloom::{build_bloom_additive_combine_bundle, build_bloom_extract_bundle},
+        wgsl_bloom::{
+            BLOOM_MAX_MIPS, build_bloom_additive_combine_bundle, build_bloom_extract_bundle,
+        },
     },
 };
 
@@ -36,7 +41,66 @@ use super::super::resource_naming::{
 use super::args::{BuilderState, SceneContext, make_fullscreen_geometry};
 use crate::renderer::shader_space::sampler::sampler_kind_for_pass_texture;
 
-/// Assemble a `"BloomNode"` layer.
+/// Extract/blur settings resolved from a bloom node's params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct BloomSettings {
+    pub threshold: f32,
+    pub smooth_width_px: f32,
+    pub strength: f32,
+    pub saturation: f32,
+    pub radius_px: f32,
+    /// Cap on downsample levels; `None` runs the full chain for the source size.
+    pub max_mip_levels: Option<u32>,
+}
+
+/// Resolve [`BloomSettings`] for `node_type`, reading numbers through `num(key, default)`.
+///
+/// `BloomNode` keeps its legacy `smoothness`/`strength` knobs. `BloomPass` exposes
+/// `softKnee` (knee half-width as a fraction of `threshold`), an unclamped
+/// `intensity` multiplier, and `mipCount`.
+pub(crate) fn bloom_settings(
+    node_type: &str,
+    mut num: impl FnMut(&str, f32) -> Result<f32>,
+) -> Result<BloomSettings> {
+    let saturation = num("saturation", 1.0)?.clamp(0.0, 1.0);
+    let radius_px = num("size", 0.5)?.clamp(0.0, 1.0) * 6.0;
+    if node_type == "BloomPass" {
+        let threshold = num("threshold", 0.8)?.clamp(0.0, 1.0);
+        let soft_knee = num("softKnee", 0.5)?.clamp(0.0, 1.0);
+        let mip_count = num("mipCount", BLOOM_MAX_MIPS as f32)?
+            .round()
+            .clamp(0.0, BLOOM_MAX_MIPS as f32) as u32;
+        return Ok(BloomSettings {
+            threshold,
+            smooth_width_px: soft_knee * threshold * 255.0,
+            strength: num("intensity", 1.0)?.max(0.0),
+            saturation,
+            radius_px,
+            max_mip_levels: Some(mip_count),
+        });
+    }
+
+    let smoothness = num("smoothness", 0.5)?.clamp(0.0, 1.0);
+    Ok(BloomSettings {
+        threshold: num("threshold", 0.5)?.clamp(0.0, 1.0),
+        smooth_width_px: (1.0 - smoothness) * 40.0,
+        strength: num("strength", 1.0)?.clamp(0.0, 1.0),
+        saturation,
+        radius_px,
+        max_mip_levels: None,
+    })
+}
+
+/// Output port a bloom node type registers its result under.
+pub(crate) fn bloom_output_port(node_type: &str) -> &'static str {
+    if node_type == "BloomPass" {
+        "pass"
+    } else {
+        "glare"
+    }
+}
+
+/// Assemble a `"BloomNode"` or `"BloomPass"` layer.
 pub(crate) fn assemble_bloom(
     sc: &SceneContext<'_>,
     bs: &mut BuilderState<'_>,
@@ -48,14 +112,15 @@ pub(crate) fn assemble_bloom(
     let tgt_w = bs.tgt_size[0];
     let tgt_h = bs.tgt_size[1];
 
+    let node_type = layer_node.node_type.as_str();
     let src_conn = incoming_connection(scene, layer_id, "pass")
-        .ok_or_else(|| anyhow!("BloomNode.pass missing for {layer_id}"))?;
+        .ok_or_else(|| anyhow!("{node_type}.pass missing for {layer_id}"))?;
     let src_spec = bs
         .pass_output_registry
         .get_for_port(&src_conn.from.node_id, &src_conn.from.port_id)
         .ok_or_else(|| {
             anyhow!(
-                "BloomNode.pass references upstream pass {}, but its output is not registered yet",
+                "{node_type}.pass references upstream pass {}, but its output is not registered yet",
                 src_conn.from.node_id
             )
         })?;
@@ -64,15 +129,16 @@ pub(crate) fn assemble_bloom(
     let base_w = base_resolution[0].max(1) as f32;
     let base_h = base_resolution[1].max(1) as f32;
 
-    let threshold = cpu_num_f32(scene, &nodes_by_id, layer_node, "threshold", 0.5)?.clamp(0.0, 1.0);
-    let smoothness =
-        cpu_num_f32(scene, &nodes_by_id, layer_node, "smoothness", 0.5)?.clamp(0.0, 1.0);
-    let strength = cpu_num_f32(scene, &nodes_by_id, layer_node, "strength", 1.0)?.clamp(0.0, 1.0);
-    let saturation =
-        cpu_num_f32(scene, &nodes_by_id, layer_node, "saturation", 1.0)?.clamp(0.0, 1.0);
-    let size = cpu_num_f32(scene, &nodes_by_id, layer_node, "size", 0.5)?.clamp(0.0, 1.0);
-    let smooth_width_px = (1.0 - smoothness) * 40.0;
-    let radius_px = size * 6.0;
+    let BloomSettings {
+        threshold,
+        smooth_width_px,
+        strength,
+        saturation,
+        radius_px,
+        max_mip_levels,
+    } = bloom_settings(node_type, |key, default| {
+        cpu_num_f32(scene, &nodes_by_id, layer_node, key, default)
+    })?;
     let tint = parse_tint_from_node_or_default(scene, &nodes_by_id, layer_node)?;
 
     let sigma = radius_px / 3.525_494;
@@ -110,7 +176,8 @@ pub(crate) fn assemble_bloom(
         BlendState::REPLACE
     };
 
-    let mip_levels = bloom_downsample_level_count(base_resolution);
+    let mip_levels =
+        bloom_downsample_level_count(base_resolution).min(max_mip_levels.unwrap_or(BLOOM_MAX_MIPS));
 
     // ---- MIP0 (extract) ----
     let mip0_tex: ResourceName = format!("sys.bloom.{layer_id}.mip0").into();
@@ -627,7 +694,10 @@ pub(crate) fn assemble_bloom(
     }
 
     bs.pass_output_registry.register(PassOutputSpec {
-        endpoint: crate::renderer::types::OutputEndpoint::new(layer_id, "glare"),
+        endpoint: crate::renderer::types::OutputEndpoint::new(
+            layer_id,
+            bloom_output_port(node_type),
+        ),
         texture_name: output_tex.clone(),
         resolution: base_resolution,
         format: if is_sampled_output {
@@ -637,5 +707,121 @@ pub(crate) fn assemble_bloom(
         },
     });
 
+    let composition_consumers = sc
+        .composition_consumers_by_source
+        .get(layer_id)
+        .cloned()
+        .unwrap_or_default();
+    for composition_id in composition_consumers {
+        let Some(comp_ctx) = sc.composition_contexts.get(&composition_id) else {
+            continue;
+        };
+        if output_tex == comp_ctx.target_texture_name {
+            continue;
+        }
+
+        let comp_w = comp_ctx.target_size_px[0];
+        let comp_h = comp_ctx.target_size_px[1];
+        let compose_geo: ResourceName =
+            format!("sys.bloom.{layer_id}.to.{composition_id}.compose.geo").into();
+        bs.geometry_buffers.push((
+            compose_geo.clone(),
+            make_fullscreen_geometry(comp_w, comp_h),
+        ));
+        let compose_pass_name: ResourceName =
+            format!("sys.bloom.{layer_id}.to.{composition_id}.compose.pass").into();
+        let compose_params = make_params(
+            [comp_w, comp_h],
+            [comp_w, comp_h],
+            [comp_w * 0.5, comp_h * 0.5],
+            resolve_effective_camera_for_pass_node(
+                scene,
+                &nodes_by_id,
+                layer_node,
+                [comp_w, comp_h],
+            )?,
+            [0.0, 0.0, 0.0, 0.0],
+        );
+        bs.render_pass_specs.push(RenderPassSpec {
+            pass_id: compose_pass_name.as_str().to_string(),
+            name: compose_pass_name.clone(),
+            geometry_buffer: compose_geo,
+            instance_buffer: None,
+            normals_buffer: None,
+            vertex_layout: Default::default(),
+            target_texture: comp_ctx.target_texture_name.clone(),
+            resolve_target: None,
+            params_buffer: format!("params.sys.bloom.{layer_id}.to.{composition_id}.compose")
+                .into(),
+            baked_data_parse_buffer: None,
+            params: compose_params,
+            graph_binding: None,
+            graph_values: None,
+            shader_wgsl: build_fullscreen_textured_bundle(
+                "return textureSample(src_tex, src_samp, in.uv);".to_string(),
+            )
+            .module,
+            texture_bindings: vec![PassTextureBinding {
+                texture: output_tex.clone(),
+                image_node_id: None,
+            }],
+            sampler_kinds: vec![SamplerKind::LinearClamp],
+            blend_state: pass_blend_state,
+            color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+            sample_count: 1,
+        });
+        bs.composite_passes.push(compose_pass_name);
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{BloomSettings, bloom_output_port, bloom_settings};
+    use crate::renderer::wgsl_bloom::BLOOM_MAX_MIPS;
+
+    fn settings(node_type: &str, params: &[(&str, f32)]) -> Result<BloomSettings> {
+        bloom_settings(node_type, |key, default| {
+            Ok(params
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map_or(default, |(_, v)| *v))
+        })
+    }
+
+    #[test]
+    fn bloom_pass_maps_soft_knee_intensity_and_mip_count() -> Result<()> {
+        let s = settings(
+            "BloomPass",
+            &[
+                ("threshold", 0.6),
+                ("softKnee", 0.5),
+                ("intensity", 2.5),
+                ("mipCount", 3.0),
+            ],
+        )?;
+        assert_eq!(s.threshold, 0.6);
+        assert!((s.smooth_width_px - 0.3 * 255.0).abs() < 1e-3);
+        assert_eq!(s.strength, 2.5);
+        assert_eq!(s.max_mip_levels, Some(3));
+
+        let clamped = settings("BloomPass", &[("mipCount", 40.0), ("intensity", -1.0)])?;
+        assert_eq!(clamped.max_mip_levels, Some(BLOOM_MAX_MIPS));
+        assert_eq!(clamped.strength, 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn bloom_node_keeps_legacy_params_and_glare_port() -> Result<()> {
+        let s = settings("BloomNode", &[("strength", 3.0), ("smoothness", 0.25)])?;
+        assert_eq!(s.strength, 1.0);
+        assert_eq!(s.smooth_width_px, 30.0);
+        assert_eq!(s.max_mip_levels, None);
+        assert_eq!(bloom_output_port("BloomNode"), "glare");
+        assert_eq!(bloom_output_port("BloomPass"), "pass");
+        Ok(())
+    }
+}
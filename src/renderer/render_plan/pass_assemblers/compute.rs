@@ -0,0 +1,634 @@
+//! ComputePass assembler.
+//!
+//! Dispatches one compute shader over a storage texture. The shader body is
+//! either raw WGSL (`params.source`, which sees `gid`, `texel`, `in`, `output`
+//! and, when connected, `color`) or the compiled `color` subgraph stored per
+//! texel. The storage texture is registered as the node's `pass` output, so
+//! PassTexture and Composite consumers sample it like any other pass.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result, anyhow, bail};
+use rust_wgpu_fiber::{
+    ResourceName,
+    eframe::wgpu::{self, Color, TextureFormat},
+};
+use serde_json::Value;
+
+use crate::{
+    dsl::{Node, SceneDSL, incoming_connection},
+    renderer::{
+        camera::legacy_projection_camera_matrix,
+        graph_uniforms::{choose_graph_binding_kind, pack_graph_values},
+        node_compiler::{
+            compile_cache::compile_material_expr_cached,
+            texture_nodes::use_compute_mip_atlas_sampling,
+        },
+        types::{
+            GraphBinding, GraphBindingKind, MaterialCompileContext, PassOutputSpec,
+            WgslShaderBundle,
+        },
+        utils::{cpu_num_u32_min_1, to_vec4_color},
+        wgsl::{build_fullscreen_textured_bundle, graph_inputs_wgsl_decl, merge_graph_input_kinds},
+    },
+};
+
+use super::super::pass_spec::{
+    ComputePassSpec, PassTextureBinding, RenderPassSpec, SamplerKind, TextureDecl, make_params,
+};
+use super::args::{BuilderState, SceneContext};
+use crate::renderer::shader_space::sampler::{
+    image_texture_binding, sampler_kind_for_pass_texture,
+};
+
+const DEFAULT_WORKGROUP_SIZE: [u32; 2] = [8, 8];
+/// WebGPU's minimum `maxComputeInvocationsPerWorkgroup`.
+const MAX_WORKGROUP_INVOCATIONS: u32 = 256;
+
+/// Parse `workgroupSize` as `{x, y}`, `[x, y]` or a single number (square).
+pub(crate) fn parse_compute_workgroup_size(params: &HashMap<String, Value>) -> Result<[u32; 2]> {
+    let Some(value) = params.get("workgroupSize") else {
+        return Ok(DEFAULT_WORKGROUP_SIZE);
+    };
+    let axis = |v: Option<&Value>, name: &str| -> Result<u32> {
+        let n = v
+            .and_then(Value::as_f64)
+            .ok_or_else(|| anyhow!("ComputePass.workgroupSize.{name} must be a number"))?;
+        if n < 1.0 || n.fract() != 0.0 {
+            bail!("ComputePass.workgroupSize.{name} must be a positive integer, got {n}");
+        }
+        Ok(n as u32)
+    };
+    let size = match value {
+        Value::Object(map) => [axis(map.get("x"), "x")?, axis(map.get("y"), "y")?],
+        Value::Array(items) if items.len() == 2 => {
+            [axis(items.first(), "x")?, axis(items.get(1), "y")?]
+        }
+        Value::Number(_) => {
+            let n = axis(Some(value), "x")?;
+            [n, n]
+        }
+        other => {
+            bail!("ComputePass.workgroupSize must be {{x, y}}, [x, y] or a number, got {other}")
+        }
+    };
+    if size[0].saturating_mul(size[1]) > MAX_WORKGROUP_INVOCATIONS {
+        bail!(
+            "ComputePass.workgroupSize {}x{} exceeds {MAX_WORKGROUP_INVOCATIONS} invocations",
+            size[0],
+            size[1]
+        );
+    }
+    Ok(size)
+}
+
+/// Parse `format` into a storage-capable, filterable texture format.
+pub(crate) fn parse_compute_storage_format(
+    params: &HashMap<String, Value>,
+) -> Result<TextureFormat> {
+    let format = params
+        .get("format")
+        .and_then(Value::as_str)
+        .unwrap_or("rgba8unorm")
+        .trim()
+        .to_ascii_lowercase();
+    match format.as_str() {
+        "rgba8unorm" => Ok(TextureFormat::Rgba8Unorm),
+        "rgba16float" => Ok(TextureFormat::Rgba16Float),
+        other => {
+            bail!("unsupported ComputePass.format: {other} (expected rgba8unorm or rgba16float)")
+        }
+    }
+}
+
+fn storage_format_wgsl(format: TextureFormat) -> Result<&'static str> {
+    match format {
+        TextureFormat::Rgba8Unorm => Ok("rgba8unorm"),
+        TextureFormat::Rgba16Float => Ok("rgba16float"),
+        other => bail!("unsupported ComputePass storage format: {other:?}"),
+    }
+}
+
+/// Compute stages have no implicit derivatives, so implicit-LOD sampling
+/// emitted by texture nodes is rewritten to sample mip 0 explicitly.
+pub(crate) fn rewrite_texture_sample_for_compute(src: &str) -> String {
+    const NEEDLE: &str = "textureSample(";
+    let mut out = src.to_string();
+    while let Some(start) = out.find(NEEDLE) {
+        let args_start = start + NEEDLE.len();
+        let mut depth = 1usize;
+        let mut close = None;
+        for (offset, ch) in out[args_start..].char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(args_start + offset);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(close) = close else {
+            break;
+        };
+        let args = out[args_start..close].to_string();
+        out.replace_range(start..=close, &format!("textureSampleLevel({args}, 0.0)"));
+    }
+    out
+}
+
+/// Build the compute shader bundle for a `ComputePass` node.
+///
+/// Group 0 holds `params` (binding 0), the storage output (binding 1) and graph
+/// inputs (binding 2); material textures use group 1 as in render passes.
+pub(crate) fn build_compute_pass_wgsl_bundle(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    pass_id: &str,
+    storage_format: TextureFormat,
+    workgroup_size: [u32; 2],
+    forced_graph_binding_kind: Option<GraphBindingKind>,
+) -> Result<WgslShaderBundle> {
+    let node = nodes_by_id
+        .get(pass_id)
+        .ok_or_else(|| anyhow!("missing ComputePass node: {pass_id}"))?;
+    let source = node
+        .params
+        .get("source")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .trim()
+        .to_string();
+
+    let mut material_ctx = MaterialCompileContext::default();
+    let color_expr = if let Some(conn) = incoming_connection(scene, pass_id, "color") {
+        let expr = compile_material_expr_cached(
+            scene,
+            nodes_by_id,
+            &conn.from.node_id,
+            &conn.from.port_id,
+            &mut material_ctx,
+        )?;
+        Some(to_vec4_color(expr))
+    } else {
+        None
+    };
+    if source.is_empty() && color_expr.is_none() {
+        bail!("ComputePass {pass_id}: set params.source or connect the color input");
+    }
+    if !material_ctx.shader_parameter_kinds.is_empty() {
+        bail!("ComputePass {pass_id}: ShaderMaterial parameters are not supported");
+    }
+
+    let graph_schema = merge_graph_input_kinds(&material_ctx, &BTreeMap::new());
+    let graph_binding_kind = graph_schema
+        .as_ref()
+        .map(|_| forced_graph_binding_kind.unwrap_or(GraphBindingKind::Uniform));
+    let format_wgsl = storage_format_wgsl(storage_format)?;
+
+    let mut common = format!(
+        r#"
+struct Params {{
+    target_size: vec2f,
+    geo_size: vec2f,
+    center: vec2f,
+
+    geo_translate: vec2f,
+    geo_scale: vec2f,
+
+    // Pack to 16-byte boundary.
+    time: f32,
+    _pad0: f32,
+
+    // 16-byte aligned.
+    color: vec4f,
+    camera: mat4x4f,
+    camera_position: vec4f,
+
+    // Frame clock shared by all passes (Time node).
+    delta_time: f32,
+    frame: u32,
+}};
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+
+@group(0) @binding(1)
+var output: texture_storage_2d<{format_wgsl}, write>;
+
+// Per-texel stand-in for the fragment input, so material code compiles unchanged.
+struct VSOut {{
+    position: vec4f,
+    uv: vec2f,
+    frag_coord_gl: vec2f,
+    local_px: vec3f,
+    geo_size_px: vec2f,
+    instance_index: u32,
+    normal: vec3f,
+    world_pos: vec3f,
+}};
+"#
+    );
+    if let (Some(schema), Some(kind)) = (graph_schema.as_ref(), graph_binding_kind) {
+        common.push_str(&graph_inputs_wgsl_decl(schema, kind));
+    }
+    // No derivatives in compute: surfaceNormal() falls back to the flat `in.normal`
+    // and mip-filtered images read their base level.
+    use_compute_mip_atlas_sampling(&mut material_ctx);
+    common.push_str(&material_ctx.wgsl_decls_with_normals(true));
+    if material_ctx.needs_f16 {
+        common = format!("enable f16;\n{common}");
+    }
+
+    let mut body = String::new();
+    if material_ctx.uses_front_facing {
+        body.push_str("    let front_facing = true;\n");
+    }
+    if let Some(color) = color_expr.as_ref() {
+        for stmt in &material_ctx.inline_stmts {
+            body.push_str(stmt);
+            body.push('\n');
+        }
+        body.push_str(&format!("    let color = {};\n", color.expr));
+    }
+    if source.is_empty() {
+        body.push_str("    textureStore(output, texel, color);\n");
+    } else {
+        body.push_str(&source);
+        body.push('\n');
+    }
+
+    let [wg_x, wg_y] = workgroup_size;
+    let compute_entry = format!(
+        r#"
+@compute @workgroup_size({wg_x}, {wg_y}, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {{
+    let size = textureDimensions(output);
+    if (gid.x >= size.x || gid.y >= size.y) {{
+        return;
+    }}
+    let texel = vec2i(gid.xy);
+    let size_f = vec2f(size);
+    let px = vec2f(gid.xy) + vec2f(0.5);
+
+    var in: VSOut;
+    in.position = vec4f(px, 0.0, 1.0);
+    // Top-left origin, matching fullscreen render-pass uv.
+    in.uv = px / size_f;
+    // GLSL-like gl_FragCoord.xy: bottom-left origin, pixel-centered.
+    in.frag_coord_gl = vec2f(px.x, size_f.y - px.y);
+    in.local_px = vec3f(in.frag_coord_gl, 0.0);
+    in.geo_size_px = size_f;
+    in.normal = vec3f(0.0, 0.0, 1.0);
+    in.world_pos = in.local_px;
+
+{body}}}
+"#
+    );
+
+    let common = rewrite_texture_sample_for_compute(&common);
+    let compute_entry = rewrite_texture_sample_for_compute(&compute_entry);
+    let module = format!("{common}{compute_entry}");
+
+    Ok(WgslShaderBundle {
+        common,
+        vertex: String::new(),
+        fragment: String::new(),
+        compute: Some(module.clone()),
+        module,
+        image_textures: material_ctx.image_textures.clone(),
+        pass_textures: material_ctx.pass_textures.clone(),
+        graph_schema,
+        graph_binding_kind,
+        shader_parameter_schema: None,
+    })
+}
+
+/// Assemble a `"ComputePass"` layer.
+pub(crate) fn assemble_compute_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let prepared = sc.prepared;
+    let scene = sc.scene();
+    let nodes_by_id = sc.nodes_by_id();
+    let ids = sc.ids();
+
+    let workgroup_size = parse_compute_workgroup_size(&layer_node.params)
+        .with_context(|| format!("invalid ComputePass {layer_id}"))?;
+    let storage_format = parse_compute_storage_format(&layer_node.params)
+        .with_context(|| format!("invalid ComputePass {layer_id}"))?;
+    let out_w = cpu_num_u32_min_1(scene, nodes_by_id, layer_node, "width", bs.tgt_size_u[0])?;
+    let out_h = cpu_num_u32_min_1(scene, nodes_by_id, layer_node, "height", bs.tgt_size_u[1])?;
+    let out_size = [out_w as f32, out_h as f32];
+
+    let mut bundle = build_compute_pass_wgsl_bundle(
+        scene,
+        nodes_by_id,
+        layer_id,
+        storage_format,
+        workgroup_size,
+        None,
+    )?;
+
+    let mut graph_binding: Option<GraphBinding> = None;
+    let mut graph_values: Option<Vec<u8>> = None;
+    if let Some(schema) = bundle.graph_schema.clone() {
+        let limits = sc.device.limits();
+        let kind = choose_graph_binding_kind(
+            schema.size_bytes,
+            limits.max_uniform_buffer_binding_size as u64,
+            limits.max_storage_buffer_binding_size as u64,
+        )?;
+        if bundle.graph_binding_kind != Some(kind) {
+            bundle = build_compute_pass_wgsl_bundle(
+                scene,
+                nodes_by_id,
+                layer_id,
+                storage_format,
+                workgroup_size,
+                Some(kind),
+            )?;
+        }
+        let schema = bundle
+            .graph_schema
+            .clone()
+            .ok_or_else(|| anyhow!("missing graph schema after graph binding selection"))?;
+        graph_values = Some(pack_graph_values(&prepared.scene, &schema)?);
+        graph_binding = Some(GraphBinding {
+            buffer_name: format!("params.{layer_id}.graph").into(),
+            kind,
+            schema,
+        });
+    }
+
+    let mut texture_bindings: Vec<PassTextureBinding> = Vec::new();
+    let mut sampler_kinds: Vec<SamplerKind> = Vec::new();
+    for id in bundle.image_textures.iter() {
+        let Some((binding, kind)) = image_texture_binding(ids, nodes_by_id, id) else {
+            continue;
+        };
+        texture_bindings.push(binding);
+        sampler_kinds.push(kind);
+    }
+    let pass_bindings = crate::renderer::render_plan::resolve_pass_texture_bindings(
+        &bs.pass_output_registry,
+        &bundle.pass_textures,
+    )?;
+    for (texture_ref, binding) in bundle.pass_textures.iter().zip(pass_bindings) {
+        texture_bindings.push(binding);
+        sampler_kinds.push(sampler_kind_for_pass_texture(&prepared.scene, texture_ref));
+    }
+
+    let output_tex: ResourceName = format!("sys.compute.{layer_id}.out").into();
+    bs.textures.push(TextureDecl {
+        name: output_tex.clone(),
+        size: [out_w, out_h],
+        format: storage_format,
+        sample_count: 1,
+        needs_sampling: true,
+    });
+
+    let pass_name: ResourceName = format!("sys.compute.{layer_id}.pass").into();
+    bs.compute_pass_specs.push(ComputePassSpec {
+        pass_id: pass_name.as_str().to_string(),
+        name: pass_name.clone(),
+        output_texture: output_tex.clone(),
+        params_buffer: format!("params.sys.compute.{layer_id}").into(),
+        params: make_params(
+            out_size,
+            out_size,
+            [out_size[0] * 0.5, out_size[1] * 0.5],
+            legacy_projection_camera_matrix(out_size),
+            [0.0, 0.0, 0.0, 0.0],
+        ),
+        graph_binding,
+        graph_values,
+        shader_wgsl: bundle
+            .compute
+            .ok_or_else(|| anyhow!("ComputePass {layer_id}: missing compute module"))?,
+        texture_bindings,
+        sampler_kinds,
+        workgroups: [
+            out_w.div_ceil(workgroup_size[0]),
+            out_h.div_ceil(workgroup_size[1]),
+            1,
+        ],
+    });
+    bs.composite_passes.push(pass_name);
+
+    bs.pass_output_registry.register(PassOutputSpec {
+        endpoint: crate::renderer::types::OutputEndpoint::new(layer_id, "pass"),
+        texture_name: output_tex.clone(),
+        resolution: [out_w, out_h],
+        format: storage_format,
+    });
+
+    // The dispatch only writes its storage texture; drawing into the scene
+    // output or a Composite target takes a fullscreen blit per target.
+    let pass_blend_state =
+        crate::renderer::render_plan::parse_render_pass_blend_state(&layer_node.params)
+            .with_context(|| format!("invalid blend params for ComputePass {layer_id}"))?;
+    let mut blit_targets: Vec<(String, ResourceName, [f32; 2])> = Vec::new();
+    if !bs.sampled_pass_ids.contains(layer_id) {
+        blit_targets.push((
+            "scene".to_string(),
+            bs.target_texture_name.clone(),
+            bs.tgt_size,
+        ));
+    }
+    for composition_id in sc
+        .composition_consumers_by_source
+        .get(layer_id)
+        .cloned()
+        .unwrap_or_default()
+    {
+        let Some(comp_ctx) = sc.composition_contexts.get(&composition_id) else {
+            continue;
+        };
+        if blit_targets
+            .iter()
+            .any(|(_, target, _)| *target == comp_ctx.target_texture_name)
+        {
+            continue;
+        }
+        blit_targets.push((
+            composition_id.clone(),
+            comp_ctx.target_texture_name.clone(),
+            comp_ctx.target_size_px,
+        ));
+    }
+
+    for (target_id, target_texture, [comp_w, comp_h]) in blit_targets {
+        let compose_geo: ResourceName =
+            format!("sys.compute.{layer_id}.to.{target_id}.compose.geo").into();
+        bs.push_fullscreen_geometry(compose_geo.clone(), comp_w, comp_h);
+
+        let compose_pass_name: ResourceName =
+            format!("sys.compute.{layer_id}.to.{target_id}.compose.pass").into();
+        bs.render_pass_specs.push(RenderPassSpec {
+            pass_id: compose_pass_name.as_str().to_string(),
+            name: compose_pass_name.clone(),
+            geometry_buffer: compose_geo,
+            instance_buffer: None,
+            normals_buffer: None,
+            vertex_layout: Default::default(),
+            target_texture,
+            resolve_target: None,
+            params_buffer: format!("params.sys.compute.{layer_id}.to.{target_id}.compose").into(),
+            baked_data_parse_buffer: None,
+            params: make_params(
+                [comp_w, comp_h],
+                [comp_w, comp_h],
+                [comp_w * 0.5, comp_h * 0.5],
+                legacy_projection_camera_matrix([comp_w, comp_h]),
+                [0.0, 0.0, 0.0, 0.0],
+            ),
+            graph_binding: None,
+            graph_values: None,
+            shader_wgsl: build_fullscreen_textured_bundle(
+                "return textureSample(src_tex, src_samp, in.uv);".to_string(),
+            )
+            .module,
+            texture_bindings: vec![PassTextureBinding {
+                texture: output_tex.clone(),
+                image_node_id: None,
+            }],
+            sampler_kinds: vec![SamplerKind::LinearClamp],
+            blend_state: pass_blend_state,
+            color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+            sample_count: 1,
+        });
+        bs.composite_passes.push(compose_pass_name);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rust_wgpu_fiber::eframe::wgpu::TextureFormat;
+    use serde_json::json;
+
+    use super::{
+        build_compute_pass_wgsl_bundle, parse_compute_workgroup_size,
+        rewrite_texture_sample_for_compute,
+    };
+    use crate::dsl::Node;
+    use crate::renderer::node_compiler::test_utils::{test_connection, test_scene};
+    use crate::renderer::validation::validate_wgsl_module;
+
+    fn node(id: &str, node_type: &str, params: serde_json::Value) -> Node {
+        Node {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            params: params
+                .as_object()
+                .cloned()
+                .map(|m| m.into_iter().collect())
+                .unwrap_or_default(),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    fn nodes_by_id(nodes: &[Node]) -> HashMap<String, Node> {
+        nodes.iter().map(|n| (n.id.clone(), n.clone())).collect()
+    }
+
+    #[test]
+    fn compute_bundle_stores_connected_color_subgraph() {
+        let nodes = vec![
+            node("tint", "ColorInput", json!({"value": [1.0, 0.5, 0.0, 1.0]})),
+            node("cp", "ComputePass", json!({})),
+        ];
+        let scene = test_scene(
+            nodes.clone(),
+            vec![test_connection("tint", "color", "cp", "color")],
+        );
+        let bundle = build_compute_pass_wgsl_bundle(
+            &scene,
+            &nodes_by_id(&nodes),
+            "cp",
+            TextureFormat::Rgba8Unorm,
+            [16, 4],
+            None,
+        )
+        .unwrap();
+
+        let module = bundle.compute.expect("compute module");
+        assert!(module.contains("@compute @workgroup_size(16, 4, 1)"));
+        assert!(module.contains("texture_storage_2d<rgba8unorm, write>"));
+        assert!(module.contains("textureStore(output, texel, color);"));
+        assert!(module.contains("var<uniform> graph_inputs: GraphInputs;"));
+        validate_wgsl_module(&module).unwrap_or_else(|e| panic!("{e:#}\n{module}"));
+    }
+
+    #[test]
+    fn compute_bundle_uses_raw_source_body() {
+        let nodes = vec![node(
+            "cp",
+            "ComputePass",
+            json!({"source": "    textureStore(output, texel, vec4f(in.uv, 0.0, 1.0));"}),
+        )];
+        let scene = test_scene(nodes.clone(), Vec::new());
+        let bundle = build_compute_pass_wgsl_bundle(
+            &scene,
+            &nodes_by_id(&nodes),
+            "cp",
+            TextureFormat::Rgba16Float,
+            [8, 8],
+            None,
+        )
+        .unwrap();
+        validate_wgsl_module(&bundle.module).unwrap_or_else(|e| panic!("{e:#}\n{}", bundle.module));
+
+        let empty = vec![node("cp", "ComputePass", json!({}))];
+        let scene = test_scene(empty.clone(), Vec::new());
+        let err = build_compute_pass_wgsl_bundle(
+            &scene,
+            &nodes_by_id(&empty),
+            "cp",
+            TextureFormat::Rgba8Unorm,
+            [8, 8],
+            None,
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("params.source"));
+    }
+
+    #[test]
+    fn texture_sample_is_rewritten_to_explicit_lod() {
+        assert_eq!(
+            rewrite_texture_sample_for_compute(
+                "textureSample(a, s, f(uv)) + textureSampleLevel(b, s, uv, 1.0)"
+            ),
+            "textureSampleLevel(a, s, f(uv), 0.0) + textureSampleLevel(b, s, uv, 1.0)"
+        );
+    }
+
+    #[test]
+    fn workgroup_size_accepts_object_array_and_scalar() {
+        let parse = |v: serde_json::Value| {
+            parse_compute_workgroup_size(&HashMap::from([("workgroupSize".to_string(), v)]))
+        };
+        assert_eq!(parse(json!({"x": 16, "y": 8})).unwrap(), [16, 8]);
+        assert_eq!(parse(json!([4, 2])).unwrap(), [4, 2]);
+        assert_eq!(parse(json!(8)).unwrap(), [8, 8]);
+        assert!(parse(json!({"x": 32, "y": 32})).is_err());
+        assert!(parse(json!(0)).is_err());
+        assert_eq!(
+            parse_compute_workgroup_size(&HashMap::new()).unwrap(),
+            [8, 8]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! ConvolutionPass assembler.
+//!
+//! Applies the WxH kernel authored in a connected `Kernel` node to a source pass.
+//! Rank-1 kernels (box, Gaussian, Sobel, ...) run as a horizontal + vertical pair
+//! with `w + h` taps per pixel; anything else runs a single generic `w * h` loop.
+//! Texels are fetched at exact centers through a nearest sampler, so results match
+//! a CPU convolution with clamp-to-edge addressing.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow, bail};
+use rust_wgpu_fiber::{
+    ResourceName,
+    eframe::wgpu::{self, BlendState, Color},
+};
+
+use crate::{
+    dsl::{Node, SceneDSL, find_node, incoming_connection},
+    renderer::{
+        camera::legacy_projection_camera_matrix,
+        render_plan::{parse_kernel_source_js_like, separable_kernel_factors},
+        types::{Kernel2D, PassOutputSpec},
+        utils::{cpu_num_f32, fmt_f32},
+        wgsl::build_fullscreen_textured_bundle,
+    },
+};
+
+use super::super::pass_spec::{
+    PassTextureBinding, RenderPassSpec, SamplerKind, TextureDecl, make_params,
+};
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::push_output_blits;
+
+/// Resolve the kernel from the node connected to `ConvolutionPass.kernel`.
+pub(crate) fn convolution_kernel(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    layer_id: &str,
+) -> Result<Kernel2D> {
+    let kernel_conn = incoming_connection(scene, layer_id, "kernel")
+        .ok_or_else(|| anyhow!("ConvolutionPass.kernel missing for {layer_id}"))?;
+    let kernel_node = find_node(nodes_by_id, &kernel_conn.from.node_id)?;
+    if kernel_node.node_type != "Kernel" {
+        bail!(
+            "ConvolutionPass.kernel must come from Kernel node, got {} for {}",
+            kernel_node.node_type,
+            kernel_node.id
+        );
+    }
+    let kernel_src = kernel_node
+        .params
+        .get("source")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    parse_kernel_source_js_like(kernel_src)
+        .with_context(|| format!("ConvolutionPass {layer_id}: invalid kernel"))
+}
+
+/// Output scale and bias applied after the last convolution stage.
+pub(crate) fn convolution_scale_and_bias(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    kernel: &Kernel2D,
+) -> Result<(f32, f32)> {
+    let normalize = node
+        .params
+        .get("normalize")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let sum: f32 = kernel.values.iter().sum();
+    let scale = if normalize && sum.abs() > 1e-6 {
+        1.0 / sum
+    } else {
+        1.0
+    };
+    let bias = cpu_num_f32(scene, nodes_by_id, node, "bias", 0.0)?;
+    Ok((scale, bias))
+}
+
+fn wgsl_f32_array(values: &[f32]) -> String {
+    let elems: Vec<String> = values.iter().map(|v| fmt_f32(*v)).collect();
+    format!("array<f32, {}>({})", values.len(), elems.join(", "))
+}
+
+fn finish_body(scale: f32, bias: f32) -> String {
+    format!(
+        " let out = sum * {scale};\n return vec4f(out.rgb + vec3f({bias}), out.a);\n",
+        scale = fmt_f32(scale),
+        bias = fmt_f32(bias),
+    )
+}
+
+/// Single-pass `w * h` convolution.
+pub(crate) fn build_convolution_wgsl(kernel: &Kernel2D, scale: f32, bias: f32) -> String {
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let center = floor(in.uv * dims) + vec2f(0.5);
+ let k = {kernel_arr};
+ var sum = vec4f(0.0);
+ for (var y: i32 = 0; y < {h}; y = y + 1) {{
+     for (var x: i32 = 0; x < {w}; x = x + 1) {{
+         let offset = vec2f(f32(x - {half_w}), f32(y - {half_h}));
+         let uv = (center + offset) / dims;
+         sum = sum + textureSampleLevel(src_tex, src_samp, uv, 0.0) * k[u32(y * {w} + x)];
+     }}
+ }}
+{finish}"#,
+        kernel_arr = wgsl_f32_array(&kernel.values),
+        w = kernel.width,
+        h = kernel.height,
+        half_w = kernel.width / 2,
+        half_h = kernel.height / 2,
+        finish = finish_body(scale, bias),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// One axis of a separable convolution. `finish` applies scale and bias.
+pub(crate) fn build_convolution_1d_wgsl(
+    weights: &[f32],
+    horizontal: bool,
+    finish: Option<(f32, f32)>,
+) -> String {
+    let axis = if horizontal {
+        "vec2f(f32(i - HALF), 0.0)"
+    } else {
+        "vec2f(0.0, f32(i - HALF))"
+    }
+    .replace("HALF", &(weights.len() / 2).to_string());
+    let tail = match finish {
+        Some((scale, bias)) => finish_body(scale, bias),
+        None => " return sum;\n".to_string(),
+    };
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let center = floor(in.uv * dims) + vec2f(0.5);
+ let k = {weights_arr};
+ var sum = vec4f(0.0);
+ for (var i: i32 = 0; i < {n}; i = i + 1) {{
+     let uv = (center + {axis}) / dims;
+     sum = sum + textureSampleLevel(src_tex, src_samp, uv, 0.0) * k[u32(i)];
+ }}
+{tail}"#,
+        weights_arr = wgsl_f32_array(weights),
+        n = weights.len(),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Shader stages for `kernel`, in execution order: `["h", "v"]` when the kernel is
+/// rank-1 with both dimensions above one, otherwise a single `"conv"` stage.
+pub(crate) fn build_convolution_stages(
+    kernel: &Kernel2D,
+    scale: f32,
+    bias: f32,
+) -> Vec<(&'static str, String)> {
+    if kernel.width > 1
+        && kernel.height > 1
+        && let Some((column, row)) = separable_kernel_factors(kernel)
+    {
+        return vec![
+            ("h", build_convolution_1d_wgsl(&row, true, None)),
+            (
+                "v",
+                build_convolution_1d_wgsl(&column, false, Some((scale, bias))),
+            ),
+        ];
+    }
+    vec![("conv", build_convolution_wgsl(kernel, scale, bias))]
+}
+
+/// Assemble a `"ConvolutionPass"` layer.
+pub(crate) fn assemble_convolution_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let scene = sc.scene();
+    let nodes_by_id = sc.nodes_by_id();
+
+    let src_conn = incoming_connection(scene, layer_id, "pass")
+        .ok_or_else(|| anyhow!("ConvolutionPass.pass missing for {layer_id}"))?;
+    let source = bs
+        .pass_output_registry
+        .get_for_port(&src_conn.from.node_id, &src_conn.from.port_id)
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "ConvolutionPass {layer_id}: upstream output {}.{} is not registered yet",
+                src_conn.from.node_id,
+                src_conn.from.port_id
+            )
+        })?;
+    let kernel = convolution_kernel(scene, nodes_by_id, layer_id)?;
+    let (scale, bias) = convolution_scale_and_bias(scene, nodes_by_id, layer_node, &kernel)?;
+
+    let [out_w, out_h] = source.resolution;
+    let out_size = [out_w as f32, out_h as f32];
+    let geo: ResourceName = format!("sys.conv.{layer_id}.geo").into();
+    bs.push_fullscreen_geometry(geo.clone(), out_size[0], out_size[1]);
+    let out_tex: ResourceName = format!("sys.conv.{layer_id}.out").into();
+
+    let mut input_texture = source.texture_name.clone();
+    let stages = build_convolution_stages(&kernel, scale, bias);
+    let last_stage = stages.len() - 1;
+    for (index, (stage, shader_wgsl)) in stages.into_iter().enumerate() {
+        let target_texture: ResourceName = if index == last_stage {
+            out_tex.clone()
+        } else {
+            format!("sys.conv.{layer_id}.{stage}").into()
+        };
+        bs.textures.push(TextureDecl {
+            name: target_texture.clone(),
+            size: [out_w, out_h],
+            format: bs.sampled_pass_format,
+            sample_count: 1,
+            needs_sampling: true,
+        });
+
+        let pass_name: ResourceName = format!("sys.conv.{layer_id}.{stage}.pass").into();
+        bs.render_pass_specs.push(RenderPassSpec {
+            pass_id: pass_name.as_str().to_string(),
+            name: pass_name.clone(),
+            geometry_buffer: geo.clone(),
+            instance_buffer: None,
+            normals_buffer: None,
+            vertex_layout: Default::default(),
+            target_texture: target_texture.clone(),
+            resolve_target: None,
+            params_buffer: format!("params.sys.conv.{layer_id}.{stage}").into(),
+            baked_data_parse_buffer: None,
+            params: make_params(
+                out_size,
+                out_size,
+                [out_size[0] * 0.5, out_size[1] * 0.5],
+                legacy_projection_camera_matrix(out_size),
+                [0.0, 0.0, 0.0, 0.0],
+            ),
+            graph_binding: None,
+            graph_values: None,
+            shader_wgsl,
+            texture_bindings: vec![PassTextureBinding {
+                texture: input_texture,
+                image_node_id: None,
+            }],
+            sampler_kinds: vec![SamplerKind::NearestClamp],
+            blend_state: BlendState::REPLACE,
+            color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+            sample_count: 1,
+        });
+        bs.composite_passes.push(pass_name);
+        input_texture = target_texture;
+    }
+
+    bs.pass_output_registry.register(PassOutputSpec {
+        endpoint: crate::renderer::types::OutputEndpoint::new(layer_id, "pass"),
+        texture_name: out_tex.clone(),
+        resolution: [out_w, out_h],
+        format: bs.sampled_pass_format,
+    });
+
+    push_output_blits(sc, bs, layer_id, layer_node, "conv", &out_tex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_convolution_stages;
+    use crate::renderer::render_plan::separable_kernel_factors;
+    use crate::renderer::types::Kernel2D;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    fn kernel(width: u32, height: u32, values: &[f32]) -> Kernel2D {
+        Kernel2D {
+            width,
+            height,
+            values: values.to_vec(),
+        }
+    }
+
+    #[test]
+    fn rank_one_kernels_factor_into_column_and_row() {
+        let sobel_x = kernel(3, 3, &[-1.0, 0.0, 1.0, -2.0, 0.0, 2.0, -1.0, 0.0, 1.0]);
+        let (column, row) = separable_kernel_factors(&sobel_x).expect("sobel is separable");
+        for y in 0..3 {
+            for x in 0..3 {
+                assert!((column[y] * row[x] - sobel_x.values[y * 3 + x]).abs() < 1e-6);
+            }
+        }
+
+        let sharpen = kernel(3, 3, &[0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0]);
+        assert!(separable_kernel_factors(&sharpen).is_none());
+        assert!(separable_kernel_factors(&kernel(2, 1, &[0.0, 0.0])).is_none());
+    }
+
+    #[test]
+    fn convolution_stages_pick_separable_fast_path_and_validate() {
+        let box3 = kernel(3, 3, &[1.0; 9]);
+        let emboss = kernel(3, 3, &[-2.0, -1.0, 0.0, -1.0, 1.0, 1.0, 0.0, 1.0, 2.0]);
+        let cases = [(box3, vec!["h", "v"]), (emboss, vec!["conv"])];
+        for (k, expected) in cases {
+            let stages = build_convolution_stages(&k, 1.0 / 9.0, 0.5);
+            let names: Vec<&str> = stages.iter().map(|(name, _)| *name).collect();
+            assert_eq!(names, expected);
+            for (_, wgsl) in &stages {
+                validate_wgsl_module(wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+            }
+            assert!(stages.last().unwrap().1.contains("vec3f(0.5)"));
+        }
+    }
+}
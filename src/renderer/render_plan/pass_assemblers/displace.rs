@@ -0,0 +1,161 @@
+//! Displace assembler.
+//!
+//! Offsets the `pass` input's UVs by a vector map read from the `map` input:
+//! red drives x, green drives y (+y is down), and `midpoint` is the neutral value.
+//! The map is premultiplied like every other pass, so transparent map pixels leave
+//! the source undisplaced. Edge handling picks the source sampler's address mode,
+//! or masks out-of-frame samples for `transparent`.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::{
+    dsl::{Node, SceneDSL},
+    renderer::{
+        utils::{cpu_num_f32, fmt_f32},
+        wgsl::{build_fullscreen_textured_bundle, with_extra_fullscreen_texture},
+    },
+};
+
+use super::super::pass_spec::SamplerKind;
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::{FullscreenFilterOptions, assemble_fullscreen_filter_with};
+
+/// How displaced lookups outside the source frame are resolved (`Displace.edge`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum DisplaceEdgeMode {
+    #[default]
+    Clamp,
+    Mirror,
+    Repeat,
+    Transparent,
+}
+
+impl DisplaceEdgeMode {
+    fn from_params(node: &Node) -> Result<Self> {
+        let Some(raw) = node.params.get("edge") else {
+            return Ok(Self::default());
+        };
+        let name = raw
+            .as_str()
+            .ok_or_else(|| anyhow!("edge must be a string, got {raw}"))?;
+        Ok(match name.trim().to_ascii_lowercase().as_str() {
+            "" | "clamp" => Self::Clamp,
+            "mirror" => Self::Mirror,
+            "repeat" | "wrap" => Self::Repeat,
+            "transparent" | "none" => Self::Transparent,
+            other => bail!("unknown edge '{other}', expected clamp, mirror, repeat or transparent"),
+        })
+    }
+
+    pub(crate) fn source_sampler(self) -> SamplerKind {
+        match self {
+            Self::Clamp | Self::Transparent => SamplerKind::LinearClamp,
+            Self::Mirror => SamplerKind::LinearMirror,
+            Self::Repeat => SamplerKind::LinearRepeat,
+        }
+    }
+}
+
+/// Resolved Displace params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DisplaceSettings {
+    /// Offset in source pixels for a map channel one unit away from `midpoint`.
+    pub scale_px: f32,
+    /// Map value that means "no offset"; 0.5 for the usual encoded vector maps.
+    pub midpoint: f32,
+    pub edge: DisplaceEdgeMode,
+}
+
+pub(crate) fn displace_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<DisplaceSettings> {
+    Ok(DisplaceSettings {
+        scale_px: cpu_num_f32(scene, nodes_by_id, node, "scale", 20.0)?,
+        midpoint: cpu_num_f32(scene, nodes_by_id, node, "midpoint", 0.5)?,
+        edge: DisplaceEdgeMode::from_params(node)?,
+    })
+}
+
+pub(crate) fn build_displace_wgsl(settings: &DisplaceSettings) -> String {
+    // `map.rg - midpoint * map.a` is the premultiplied form of `(rg - midpoint) * a`.
+    let sample = match settings.edge {
+        DisplaceEdgeMode::Transparent => {
+            r#" let inside = f32(all(uv >= vec2f(0.0)) && all(uv <= vec2f(1.0)));
+ return textureSampleLevel(src_tex, src_samp, uv, 0.0) * inside;
+"#
+        }
+        _ => " return textureSampleLevel(src_tex, src_samp, uv, 0.0);\n",
+    };
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let map = textureSampleLevel(map_tex, map_samp, in.uv, 0.0);
+ let offset = (map.rg - vec2f({midpoint}) * map.a) * {scale};
+ let uv = in.uv + offset / dims;
+{sample}"#,
+        midpoint = fmt_f32(settings.midpoint),
+        scale = fmt_f32(settings.scale_px),
+    );
+    with_extra_fullscreen_texture(
+        &build_fullscreen_textured_bundle(body).module,
+        "map_tex",
+        "map_samp",
+        2,
+    )
+}
+
+/// Assemble a `"Displace"` layer.
+pub(crate) fn assemble_displace_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = displace_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_displace_wgsl(&settings);
+    let options = FullscreenFilterOptions {
+        source_sampler: Some(settings.edge.source_sampler()),
+        extra_inputs: &["map"],
+        table: None,
+    };
+    assemble_fullscreen_filter_with(
+        sc,
+        bs,
+        layer_id,
+        layer_node,
+        "displace",
+        shader_wgsl,
+        options,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    #[test]
+    fn displace_shaders_validate_for_every_edge_mode() {
+        for edge in [
+            DisplaceEdgeMode::Clamp,
+            DisplaceEdgeMode::Mirror,
+            DisplaceEdgeMode::Repeat,
+            DisplaceEdgeMode::Transparent,
+        ] {
+            let wgsl = build_displace_wgsl(&DisplaceSettings {
+                scale_px: 12.0,
+                midpoint: 0.5,
+                edge,
+            });
+            validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+            assert_eq!(
+                wgsl.contains("let inside"),
+                edge == DisplaceEdgeMode::Transparent
+            );
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! DepthOfFieldPass assembler.
+//!
+//! Three passes: a half-resolution prefilter that stores the source color with its
+//! circle of confusion (CoC) in alpha, a gather pass that approximates bokeh scatter
+//! by accepting each spiral tap whose own CoC reaches the shaded pixel, and a
+//! full-resolution composite that blends the sharp source toward the blurred result.
+//!
+//! Focus comes from the optional `focus` pass input (red channel) or, when that is
+//! unconnected, from the `focusExpression` param evaluated per pixel.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use rust_wgpu_fiber::{
+    ResourceName,
+    eframe::wgpu::{self, BlendState, Color},
+};
+use serde_json::Value;
+
+use crate::{
+    dsl::{Node, SceneDSL, incoming_connection},
+    renderer::{
+        camera::legacy_projection_camera_matrix,
+        types::PassOutputSpec,
+        utils::{cpu_num_f32, cpu_num_f32_min_0},
+        wgsl::{
+            build_fullscreen_textured_bundle, clamp_min_1, fmt_f32, with_extra_fullscreen_texture,
+        },
+    },
+};
+
+use super::super::pass_spec::{
+    PassTextureBinding, RenderPassSpec, SamplerKind, TextureDecl, make_params,
+};
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::push_output_blits;
+
+/// Spiral taps per gather pixel.
+pub(crate) const DOF_GATHER_TAPS: u32 = 48;
+/// Upper bound on `maxCoC`, in full-resolution pixels.
+pub(crate) const DOF_MAX_COC_PX: f32 = 64.0;
+/// Focus value used when neither `focus` nor `focusExpression` is provided.
+const DEFAULT_FOCUS_EXPRESSION: &str = "in.uv.y";
+
+/// CoC settings resolved from a DepthOfFieldPass node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DofSettings {
+    pub focus_distance: f32,
+    pub focus_range: f32,
+    pub coc_scale: f32,
+    /// Largest blur radius in full-resolution pixels.
+    pub max_coc_px: f32,
+}
+
+impl DofSettings {
+    /// Gather radius in half-resolution pixels.
+    pub(crate) fn gather_radius_px(&self) -> f32 {
+        self.max_coc_px * 0.5
+    }
+}
+
+pub(crate) fn dof_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<DofSettings> {
+    Ok(DofSettings {
+        focus_distance: cpu_num_f32(scene, nodes_by_id, node, "focusDistance", 0.5)?,
+        focus_range: cpu_num_f32_min_0(scene, nodes_by_id, node, "focusRange", 0.1)?,
+        coc_scale: cpu_num_f32_min_0(scene, nodes_by_id, node, "cocScale", 4.0)?,
+        max_coc_px: cpu_num_f32(scene, nodes_by_id, node, "maxCoC", 16.0)?
+            .clamp(0.0, DOF_MAX_COC_PX),
+    })
+}
+
+/// Prefilter: `src_tex` is the source pass, `focus_tex` (bindings 2/3) the focus
+/// map when `has_focus_texture`. Writes `vec4(src.rgb, coc)` with coc in 0..1.
+pub(crate) fn build_dof_coc_wgsl(
+    node: &Node,
+    settings: &DofSettings,
+    has_focus_texture: bool,
+) -> String {
+    let focus = if has_focus_texture {
+        "textureSample(focus_tex, focus_samp, in.uv).r".to_string()
+    } else {
+        node.params
+            .get("focusExpression")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(DEFAULT_FOCUS_EXPRESSION)
+            .to_string()
+    };
+    let body = format!(
+        r#"    let c = textureSample(src_tex, src_samp, in.uv);
+    let focus = f32({focus});
+    let coc = clamp((abs(focus - f32({focus_distance})) - f32({focus_range})) * f32({coc_scale}), 0.0, 1.0);
+    return vec4f(c.rgb, coc);"#,
+        focus_distance = fmt_f32(settings.focus_distance),
+        focus_range = fmt_f32(settings.focus_range),
+        coc_scale = fmt_f32(settings.coc_scale),
+    );
+    let module = build_fullscreen_textured_bundle(body).module;
+    if has_focus_texture {
+        with_extra_fullscreen_texture(&module, "focus_tex", "focus_samp", 2)
+    } else {
+        module
+    }
+}
+
+/// Gather: golden-angle spiral over the prefiltered texture. A tap at distance `r`
+/// contributes only if its own CoC radius covers `r`, which lets out-of-focus
+/// neighbours bleed over sharp pixels the way scattered bokeh would.
+pub(crate) fn build_dof_gather_wgsl(settings: &DofSettings) -> String {
+    let body = format!(
+        r#"    let texel = 1.0 / vec2f(textureDimensions(src_tex));
+    let max_radius = f32({radius});
+    let center = textureSampleLevel(src_tex, src_samp, in.uv, 0.0);
+    var acc = vec4f(center.rgb, 1.0);
+    var spread = center.a;
+    for (var i = 1u; i < {taps}u; i = i + 1u) {{
+        let fi = f32(i);
+        let r = sqrt(fi / f32({taps})) * max_radius;
+        let theta = fi * 2.39996323;
+        let offset = vec2f(cos(theta), sin(theta)) * r;
+        let s = textureSampleLevel(src_tex, src_samp, in.uv + offset * texel, 0.0);
+        let w = smoothstep(r - 1.0, r + 1.0, s.a * max_radius);
+        acc = acc + vec4f(s.rgb * w, w);
+        spread = max(spread, s.a * w);
+    }}
+    return vec4f(acc.rgb / acc.a, spread);"#,
+        radius = fmt_f32(settings.gather_radius_px()),
+        taps = DOF_GATHER_TAPS,
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Composite: `src_tex` is the sharp source, `bokeh_tex` (bindings 2/3) the gather
+/// result. Blends fully once the CoC exceeds one half-resolution pixel.
+pub(crate) fn build_dof_composite_wgsl(settings: &DofSettings) -> String {
+    let body = format!(
+        r#"    let sharp = textureSample(src_tex, src_samp, in.uv);
+    let bokeh = textureSample(bokeh_tex, bokeh_samp, in.uv);
+    let t = clamp(bokeh.a * f32({radius}), 0.0, 1.0);
+    return vec4f(mix(sharp.rgb, bokeh.rgb, t), sharp.a);"#,
+        radius = fmt_f32(settings.gather_radius_px()),
+    );
+    let module = build_fullscreen_textured_bundle(body).module;
+    with_extra_fullscreen_texture(&module, "bokeh_tex", "bokeh_samp", 2)
+}
+
+/// Assemble a `"DepthOfFieldPass"` layer.
+pub(crate) fn assemble_dof_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let scene = sc.scene();
+    let nodes_by_id = sc.nodes_by_id();
+
+    let upstream = |port: &str| -> Result<Option<PassOutputSpec>> {
+        let Some(conn) = incoming_connection(scene, layer_id, port) else {
+            return Ok(None);
+        };
+        bs.pass_output_registry
+            .get_for_port(&conn.from.node_id, &conn.from.port_id)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| {
+                anyhow!(
+                    "DepthOfFieldPass {layer_id}: upstream output {}.{} for `{port}` is not registered yet",
+                    conn.from.node_id,
+                    conn.from.port_id
+                )
+            })
+    };
+    let source =
+        upstream("pass")?.ok_or_else(|| anyhow!("DepthOfFieldPass.pass missing for {layer_id}"))?;
+    let focus = upstream("focus")?;
+    let settings = dof_settings(scene, nodes_by_id, layer_node)?;
+
+    let [full_w, full_h] = source.resolution;
+    let half_size_u = [clamp_min_1(full_w / 2), clamp_min_1(full_h / 2)];
+    let full_size = [full_w as f32, full_h as f32];
+    let half_size = [half_size_u[0] as f32, half_size_u[1] as f32];
+
+    let coc_tex: ResourceName = format!("sys.dof.{layer_id}.coc").into();
+    let gather_tex: ResourceName = format!("sys.dof.{layer_id}.gather").into();
+    let out_tex: ResourceName = format!("sys.dof.{layer_id}.out").into();
+    for (name, size) in [
+        (&coc_tex, half_size_u),
+        (&gather_tex, half_size_u),
+        (&out_tex, [full_w, full_h]),
+    ] {
+        bs.textures.push(TextureDecl {
+            name: name.clone(),
+            size,
+            format: bs.sampled_pass_format,
+            sample_count: 1,
+            needs_sampling: true,
+        });
+    }
+
+    let half_geo: ResourceName = format!("sys.dof.{layer_id}.half.geo").into();
+    bs.push_fullscreen_geometry(half_geo.clone(), half_size[0], half_size[1]);
+    let full_geo: ResourceName = format!("sys.dof.{layer_id}.full.geo").into();
+    bs.push_fullscreen_geometry(full_geo.clone(), full_size[0], full_size[1]);
+    let fullscreen_params = |size: [f32; 2]| {
+        make_params(
+            size,
+            size,
+            [size[0] * 0.5, size[1] * 0.5],
+            legacy_projection_camera_matrix(size),
+            [0.0, 0.0, 0.0, 0.0],
+        )
+    };
+
+    let mut coc_bindings = vec![PassTextureBinding {
+        texture: source.texture_name.clone(),
+        image_node_id: None,
+    }];
+    if let Some(focus) = &focus {
+        coc_bindings.push(PassTextureBinding {
+            texture: focus.texture_name.clone(),
+            image_node_id: None,
+        });
+    }
+    let stages = [
+        (
+            "coc",
+            half_geo.clone(),
+            half_size,
+            coc_tex.clone(),
+            build_dof_coc_wgsl(layer_node, &settings, focus.is_some()),
+            coc_bindings,
+        ),
+        (
+            "gather",
+            half_geo,
+            half_size,
+            gather_tex.clone(),
+            build_dof_gather_wgsl(&settings),
+            vec![PassTextureBinding {
+                texture: coc_tex,
+                image_node_id: None,
+            }],
+        ),
+        (
+            "composite",
+            full_geo,
+            full_size,
+            out_tex.clone(),
+            build_dof_composite_wgsl(&settings),
+            vec![
+                PassTextureBinding {
+                    texture: source.texture_name.clone(),
+                    image_node_id: None,
+                },
+                PassTextureBinding {
+                    texture: gather_tex,
+                    image_node_id: None,
+                },
+            ],
+        ),
+    ];
+    for (stage, geometry_buffer, size, target_texture, shader_wgsl, texture_bindings) in stages {
+        let pass_name: ResourceName = format!("sys.dof.{layer_id}.{stage}.pass").into();
+        bs.render_pass_specs.push(RenderPassSpec {
+            pass_id: pass_name.as_str().to_string(),
+            name: pass_name.clone(),
+            geometry_buffer,
+            instance_buffer: None,
+            normals_buffer: None,
+            vertex_layout: Default::default(),
+            target_texture,
+            resolve_target: None,
+            params_buffer: format!("params.sys.dof.{layer_id}.{stage}").into(),
+            baked_data_parse_buffer: None,
+            params: fullscreen_params(size),
+            graph_binding: None,
+            graph_values: None,
+            shader_wgsl,
+            sampler_kinds: vec![SamplerKind::LinearClamp; texture_bindings.len()],
+            texture_bindings,
+            blend_state: BlendState::REPLACE,
+            color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+            sample_count: 1,
+        });
+        bs.composite_passes.push(pass_name);
+    }
+
+    bs.pass_output_registry.register(PassOutputSpec {
+        endpoint: crate::renderer::types::OutputEndpoint::new(layer_id, "pass"),
+        texture_name: out_tex.clone(),
+        resolution: [full_w, full_h],
+        format: bs.sampled_pass_format,
+    });
+
+    push_output_blits(sc, bs, layer_id, layer_node, "dof", &out_tex)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::{DofSettings, build_dof_coc_wgsl, build_dof_composite_wgsl, build_dof_gather_wgsl};
+    use crate::dsl::Node;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    const SETTINGS: DofSettings = DofSettings {
+        focus_distance: 0.5,
+        focus_range: 0.1,
+        coc_scale: 4.0,
+        max_coc_px: 16.0,
+    };
+
+    fn dof_node(params: serde_json::Value) -> Node {
+        Node {
+            id: "dof".to_string(),
+            node_type: "DepthOfFieldPass".to_string(),
+            params: params
+                .as_object()
+                .cloned()
+                .map(|m| m.into_iter().collect())
+                .unwrap_or_else(HashMap::new),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    #[test]
+    fn dof_shaders_validate_with_and_without_focus_texture() {
+        let node = dof_node(json!({ "focusExpression": "distance(in.uv, vec2f(0.5))" }));
+        for has_focus_texture in [true, false] {
+            let wgsl = build_dof_coc_wgsl(&node, &SETTINGS, has_focus_texture);
+            assert_eq!(wgsl.contains("var focus_tex"), has_focus_texture);
+            assert_eq!(wgsl.contains("distance(in.uv"), !has_focus_texture);
+            validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+        }
+        for wgsl in [
+            build_dof_gather_wgsl(&SETTINGS),
+            build_dof_composite_wgsl(&SETTINGS),
+        ] {
+            validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+        }
+    }
+
+    #[test]
+    fn dof_coc_falls_back_to_vertical_focus_expression() {
+        let wgsl = build_dof_coc_wgsl(
+            &dof_node(json!({ "focusExpression": "  " })),
+            &SETTINGS,
+            false,
+        );
+        assert!(wgsl.contains("let focus = f32(in.uv.y);"));
+        assert_eq!(SETTINGS.gather_radius_px(), 8.0);
+    }
+}
@@ -0,0 +1,265 @@
+//! DropShadow assembler.
+//!
+//! Three full-resolution stages over the `pass` input:
+//! `h` samples the source alpha at the shadow offset and blurs it horizontally,
+//! `v` blurs vertically into the shadow mask, and `composite` draws the tinted
+//! mask under the source. Everything is sized from the registered source output,
+//! so the node keeps working when upstream extend padding changes the texture size.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use rust_wgpu_fiber::{
+    ResourceName,
+    eframe::wgpu::{self, BlendState, Color},
+};
+
+use crate::{
+    dsl::{Node, SceneDSL, incoming_connection},
+    renderer::{
+        camera::legacy_projection_camera_matrix,
+        types::PassOutputSpec,
+        utils::{cpu_num_f32, cpu_num_f32_min_0, fmt_f32},
+        wgsl::{build_fullscreen_textured_bundle, with_extra_fullscreen_texture},
+    },
+};
+
+use super::super::pass_spec::{
+    PassTextureBinding, RenderPassSpec, SamplerKind, TextureDecl, make_params,
+};
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::push_output_blits;
+use super::mesh_gradient::parse_color_from_params;
+
+/// Upper bound on the blur radius, in pixels; each blur stage loops `2r + 1` taps.
+pub(crate) const DROP_SHADOW_MAX_BLUR_PX: f32 = 128.0;
+
+/// Resolved DropShadow params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DropShadowSettings {
+    /// Shadow offset in pixels; +x right, +y down.
+    pub offset_px: [f32; 2],
+    /// Gaussian blur radius in pixels (about two sigma).
+    pub blur_px: f32,
+    /// Straight (non-premultiplied) RGBA shadow color; alpha is the shadow opacity.
+    pub color: [f32; 4],
+}
+
+pub(crate) fn drop_shadow_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<DropShadowSettings> {
+    Ok(DropShadowSettings {
+        offset_px: [
+            cpu_num_f32(scene, nodes_by_id, node, "offsetX", 4.0)?,
+            cpu_num_f32(scene, nodes_by_id, node, "offsetY", 4.0)?,
+        ],
+        blur_px: cpu_num_f32_min_0(scene, nodes_by_id, node, "blur", 8.0)?
+            .min(DROP_SHADOW_MAX_BLUR_PX),
+        color: parse_color_from_params(&node.params, "color").unwrap_or([0.0, 0.0, 0.0, 0.5]),
+    })
+}
+
+/// One axis of the alpha blur; `offset_px` shifts the lookup (first stage only).
+pub(crate) fn build_drop_shadow_blur_wgsl(
+    settings: &DropShadowSettings,
+    horizontal: bool,
+    offset_px: [f32; 2],
+) -> String {
+    let radius = settings.blur_px.ceil() as i32;
+    let sigma = (settings.blur_px * 0.5).max(1e-3);
+    let axis = if horizontal {
+        "vec2f(1.0, 0.0)"
+    } else {
+        "vec2f(0.0, 1.0)"
+    };
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let origin = in.uv - vec2f({ox}, {oy}) / dims;
+ var sum = 0.0;
+ var weight_sum = 0.0;
+ for (var i: i32 = -{radius}; i <= {radius}; i = i + 1) {{
+     let w = exp(-f32(i * i) / {two_sigma_sq});
+     let uv = origin + {axis} * f32(i) / dims;
+     let inside = f32(all(uv >= vec2f(0.0)) && all(uv <= vec2f(1.0)));
+     sum = sum + w * textureSampleLevel(src_tex, src_samp, uv, 0.0).a * inside;
+     weight_sum = weight_sum + w;
+ }}
+ return vec4f(sum / weight_sum);
+"#,
+        ox = fmt_f32(offset_px[0]),
+        oy = fmt_f32(offset_px[1]),
+        two_sigma_sq = fmt_f32(2.0 * sigma * sigma),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Draw the tinted shadow mask (`shadow_tex`) under the source (`src_tex`).
+pub(crate) fn build_drop_shadow_composite_wgsl(settings: &DropShadowSettings) -> String {
+    let [r, g, b, a] = settings.color;
+    let body = format!(
+        r#"
+ let src = textureSample(src_tex, src_samp, in.uv);
+ let mask = textureSample(shadow_tex, shadow_samp, in.uv).a;
+ let color = vec4f({r}, {g}, {b}, {a});
+ let shadow = vec4f(color.rgb * color.a, color.a) * mask;
+ return src + shadow * (1.0 - src.a);
+"#,
+        r = fmt_f32(r),
+        g = fmt_f32(g),
+        b = fmt_f32(b),
+        a = fmt_f32(a.clamp(0.0, 1.0)),
+    );
+    with_extra_fullscreen_texture(
+        &build_fullscreen_textured_bundle(body).module,
+        "shadow_tex",
+        "shadow_samp",
+        2,
+    )
+}
+
+/// Assemble a `"DropShadow"` layer.
+pub(crate) fn assemble_drop_shadow_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let src_conn = incoming_connection(sc.scene(), layer_id, "pass")
+        .ok_or_else(|| anyhow!("DropShadow.pass missing for {layer_id}"))?;
+    let source = bs
+        .pass_output_registry
+        .get_for_port(&src_conn.from.node_id, &src_conn.from.port_id)
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "DropShadow {layer_id}: upstream output {}.{} is not registered yet",
+                src_conn.from.node_id,
+                src_conn.from.port_id
+            )
+        })?;
+    let settings = drop_shadow_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+
+    let [out_w, out_h] = source.resolution;
+    let size = [out_w as f32, out_h as f32];
+    let h_tex: ResourceName = format!("sys.shadow.{layer_id}.h").into();
+    let mask_tex: ResourceName = format!("sys.shadow.{layer_id}.mask").into();
+    let out_tex: ResourceName = format!("sys.shadow.{layer_id}.out").into();
+    for name in [&h_tex, &mask_tex, &out_tex] {
+        bs.textures.push(TextureDecl {
+            name: name.clone(),
+            size: [out_w, out_h],
+            format: bs.sampled_pass_format,
+            sample_count: 1,
+            needs_sampling: true,
+        });
+    }
+    let geo: ResourceName = format!("sys.shadow.{layer_id}.geo").into();
+    bs.push_fullscreen_geometry(geo.clone(), size[0], size[1]);
+
+    let stages = [
+        (
+            "h",
+            h_tex.clone(),
+            build_drop_shadow_blur_wgsl(&settings, true, settings.offset_px),
+            vec![source.texture_name.clone()],
+        ),
+        (
+            "v",
+            mask_tex.clone(),
+            build_drop_shadow_blur_wgsl(&settings, false, [0.0, 0.0]),
+            vec![h_tex],
+        ),
+        (
+            "composite",
+            out_tex.clone(),
+            build_drop_shadow_composite_wgsl(&settings),
+            vec![source.texture_name.clone(), mask_tex],
+        ),
+    ];
+    for (stage, target_texture, shader_wgsl, inputs) in stages {
+        let pass_name: ResourceName = format!("sys.shadow.{layer_id}.{stage}.pass").into();
+        bs.render_pass_specs.push(RenderPassSpec {
+            pass_id: pass_name.as_str().to_string(),
+            name: pass_name.clone(),
+            geometry_buffer: geo.clone(),
+            instance_buffer: None,
+            normals_buffer: None,
+            vertex_layout: Default::default(),
+            target_texture,
+            resolve_target: None,
+            params_buffer: format!("params.sys.shadow.{layer_id}.{stage}").into(),
+            baked_data_parse_buffer: None,
+            params: make_params(
+                size,
+                size,
+                [size[0] * 0.5, size[1] * 0.5],
+                legacy_projection_camera_matrix(size),
+                [0.0, 0.0, 0.0, 0.0],
+            ),
+            graph_binding: None,
+            graph_values: None,
+            shader_wgsl,
+            sampler_kinds: vec![SamplerKind::LinearClamp; inputs.len()],
+            texture_bindings: inputs
+                .into_iter()
+                .map(|texture| PassTextureBinding {
+                    texture,
+                    image_node_id: None,
+                })
+                .collect(),
+            blend_state: BlendState::REPLACE,
+            color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+            sample_count: 1,
+        });
+        bs.composite_passes.push(pass_name);
+    }
+
+    bs.pass_output_registry.register(PassOutputSpec {
+        endpoint: crate::renderer::types::OutputEndpoint::new(layer_id, "pass"),
+        texture_name: out_tex.clone(),
+        resolution: [out_w, out_h],
+        format: bs.sampled_pass_format,
+    });
+
+    push_output_blits(sc, bs, layer_id, layer_node, "shadow", &out_tex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    #[test]
+    fn drop_shadow_shaders_validate() {
+        let settings = DropShadowSettings {
+            offset_px: [6.0, -3.0],
+            blur_px: 5.5,
+            color: [0.0, 0.0, 0.2, 0.6],
+        };
+        let modules = [
+            build_drop_shadow_blur_wgsl(&settings, true, settings.offset_px),
+            build_drop_shadow_blur_wgsl(&settings, false, [0.0, 0.0]),
+            build_drop_shadow_composite_wgsl(&settings),
+        ];
+        for wgsl in &modules {
+            validate_wgsl_module(wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+        }
+        assert!(modules[0].contains("i <= 6;"));
+        assert!(modules[2].contains("var shadow_tex: texture_2d<f32>;"));
+    }
+
+    #[test]
+    fn zero_blur_is_a_single_tap() {
+        let settings = DropShadowSettings {
+            offset_px: [0.0, 0.0],
+            blur_px: 0.0,
+            color: [0.0, 0.0, 0.0, 1.0],
+        };
+        let wgsl = build_drop_shadow_blur_wgsl(&settings, true, settings.offset_px);
+        assert!(wgsl.contains("var i: i32 = -0; i <= 0;"));
+        validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+    }
+}
@@ -0,0 +1,241 @@
+//! EdgeDetect assembler.
+//!
+//! Runs a 3x3 gradient operator over the luma and alpha of a source pass. Taps are
+//! spaced `thickness` pixels apart, so larger values draw wider lines. In `mask`
+//! mode the pass outputs the edge strength as a white premultiplied mask; in
+//! `outline` mode the edge is tinted with `outlineColor` and composited over the
+//! source.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::Value;
+
+use crate::{
+    dsl::{Node, SceneDSL},
+    renderer::{
+        utils::{cpu_num_f32, cpu_num_f32_min_0, fmt_f32},
+        wgsl::build_fullscreen_textured_bundle,
+    },
+};
+
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::assemble_fullscreen_filter;
+use super::mesh_gradient::parse_color_from_params;
+
+/// Width of the smoothstep ramp above `threshold`, in normalized gradient units.
+const EDGE_SOFTNESS: f32 = 0.05;
+
+/// Gradient operator selected by `EdgeDetect.operator`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum EdgeOperator {
+    #[default]
+    Sobel,
+    Prewitt,
+    Laplacian,
+}
+
+/// What `EdgeDetect` writes, selected by `EdgeDetect.mode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum EdgeOutputMode {
+    #[default]
+    Mask,
+    Outline,
+}
+
+fn string_param<'a>(params: &'a HashMap<String, Value>, key: &str) -> Result<Option<&'a str>> {
+    let Some(raw) = params.get(key) else {
+        return Ok(None);
+    };
+    raw.as_str()
+        .map(Some)
+        .ok_or_else(|| anyhow!("{key} must be a string, got {raw}"))
+}
+
+impl EdgeOperator {
+    pub(crate) fn from_params(params: &HashMap<String, Value>) -> Result<Self> {
+        let Some(name) = string_param(params, "operator")? else {
+            return Ok(Self::default());
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "sobel" => Ok(Self::Sobel),
+            "prewitt" => Ok(Self::Prewitt),
+            "laplacian" => Ok(Self::Laplacian),
+            other => bail!("unknown operator '{other}', expected sobel, prewitt, or laplacian"),
+        }
+    }
+}
+
+impl EdgeOutputMode {
+    pub(crate) fn from_params(params: &HashMap<String, Value>) -> Result<Self> {
+        let Some(name) = string_param(params, "mode")? else {
+            return Ok(Self::default());
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "mask" => Ok(Self::Mask),
+            "outline" | "outlinecolor" => Ok(Self::Outline),
+            other => bail!("unknown mode '{other}', expected mask or outline"),
+        }
+    }
+}
+
+/// Resolved EdgeDetect params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct EdgeDetectSettings {
+    pub operator: EdgeOperator,
+    pub mode: EdgeOutputMode,
+    /// Tap spacing in pixels (>= 1).
+    pub thickness_px: f32,
+    /// Normalized gradient magnitude where the edge starts to appear.
+    pub threshold: f32,
+    /// Straight (non-premultiplied) RGBA outline color.
+    pub outline_color: [f32; 4],
+}
+
+pub(crate) fn edge_detect_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<EdgeDetectSettings> {
+    Ok(EdgeDetectSettings {
+        operator: EdgeOperator::from_params(&node.params)?,
+        mode: EdgeOutputMode::from_params(&node.params)?,
+        thickness_px: cpu_num_f32(scene, nodes_by_id, node, "thickness", 1.0)?.max(1.0),
+        threshold: cpu_num_f32_min_0(scene, nodes_by_id, node, "threshold", 0.1)?,
+        outline_color: parse_color_from_params(&node.params, "outlineColor")
+            .unwrap_or([0.0, 0.0, 0.0, 1.0]),
+    })
+}
+
+/// Build the EdgeDetect fragment module. `src_tex` is the `pass` input.
+pub(crate) fn build_edge_detect_wgsl(settings: &EdgeDetectSettings) -> String {
+    // Taps are laid out row-major, `s[0]` top-left to `s[8]` bottom-right; each
+    // holds (luma, alpha). Magnitudes are scaled so a hard 0->1 step reads as 1.
+    let magnitude = match settings.operator {
+        EdgeOperator::Sobel | EdgeOperator::Prewitt => {
+            let (w, norm) = if settings.operator == EdgeOperator::Sobel {
+                ("2.0", "4.0")
+            } else {
+                ("1.0", "3.0")
+            };
+            format!(
+                r#"
+ let gx = (s[2] + {w} * s[5] + s[8]) - (s[0] + {w} * s[3] + s[6]);
+ let gy = (s[6] + {w} * s[7] + s[8]) - (s[0] + {w} * s[1] + s[2]);
+ let mag = max(length(vec2f(gx.x, gy.x)), length(vec2f(gx.y, gy.y))) / {norm};"#
+            )
+        }
+        EdgeOperator::Laplacian => r#"
+ let lap = s[1] + s[3] + s[5] + s[7] - 4.0 * s[4];
+ let mag = max(abs(lap.x), abs(lap.y));"#
+            .to_string(),
+    };
+    let output = match settings.mode {
+        EdgeOutputMode::Mask => " return vec4f(edge);".to_string(),
+        EdgeOutputMode::Outline => {
+            let [r, g, b, a] = settings.outline_color;
+            format!(
+                r#"
+ let color = vec4f({r}, {g}, {b}, {a});
+ let outline = vec4f(color.rgb * color.a, color.a) * edge;
+ return outline + src * (1.0 - outline.a);"#,
+                r = fmt_f32(r),
+                g = fmt_f32(g),
+                b = fmt_f32(b),
+                a = fmt_f32(a.clamp(0.0, 1.0)),
+            )
+        }
+    };
+    let body = format!(
+        r#"
+ let src = textureSample(src_tex, src_samp, in.uv);
+ let texel = {thickness} / vec2f(textureDimensions(src_tex));
+ var s: array<vec2f, 9>;
+ for (var y: i32 = -1; y <= 1; y = y + 1) {{
+     for (var x: i32 = -1; x <= 1; x = x + 1) {{
+         let c = textureSampleLevel(src_tex, src_samp, in.uv + vec2f(f32(x), f32(y)) * texel, 0.0);
+         s[u32((y + 1) * 3 + x + 1)] = vec2f(dot(c.rgb, vec3f(0.2126, 0.7152, 0.0722)), c.a);
+     }}
+ }}{magnitude}
+ let edge = smoothstep({threshold}, {threshold_hi}, mag);
+{output}
+"#,
+        thickness = fmt_f32(settings.thickness_px),
+        threshold = fmt_f32(settings.threshold),
+        threshold_hi = fmt_f32(settings.threshold + EDGE_SOFTNESS),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Assemble an `"EdgeDetect"` layer.
+pub(crate) fn assemble_edge_detect_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = edge_detect_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_edge_detect_wgsl(&settings);
+    assemble_fullscreen_filter(sc, bs, layer_id, layer_node, "edge", shader_wgsl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::validation::validate_wgsl_module;
+    use serde_json::json;
+
+    #[test]
+    fn edge_detect_params_parse_with_defaults_and_reject_unknown_names() {
+        let empty = HashMap::new();
+        assert_eq!(
+            EdgeOperator::from_params(&empty).unwrap(),
+            EdgeOperator::Sobel
+        );
+        assert_eq!(
+            EdgeOutputMode::from_params(&empty).unwrap(),
+            EdgeOutputMode::Mask
+        );
+
+        let params = HashMap::from([
+            ("operator".to_string(), json!("Laplacian")),
+            ("mode".to_string(), json!("outline")),
+        ]);
+        assert_eq!(
+            EdgeOperator::from_params(&params).unwrap(),
+            EdgeOperator::Laplacian
+        );
+        assert_eq!(
+            EdgeOutputMode::from_params(&params).unwrap(),
+            EdgeOutputMode::Outline
+        );
+
+        let bad = HashMap::from([("operator".to_string(), json!("canny"))]);
+        assert!(EdgeOperator::from_params(&bad).is_err());
+        let bad = HashMap::from([("mode".to_string(), json!(3))]);
+        assert!(EdgeOutputMode::from_params(&bad).is_err());
+    }
+
+    #[test]
+    fn edge_detect_shaders_validate_for_every_operator_and_mode() {
+        for operator in [
+            EdgeOperator::Sobel,
+            EdgeOperator::Prewitt,
+            EdgeOperator::Laplacian,
+        ] {
+            for mode in [EdgeOutputMode::Mask, EdgeOutputMode::Outline] {
+                let settings = EdgeDetectSettings {
+                    operator,
+                    mode,
+                    thickness_px: 2.0,
+                    threshold: 0.25,
+                    outline_color: [1.0, 0.5, 0.0, 1.0],
+                };
+                let wgsl = build_edge_detect_wgsl(&settings);
+                validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+                assert!(wgsl.contains("smoothstep(0.25, 0.3"));
+                assert_eq!(wgsl.contains("outline"), mode == EdgeOutputMode::Outline);
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! FeedbackPass assembler.
+//!
+//! A feedback pass owns two textures: `cur`, written every frame and exposed as
+//! the node's `pass` output, and `prev`, holding the previous frame. Pipelines
+//! and bind groups are static, so instead of swapping bindings the pass is
+//! followed by a history blit that copies `cur` into `prev` for the next frame.
+
+use anyhow::{Result, anyhow};
+use rust_wgpu_fiber::{
+    ResourceName,
+    eframe::wgpu::{self, BlendState, Color},
+};
+use serde_json::Value;
+
+use crate::{
+    dsl::{Node, incoming_connection},
+    renderer::{
+        camera::legacy_projection_camera_matrix,
+        types::PassOutputSpec,
+        utils::cpu_num_f32,
+        wgsl::{build_fullscreen_textured_bundle, fmt_f32, with_extra_fullscreen_texture},
+    },
+};
+
+use super::super::pass_spec::{
+    PassTextureBinding, RenderPassSpec, SamplerKind, TextureDecl, make_params,
+};
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::push_output_blits;
+
+pub(crate) const DEFAULT_DECAY: f32 = 0.95;
+
+/// Build the FeedbackPass fragment module.
+///
+/// `src_tex` is the `pass` input (the previous frame when unconnected) and
+/// `prev_tex` the previous frame; `decay` is in scope for `source` bodies.
+pub(crate) fn build_feedback_wgsl(node: &Node, decay: f32, has_input: bool) -> String {
+    let source = node
+        .params
+        .get("source")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let body = match source {
+        Some(source) => source.to_string(),
+        None if has_input => r#"    let prev = textureSample(prev_tex, prev_samp, in.uv) * decay;
+    let src = textureSample(src_tex, src_samp, in.uv);
+    return src + prev * (1.0 - src.a);"#
+            .to_string(),
+        None => "    return textureSample(prev_tex, prev_samp, in.uv) * decay;".to_string(),
+    };
+    let fragment_body = format!("    let decay = f32({});\n{body}", fmt_f32(decay));
+
+    let module = build_fullscreen_textured_bundle(fragment_body).module;
+    with_extra_fullscreen_texture(&module, "prev_tex", "prev_samp", 2)
+}
+
+/// Assemble a `"FeedbackPass"` layer.
+pub(crate) fn assemble_feedback_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let scene = sc.scene();
+    let nodes_by_id = sc.nodes_by_id();
+
+    let decay = cpu_num_f32(scene, nodes_by_id, layer_node, "decay", DEFAULT_DECAY)?.max(0.0);
+    let input_texture = match incoming_connection(scene, layer_id, "pass") {
+        Some(conn) => Some(
+            bs.pass_output_registry
+                .get_for_port(&conn.from.node_id, &conn.from.port_id)
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "FeedbackPass {layer_id}: upstream output {}.{} is not registered yet",
+                        conn.from.node_id,
+                        conn.from.port_id
+                    )
+                })?,
+        ),
+        None => None,
+    };
+    let [out_w, out_h] = input_texture
+        .as_ref()
+        .map(|spec| spec.resolution)
+        .unwrap_or(bs.tgt_size_u);
+    let out_size = [out_w as f32, out_h as f32];
+    let center = [out_size[0] * 0.5, out_size[1] * 0.5];
+
+    let cur_tex: ResourceName = format!("sys.feedback.{layer_id}.cur").into();
+    let prev_tex: ResourceName = format!("sys.feedback.{layer_id}.prev").into();
+    for name in [&cur_tex, &prev_tex] {
+        bs.textures.push(TextureDecl {
+            name: name.clone(),
+            size: [out_w, out_h],
+            format: bs.sampled_pass_format,
+            sample_count: 1,
+            needs_sampling: true,
+        });
+    }
+
+    let geo: ResourceName = format!("sys.feedback.{layer_id}.geo").into();
+    bs.push_fullscreen_geometry(geo.clone(), out_size[0], out_size[1]);
+    let params = make_params(
+        out_size,
+        out_size,
+        center,
+        legacy_projection_camera_matrix(out_size),
+        [0.0, 0.0, 0.0, 0.0],
+    );
+
+    let src_binding = input_texture
+        .as_ref()
+        .map(|spec| spec.texture_name.clone())
+        .unwrap_or_else(|| prev_tex.clone());
+    let pass_name: ResourceName = format!("sys.feedback.{layer_id}.pass").into();
+    bs.render_pass_specs.push(RenderPassSpec {
+        pass_id: pass_name.as_str().to_string(),
+        name: pass_name.clone(),
+        geometry_buffer: geo.clone(),
+        instance_buffer: None,
+        normals_buffer: None,
+        vertex_layout: Default::default(),
+        target_texture: cur_tex.clone(),
+        resolve_target: None,
+        params_buffer: format!("params.sys.feedback.{layer_id}").into(),
+        baked_data_parse_buffer: None,
+        params,
+        graph_binding: None,
+        graph_values: None,
+        shader_wgsl: build_feedback_wgsl(layer_node, decay, input_texture.is_some()),
+        texture_bindings: vec![
+            PassTextureBinding {
+                texture: src_binding,
+                image_node_id: None,
+            },
+            PassTextureBinding {
+                texture: prev_tex.clone(),
+                image_node_id: None,
+            },
+        ],
+        sampler_kinds: vec![SamplerKind::LinearClamp, SamplerKind::LinearClamp],
+        blend_state: BlendState::REPLACE,
+        color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+        sample_count: 1,
+    });
+    bs.composite_passes.push(pass_name);
+
+    // Only this pass reads `prev`, so the history copy can run right away.
+    let history_pass_name: ResourceName = format!("sys.feedback.{layer_id}.history.pass").into();
+    bs.render_pass_specs.push(RenderPassSpec {
+        pass_id: history_pass_name.as_str().to_string(),
+        name: history_pass_name.clone(),
+        geometry_buffer: geo,
+        instance_buffer: None,
+        normals_buffer: None,
+        vertex_layout: Default::default(),
+        target_texture: prev_tex,
+        resolve_target: None,
+        params_buffer: format!("params.sys.feedback.{layer_id}.history").into(),
+        baked_data_parse_buffer: None,
+        params,
+        graph_binding: None,
+        graph_values: None,
+        shader_wgsl: build_fullscreen_textured_bundle(
+            "return textureSample(src_tex, src_samp, in.uv);".to_string(),
+        )
+        .module,
+        texture_bindings: vec![PassTextureBinding {
+            texture: cur_tex.clone(),
+            image_node_id: None,
+        }],
+        sampler_kinds: vec![SamplerKind::NearestClamp],
+        blend_state: BlendState::REPLACE,
+        color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+        sample_count: 1,
+    });
+    bs.composite_passes.push(history_pass_name);
+
+    bs.pass_output_registry.register(PassOutputSpec {
+        endpoint: crate::renderer::types::OutputEndpoint::new(layer_id, "pass"),
+        texture_name: cur_tex.clone(),
+        resolution: [out_w, out_h],
+        format: bs.sampled_pass_format,
+    });
+
+    push_output_blits(sc, bs, layer_id, layer_node, "feedback", &cur_tex)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::build_feedback_wgsl;
+    use crate::dsl::Node;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    fn feedback_node(params: serde_json::Value) -> Node {
+        Node {
+            id: "fb".to_string(),
+            node_type: "FeedbackPass".to_string(),
+            params: params
+                .as_object()
+                .cloned()
+                .map(|m| m.into_iter().collect())
+                .unwrap_or_else(HashMap::new),
+            inputs: Vec::new(),
+            input_bindings: Vec::new(),
+            outputs: Vec::new(),
+            wgsl_override: None,
+        }
+    }
+
+    #[test]
+    fn default_feedback_shader_blends_input_over_decayed_history() {
+        for has_input in [true, false] {
+            let wgsl = build_feedback_wgsl(&feedback_node(json!({})), 0.5, has_input);
+            assert!(wgsl.contains("var prev_tex: texture_2d<f32>;"));
+            assert!(wgsl.contains("let decay = f32(0.5);"));
+            assert_eq!(wgsl.contains("src + prev * (1.0 - src.a)"), has_input);
+            validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+        }
+    }
+
+    #[test]
+    fn feedback_source_body_can_read_neighbouring_history_texels() {
+        let node = feedback_node(json!({
+            "source": "    let px = 1.0 / params.target_size;\n    let n = textureSample(prev_tex, prev_samp, in.uv + vec2f(0.0, px.y));\n    return max(n * decay, textureSample(src_tex, src_samp, in.uv));"
+        }));
+        let wgsl = build_feedback_wgsl(&node, 1.0, true);
+        validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+    }
+}
@@ -0,0 +1,259 @@
+//! Finishing pass assemblers: `ChromaticAberration`, `Vignette`, and `FilmGrain`.
+//!
+//! Each is a single fullscreen pass over its `pass` input (usually a Composite),
+//! so stylized output can be finished on the server. All three operate on
+//! premultiplied color and leave coverage (alpha) unchanged, except chromatic
+//! aberration, which takes the widest alpha of the shifted channels.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::{
+    dsl::{Node, SceneDSL},
+    renderer::{
+        utils::{cpu_num_f32, cpu_num_f32_min_0, fmt_f32},
+        wgsl::build_fullscreen_textured_bundle,
+    },
+};
+
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::assemble_fullscreen_filter;
+use super::mesh_gradient::parse_color_from_params;
+
+/// Resolved ChromaticAberration params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ChromaticAberrationSettings {
+    /// Red/blue shift in pixels at the frame corners; negative swaps the channels.
+    pub amount_px: f32,
+    /// Exponent on the normalized radius; 1 is linear, higher keeps the center clean.
+    pub falloff: f32,
+}
+
+pub(crate) fn chromatic_aberration_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<ChromaticAberrationSettings> {
+    Ok(ChromaticAberrationSettings {
+        amount_px: cpu_num_f32(scene, nodes_by_id, node, "amount", 4.0)?,
+        falloff: cpu_num_f32(scene, nodes_by_id, node, "falloff", 1.0)?.max(0.0),
+    })
+}
+
+pub(crate) fn build_chromatic_aberration_wgsl(settings: &ChromaticAberrationSettings) -> String {
+    // `dir * 2` reaches length sqrt(2) at the corners; dividing by it makes
+    // `amount` the corner shift in pixels.
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let dir = (in.uv - vec2f(0.5)) * 2.0;
+ let radius = min(length(dir) / sqrt(2.0), 1.0);
+ let shift = dir / sqrt(2.0) * pow(radius, {falloff}) * {amount} / dims;
+ let r = textureSampleLevel(src_tex, src_samp, in.uv - shift, 0.0);
+ let g = textureSampleLevel(src_tex, src_samp, in.uv, 0.0);
+ let b = textureSampleLevel(src_tex, src_samp, in.uv + shift, 0.0);
+ return vec4f(r.r, g.g, b.b, max(g.a, max(r.a, b.a)));
+"#,
+        amount = fmt_f32(settings.amount_px),
+        falloff = fmt_f32(settings.falloff),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Resolved Vignette params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct VignetteSettings {
+    /// Blend toward `color` at full vignette, 0..=1.
+    pub intensity: f32,
+    /// Normalized distance (1 = edge midpoint) where darkening starts.
+    pub radius: f32,
+    /// Width of the ramp past `radius`.
+    pub feather: f32,
+    /// 0 follows the frame's aspect ratio (oval), 1 is a perfect circle.
+    pub roundness: f32,
+    /// Straight (non-premultiplied) RGBA vignette color.
+    pub color: [f32; 4],
+}
+
+pub(crate) fn vignette_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<VignetteSettings> {
+    Ok(VignetteSettings {
+        intensity: cpu_num_f32(scene, nodes_by_id, node, "intensity", 0.5)?.clamp(0.0, 1.0),
+        radius: cpu_num_f32_min_0(scene, nodes_by_id, node, "radius", 0.75)?,
+        feather: cpu_num_f32(scene, nodes_by_id, node, "feather", 0.5)?.max(1e-3),
+        roundness: cpu_num_f32(scene, nodes_by_id, node, "roundness", 1.0)?.clamp(0.0, 1.0),
+        color: parse_color_from_params(&node.params, "color").unwrap_or([0.0, 0.0, 0.0, 1.0]),
+    })
+}
+
+pub(crate) fn build_vignette_wgsl(settings: &VignetteSettings) -> String {
+    let [r, g, b, a] = settings.color;
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let src = textureSample(src_tex, src_samp, in.uv);
+ var d = (in.uv - vec2f(0.5)) * 2.0;
+ d.x = d.x * mix(1.0, dims.x / dims.y, {roundness});
+ let v = smoothstep({radius}, {radius_end}, length(d)) * {intensity} * {a};
+ let tint = vec3f({r}, {g}, {b}) * src.a;
+ return vec4f(mix(src.rgb, tint, v), src.a);
+"#,
+        roundness = fmt_f32(settings.roundness),
+        radius = fmt_f32(settings.radius),
+        radius_end = fmt_f32(settings.radius + settings.feather),
+        intensity = fmt_f32(settings.intensity),
+        r = fmt_f32(r),
+        g = fmt_f32(g),
+        b = fmt_f32(b),
+        a = fmt_f32(a.clamp(0.0, 1.0)),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Resolved FilmGrain params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct FilmGrainSettings {
+    /// Peak grain amplitude in color units.
+    pub intensity: f32,
+    /// Grain cell size in pixels (>= 1).
+    pub size_px: f32,
+    /// 0 applies grain evenly; 1 fades it out completely in highlights.
+    pub luminance_response: f32,
+    /// Reseed the grain from the frame clock every frame.
+    pub animated: bool,
+    pub seed: u32,
+}
+
+pub(crate) fn film_grain_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<FilmGrainSettings> {
+    Ok(FilmGrainSettings {
+        intensity: cpu_num_f32_min_0(scene, nodes_by_id, node, "intensity", 0.1)?,
+        size_px: cpu_num_f32(scene, nodes_by_id, node, "size", 1.5)?.max(1.0),
+        luminance_response: cpu_num_f32(scene, nodes_by_id, node, "luminanceResponse", 0.5)?
+            .clamp(0.0, 1.0),
+        animated: node
+            .params
+            .get("animated")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true),
+        seed: cpu_num_f32_min_0(scene, nodes_by_id, node, "seed", 0.0)? as u32,
+    })
+}
+
+pub(crate) fn build_film_grain_wgsl(settings: &FilmGrainSettings) -> String {
+    // Integer hash per grain cell; WGSL requires parentheses when mixing bitwise
+    // and arithmetic operators.
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let src = textureSample(src_tex, src_samp, in.uv);
+ let cell = vec2u(floor(in.uv * dims / {size}));
+ let seed = {seed}u + params.frame * {animated}u;
+ var h = ((cell.x * 0x8da6b343u) ^ (cell.y * 0xd8163841u)) ^ (seed * 0xcb1ab31fu);
+ h = (h ^ (h >> 16u)) * 0x7feb352du;
+ h = (h ^ (h >> 15u)) * 0x846ca68bu;
+ h = h ^ (h >> 16u);
+ let noise = f32(h) / 4294967295.0 - 0.5;
+ let luma = dot(src.rgb, vec3f(0.2126, 0.7152, 0.0722)) / max(src.a, 1e-4);
+ let weight = mix(1.0, 1.0 - clamp(luma, 0.0, 1.0), {response});
+ let rgb = src.rgb + vec3f(noise * {intensity} * weight * src.a);
+ return vec4f(clamp(rgb, vec3f(0.0), vec3f(src.a)), src.a);
+"#,
+        size = fmt_f32(settings.size_px),
+        seed = settings.seed,
+        animated = u32::from(settings.animated),
+        response = fmt_f32(settings.luminance_response),
+        intensity = fmt_f32(settings.intensity),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Assemble a `"ChromaticAberration"` layer.
+pub(crate) fn assemble_chromatic_aberration_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = chromatic_aberration_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_chromatic_aberration_wgsl(&settings);
+    assemble_fullscreen_filter(sc, bs, layer_id, layer_node, "chroma", shader_wgsl)
+}
+
+/// Assemble a `"Vignette"` layer.
+pub(crate) fn assemble_vignette_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = vignette_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_vignette_wgsl(&settings);
+    assemble_fullscreen_filter(sc, bs, layer_id, layer_node, "vignette", shader_wgsl)
+}
+
+/// Assemble a `"FilmGrain"` layer.
+pub(crate) fn assemble_film_grain_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = film_grain_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_film_grain_wgsl(&settings);
+    assemble_fullscreen_filter(sc, bs, layer_id, layer_node, "grain", shader_wgsl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    #[test]
+    fn finishing_shaders_validate() {
+        let modules = [
+            build_chromatic_aberration_wgsl(&ChromaticAberrationSettings {
+                amount_px: 4.0,
+                falloff: 2.0,
+            }),
+            build_vignette_wgsl(&VignetteSettings {
+                intensity: 0.5,
+                radius: 0.75,
+                feather: 0.5,
+                roundness: 0.0,
+                color: [0.1, 0.0, 0.2, 1.0],
+            }),
+            build_film_grain_wgsl(&FilmGrainSettings {
+                intensity: 0.1,
+                size_px: 1.5,
+                luminance_response: 0.5,
+                animated: true,
+                seed: 7,
+            }),
+        ];
+        for wgsl in &modules {
+            validate_wgsl_module(wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+        }
+    }
+
+    #[test]
+    fn static_film_grain_ignores_the_frame_clock() {
+        let mut settings = FilmGrainSettings {
+            intensity: 0.1,
+            size_px: 1.0,
+            luminance_response: 0.0,
+            animated: true,
+            seed: 3,
+        };
+        assert!(build_film_grain_wgsl(&settings).contains("params.frame * 1u"));
+        settings.animated = false;
+        assert!(build_film_grain_wgsl(&settings).contains("params.frame * 0u"));
+    }
+}
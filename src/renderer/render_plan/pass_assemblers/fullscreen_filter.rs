@@ -0,0 +1,264 @@
+//! Shared plumbing for single-output filter passes.
+//!
+//! Filter nodes render into their own `sys.{prefix}.{id}.out` texture, register it
+//! as the node's `pass` output, and then blit it into the scene target (when no
+//! downstream pass samples it) and into every consuming Composite target.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use rust_wgpu_fiber::{
+    ResourceName,
+    eframe::wgpu::{self, BlendState, Color},
+};
+
+use crate::{
+    dsl::{Node, incoming_connection},
+    renderer::{
+        camera::legacy_projection_camera_matrix, types::PassOutputSpec,
+        wgsl::build_fullscreen_textured_bundle,
+    },
+};
+
+use super::super::pass_spec::{
+    PassTextureBinding, RenderPassSpec, SamplerKind, TextureDecl, make_params,
+};
+use super::args::{BuilderState, SceneContext};
+
+/// Blit `output_texture` into the scene target and Composite consumers of `layer_id`,
+/// using the node's blend params.
+pub(crate) fn push_output_blits(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+    prefix: &str,
+    output_texture: &ResourceName,
+) -> Result<()> {
+    let pass_blend_state =
+        crate::renderer::render_plan::parse_render_pass_blend_state(&layer_node.params)
+            .with_context(|| {
+                format!(
+                    "invalid blend params for {} {layer_id}",
+                    layer_node.node_type
+                )
+            })?;
+    let mut blit_targets: Vec<(String, ResourceName, [f32; 2])> = Vec::new();
+    if !bs.sampled_pass_ids.contains(layer_id) {
+        blit_targets.push((
+            "scene".to_string(),
+            bs.target_texture_name.clone(),
+            bs.tgt_size,
+        ));
+    }
+    for composition_id in sc
+        .composition_consumers_by_source
+        .get(layer_id)
+        .cloned()
+        .unwrap_or_default()
+    {
+        let Some(comp_ctx) = sc.composition_contexts.get(&composition_id) else {
+            continue;
+        };
+        if blit_targets
+            .iter()
+            .any(|(_, target, _)| *target == comp_ctx.target_texture_name)
+        {
+            continue;
+        }
+        blit_targets.push((
+            composition_id.clone(),
+            comp_ctx.target_texture_name.clone(),
+            comp_ctx.target_size_px,
+        ));
+    }
+
+    for (target_id, target_texture, [comp_w, comp_h]) in blit_targets {
+        let compose_geo: ResourceName =
+            format!("sys.{prefix}.{layer_id}.to.{target_id}.compose.geo").into();
+        bs.push_fullscreen_geometry(compose_geo.clone(), comp_w, comp_h);
+
+        let compose_pass_name: ResourceName =
+            format!("sys.{prefix}.{layer_id}.to.{target_id}.compose.pass").into();
+        bs.render_pass_specs.push(RenderPassSpec {
+            pass_id: compose_pass_name.as_str().to_string(),
+            name: compose_pass_name.clone(),
+            geometry_buffer: compose_geo,
+            instance_buffer: None,
+            normals_buffer: None,
+            vertex_layout: Default::default(),
+            target_texture,
+            resolve_target: None,
+            params_buffer: format!("params.sys.{prefix}.{layer_id}.to.{target_id}.compose").into(),
+            baked_data_parse_buffer: None,
+            params: make_params(
+                [comp_w, comp_h],
+                [comp_w, comp_h],
+                [comp_w * 0.5, comp_h * 0.5],
+                legacy_projection_camera_matrix([comp_w, comp_h]),
+                [0.0, 0.0, 0.0, 0.0],
+            ),
+            graph_binding: None,
+            graph_values: None,
+            shader_wgsl: build_fullscreen_textured_bundle(
+                "return textureSample(src_tex, src_samp, in.uv);".to_string(),
+            )
+            .module,
+            texture_bindings: vec![PassTextureBinding {
+                texture: output_texture.clone(),
+                image_node_id: None,
+            }],
+            sampler_kinds: vec![SamplerKind::LinearClamp],
+            blend_state: pass_blend_state,
+            color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+            sample_count: 1,
+        });
+        bs.composite_passes.push(compose_pass_name);
+    }
+
+    Ok(())
+}
+
+/// Assemble a one-pass filter: `shader_wgsl` samples the `pass` input as `src_tex`
+/// and writes `sys.{prefix}.{id}.out` at the input's resolution.
+pub(crate) fn assemble_fullscreen_filter(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+    prefix: &str,
+    shader_wgsl: String,
+) -> Result<()> {
+    assemble_fullscreen_filter_with_table(sc, bs, layer_id, layer_node, prefix, shader_wgsl, None)
+}
+
+/// [`assemble_fullscreen_filter`] with an optional read-only table bound as
+/// `baked_data_parse: array<vec4f>` at `@group(0) @binding(1)`.
+pub(crate) fn assemble_fullscreen_filter_with_table(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+    prefix: &str,
+    shader_wgsl: String,
+    table: Option<Arc<[u8]>>,
+) -> Result<()> {
+    let options = FullscreenFilterOptions {
+        table,
+        ..Default::default()
+    };
+    assemble_fullscreen_filter_with(sc, bs, layer_id, layer_node, prefix, shader_wgsl, options)
+}
+
+/// Optional bindings for [`assemble_fullscreen_filter_with`].
+#[derive(Default)]
+pub(crate) struct FullscreenFilterOptions<'a> {
+    /// Sampler for `src_tex`; `LinearClamp` when unset.
+    pub source_sampler: Option<SamplerKind>,
+    /// Required pass input ports bound after `src_tex`, in order, starting at
+    /// `@group(1) @binding(2)` (see `with_extra_fullscreen_texture`).
+    pub extra_inputs: &'a [&'a str],
+    /// Read-only table bound as `baked_data_parse: array<vec4f>` at `@group(0) @binding(1)`.
+    pub table: Option<Arc<[u8]>>,
+}
+
+/// [`assemble_fullscreen_filter`] with extra input textures, a source sampler
+/// override and/or a storage table.
+pub(crate) fn assemble_fullscreen_filter_with(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+    prefix: &str,
+    shader_wgsl: String,
+    options: FullscreenFilterOptions<'_>,
+) -> Result<()> {
+    let node_type = layer_node.node_type.as_str();
+    let upstream = |port: &str| -> Result<PassOutputSpec> {
+        let conn = incoming_connection(sc.scene(), layer_id, port)
+            .ok_or_else(|| anyhow!("{node_type}.{port} missing for {layer_id}"))?;
+        bs.pass_output_registry
+            .get_for_port(&conn.from.node_id, &conn.from.port_id)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "{node_type} {layer_id}: upstream output {}.{} for `{port}` is not registered yet",
+                    conn.from.node_id,
+                    conn.from.port_id
+                )
+            })
+    };
+    let source = upstream("pass")?;
+    let mut texture_bindings = vec![PassTextureBinding {
+        texture: source.texture_name.clone(),
+        image_node_id: None,
+    }];
+    let mut sampler_kinds = vec![options.source_sampler.unwrap_or(SamplerKind::LinearClamp)];
+    for port in options.extra_inputs {
+        texture_bindings.push(PassTextureBinding {
+            texture: upstream(port)?.texture_name,
+            image_node_id: None,
+        });
+        sampler_kinds.push(SamplerKind::LinearClamp);
+    }
+
+    let [out_w, out_h] = source.resolution;
+    let out_size = [out_w as f32, out_h as f32];
+    let out_tex: ResourceName = format!("sys.{prefix}.{layer_id}.out").into();
+    bs.textures.push(TextureDecl {
+        name: out_tex.clone(),
+        size: [out_w, out_h],
+        format: bs.sampled_pass_format,
+        sample_count: 1,
+        needs_sampling: true,
+    });
+    let geo: ResourceName = format!("sys.{prefix}.{layer_id}.geo").into();
+    bs.push_fullscreen_geometry(geo.clone(), out_size[0], out_size[1]);
+
+    let pass_name: ResourceName = format!("sys.{prefix}.{layer_id}.pass").into();
+    let table_buffer = options.table.map(|bytes| {
+        let buffer: ResourceName = format!("sys.{prefix}.{layer_id}.table").into();
+        bs.baked_data_parse_bytes_by_pass
+            .insert(pass_name.as_str().to_string(), bytes);
+        bs.baked_data_parse_buffer_to_pass_id
+            .insert(buffer.clone(), pass_name.as_str().to_string());
+        buffer
+    });
+    bs.render_pass_specs.push(RenderPassSpec {
+        pass_id: pass_name.as_str().to_string(),
+        name: pass_name.clone(),
+        geometry_buffer: geo,
+        instance_buffer: None,
+        normals_buffer: None,
+        vertex_layout: Default::default(),
+        target_texture: out_tex.clone(),
+        resolve_target: None,
+        params_buffer: format!("params.sys.{prefix}.{layer_id}").into(),
+        baked_data_parse_buffer: table_buffer,
+        params: make_params(
+            out_size,
+            out_size,
+            [out_size[0] * 0.5, out_size[1] * 0.5],
+            legacy_projection_camera_matrix(out_size),
+            [0.0, 0.0, 0.0, 0.0],
+        ),
+        graph_binding: None,
+        graph_values: None,
+        shader_wgsl,
+        texture_bindings,
+        sampler_kinds,
+        blend_state: BlendState::REPLACE,
+        color_load_op: wgpu::LoadOp::Clear(Color::TRANSPARENT),
+        sample_count: 1,
+    });
+    bs.composite_passes.push(pass_name);
+
+    bs.pass_output_registry.register(PassOutputSpec {
+        endpoint: crate::renderer::types::OutputEndpoint::new(layer_id, "pass"),
+        texture_name: out_tex.clone(),
+        resolution: [out_w, out_h],
+        format: bs.sampled_pass_format,
+    });
+
+    push_output_blits(sc, bs, layer_id, layer_node, prefix, &out_tex)
+}
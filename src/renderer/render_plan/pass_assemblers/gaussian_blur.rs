@@ -3,26 +3,34 @@
 //! Handles the `"GuassianBlurPass"` node type. Takes a source pass texture,
 //! optionally downsamples, applies horizontal + vertical separated Gaussian blur,
 //! and optionally upsamples back to the target resolution.
+//!
+//! `kernelType` swaps the shaders used for those stages: `gaussian` (default),
+//! `box` (exact uniform window at full resolution), or `kawase` (dual-Kawase
+//! downsample/upsample around two diagonal Kawase iterations).
+
+use std::collections::HashMap;
 
 use anyhow::{Context, Result, anyhow, bail};
 use rust_wgpu_fiber::{
     ResourceName,
     eframe::wgpu::{self, BlendState, Color},
 };
+use serde_json::Value;
 
 use crate::{
     dsl::{Node, incoming_connection},
     renderer::{
         camera::pass_node_uses_custom_camera,
         graph_uniforms::{choose_graph_binding_kind, pack_graph_values},
-        types::{GraphBinding, PassOutputSpec},
+        types::{GraphBinding, PassOutputSpec, WgslShaderBundle},
         utils::{cpu_num_f32_min_0, cpu_num_u32_min_1},
         wgsl::{
             build_blur_image_wgsl_bundle, build_blur_image_wgsl_bundle_with_graph_binding,
-            build_downsample_bundle, build_fullscreen_textured_bundle,
-            build_horizontal_blur_bundle_with_tap_count, build_upsample_bilinear_bundle,
-            build_vertical_blur_bundle_with_tap_count, clamp_min_1, gaussian_kernel_8,
-            gaussian_mip_level_and_sigma_p,
+            build_box_blur_bundle, build_downsample_bundle, build_fullscreen_textured_bundle,
+            build_horizontal_blur_bundle_with_tap_count, build_kawase_blur_bundle,
+            build_kawase_downsample_bundle, build_kawase_upsample_bundle,
+            build_upsample_bilinear_bundle, build_vertical_blur_bundle_with_tap_count, clamp_min_1,
+            gaussian_kernel_8, gaussian_mip_level_and_sigma_p,
         },
     },
 };
@@ -38,9 +46,105 @@ use super::super::resource_naming::{
 use super::args::{BuilderState, SceneContext, make_fullscreen_geometry};
 use crate::renderer::shader_space::image_utils::image_node_dimensions;
 use crate::renderer::shader_space::sampler::{
-    sampler_kind_for_pass_texture, sampler_kind_from_node_params,
+    image_texture_binding, sampler_kind_for_pass_texture, sampler_kind_from_node_params,
 };
 
+/// Largest box blur radius, in pixels; the box shader loops over the whole window.
+pub(crate) const BOX_BLUR_MAX_RADIUS_PX: u32 = 256;
+
+/// Blur filter selected by `GuassianBlurPass.kernelType`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum BlurKernelType {
+    #[default]
+    Gaussian,
+    Box,
+    Kawase,
+}
+
+impl BlurKernelType {
+    pub(crate) fn from_params(params: &HashMap<String, Value>) -> Result<Self> {
+        let Some(raw) = params.get("kernelType") else {
+            return Ok(Self::default());
+        };
+        let name = raw
+            .as_str()
+            .ok_or_else(|| anyhow!("kernelType must be a string, got {raw}"))?;
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "gaussian" => Ok(Self::Gaussian),
+            "box" => Ok(Self::Box),
+            "kawase" | "dual-kawase" | "dualkawase" => Ok(Self::Kawase),
+            other => bail!("unknown kernelType '{other}', expected gaussian, box, or kawase"),
+        }
+    }
+}
+
+/// Shaders and MIP factor for one blur, shared with the WGSL bundle listing so
+/// both agree on pass contents.
+pub(crate) struct BlurKernelPlan {
+    pub kernel_type: BlurKernelType,
+    pub downsample_factor: u32,
+    pub horizontal: WgslShaderBundle,
+    pub vertical: WgslShaderBundle,
+}
+
+impl BlurKernelPlan {
+    /// `radius_px` is the authored full-resolution radius.
+    pub(crate) fn new(kernel_type: BlurKernelType, radius_px: f32) -> Self {
+        // Radius is an analytic cutoff, not sigma: k = sqrt(2*ln(1/0.002)) ≈ 3.525494.
+        let sigma = radius_px / 3.525_494;
+        match kernel_type {
+            BlurKernelType::Gaussian => {
+                let (mip_level, sigma_p) = gaussian_mip_level_and_sigma_p(sigma);
+                let (kernel, offset, num) = gaussian_kernel_8(sigma_p.max(1e-6));
+                let tap_count = num.clamp(1, 8);
+                Self {
+                    kernel_type,
+                    downsample_factor: 1 << mip_level,
+                    horizontal: build_horizontal_blur_bundle_with_tap_count(
+                        kernel, offset, tap_count,
+                    ),
+                    vertical: build_vertical_blur_bundle_with_tap_count(kernel, offset, tap_count),
+                }
+            }
+            BlurKernelType::Box => {
+                let radius = radius_px.round().clamp(0.0, BOX_BLUR_MAX_RADIUS_PX as f32) as u32;
+                Self {
+                    kernel_type,
+                    downsample_factor: 1,
+                    horizontal: build_box_blur_bundle(radius, true),
+                    vertical: build_box_blur_bundle(radius, false),
+                }
+            }
+            BlurKernelType::Kawase => {
+                // `sigma_p` is the variance left after downsampling. Each diagonal
+                // iteration adds about d^2 + 0.25 (bilinear) per axis.
+                let (mip_level, sigma_p) = gaussian_mip_level_and_sigma_p(sigma);
+                let d = (sigma_p * 0.5 - 0.25).max(0.0).sqrt();
+                Self {
+                    kernel_type,
+                    downsample_factor: 1 << mip_level,
+                    horizontal: build_kawase_blur_bundle(d),
+                    vertical: build_kawase_blur_bundle(d),
+                }
+            }
+        }
+    }
+
+    pub(crate) fn downsample_bundle(&self, step: u32) -> Result<WgslShaderBundle> {
+        match self.kernel_type {
+            BlurKernelType::Kawase => build_kawase_downsample_bundle(step),
+            BlurKernelType::Gaussian | BlurKernelType::Box => build_downsample_bundle(step),
+        }
+    }
+
+    pub(crate) fn upsample_bundle(&self) -> WgslShaderBundle {
+        match self.kernel_type {
+            BlurKernelType::Kawase => build_kawase_upsample_bundle(),
+            BlurKernelType::Gaussian | BlurKernelType::Box => build_upsample_bilinear_bundle(),
+        }
+    }
+}
+
 /// Assemble a `"GuassianBlurPass"` layer.
 pub(crate) fn assemble_gaussian_blur(
     sc: &SceneContext<'_>,
@@ -281,17 +385,10 @@ pub(crate) fn assemble_gaussian_blur(
         let mut src_sampler_kinds: Vec<SamplerKind> = Vec::new();
 
         for id in src_bundle.image_textures.iter() {
-            let Some(tex) = ids.get(id).cloned() else {
+            let Some((binding, kind)) = image_texture_binding(ids, nodes_by_id, id) else {
                 continue;
             };
-            src_texture_bindings.push(PassTextureBinding {
-                texture: tex,
-                image_node_id: Some(id.clone()),
-            });
-            let kind = nodes_by_id
-                .get(id)
-                .map(|n| sampler_kind_from_node_params(&n.params))
-                .unwrap_or(SamplerKind::LinearClamp);
+            src_texture_bindings.push(binding);
             src_sampler_kinds.push(kind);
         }
 
@@ -346,12 +443,10 @@ pub(crate) fn assemble_gaussian_blur(
         src_resolution[1],
     )?;
 
-    // sigma from radius
-    let sigma = radius_px / 3.525_494;
-    let (mip_level, sigma_p) = gaussian_mip_level_and_sigma_p(sigma);
-    let downsample_factor: u32 = 1 << mip_level;
-    let (kernel, offset, num) = gaussian_kernel_8(sigma_p.max(1e-6));
-    let tap_count = num.clamp(1, 8);
+    let kernel_type = BlurKernelType::from_params(&layer_node.params)
+        .with_context(|| format!("GuassianBlurPass {layer_id}"))?;
+    let kernel_plan = BlurKernelPlan::new(kernel_type, radius_px);
+    let downsample_factor = kernel_plan.downsample_factor;
     let is_sampled_output = bs.sampled_pass_ids.contains(layer_id);
     let skip_factor1_downsample = should_skip_blur_downsample_pass(downsample_factor);
     let skip_factor1_upsample =
@@ -481,7 +576,7 @@ pub(crate) fn assemble_gaussian_blur(
     let mut prev_tex: Option<ResourceName> = None;
     for (step, tex, step_w, step_h, step_geo) in &step_textures {
         let params_name: ResourceName = format!("params.sys.blur.{layer_id}.ds.{step}").into();
-        let bundle = build_downsample_bundle(*step)?;
+        let bundle = kernel_plan.downsample_bundle(*step)?;
 
         let sampler_kind = if prev_tex.is_none() {
             initial_blur_source_sampler_kind.unwrap_or(SamplerKind::LinearMirror)
@@ -560,7 +655,6 @@ pub(crate) fn assemble_gaussian_blur(
     // 2) Horizontal blur: ds_src_tex -> h_tex
     let params_h: ResourceName =
         format!("params.sys.blur.{layer_id}.h.ds{downsample_factor}").into();
-    let bundle_h = build_horizontal_blur_bundle_with_tap_count(kernel, offset, tap_count);
     let ds_w_f = ds_w as f32;
     let ds_h_f = ds_h as f32;
     let params_h_val = make_params(
@@ -593,7 +687,7 @@ pub(crate) fn assemble_gaussian_blur(
         params: params_h_val,
         graph_binding: None,
         graph_values: None,
-        shader_wgsl: bundle_h.module,
+        shader_wgsl: kernel_plan.horizontal.module.clone(),
         texture_bindings: vec![PassTextureBinding {
             texture: ds_src_tex.clone(),
             image_node_id: ds_src_image_node_id,
@@ -608,7 +702,6 @@ pub(crate) fn assemble_gaussian_blur(
     // 3) Vertical blur: h_tex -> v_tex
     let params_v: ResourceName =
         format!("params.sys.blur.{layer_id}.v.ds{downsample_factor}").into();
-    let bundle_v = build_vertical_blur_bundle_with_tap_count(kernel, offset, tap_count);
     let pass_name_v: ResourceName =
         format!("sys.blur.{layer_id}.v.ds{downsample_factor}.pass").into();
     let params_v_val = make_params(
@@ -638,7 +731,7 @@ pub(crate) fn assemble_gaussian_blur(
         params: params_v_val,
         graph_binding: None,
         graph_values: None,
-        shader_wgsl: bundle_v.module,
+        shader_wgsl: kernel_plan.vertical.module.clone(),
         texture_bindings: vec![PassTextureBinding {
             texture: h_tex.clone(),
             image_node_id: None,
@@ -660,7 +753,7 @@ pub(crate) fn assemble_gaussian_blur(
             .ok_or_else(|| anyhow!("GuassianBlurPass: missing upsample geometry"))?;
         let params_u: ResourceName =
             format!("params.sys.blur.{layer_id}.upsample_bilinear.ds{downsample_factor}").into();
-        let bundle_u = build_upsample_bilinear_bundle();
+        let bundle_u = kernel_plan.upsample_bundle();
         let upsample_target_size: [f32; 2] = if output_tex == target_texture_name {
             [tgt_w, tgt_h]
         } else {
@@ -798,3 +891,46 @@ pub(crate) fn assemble_gaussian_blur(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::{BlurKernelPlan, BlurKernelType};
+
+    #[test]
+    fn kernel_type_parses_known_names_and_rejects_others() {
+        let parse = |v: serde_json::Value| {
+            BlurKernelType::from_params(&HashMap::from([("kernelType".to_string(), v)]))
+        };
+        assert_eq!(
+            BlurKernelType::from_params(&HashMap::new()).unwrap(),
+            BlurKernelType::Gaussian
+        );
+        assert_eq!(parse(json!("Box")).unwrap(), BlurKernelType::Box);
+        assert_eq!(parse(json!("dual-kawase")).unwrap(), BlurKernelType::Kawase);
+        assert!(parse(json!("bilateral")).is_err());
+        assert!(parse(json!(2)).is_err());
+    }
+
+    #[test]
+    fn box_blur_stays_full_res_while_kawase_follows_gaussian_mips() {
+        let radius = 60.0;
+        let gaussian = BlurKernelPlan::new(BlurKernelType::Gaussian, radius);
+        let kawase = BlurKernelPlan::new(BlurKernelType::Kawase, radius);
+        let boxed = BlurKernelPlan::new(BlurKernelType::Box, radius);
+        assert!(gaussian.downsample_factor > 1);
+        assert_eq!(kawase.downsample_factor, gaussian.downsample_factor);
+        assert_eq!(boxed.downsample_factor, 1);
+        assert!(boxed.horizontal.module.contains("let radius: i32 = 60;"));
+        assert!(
+            kawase
+                .downsample_bundle(kawase.downsample_factor.min(8))
+                .unwrap()
+                .module
+                .contains("* 0.125")
+        );
+    }
+}
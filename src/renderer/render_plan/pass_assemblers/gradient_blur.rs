@@ -30,7 +30,7 @@ use super::super::resource_naming::{
 use super::args::{BuilderState, SceneContext, make_fullscreen_geometry};
 use crate::renderer::shader_space::image_utils::image_node_dimensions;
 use crate::renderer::shader_space::sampler::{
-    sampler_kind_for_pass_texture, sampler_kind_from_node_params,
+    image_texture_binding, sampler_kind_for_pass_texture,
 };
 
 /// Assemble a `"GradientBlur"` layer.
@@ -234,17 +234,10 @@ pub(crate) fn assemble_gradient_blur(
         let mut src_sampler_kinds: Vec<SamplerKind> = Vec::new();
 
         for id in src_bundle.image_textures.iter() {
-            let Some(tex) = ids.get(id).cloned() else {
+            let Some((binding, kind)) = image_texture_binding(ids, nodes_by_id, id) else {
                 continue;
             };
-            src_texture_bindings.push(PassTextureBinding {
-                texture: tex,
-                image_node_id: Some(id.clone()),
-            });
-            let kind = nodes_by_id
-                .get(id)
-                .map(|n| sampler_kind_from_node_params(&n.params))
-                .unwrap_or(SamplerKind::LinearClamp);
+            src_texture_bindings.push(binding);
             src_sampler_kinds.push(kind);
         }
 
@@ -527,17 +520,10 @@ pub(crate) fn assemble_gradient_blur(
 
     // Image textures from mask expression.
     for id in composite_bundle.image_textures.iter() {
-        let Some(tex) = ids.get(id).cloned() else {
+        let Some((binding, kind)) = image_texture_binding(ids, nodes_by_id, id) else {
             continue;
         };
-        final_texture_bindings.push(PassTextureBinding {
-            texture: tex,
-            image_node_id: Some(id.clone()),
-        });
-        let kind = nodes_by_id
-            .get(id)
-            .map(|n| sampler_kind_from_node_params(&n.params))
-            .unwrap_or(SamplerKind::LinearClamp);
+        final_texture_bindings.push(binding);
         final_sampler_kinds.push(kind);
     }
 
@@ -0,0 +1,181 @@
+//! LensDistortion assembler.
+//!
+//! Brown–Conrady lens model over a source pass. Coordinates are centered and
+//! scaled so the vertical half-extent is 1 (x is multiplied by the aspect ratio),
+//! then mapped through
+//!
+//! ```text
+//! r² = x² + y²
+//! x' = x (1 + k1 r² + k2 r⁴) + 2 p1 x y + p2 (r² + 2 x²)
+//! y' = y (1 + k1 r² + k2 r⁴) + p1 (r² + 2 y²) + 2 p2 x y
+//! ```
+//!
+//! `distort` samples the source at the mapped position (negative `k1` gives
+//! barrel, positive pincushion). `undistort` inverts the mapping with a fixed-point
+//! iteration, as OpenCV's `undistortPoints` does, so footage shot through a real
+//! lens can be flattened with its calibration coefficients.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::{
+    dsl::{Node, SceneDSL},
+    renderer::{
+        utils::{cpu_num_f32, fmt_f32},
+        wgsl::build_fullscreen_textured_bundle,
+    },
+};
+
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::assemble_fullscreen_filter;
+
+/// Fixed-point iterations used by `undistort`; converges for typical |k1| < 0.5.
+const UNDISTORT_ITERATIONS: u32 = 8;
+
+/// Direction of the lens mapping, selected by `LensDistortion.mode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LensDistortionMode {
+    #[default]
+    Distort,
+    Undistort,
+}
+
+/// Resolved LensDistortion params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LensDistortionSettings {
+    pub mode: LensDistortionMode,
+    pub k1: f32,
+    pub k2: f32,
+    pub p1: f32,
+    pub p2: f32,
+    /// Zoom applied before the mapping; > 1 crops in to hide the borders.
+    pub scale: f32,
+    /// Extra displacement for red (+) and blue (-) relative to green.
+    pub chromatic: f32,
+}
+
+pub(crate) fn lens_distortion_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<LensDistortionSettings> {
+    let mode = match node.params.get("mode") {
+        None => LensDistortionMode::default(),
+        Some(raw) => {
+            let name = raw
+                .as_str()
+                .ok_or_else(|| anyhow!("mode must be a string, got {raw}"))?;
+            match name.trim().to_ascii_lowercase().as_str() {
+                "" | "distort" => LensDistortionMode::Distort,
+                "undistort" | "inverse" => LensDistortionMode::Undistort,
+                other => bail!("unknown mode '{other}', expected distort or undistort"),
+            }
+        }
+    };
+    let num = |key: &str, default: f32| cpu_num_f32(scene, nodes_by_id, node, key, default);
+    Ok(LensDistortionSettings {
+        mode,
+        k1: num("k1", -0.15)?,
+        k2: num("k2", 0.0)?,
+        p1: num("p1", 0.0)?,
+        p2: num("p2", 0.0)?,
+        scale: num("scale", 1.0)?.max(1e-3),
+        chromatic: num("chromatic", 0.0)?,
+    })
+}
+
+pub(crate) fn build_lens_distortion_wgsl(settings: &LensDistortionSettings) -> String {
+    let radial = |v: &str| {
+        format!(
+            "(1.0 + {k1} * dot({v}, {v}) + {k2} * dot({v}, {v}) * dot({v}, {v}))",
+            k1 = fmt_f32(settings.k1),
+            k2 = fmt_f32(settings.k2),
+        )
+    };
+    let tangential = |v: &str| {
+        format!(
+            "vec2f(2.0 * {p1} * {v}.x * {v}.y + {p2} * (dot({v}, {v}) + 2.0 * {v}.x * {v}.x), \
+             {p1} * (dot({v}, {v}) + 2.0 * {v}.y * {v}.y) + 2.0 * {p2} * {v}.x * {v}.y)",
+            p1 = fmt_f32(settings.p1),
+            p2 = fmt_f32(settings.p2),
+        )
+    };
+    let mapping = match settings.mode {
+        LensDistortionMode::Distort => format!(
+            " let q = p * {radial} + {tangential};\n",
+            radial = radial("p"),
+            tangential = tangential("p"),
+        ),
+        LensDistortionMode::Undistort => format!(
+            r#" var q = p;
+ for (var i: u32 = 0u; i < {iterations}u; i = i + 1u) {{
+     q = (p - {tangential}) / {radial};
+ }}
+"#,
+            iterations = UNDISTORT_ITERATIONS,
+            radial = radial("q"),
+            tangential = tangential("q"),
+        ),
+    };
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let aspect = vec2f(dims.x / dims.y, 1.0);
+ let p = (in.uv - vec2f(0.5)) * 2.0 * aspect / {scale};
+{mapping} let delta = (q / aspect * 0.5 + vec2f(0.5)) - in.uv;
+ let uv_r = in.uv + delta * (1.0 + {chromatic});
+ let uv_g = in.uv + delta;
+ let uv_b = in.uv + delta * (1.0 - {chromatic});
+ let in_r = f32(all(uv_r >= vec2f(0.0)) && all(uv_r <= vec2f(1.0)));
+ let in_g = f32(all(uv_g >= vec2f(0.0)) && all(uv_g <= vec2f(1.0)));
+ let in_b = f32(all(uv_b >= vec2f(0.0)) && all(uv_b <= vec2f(1.0)));
+ let r = textureSampleLevel(src_tex, src_samp, uv_r, 0.0) * in_r;
+ let g = textureSampleLevel(src_tex, src_samp, uv_g, 0.0) * in_g;
+ let b = textureSampleLevel(src_tex, src_samp, uv_b, 0.0) * in_b;
+ return vec4f(r.r, g.g, b.b, max(g.a, max(r.a, b.a)));
+"#,
+        scale = fmt_f32(settings.scale),
+        chromatic = fmt_f32(settings.chromatic),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Assemble a `"LensDistortion"` layer.
+pub(crate) fn assemble_lens_distortion_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = lens_distortion_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_lens_distortion_wgsl(&settings);
+    assemble_fullscreen_filter(sc, bs, layer_id, layer_node, "lens", shader_wgsl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    #[test]
+    fn lens_distortion_shaders_validate_in_both_modes() {
+        for mode in [LensDistortionMode::Distort, LensDistortionMode::Undistort] {
+            let settings = LensDistortionSettings {
+                mode,
+                k1: -0.2,
+                k2: 0.05,
+                p1: 0.001,
+                p2: -0.002,
+                scale: 1.1,
+                chromatic: 0.02,
+            };
+            let wgsl = build_lens_distortion_wgsl(&settings);
+            validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+            assert_eq!(
+                wgsl.contains("for (var i: u32"),
+                mode == LensDistortionMode::Undistort
+            );
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! LUTPass assembler and `.cube` loader.
+//!
+//! The LUT is read from the asset store (`assetId`), parsed as an Adobe/Resolve
+//! `.cube` 3D table and applied as a single fullscreen pass over the `pass` input.
+//! The texture pool only declares 2D textures, so the lattice is bound as a
+//! read-only `array<vec4f>` storage table (red fastest, then green, then blue, as
+//! in the file) and interpolated in the shader, trilinearly or tetrahedrally.
+//! Either way lookups keep full f32 precision.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::Value;
+
+use crate::{
+    asset_store::AssetStore,
+    dsl::{Node, SceneDSL},
+    renderer::{
+        utils::{as_bytes_slice, cpu_num_f32, fmt_f32},
+        wgsl::build_fullscreen_textured_bundle,
+    },
+};
+
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::assemble_fullscreen_filter_with_table;
+
+/// Largest accepted `LUT_3D_SIZE`; 129³ entries is ~34 MB of table.
+pub(crate) const LUT_MAX_SIZE: u32 = 129;
+
+/// A parsed `.cube` 3D LUT.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CubeLut {
+    pub size: u32,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+    /// `size³` output colors, red index fastest.
+    pub table: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// Table packed as `vec4f` per lattice point for the storage binding.
+    pub(crate) fn storage_bytes(&self) -> Arc<[u8]> {
+        let packed: Vec<f32> = self
+            .table
+            .iter()
+            .flat_map(|[r, g, b]| [*r, *g, *b, 1.0])
+            .collect();
+        Arc::from(as_bytes_slice(&packed).to_vec())
+    }
+}
+
+fn parse_cube_floats<const N: usize>(rest: &[&str], line_no: usize) -> Result<[f32; N]> {
+    if rest.len() != N {
+        bail!("line {line_no}: expected {N} numbers, got {}", rest.len());
+    }
+    let mut out = [0.0; N];
+    for (slot, token) in out.iter_mut().zip(rest) {
+        *slot = token
+            .parse::<f32>()
+            .with_context(|| format!("line {line_no}: invalid number '{token}'"))?;
+    }
+    Ok(out)
+}
+
+/// Parse the text of a `.cube` file. Only 3D LUTs are supported.
+pub(crate) fn parse_cube_lut(text: &str) -> Result<CubeLut> {
+    let mut size: Option<u32> = None;
+    let mut domain_min = [0.0; 3];
+    let mut domain_max = [1.0; 3];
+    let mut table: Vec<[f32; 3]> = Vec::new();
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens[0].to_ascii_uppercase().as_str() {
+            "TITLE" => {}
+            "LUT_1D_SIZE" => bail!("line {line_no}: 1D LUTs are not supported"),
+            "LUT_3D_SIZE" => {
+                let [n] = parse_cube_floats::<1>(&tokens[1..], line_no)?;
+                if n.fract() != 0.0 || !(2.0..=LUT_MAX_SIZE as f32).contains(&n) {
+                    bail!("line {line_no}: LUT_3D_SIZE must be an integer in 2..={LUT_MAX_SIZE}");
+                }
+                size = Some(n as u32);
+            }
+            "DOMAIN_MIN" => domain_min = parse_cube_floats(&tokens[1..], line_no)?,
+            "DOMAIN_MAX" => domain_max = parse_cube_floats(&tokens[1..], line_no)?,
+            "LUT_3D_INPUT_RANGE" => {
+                let [min, max] = parse_cube_floats::<2>(&tokens[1..], line_no)?;
+                domain_min = [min; 3];
+                domain_max = [max; 3];
+            }
+            keyword if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                // Vendor extensions (LUT_IN_VIDEO_RANGE, ...) don't affect the table.
+            }
+            _ => table.push(parse_cube_floats(&tokens, line_no)?),
+        }
+    }
+
+    let size = size.ok_or_else(|| anyhow!("missing LUT_3D_SIZE"))?;
+    let expected = (size as usize).pow(3);
+    if table.len() != expected {
+        bail!(
+            "LUT_3D_SIZE {size} needs {expected} entries, got {}",
+            table.len()
+        );
+    }
+    if (0..3).any(|i| domain_max[i] <= domain_min[i]) {
+        bail!("DOMAIN_MAX must be greater than DOMAIN_MIN on every channel");
+    }
+    Ok(CubeLut {
+        size,
+        domain_min,
+        domain_max,
+        table,
+    })
+}
+
+/// Load and parse the `.cube` asset referenced by `LUTPass.assetId`.
+pub(crate) fn load_lut_asset(asset_store: Option<&AssetStore>, node: &Node) -> Result<CubeLut> {
+    let asset_id = node
+        .params
+        .get("assetId")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| {
+            anyhow!(
+                "LUTPass.params.assetId must be a non-empty string (node {})",
+                node.id
+            )
+        })?;
+    let store = asset_store.ok_or_else(|| {
+        anyhow!(
+            "LUTPass node '{}': has assetId '{asset_id}' but no asset store provided",
+            node.id
+        )
+    })?;
+    let data = store.get(asset_id).ok_or_else(|| {
+        anyhow!(
+            "LUTPass node '{}': asset '{asset_id}' not found in asset store",
+            node.id
+        )
+    })?;
+    let text = std::str::from_utf8(&data.bytes)
+        .with_context(|| format!("LUTPass asset '{asset_id}' is not UTF-8 text"))?;
+    parse_cube_lut(text).with_context(|| format!("LUTPass asset '{asset_id}': invalid .cube file"))
+}
+
+/// Lattice interpolation selected by `LUTPass.interpolation`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LutInterpolation {
+    #[default]
+    Trilinear,
+    Tetrahedral,
+}
+
+impl LutInterpolation {
+    pub(crate) fn from_params(params: &HashMap<String, Value>) -> Result<Self> {
+        let Some(raw) = params.get("interpolation") else {
+            return Ok(Self::default());
+        };
+        let name = raw
+            .as_str()
+            .ok_or_else(|| anyhow!("interpolation must be a string, got {raw}"))?;
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "trilinear" => Ok(Self::Trilinear),
+            "tetrahedral" => Ok(Self::Tetrahedral),
+            other => bail!("unknown interpolation '{other}', expected trilinear or tetrahedral"),
+        }
+    }
+}
+
+/// Resolved LUTPass params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LutSettings {
+    pub interpolation: LutInterpolation,
+    /// Blend between the source (0) and the graded color (1).
+    pub intensity: f32,
+    /// Look up sRGB-encoded values (display-referred LUTs) instead of linear ones.
+    pub srgb: bool,
+}
+
+pub(crate) fn lut_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<LutSettings> {
+    let srgb = match node
+        .params
+        .get("colorSpace")
+        .and_then(|v| v.as_str())
+        .unwrap_or("srgb")
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "srgb" => true,
+        "linear" => false,
+        other => bail!("unsupported LUTPass.colorSpace: {other}"),
+    };
+    Ok(LutSettings {
+        interpolation: LutInterpolation::from_params(&node.params)?,
+        intensity: cpu_num_f32(scene, nodes_by_id, node, "intensity", 1.0)?.clamp(0.0, 1.0),
+        srgb,
+    })
+}
+
+fn wgsl_vec3(v: [f32; 3]) -> String {
+    format!(
+        "vec3f({}, {}, {})",
+        fmt_f32(v[0]),
+        fmt_f32(v[1]),
+        fmt_f32(v[2])
+    )
+}
+
+pub(crate) fn build_lut_wgsl(lut: &CubeLut, settings: &LutSettings) -> String {
+    let blend = match settings.interpolation {
+        LutInterpolation::Trilinear => {
+            r#"
+ let c00 = mix(c000, c100, f.x);
+ let c10 = mix(c010, c110, f.x);
+ let c01 = mix(c001, c101, f.x);
+ let c11 = mix(c011, c111, f.x);
+ let graded = mix(mix(c00, c10, f.y), mix(c01, c11, f.y), f.z);"#
+        }
+        LutInterpolation::Tetrahedral => {
+            r#"
+ var graded: vec3f;
+ if (f.x > f.y) {
+     if (f.y > f.z) {
+         graded = (1.0 - f.x) * c000 + (f.x - f.y) * c100 + (f.y - f.z) * c110 + f.z * c111;
+     } else if (f.x > f.z) {
+         graded = (1.0 - f.x) * c000 + (f.x - f.z) * c100 + (f.z - f.y) * c101 + f.y * c111;
+     } else {
+         graded = (1.0 - f.z) * c000 + (f.z - f.x) * c001 + (f.x - f.y) * c101 + f.y * c111;
+     }
+ } else {
+     if (f.z > f.y) {
+         graded = (1.0 - f.z) * c000 + (f.z - f.y) * c001 + (f.y - f.x) * c011 + f.x * c111;
+     } else if (f.z > f.x) {
+         graded = (1.0 - f.y) * c000 + (f.y - f.z) * c010 + (f.z - f.x) * c011 + f.x * c111;
+     } else {
+         graded = (1.0 - f.y) * c000 + (f.y - f.x) * c010 + (f.x - f.z) * c110 + f.z * c111;
+     }
+ }"#
+        }
+    };
+    let (encode, decode) = if settings.srgb {
+        (
+            " let lookup = select(1.055 * pow(max(straight, vec3f(0.0)), vec3f(1.0 / 2.4)) - 0.055, straight * 12.92, straight <= vec3f(0.0031308));\n",
+            " let out_rgb = select(pow((graded + 0.055) / 1.055, vec3f(2.4)), graded / 12.92, graded <= vec3f(0.04045));\n",
+        )
+    } else {
+        (" let lookup = straight;\n", " let out_rgb = graded;\n")
+    };
+    let body = format!(
+        r#"
+ let src = textureSample(src_tex, src_samp, in.uv);
+ let straight = src.rgb / max(src.a, 1e-6);
+{encode} let t = clamp((lookup - {domain_min}) / ({domain_max} - {domain_min}), vec3f(0.0), vec3f(1.0)) * {last};
+ let base = min(vec3u(floor(t)), vec3u({last_u}u));
+ let f = t - vec3f(base);
+ let i = base.x + base.y * {n}u + base.z * {n2}u;
+ let c000 = baked_data_parse[i].rgb;
+ let c100 = baked_data_parse[i + 1u].rgb;
+ let c010 = baked_data_parse[i + {n}u].rgb;
+ let c110 = baked_data_parse[i + {n}u + 1u].rgb;
+ let c001 = baked_data_parse[i + {n2}u].rgb;
+ let c101 = baked_data_parse[i + {n2}u + 1u].rgb;
+ let c011 = baked_data_parse[i + {n2}u + {n}u].rgb;
+ let c111 = baked_data_parse[i + {n2}u + {n}u + 1u].rgb;{blend}
+{decode} let rgb = mix(straight, out_rgb, {intensity});
+ return vec4f(rgb * src.a, src.a);
+"#,
+        domain_min = wgsl_vec3(lut.domain_min),
+        domain_max = wgsl_vec3(lut.domain_max),
+        last = fmt_f32((lut.size - 1) as f32),
+        last_u = lut.size - 2,
+        n = lut.size,
+        n2 = lut.size * lut.size,
+        intensity = fmt_f32(settings.intensity),
+    );
+    let module = build_fullscreen_textured_bundle(body).module;
+    format!("@group(0) @binding(1)\nvar<storage, read> baked_data_parse: array<vec4f>;\n{module}")
+}
+
+/// Assemble a `"LUTPass"` layer.
+pub(crate) fn assemble_lut_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = lut_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let lut = load_lut_asset(sc.asset_store, layer_node)?;
+    let shader_wgsl = build_lut_wgsl(&lut, &settings);
+    assemble_fullscreen_filter_with_table(
+        sc,
+        bs,
+        layer_id,
+        layer_node,
+        "lut",
+        shader_wgsl,
+        Some(lut.storage_bytes()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    fn identity_cube(size: u32) -> String {
+        let mut text = format!("TITLE \"identity\"\n# comment\nLUT_3D_SIZE {size}\n");
+        let last = (size - 1) as f32;
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    text.push_str(&format!(
+                        "{} {} {}\n",
+                        r as f32 / last,
+                        g as f32 / last,
+                        b as f32 / last
+                    ));
+                }
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn cube_parser_reads_table_in_red_fastest_order() {
+        let lut = parse_cube_lut(&identity_cube(3)).unwrap();
+        assert_eq!(lut.size, 3);
+        assert_eq!(lut.domain_min, [0.0; 3]);
+        assert_eq!(lut.domain_max, [1.0; 3]);
+        assert_eq!(lut.table.len(), 27);
+        assert_eq!(lut.table[1], [0.5, 0.0, 0.0]);
+        assert_eq!(lut.table[3], [0.0, 0.5, 0.0]);
+        assert_eq!(lut.table[9], [0.0, 0.0, 0.5]);
+        assert_eq!(lut.storage_bytes().len(), 27 * 16);
+    }
+
+    #[test]
+    fn cube_parser_rejects_malformed_files() {
+        assert!(parse_cube_lut("LUT_1D_SIZE 2\n0 0 0\n1 1 1\n").is_err());
+        assert!(parse_cube_lut("0 0 0\n").is_err());
+        assert!(parse_cube_lut("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        let bad_domain = format!("DOMAIN_MIN 1 1 1\n{}", identity_cube(2));
+        assert!(parse_cube_lut(&bad_domain).is_err());
+    }
+
+    #[test]
+    fn lut_shaders_validate_for_both_interpolations() {
+        let lut = parse_cube_lut(&identity_cube(2)).unwrap();
+        for interpolation in [LutInterpolation::Trilinear, LutInterpolation::Tetrahedral] {
+            for srgb in [true, false] {
+                let settings = LutSettings {
+                    interpolation,
+                    intensity: 0.75,
+                    srgb,
+                };
+                let wgsl = build_lut_wgsl(&lut, &settings);
+                validate_wgsl_module(&wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+            }
+        }
+    }
+}
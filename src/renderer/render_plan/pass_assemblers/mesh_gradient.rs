@@ -726,7 +726,7 @@ fn parse_vec2_value(value: &Value) -> Option<[f32; 2]> {
     None
 }
 
-fn parse_color_from_params(
+pub(crate) fn parse_color_from_params(
     params: &std::collections::HashMap<String, Value>,
     key: &str,
 ) -> Option<[f32; 4]> {
@@ -2,15 +2,29 @@
 //!
 //! Each sub-module exposes an `assemble_*` function that receives
 //! `&SceneContext` + `&mut BuilderState` + the layer information, and pushes
-//! textures, geometry buffers, and `RenderPassSpec`s into the builder state.
+//! textures, geometry buffers, and `RenderPassSpec`s (or `ComputePassSpec`s)
+//! into the builder state.
 
+pub(crate) mod anti_alias;
 pub(crate) mod args;
 pub(crate) mod bloom;
 pub(crate) mod composite;
+pub(crate) mod compute;
+pub(crate) mod convolution;
+pub(crate) mod displace;
+pub(crate) mod dof;
 pub(crate) mod downsample;
+pub(crate) mod drop_shadow;
+pub(crate) mod edge_detect;
+pub(crate) mod feedback;
+pub(crate) mod finishing;
+pub(crate) mod fullscreen_filter;
 pub(crate) mod gaussian_blur;
 pub(crate) mod gradient_blur;
 pub(crate) mod intelligent_light;
+pub(crate) mod lens_distortion;
+pub(crate) mod lut;
 pub(crate) mod mesh_gradient;
 pub(crate) mod render_pass;
+pub(crate) mod stylize;
 pub(crate) mod upsample;
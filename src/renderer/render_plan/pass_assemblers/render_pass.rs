@@ -7,7 +7,7 @@
 
 use std::{collections::HashMap, sync::Arc};
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use rust_wgpu_fiber::{
     ResourceName,
     eframe::wgpu::{self, Color, TextureFormat},
@@ -21,10 +21,10 @@ use crate::{
         graph_uniforms::{choose_graph_binding_kind, pack_graph_values},
         node_compiler::geometry_nodes::{rect2d_geometry_vertices, rect2d_unit_geometry_vertices},
         render_plan::types::ShaderParameterBufferPlan,
-        scene_prep::bake_data_parse_nodes,
+        scene_prep::{bake_data_parse_nodes, resolve_instance_attributes},
         types::{
             BakedDataParseMeta, BakedValue, GraphBinding, GraphBindingKind, MaterialCompileContext,
-            PassOutputSpec,
+            PassOutputSpec, WgslShaderBundle,
         },
         utils::{as_bytes_slice, cpu_num_u32_floor},
         wgsl::{
@@ -42,9 +42,14 @@ use super::super::resource_naming::{
     parse_render_pass_cull_mode, parse_render_pass_depth_test, readable_pass_name_for_node,
     sampled_render_pass_output_size, select_effective_msaa_sample_count,
 };
+use super::super::stencil::{
+    PassStencilAttachment, STENCIL_ATTACHMENT_FORMAT, parse_render_pass_stencil,
+    with_stencil_coverage_discard,
+};
+use super::super::viewport::{parse_pass_rect, with_fragment_clip_rect};
 use super::args::{BuilderState, SceneContext, make_fullscreen_geometry};
 use crate::renderer::shader_space::sampler::{
-    sampler_kind_for_pass_texture, sampler_kind_from_node_params,
+    image_texture_binding, sampler_kind_for_pass_texture,
 };
 
 /// Assemble a `"RenderPass"` layer.
@@ -136,6 +141,26 @@ pub(crate) fn assemble_render_pass(
                     crate::dsl::node_display_label_with_id(layer_node)
                 )
             })?;
+    let load_op = crate::renderer::render_plan::parse_render_pass_load_op(&layer_node.params)
+        .with_context(|| {
+            format!(
+                "invalid load op params for {}",
+                crate::dsl::node_display_label_with_id(layer_node)
+            )
+        })?;
+    let [viewport, scissor] = ["viewport", "scissor"].map(|key| {
+        parse_pass_rect(&layer_node.params, key).with_context(|| {
+            format!(
+                "invalid {key} params for {}",
+                crate::dsl::node_display_label_with_id(layer_node)
+            )
+        })
+    });
+    let (viewport, scissor) = (viewport?, scissor?);
+    let clip_rect = match (viewport, scissor) {
+        (Some(v), Some(s)) => Some(v.intersect(&s)),
+        (v, s) => v.or(s),
+    };
     let cull_mode = parse_render_pass_cull_mode(&layer_node.params).with_context(|| {
         format!(
             "invalid culling params for {}",
@@ -149,6 +174,18 @@ pub(crate) fn assemble_render_pass(
                 crate::dsl::node_display_label_with_id(layer_node)
             )
         })?;
+    let stencil = parse_render_pass_stencil(&layer_node.params).with_context(|| {
+        format!(
+            "invalid stencil params for {}",
+            crate::dsl::node_display_label_with_id(layer_node)
+        )
+    })?;
+    if depth_test_enabled && stencil.is_some() {
+        bail!(
+            "{}: depthTest cannot be combined with stencilWrite/stencilTest",
+            crate::dsl::node_display_label_with_id(layer_node)
+        );
+    }
 
     let render_geo_node_id = incoming_connection(&prepared.scene, layer_id, "geometry")
         .map(|c| c.from.node_id.clone())
@@ -261,6 +298,39 @@ pub(crate) fn assemble_render_pass(
     } else {
         None
     };
+    // Stencil masks live on one attachment per render target so a later pass
+    // drawing into the same texture can test what an earlier pass wrote.
+    let stencil_attachment = if let Some(stencil) = stencil {
+        let stencil_tex: ResourceName = format!(
+            "sys.stencil.{}.{}",
+            pass_render_target_texture.as_str(),
+            msaa_sample_count
+        )
+        .into();
+        let first_use = !bs.textures.iter().any(|decl| decl.name == stencil_tex);
+        if first_use {
+            if !stencil.writes {
+                bail!(
+                    "{}: stencilTest requires an earlier pass with stencilWrite targeting the same texture",
+                    crate::dsl::node_display_label_with_id(layer_node)
+                );
+            }
+            bs.textures.push(TextureDecl {
+                name: stencil_tex.clone(),
+                size: [pass_target_w_u, pass_target_h_u],
+                format: STENCIL_ATTACHMENT_FORMAT,
+                sample_count: msaa_sample_count,
+                needs_sampling: false,
+            });
+        }
+        Some(PassStencilAttachment {
+            texture: stencil_tex,
+            stencil,
+            clear: first_use,
+        })
+    } else {
+        None
+    };
     let pass_target_w = pass_target_w_u as f32;
     let pass_target_h = pass_target_h_u as f32;
 
@@ -283,10 +353,13 @@ pub(crate) fn assemble_render_pass(
         slot_by_output.insert(k.clone(), i as u32);
     }
 
+    let attribute_slots = resolve_instance_attributes(layer_node, layer_id, &slot_by_output)?;
+
     let meta = Arc::new(BakedDataParseMeta {
         pass_id: layer_id.to_string(),
         outputs_per_instance: keys.len() as u32,
         slot_by_output,
+        attribute_slots,
     });
 
     let mut packed: Vec<f32> = Vec::new();
@@ -437,6 +510,13 @@ pub(crate) fn assemble_render_pass(
 
     let params_name: ResourceName = format!("params.{layer_id}").into();
     let mut params = main_pass_params;
+    if let Some(viewport) = viewport {
+        // Squeeze the pass's clip space into the viewport rect of its target.
+        params.camera = crate::renderer::camera::mat4_mul_col_major(
+            viewport.viewport_clip_transform([pass_target_w, pass_target_h]),
+            params.camera,
+        );
+    }
 
     // Resolve camera world-space position for ViewVector nodes.
     let cam_pos = crate::renderer::camera::resolve_camera_position_for_pass_node(
@@ -461,120 +541,59 @@ pub(crate) fn assemble_render_pass(
     let has_normals = normals_bytes.is_some();
     let fullscreen_vertex_positioning = use_fullscreen_main_pass && rect_dyn_2.is_some();
 
-    let mut bundle = build_pass_wgsl_bundle_with_graph_binding(
-        &prepared.scene,
-        nodes_by_id,
-        Some(baked_arc.clone()),
-        bs.baked_data_parse_meta_by_pass.get(layer_id).cloned(),
-        layer_id,
-        is_instanced,
-        translate_expr_wgsl.clone(),
-        vertex_inline_stmts_for_bundle.clone(),
-        vertex_wgsl_decls_for_bundle.clone(),
-        vertex_uses_instance_index,
-        main_pass_rect_dyn.clone(),
-        vertex_graph_input_kinds_for_bundle.clone(),
-        None,
-        fullscreen_vertex_positioning,
-        has_normals,
-    )?;
-
-    let mut graph_binding: Option<GraphBinding> = None;
-    let mut graph_values: Option<Vec<u8>> = None;
-    if let Some(schema) = bundle.graph_schema.clone() {
-        let limits = device.limits();
-        let kind = choose_graph_binding_kind(
-            schema.size_bytes,
-            limits.max_uniform_buffer_binding_size as u64,
-            limits.max_storage_buffer_binding_size as u64,
-        )?;
-
-        if bundle.graph_binding_kind != Some(kind) {
-            bundle = build_pass_wgsl_bundle_with_graph_binding(
-                &prepared.scene,
-                nodes_by_id,
-                Some(baked_arc.clone()),
-                bs.baked_data_parse_meta_by_pass.get(layer_id).cloned(),
-                layer_id,
-                is_instanced,
-                translate_expr_wgsl.clone(),
-                vertex_inline_stmts_for_bundle.clone(),
-                vertex_wgsl_decls_for_bundle.clone(),
-                vertex_uses_instance_index,
-                main_pass_rect_dyn.clone(),
-                vertex_graph_input_kinds_for_bundle.clone(),
-                Some(kind),
-                fullscreen_vertex_positioning,
-                has_normals,
-            )?;
-        }
-
-        let schema = bundle
-            .graph_schema
-            .clone()
-            .ok_or_else(|| anyhow!("missing graph schema after graph binding selection"))?;
-        let graph_buffer_name: ResourceName = format!("params.{layer_id}.graph").into();
-        let values = pack_graph_values(&prepared.scene, &schema)?;
-        graph_values = Some(values);
-        graph_binding = Some(GraphBinding {
-            buffer_name: graph_buffer_name,
-            kind,
-            schema,
-        });
-    }
-
-    if let Some(schema) = bundle.shader_parameter_schema.clone() {
-        let max_storage_bytes = device.limits().max_storage_buffer_binding_size as u64;
-        if schema.size_bytes > max_storage_bytes {
-            return Err(anyhow!(
-                "ShaderMaterial parameter buffer size {} exceeds device storage-buffer limit {}",
-                schema.size_bytes,
-                max_storage_bytes
-            ));
-        }
-        let binding = GraphBinding {
-            buffer_name: format!("params.{layer_id}.shader_material").into(),
-            kind: GraphBindingKind::StorageRead,
-            schema,
-        };
-        let values = pack_graph_values(&prepared.scene, &binding.schema)?;
-        bs.shader_parameter_buffers_by_pass.insert(
-            pass_name.as_str().to_string(),
-            ShaderParameterBufferPlan { binding, values },
-        );
-    }
-
-    let shader_wgsl = bundle.module;
-
-    let mut texture_bindings: Vec<PassTextureBinding> = Vec::new();
-    let mut sampler_kinds: Vec<SamplerKind> = Vec::new();
-
-    // ImageTexture bindings first.
-    for id in bundle.image_textures.iter() {
-        let Some(tex) = ids.get(id).cloned() else {
-            continue;
-        };
-        texture_bindings.push(PassTextureBinding {
-            texture: tex,
-            image_node_id: Some(id.clone()),
-        });
-        let kind = nodes_by_id
-            .get(id)
-            .map(|n| sampler_kind_from_node_params(&n.params))
-            .unwrap_or(SamplerKind::LinearClamp);
-        sampler_kinds.push(kind);
-    }
+    let baked_meta = bs.baked_data_parse_meta_by_pass.get(layer_id).cloned();
+    let build_bundle = |graph_binding_kind: Option<GraphBindingKind>| {
+        build_pass_wgsl_bundle_with_graph_binding(
+            &prepared.scene,
+            nodes_by_id,
+            Some(baked_arc.clone()),
+            baked_meta.clone(),
+            layer_id,
+            is_instanced,
+            translate_expr_wgsl.clone(),
+            vertex_inline_stmts_for_bundle.clone(),
+            vertex_wgsl_decls_for_bundle.clone(),
+            vertex_uses_instance_index,
+            main_pass_rect_dyn.clone(),
+            vertex_graph_input_kinds_for_bundle.clone(),
+            graph_binding_kind,
+            fullscreen_vertex_positioning,
+            has_normals,
+            sc.channel_debug,
+        )
+    };
 
-    // PassTexture bindings next.
-    let pass_bindings = crate::renderer::render_plan::resolve_pass_texture_bindings(
-        &bs.pass_output_registry,
-        &bundle.pass_textures,
+    let (bundle, graph_binding, graph_values) = select_graph_binding(
+        sc,
+        build_bundle(None)?,
+        format!("params.{layer_id}.graph").into(),
+        |kind| build_bundle(Some(kind)),
     )?;
-    for (texture_ref, binding) in bundle.pass_textures.iter().zip(pass_bindings) {
-        texture_bindings.push(binding);
-        sampler_kinds.push(sampler_kind_for_pass_texture(&prepared.scene, texture_ref));
+    if let Some(plan) = shader_parameter_buffer_plan(
+        sc,
+        &bundle,
+        format!("params.{layer_id}.shader_material").into(),
+    )? {
+        bs.shader_parameter_buffers_by_pass
+            .insert(pass_name.as_str().to_string(), plan);
     }
 
+    let (texture_bindings, sampler_kinds) = material_texture_bindings(sc, bs, &bundle)?;
+    let shader_wgsl = match &clip_rect {
+        Some(rect) => with_fragment_clip_rect(&bundle.module, rect)?,
+        None => bundle.module,
+    };
+    let shader_wgsl = if stencil.is_some_and(|s| s.writes) {
+        with_stencil_coverage_discard(&shader_wgsl).with_context(|| {
+            format!(
+                "stencilWrite coverage for {}",
+                crate::dsl::node_display_label_with_id(layer_node)
+            )
+        })?
+    } else {
+        shader_wgsl
+    };
+
     let instance_buffer = if is_instanced {
         let b: ResourceName = format!("sys.pass.{layer_id}.instances").into();
 
@@ -607,15 +626,16 @@ pub(crate) fn assemble_render_pass(
         Some(baked_buf_name.clone())
     };
 
+    let normals_buffer: Option<ResourceName> = normals_bytes
+        .as_ref()
+        .map(|_| format!("{}.normals", main_pass_geometry_buffer).into());
+
     bs.render_pass_specs.push(RenderPassSpec {
         pass_id: pass_name.as_str().to_string(),
         name: pass_name.clone(),
         geometry_buffer: main_pass_geometry_buffer.clone(),
-        instance_buffer,
-        normals_buffer: normals_bytes.as_ref().map(|_| {
-            let nb_name: ResourceName = format!("{}.normals", main_pass_geometry_buffer).into();
-            nb_name
-        }),
+        instance_buffer: instance_buffer.clone(),
+        normals_buffer: normals_buffer.clone(),
         vertex_layout: Default::default(),
         target_texture: pass_render_target_texture.clone(),
         resolve_target: pass_resolve_target,
@@ -633,11 +653,19 @@ pub(crate) fn assemble_render_pass(
     });
     bs.pass_cull_mode_by_name
         .insert(pass_name.clone(), cull_mode);
+    bs.pass_load_op_by_name.insert(pass_name.clone(), load_op);
+    if let Some(rect) = clip_rect {
+        bs.pass_clip_rect_by_name.insert(pass_name.clone(), rect);
+    }
     if let Some(depth_attachment) = depth_stencil_attachment.clone() {
         bs.pass_depth_attachment_by_name
             .insert(pass_name.clone(), depth_attachment);
     }
-    bs.composite_passes.push(pass_name);
+    if let Some(stencil_attachment) = stencil_attachment {
+        bs.pass_stencil_by_name
+            .insert(pass_name.clone(), stencil_attachment);
+    }
+    bs.composite_passes.push(pass_name.clone());
 
     // Build depth-resolve pass BEFORE compose passes so that it
     // executes first and the resolved texture is ready to sample.
@@ -966,3 +994,94 @@ pub(crate) fn assemble_render_pass(
 
     Ok(())
 }
+
+/// Pick the graph buffer binding for `bundle` against the device limits,
+/// rebuilding the shader when the chosen kind differs from the default.
+fn select_graph_binding(
+    sc: &SceneContext<'_>,
+    mut bundle: WgslShaderBundle,
+    buffer_name: ResourceName,
+    rebuild: impl Fn(GraphBindingKind) -> Result<WgslShaderBundle>,
+) -> Result<(WgslShaderBundle, Option<GraphBinding>, Option<Vec<u8>>)> {
+    let Some(schema) = bundle.graph_schema.clone() else {
+        return Ok((bundle, None, None));
+    };
+    let limits = sc.device.limits();
+    let kind = choose_graph_binding_kind(
+        schema.size_bytes,
+        limits.max_uniform_buffer_binding_size as u64,
+        limits.max_storage_buffer_binding_size as u64,
+    )?;
+    if bundle.graph_binding_kind != Some(kind) {
+        bundle = rebuild(kind)?;
+    }
+
+    let schema = bundle
+        .graph_schema
+        .clone()
+        .ok_or_else(|| anyhow!("missing graph schema after graph binding selection"))?;
+    let values = pack_graph_values(sc.scene(), &schema)?;
+    Ok((
+        bundle,
+        Some(GraphBinding {
+            buffer_name,
+            kind,
+            schema,
+        }),
+        Some(values),
+    ))
+}
+
+/// Storage buffer plan for ShaderMaterial parameters used by `bundle`, if any.
+fn shader_parameter_buffer_plan(
+    sc: &SceneContext<'_>,
+    bundle: &WgslShaderBundle,
+    buffer_name: ResourceName,
+) -> Result<Option<ShaderParameterBufferPlan>> {
+    let Some(schema) = bundle.shader_parameter_schema.clone() else {
+        return Ok(None);
+    };
+    let max_storage_bytes = sc.device.limits().max_storage_buffer_binding_size as u64;
+    if schema.size_bytes > max_storage_bytes {
+        return Err(anyhow!(
+            "ShaderMaterial parameter buffer size {} exceeds device storage-buffer limit {}",
+            schema.size_bytes,
+            max_storage_bytes
+        ));
+    }
+    let binding = GraphBinding {
+        buffer_name,
+        kind: GraphBindingKind::StorageRead,
+        schema,
+    };
+    let values = pack_graph_values(sc.scene(), &binding.schema)?;
+    Ok(Some(ShaderParameterBufferPlan { binding, values }))
+}
+
+/// Texture bindings for `bundle`: ImageTexture bindings first, then PassTextures.
+fn material_texture_bindings(
+    sc: &SceneContext<'_>,
+    bs: &BuilderState<'_>,
+    bundle: &WgslShaderBundle,
+) -> Result<(Vec<PassTextureBinding>, Vec<SamplerKind>)> {
+    let mut texture_bindings: Vec<PassTextureBinding> = Vec::new();
+    let mut sampler_kinds: Vec<SamplerKind> = Vec::new();
+
+    for id in bundle.image_textures.iter() {
+        let Some((binding, kind)) = image_texture_binding(sc.ids(), sc.nodes_by_id(), id) else {
+            continue;
+        };
+        texture_bindings.push(binding);
+        sampler_kinds.push(kind);
+    }
+
+    let pass_bindings = crate::renderer::render_plan::resolve_pass_texture_bindings(
+        &bs.pass_output_registry,
+        &bundle.pass_textures,
+    )?;
+    for (texture_ref, binding) in bundle.pass_textures.iter().zip(pass_bindings) {
+        texture_bindings.push(binding);
+        sampler_kinds.push(sampler_kind_for_pass_texture(sc.scene(), texture_ref));
+    }
+    Ok((texture_bindings, sampler_kinds))
+}
@@ -0,0 +1,412 @@
+//! Stylize pass assemblers: `Pixelate`, `Posterize`, and `Dither`.
+//!
+//! Each is a single fullscreen REPLACE pass over its `pass` input. Posterize and
+//! Dither quantize straight (un-premultiplied) color and re-apply coverage, so
+//! soft edges keep their alpha. Dither thresholds come either from an ordered
+//! Bayer matrix inlined into the shader or from a 64x64 blue-noise tile generated
+//! once with void-and-cluster and bound as a storage table.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::Value;
+
+use crate::{
+    dsl::{Node, SceneDSL},
+    renderer::{
+        utils::{as_bytes_slice, cpu_num_f32, fmt_f32},
+        wgsl::build_fullscreen_textured_bundle,
+    },
+};
+
+use super::args::{BuilderState, SceneContext};
+use super::fullscreen_filter::{assemble_fullscreen_filter, assemble_fullscreen_filter_with_table};
+
+/// Side of the generated blue-noise tile, in pixels.
+pub(crate) const BLUE_NOISE_SIZE: usize = 64;
+
+/// Quantize straight color to `levels` steps per channel; `threshold` (WGSL,
+/// 0..1) replaces the 0.5 rounding offset when dithering.
+fn quantize_body(levels: f32, threshold: &str) -> String {
+    format!(
+        r#" let straight = src.rgb / max(src.a, 1e-6);
+ let steps = {steps};
+ let q = floor(clamp(straight, vec3f(0.0), vec3f(1.0)) * steps + vec3f({threshold})) / steps;
+ return vec4f(min(q, vec3f(1.0)) * src.a, src.a);
+"#,
+        steps = fmt_f32(levels - 1.0),
+    )
+}
+
+fn levels_param(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+    default: f32,
+) -> Result<f32> {
+    Ok(cpu_num_f32(scene, nodes_by_id, node, "levels", default)?
+        .round()
+        .clamp(2.0, 256.0))
+}
+
+/// Resolved Pixelate params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PixelateSettings {
+    /// Block edge in pixels (>= 1).
+    pub block_size_px: f32,
+}
+
+pub(crate) fn pixelate_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<PixelateSettings> {
+    Ok(PixelateSettings {
+        block_size_px: cpu_num_f32(scene, nodes_by_id, node, "blockSize", 8.0)?
+            .round()
+            .max(1.0),
+    })
+}
+
+pub(crate) fn build_pixelate_wgsl(settings: &PixelateSettings) -> String {
+    let body = format!(
+        r#"
+ let dims = vec2f(textureDimensions(src_tex));
+ let block = {block};
+ let cell = floor(in.uv * dims / block);
+ let uv = min((cell + vec2f(0.5)) * block, dims - vec2f(0.5)) / dims;
+ return textureSampleLevel(src_tex, src_samp, uv, 0.0);
+"#,
+        block = fmt_f32(settings.block_size_px),
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Resolved Posterize params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PosterizeSettings {
+    /// Output levels per channel, 2..=256.
+    pub levels: f32,
+}
+
+pub(crate) fn posterize_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<PosterizeSettings> {
+    Ok(PosterizeSettings {
+        levels: levels_param(scene, nodes_by_id, node, 4.0)?,
+    })
+}
+
+pub(crate) fn build_posterize_wgsl(settings: &PosterizeSettings) -> String {
+    let body = format!(
+        "\n let src = textureSample(src_tex, src_samp, in.uv);\n{}",
+        quantize_body(settings.levels, "0.5")
+    );
+    build_fullscreen_textured_bundle(body).module
+}
+
+/// Threshold pattern selected by `Dither.pattern`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DitherPattern {
+    /// Ordered dither with an `n x n` Bayer matrix (`n` in 2, 4, 8).
+    Bayer(u32),
+    BlueNoise,
+}
+
+impl DitherPattern {
+    pub(crate) fn from_params(params: &HashMap<String, Value>) -> Result<Self> {
+        let name = match params.get("pattern") {
+            None => "bayer",
+            Some(raw) => raw
+                .as_str()
+                .ok_or_else(|| anyhow!("pattern must be a string, got {raw}"))?,
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "bayer" => {
+                let size = params
+                    .get("bayerSize")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(4);
+                match size {
+                    2 | 4 | 8 => Ok(Self::Bayer(size as u32)),
+                    other => bail!("bayerSize must be 2, 4, or 8, got {other}"),
+                }
+            }
+            "bluenoise" | "blue-noise" | "blue_noise" => Ok(Self::BlueNoise),
+            other => bail!("unknown pattern '{other}', expected bayer or blueNoise"),
+        }
+    }
+}
+
+/// Resolved Dither params.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DitherSettings {
+    pub pattern: DitherPattern,
+    /// Output levels per channel, 2..=256.
+    pub levels: f32,
+}
+
+pub(crate) fn dither_settings(
+    scene: &SceneDSL,
+    nodes_by_id: &HashMap<String, Node>,
+    node: &Node,
+) -> Result<DitherSettings> {
+    Ok(DitherSettings {
+        pattern: DitherPattern::from_params(&node.params)?,
+        levels: levels_param(scene, nodes_by_id, node, 2.0)?,
+    })
+}
+
+/// Bayer index matrix of side `n` (a power of two), row-major.
+pub(crate) fn bayer_matrix(n: u32) -> Vec<u32> {
+    let mut m = vec![0u32];
+    let mut side = 1usize;
+    while side < n as usize {
+        let next = side * 2;
+        let mut grown = vec![0u32; next * next];
+        for y in 0..side {
+            for x in 0..side {
+                let v = 4 * m[y * side + x];
+                grown[y * next + x] = v;
+                grown[y * next + x + side] = v + 2;
+                grown[(y + side) * next + x] = v + 3;
+                grown[(y + side) * next + x + side] = v + 1;
+            }
+        }
+        m = grown;
+        side = next;
+    }
+    m
+}
+
+/// Ranks of a `BLUE_NOISE_SIZE`² blue-noise tile, generated once with Ulichney's
+/// void-and-cluster method on a torus.
+pub(crate) fn blue_noise_ranks() -> &'static [u32] {
+    static RANKS: OnceLock<Vec<u32>> = OnceLock::new();
+    RANKS.get_or_init(|| void_and_cluster(BLUE_NOISE_SIZE, 1.5))
+}
+
+fn void_and_cluster(size: usize, sigma: f32) -> Vec<u32> {
+    let n = size * size;
+    // Gaussian weight for every toroidal offset.
+    let mut kernel = vec![0.0f32; n];
+    for dy in 0..size {
+        for dx in 0..size {
+            let wx = dx.min(size - dx) as f32;
+            let wy = dy.min(size - dy) as f32;
+            kernel[dy * size + dx] = (-(wx * wx + wy * wy) / (2.0 * sigma * sigma)).exp();
+        }
+    }
+    let splat = |energy: &mut [f32], at: usize, sign: f32| {
+        let (ax, ay) = (at % size, at / size);
+        for y in 0..size {
+            let dy = (y + size - ay) % size;
+            for x in 0..size {
+                let dx = (x + size - ax) % size;
+                energy[y * size + x] += sign * kernel[dy * size + dx];
+            }
+        }
+    };
+    let pick = |bits: &[bool], energy: &[f32], want: bool, tightest: bool| -> usize {
+        let mut best = usize::MAX;
+        for i in 0..n {
+            if bits[i] != want {
+                continue;
+            }
+            let better = best == usize::MAX
+                || if tightest {
+                    energy[i] > energy[best]
+                } else {
+                    energy[i] < energy[best]
+                };
+            if better {
+                best = i;
+            }
+        }
+        best
+    };
+
+    // Deterministic initial pattern with ~10% minority pixels.
+    let mut state = 0x9e37_79b9_u32;
+    let mut bits = vec![false; n];
+    let mut energy = vec![0.0f32; n];
+    let mut ones = 0usize;
+    while ones < n / 10 {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        let i = state as usize % n;
+        if !bits[i] {
+            bits[i] = true;
+            splat(&mut energy, i, 1.0);
+            ones += 1;
+        }
+    }
+    // Spread the initial points: move the tightest cluster into the largest void
+    // until that no longer changes anything (bounded in case it oscillates).
+    for _ in 0..n {
+        let cluster = pick(&bits, &energy, true, true);
+        bits[cluster] = false;
+        splat(&mut energy, cluster, -1.0);
+        let void = pick(&bits, &energy, false, false);
+        bits[void] = true;
+        splat(&mut energy, void, 1.0);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0u32; n];
+    // Phase 1: rank the initial points by removing tightest clusters.
+    let (mut phase_bits, mut phase_energy) = (bits.clone(), energy.clone());
+    for rank in (0..ones).rev() {
+        let cluster = pick(&phase_bits, &phase_energy, true, true);
+        phase_bits[cluster] = false;
+        splat(&mut phase_energy, cluster, -1.0);
+        ranks[cluster] = rank as u32;
+    }
+    // Phases 2 and 3: fill the largest voids. Past half coverage, the tightest
+    // cluster of zeros is exactly the zero with the lowest energy from the ones.
+    for rank in ones..n {
+        let void = pick(&bits, &energy, false, false);
+        bits[void] = true;
+        splat(&mut energy, void, 1.0);
+        ranks[void] = rank as u32;
+    }
+    ranks
+}
+
+/// Blue-noise thresholds packed four per `vec4f` for the storage table.
+fn blue_noise_table() -> Arc<[u8]> {
+    let n = (BLUE_NOISE_SIZE * BLUE_NOISE_SIZE) as f32;
+    let thresholds: Vec<f32> = blue_noise_ranks()
+        .iter()
+        .map(|rank| (*rank as f32 + 0.5) / n)
+        .collect();
+    Arc::from(as_bytes_slice(&thresholds).to_vec())
+}
+
+pub(crate) fn build_dither_wgsl(settings: &DitherSettings) -> String {
+    let (decls, threshold) = match settings.pattern {
+        DitherPattern::Bayer(n) => {
+            let cells = n * n;
+            let values: Vec<String> = bayer_matrix(n)
+                .into_iter()
+                .map(|v| fmt_f32((v as f32 + 0.5) / cells as f32))
+                .collect();
+            (
+                String::new(),
+                format!(
+                    " let bayer = array<f32, {cells}>({values});\n let cell = pixel % vec2u({n}u);\n let threshold = bayer[cell.y * {n}u + cell.x];\n",
+                    values = values.join(", "),
+                ),
+            )
+        }
+        DitherPattern::BlueNoise => (
+            "@group(0) @binding(1)\nvar<storage, read> baked_data_parse: array<vec4f>;\n"
+                .to_string(),
+            format!(
+                " let cell = pixel % vec2u({size}u);\n let idx = cell.y * {size}u + cell.x;\n let threshold = baked_data_parse[idx >> 2u][idx & 3u];\n",
+                size = BLUE_NOISE_SIZE,
+            ),
+        ),
+    };
+    let body = format!(
+        "\n let src = textureSample(src_tex, src_samp, in.uv);\n let pixel = vec2u(in.uv * vec2f(textureDimensions(src_tex)));\n{threshold}{}",
+        quantize_body(settings.levels, "threshold")
+    );
+    format!("{decls}{}", build_fullscreen_textured_bundle(body).module)
+}
+
+/// Assemble a `"Pixelate"` layer.
+pub(crate) fn assemble_pixelate_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = pixelate_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_pixelate_wgsl(&settings);
+    assemble_fullscreen_filter(sc, bs, layer_id, layer_node, "pixelate", shader_wgsl)
+}
+
+/// Assemble a `"Posterize"` layer.
+pub(crate) fn assemble_posterize_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = posterize_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_posterize_wgsl(&settings);
+    assemble_fullscreen_filter(sc, bs, layer_id, layer_node, "posterize", shader_wgsl)
+}
+
+/// Assemble a `"Dither"` layer.
+pub(crate) fn assemble_dither_pass(
+    sc: &SceneContext<'_>,
+    bs: &mut BuilderState<'_>,
+    layer_id: &str,
+    layer_node: &Node,
+) -> Result<()> {
+    let settings = dither_settings(sc.scene(), sc.nodes_by_id(), layer_node)?;
+    let shader_wgsl = build_dither_wgsl(&settings);
+    let table = (settings.pattern == DitherPattern::BlueNoise).then(blue_noise_table);
+    assemble_fullscreen_filter_with_table(
+        sc,
+        bs,
+        layer_id,
+        layer_node,
+        "dither",
+        shader_wgsl,
+        table,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::validation::validate_wgsl_module;
+
+    #[test]
+    fn bayer_and_blue_noise_ranks_are_permutations() {
+        assert_eq!(bayer_matrix(2), vec![0, 2, 3, 1]);
+        for (ranks, n) in [
+            (bayer_matrix(8), 64usize),
+            (
+                blue_noise_ranks().to_vec(),
+                BLUE_NOISE_SIZE * BLUE_NOISE_SIZE,
+            ),
+        ] {
+            let mut sorted = ranks.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..n as u32).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn stylize_shaders_validate() {
+        let mut modules = vec![
+            build_pixelate_wgsl(&PixelateSettings { block_size_px: 8.0 }),
+            build_posterize_wgsl(&PosterizeSettings { levels: 4.0 }),
+        ];
+        for pattern in [
+            DitherPattern::Bayer(2),
+            DitherPattern::Bayer(8),
+            DitherPattern::BlueNoise,
+        ] {
+            modules.push(build_dither_wgsl(&DitherSettings {
+                pattern,
+                levels: 2.0,
+            }));
+        }
+        for wgsl in &modules {
+            validate_wgsl_module(wgsl).unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+        }
+    }
+}
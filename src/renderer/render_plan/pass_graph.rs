@@ -76,9 +76,9 @@ fn deps_for_pass_node(
                 .map(|texture_ref| texture_ref.source.node_id)
                 .collect())
         }
-        "BloomNode" => {
+        "BloomNode" | "BloomPass" => {
             let source_conn = incoming_connection(scene, pass_node_id, "pass")
-                .ok_or_else(|| anyhow!("BloomNode.pass missing for {pass_node_id}"))?;
+                .ok_or_else(|| anyhow!("{}.pass missing for {pass_node_id}", node.node_type))?;
             Ok(vec![source_conn.from.node_id.clone()])
         }
         "Downsample" => {
@@ -94,7 +94,73 @@ fn deps_for_pass_node(
             Ok(vec![source_conn.from.node_id.clone()])
         }
         "Composite" => composite_layers_in_draw_order(scene, nodes_by_id, pass_node_id),
+        "FeedbackPass" => {
+            // The previous frame is internal history, not a graph dependency.
+            Ok(incoming_connection(scene, pass_node_id, "pass")
+                .map(|conn| vec![conn.from.node_id.clone()])
+                .unwrap_or_default())
+        }
+        "ConvolutionPass" => {
+            let source_conn = incoming_connection(scene, pass_node_id, "pass")
+                .ok_or_else(|| anyhow!("ConvolutionPass.pass missing for {pass_node_id}"))?;
+            Ok(vec![source_conn.from.node_id.clone()])
+        }
+        "EdgeDetect"
+        | "ChromaticAberration"
+        | "Vignette"
+        | "FilmGrain"
+        | "LensDistortion"
+        | "LUTPass"
+        | "Pixelate"
+        | "Posterize"
+        | "Dither"
+        | "DropShadow"
+        | "AntiAliasPass" => {
+            let source_conn = incoming_connection(scene, pass_node_id, "pass")
+                .ok_or_else(|| anyhow!("{}.pass missing for {pass_node_id}", node.node_type))?;
+            Ok(vec![source_conn.from.node_id.clone()])
+        }
+        "Displace" => {
+            let source_conn = incoming_connection(scene, pass_node_id, "pass")
+                .ok_or_else(|| anyhow!("Displace.pass missing for {pass_node_id}"))?;
+            let map_conn = incoming_connection(scene, pass_node_id, "map")
+                .ok_or_else(|| anyhow!("Displace.map missing for {pass_node_id}"))?;
+            Ok(vec![
+                source_conn.from.node_id.clone(),
+                map_conn.from.node_id.clone(),
+            ])
+        }
+        "DepthOfFieldPass" => {
+            let source_conn = incoming_connection(scene, pass_node_id, "pass")
+                .ok_or_else(|| anyhow!("DepthOfFieldPass.pass missing for {pass_node_id}"))?;
+            let mut deps = vec![source_conn.from.node_id.clone()];
+            if let Some(focus_conn) = incoming_connection(scene, pass_node_id, "focus") {
+                deps.push(focus_conn.from.node_id.clone());
+            }
+            Ok(deps)
+        }
         "IntelligentLight" | "MeshGradient" => Ok(Vec::new()),
+        "ComputePass" => {
+            // Only the compiled `color` subgraph can sample other passes.
+            let Some(conn) = incoming_connection(scene, pass_node_id, "color") else {
+                return Ok(Vec::new());
+            };
+            let mut ctx = crate::renderer::types::MaterialCompileContext::default();
+            let mut cache = std::collections::HashMap::new();
+            crate::renderer::node_compiler::compile_material_expr(
+                scene,
+                nodes_by_id,
+                &conn.from.node_id,
+                Some(&conn.from.port_id),
+                &mut ctx,
+                &mut cache,
+            )?;
+            Ok(ctx
+                .pass_textures
+                .into_iter()
+                .map(|texture_ref| texture_ref.source.node_id)
+                .collect())
+        }
         "GradientBlur" => {
             // GradientBlur reads "source" input (not "pass").
             let Some(conn) = incoming_connection(scene, pass_node_id, "source") else {
@@ -425,6 +491,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let nodes_by_id: HashMap<String, Node> = scene
@@ -441,49 +508,103 @@ mod tests {
 
     #[test]
     fn bloom_depends_on_pass_input_in_render_order() -> Result<()> {
+        for (bloom_type, bloom_port) in [("BloomNode", "glare"), ("BloomPass", "pass")] {
+            let scene = SceneDSL {
+                version: "1".to_string(),
+                metadata: Metadata {
+                    name: "bloom-pass-order".to_string(),
+                    created: None,
+                    modified: None,
+                },
+                nodes: vec![
+                    node("source_comp", "Composite"),
+                    node("bloom", bloom_type),
+                    node("out_comp", "Composite"),
+                ],
+                connections: vec![
+                    Connection {
+                        id: "c_source".to_string(),
+                        from: Endpoint {
+                            node_id: "source_comp".to_string(),
+                            port_id: "pass".to_string(),
+                        },
+                        to: Endpoint {
+                            node_id: "bloom".to_string(),
+                            port_id: "pass".to_string(),
+                        },
+                    },
+                    Connection {
+                        id: "c_out".to_string(),
+                        from: Endpoint {
+                            node_id: "bloom".to_string(),
+                            port_id: bloom_port.to_string(),
+                        },
+                        to: Endpoint {
+                            node_id: "out_comp".to_string(),
+                            port_id: "pass".to_string(),
+                        },
+                    },
+                ],
+                outputs: None,
+                groups: Vec::new(),
+                assets: HashMap::new(),
+                state_machine: None,
+                debug_artifacts: None,
+                feature_flags: Default::default(),
+            };
+
+            let nodes_by_id: HashMap<String, Node> = scene
+                .nodes
+                .iter()
+                .cloned()
+                .map(|n| (n.id.clone(), n))
+                .collect();
+
+            let order =
+                compute_pass_render_order(&scene, &nodes_by_id, &[String::from("out_comp")])?;
+            assert_eq!(order, vec!["source_comp", "bloom", "out_comp"]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn depth_of_field_depends_on_source_and_focus_passes() -> Result<()> {
+        let link = |id: &str, from: &str, to: &str, to_port: &str| Connection {
+            id: id.to_string(),
+            from: Endpoint {
+                node_id: from.to_string(),
+                port_id: "pass".to_string(),
+            },
+            to: Endpoint {
+                node_id: to.to_string(),
+                port_id: to_port.to_string(),
+            },
+        };
         let scene = SceneDSL {
             version: "1".to_string(),
             metadata: Metadata {
-                name: "bloom-pass-order".to_string(),
+                name: "dof-pass-order".to_string(),
                 created: None,
                 modified: None,
             },
             nodes: vec![
-                node("source_comp", "Composite"),
-                node("bloom", "BloomNode"),
+                node("color_comp", "Composite"),
+                node("depth_comp", "Composite"),
+                node("dof", "DepthOfFieldPass"),
                 node("out_comp", "Composite"),
             ],
             connections: vec![
-                Connection {
-                    id: "c_source".to_string(),
-                    from: Endpoint {
-                        node_id: "source_comp".to_string(),
-                        port_id: "pass".to_string(),
-                    },
-                    to: Endpoint {
-                        node_id: "bloom".to_string(),
-                        port_id: "pass".to_string(),
-                    },
-                },
-                Connection {
-                    id: "c_out".to_string(),
-                    from: Endpoint {
-                        node_id: "bloom".to_string(),
-                        port_id: "glare".to_string(),
-                    },
-                    to: Endpoint {
-                        node_id: "out_comp".to_string(),
-                        port_id: "pass".to_string(),
-                    },
-                },
+                link("c_color", "color_comp", "dof", "pass"),
+                link("c_depth", "depth_comp", "dof", "focus"),
+                link("c_out", "dof", "out_comp", "pass"),
             ],
             outputs: None,
             groups: Vec::new(),
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
-
         let nodes_by_id: HashMap<String, Node> = scene
             .nodes
             .iter()
@@ -492,7 +613,16 @@ mod tests {
             .collect();
 
         let order = compute_pass_render_order(&scene, &nodes_by_id, &[String::from("out_comp")])?;
-        assert_eq!(order, vec!["source_comp", "bloom", "out_comp"]);
+        assert_eq!(order.last().map(String::as_str), Some("out_comp"));
+        let dof_at = order.iter().position(|id| id == "dof").unwrap();
+        for dep in ["color_comp", "depth_comp"] {
+            assert!(order.iter().position(|id| id == dep).unwrap() < dof_at);
+        }
+
+        let sampled =
+            sampled_pass_node_ids_from_roots(&scene, &nodes_by_id, &[String::from("out_comp")])?;
+        assert!(sampled.contains("color_comp") && sampled.contains("depth_comp"));
+        assert!(!sampled.contains("dof"));
         Ok(())
     }
 
@@ -565,6 +695,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let nodes_by_id: HashMap<String, Node> = scene
@@ -673,6 +804,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let nodes_by_id: HashMap<String, Node> = scene
@@ -769,6 +901,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let nodes_by_id: HashMap<String, Node> = scene
@@ -830,6 +963,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let nodes_by_id: HashMap<String, Node> = scene
@@ -874,6 +1008,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let nodes_by_id: HashMap<String, Node> = scene
@@ -991,6 +1126,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let nodes_by_id: HashMap<String, Node> = scene
             .nodes
@@ -20,6 +20,7 @@ pub(crate) trait PassPlanner {
 }
 
 struct RenderPassPlanner;
+struct BloomNodePlanner;
 struct BloomPassPlanner;
 struct GaussianBlurPassPlanner;
 struct GradientBlurPlanner;
@@ -28,6 +29,22 @@ struct UpsamplePassPlanner;
 struct CompositePassPlanner;
 struct IntelligentLightPassPlanner;
 struct MeshGradientPassPlanner;
+struct ComputePassPlanner;
+struct FeedbackPassPlanner;
+struct DepthOfFieldPassPlanner;
+struct ConvolutionPassPlanner;
+struct EdgeDetectPlanner;
+struct ChromaticAberrationPlanner;
+struct VignettePlanner;
+struct FilmGrainPlanner;
+struct LensDistortionPlanner;
+struct LUTPassPlanner;
+struct PixelatePlanner;
+struct PosterizePlanner;
+struct DitherPlanner;
+struct DropShadowPlanner;
+struct DisplacePlanner;
+struct AntiAliasPassPlanner;
 
 impl PassPlanner for RenderPassPlanner {
     fn node_type(&self) -> &'static str {
@@ -45,7 +62,7 @@ impl PassPlanner for RenderPassPlanner {
     }
 }
 
-impl PassPlanner for BloomPassPlanner {
+impl PassPlanner for BloomNodePlanner {
     fn node_type(&self) -> &'static str {
         "BloomNode"
     }
@@ -61,6 +78,22 @@ impl PassPlanner for BloomPassPlanner {
     }
 }
 
+impl PassPlanner for BloomPassPlanner {
+    fn node_type(&self) -> &'static str {
+        "BloomPass"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::bloom::assemble_bloom(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
 impl PassPlanner for GaussianBlurPassPlanner {
     fn node_type(&self) -> &'static str {
         "GuassianBlurPass"
@@ -175,6 +208,272 @@ impl PassPlanner for MeshGradientPassPlanner {
     }
 }
 
+impl PassPlanner for ComputePassPlanner {
+    fn node_type(&self) -> &'static str {
+        "ComputePass"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::compute::assemble_compute_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for FeedbackPassPlanner {
+    fn node_type(&self) -> &'static str {
+        "FeedbackPass"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::feedback::assemble_feedback_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for DepthOfFieldPassPlanner {
+    fn node_type(&self) -> &'static str {
+        "DepthOfFieldPass"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::dof::assemble_dof_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for ConvolutionPassPlanner {
+    fn node_type(&self) -> &'static str {
+        "ConvolutionPass"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::convolution::assemble_convolution_pass(
+            scene_ref, ctx, layer_id, layer_node,
+        )
+    }
+}
+
+impl PassPlanner for EdgeDetectPlanner {
+    fn node_type(&self) -> &'static str {
+        "EdgeDetect"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::edge_detect::assemble_edge_detect_pass(
+            scene_ref, ctx, layer_id, layer_node,
+        )
+    }
+}
+
+impl PassPlanner for ChromaticAberrationPlanner {
+    fn node_type(&self) -> &'static str {
+        "ChromaticAberration"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::finishing::assemble_chromatic_aberration_pass(
+            scene_ref, ctx, layer_id, layer_node,
+        )
+    }
+}
+
+impl PassPlanner for VignettePlanner {
+    fn node_type(&self) -> &'static str {
+        "Vignette"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::finishing::assemble_vignette_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for FilmGrainPlanner {
+    fn node_type(&self) -> &'static str {
+        "FilmGrain"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::finishing::assemble_film_grain_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for LensDistortionPlanner {
+    fn node_type(&self) -> &'static str {
+        "LensDistortion"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::lens_distortion::assemble_lens_distortion_pass(
+            scene_ref, ctx, layer_id, layer_node,
+        )
+    }
+}
+
+impl PassPlanner for LUTPassPlanner {
+    fn node_type(&self) -> &'static str {
+        "LUTPass"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::lut::assemble_lut_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for PixelatePlanner {
+    fn node_type(&self) -> &'static str {
+        "Pixelate"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::stylize::assemble_pixelate_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for PosterizePlanner {
+    fn node_type(&self) -> &'static str {
+        "Posterize"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::stylize::assemble_posterize_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for DitherPlanner {
+    fn node_type(&self) -> &'static str {
+        "Dither"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::stylize::assemble_dither_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for DropShadowPlanner {
+    fn node_type(&self) -> &'static str {
+        "DropShadow"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::drop_shadow::assemble_drop_shadow_pass(
+            scene_ref, ctx, layer_id, layer_node,
+        )
+    }
+}
+
+impl PassPlanner for DisplacePlanner {
+    fn node_type(&self) -> &'static str {
+        "Displace"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::displace::assemble_displace_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
+impl PassPlanner for AntiAliasPassPlanner {
+    fn node_type(&self) -> &'static str {
+        "AntiAliasPass"
+    }
+
+    fn plan(
+        &self,
+        scene_ref: &SceneContext<'_>,
+        ctx: &mut BuilderState<'_>,
+        layer_id: &str,
+        layer_node: &Node,
+    ) -> Result<()> {
+        pass_assemblers::anti_alias::assemble_anti_alias_pass(scene_ref, ctx, layer_id, layer_node)
+    }
+}
+
 pub(crate) struct PassPlannerRegistry {
     planners: Vec<Box<dyn PassPlanner + Send + Sync>>,
 }
@@ -184,6 +483,7 @@ impl Default for PassPlannerRegistry {
         Self {
             planners: vec![
                 Box::new(RenderPassPlanner),
+                Box::new(BloomNodePlanner),
                 Box::new(BloomPassPlanner),
                 Box::new(GaussianBlurPassPlanner),
                 Box::new(GradientBlurPlanner),
@@ -192,6 +492,22 @@ impl Default for PassPlannerRegistry {
                 Box::new(CompositePassPlanner),
                 Box::new(IntelligentLightPassPlanner),
                 Box::new(MeshGradientPassPlanner),
+                Box::new(ComputePassPlanner),
+                Box::new(FeedbackPassPlanner),
+                Box::new(DepthOfFieldPassPlanner),
+                Box::new(ConvolutionPassPlanner),
+                Box::new(EdgeDetectPlanner),
+                Box::new(ChromaticAberrationPlanner),
+                Box::new(VignettePlanner),
+                Box::new(FilmGrainPlanner),
+                Box::new(LensDistortionPlanner),
+                Box::new(LUTPassPlanner),
+                Box::new(PixelatePlanner),
+                Box::new(PosterizePlanner),
+                Box::new(DitherPlanner),
+                Box::new(DropShadowPlanner),
+                Box::new(DisplacePlanner),
+                Box::new(AntiAliasPassPlanner),
             ],
         }
     }
@@ -211,7 +527,7 @@ impl PassPlannerRegistry {
             .find(|planner| planner.node_type() == layer_node.node_type)
         else {
             bail!(
-                "Composite layer must be a pass node (RenderPass/GuassianBlurPass/Downsample/Upsample/GradientBlur/Composite/BloomNode/IntelligentLight/MeshGradient), got {} for {}. \
+                "Composite layer must be a pass node (RenderPass/GuassianBlurPass/Downsample/Upsample/GradientBlur/Composite/BloomNode/BloomPass/IntelligentLight/MeshGradient/ComputePass/FeedbackPass/DepthOfFieldPass/ConvolutionPass/EdgeDetect/ChromaticAberration/Vignette/FilmGrain/LensDistortion/LUTPass/Pixelate/Posterize/Dither/DropShadow/Displace/AntiAliasPass), got {} for {}. \
                  To enable chain support for new pass types, update the pass planner registry.",
                 layer_node.node_type,
                 layer_id
@@ -1,7 +1,7 @@
 //! Shared pass-planning helper utilities.
 
 pub(crate) use crate::renderer::render_plan::types::{
-    DepthResolvePass, PassTextureBinding, RenderPassSpec, SamplerKind,
+    ComputePassSpec, DepthResolvePass, PassTextureBinding, RenderPassSpec, SamplerKind,
     TextureCapabilityRequirement, TextureDecl, VertexLayoutKind,
 };
 
@@ -29,6 +29,9 @@ pub(crate) fn make_params(
         color,
         camera,
         camera_position: [0.0, 0.0, 0.0, 0.0],
+        delta_time: 0.0,
+        frame: 0,
+        _pad1: [0.0; 2],
     }
 }
 
@@ -18,32 +18,44 @@ use crate::{
         camera::legacy_projection_camera_matrix,
         geometry_resolver::{is_pass_like_node_type, resolve_scene_draw_contexts},
         graph_uniforms::{compute_pipeline_signature_for_pass_bindings, hash_bytes},
-        node_compiler::geometry_nodes::{rect2d_geometry_vertices, rect2d_unit_geometry_vertices},
+        node_compiler::{
+            compile_cache::prewarm_material_cache,
+            geometry_nodes::{rect2d_geometry_vertices, rect2d_unit_geometry_vertices},
+        },
         scene_prep::{PreparedScene, ScenePrepReport, prepare_scene_with_report},
         shader_space::{
             image_utils::{ensure_rgba8, load_image_from_data_url_checked, load_image_from_path},
-            sampler::build_image_premultiply_wgsl,
+            sampler::{
+                build_image_mip_atlas_wgsl, build_image_premultiply_wgsl, image_mip_atlas_name,
+            },
+            text_atlas::rasterize_text_node,
         },
         types::{MaterialCompileContext, PassBindings, PassOutputRegistry},
         utils::{as_bytes_slice, cpu_num_u32_min_1},
+        wgsl_templates::mip_atlas::{mip_atlas_level_count, mip_atlas_size},
     },
 };
 
 use super::{
+    blend::PassLoadOp,
     compute_pass_render_order, forward_root_dependencies_from_roots,
     load_gltf_geometry_pixel_space,
     pass_assemblers::args::{BuilderState, SceneContext, make_fullscreen_geometry},
     pass_handlers::PassPlannerRegistry,
-    pass_spec::{PassTextureBinding, RenderPassSpec, SamplerKind, TextureDecl, make_params},
+    pass_spec::{
+        ComputePassSpec, PassTextureBinding, RenderPassSpec, SamplerKind, TextureDecl, make_params,
+    },
     resolve_geometry_for_render_pass,
     resource_naming::{
         UI_PRESENT_HDR_GAMMA_SUFFIX, UI_PRESENT_SDR_SRGB_SUFFIX, build_hdr_gamma_encode_wgsl,
         build_srgb_display_encode_wgsl,
     },
     sampled_pass_node_ids_from_roots,
+    stencil::PassStencilAttachment,
     types::{
         ImagePrepass, ImageTextureSpec, PlanBuildOptions, PlanningDevice, RenderPlan, ResourcePlans,
     },
+    viewport::PassRect,
 };
 
 pub(crate) struct RenderPlanner {
@@ -160,12 +172,16 @@ impl RenderPlanner {
         let mut textures: Vec<TextureDecl> = Vec::new();
         let mut image_textures: Vec<ImageTextureSpec> = Vec::new();
         let mut render_pass_specs: Vec<RenderPassSpec> = Vec::new();
+        let mut compute_pass_specs: Vec<ComputePassSpec> = Vec::new();
         let mut composite_passes: Vec<ResourceName> = Vec::new();
         let mut depth_resolve_passes = Vec::new();
         let mut image_prepasses: Vec<ImagePrepass> = Vec::new();
         let mut prepass_texture_samples: Vec<(String, ResourceName)> = Vec::new();
         let mut pass_cull_mode_by_name: HashMap<ResourceName, Option<wgpu::Face>> = HashMap::new();
         let mut pass_depth_attachment_by_name: HashMap<ResourceName, ResourceName> = HashMap::new();
+        let mut pass_stencil_by_name: HashMap<ResourceName, PassStencilAttachment> = HashMap::new();
+        let mut pass_load_op_by_name: HashMap<ResourceName, PassLoadOp> = HashMap::new();
+        let mut pass_clip_rect_by_name: HashMap<ResourceName, PassRect> = HashMap::new();
         let mut baked_data_parse_meta_by_pass = HashMap::new();
         let mut baked_data_parse_bytes_by_pass = HashMap::new();
         let mut baked_data_parse_buffer_to_pass_id = HashMap::new();
@@ -350,6 +366,22 @@ impl RenderPlanner {
         execution_items.extend(warmup_items);
         execution_items.extend(normal_items);
 
+        // Material compilation dominates assembly on large scenes and only reads
+        // the prepared scene, so it runs in parallel ahead of the serial loop.
+        let material_roots: Vec<(String, String)> = execution_items
+            .iter()
+            .filter(|(layer_id, _)| {
+                nodes_by_id
+                    .get(layer_id)
+                    .is_some_and(|node| node.node_type == "RenderPass")
+            })
+            .filter_map(|(layer_id, _)| incoming_connection(&prepared.scene, layer_id, "material"))
+            .map(|conn| (conn.from.node_id.clone(), conn.from.port_id.clone()))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        prewarm_material_cache(&prepared.scene, nodes_by_id, &material_roots);
+
         let registry = PassPlannerRegistry::default();
         let mut deferred_target_compose_passes_by_layer: HashMap<String, Vec<ResourceName>> =
             HashMap::new();
@@ -374,6 +406,7 @@ impl RenderPlanner {
                 asset_store,
                 device: &planning_device,
                 adapter,
+                channel_debug: self.options.channel_debug,
             };
             let mut builder_state = BuilderState {
                 target_texture_name: &target_texture_name,
@@ -385,10 +418,14 @@ impl RenderPlanner {
                 instance_buffers: &mut instance_buffers,
                 textures: &mut textures,
                 render_pass_specs: &mut render_pass_specs,
+                compute_pass_specs: &mut compute_pass_specs,
                 composite_passes: &mut composite_passes,
                 depth_resolve_passes: &mut depth_resolve_passes,
                 pass_cull_mode_by_name: &mut pass_cull_mode_by_name,
                 pass_depth_attachment_by_name: &mut pass_depth_attachment_by_name,
+                pass_stencil_by_name: &mut pass_stencil_by_name,
+                pass_load_op_by_name: &mut pass_load_op_by_name,
+                pass_clip_rect_by_name: &mut pass_clip_rect_by_name,
                 pass_output_registry: &mut pass_output_registry,
                 sampled_pass_ids: &sampled_pass_ids,
                 baked_data_parse_meta_by_pass: &mut baked_data_parse_meta_by_pass,
@@ -436,12 +473,18 @@ impl RenderPlanner {
         }
 
         let mut sdr_encode_pass_name: Option<ResourceName> = None;
+        // 8-bit exports dither when requested by the build or by the File target.
+        let dither_sdr_output = self.options.dither_sdr_output
+            || crate::dsl::file_render_target(&prepared.scene)
+                .ok()
+                .flatten()
+                .is_some_and(|target| target.dither);
         let encode_passes: Vec<(&ResourceName, &str, String)> = [
             sdr_srgb_texture.as_ref().map(|tex| {
                 (
                     tex,
                     UI_PRESENT_SDR_SRGB_SUFFIX,
-                    build_srgb_display_encode_wgsl("src_tex", "src_samp"),
+                    build_srgb_display_encode_wgsl("src_tex", "src_samp", dither_sdr_output),
                 )
             }),
             hdr_gamma_texture.as_ref().map(|tex| {
@@ -506,11 +549,16 @@ impl RenderPlanner {
             }
         }
 
-        normalize_first_write_load_ops(&composite_passes, &mut render_pass_specs);
+        normalize_first_write_load_ops(
+            &composite_passes,
+            &pass_load_op_by_name,
+            &mut render_pass_specs,
+        );
         plan_image_textures(
             &prepared,
             asset_store,
             &render_pass_specs,
+            &compute_pass_specs,
             &mut image_textures,
             &mut textures,
             &mut geometry_buffers,
@@ -536,6 +584,16 @@ impl RenderPlanner {
                     .map(|plan| hash_bytes(plan.values.as_slice())),
                 extension: pass_extensions.get(&spec.pass_id).cloned(),
             })
+            .chain(compute_pass_specs.iter().map(|spec| PassBindings {
+                pass_id: spec.pass_id.clone(),
+                params_buffer: spec.params_buffer.clone(),
+                base_params: spec.params,
+                graph_binding: spec.graph_binding.clone(),
+                last_graph_hash: spec.graph_values.as_ref().map(|v| hash_bytes(v.as_slice())),
+                shader_parameter_binding: None,
+                last_shader_parameter_hash: None,
+                extension: None,
+            }))
             .collect();
         let _pipeline_signature =
             compute_pipeline_signature_for_pass_bindings(&prepared.scene, &pass_bindings);
@@ -569,12 +627,17 @@ impl RenderPlanner {
                 textures,
                 image_textures,
                 render_pass_specs,
+                compute_pass_specs,
                 composite_passes,
                 depth_resolve_passes,
                 image_prepasses,
                 prepass_texture_samples,
                 pass_cull_mode_by_name,
+                pass_polygon_mode_by_name: HashMap::new(),
                 pass_depth_attachment_by_name,
+                pass_stencil_by_name,
+                pass_clip_rect_by_name,
+                pass_non_finite_counter_by_name: HashMap::new(),
                 pass_output_registry,
                 pass_bindings,
                 baked_data_parse_bytes_by_pass,
@@ -683,7 +746,7 @@ fn collect_processing_source_pass_ids(
             }
             continue;
         }
-        if node.node_type == "BloomNode" {
+        if matches!(node.node_type.as_str(), "BloomNode" | "BloomPass") {
             if let Some(conn) = incoming_connection(&prepared.scene, node_id, "pass") {
                 let src_is_pass_like = prepared
                     .nodes_by_id
@@ -717,8 +780,11 @@ fn collect_processing_source_pass_ids(
     )
 }
 
+/// Assign color load ops in execution order: passes clear on the first write to
+/// their target and load afterwards, unless the node asked otherwise via `loadOp`.
 fn normalize_first_write_load_ops(
     composite_passes: &[ResourceName],
+    pass_load_op_by_name: &HashMap<ResourceName, PassLoadOp>,
     render_pass_specs: &mut [RenderPassSpec],
 ) {
     let pass_order: HashMap<ResourceName, usize> = composite_passes
@@ -736,11 +802,12 @@ fn normalize_first_write_load_ops(
     let mut seen_targets: HashSet<ResourceName> = HashSet::new();
     for idx in spec_indices_by_exec_order {
         let spec = &mut render_pass_specs[idx];
-        if seen_targets.insert(spec.target_texture.clone()) {
-            spec.color_load_op = wgpu::LoadOp::Clear(Color::TRANSPARENT);
-        } else {
-            spec.color_load_op = wgpu::LoadOp::Load;
-        }
+        let first_write = seen_targets.insert(spec.target_texture.clone());
+        spec.color_load_op = pass_load_op_by_name
+            .get(&spec.name)
+            .copied()
+            .unwrap_or_default()
+            .resolve(first_write);
     }
 }
 
@@ -748,6 +815,7 @@ fn plan_image_textures(
     prepared: &PreparedScene,
     asset_store: Option<&AssetStore>,
     render_pass_specs: &[RenderPassSpec],
+    compute_pass_specs: &[ComputePassSpec],
     image_textures: &mut Vec<ImageTextureSpec>,
     textures: &mut Vec<TextureDecl>,
     geometry_buffers: &mut Vec<(ResourceName, Arc<[u8]>)>,
@@ -757,9 +825,22 @@ fn plan_image_textures(
     let rel_base = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let mut seen_image_nodes: HashSet<String> = HashSet::new();
 
-    let specs_snapshot = render_pass_specs.to_vec();
-    for pass in &specs_snapshot {
-        for binding in &pass.texture_bindings {
+    let binding_sets: Vec<Vec<PassTextureBinding>> = render_pass_specs
+        .iter()
+        .map(|pass| pass.texture_bindings.clone())
+        .chain(
+            compute_pass_specs
+                .iter()
+                .map(|pass| pass.texture_bindings.clone()),
+        )
+        .collect();
+    let bound_textures: HashSet<&ResourceName> = binding_sets
+        .iter()
+        .flatten()
+        .map(|binding| &binding.texture)
+        .collect();
+    for bindings in &binding_sets {
+        for binding in bindings {
             let Some(node_id) = binding.image_node_id.as_ref() else {
                 continue;
             };
@@ -768,13 +849,33 @@ fn plan_image_textures(
             }
 
             let node = find_node(&prepared.nodes_by_id, node_id)?;
-            if node.node_type != "ImageTexture" && node.node_type != "Matcap" {
+            if !matches!(
+                node.node_type.as_str(),
+                "ImageTexture" | "Matcap" | "EnvironmentTexture" | "Text"
+            ) {
                 bail!(
                     "expected ImageTexture node for {node_id}, got {}",
                     node.node_type
                 );
             }
 
+            if node.node_type == "Text" {
+                // Rasterized already premultiplied and linear; no prepass needed.
+                let image = rasterize_text_node(node, asset_store, &rel_base)?;
+                let name = prepared
+                    .ids
+                    .get(node_id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("missing name for node: {node_id}"))?;
+                image_textures.push(ImageTextureSpec {
+                    name,
+                    image: Arc::new(image),
+                    usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                    srgb: false,
+                });
+                continue;
+            }
+
             let asset_id = node
                 .params
                 .get("assetId")
@@ -844,6 +945,10 @@ fn plan_image_textures(
                 .cloned()
                 .ok_or_else(|| anyhow!("missing name for node: {node_id}"))?;
 
+            let mip_base = bound_textures
+                .contains(&&image_mip_atlas_name(node_id))
+                .then(|| name.clone());
+
             if needs_premultiply {
                 let src_name: ResourceName = format!("sys.image.{node_id}.src").into();
                 image_textures.push(ImageTextureSpec {
@@ -903,12 +1008,92 @@ fn plan_image_textures(
                     srgb: is_srgb,
                 });
             }
+
+            // After the premultiply prepass, which writes the base.
+            if let Some(base) = mip_base {
+                plan_image_mip_atlas(
+                    node_id,
+                    &base,
+                    [img_w, img_h],
+                    if is_srgb {
+                        TextureFormat::Rgba16Float
+                    } else {
+                        TextureFormat::Rgba8Unorm
+                    },
+                    textures,
+                    geometry_buffers,
+                    image_prepasses,
+                    prepass_texture_samples,
+                );
+            }
         }
     }
 
     Ok(())
 }
 
+/// Pack `base`'s mip chain into the node's atlas (see `wgsl_templates::mip_atlas`).
+///
+/// One prepass per level, ping-ponging between the atlas and a scratch copy so
+/// each pass reads the previous levels while writing the next; the parity is
+/// chosen so the last level lands in the atlas.
+#[allow(clippy::too_many_arguments)]
+fn plan_image_mip_atlas(
+    node_id: &str,
+    base: &ResourceName,
+    [img_w, img_h]: [u32; 2],
+    format: TextureFormat,
+    textures: &mut Vec<TextureDecl>,
+    geometry_buffers: &mut Vec<(ResourceName, Arc<[u8]>)>,
+    image_prepasses: &mut Vec<ImagePrepass>,
+    prepass_texture_samples: &mut Vec<(String, ResourceName)>,
+) {
+    let atlas = image_mip_atlas_name(node_id);
+    let scratch: ResourceName = format!("{atlas}.scratch").into();
+    let size = mip_atlas_size(img_w, img_h);
+    let levels = mip_atlas_level_count(img_w, img_h);
+    for decl_name in [&atlas, &scratch].into_iter().take(levels.min(2) as usize) {
+        textures.push(TextureDecl {
+            name: decl_name.clone(),
+            size,
+            format,
+            sample_count: 1,
+            needs_sampling: false,
+        });
+    }
+
+    let [w, h] = [size[0] as f32, size[1] as f32];
+    let geo: ResourceName = format!("sys.image.{node_id}.mips.geo").into();
+    geometry_buffers.push((geo.clone(), make_fullscreen_geometry(w, h)));
+
+    let mut src = base.clone();
+    for level in 0..levels {
+        let dst = if (levels - 1 - level) % 2 == 0 {
+            atlas.clone()
+        } else {
+            scratch.clone()
+        };
+        let pass_name: ResourceName = format!("sys.image.{node_id}.mips.{level}.pass").into();
+        image_prepasses.push(ImagePrepass {
+            pass_name: pass_name.clone(),
+            geometry_buffer: geo.clone(),
+            params_buffer: format!("params.sys.image.{node_id}.mips.{level}").into(),
+            params: make_params(
+                [w, h],
+                [w, h],
+                [w * 0.5, h * 0.5],
+                legacy_projection_camera_matrix([w, h]),
+                [0.0, 0.0, 0.0, 0.0],
+            ),
+            src_texture: src.clone(),
+            dst_texture: dst.clone(),
+            shader_wgsl: build_image_mip_atlas_wgsl(level),
+        });
+        prepass_texture_samples.push((pass_name.as_str().to_string(), src));
+        src = dst;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -938,6 +1123,8 @@ mod tests {
             gpu_caps: PlanningGpuCaps::default(),
             presentation_mode,
             debug_dump_wgsl_dir: None,
+            channel_debug: crate::renderer::ChannelDebugMode::Off,
+            dither_sdr_output: false,
         })
     }
 
@@ -1234,6 +1421,8 @@ mod tests {
         "sys.blur.GuassianBlurPass_18.h:Rgba8Unorm:1080x2400:samples=1",
         "sys.blur.GuassianBlurPass_18.src:Rgba8Unorm:1080x2400:samples=1",
         "sys.blur.GuassianBlurPass_18.v:Rgba8Unorm:1080x2400:samples=1",
+        "sys.image.node_15.mips.scratch:Rgba16Float:1620x2400:samples=1",
+        "sys.image.node_15.mips:Rgba16Float:1620x2400:samples=1",
         "sys.pass.node_11.out:Rgba8Unorm:1080x2400:samples=1",
     ],
     image_textures: [
@@ -1241,6 +1430,17 @@ mod tests {
     ],
     image_prepasses: [
         "sys.image.node_15.premultiply.pass",
+        "sys.image.node_15.mips.0.pass",
+        "sys.image.node_15.mips.1.pass",
+        "sys.image.node_15.mips.2.pass",
+        "sys.image.node_15.mips.3.pass",
+        "sys.image.node_15.mips.4.pass",
+        "sys.image.node_15.mips.5.pass",
+        "sys.image.node_15.mips.6.pass",
+        "sys.image.node_15.mips.7.pass",
+        "sys.image.node_15.mips.8.pass",
+        "sys.image.node_15.mips.9.pass",
+        "sys.image.node_15.mips.10.pass",
     ],
     pass_order: [
         "node_11.pass",
@@ -1483,6 +1683,64 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn mipmap_filter_packs_the_image_mip_chain_after_premultiply() -> Result<()> {
+        let (mut scene, assets) = load_case("colorspace-image")?;
+        let image = scene
+            .nodes
+            .iter_mut()
+            .find(|node| node.id == "ImageTexture_9")
+            .expect("colorspace-image has ImageTexture_9");
+        image
+            .params
+            .insert("mipmapFilter".to_string(), serde_json::json!("linear"));
+        let plan = planner_for_mode(ShaderSpacePresentationMode::UiSdrDisplayEncode).plan(
+            &scene,
+            assets.as_ref(),
+            None,
+        )?;
+        let resources = &plan.resources;
+
+        let base = resources
+            .textures
+            .iter()
+            .find(|decl| decl.name.as_str() == "ImageTexture_9")
+            .expect("premultiplied base");
+        let atlas = resources
+            .textures
+            .iter()
+            .find(|decl| decl.name.as_str() == "sys.image.ImageTexture_9.mips")
+            .expect("mip atlas");
+        assert_eq!(atlas.size, mip_atlas_size(base.size[0], base.size[1]));
+        assert_eq!(atlas.format, base.format);
+
+        let levels = mip_atlas_level_count(base.size[0], base.size[1]) as usize;
+        let prepasses = &resources.image_prepasses;
+        assert_eq!(prepasses.len(), 1 + levels);
+        assert_eq!(
+            prepasses[0].pass_name.as_str(),
+            "sys.image.ImageTexture_9.premultiply.pass"
+        );
+        assert_eq!(prepasses[1].src_texture.as_str(), "ImageTexture_9");
+        for pair in prepasses[1..].windows(2) {
+            assert_eq!(pair[1].src_texture, pair[0].dst_texture);
+            assert_ne!(pair[1].dst_texture, pair[0].dst_texture);
+        }
+        assert_eq!(prepasses[levels].dst_texture, atlas.name);
+
+        let sampling_pass = resources
+            .render_pass_specs
+            .iter()
+            .find(|spec| {
+                spec.texture_bindings
+                    .iter()
+                    .any(|binding| binding.texture == atlas.name)
+            })
+            .expect("material binds the atlas");
+        assert!(sampling_pass.shader_wgsl.contains("nf_mip_atlas_sample("));
+        Ok(())
+    }
+
     #[test]
     fn presentation_routing_modes_are_planned_without_gpu() -> Result<()> {
         let (scene, assets) = load_case("graph-rectangle")?;
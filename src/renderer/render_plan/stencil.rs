@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+use rust_wgpu_fiber::eframe::wgpu;
+
+use crate::dsl::parse_str;
+use crate::renderer::shader_space::wrap_generated_fragment_entry;
+
+/// Depth-stencil format used for shared stencil masks.
+pub(crate) const STENCIL_ATTACHMENT_FORMAT: wgpu::TextureFormat =
+    wgpu::TextureFormat::Depth24PlusStencil8;
+
+/// Stencil behaviour requested by a RenderPass.
+///
+/// A pass either writes its reference value wherever its material covers
+/// (`stencilWrite`) or only draws where the existing mask compares true
+/// against `stencilRef` (`stencilTest`). Both share one attachment per
+/// color target so later passes can test what earlier passes wrote.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PassStencil {
+    pub face: wgpu::StencilFaceState,
+    pub reference: u32,
+    pub writes: bool,
+}
+
+impl PassStencil {
+    pub(crate) fn depth_stencil_state(&self) -> wgpu::DepthStencilState {
+        wgpu::DepthStencilState {
+            format: STENCIL_ATTACHMENT_FORMAT,
+            // Stencil-only passes must not be culled by the (unused) depth aspect.
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState {
+                front: self.face,
+                back: self.face,
+                read_mask: 0xff,
+                write_mask: if self.writes { 0xff } else { 0x00 },
+            },
+            bias: wgpu::DepthBiasState::default(),
+        }
+    }
+}
+
+/// Fragments with alpha below this are discarded by stencil-writing passes, so
+/// the mask follows the material's coverage instead of the rasterized quad.
+const STENCIL_WRITE_MIN_ALPHA: f32 = 1.0 / 255.0;
+
+const STENCIL_INNER_FRAGMENT_NAME: &str = "fs_main_stencil_inner";
+
+/// Wrap `fs_main` so a stencil-writing pass discards transparent fragments.
+///
+/// The stencil op runs for every fragment that survives the shader, so without
+/// this an SDF or textured material would still mask its whole bounding rect.
+pub(crate) fn with_stencil_coverage_discard(module: &str) -> Result<String> {
+    let body = format!(
+        "    let color = {STENCIL_INNER_FRAGMENT_NAME}(in);\n    if (color.a < {STENCIL_WRITE_MIN_ALPHA:.8}) {{\n        discard;\n    }}\n    return color;\n"
+    );
+    wrap_generated_fragment_entry(module, STENCIL_INNER_FRAGMENT_NAME, &body)
+        .ok_or_else(|| anyhow!("stencilWrite needs a generated fs_main(in: VSOut) entry point"))
+}
+
+/// A pass's stencil state plus the shared attachment it binds.
+#[derive(Clone, Debug)]
+pub(crate) struct PassStencilAttachment {
+    pub texture: rust_wgpu_fiber::ResourceName,
+    pub stencil: PassStencil,
+    /// True for the first pass that touches the attachment in a frame; it
+    /// clears the mask to 0 before drawing. Later passes load it.
+    pub clear: bool,
+}
+
+fn normalize_stencil_token(s: &str) -> String {
+    s.trim().to_ascii_lowercase().replace('_', "-")
+}
+
+fn parse_stencil_compare(token: &str) -> Result<Option<wgpu::CompareFunction>> {
+    let token = normalize_stencil_token(token);
+    Ok(Some(match token.as_str() {
+        "" | "off" | "none" | "disabled" => return Ok(None),
+        "equal" | "inside" => wgpu::CompareFunction::Equal,
+        "not-equal" | "outside" => wgpu::CompareFunction::NotEqual,
+        "less" => wgpu::CompareFunction::Less,
+        "less-equal" => wgpu::CompareFunction::LessEqual,
+        "greater" => wgpu::CompareFunction::Greater,
+        "greater-equal" => wgpu::CompareFunction::GreaterEqual,
+        "always" => wgpu::CompareFunction::Always,
+        "never" => wgpu::CompareFunction::Never,
+        other => bail!("unsupported stencilTest: {other}"),
+    }))
+}
+
+fn parse_stencil_value(value: &serde_json::Value, key: &str) -> Result<u32> {
+    let v = value
+        .as_u64()
+        .ok_or_else(|| anyhow!("'{key}' must be an integer in 1..=255, got {value}"))?;
+    if !(1..=255).contains(&v) {
+        bail!("'{key}' must be an integer in 1..=255, got {v}");
+    }
+    Ok(v as u32)
+}
+
+/// Parse `stencilWrite` / `stencilTest` / `stencilRef` from RenderPass params.
+///
+/// - `stencilWrite`: `false` (default) | `true` (writes 1) | integer 1..=255
+/// - `stencilTest`: `"off"` (default) | compare function (`equal`, `not-equal`, ...;
+///   `inside` / `outside` are aliases for equal / not-equal)
+/// - `stencilRef`: value compared by `stencilTest` (default 1)
+///
+/// Returns `None` when the pass does not use the stencil buffer.
+pub(crate) fn parse_render_pass_stencil(
+    params: &HashMap<String, serde_json::Value>,
+) -> Result<Option<PassStencil>> {
+    let write_ref = match params.get("stencilWrite") {
+        None | Some(serde_json::Value::Null) | Some(serde_json::Value::Bool(false)) => None,
+        Some(serde_json::Value::Bool(true)) => Some(1),
+        Some(v) => Some(parse_stencil_value(v, "stencilWrite")?),
+    };
+    let test_compare = match parse_str(params, "stencilTest") {
+        Some(token) => parse_stencil_compare(token)?,
+        None => None,
+    };
+
+    match (write_ref, test_compare) {
+        (None, None) => Ok(None),
+        (Some(_), Some(_)) => {
+            bail!("stencilWrite and stencilTest cannot be combined on one pass")
+        }
+        (Some(reference), None) => Ok(Some(PassStencil {
+            face: wgpu::StencilFaceState {
+                compare: wgpu::CompareFunction::Always,
+                fail_op: wgpu::StencilOperation::Keep,
+                depth_fail_op: wgpu::StencilOperation::Keep,
+                pass_op: wgpu::StencilOperation::Replace,
+            },
+            reference,
+            writes: true,
+        })),
+        (None, Some(compare)) => {
+            let reference = match params.get("stencilRef") {
+                Some(v) => parse_stencil_value(v, "stencilRef")?,
+                None => 1,
+            };
+            Ok(Some(PassStencil {
+                face: wgpu::StencilFaceState {
+                    compare,
+                    fail_op: wgpu::StencilOperation::Keep,
+                    depth_fail_op: wgpu::StencilOperation::Keep,
+                    pass_op: wgpu::StencilOperation::Keep,
+                },
+                reference,
+                writes: false,
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params(v: serde_json::Value) -> HashMap<String, serde_json::Value> {
+        v.as_object()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn defaults_disable_stencil() {
+        let got = parse_render_pass_stencil(&params(json!({
+            "stencilWrite": false,
+            "stencilTest": "off",
+            "stencilRef": 1
+        })))
+        .expect("parse defaults");
+        assert_eq!(got, None);
+        assert_eq!(
+            parse_render_pass_stencil(&HashMap::new()).expect("parse empty"),
+            None
+        );
+    }
+
+    #[test]
+    fn stencil_write_replaces_with_reference() {
+        let got = parse_render_pass_stencil(&params(json!({"stencilWrite": 3})))
+            .expect("parse write")
+            .expect("stencil enabled");
+        assert!(got.writes);
+        assert_eq!(got.reference, 3);
+        assert_eq!(got.face.compare, wgpu::CompareFunction::Always);
+        assert_eq!(got.face.pass_op, wgpu::StencilOperation::Replace);
+        assert_eq!(got.depth_stencil_state().stencil.write_mask, 0xff);
+
+        let got = parse_render_pass_stencil(&params(json!({"stencilWrite": true})))
+            .expect("parse bool write")
+            .expect("stencil enabled");
+        assert_eq!(got.reference, 1);
+    }
+
+    #[test]
+    fn stencil_test_keeps_mask_and_compares() {
+        let got = parse_render_pass_stencil(&params(json!({
+            "stencilTest": "outside",
+            "stencilRef": 2
+        })))
+        .expect("parse test")
+        .expect("stencil enabled");
+        assert!(!got.writes);
+        assert_eq!(got.reference, 2);
+        assert_eq!(got.face.compare, wgpu::CompareFunction::NotEqual);
+        assert_eq!(got.face.pass_op, wgpu::StencilOperation::Keep);
+        assert_eq!(got.depth_stencil_state().stencil.write_mask, 0x00);
+    }
+
+    #[test]
+    fn stencil_write_discards_transparent_fragments() {
+        let module = "struct VSOut { @location(0) uv: vec2f };\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {\n    return vec4f(1.0, 1.0, 1.0, 0.0);\n}\n";
+        let wrapped = with_stencil_coverage_discard(module).expect("wrap fs_main");
+        assert!(wrapped.contains("fn fs_main_stencil_inner(in: VSOut) -> vec4f"));
+        assert!(wrapped.contains("let color = fs_main_stencil_inner(in);"));
+        assert!(wrapped.contains("if (color.a < 0.00392157) {\n        discard;"));
+        assert_eq!(wrapped.matches("@fragment").count(), 1);
+
+        assert!(with_stencil_coverage_discard("fn vs_main() {}").is_err());
+    }
+
+    #[test]
+    fn invalid_stencil_params_are_rejected() {
+        for p in [
+            json!({"stencilWrite": 0}),
+            json!({"stencilWrite": 256}),
+            json!({"stencilTest": "sometimes"}),
+            json!({"stencilTest": "equal", "stencilRef": -1}),
+            json!({"stencilWrite": 1, "stencilTest": "equal"}),
+        ] {
+            assert!(
+                parse_render_pass_stencil(&params(p.clone())).is_err(),
+                "expected error for {p}"
+            );
+        }
+    }
+}
@@ -7,12 +7,15 @@ use rust_wgpu_fiber::{
 };
 
 use crate::renderer::{
-    ShaderSpacePresentationMode,
+    ChannelDebugMode, ShaderSpacePresentationMode,
     pass_debug::PassDebugSource,
     scene_prep::{PreparedScene, ScenePrepReport},
     types::{GraphBinding, Params, PassBindings, PassExtension, PassOutputRegistry},
 };
 
+use super::stencil::PassStencilAttachment;
+use super::viewport::PassRect;
+
 #[derive(Clone, Debug, Default)]
 pub(crate) struct PlanningGpuCaps {
     pub features: wgpu::Features,
@@ -49,6 +52,9 @@ pub(crate) struct PlanBuildOptions {
     pub gpu_caps: PlanningGpuCaps,
     pub presentation_mode: ShaderSpacePresentationMode,
     pub debug_dump_wgsl_dir: Option<PathBuf>,
+    pub channel_debug: ChannelDebugMode,
+    /// Dither the SDR display-encode pass when quantizing to 8 bits.
+    pub dither_sdr_output: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -102,6 +108,24 @@ pub(crate) struct RenderPassSpec {
     pub sample_count: u32,
 }
 
+/// A `ComputePass` dispatch writing one storage texture.
+#[derive(Clone, Debug)]
+pub(crate) struct ComputePassSpec {
+    pub pass_id: String,
+    pub name: ResourceName,
+    /// Storage texture written by the shader (group 0 / binding 1).
+    pub output_texture: ResourceName,
+    pub params_buffer: ResourceName,
+    pub params: Params,
+    pub graph_binding: Option<GraphBinding>,
+    pub graph_values: Option<Vec<u8>>,
+    pub shader_wgsl: String,
+    pub texture_bindings: Vec<PassTextureBinding>,
+    pub sampler_kinds: Vec<SamplerKind>,
+    /// Workgroup counts covering the output texture.
+    pub workgroups: [u32; 3],
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub(crate) enum VertexLayoutKind {
     #[default]
@@ -159,12 +183,21 @@ pub(crate) struct ResourcePlans {
     pub textures: Vec<TextureDecl>,
     pub image_textures: Vec<ImageTextureSpec>,
     pub render_pass_specs: Vec<RenderPassSpec>,
+    pub compute_pass_specs: Vec<ComputePassSpec>,
     pub composite_passes: Vec<ResourceName>,
     pub depth_resolve_passes: Vec<DepthResolvePass>,
     pub image_prepasses: Vec<ImagePrepass>,
     pub prepass_texture_samples: Vec<(String, ResourceName)>,
     pub pass_cull_mode_by_name: HashMap<ResourceName, Option<wgpu::Face>>,
+    /// Non-fill rasterization requested by pass debug overlays; absent means fill.
+    pub pass_polygon_mode_by_name: HashMap<ResourceName, wgpu::PolygonMode>,
     pub pass_depth_attachment_by_name: HashMap<ResourceName, ResourceName>,
+    /// Stencil mask state for passes using `stencilWrite` / `stencilTest`.
+    pub pass_stencil_by_name: HashMap<ResourceName, PassStencilAttachment>,
+    /// Fragment clip rect (target pixels) for passes with `viewport` / `scissor`.
+    pub pass_clip_rect_by_name: HashMap<ResourceName, PassRect>,
+    /// Safe mode counter buffer for each sanitized pass.
+    pub pass_non_finite_counter_by_name: HashMap<ResourceName, ResourceName>,
     #[allow(dead_code)]
     pub pass_output_registry: PassOutputRegistry,
     #[allow(dead_code)]
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::renderer::utils::fmt_f32;
+
+/// A rectangle in target pixels with a top-left origin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PassRect {
+    pub origin: [f32; 2],
+    pub size: [f32; 2],
+}
+
+impl PassRect {
+    fn max(&self) -> [f32; 2] {
+        [self.origin[0] + self.size[0], self.origin[1] + self.size[1]]
+    }
+
+    /// Overlap of two rects; an empty overlap keeps a zero size so the pass
+    /// draws nothing rather than everything.
+    pub(crate) fn intersect(&self, other: &PassRect) -> PassRect {
+        let [ax, ay] = self.max();
+        let [bx, by] = other.max();
+        let origin = [
+            self.origin[0].max(other.origin[0]),
+            self.origin[1].max(other.origin[1]),
+        ];
+        PassRect {
+            origin,
+            size: [
+                (ax.min(bx) - origin[0]).max(0.0),
+                (ay.min(by) - origin[1]).max(0.0),
+            ],
+        }
+    }
+
+    /// Column-major clip-space transform that squeezes the full NDC square of a
+    /// `target_size` target into this rect (the inverse of an output crop window).
+    pub(crate) fn viewport_clip_transform(&self, target_size: [f32; 2]) -> [f32; 16] {
+        let target_w = target_size[0].max(1.0);
+        let target_h = target_size[1].max(1.0);
+        let sx = self.size[0] / target_w;
+        let sy = self.size[1] / target_h;
+        // Rect centre in NDC. Target rows run top-down while NDC y points up.
+        let cx = (2.0 * self.origin[0] + self.size[0]) / target_w - 1.0;
+        let cy = 1.0 - (2.0 * self.origin[1] + self.size[1]) / target_h;
+        [
+            sx, 0.0, 0.0, 0.0, 0.0, sy, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, cx, cy, 0.0, 1.0,
+        ]
+    }
+}
+
+fn rect_field(value: &serde_json::Value, key: &str, field: &str) -> Result<f32> {
+    let v = value
+        .get(field)
+        .and_then(|v| v.as_f64())
+        .ok_or_else(|| anyhow!("'{key}.{field}' must be a number"))?;
+    if !v.is_finite() {
+        bail!("'{key}.{field}' must be finite, got {v}");
+    }
+    Ok(v as f32)
+}
+
+/// Parse a `{ x, y, width, height }` rect param (target pixels, top-left origin).
+///
+/// Returns `None` when the key is absent or null.
+pub(crate) fn parse_pass_rect(
+    params: &HashMap<String, serde_json::Value>,
+    key: &str,
+) -> Result<Option<PassRect>> {
+    let value = match params.get(key) {
+        None | Some(serde_json::Value::Null) => return Ok(None),
+        Some(v) if v.is_object() => v,
+        Some(v) => bail!("'{key}' must be an object with x, y, width, height, got {v}"),
+    };
+    let rect = PassRect {
+        origin: [rect_field(value, key, "x")?, rect_field(value, key, "y")?],
+        size: [
+            rect_field(value, key, "width")?,
+            rect_field(value, key, "height")?,
+        ],
+    };
+    if rect.size.iter().any(|v| *v <= 0.0) {
+        bail!("'{key}' width and height must be positive");
+    }
+    Ok(Some(rect))
+}
+
+/// Prepend a fragment test to `fs_main` that discards everything outside `rect`.
+///
+/// This is the scissor: render passes are built without a hardware scissor
+/// rect, and a viewport folded into the camera does not clip geometry that
+/// extends past it.
+pub(crate) fn with_fragment_clip_rect(module: &str, rect: &PassRect) -> Result<String> {
+    let entry = module
+        .find("fn fs_main(in: VSOut")
+        .ok_or_else(|| anyhow!("clip rect needs an fs_main(in: VSOut, ..) entry point"))?;
+    let body = module[entry..]
+        .find('{')
+        .map(|at| entry + at + 1)
+        .ok_or_else(|| anyhow!("malformed fs_main entry point"))?;
+    let [x1, y1] = rect.max();
+    let test = format!(
+        "\n    if (any(in.position.xy < vec2f({}, {})) || any(in.position.xy >= vec2f({}, {}))) {{\n        discard;\n    }}",
+        fmt_f32(rect.origin[0]),
+        fmt_f32(rect.origin[1]),
+        fmt_f32(x1),
+        fmt_f32(y1)
+    );
+    Ok(format!("{}{test}{}", &module[..body], &module[body..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn apply(m: &[f32; 16], p: [f32; 2]) -> [f32; 2] {
+        [m[0] * p[0] + m[12], m[5] * p[1] + m[13]]
+    }
+
+    #[test]
+    fn viewport_maps_ndc_corners_onto_the_rect() {
+        let rect = PassRect {
+            origin: [100.0, 50.0],
+            size: [200.0, 100.0],
+        };
+        let m = rect.viewport_clip_transform([400.0, 200.0]);
+        // NDC top-left (-1, 1) -> pixel (100, 50) -> NDC (-0.5, 0.5).
+        assert_eq!(apply(&m, [-1.0, 1.0]), [-0.5, 0.5]);
+        // NDC bottom-right (1, -1) -> pixel (300, 150) -> NDC (0.5, -0.5).
+        assert_eq!(apply(&m, [1.0, -1.0]), [0.5, -0.5]);
+    }
+
+    #[test]
+    fn rect_params_parse_and_intersect() {
+        let params = HashMap::from([
+            (
+                "viewport".to_string(),
+                json!({ "x": 0, "y": 0, "width": 200, "height": 100 }),
+            ),
+            (
+                "scissor".to_string(),
+                json!({ "x": 150, "y": 50, "width": 100, "height": 100 }),
+            ),
+        ]);
+        let viewport = parse_pass_rect(&params, "viewport").unwrap().unwrap();
+        let scissor = parse_pass_rect(&params, "scissor").unwrap().unwrap();
+        assert_eq!(
+            viewport.intersect(&scissor),
+            PassRect {
+                origin: [150.0, 50.0],
+                size: [50.0, 50.0],
+            }
+        );
+        assert!(parse_pass_rect(&params, "missing").unwrap().is_none());
+
+        let bad = HashMap::from([(
+            "scissor".to_string(),
+            json!({ "x": 0, "y": 0, "width": 0, "height": 10 }),
+        )]);
+        assert!(parse_pass_rect(&bad, "scissor").is_err());
+    }
+
+    #[test]
+    fn clip_rect_discards_outside_fragments() {
+        let module = "struct VSOut {\n    @builtin(position) position: vec4f,\n};\n\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {\n    return vec4f(1.0);\n}\n";
+        let rect = PassRect {
+            origin: [10.0, 20.0],
+            size: [30.0, 40.0],
+        };
+        let wgsl = with_fragment_clip_rect(module, &rect).unwrap();
+        assert!(wgsl.contains(
+            "fn fs_main(in: VSOut) -> @location(0) vec4f {\n    if (any(in.position.xy < vec2f(10.0, 20.0)) || any(in.position.xy >= vec2f(40.0, 60.0))) {\n        discard;\n    }\n    return vec4f(1.0);"
+        ));
+        crate::renderer::validation::validate_wgsl_module(&wgsl)
+            .unwrap_or_else(|e| panic!("{e:#}\n{wgsl}"));
+    }
+}
@@ -182,6 +182,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let nodes_by_id: HashMap<String, Node> = scene
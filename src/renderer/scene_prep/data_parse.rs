@@ -219,3 +219,66 @@ pub(crate) fn bake_data_parse_nodes(
 
     Ok(baked)
 }
+
+/// Resolve a pass's `instanceAttributes` param into named vec4 slots.
+///
+/// Each entry maps an attribute name to a DataParse output, either as a
+/// `"nodeId.portId"` string or as `{ "node": ..., "port": ... }`. The referenced
+/// output must have been baked for `pass_id`.
+pub(crate) fn resolve_instance_attributes(
+    pass_node: &Node,
+    pass_id: &str,
+    slot_by_output: &HashMap<(String, String, String), u32>,
+) -> Result<HashMap<String, u32>> {
+    let mut slots: HashMap<String, u32> = HashMap::new();
+    let Some(decl) = pass_node.params.get("instanceAttributes") else {
+        return Ok(slots);
+    };
+    let decl = decl.as_object().ok_or_else(|| {
+        anyhow!(
+            "{}.instanceAttributes must be an object of name -> DataParse output",
+            pass_node.id
+        )
+    })?;
+
+    for (name, source) in decl {
+        let (node_id, port_id) = match source {
+            serde_json::Value::String(s) => s.split_once('.').ok_or_else(|| {
+                anyhow!(
+                    "{}.instanceAttributes.{name}: expected \"nodeId.portId\", got {s:?}",
+                    pass_node.id
+                )
+            })?,
+            serde_json::Value::Object(o) => {
+                let field = |key: &str| {
+                    o.get(key).and_then(|v| v.as_str()).ok_or_else(|| {
+                        anyhow!(
+                            "{}.instanceAttributes.{name}: missing string field '{key}'",
+                            pass_node.id
+                        )
+                    })
+                };
+                (field("node")?, field("port")?)
+            }
+            other => bail!(
+                "{}.instanceAttributes.{name}: expected string or object, got {other}",
+                pass_node.id
+            ),
+        };
+
+        let key = (
+            pass_id.to_string(),
+            node_id.to_string(),
+            port_id.to_string(),
+        );
+        let slot = *slot_by_output.get(&key).ok_or_else(|| {
+            anyhow!(
+                "{}.instanceAttributes.{name}: {node_id}.{port_id} is not a baked DataParse output",
+                pass_node.id
+            )
+        })?;
+        slots.insert(name.clone(), slot);
+    }
+
+    Ok(slots)
+}
@@ -0,0 +1,132 @@
+//! Resolves node `enabledWhen` conditions against the scene's `featureFlags`.
+//!
+//! A condition is an OR of AND terms over flag names, e.g.
+//! `"highQuality && !mobile || forceBloom"`. Unknown flags read as `false`.
+//! Disabled nodes are dropped together with every connection touching them, so
+//! downstream inputs fall back to their defaults exactly as if the wire were
+//! never drawn.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{Result, bail};
+
+use crate::dsl::{ENABLED_WHEN_PARAM, SceneDSL};
+
+/// Evaluates one `enabledWhen` expression.
+pub(crate) fn eval_enabled_when(condition: &str, flags: &BTreeMap<String, bool>) -> Result<bool> {
+    let mut any = false;
+    for term in condition.split("||") {
+        let mut all = true;
+        for atom in term.split("&&") {
+            let atom = atom.trim();
+            let (negated, name) = match atom.strip_prefix('!') {
+                Some(name) => (true, name.trim()),
+                None => (false, atom),
+            };
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+            {
+                bail!("invalid enabledWhen condition {condition:?}");
+            }
+            let value = flags.get(name).copied().unwrap_or(false);
+            all &= value != negated;
+        }
+        any |= all;
+    }
+    Ok(any)
+}
+
+/// Removes nodes whose `enabledWhen` evaluates to false and strips the
+/// condition from the nodes that stay. Returns the number of removed nodes.
+pub(crate) fn apply_feature_flags(scene: &mut SceneDSL) -> Result<usize> {
+    let mut disabled: HashSet<String> = HashSet::new();
+    for node in &mut scene.nodes {
+        let Some(condition) = node.params.remove(ENABLED_WHEN_PARAM) else {
+            continue;
+        };
+        let Some(condition) = condition.as_str() else {
+            bail!("node '{}': enabledWhen must be a string", node.id);
+        };
+        let enabled = eval_enabled_when(condition, &scene.feature_flags)
+            .map_err(|e| e.context(format!("node '{}'", node.id)))?;
+        if !enabled {
+            disabled.insert(node.id.clone());
+        }
+    }
+    if disabled.is_empty() {
+        return Ok(0);
+    }
+
+    scene.nodes.retain(|n| !disabled.contains(&n.id));
+    scene
+        .connections
+        .retain(|c| !disabled.contains(&c.from.node_id) && !disabled.contains(&c.to.node_id));
+    if let Some(outputs) = scene.outputs.as_mut() {
+        outputs.retain(|_, node_id| !disabled.contains(node_id));
+    }
+    Ok(disabled.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use super::{apply_feature_flags, eval_enabled_when};
+    use crate::dsl::{ENABLED_WHEN_PARAM, SceneDSL};
+
+    fn flags(entries: &[(&str, bool)]) -> BTreeMap<String, bool> {
+        entries
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn evaluates_and_or_not_with_unknown_flags_false() {
+        let flags = flags(&[("mobile", true), ("highQuality", false)]);
+        assert!(eval_enabled_when("mobile", &flags).unwrap());
+        assert!(!eval_enabled_when("!mobile", &flags).unwrap());
+        assert!(!eval_enabled_when("mobile && highQuality", &flags).unwrap());
+        assert!(eval_enabled_when("highQuality || mobile", &flags).unwrap());
+        assert!(eval_enabled_when("!highQuality && !unknown", &flags).unwrap());
+        assert!(!eval_enabled_when("unknown", &flags).unwrap());
+        assert!(eval_enabled_when("mobile &&", &flags).is_err());
+        assert!(eval_enabled_when("(mobile)", &flags).is_err());
+    }
+
+    #[test]
+    fn drops_disabled_nodes_and_their_connections() {
+        let mut scene: SceneDSL = serde_json::from_value(json!({
+            "version": "1.0",
+            "metadata": { "name": "flags" },
+            "featureFlags": { "highQuality": false, "mobile": true },
+            "nodes": [
+                { "id": "a", "type": "FloatInput", "enabledWhen": "highQuality" },
+                { "id": "b", "type": "FloatInput", "enabledWhen": "mobile" },
+                { "id": "c", "type": "Math" }
+            ],
+            "connections": [
+                { "id": "a_c", "from": { "nodeId": "a", "portId": "value" }, "to": { "nodeId": "c", "portId": "a" } },
+                { "id": "b_c", "from": { "nodeId": "b", "portId": "value" }, "to": { "nodeId": "c", "portId": "b" } }
+            ],
+            "outputs": null
+        }))
+        .unwrap();
+
+        assert_eq!(apply_feature_flags(&mut scene).unwrap(), 1);
+        let ids: Vec<&str> = scene.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["b", "c"]);
+        assert_eq!(scene.connections.len(), 1);
+        assert_eq!(scene.connections[0].id, "b_c");
+        assert!(
+            scene
+                .nodes
+                .iter()
+                .all(|n| !n.params.contains_key(ENABLED_WHEN_PARAM))
+        );
+    }
+}
@@ -4,6 +4,11 @@ use anyhow::{Result, bail};
 
 use crate::dsl::{Node, SceneDSL};
 
+/// Node types that load their image from params.{assetId,dataUrl,path}.
+fn takes_inlined_image(node_type: &str) -> bool {
+    matches!(node_type, "ImageTexture" | "EnvironmentTexture")
+}
+
 pub(crate) fn copy_image_file_params_into_image_texture(
     dst: &mut Node,
     asset_id: Option<serde_json::Value>,
@@ -81,20 +86,22 @@ pub(crate) fn inline_image_file_connections_into_image_textures(
         let Some(dst) = by_id.get(&c.to.node_id) else {
             continue;
         };
-        if dst.node_type != "ImageTexture" {
+        if !takes_inlined_image(&dst.node_type) {
             continue;
         }
 
         let Some(src) = by_id.get(&c.from.node_id) else {
             bail!(
-                "ImageTexture '{}' has image input from missing node '{}'",
+                "{} '{}' has image input from missing node '{}'",
+                dst.node_type,
                 c.to.node_id,
                 c.from.node_id
             );
         };
         if src.node_type != "ImageFile" {
             bail!(
-                "ImageTexture '{}' image input must come from ImageFile, got {} (node {})",
+                "{} '{}' image input must come from ImageFile, got {} (node {})",
+                dst.node_type,
                 c.to.node_id,
                 src.node_type,
                 src.id
@@ -115,7 +122,7 @@ pub(crate) fn inline_image_file_connections_into_image_textures(
                 dst_id
             );
         };
-        if dst.node_type != "ImageTexture" {
+        if !takes_inlined_image(&dst.node_type) {
             bail!(
                 "expected ImageTexture node '{}' when inlining ImageFile, got {}",
                 dst_id,
@@ -2,6 +2,7 @@ mod auto_wrap;
 mod composite;
 mod data_parse;
 pub(crate) mod data_parse_runtime;
+mod feature_flags;
 pub(crate) mod graph;
 mod group_expand;
 mod image_inline;
@@ -10,7 +11,7 @@ mod pipeline;
 mod types;
 
 pub use composite::{composite_layers_in_draw_order, composition_layers_by_id};
-pub(crate) use data_parse::bake_data_parse_nodes;
+pub(crate) use data_parse::{bake_data_parse_nodes, resolve_instance_attributes};
 pub use pipeline::prepare_scene;
 pub(crate) use pipeline::prepare_scene_with_report;
 pub use types::{PreparedScene, ScenePrepReport};
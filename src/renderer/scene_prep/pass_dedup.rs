@@ -19,6 +19,7 @@ use crate::dsl::SceneDSL;
 const PASS_NODE_TYPES: &[&str] = &[
     "RenderPass",
     "BloomNode",
+    "BloomPass",
     "Downsample",
     "Upsample",
     "GuassianBlurPass",
@@ -532,6 +533,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let report = dedup_identical_passes(&mut scene);
@@ -596,6 +598,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let report = dedup_identical_passes(&mut scene);
@@ -649,6 +652,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let report = dedup_identical_passes(&mut scene);
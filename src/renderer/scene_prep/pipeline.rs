@@ -13,6 +13,7 @@ use super::{
     auto_wrap::auto_wrap_primitive_pass_inputs,
     composite::composition_layers_by_id,
     data_parse::bake_data_parse_nodes,
+    feature_flags::apply_feature_flags,
     graph::{topo_sort, upstream_reachable},
     group_expand::expand_group_instances,
     image_inline::inline_image_file_connections_into_image_textures,
@@ -27,9 +28,13 @@ pub fn prepare_scene(input: &SceneDSL) -> Result<PreparedScene> {
 pub(crate) fn prepare_scene_with_report(
     input: &SceneDSL,
 ) -> Result<(PreparedScene, ScenePrepReport)> {
-    // Expand group instances before any filtering/validation.
+    // Expand group instances before any filtering/validation. Feature flags are
+    // resolved on both sides of the expansion: first so a disabled GroupInstance
+    // is never expanded, then for the group-internal nodes it brought in.
     let mut expanded = input.clone();
+    let mut disabled_feature_nodes = apply_feature_flags(&mut expanded)?;
     let expanded_group_instances = expand_group_instances(&mut expanded)?;
+    disabled_feature_nodes += apply_feature_flags(&mut expanded)?;
 
     // 1) Locate the RenderTarget-category node. Without it, the graph has no "main" entry.
     let scheme = schema::load_default_scheme()?;
@@ -89,6 +94,7 @@ pub(crate) fn prepare_scene_with_report(
         assets: expanded.assets.clone(),
         state_machine: expanded.state_machine.clone(),
         debug_artifacts: expanded.debug_artifacts.clone(),
+        feature_flags: expanded.feature_flags.clone(),
     };
 
     // Coerce primitive shader values into passes by synthesizing a fullscreen RenderPass.
@@ -186,6 +192,7 @@ pub(crate) fn prepare_scene_with_report(
         expanded_group_instances,
         auto_wrapped_pass_inputs,
         inlined_image_file_bindings,
+        disabled_feature_nodes,
     };
 
     Ok((prepared, report))
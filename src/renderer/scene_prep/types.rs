@@ -27,4 +27,5 @@ pub struct ScenePrepReport {
     pub expanded_group_instances: usize,
     pub auto_wrapped_pass_inputs: usize,
     pub inlined_image_file_bindings: usize,
+    pub disabled_feature_nodes: usize,
 }
@@ -0,0 +1,351 @@
+//! Device-free scene validation for `--validate` and the `validate_scene` WS
+//! request.
+//!
+//! Runs scene preparation, WGSL generation and full naga validation of every
+//! pass module, collecting the results as diagnostics instead of failing on
+//! the first error. No adapter or device is created.
+
+use serde::Serialize;
+
+use crate::asset_store::AssetStore;
+use crate::dsl::SceneDSL;
+use crate::schema;
+
+use super::scene_prep::{graph::upstream_reachable, prepare_scene};
+use super::validation::validate_wgsl_module;
+use super::wgsl::build_all_pass_wgsl_bundles_from_scene_with_assets;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// Which validation step produced a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticStage {
+    /// Reading or parsing the scene file.
+    Load,
+    /// Scene preparation: RenderTarget lookup, group expansion, schema checks.
+    Prepare,
+    /// WGSL generation for the scene's passes.
+    Codegen,
+    /// naga parse and validation of a generated pass module.
+    Wgsl,
+    /// Graph structure that renders but is likely a mistake.
+    Graph,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub stage: DiagnosticStage,
+    /// Scene node ids the diagnostic refers to, best effort for free-form errors.
+    pub node_ids: Vec<String>,
+    /// The node to highlight: the owner of `port` when one is named, else the
+    /// first of `node_ids`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    /// Port or param named as `<nodeId>.<port>` in the message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    /// Generated pass the diagnostic came from, for `wgsl` diagnostics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pass_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneValidationReport {
+    pub ok: bool,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub passes_validated: usize,
+    pub diagnostics: Vec<SceneDiagnostic>,
+}
+
+impl SceneValidationReport {
+    /// A report for a scene that could not be loaded at all.
+    pub fn load_failure(message: String) -> Self {
+        let mut report = Self::default();
+        report.push(
+            DiagnosticSeverity::Error,
+            DiagnosticStage::Load,
+            Vec::new(),
+            None,
+            message,
+        );
+        report.finish()
+    }
+
+    fn push(
+        &mut self,
+        severity: DiagnosticSeverity,
+        stage: DiagnosticStage,
+        node_ids: Vec<String>,
+        pass_id: Option<String>,
+        message: String,
+    ) {
+        self.diagnostics.push(SceneDiagnostic {
+            severity,
+            stage,
+            node_id: node_ids.first().cloned(),
+            node_ids,
+            port: None,
+            pass_id,
+            message,
+        });
+    }
+
+    /// Push an error parsed from a free-form message, locating its nodes and
+    /// port by name.
+    fn push_message_error(&mut self, scene: &SceneDSL, stage: DiagnosticStage, message: String) {
+        let node_ids = node_ids_in_message(scene, &message);
+        let node_port = node_port_in_message(scene, &message);
+        self.push(DiagnosticSeverity::Error, stage, node_ids, None, message);
+        if let Some((node_id, port)) = node_port
+            && let Some(diagnostic) = self.diagnostics.last_mut()
+        {
+            diagnostic.node_id = Some(node_id);
+            diagnostic.port = Some(port);
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        self.error_count = self
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Error)
+            .count();
+        self.warning_count = self.diagnostics.len() - self.error_count;
+        self.ok = self.error_count == 0;
+        self
+    }
+}
+
+/// Node ids that appear as whole tokens in `message`, so `Blur_1` is not
+/// reported for a message about `Blur_12`.
+fn node_ids_in_message(scene: &SceneDSL, message: &str) -> Vec<String> {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    let mut ids: Vec<String> = scene
+        .nodes
+        .iter()
+        .filter(|node| {
+            message.match_indices(node.id.as_str()).any(|(at, _)| {
+                let before = message[..at].chars().next_back();
+                let after = message[at + node.id.len()..].chars().next();
+                !before.is_some_and(is_id_char) && !after.is_some_and(is_id_char)
+            })
+        })
+        .map(|node| node.id.clone())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// The last `<nodeId>.<port>` reference in `message`. For a connection type
+/// mismatch that is the input side, which is usually the one to fix.
+fn node_port_in_message(scene: &SceneDSL, message: &str) -> Option<(String, String)> {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    let is_port_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == ':';
+    scene
+        .nodes
+        .iter()
+        .flat_map(|node| {
+            message
+                .match_indices(node.id.as_str())
+                .filter_map(move |(at, _)| {
+                    if message[..at].chars().next_back().is_some_and(is_id_char) {
+                        return None;
+                    }
+                    let rest = message[at + node.id.len()..].strip_prefix('.')?;
+                    let port_len = rest.find(|c: char| !is_port_char(c)).unwrap_or(rest.len());
+                    let port = rest[..port_len].trim_end_matches(':');
+                    (!port.is_empty()).then(|| (at, node.id.clone(), port.to_string()))
+                })
+        })
+        .max_by_key(|(at, node_id, _)| (*at, node_id.len()))
+        .map(|(_, node_id, port)| (node_id, port))
+}
+
+/// Items of a `...:\n- a\n- b` error list, as produced by scheme validation.
+fn message_list_items(message: &str) -> Vec<&str> {
+    message
+        .lines()
+        .filter_map(|line| line.strip_prefix("- "))
+        .collect()
+}
+
+/// The scene node a generated pass belongs to: the pass id itself, or the
+/// longest node id it extends (`Blur_3.h` → `Blur_3`).
+fn owning_node_id(scene: &SceneDSL, pass_id: &str) -> Option<String> {
+    scene
+        .nodes
+        .iter()
+        .filter(|node| {
+            pass_id == node.id
+                || pass_id
+                    .strip_prefix(node.id.as_str())
+                    .is_some_and(|rest| rest.starts_with(['.', ':', '/']))
+        })
+        .max_by_key(|node| node.id.len())
+        .map(|node| node.id.clone())
+}
+
+fn unreachable_node_warnings(scene: &SceneDSL, report: &mut SceneValidationReport) {
+    let Ok(scheme) = schema::load_default_scheme() else {
+        return;
+    };
+    let Some(target) = scene.nodes.iter().find(|n| {
+        scheme
+            .nodes
+            .get(&n.node_type)
+            .and_then(|s| s.category.as_deref())
+            == Some("RenderTarget")
+    }) else {
+        return;
+    };
+    let reachable = upstream_reachable(scene, &target.id);
+    for node in scene.nodes.iter().filter(|n| !reachable.contains(&n.id)) {
+        report.push(
+            DiagnosticSeverity::Warning,
+            DiagnosticStage::Graph,
+            vec![node.id.clone()],
+            None,
+            format!(
+                "{} ({}) is not connected to the RenderTarget and is ignored",
+                node.id, node.node_type
+            ),
+        );
+    }
+}
+
+/// Validate `scene` without a GPU: prepare it, generate WGSL for every pass
+/// and run naga validation on each module.
+pub fn validate_scene(scene: &SceneDSL, asset_store: Option<&AssetStore>) -> SceneValidationReport {
+    let mut report = SceneValidationReport::default();
+    unreachable_node_warnings(scene, &mut report);
+
+    if let Err(e) = prepare_scene(scene) {
+        let message = format!("{e:#}");
+        let items = message_list_items(&message);
+        if items.is_empty() {
+            report.push_message_error(scene, DiagnosticStage::Prepare, message);
+        } else {
+            for item in items {
+                report.push_message_error(scene, DiagnosticStage::Prepare, item.to_string());
+            }
+        }
+        return report.finish();
+    }
+
+    let bundles = match build_all_pass_wgsl_bundles_from_scene_with_assets(scene, asset_store) {
+        Ok(bundles) => bundles,
+        Err(e) => {
+            report.push_message_error(scene, DiagnosticStage::Codegen, format!("{e:#}"));
+            return report.finish();
+        }
+    };
+
+    for (pass_id, bundle) in &bundles {
+        let modules = std::iter::once(&bundle.module).chain(bundle.compute.as_ref());
+        for source in modules {
+            if let Err(e) = validate_wgsl_module(source) {
+                report.push(
+                    DiagnosticSeverity::Error,
+                    DiagnosticStage::Wgsl,
+                    owning_node_id(scene, pass_id).into_iter().collect(),
+                    Some(pass_id.clone()),
+                    format!("{e:#}"),
+                );
+            }
+        }
+    }
+    report.passes_validated = bundles.len();
+    report.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scene_with_nodes(ids: &[&str]) -> SceneDSL {
+        let nodes: Vec<_> = ids
+            .iter()
+            .map(|id| json!({ "id": id, "type": "FloatInput", "params": {} }))
+            .collect();
+        serde_json::from_value(json!({
+            "version": "1.0",
+            "metadata": { "name": "validate" },
+            "nodes": nodes,
+            "connections": [],
+            "outputs": null
+        }))
+        .expect("scene should deserialize")
+    }
+
+    #[test]
+    fn diagnostics_resolve_node_ids() {
+        let scene = scene_with_nodes(&["Blur_1", "Blur_12", "RenderPass_3"]);
+        assert_eq!(
+            node_ids_in_message(
+                &scene,
+                "RenderPass.geometry missing for RenderPass_3 (Blur_12)"
+            ),
+            vec!["Blur_12".to_string(), "RenderPass_3".to_string()]
+        );
+        assert_eq!(
+            owning_node_id(&scene, "Blur_12.h").as_deref(),
+            Some("Blur_12")
+        );
+        assert_eq!(owning_node_id(&scene, "Blur_1").as_deref(), Some("Blur_1"));
+        assert_eq!(owning_node_id(&scene, "sys.present"), None);
+    }
+
+    #[test]
+    fn diagnostics_locate_ports_and_split_error_lists() {
+        let scene = scene_with_nodes(&["Blur_1", "Blur_12", "Mix_2"]);
+        assert_eq!(
+            node_port_in_message(
+                &scene,
+                "connection 'c1' type mismatch: 'Blur_12.output' (color) -> 'Mix_2.factor' (float)"
+            ),
+            Some(("Mix_2".to_string(), "factor".to_string()))
+        );
+        assert_eq!(
+            node_port_in_message(&scene, "Blur_1 (Blur) is not connected"),
+            None
+        );
+        assert_eq!(
+            message_list_items("scene failed scheme validation (2 error(s)):\n- a\n- b"),
+            vec!["a", "b"]
+        );
+
+        let mut report = SceneValidationReport::default();
+        report.push_message_error(
+            &scene,
+            DiagnosticStage::Prepare,
+            "missing required param 'Blur_1.radius' (type Float)".to_string(),
+        );
+        let diagnostic = &report.diagnostics[0];
+        assert_eq!(diagnostic.node_id.as_deref(), Some("Blur_1"));
+        assert_eq!(diagnostic.port.as_deref(), Some("radius"));
+    }
+
+    #[test]
+    fn scene_without_render_target_reports_prepare_error() {
+        let report = validate_scene(&scene_with_nodes(&["a"]), None);
+        assert!(!report.ok);
+        assert_eq!(report.error_count, 1);
+        assert_eq!(report.diagnostics[0].stage, DiagnosticStage::Prepare);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["diagnostics"][0]["severity"], "error");
+        assert!(json["diagnostics"][0].get("passId").is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use anyhow::{Result, bail};
+use rayon::prelude::*;
 use rust_wgpu_fiber::shader_space::ShaderSpace;
 use rust_wgpu_fiber::{ResourceName, eframe::wgpu};
 
@@ -8,16 +9,26 @@ use crate::{
     asset_store::AssetStore,
     dsl::SceneDSL,
     renderer::{
+        ChannelDebugMode,
         pass_debug::PassDebugSource,
         render_plan::{
             planner::RenderPlanner,
             types::{PlanBuildOptions, PlanningGpuCaps},
         },
         types::PassBindings,
+        validation::warn_invalid_wgsl_module_cached,
     },
 };
 
-use super::{error_space, finalizer::ShaderSpaceFinalizer};
+use super::{
+    crop::{OutputCrop, apply_output_crop},
+    debug_overlay::{PassDebugOverlay, apply_pass_debug_overlays},
+    error_space,
+    finalizer::ShaderSpaceFinalizer,
+    pick::{PickTarget, apply_pick_id_passes},
+    progress::{ProgressTracker, RenderProgress, RenderProgressStage},
+    sanitize::{NonFiniteSanitizeOptions, apply_non_finite_sanitize},
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ShaderSpacePresentationMode {
@@ -42,6 +53,27 @@ pub struct ShaderSpaceBuildOptions {
     pub debug_dump_wgsl_dir: Option<PathBuf>,
     pub pass_shader_overrides: HashMap<String, String>,
     pub strict_pass_shader_overrides: bool,
+    /// Debug overlays keyed by render pass name, applied after shader overrides.
+    pub pass_debug_overlays: HashMap<String, PassDebugOverlay>,
+    /// Global coordinate visualization replacing every draw pass's material output.
+    pub channel_debug: ChannelDebugMode,
+    /// Safe mode: replace NaN/Inf fragment outputs with a sentinel colour.
+    pub non_finite_sanitize: NonFiniteSanitizeOptions,
+    /// Skip naga validation for modules that already validated in this
+    /// process. Failing modules are logged the first time either way.
+    pub skip_trusted_wgsl_validation: bool,
+    /// Add triangular-PDF dither when encoding to 8-bit display/export
+    /// textures. A `File` target with `dither: true` enables it as well.
+    pub dither_sdr_output: bool,
+    /// Render only this window of the output; see [`OutputCrop`].
+    pub output_crop: Option<OutputCrop>,
+    /// Also render pass ids into [`super::PICK_TEXTURE_NAME`] for canvas picking.
+    pub pick_ids: bool,
+    /// Plan against baseline wgpu features and limits instead of the device's,
+    /// ignore adapter-specific MSAA support and freeze frame-dependent node
+    /// params, so the same scene builds the same passes on every run. The
+    /// device should come from [`super::deterministic_renderer_config`].
+    pub deterministic: bool,
 }
 
 pub struct ShaderSpaceBuildResult {
@@ -61,6 +93,14 @@ pub struct ShaderSpaceBuildResult {
     pub pass_bindings: Vec<PassBindings>,
     pub pipeline_signature: [u8; 32],
     pub pass_debug_sources: HashMap<String, PassDebugSource>,
+    /// The crop the output textures cover, when `output_crop` was requested
+    /// and the scene supports it. `None` means the full frame was built.
+    pub output_crop: Option<OutputCrop>,
+    /// Passes addressed by the ids in the pick texture; empty unless
+    /// `pick_ids` was requested.
+    pub pick_targets: Vec<PickTarget>,
+    /// Composited passes in execution order, for per-pass progress renders.
+    pub pass_order: Vec<ResourceName>,
 }
 
 pub struct ShaderSpaceBuilder {
@@ -98,26 +138,67 @@ impl ShaderSpaceBuilder {
     }
 
     pub fn build(self, scene: &SceneDSL) -> Result<ShaderSpaceBuildResult> {
-        let plan_options = PlanBuildOptions {
-            gpu_caps: PlanningGpuCaps {
+        self.build_with_progress(scene, &mut |_| {})
+    }
+
+    /// As [`Self::build`], reporting planning, shader validation and pipeline
+    /// creation as they finish.
+    pub fn build_with_progress(
+        self,
+        scene: &SceneDSL,
+        on_progress: &mut dyn FnMut(RenderProgress),
+    ) -> Result<ShaderSpaceBuildResult> {
+        let mut progress = ProgressTracker::new(RenderProgressStage::Build, 3, on_progress);
+        let pinned_scene;
+        let (scene, gpu_caps, adapter) = if self.options.deterministic {
+            if !wgpu::Limits::default().check_limits(&self.device.limits()) {
+                bail!(
+                    "deterministic rendering requires a device supporting the default wgpu limits"
+                );
+            }
+            let mut scene = scene.clone();
+            crate::dsl::pin_frame_dependent_params(&mut scene);
+            pinned_scene = scene;
+            (&pinned_scene, PlanningGpuCaps::default(), None)
+        } else {
+            let gpu_caps = PlanningGpuCaps {
                 features: self.device.features(),
                 limits: self.device.limits().clone(),
-            },
+            };
+            (scene, gpu_caps, self.adapter.as_ref())
+        };
+        let features = gpu_caps.features;
+        let plan_options = PlanBuildOptions {
+            gpu_caps,
             presentation_mode: self.options.presentation_mode,
             debug_dump_wgsl_dir: self.options.debug_dump_wgsl_dir.clone(),
+            channel_debug: self.options.channel_debug,
+            dither_sdr_output: self.options.dither_sdr_output,
         };
-        let mut plan = RenderPlanner::new(plan_options).plan(
-            scene,
-            self.asset_store.as_ref(),
-            self.adapter.as_ref(),
-        )?;
+        let mut plan =
+            RenderPlanner::new(plan_options).plan(scene, self.asset_store.as_ref(), adapter)?;
         apply_pass_shader_overrides(
             &mut plan,
             &self.options.pass_shader_overrides,
             self.options.strict_pass_shader_overrides,
         )?;
+        apply_pass_debug_overlays(&mut plan, &self.options.pass_debug_overlays, features);
+        apply_non_finite_sanitize(&mut plan, self.options.non_finite_sanitize);
+        let pick_targets = if self.options.pick_ids {
+            apply_pick_id_passes(&mut plan)
+        } else {
+            Vec::new()
+        };
+        let output_crop = self
+            .options
+            .output_crop
+            .filter(|crop| apply_output_crop(&mut plan, *crop));
+        progress.step("plan");
+        validate_plan_shaders(&plan, self.options.skip_trusted_wgsl_validation);
+        progress.step("validate");
         let finalized =
             ShaderSpaceFinalizer::finalize(&plan, self.device, self.queue, self.adapter.as_ref())?;
+        progress.step("pipelines");
 
         Ok(ShaderSpaceBuildResult {
             shader_space: finalized.shader_space,
@@ -129,6 +210,9 @@ impl ShaderSpaceBuilder {
             pass_bindings: finalized.pass_bindings,
             pipeline_signature: finalized.pipeline_signature,
             pass_debug_sources: plan.pass_debug_sources,
+            output_crop,
+            pick_targets,
+            pass_order: finalized.pass_order,
         })
     }
 
@@ -146,6 +230,9 @@ impl ShaderSpaceBuilder {
             pass_bindings,
             pipeline_signature,
             pass_debug_sources: HashMap::new(),
+            output_crop: None,
+            pick_targets: Vec::new(),
+            pass_order: Vec::new(),
         })
     }
 }
@@ -184,6 +271,14 @@ fn apply_pass_shader_overrides(
         }
     }
 
+    for spec in &mut plan.resources.compute_pass_specs {
+        let pass_name = spec.name.as_str();
+        if let Some(source) = overrides.get(pass_name) {
+            spec.shader_wgsl = source.clone();
+            applied.insert(pass_name.to_string());
+        }
+    }
+
     if strict && applied.len() != overrides.len() {
         let mut missing = overrides
             .keys()
@@ -200,6 +295,43 @@ fn apply_pass_shader_overrides(
     Ok(())
 }
 
+/// Runs naga over every pass module before any pipeline is created and logs
+/// failures by pass name. The check is advisory; see
+/// [`warn_invalid_wgsl_module_cached`].
+fn validate_plan_shaders(
+    plan: &crate::renderer::render_plan::types::RenderPlan,
+    skip_trusted: bool,
+) {
+    let resources = &plan.resources;
+    let modules: Vec<(&str, &str)> = resources
+        .render_pass_specs
+        .iter()
+        .map(|spec| (spec.name.as_str(), spec.shader_wgsl.as_str()))
+        .chain(
+            resources
+                .image_prepasses
+                .iter()
+                .map(|spec| (spec.pass_name.as_str(), spec.shader_wgsl.as_str())),
+        )
+        .chain(
+            resources
+                .depth_resolve_passes
+                .iter()
+                .map(|spec| (spec.pass_name.as_str(), spec.shader_wgsl.as_str())),
+        )
+        .chain(
+            resources
+                .compute_pass_specs
+                .iter()
+                .map(|spec| (spec.name.as_str(), spec.shader_wgsl.as_str())),
+        )
+        .collect();
+
+    modules.par_iter().for_each(|(pass_name, source)| {
+        warn_invalid_wgsl_module_cached(source, &format!("pass {pass_name}"), skip_trusted);
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, path::PathBuf};
@@ -242,6 +374,8 @@ mod tests {
             gpu_caps: PlanningGpuCaps::default(),
             presentation_mode: ShaderSpacePresentationMode::UiSdrDisplayEncode,
             debug_dump_wgsl_dir: None,
+            channel_debug: Default::default(),
+            dither_sdr_output: false,
         })
         .plan(&scene, assets.as_ref(), None)?;
 
@@ -0,0 +1,260 @@
+//! Output crop: render only a window of the full frame.
+//!
+//! When the canvas is zoomed into a large output, the visible window can be
+//! rendered on its own at display density instead of magnifying the full-frame
+//! texture. Frame-sized targets shrink to the crop's target size, and every
+//! draw pass keeps its pixel-space geometry but gets a clip-space window
+//! transform prepended to its camera, so materials still see full-frame
+//! `frag_coord` values and fine detail is evaluated at the higher density.
+//!
+//! Display-encode passes read the scene output 1:1 through a fullscreen quad,
+//! so they stay correct when the source and destination shrink together. Any
+//! other pass that samples a frame-sized target (pass textures, blur and
+//! resolve chains) would need its uv mapping rewritten, so such plans are left
+//! uncropped and the caller falls back to the full frame.
+
+use std::collections::HashSet;
+
+use rust_wgpu_fiber::ResourceName;
+
+use crate::renderer::{
+    camera::mat4_mul_col_major,
+    render_plan::{
+        resource_naming::{UI_PRESENT_HDR_GAMMA_SUFFIX, UI_PRESENT_SDR_SRGB_SUFFIX},
+        types::RenderPlan,
+    },
+};
+
+/// A window of the scene output, in output pixels with a top-left origin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutputCrop {
+    pub origin: [f32; 2],
+    pub size: [f32; 2],
+    /// Size of the cropped targets. Larger than `size` renders the window
+    /// supersampled, e.g. at device-pixel density while zoomed in.
+    pub target_size: [u32; 2],
+}
+
+impl OutputCrop {
+    /// Column-major clip-space transform that maps the window onto the full
+    /// NDC square of a `frame_size` output.
+    pub fn clip_transform(&self, frame_size: [u32; 2]) -> [f32; 16] {
+        let frame_w = frame_size[0].max(1) as f32;
+        let frame_h = frame_size[1].max(1) as f32;
+        let sx = self.size[0] / frame_w;
+        let sy = self.size[1] / frame_h;
+        // Window centre in NDC. Output rows run top-down while NDC y points up.
+        let cx = (2.0 * self.origin[0] + self.size[0]) / frame_w - 1.0;
+        let cy = 1.0 - (2.0 * self.origin[1] + self.size[1]) / frame_h;
+        [
+            1.0 / sx,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0 / sy,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+            -cx / sx,
+            -cy / sy,
+            0.0,
+            1.0,
+        ]
+    }
+
+    fn is_valid(&self) -> bool {
+        self.size.iter().all(|v| v.is_finite() && *v > 0.0)
+            && self.origin.iter().all(|v| v.is_finite())
+            && self.target_size.iter().all(|v| *v > 0)
+    }
+}
+
+fn is_display_encode_pass(name: &ResourceName) -> bool {
+    let name = name.as_str();
+    [UI_PRESENT_SDR_SRGB_SUFFIX, UI_PRESENT_HDR_GAMMA_SUFFIX]
+        .iter()
+        .any(|suffix| {
+            name.strip_suffix(".pass")
+                .is_some_and(|stem| stem.ends_with(suffix))
+        })
+}
+
+/// Rewrites `plan` so it renders only `crop`. Returns false, leaving the plan
+/// untouched, when the plan has passes the crop cannot be applied to.
+pub(crate) fn apply_output_crop(plan: &mut RenderPlan, crop: OutputCrop) -> bool {
+    if !crop.is_valid() {
+        return false;
+    }
+    let resources = &mut plan.resources;
+    // Clip rects are baked into shaders in full-frame target pixels.
+    if !resources.depth_resolve_passes.is_empty()
+        || !resources.pass_extensions.is_empty()
+        || !resources.pass_clip_rect_by_name.is_empty()
+    {
+        return false;
+    }
+    let Some(frame_size) = resources
+        .textures
+        .iter()
+        .find(|decl| decl.name == plan.scene_output_texture)
+        .map(|decl| decl.size)
+    else {
+        return false;
+    };
+
+    let prepass_outputs: HashSet<&ResourceName> = resources
+        .image_prepasses
+        .iter()
+        .map(|prepass| &prepass.dst_texture)
+        .collect();
+    let frame_textures: HashSet<ResourceName> = resources
+        .textures
+        .iter()
+        .filter(|decl| decl.size == frame_size && !prepass_outputs.contains(&decl.name))
+        .map(|decl| decl.name.clone())
+        .collect();
+
+    for spec in &resources.render_pass_specs {
+        if !frame_textures.contains(&spec.target_texture) {
+            return false;
+        }
+        let samples_frame = spec
+            .texture_bindings
+            .iter()
+            .any(|binding| frame_textures.contains(&binding.texture));
+        if samples_frame && !is_display_encode_pass(&spec.name) {
+            return false;
+        }
+    }
+
+    for decl in &mut resources.textures {
+        if frame_textures.contains(&decl.name) {
+            decl.size = crop.target_size;
+        }
+    }
+    let window = crop.clip_transform(frame_size);
+    for spec in &mut resources.render_pass_specs {
+        if !is_display_encode_pass(&spec.name) {
+            spec.params.camera = mat4_mul_col_major(window, spec.params.camera);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anyhow::Result;
+
+    use super::{OutputCrop, apply_output_crop};
+    use crate::{
+        asset_store,
+        renderer::{
+            ShaderSpacePresentationMode,
+            camera::{legacy_projection_camera_matrix, mat4_mul_col_major},
+            render_plan::{
+                planner::RenderPlanner,
+                types::{PlanBuildOptions, PlanningGpuCaps, RenderPlan},
+            },
+        },
+    };
+
+    fn plan_case(case_name: &str) -> Result<RenderPlan> {
+        let archive = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/render/editor-examples")
+            .join(case_name)
+            .join("scene.nforge");
+        let (scene, store) = asset_store::load_from_nforge(&archive)?;
+        let store = (!scene.assets.is_empty()).then_some(&store);
+        RenderPlanner::new(PlanBuildOptions {
+            gpu_caps: PlanningGpuCaps::default(),
+            presentation_mode: ShaderSpacePresentationMode::UiSdrDisplayEncode,
+            debug_dump_wgsl_dir: None,
+            channel_debug: Default::default(),
+            dither_sdr_output: false,
+        })
+        .plan(&scene, store, None)
+    }
+
+    fn project(m: [f32; 16], p: [f32; 2]) -> [f32; 2] {
+        let x = m[0] * p[0] + m[4] * p[1] + m[12];
+        let y = m[1] * p[0] + m[5] * p[1] + m[13];
+        let w = m[3] * p[0] + m[7] * p[1] + m[15];
+        [x / w, y / w]
+    }
+
+    #[test]
+    fn clip_transform_maps_window_corners_to_ndc_corners() {
+        let frame = [800, 600];
+        let crop = OutputCrop {
+            origin: [100.0, 50.0],
+            size: [200.0, 100.0],
+            target_size: [400, 200],
+        };
+        let camera = mat4_mul_col_major(
+            crop.clip_transform(frame),
+            legacy_projection_camera_matrix([800.0, 600.0]),
+        );
+        // Pixel space is y-up: output row 50 from the top is y = 550.
+        let top_left = project(camera, [100.0, 550.0]);
+        let bottom_right = project(camera, [300.0, 450.0]);
+        for (got, want) in [(top_left, [-1.0, 1.0]), (bottom_right, [1.0, -1.0])] {
+            assert!((got[0] - want[0]).abs() < 1e-5, "{got:?} vs {want:?}");
+            assert!((got[1] - want[1]).abs() < 1e-5, "{got:?} vs {want:?}");
+        }
+    }
+
+    #[test]
+    fn crop_resizes_frame_targets_and_offsets_draw_cameras() -> Result<()> {
+        let mut plan = plan_case("graph-rectangle")?;
+        let before = plan.clone();
+        let crop = OutputCrop {
+            origin: [16.0, 32.0],
+            size: [64.0, 48.0],
+            target_size: [128, 96],
+        };
+        assert!(apply_output_crop(&mut plan, crop));
+
+        let output = plan
+            .resources
+            .textures
+            .iter()
+            .find(|decl| decl.name == plan.scene_output_texture)
+            .expect("scene output texture");
+        assert_eq!(output.size, [128, 96]);
+        for (spec, original) in plan
+            .resources
+            .render_pass_specs
+            .iter()
+            .zip(&before.resources.render_pass_specs)
+        {
+            assert_eq!(spec.shader_wgsl, original.shader_wgsl);
+            if super::is_display_encode_pass(&spec.name) {
+                assert_eq!(spec.params.camera, original.params.camera);
+            } else {
+                assert_ne!(spec.params.camera, original.params.camera);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn crop_is_refused_for_plans_with_resampled_targets() -> Result<()> {
+        let mut plan = plan_case("bloom")?;
+        let sizes: Vec<[u32; 2]> = plan.resources.textures.iter().map(|t| t.size).collect();
+        let crop = OutputCrop {
+            origin: [0.0, 0.0],
+            size: [32.0, 32.0],
+            target_size: [32, 32],
+        };
+        assert!(!apply_output_crop(&mut plan, crop));
+        let after: Vec<[u32; 2]> = plan.resources.textures.iter().map(|t| t.size).collect();
+        assert_eq!(sizes, after);
+        Ok(())
+    }
+}
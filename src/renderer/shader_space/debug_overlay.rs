@@ -0,0 +1,199 @@
+//! Per-pass debug overlays applied to a `RenderPlan` before finalization.
+//!
+//! Overlays wrap the generated `fs_main` instead of replacing it, so the
+//! pass keeps its bindings and discard behaviour while its output is
+//! recoloured (tint), drawn as lines (wireframe) or accumulated additively
+//! (overdraw heatmap).
+
+use std::collections::HashMap;
+
+use rust_wgpu_fiber::eframe::wgpu::{self, BlendState};
+
+use crate::renderer::render_plan::types::RenderPlan;
+
+const FRAGMENT_SIGNATURE: &str = "fn fs_main(in: VSOut) -> @location(0) vec4f";
+const INNER_FRAGMENT_NAME: &str = "fs_main_debug_inner";
+const TINT_MIX: f32 = 0.6;
+/// Colour added per covering fragment in overdraw mode; ~8 layers saturate red.
+const OVERDRAW_STEP: [f32; 3] = [0.125, 0.05, 0.02];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PassDebugOverlay {
+    /// Mix the pass output with a stable per-pass colour.
+    Tint,
+    /// Rasterize triangle edges only (needs `POLYGON_MODE_LINE`).
+    Wireframe,
+    /// Additively accumulate a constant per fragment to show overdraw.
+    Overdraw,
+}
+
+impl PassDebugOverlay {
+    pub const ALL: [Self; 3] = [Self::Tint, Self::Wireframe, Self::Overdraw];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Tint => "Tint",
+            Self::Wireframe => "Wireframe",
+            Self::Overdraw => "Overdraw",
+        }
+    }
+}
+
+/// Rewrites the render pass specs named in `overlays`. Wireframe falls back to
+/// tint when the device lacks `POLYGON_MODE_LINE`. Passes whose fragment entry
+/// does not match the generated shape are left untouched.
+pub(crate) fn apply_pass_debug_overlays(
+    plan: &mut RenderPlan,
+    overlays: &HashMap<String, PassDebugOverlay>,
+    features: wgpu::Features,
+) {
+    if overlays.is_empty() {
+        return;
+    }
+
+    let supports_line_mode = features.contains(wgpu::Features::POLYGON_MODE_LINE);
+    for spec in &mut plan.resources.render_pass_specs {
+        let Some(&requested) = overlays.get(spec.name.as_str()) else {
+            continue;
+        };
+        let overlay = if requested == PassDebugOverlay::Wireframe && !supports_line_mode {
+            eprintln!(
+                "[pass-debug-overlay] POLYGON_MODE_LINE unsupported; tinting {} instead",
+                spec.name.as_str()
+            );
+            PassDebugOverlay::Tint
+        } else {
+            requested
+        };
+
+        let Some(shader_wgsl) = wrap_fragment_entry(&spec.shader_wgsl, spec.name.as_str(), overlay)
+        else {
+            eprintln!(
+                "[pass-debug-overlay] fragment entry not found; skipping {}",
+                spec.name.as_str()
+            );
+            continue;
+        };
+        spec.shader_wgsl = shader_wgsl;
+
+        match overlay {
+            PassDebugOverlay::Tint => {}
+            PassDebugOverlay::Wireframe => {
+                plan.resources
+                    .pass_polygon_mode_by_name
+                    .insert(spec.name.clone(), wgpu::PolygonMode::Line);
+            }
+            PassDebugOverlay::Overdraw => {
+                spec.blend_state = BlendState {
+                    color: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::One,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    alpha: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::One,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                };
+            }
+        }
+    }
+}
+
+/// Stable, well-separated colour for a pass name (FNV-1a hash → hue).
+pub fn pass_debug_color(pass_name: &str) -> [f32; 3] {
+    let hash = pass_name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    });
+    let hue = (hash % 360) as f32 / 60.0;
+    let (saturation, value) = (0.75, 1.0);
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    [r + m, g + m, b + m]
+}
+
+/// Renames the generated `fs_main` to `inner_name` (dropping its `@fragment`
+/// attribute) and appends a new `fs_main` with `body`. Returns `None` when the
+/// source does not contain the generated fragment entry shape.
+pub(crate) fn wrap_generated_fragment_entry(
+    source: &str,
+    inner_name: &str,
+    body: &str,
+) -> Option<String> {
+    let signature_at = source.find(FRAGMENT_SIGNATURE)?;
+    let head = source[..signature_at].trim_end();
+    let head = head.strip_suffix("@fragment")?;
+    let rest = &source[signature_at + FRAGMENT_SIGNATURE.len()..];
+
+    Some(format!(
+        "{head}fn {inner_name}(in: VSOut) -> vec4f{rest}\n@fragment\n{FRAGMENT_SIGNATURE} {{\n{body}}}\n"
+    ))
+}
+
+fn wrap_fragment_entry(source: &str, pass_name: &str, overlay: PassDebugOverlay) -> Option<String> {
+    let [r, g, b] = pass_debug_color(pass_name);
+    let body = match overlay {
+        PassDebugOverlay::Tint => format!(
+            "    let base = {INNER_FRAGMENT_NAME}(in);\n    let tint = vec3f({r:.4}, {g:.4}, {b:.4}) * base.a;\n    return vec4f(mix(base.rgb, tint, {TINT_MIX:.2}), base.a);\n"
+        ),
+        PassDebugOverlay::Wireframe => format!(
+            "    _ = {INNER_FRAGMENT_NAME}(in);\n    return vec4f({r:.4}, {g:.4}, {b:.4}, 1.0);\n"
+        ),
+        PassDebugOverlay::Overdraw => {
+            let [r, g, b] = OVERDRAW_STEP;
+            format!(
+                "    _ = {INNER_FRAGMENT_NAME}(in);\n    return vec4f({r:.4}, {g:.4}, {b:.4}, 0.0);\n"
+            )
+        }
+    };
+
+    wrap_generated_fragment_entry(source, INNER_FRAGMENT_NAME, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PassDebugOverlay, pass_debug_color, wrap_fragment_entry};
+
+    const SOURCE: &str = "struct VSOut { @builtin(position) position: vec4f };\n\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {\n    return vec4f(1.0);\n}\n";
+
+    #[test]
+    fn tint_wraps_fragment_entry_once() {
+        let wrapped =
+            wrap_fragment_entry(SOURCE, "node_2.pass", PassDebugOverlay::Tint).expect("wrapped");
+        assert_eq!(wrapped.matches("@fragment").count(), 1);
+        assert!(wrapped.contains("fn fs_main_debug_inner(in: VSOut) -> vec4f {"));
+        assert!(wrapped.contains("let base = fs_main_debug_inner(in);"));
+        crate::renderer::validate_wgsl(&wrapped).expect("valid wgsl");
+    }
+
+    #[test]
+    fn overdraw_and_wireframe_produce_valid_wgsl() {
+        for overlay in [PassDebugOverlay::Overdraw, PassDebugOverlay::Wireframe] {
+            let wrapped = wrap_fragment_entry(SOURCE, "node_2.pass", overlay).expect("wrapped");
+            crate::renderer::validate_wgsl(&wrapped).expect("valid wgsl");
+        }
+    }
+
+    #[test]
+    fn unknown_fragment_shape_is_skipped() {
+        let source = "@fragment\nfn fs_main() -> @location(0) vec4f { return vec4f(1.0); }";
+        assert!(wrap_fragment_entry(source, "p", PassDebugOverlay::Tint).is_none());
+    }
+
+    #[test]
+    fn pass_colors_are_stable_and_in_range() {
+        let color = pass_debug_color("node_2.pass");
+        assert_eq!(color, pass_debug_color("node_2.pass"));
+        assert!(color.iter().all(|c| (0.0..=1.0).contains(c)));
+    }
+}
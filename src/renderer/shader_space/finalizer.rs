@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -20,6 +21,7 @@ use crate::renderer::{
     utils::{as_bytes, as_bytes_slice},
 };
 
+use super::sanitize::NON_FINITE_COUNTER_BINDING;
 use super::texture_caps::{
     collect_texture_capability_requirements, validate_texture_capability_requirements,
 };
@@ -30,6 +32,8 @@ pub(crate) struct FinalizedShaderSpace {
     pub shader_space: ShaderSpace,
     pub pass_bindings: Vec<PassBindings>,
     pub pipeline_signature: [u8; 32],
+    /// Passes in composition order.
+    pub pass_order: Vec<ResourceName>,
 }
 
 pub(crate) struct ShaderSpaceFinalizer;
@@ -68,6 +72,23 @@ impl ShaderSpaceFinalizer {
                     }),
                 extension: resources.pass_extensions.get(&spec.pass_id).cloned(),
             })
+            .chain(
+                resources
+                    .compute_pass_specs
+                    .iter()
+                    .map(|spec| PassBindings {
+                        pass_id: spec.pass_id.clone(),
+                        params_buffer: spec.params_buffer.clone(),
+                        base_params: spec.params,
+                        graph_binding: spec.graph_binding.clone(),
+                        last_graph_hash: spec.graph_values.as_ref().map(|values| {
+                            crate::renderer::graph_uniforms::hash_bytes(values.as_slice())
+                        }),
+                        shader_parameter_binding: None,
+                        last_shader_parameter_hash: None,
+                        extension: None,
+                    }),
+            )
             .collect();
         let pipeline_signature =
             compute_pipeline_signature_for_pass_bindings(&plan.prepared.scene, &pass_bindings);
@@ -132,6 +153,15 @@ impl ShaderSpaceFinalizer {
                 usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
             });
         }
+        for counter in resources.pass_non_finite_counter_by_name.values() {
+            buffer_specs.push(BufferSpec::Sized {
+                name: counter.clone(),
+                size: core::mem::size_of::<u32>(),
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_SRC
+                    | wgpu::BufferUsages::COPY_DST,
+            });
+        }
         for spec in &resources.image_prepasses {
             buffer_specs.push(BufferSpec::Sized {
                 name: spec.params_buffer.clone(),
@@ -156,6 +186,11 @@ impl ShaderSpaceFinalizer {
         }
         shader_space.declare_buffers(buffer_specs);
 
+        let compute_outputs: HashSet<&ResourceName> = resources
+            .compute_pass_specs
+            .iter()
+            .map(|spec| &spec.output_texture)
+            .collect();
         let mut texture_specs: Vec<FiberTextureSpec> = resources
             .textures
             .iter()
@@ -163,7 +198,11 @@ impl ShaderSpaceFinalizer {
                 name: texture.name.clone(),
                 resolution: texture.size,
                 format: texture.format,
-                usage: if texture.sample_count > 1 {
+                usage: if compute_outputs.contains(&texture.name) {
+                    TextureUsages::STORAGE_BINDING
+                        | TextureUsages::TEXTURE_BINDING
+                        | TextureUsages::COPY_SRC
+                } else if texture.sample_count > 1 {
                     let base = TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
                     if texture.needs_sampling {
                         base | TextureUsages::TEXTURE_BINDING
@@ -294,10 +333,20 @@ impl ShaderSpaceFinalizer {
                 .get(&spec.name)
                 .copied()
                 .unwrap_or(None);
+            let polygon_mode = resources
+                .pass_polygon_mode_by_name
+                .get(&spec.name)
+                .copied()
+                .unwrap_or(wgpu::PolygonMode::Fill);
             let depth_stencil_attachment = resources
                 .pass_depth_attachment_by_name
                 .get(&spec.name)
                 .cloned();
+            let stencil_attachment = resources.pass_stencil_by_name.get(&spec.name).cloned();
+            let non_finite_counter = resources
+                .pass_non_finite_counter_by_name
+                .get(&spec.name)
+                .cloned();
             let graph_binding = spec.graph_binding.clone();
             let shader_parameter_binding = resources
                 .shader_parameter_buffers_by_pass
@@ -380,6 +429,15 @@ impl ShaderSpaceFinalizer {
                         true,
                     );
                 }
+                if let Some(non_finite_counter) = non_finite_counter.clone() {
+                    pass_builder = pass_builder.bind_storage_buffer(
+                        0,
+                        NON_FINITE_COUNTER_BINDING,
+                        non_finite_counter,
+                        ShaderStages::FRAGMENT,
+                        false,
+                    );
+                }
 
                 let vertex_attributes = match spec.vertex_layout {
                     VertexLayoutKind::PositionUv => {
@@ -445,16 +503,106 @@ impl ShaderSpaceFinalizer {
                 if let Some(depth_texture) = depth_stencil_attachment.clone() {
                     pass_builder = pass_builder.bind_depth_stencil_attachment(depth_texture);
                 }
+                if let Some(stencil) = stencil_attachment.clone() {
+                    let stencil_load_op = if stencil.clear {
+                        wgpu::LoadOp::Clear(0)
+                    } else {
+                        wgpu::LoadOp::Load
+                    };
+                    pass_builder = pass_builder
+                        .bind_depth_stencil_attachment(stencil.texture)
+                        .depth_stencil_state(stencil.stencil.depth_stencil_state())
+                        .stencil_reference(stencil.stencil.reference)
+                        .stencil_load_op(stencil_load_op);
+                }
                 if let Some(resolve_target) = resolve_target.clone() {
                     pass_builder = pass_builder.resolve_target(resolve_target);
                 }
                 pass_builder
                     .cull_mode(cull_mode)
+                    .polygon_mode(polygon_mode)
                     .blending(blend_state)
                     .load_op(color_load_op)
             });
         }
 
+        for spec in &resources.compute_pass_specs {
+            let texture_names: Vec<ResourceName> = spec
+                .texture_bindings
+                .iter()
+                .map(|binding| binding.texture.clone())
+                .collect();
+            let sampler_names: Vec<ResourceName> = spec
+                .sampler_kinds
+                .iter()
+                .map(|kind| match kind {
+                    SamplerKind::NearestClamp => nearest_sampler.clone(),
+                    SamplerKind::NearestMirror => nearest_mirror_sampler.clone(),
+                    SamplerKind::NearestRepeat => nearest_repeat_sampler.clone(),
+                    SamplerKind::LinearMirror => linear_mirror_sampler.clone(),
+                    SamplerKind::LinearRepeat => linear_repeat_sampler.clone(),
+                    SamplerKind::LinearClamp => linear_clamp_sampler.clone(),
+                })
+                .collect();
+            let fallback_sampler = linear_clamp_sampler.clone();
+
+            if let Some(dir) = &plan.debug_dump_wgsl_dir {
+                let debug_dump_path =
+                    dir.join(format!("node-forge-pass.{}.wgsl", spec.name.as_str()));
+                if let Some(parent) = debug_dump_path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                let _ = std::fs::write(&debug_dump_path, &spec.shader_wgsl);
+            }
+
+            let shader_desc = wgpu::ShaderModuleDescriptor {
+                label: Some("node-forge-compute"),
+                source: wgpu::ShaderSource::Wgsl(Cow::Owned(spec.shader_wgsl.clone())),
+            };
+            shader_space.compute_pass(spec.name.clone(), move |builder| {
+                let mut pass_builder = builder
+                    .shader(shader_desc)
+                    .bind_uniform_buffer(0, 0, spec.params_buffer.clone(), ShaderStages::COMPUTE)
+                    .bind_storage_texture(0, 1, spec.output_texture.clone(), ShaderStages::COMPUTE);
+
+                if let Some(graph_binding) = spec.graph_binding.clone() {
+                    pass_builder = match graph_binding.kind {
+                        GraphBindingKind::Uniform => pass_builder.bind_uniform_buffer(
+                            0,
+                            2,
+                            graph_binding.buffer_name.clone(),
+                            ShaderStages::COMPUTE,
+                        ),
+                        GraphBindingKind::StorageRead => pass_builder.bind_storage_buffer(
+                            0,
+                            2,
+                            graph_binding.buffer_name.clone(),
+                            ShaderStages::COMPUTE,
+                            true,
+                        ),
+                    };
+                }
+
+                debug_assert_eq!(texture_names.len(), sampler_names.len());
+                for (index, texture_name) in texture_names.iter().enumerate() {
+                    let tex_binding = (index as u32) * 2;
+                    pass_builder = pass_builder
+                        .bind_texture(1, tex_binding, texture_name.clone(), ShaderStages::COMPUTE)
+                        .bind_sampler(
+                            1,
+                            tex_binding + 1,
+                            sampler_names
+                                .get(index)
+                                .cloned()
+                                .unwrap_or_else(|| fallback_sampler.clone()),
+                            ShaderStages::COMPUTE,
+                        );
+                }
+
+                pass_builder.workgroups(spec.workgroups)
+            });
+        }
+
         for spec in &resources.image_prepasses {
             let shader_desc = wgpu::ShaderModuleDescriptor {
                 label: Some("node-forge-imgpm"),
@@ -527,6 +675,7 @@ impl ShaderSpaceFinalizer {
             ordered.append(&mut composite_passes);
             composite_passes = ordered;
         }
+        let pass_order = composite_passes.clone();
         shader_space
             .composite(move |composer| compose_in_strict_order(composer, &composite_passes));
         shader_space.prepare();
@@ -547,6 +696,12 @@ impl ShaderSpaceFinalizer {
                 )?;
             }
         }
+        for spec in &resources.compute_pass_specs {
+            shader_space.write_buffer(spec.params_buffer.as_str(), 0, as_bytes(&spec.params))?;
+            if let (Some(graph_binding), Some(values)) = (&spec.graph_binding, &spec.graph_values) {
+                shader_space.write_buffer(graph_binding.buffer_name.as_str(), 0, values)?;
+            }
+        }
         for spec in &resources.image_prepasses {
             shader_space.write_buffer(spec.params_buffer.as_str(), 0, as_bytes(&spec.params))?;
         }
@@ -558,6 +713,7 @@ impl ShaderSpaceFinalizer {
             shader_space,
             pass_bindings,
             pipeline_signature,
+            pass_order,
         })
     }
 }
@@ -0,0 +1,178 @@
+//! GPU time per pass for `render_stats`. `ShaderSpace` encodes each pass
+//! internally, so timestamps cannot go inside its render passes; instead
+//! every pass is submitted on its own and an empty compute pass writes a
+//! timestamp between submissions. The gap between two timestamps is the GPU
+//! time of the pass submitted in between.
+
+use std::collections::HashSet;
+
+use rust_wgpu_fiber::{ResourceName, eframe::wgpu, shader_space::ShaderSpace};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassGpuTiming {
+    pub pass_name: String,
+    pub gpu_ms: f64,
+}
+
+/// GPU timings of one frame rendered by [`render_with_gpu_timestamps`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GpuFrameTiming {
+    /// Passes in execution order.
+    pub passes: Vec<PassGpuTiming>,
+    /// Queue submissions the frame took, including the timestamp writes and
+    /// the query readback.
+    pub submit_count: u32,
+}
+
+impl GpuFrameTiming {
+    pub fn gpu_ms(&self) -> f64 {
+        self.passes.iter().map(|pass| pass.gpu_ms).sum()
+    }
+
+    pub fn pass_gpu_ms(&self, pass_name: &str) -> Option<f64> {
+        self.passes
+            .iter()
+            .find(|pass| pass.pass_name == pass_name)
+            .map(|pass| pass.gpu_ms)
+    }
+}
+
+/// Composited passes in the order `ShaderSpace::render` runs them.
+fn execution_order(shader_space: &ShaderSpace) -> Vec<ResourceName> {
+    let mut order = shader_space.composition.flatten();
+    order.reverse();
+    let mut seen = HashSet::new();
+    order
+        .into_iter()
+        .map(|dependency| dependency.pass_name)
+        .filter(|name| seen.insert(name.as_str().to_string()))
+        .collect()
+}
+
+/// Renders one frame pass by pass with a timestamp query between
+/// submissions and blocks until the timings are read back. Returns `None`
+/// when the device lacks `TIMESTAMP_QUERY` or the readback fails; the frame
+/// has still been rendered if passes exist.
+pub fn render_with_gpu_timestamps(shader_space: &ShaderSpace) -> Option<GpuFrameTiming> {
+    let device = &shader_space.device;
+    let queue = &shader_space.queue;
+    let pass_order = execution_order(shader_space);
+    let query_count = pass_order.len() as u32 + 1;
+    if pass_order.is_empty()
+        || !device.features().contains(wgpu::Features::TIMESTAMP_QUERY)
+        || query_count > wgpu::QUERY_SET_MAX_QUERIES
+    {
+        return None;
+    }
+
+    let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+        label: Some("sys.render_stats.timestamps"),
+        ty: wgpu::QueryType::Timestamp,
+        count: query_count,
+    });
+    let mut submit_count = 0u32;
+    let write_timestamp = |index: u32, submit_count: &mut u32| {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("sys.render_stats.timestamp.encoder"),
+        });
+        drop(encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("sys.render_stats.timestamp"),
+            timestamp_writes: Some(wgpu::ComputePassTimestampWrites {
+                query_set: &query_set,
+                beginning_of_pass_write_index: Some(index),
+                end_of_pass_write_index: None,
+            }),
+        }));
+        queue.submit(std::iter::once(encoder.finish()));
+        *submit_count += 1;
+    };
+
+    write_timestamp(0, &mut submit_count);
+    for (index, pass_name) in pass_order.iter().enumerate() {
+        shader_space.render_pass_by_name(pass_name.as_str());
+        submit_count += 1;
+        write_timestamp(index as u32 + 1, &mut submit_count);
+    }
+
+    let size = query_count as u64 * wgpu::QUERY_SIZE as u64;
+    let resolve = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("sys.render_stats.timestamps.resolve"),
+        size,
+        usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("sys.render_stats.timestamps.readback"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("sys.render_stats.timestamps.resolve.encoder"),
+    });
+    encoder.resolve_query_set(&query_set, 0..query_count, &resolve, 0);
+    encoder.copy_buffer_to_buffer(&resolve, 0, &readback, 0, size);
+    queue.submit(std::iter::once(encoder.finish()));
+    submit_count += 1;
+
+    let slice = readback.slice(..);
+    let (tx, rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = tx.send(result);
+    });
+    let _ = device.poll(wgpu::PollType::wait_indefinitely());
+    rx.recv().ok()?.ok()?;
+    let ticks: Vec<u64> = {
+        let mapped = slice.get_mapped_range();
+        mapped
+            .chunks_exact(wgpu::QUERY_SIZE as usize)
+            .map(|bytes| {
+                u64::from_ne_bytes([
+                    bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+                ])
+            })
+            .collect()
+    };
+    readback.unmap();
+
+    Some(GpuFrameTiming {
+        passes: pass_timings(&pass_order, &ticks, queue.get_timestamp_period()),
+        submit_count,
+    })
+}
+
+/// Pairs each pass with the gap between the timestamps around it.
+/// `period_ns` is nanoseconds per timestamp tick.
+fn pass_timings(pass_order: &[ResourceName], ticks: &[u64], period_ns: f32) -> Vec<PassGpuTiming> {
+    pass_order
+        .iter()
+        .zip(ticks.windows(2))
+        .map(|(pass_name, pair)| PassGpuTiming {
+            pass_name: pass_name.as_str().to_string(),
+            gpu_ms: pair[1].saturating_sub(pair[0]) as f64 * period_ns as f64 / 1_000_000.0,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pass_timings_convert_timestamp_gaps_to_milliseconds() {
+        let order = [ResourceName::from("a"), ResourceName::from("b")];
+        let timings = pass_timings(&order, &[1_000, 3_000_000, 2_000_000], 2.0);
+        assert_eq!(timings[0].pass_name, "a");
+        assert!((timings[0].gpu_ms - 5.998).abs() < 1e-9);
+        // Timestamps that go backwards clamp to zero instead of underflowing.
+        assert_eq!(timings[1].gpu_ms, 0.0);
+
+        let frame = GpuFrameTiming {
+            passes: timings,
+            submit_count: 6,
+        };
+        assert!((frame.gpu_ms() - 5.998).abs() < 1e-9);
+        assert_eq!(frame.pass_gpu_ms("b"), Some(0.0));
+        assert_eq!(frame.pass_gpu_ms("missing"), None);
+    }
+}
@@ -1,70 +1,137 @@
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Result, anyhow, bail};
 use rust_wgpu_fiber::HeadlessRenderer;
 use rust_wgpu_fiber::HeadlessRendererConfig;
-use rust_wgpu_fiber::eframe::wgpu::TextureFormat;
+use rust_wgpu_fiber::eframe::wgpu::{self, TextureFormat};
 
 use crate::asset_store::AssetStore;
-use crate::dsl::SceneDSL;
+use crate::color::linear_to_srgb;
+use crate::dsl::{FileBitDepth, SceneDSL};
 use crate::profile::{self, ProfileAccumulator, ProfileRunConfig, ProfileWriter};
+use crate::renderer::types::FrameTiming;
 use crate::ui::resource_tree::ResourceSnapshot;
 
-use super::api::{ShaderSpaceBuildOptions, ShaderSpaceBuilder, ShaderSpacePresentationMode};
+use super::api::{
+    ShaderSpaceBuildOptions, ShaderSpaceBuildResult, ShaderSpaceBuilder,
+    ShaderSpacePresentationMode,
+};
+use super::progress::{RenderProgress, render_with_progress};
+use super::sampler::update_pass_params;
+use super::video::{VideoEncodeOptions, VideoEncoder, is_video_output_path};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum HeadlessOutputKind {
     Png,
+    /// 8-bit TIFF from the display-encoded export texture.
+    Tiff,
+    /// 16-bit sRGB-encoded PNG or TIFF (by extension) from the Rgba16Float scene output.
+    Rgba16,
+    /// 8-bit JPEG from the export texture, flattened over black.
+    Jpeg,
+    /// 8-bit lossless WebP from the export texture; `File.quality` is ignored.
+    WebP,
     Exr,
 }
 
-fn route_headless_output(format: TextureFormat, output_path: &Path) -> Result<HeadlessOutputKind> {
+/// Warning for a lossy `File.quality` on WebP output, which the bundled
+/// lossless-only encoder cannot honour.
+fn webp_quality_warning(quality: Option<u8>) -> Option<String> {
+    quality.filter(|&quality| quality < 100).map(|quality| {
+        format!("File.quality {quality} is ignored for .webp: WebP output is always lossless")
+    })
+}
+
+fn route_headless_output(
+    format: TextureFormat,
+    output_path: &Path,
+    bit_depth: FileBitDepth,
+) -> Result<HeadlessOutputKind> {
+    if is_video_output_path(output_path) {
+        bail!(
+            "video output {} requires a frame range (--frames)",
+            output_path.display()
+        );
+    }
+    let ext = output_path
+        .extension()
+        .and_then(|v| v.to_str())
+        .map(|v| v.to_ascii_lowercase());
+    let is_tiff = matches!(ext.as_deref(), Some("tif" | "tiff"));
+    let eight_bit_only = match ext.as_deref() {
+        Some("jpg" | "jpeg") => Some(HeadlessOutputKind::Jpeg),
+        Some("webp") => Some(HeadlessOutputKind::WebP),
+        _ => None,
+    };
+    if let Some(kind) = eight_bit_only
+        && matches!(
+            format,
+            TextureFormat::Rgba16Float | TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+        )
+    {
+        if bit_depth == FileBitDepth::Sixteen {
+            bail!(
+                "bitDepth 16 is not available for {}; use .png or .tiff",
+                output_path.display()
+            );
+        }
+        return Ok(kind);
+    }
     match format {
-        TextureFormat::Rgba16Float => {
-            let ext = output_path
-                .extension()
-                .and_then(|v| v.to_str())
-                .map(|v| v.to_ascii_lowercase());
-            if ext.as_deref() != Some("exr") {
-                bail!(
-                    "scene output format {:?}: .exr required for HDR output; got {}",
-                    format,
-                    output_path.display()
-                );
+        TextureFormat::Rgba16Float => match ext.as_deref() {
+            Some("exr") => Ok(HeadlessOutputKind::Exr),
+            Some("png") if bit_depth == FileBitDepth::Eight => Ok(HeadlessOutputKind::Png),
+            Some("tif" | "tiff") if bit_depth == FileBitDepth::Eight => {
+                Ok(HeadlessOutputKind::Tiff)
             }
-            Ok(HeadlessOutputKind::Exr)
+            Some("png" | "tif" | "tiff") => Ok(HeadlessOutputKind::Rgba16),
+            _ => bail!(
+                "scene output format {:?}: .exr, .png, .tiff, .jpg or .webp required for HDR output; got {}",
+                format,
+                output_path.display()
+            ),
+        },
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+            if bit_depth == FileBitDepth::Sixteen {
+                bail!("bitDepth 16 requires an Rgba16Float scene output; got {format:?}");
+            }
+            Ok(if is_tiff {
+                HeadlessOutputKind::Tiff
+            } else {
+                HeadlessOutputKind::Png
+            })
         }
-        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => Ok(HeadlessOutputKind::Png),
         other => bail!(
-            "headless file export unsupported for scene output format {other:?}; supported: Rgba8Unorm/Rgba8UnormSrgb (png), Rgba16Float (exr)"
+            "headless file export unsupported for scene output format {other:?}; supported: Rgba8Unorm/Rgba8UnormSrgb (png, tiff, jpg, webp), Rgba16Float (exr, 16-bit png/tiff, jpg, webp)"
         ),
     }
 }
 
-pub fn render_scene_to_file_headless(
+/// Encode linear premultiplied RGBA floats as 16-bit samples, matching the 8-bit
+/// display encode: sRGB transfer on clamped color, alpha kept linear.
+fn encode_rgba16_samples(channels: &[f32]) -> Vec<u16> {
+    let quantize = |v: f32| (v.clamp(0.0, 1.0) * 65535.0).round() as u16;
+    channels
+        .chunks_exact(4)
+        .flat_map(|px| {
+            [
+                quantize(linear_to_srgb(px[0].clamp(0.0, 1.0))),
+                quantize(linear_to_srgb(px[1].clamp(0.0, 1.0))),
+                quantize(linear_to_srgb(px[2].clamp(0.0, 1.0))),
+                quantize(px[3]),
+            ]
+        })
+        .collect()
+}
+
+/// Write the rendered scene to `output_path` in the format routed from the
+/// scene output format, file extension and the File target's `bitDepth`.
+fn save_headless_output(
     scene: &SceneDSL,
-    output_path: impl AsRef<Path>,
-    asset_store: Option<&AssetStore>,
+    result: &ShaderSpaceBuildResult,
+    output_path: &Path,
 ) -> Result<()> {
-    let output_path = output_path.as_ref();
-    let renderer = HeadlessRenderer::new(HeadlessRendererConfig::default())
-        .map_err(|e| anyhow!("failed to create headless renderer: {e}"))?;
-
-    // Use UiSdrDisplayEncode so the assembler creates a display-encode pass
-    // that bakes linear→sRGB into a presentation texture.  PNG export reads
-    // that texture for correct gamma.  EXR stays on the raw scene output.
-    let mut builder = ShaderSpaceBuilder::new(renderer.device.clone(), renderer.queue.clone())
-        .with_adapter(renderer.adapter.clone())
-        .with_options(ShaderSpaceBuildOptions {
-            presentation_mode: ShaderSpacePresentationMode::UiSdrDisplayEncode,
-            ..Default::default()
-        });
-    if let Some(store) = asset_store {
-        builder = builder.with_asset_store(store.clone());
-    }
-    let result = builder.build(scene)?;
-
-    result.shader_space.render();
     let output_info = result
         .shader_space
         .texture_info(result.scene_output_texture.as_str())
@@ -74,7 +141,20 @@ pub fn render_scene_to_file_headless(
                 result.scene_output_texture
             )
         })?;
-    match route_headless_output(output_info.format, output_path)? {
+    let file_target = crate::dsl::file_render_target(scene)?;
+    let bit_depth = file_target
+        .as_ref()
+        .map(|target| target.bit_depth)
+        .unwrap_or_default();
+    let read_export_rgba8 = || {
+        let image = result
+            .shader_space
+            .read_texture_rgba8(result.export_output_texture.as_str())
+            .map_err(|e| anyhow!("failed to read export texture: {e}"))?;
+        image::RgbaImage::from_raw(image.width, image.height, image.bytes)
+            .ok_or_else(|| anyhow!("export texture readback has an unexpected size"))
+    };
+    match route_headless_output(output_info.format, output_path, bit_depth)? {
         HeadlessOutputKind::Png => {
             // Read from the display-encode export texture (sRGB-encoded bytes)
             // so the PNG contains correct gamma.
@@ -84,6 +164,59 @@ pub fn render_scene_to_file_headless(
                 .save_texture_png(tex_name, output_path)
                 .map_err(|e| anyhow!("failed to save png: {e}"))?
         }
+        HeadlessOutputKind::Tiff => read_export_rgba8()?
+            .save_with_format(output_path, image::ImageFormat::Tiff)
+            .map_err(|e| anyhow!("failed to save tiff: {e}"))?,
+        HeadlessOutputKind::Jpeg => {
+            // Premultiplied color without alpha is the image composited over black.
+            let rgb = image::DynamicImage::ImageRgba8(read_export_rgba8()?).to_rgb8();
+            let quality = file_target
+                .as_ref()
+                .and_then(|target| target.quality)
+                .unwrap_or(crate::dsl::DEFAULT_FILE_QUALITY);
+            let file = std::io::BufWriter::new(
+                std::fs::File::create(output_path)
+                    .map_err(|e| anyhow!("failed to create {}: {e}", output_path.display()))?,
+            );
+            image::codecs::jpeg::JpegEncoder::new_with_quality(file, quality)
+                .encode_image(&rgb)
+                .map_err(|e| anyhow!("failed to save jpeg: {e}"))?
+        }
+        HeadlessOutputKind::WebP => {
+            if let Some(warning) =
+                webp_quality_warning(file_target.as_ref().and_then(|target| target.quality))
+            {
+                eprintln!("[headless] {warning}");
+            }
+            let image = read_export_rgba8()?;
+            let file = std::io::BufWriter::new(
+                std::fs::File::create(output_path)
+                    .map_err(|e| anyhow!("failed to create {}: {e}", output_path.display()))?,
+            );
+            image::codecs::webp::WebPEncoder::new_lossless(file)
+                .encode(
+                    image.as_raw(),
+                    image.width(),
+                    image.height(),
+                    image::ExtendedColorType::Rgba8,
+                )
+                .map_err(|e| anyhow!("failed to save webp: {e}"))?
+        }
+        HeadlessOutputKind::Rgba16 => {
+            let image = result
+                .shader_space
+                .read_texture_rgba16f(result.scene_output_texture.as_str())
+                .map_err(|e| anyhow!("failed to read scene output texture: {e}"))?;
+            let buffer = image::ImageBuffer::<image::Rgba<u16>, Vec<u16>>::from_raw(
+                image.width,
+                image.height,
+                encode_rgba16_samples(&image.channels),
+            )
+            .ok_or_else(|| anyhow!("scene output readback has an unexpected size"))?;
+            buffer
+                .save(output_path)
+                .map_err(|e| anyhow!("failed to save 16-bit image: {e}"))?
+        }
         HeadlessOutputKind::Exr => result
             .shader_space
             .save_texture_exr(result.scene_output_texture.as_str(), output_path)
@@ -92,27 +225,295 @@ pub fn render_scene_to_file_headless(
     Ok(())
 }
 
-pub fn render_scene_to_file_headless_profiled(
+fn build_headless_scene(
+    renderer: &HeadlessRenderer,
     scene: &SceneDSL,
-    output_path: impl AsRef<Path>,
     asset_store: Option<&AssetStore>,
-    profile_config: &ProfileRunConfig,
-    writer: &mut ProfileWriter,
-) -> Result<()> {
-    let output_path = output_path.as_ref();
-    let renderer = HeadlessRenderer::new(HeadlessRendererConfig::default())
-        .map_err(|e| anyhow!("failed to create headless renderer: {e}"))?;
-
+    deterministic: bool,
+    on_progress: Option<&mut dyn FnMut(RenderProgress)>,
+) -> Result<ShaderSpaceBuildResult> {
+    // Use UiSdrDisplayEncode so the assembler creates a display-encode pass
+    // that bakes linear→sRGB into a presentation texture.  PNG export reads
+    // that texture for correct gamma.  EXR and 16-bit PNG/TIFF read the raw
+    // scene output.
     let mut builder = ShaderSpaceBuilder::new(renderer.device.clone(), renderer.queue.clone())
         .with_adapter(renderer.adapter.clone())
         .with_options(ShaderSpaceBuildOptions {
             presentation_mode: ShaderSpacePresentationMode::UiSdrDisplayEncode,
+            deterministic,
             ..Default::default()
         });
     if let Some(store) = asset_store {
         builder = builder.with_asset_store(store.clone());
     }
-    let result = builder.build(scene)?;
+    match on_progress {
+        Some(on_progress) => builder.build_with_progress(scene, on_progress),
+        None => builder.build(scene),
+    }
+}
+
+/// Device config for deterministic renders: the default wgpu limits and no
+/// optional features, matching what deterministic builds plan against.
+pub fn deterministic_renderer_config() -> HeadlessRendererConfig {
+    HeadlessRendererConfig {
+        required_features: wgpu::Features::empty(),
+        required_limits: wgpu::Limits::default(),
+        ..HeadlessRendererConfig::default()
+    }
+}
+
+/// A headless device shared by several renders, so batch runs pay adapter and
+/// device initialization once.
+pub struct HeadlessSession {
+    renderer: HeadlessRenderer,
+    deterministic: bool,
+}
+
+impl HeadlessSession {
+    pub fn new() -> Result<Self> {
+        Self::with_deterministic(false)
+    }
+
+    /// When `deterministic` is set, render with
+    /// [`ShaderSpaceBuildOptions::deterministic`] on a device created from
+    /// [`deterministic_renderer_config`], with the frame clock pinned to time
+    /// 0, frame 0, so identical scenes produce byte-identical output across
+    /// runs on the same adapter.
+    pub fn with_deterministic(deterministic: bool) -> Result<Self> {
+        let config = if deterministic {
+            deterministic_renderer_config()
+        } else {
+            HeadlessRendererConfig::default()
+        };
+        let renderer = HeadlessRenderer::new(config)
+            .map_err(|e| anyhow!("failed to create headless renderer: {e}"))?;
+        Ok(Self {
+            renderer,
+            deterministic,
+        })
+    }
+
+    /// Build `scene` on this session's device, render one frame and save it.
+    pub fn render_scene_to_file(
+        &self,
+        scene: &SceneDSL,
+        output_path: impl AsRef<Path>,
+        asset_store: Option<&AssetStore>,
+    ) -> Result<()> {
+        let result = self.render_frame(scene, asset_store, None)?;
+        save_headless_output(scene, &result, output_path.as_ref())
+    }
+
+    /// As [`Self::render_scene_to_file`], reporting build steps and each pass
+    /// as it finishes. Passes are submitted and waited on one at a time.
+    pub fn render_scene_to_file_with_progress(
+        &self,
+        scene: &SceneDSL,
+        output_path: impl AsRef<Path>,
+        asset_store: Option<&AssetStore>,
+        on_progress: &mut dyn FnMut(RenderProgress),
+    ) -> Result<()> {
+        let result = self.render_frame(scene, asset_store, Some(on_progress))?;
+        save_headless_output(scene, &result, output_path.as_ref())
+    }
+
+    /// Build `scene`, render one frame and return the display-encoded
+    /// (sRGB) export texture without touching the filesystem.
+    pub fn render_scene_to_rgba8(
+        &self,
+        scene: &SceneDSL,
+        asset_store: Option<&AssetStore>,
+    ) -> Result<image::RgbaImage> {
+        let result = self.render_frame(scene, asset_store, None)?;
+        read_export_image(&result)
+    }
+
+    /// As [`Self::render_scene_to_rgba8`], reporting build steps and each pass
+    /// as it finishes.
+    pub fn render_scene_to_rgba8_with_progress(
+        &self,
+        scene: &SceneDSL,
+        asset_store: Option<&AssetStore>,
+        on_progress: &mut dyn FnMut(RenderProgress),
+    ) -> Result<image::RgbaImage> {
+        let result = self.render_frame(scene, asset_store, Some(on_progress))?;
+        read_export_image(&result)
+    }
+
+    fn render_frame(
+        &self,
+        scene: &SceneDSL,
+        asset_store: Option<&AssetStore>,
+        mut on_progress: Option<&mut dyn FnMut(RenderProgress)>,
+    ) -> Result<ShaderSpaceBuildResult> {
+        let result = build_headless_scene(
+            &self.renderer,
+            scene,
+            asset_store,
+            self.deterministic,
+            on_progress
+                .as_mut()
+                .map(|on_progress| &mut **on_progress as &mut dyn FnMut(RenderProgress)),
+        )?;
+        if self.deterministic {
+            write_pinned_frame_clock(&result)?;
+        }
+        match on_progress {
+            Some(on_progress) => {
+                render_with_progress(&result.shader_space, &result.pass_order, on_progress)
+            }
+            None => result.shader_space.render(),
+        }
+        Ok(result)
+    }
+}
+
+fn read_export_image(result: &ShaderSpaceBuildResult) -> Result<image::RgbaImage> {
+    let image = result
+        .shader_space
+        .read_texture_rgba8(result.export_output_texture.as_str())
+        .map_err(|e| anyhow!("failed to read export texture: {e}"))?;
+    image::RgbaImage::from_raw(image.width, image.height, image.bytes)
+        .ok_or_else(|| anyhow!("export texture readback has an unexpected size"))
+}
+
+pub fn render_scene_to_file_headless(
+    scene: &SceneDSL,
+    output_path: impl AsRef<Path>,
+    asset_store: Option<&AssetStore>,
+) -> Result<()> {
+    HeadlessSession::new()?.render_scene_to_file(scene, output_path, asset_store)
+}
+
+/// Render `frames` as an image sequence, advancing scene time by `1 / fps` per frame.
+///
+/// The scene is built once; only the frame clock changes between frames.
+/// `output_path` maps a frame index to the file it is written to.
+pub fn render_scene_sequence_headless(
+    scene: &SceneDSL,
+    asset_store: Option<&AssetStore>,
+    frames: Range<u32>,
+    fps: f32,
+    mut output_path: impl FnMut(u32) -> Result<PathBuf>,
+) -> Result<Vec<PathBuf>> {
+    validate_frame_range(&frames, fps)?;
+    let renderer = HeadlessRenderer::new(HeadlessRendererConfig::default())
+        .map_err(|e| anyhow!("failed to create headless renderer: {e}"))?;
+    let result = build_headless_scene(&renderer, scene, asset_store, false, None)?;
+
+    let mut written = Vec::with_capacity(frames.len());
+    for frame in frames {
+        write_frame_clock(&result, frame, Some(fps))?;
+        result.shader_space.render();
+        let path = output_path(frame)?;
+        save_headless_output(scene, &result, &path)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Render `frames` into a single `.mp4` or `.webm` file at `fps`.
+///
+/// Frames come from the display-encoded export texture, as for PNG output, and
+/// are streamed to an ffmpeg subprocess as they render.
+pub fn render_scene_video_headless(
+    scene: &SceneDSL,
+    asset_store: Option<&AssetStore>,
+    frames: Range<u32>,
+    fps: f32,
+    output_path: impl AsRef<Path>,
+    options: &VideoEncodeOptions,
+) -> Result<()> {
+    validate_frame_range(&frames, fps)?;
+    let renderer = HeadlessRenderer::new(HeadlessRendererConfig::default())
+        .map_err(|e| anyhow!("failed to create headless renderer: {e}"))?;
+    let result = build_headless_scene(&renderer, scene, asset_store, false, None)?;
+    let export_info = result
+        .shader_space
+        .texture_info(result.export_output_texture.as_str())
+        .ok_or_else(|| {
+            anyhow!(
+                "missing export texture info: {}",
+                result.export_output_texture
+            )
+        })?;
+
+    let mut encoder = VideoEncoder::spawn(
+        output_path.as_ref(),
+        [export_info.size.width, export_info.size.height],
+        fps,
+        options,
+    )?;
+    for frame in frames {
+        write_frame_clock(&result, frame, Some(fps))?;
+        result.shader_space.render();
+        let image = result
+            .shader_space
+            .read_texture_rgba8(result.export_output_texture.as_str())
+            .map_err(|e| anyhow!("failed to read export texture: {e}"))?;
+        encoder.write_frame(&image.bytes)?;
+    }
+    encoder.finish()
+}
+
+fn validate_frame_range(frames: &Range<u32>, fps: f32) -> Result<()> {
+    if frames.is_empty() {
+        bail!("frame range {}..{} is empty", frames.start, frames.end);
+    }
+    if !(fps.is_finite() && fps > 0.0) {
+        bail!("fps must be positive, got {fps}");
+    }
+    Ok(())
+}
+
+/// Write the headless frame clock into every pass's params.
+///
+/// Headless runs have no wall clock. Without `fps`, scene time stays at the pass's
+/// base value and only the frame index advances; with `fps`, time advances by
+/// `frame / fps` from that base.
+fn write_frame_clock(result: &ShaderSpaceBuildResult, frame: u32, fps: Option<f32>) -> Result<()> {
+    let (time_offset, delta_secs) = match fps {
+        Some(fps) => (frame as f32 / fps, 1.0 / fps),
+        None => (0.0, 0.0),
+    };
+    for pass in &result.pass_bindings {
+        let params = pass.base_params.with_frame_timing(FrameTiming {
+            time_secs: pass.base_params.time + time_offset,
+            delta_secs,
+            frame,
+        });
+        update_pass_params(&result.shader_space, pass, &params)
+            .map_err(|e| anyhow!("failed to update params for {}: {e}", pass.pass_id))?;
+    }
+    Ok(())
+}
+
+/// Write time 0, frame 0 into every pass's params regardless of the planned
+/// base values, for deterministic renders.
+fn write_pinned_frame_clock(result: &ShaderSpaceBuildResult) -> Result<()> {
+    for pass in &result.pass_bindings {
+        let params = pass.base_params.with_frame_timing(FrameTiming {
+            time_secs: 0.0,
+            delta_secs: 0.0,
+            frame: 0,
+        });
+        update_pass_params(&result.shader_space, pass, &params)
+            .map_err(|e| anyhow!("failed to update params for {}: {e}", pass.pass_id))?;
+    }
+    Ok(())
+}
+
+pub fn render_scene_to_file_headless_profiled(
+    scene: &SceneDSL,
+    output_path: impl AsRef<Path>,
+    asset_store: Option<&AssetStore>,
+    profile_config: &ProfileRunConfig,
+    writer: &mut ProfileWriter,
+) -> Result<()> {
+    let output_path = output_path.as_ref();
+    let renderer = HeadlessRenderer::new(HeadlessRendererConfig::default())
+        .map_err(|e| anyhow!("failed to create headless renderer: {e}"))?;
+    let result = build_headless_scene(&renderer, scene, asset_store, false, None)?;
     let snapshot = ResourceSnapshot::capture(
         &result.shader_space,
         &result.pass_bindings,
@@ -138,8 +539,13 @@ pub fn render_scene_to_file_headless_profiled(
         result.present_output_texture.as_str(),
         result.export_output_texture.as_str(),
         &snapshot,
+        profile::texture_memory_bytes(&result.shader_space, &snapshot),
     ))?;
+    // Drive the Time node's `frame` output from the headless frame counter.
+    let mut frame_counter: u32 = 0;
     for _ in 0..profile_config.warmup_frames {
+        write_frame_clock(&result, frame_counter, None)?;
+        frame_counter += 1;
         let _ = result.shader_space.render_profiled(true);
     }
 
@@ -147,6 +553,8 @@ pub fn render_scene_to_file_headless_profiled(
     let mut accumulator = ProfileAccumulator::default();
     let measured_frames = profile_config.frames.max(1);
     for frame_index in 0..measured_frames {
+        write_frame_clock(&result, frame_counter, None)?;
+        frame_counter += 1;
         let frame_profile = result.shader_space.render_profiled(true);
         accumulator.observe_frame(&frame_profile);
         writer.emit(&profile::frame_sample_event(
@@ -164,28 +572,7 @@ pub fn render_scene_to_file_headless_profiled(
         }
     }
 
-    let output_info = result
-        .shader_space
-        .texture_info(result.scene_output_texture.as_str())
-        .ok_or_else(|| {
-            anyhow!(
-                "missing scene output texture info: {}",
-                result.scene_output_texture
-            )
-        })?;
-    match route_headless_output(output_info.format, output_path)? {
-        HeadlessOutputKind::Png => {
-            let tex_name = result.export_output_texture.as_str();
-            result
-                .shader_space
-                .save_texture_png(tex_name, output_path)
-                .map_err(|e| anyhow!("failed to save png: {e}"))?
-        }
-        HeadlessOutputKind::Exr => result
-            .shader_space
-            .save_texture_exr(result.scene_output_texture.as_str(), output_path)
-            .map_err(|e| anyhow!("failed to save exr: {e}"))?,
-    }
+    save_headless_output(scene, &result, output_path)?;
 
     writer.emit(&profile::run_end_event(
         &run_id,
@@ -210,35 +597,149 @@ mod tests {
 
     #[test]
     fn route_headless_output_accepts_hdr_exr() {
-        let out = route_headless_output(TextureFormat::Rgba16Float, Path::new("/tmp/out.exr"))
-            .expect("rgba16float + exr should be accepted");
+        let out = route_headless_output(
+            TextureFormat::Rgba16Float,
+            Path::new("/tmp/out.exr"),
+            FileBitDepth::Auto,
+        )
+        .expect("rgba16float + exr should be accepted");
         assert_eq!(out, HeadlessOutputKind::Exr);
     }
 
     #[test]
-    fn route_headless_output_rejects_hdr_non_exr() {
-        let err = route_headless_output(TextureFormat::Rgba16Float, Path::new("/tmp/out.png"))
-            .expect_err("rgba16float + png should fail");
+    fn route_headless_output_rejects_hdr_unknown_extension() {
+        let err = route_headless_output(
+            TextureFormat::Rgba16Float,
+            Path::new("/tmp/out.bmp"),
+            FileBitDepth::Auto,
+        )
+        .expect_err("rgba16float + bmp should fail");
         let msg = err.to_string();
-        assert!(msg.contains(".exr required"));
+        assert!(msg.contains(".exr, .png, .tiff, .jpg or .webp required"));
+    }
+
+    #[test]
+    fn route_headless_output_rejects_single_frame_video() {
+        let err = route_headless_output(
+            TextureFormat::Rgba8Unorm,
+            Path::new("/tmp/out.mp4"),
+            FileBitDepth::Auto,
+        )
+        .expect_err("single-frame mp4 should fail");
+        assert!(err.to_string().contains("requires a frame range"));
+    }
+
+    #[test]
+    fn route_headless_output_routes_hdr_png_and_tiff_by_bit_depth() {
+        let route = |path: &str, bit_depth| {
+            route_headless_output(TextureFormat::Rgba16Float, Path::new(path), bit_depth).unwrap()
+        };
+        assert_eq!(
+            route("/tmp/out.png", FileBitDepth::Auto),
+            HeadlessOutputKind::Rgba16
+        );
+        assert_eq!(
+            route("/tmp/out.TIFF", FileBitDepth::Sixteen),
+            HeadlessOutputKind::Rgba16
+        );
+        assert_eq!(
+            route("/tmp/out.png", FileBitDepth::Eight),
+            HeadlessOutputKind::Png
+        );
+        assert_eq!(
+            route("/tmp/out.tif", FileBitDepth::Eight),
+            HeadlessOutputKind::Tiff
+        );
     }
 
     #[test]
     fn route_headless_output_routes_rgba8_to_png() {
         assert_eq!(
-            route_headless_output(TextureFormat::Rgba8Unorm, Path::new("/tmp/out.png")).unwrap(),
+            route_headless_output(
+                TextureFormat::Rgba8Unorm,
+                Path::new("/tmp/out.png"),
+                FileBitDepth::Auto
+            )
+            .unwrap(),
             HeadlessOutputKind::Png
         );
         assert_eq!(
-            route_headless_output(TextureFormat::Rgba8UnormSrgb, Path::new("/tmp/out")).unwrap(),
+            route_headless_output(
+                TextureFormat::Rgba8UnormSrgb,
+                Path::new("/tmp/out"),
+                FileBitDepth::Auto
+            )
+            .unwrap(),
             HeadlessOutputKind::Png
         );
+        assert_eq!(
+            route_headless_output(
+                TextureFormat::Rgba8Unorm,
+                Path::new("/tmp/out.tiff"),
+                FileBitDepth::Eight
+            )
+            .unwrap(),
+            HeadlessOutputKind::Tiff
+        );
+        assert!(
+            route_headless_output(
+                TextureFormat::Rgba8Unorm,
+                Path::new("/tmp/out.png"),
+                FileBitDepth::Sixteen
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn route_headless_output_routes_jpeg_and_webp_as_8_bit() {
+        for format in [TextureFormat::Rgba8Unorm, TextureFormat::Rgba16Float] {
+            assert_eq!(
+                route_headless_output(format, Path::new("/tmp/out.JPG"), FileBitDepth::Auto)
+                    .unwrap(),
+                HeadlessOutputKind::Jpeg
+            );
+            assert_eq!(
+                route_headless_output(format, Path::new("/tmp/out.webp"), FileBitDepth::Auto)
+                    .unwrap(),
+                HeadlessOutputKind::WebP
+            );
+        }
+        assert!(
+            route_headless_output(
+                TextureFormat::Rgba16Float,
+                Path::new("/tmp/out.jpeg"),
+                FileBitDepth::Sixteen
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn webp_warns_only_for_a_lossy_quality() {
+        assert!(webp_quality_warning(None).is_none());
+        assert!(webp_quality_warning(Some(100)).is_none());
+        let warning = webp_quality_warning(Some(90)).expect("lossy quality warns");
+        assert!(warning.contains("File.quality 90 is ignored for .webp"));
+    }
+
+    #[test]
+    fn rgba16_samples_are_srgb_encoded_with_linear_alpha() {
+        let samples = encode_rgba16_samples(&[0.0, 1.0, 2.0, 0.5, 0.214, -1.0, 0.0, 1.0]);
+        assert_eq!(&samples[..4], &[0, 65535, 65535, 32768]);
+        // linear 0.214 is sRGB ~0.5; 8-bit output could only land on 127 or 128.
+        assert!((samples[4] as i32 - 32768).abs() < 200);
+        assert_eq!(samples[5], 0);
     }
 
     #[test]
     fn route_headless_output_rejects_unsupported_format() {
-        let err = route_headless_output(TextureFormat::Bgra8Unorm, Path::new("/tmp/out.png"))
-            .expect_err("unsupported output format should fail");
+        let err = route_headless_output(
+            TextureFormat::Bgra8Unorm,
+            Path::new("/tmp/out.png"),
+            FileBitDepth::Auto,
+        )
+        .expect_err("unsupported output format should fail");
         assert!(
             err.to_string()
                 .contains("headless file export unsupported for scene output format")
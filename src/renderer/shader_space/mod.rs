@@ -1,18 +1,35 @@
 mod api;
+mod crop;
+mod debug_overlay;
 mod error_space;
 pub(crate) mod finalizer;
+mod gpu_timing;
 mod headless;
+mod pick;
 pub(crate) mod image_utils;
+pub(crate) mod progress;
 pub(crate) mod sampler;
+mod sanitize;
+pub(crate) mod text_atlas;
 pub(crate) mod texture_caps;
+mod video;
 
 pub use api::{
     ShaderSpaceBuildOptions, ShaderSpaceBuildResult, ShaderSpaceBuilder,
     ShaderSpacePresentationMode,
 };
+pub use crop::OutputCrop;
+pub use debug_overlay::{PassDebugOverlay, pass_debug_color};
+pub(crate) use debug_overlay::wrap_generated_fragment_entry;
+pub use gpu_timing::{GpuFrameTiming, PassGpuTiming, render_with_gpu_timestamps};
 pub use headless::{
+    HeadlessSession, deterministic_renderer_config, render_scene_sequence_headless,
     render_scene_to_file_headless, render_scene_to_file_headless_profiled,
-    render_scene_to_png_headless,
+    render_scene_to_png_headless, render_scene_video_headless,
 };
 pub(crate) use image_utils::image_node_dimensions;
+pub use pick::{PICK_TEXTURE_NAME, PickTarget, pick_id_at};
+pub use progress::{RenderProgress, RenderProgressStage};
 pub use sampler::update_pass_params;
+pub use sanitize::{NonFiniteSanitizeMode, NonFiniteSanitizeOptions, take_non_finite_counts};
+pub use video::{VideoEncodeOptions, is_video_output_path};
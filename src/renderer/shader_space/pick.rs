@@ -0,0 +1,295 @@
+//! GPU picking: an ID pass that records which draw pass produced each output texel.
+//!
+//! Every pass that writes the scene output is duplicated into a pick pass that
+//! draws the same geometry with the same bindings into [`PICK_TEXTURE_NAME`].
+//! The duplicate wraps the generated `fs_main`, discards fragments the material
+//! leaves transparent and writes the pass id instead of a colour, so after a
+//! frame each texel holds the top-most contributing pass. Compose passes carry
+//! the id of the layer they blit, which keeps sampled and post-processed layers
+//! pickable.
+//!
+//! Pipelines always carry a blend state, so the id target is `Rgba8Unorm` with
+//! the id packed into the r/g/b bytes and written with `REPLACE`; 8-bit unorm
+//! round-trips `k / 255` exactly. A cleared texel (id 0) means no pass.
+
+use rust_wgpu_fiber::{
+    ResourceName,
+    eframe::wgpu::{self, BlendState, Color, TextureFormat},
+};
+use serde::Serialize;
+
+use crate::{
+    dsl::{SceneDSL, incoming_connection},
+    renderer::render_plan::{
+        resource_naming::readable_pass_name_for_node,
+        types::{RenderPlan, TextureDecl},
+    },
+};
+
+use super::debug_overlay::wrap_generated_fragment_entry;
+
+/// Offscreen target holding the pass id of every output texel.
+pub const PICK_TEXTURE_NAME: &str = "sys.pick.ids";
+
+const INNER_FRAGMENT_NAME: &str = "fs_main_pick_inner";
+/// Ids are packed into 24 bits of an rgba8 texel.
+const MAX_PICK_ID: u32 = 0x00ff_ffff;
+/// Coverage below one 8-bit step does not count as contributing.
+const PICK_MIN_ALPHA: f32 = 1.0 / 255.0;
+
+/// A pass reported by a pick. Pick id `n` refers to entry `n - 1`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickTarget {
+    pub pass_name: String,
+    /// Scene node that owns the pass (RenderPass, blur, composition, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_type: Option<String>,
+    /// Node wired into the owner's `geometry` input, when it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometry_node_id: Option<String>,
+}
+
+/// Adds a pick pass after every pass that writes the scene output and returns
+/// the pick targets in id order. Passes whose fragment entry does not match the
+/// generated shape are not pickable.
+pub(crate) fn apply_pick_id_passes(plan: &mut RenderPlan) -> Vec<PickTarget> {
+    let resources = &mut plan.resources;
+    let Some(frame_size) = resources
+        .textures
+        .iter()
+        .find(|decl| decl.name == plan.scene_output_texture)
+        .map(|decl| decl.size)
+    else {
+        return Vec::new();
+    };
+
+    let mut targets = Vec::new();
+    let mut pick_specs = Vec::new();
+    for spec in &resources.render_pass_specs {
+        if spec.target_texture != plan.scene_output_texture
+            && spec.resolve_target.as_ref() != Some(&plan.scene_output_texture)
+        {
+            continue;
+        }
+        let id = targets.len() as u32 + 1;
+        if id > MAX_PICK_ID {
+            eprintln!("[pick] more than {MAX_PICK_ID} output passes; the rest are not pickable");
+            break;
+        }
+        let Some(shader_wgsl) =
+            wrap_generated_fragment_entry(&spec.shader_wgsl, INNER_FRAGMENT_NAME, &pick_body(id))
+        else {
+            eprintln!(
+                "[pick] fragment entry not found; {} is not pickable",
+                spec.name.as_str()
+            );
+            continue;
+        };
+
+        let pick_name: ResourceName = format!("sys.pick.{}", spec.name.as_str()).into();
+        let mut pick = spec.clone();
+        pick.name = pick_name.clone();
+        pick.target_texture = PICK_TEXTURE_NAME.into();
+        pick.resolve_target = None;
+        pick.sample_count = 1;
+        pick.params_buffer = format!("params.{}", pick_name.as_str()).into();
+        if let Some(binding) = pick.graph_binding.as_mut() {
+            binding.buffer_name = format!("params.{}.graph", pick_name.as_str()).into();
+        }
+        pick.baked_data_parse_buffer = spec
+            .baked_data_parse_buffer
+            .as_ref()
+            .map(|name| format!("{}.pick", name.as_str()).into());
+        pick.shader_wgsl = shader_wgsl;
+        pick.blend_state = BlendState::REPLACE;
+        pick.color_load_op = wgpu::LoadOp::Load;
+
+        targets.push(pick_target_for_pass(
+            &plan.prepared.scene,
+            spec.name.as_str(),
+        ));
+        pick_specs.push((spec.name.clone(), pick));
+    }
+    if pick_specs.is_empty() {
+        return targets;
+    }
+
+    resources.textures.push(TextureDecl {
+        name: PICK_TEXTURE_NAME.into(),
+        size: frame_size,
+        format: TextureFormat::Rgba8Unorm,
+        sample_count: 1,
+        needs_sampling: false,
+    });
+    for (source_name, pick) in pick_specs {
+        if let Some(cull_mode) = resources.pass_cull_mode_by_name.get(&source_name).copied() {
+            resources
+                .pass_cull_mode_by_name
+                .insert(pick.name.clone(), cull_mode);
+        }
+        // A sanitized source shader still bumps its counter; give the pick copy
+        // its own so id draws don't inflate the reported counts.
+        if resources
+            .pass_non_finite_counter_by_name
+            .contains_key(&source_name)
+        {
+            resources.pass_non_finite_counter_by_name.insert(
+                pick.name.clone(),
+                format!("{}.non_finite", pick.name.as_str()).into(),
+            );
+        }
+        if let (Some(source_buffer), Some(pick_buffer)) = (
+            resources
+                .render_pass_specs
+                .iter()
+                .find(|spec| spec.name == source_name)
+                .and_then(|spec| spec.baked_data_parse_buffer.clone()),
+            pick.baked_data_parse_buffer.clone(),
+        ) && let Some(pass_id) = resources
+            .baked_data_parse_buffer_to_pass_id
+            .get(&source_buffer)
+            .cloned()
+        {
+            resources
+                .baked_data_parse_buffer_to_pass_id
+                .insert(pick_buffer, pass_id);
+        }
+        // Run right after the source so later layers overwrite its ids.
+        if let Some(index) = resources
+            .composite_passes
+            .iter()
+            .position(|name| *name == source_name)
+        {
+            resources
+                .composite_passes
+                .insert(index + 1, pick.name.clone());
+        }
+        resources.render_pass_specs.push(pick);
+    }
+
+    let first_pick = resources
+        .composite_passes
+        .iter()
+        .find(|name| name.as_str().starts_with("sys.pick."))
+        .cloned();
+    if let Some(first_pick) = first_pick
+        && let Some(spec) = resources
+            .render_pass_specs
+            .iter_mut()
+            .find(|spec| spec.name == first_pick)
+    {
+        spec.color_load_op = wgpu::LoadOp::Clear(Color::TRANSPARENT);
+    }
+    targets
+}
+
+fn pick_body(id: u32) -> String {
+    let [r, g, b, a] = pick_id_color(id);
+    format!(
+        "    let color = {INNER_FRAGMENT_NAME}(in);\n    if (color.a < {PICK_MIN_ALPHA:.8}) {{\n        discard;\n    }}\n    return vec4f({r:.8}, {g:.8}, {b:.8}, {a:.1});\n"
+    )
+}
+
+/// Unorm colour that stores `id` in the r/g/b bytes of an rgba8 texel.
+fn pick_id_color(id: u32) -> [f32; 4] {
+    let byte = |shift: u32| ((id >> shift) & 0xff) as f32 / 255.0;
+    [byte(0), byte(8), byte(16), 1.0]
+}
+
+/// Pick id stored at (`x`, `y`) of a tightly packed rgba8 readback of
+/// [`PICK_TEXTURE_NAME`], or `None` when no pass covers the texel.
+pub fn pick_id_at(bytes: &[u8], width: u32, height: u32, x: u32, y: u32) -> Option<u32> {
+    if x >= width || y >= height {
+        return None;
+    }
+    let offset = (y as usize * width as usize + x as usize) * 4;
+    let texel = bytes.get(offset..offset + 4)?;
+    if texel[3] != 255 {
+        return None;
+    }
+    let id = u32::from(texel[0]) | (u32::from(texel[1]) << 8) | (u32::from(texel[2]) << 16);
+    (id != 0).then_some(id)
+}
+
+fn pick_target_for_pass(scene: &SceneDSL, pass_name: &str) -> PickTarget {
+    let owner = pass_owner_node(scene, pass_name);
+    PickTarget {
+        pass_name: pass_name.to_string(),
+        node_id: owner.map(|node| node.id.clone()),
+        node_type: owner.map(|node| node.node_type.clone()),
+        geometry_node_id: owner
+            .and_then(|node| incoming_connection(scene, &node.id, "geometry"))
+            .map(|conn| conn.from.node_id.clone()),
+    }
+}
+
+/// Draw passes are named after their node; internal passes follow
+/// `sys.<kind>.<node id>.<suffix>`.
+fn pass_owner_node<'a>(scene: &'a SceneDSL, pass_name: &str) -> Option<&'a crate::dsl::Node> {
+    if let Some(node) = scene
+        .nodes
+        .iter()
+        .find(|node| readable_pass_name_for_node(node).as_str() == pass_name)
+    {
+        return Some(node);
+    }
+    let (_, rest) = pass_name.strip_prefix("sys.")?.split_once('.')?;
+    scene
+        .nodes
+        .iter()
+        .filter(|node| {
+            rest.strip_prefix(node.id.as_str())
+                .is_some_and(|tail| tail.starts_with('.'))
+        })
+        .max_by_key(|node| node.id.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{INNER_FRAGMENT_NAME, pass_owner_node, pick_body, pick_id_at, pick_id_color};
+    use crate::renderer::shader_space::debug_overlay::wrap_generated_fragment_entry;
+
+    #[test]
+    fn pick_wrapper_is_valid_wgsl_and_ids_round_trip() {
+        let source = "struct VSOut { @builtin(position) position: vec4f };\n\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {\n    return vec4f(in.position.x);\n}\n";
+        let wrapped =
+            wrap_generated_fragment_entry(source, INNER_FRAGMENT_NAME, &pick_body(0x01_0203))
+                .expect("wrapped");
+        crate::renderer::validate_wgsl(&wrapped).expect("valid wgsl");
+
+        for id in [1, 255, 256, 0x01_0203, 0x00ff_ffff] {
+            let texel: Vec<u8> = pick_id_color(id)
+                .iter()
+                .map(|c| (c * 255.0).round() as u8)
+                .collect();
+            assert_eq!(pick_id_at(&texel, 1, 1, 0, 0), Some(id));
+        }
+        assert_eq!(pick_id_at(&[0, 0, 0, 0], 1, 1, 0, 0), None);
+        assert_eq!(pick_id_at(&[1, 0, 0, 255], 1, 1, 1, 0), None);
+    }
+
+    #[test]
+    fn pass_names_resolve_to_owner_nodes() {
+        let scene: crate::dsl::SceneDSL = serde_json::from_value(serde_json::json!({
+            "version": "1.0",
+            "metadata": { "name": "pick" },
+            "nodes": [
+                { "id": "blur", "type": "GuassianBlurPass" },
+                { "id": "blur.2", "type": "GuassianBlurPass" },
+                { "id": "draw", "type": "RenderPass" }
+            ],
+            "connections": [],
+            "outputs": null
+        }))
+        .unwrap();
+
+        let owner = |name: &str| pass_owner_node(&scene, name).map(|node| node.id.as_str());
+        assert_eq!(owner("draw.pass"), Some("draw"));
+        assert_eq!(owner("sys.blur.blur.to.comp.compose.pass"), Some("blur"));
+        assert_eq!(owner("sys.blur.blur.2.h.ds1.pass"), Some("blur.2"));
+        assert_eq!(owner("sys.blur.other.pass"), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! Step-by-step progress for shader space builds and renders, so clients can
+//! show how far a heavy scene (long blur chains, large pyramids) has got
+//! instead of a single "rendering…" state.
+
+use std::time::Instant;
+
+use rust_wgpu_fiber::{ResourceName, eframe::wgpu, shader_space::ShaderSpace};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderProgressStage {
+    /// Planning, shader validation and pipeline creation, one step each.
+    Build,
+    /// GPU execution, one step per composited pass.
+    Render,
+}
+
+/// One finished step of a build or render.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderProgress {
+    pub stage: RenderProgressStage,
+    /// 1-based index of the finished step.
+    pub pass_index: usize,
+    pub pass_total: usize,
+    pub pass_name: String,
+    /// Time since the stage started.
+    pub elapsed_ms: f64,
+}
+
+/// Reports finished steps of one stage against a fixed total.
+pub(crate) struct ProgressTracker<'a> {
+    stage: RenderProgressStage,
+    total: usize,
+    finished: usize,
+    started_at: Instant,
+    on_progress: &'a mut dyn FnMut(RenderProgress),
+}
+
+impl<'a> ProgressTracker<'a> {
+    pub(crate) fn new(
+        stage: RenderProgressStage,
+        total: usize,
+        on_progress: &'a mut dyn FnMut(RenderProgress),
+    ) -> Self {
+        Self {
+            stage,
+            total,
+            finished: 0,
+            started_at: Instant::now(),
+            on_progress,
+        }
+    }
+
+    pub(crate) fn step(&mut self, name: &str) {
+        self.finished = (self.finished + 1).min(self.total);
+        (self.on_progress)(RenderProgress {
+            stage: self.stage,
+            pass_index: self.finished,
+            pass_total: self.total,
+            pass_name: name.to_string(),
+            elapsed_ms: self.started_at.elapsed().as_secs_f64() * 1000.0,
+        });
+    }
+}
+
+/// Runs the composited passes one at a time in `pass_order`, reporting each
+/// once the GPU has finished it. Each pass is its own submission followed by
+/// a blocking wait, so this is slower than `ShaderSpace::render` and only
+/// meant for renders someone is watching. Falls back to a single `render`
+/// when the pass order is unknown.
+pub(crate) fn render_with_progress(
+    shader_space: &ShaderSpace,
+    pass_order: &[ResourceName],
+    on_progress: &mut dyn FnMut(RenderProgress),
+) {
+    if pass_order.is_empty() {
+        shader_space.render();
+        return;
+    }
+    let mut tracker =
+        ProgressTracker::new(RenderProgressStage::Render, pass_order.len(), on_progress);
+    for pass_name in pass_order {
+        shader_space.render_pass_by_name(pass_name.as_str());
+        let _ = shader_space
+            .device
+            .poll(wgpu::PollType::wait_indefinitely());
+        tracker.step(pass_name.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_reports_one_based_steps_and_serializes_camel_case() {
+        let mut reports = Vec::new();
+        let mut sink = |progress: RenderProgress| reports.push(progress);
+        let mut tracker = ProgressTracker::new(RenderProgressStage::Build, 2, &mut sink);
+        tracker.step("plan");
+        tracker.step("validate");
+        tracker.step("extra");
+        drop(tracker);
+
+        assert_eq!(
+            reports.iter().map(|p| p.pass_index).collect::<Vec<_>>(),
+            [1, 2, 2]
+        );
+        let value = serde_json::to_value(&reports[0]).unwrap();
+        assert_eq!(value["stage"], "build");
+        assert_eq!(value["passIndex"], 1);
+        assert_eq!(value["passTotal"], 2);
+        assert_eq!(value["passName"], "plan");
+        assert!(value["elapsedMs"].is_number());
+    }
+}
@@ -5,12 +5,15 @@
 
 use std::collections::HashMap;
 
-use rust_wgpu_fiber::shader_space::{ShaderSpace, ShaderSpaceResult};
+use rust_wgpu_fiber::{
+    ResourceName,
+    shader_space::{ShaderSpace, ShaderSpaceResult},
+};
 
 use crate::renderer::types::PassTextureRef;
-use crate::{dsl::SceneDSL, renderer::types::Params, renderer::utils::as_bytes};
+use crate::{dsl::Node, dsl::SceneDSL, renderer::types::Params, renderer::utils::as_bytes};
 
-use crate::renderer::render_plan::pass_spec::SamplerKind;
+use crate::renderer::render_plan::pass_spec::{PassTextureBinding, SamplerKind};
 use crate::renderer::types::PassBindings;
 
 pub(crate) fn sampler_kind_from_node_params(
@@ -71,6 +74,94 @@ pub(crate) fn sampler_kind_from_node_params(
     }
 }
 
+/// Mip filtering requested by an ImageTexture's `mipmapFilter` param.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MipmapFilter {
+    Nearest,
+    Linear,
+}
+
+/// `mipmapFilter`: "linear" | "nearest" | "none" (default; no mip chain).
+pub(crate) fn mipmap_filter_from_node_params(
+    params: &HashMap<String, serde_json::Value>,
+) -> Option<MipmapFilter> {
+    let filter = params
+        .get("mipmapFilter")
+        .and_then(|v| v.as_str())?
+        .trim()
+        .to_ascii_lowercase();
+    match filter.as_str() {
+        "linear" => Some(MipmapFilter::Linear),
+        "nearest" => Some(MipmapFilter::Nearest),
+        _ => None,
+    }
+}
+
+/// Whether `node` samples through a mip atlas rather than its base image.
+pub(crate) fn image_uses_mip_atlas(node: &Node) -> bool {
+    node.node_type == "ImageTexture" && mipmap_filter_from_node_params(&node.params).is_some()
+}
+
+pub(crate) fn image_mip_atlas_name(node_id: &str) -> ResourceName {
+    format!("sys.image.{node_id}.mips").into()
+}
+
+const MIP_ATLAS_BINDING_SUFFIX: &str = "#mips";
+
+/// Material binding id for `node_id`'s mip atlas. The base image keeps its own
+/// binding (the node id) for the `texture` output and other direct reads.
+pub(crate) fn mip_atlas_binding_id(node_id: &str) -> String {
+    format!("{node_id}{MIP_ATLAS_BINDING_SUFFIX}")
+}
+
+/// Address mode code understood by `nf_mip_atlas_address`.
+pub(crate) fn mip_atlas_address_mode(kind: SamplerKind) -> u32 {
+    match kind {
+        SamplerKind::NearestClamp | SamplerKind::LinearClamp => 0,
+        SamplerKind::NearestRepeat | SamplerKind::LinearRepeat => 1,
+        SamplerKind::NearestMirror | SamplerKind::LinearMirror => 2,
+    }
+}
+
+/// Texture + sampler for an entry of a material's `image_textures`.
+///
+/// Mip atlas bindings (see [`mip_atlas_binding_id`]) use a clamp sampler: the
+/// material applies the address mode itself so filtering never crosses into a
+/// neighbouring level.
+pub(crate) fn image_texture_binding(
+    ids: &HashMap<String, ResourceName>,
+    nodes_by_id: &HashMap<String, Node>,
+    binding_id: &str,
+) -> Option<(PassTextureBinding, SamplerKind)> {
+    if let Some(node_id) = binding_id.strip_suffix(MIP_ATLAS_BINDING_SUFFIX) {
+        let node = nodes_by_id
+            .get(node_id)
+            .filter(|node| image_uses_mip_atlas(node))?;
+        let kind = match sampler_kind_from_node_params(&node.params) {
+            SamplerKind::NearestClamp | SamplerKind::NearestMirror | SamplerKind::NearestRepeat => {
+                SamplerKind::NearestClamp
+            }
+            _ => SamplerKind::LinearClamp,
+        };
+        let binding = PassTextureBinding {
+            texture: image_mip_atlas_name(node_id),
+            image_node_id: Some(node_id.to_string()),
+        };
+        return Some((binding, kind));
+    }
+
+    let texture = ids.get(binding_id).cloned()?;
+    let kind = nodes_by_id
+        .get(binding_id)
+        .map(|n| sampler_kind_from_node_params(&n.params))
+        .unwrap_or(SamplerKind::LinearClamp);
+    let binding = PassTextureBinding {
+        texture,
+        image_node_id: Some(binding_id.to_string()),
+    };
+    Some((binding, kind))
+}
+
 pub(crate) fn sampler_kind_for_pass_texture(
     scene: &SceneDSL,
     texture_ref: &PassTextureRef,
@@ -88,6 +179,10 @@ pub(crate) fn build_image_premultiply_wgsl(tex_var: &str, samp_var: &str) -> Str
     crate::renderer::wgsl_templates::build_image_premultiply_wgsl(tex_var, samp_var)
 }
 
+pub(crate) fn build_image_mip_atlas_wgsl(level: u32) -> String {
+    crate::renderer::wgsl_templates::build_image_mip_atlas_wgsl(level)
+}
+
 pub fn update_pass_params(
     shader_space: &ShaderSpace,
     pass: &PassBindings,
@@ -152,4 +247,34 @@ mod tests {
             SamplerKind::LinearClamp
         );
     }
+
+    #[test]
+    fn mip_atlas_bindings_use_a_clamp_sampler_next_to_the_base_image() {
+        let mut image = pass_texture_node("img", "nearest");
+        image.node_type = "ImageTexture".to_string();
+        image.params.insert(
+            "addressModeU".to_string(),
+            serde_json::Value::String("repeat".to_string()),
+        );
+        image.params.insert(
+            "mipmapFilter".to_string(),
+            serde_json::Value::String("linear".to_string()),
+        );
+        let ids = HashMap::from([("img".to_string(), ResourceName::from("img_tex"))]);
+        let nodes_by_id = HashMap::from([("img".to_string(), image.clone())]);
+
+        let (binding, kind) = image_texture_binding(&ids, &nodes_by_id, "img").unwrap();
+        assert_eq!(binding.texture.as_str(), "img_tex");
+        assert_eq!(kind, SamplerKind::NearestRepeat);
+
+        let atlas_id = mip_atlas_binding_id("img");
+        let (binding, kind) = image_texture_binding(&ids, &nodes_by_id, &atlas_id).unwrap();
+        assert_eq!(binding.texture.as_str(), "sys.image.img.mips");
+        assert_eq!(binding.image_node_id.as_deref(), Some("img"));
+        assert_eq!(kind, SamplerKind::NearestClamp);
+
+        image.params.remove("mipmapFilter");
+        let nodes_by_id = HashMap::from([("img".to_string(), image)]);
+        assert!(image_texture_binding(&ids, &nodes_by_id, &atlas_id).is_none());
+    }
 }
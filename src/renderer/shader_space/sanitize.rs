@@ -0,0 +1,267 @@
+//! Safe mode: replace NaN/Inf fragment outputs with a sentinel colour.
+//!
+//! A single non-finite texel poisons every blur/downsample tap that reads it,
+//! so one bad pass can blank an entire chain. The sanitize stage wraps the
+//! generated `fs_main` and checks the exponent bits of the result, which is
+//! robust against drivers that fold `x != x` away.
+//!
+//! Every replaced fragment also bumps an atomic counter in a small per-pass
+//! storage buffer, so the reported counts are exact per pass rather than
+//! inferred from sentinel-coloured texels in the output.
+
+use rust_wgpu_fiber::{ResourceName, eframe::wgpu, shader_space::ShaderSpace};
+
+use crate::renderer::render_plan::types::RenderPlan;
+
+use super::debug_overlay::wrap_generated_fragment_entry;
+
+const INNER_FRAGMENT_NAME: &str = "fs_main_sanitize_inner";
+
+/// Group 0 slot of the per-pass counter; 0..=3 are params, baked data, graph
+/// inputs and shader parameters.
+pub(crate) const NON_FINITE_COUNTER_BINDING: u32 = 4;
+const NON_FINITE_COUNTER_VAR: &str = "sanitize_non_finite_count";
+const NON_FINITE_COUNTER_PREFIX: &str = "sys.sanitize.";
+const NON_FINITE_COUNTER_SUFFIX: &str = ".non_finite";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NonFiniteSanitizeMode {
+    #[default]
+    Off,
+    /// Only passes that write the scene output texture.
+    FinalComposite,
+    /// Every draw pass, so intermediate targets never carry NaN/Inf.
+    EveryPass,
+}
+
+impl NonFiniteSanitizeMode {
+    pub const ALL: [Self; 3] = [Self::Off, Self::FinalComposite, Self::EveryPass];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::FinalComposite => "Final",
+            Self::EveryPass => "All",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NonFiniteSanitizeOptions {
+    pub mode: NonFiniteSanitizeMode,
+    /// Premultiplied RGBA written in place of a non-finite fragment.
+    pub sentinel: [f32; 4],
+}
+
+impl NonFiniteSanitizeOptions {
+    /// Magenta: rare in real content and exactly representable in f16.
+    pub const DEFAULT_SENTINEL: [f32; 4] = [1.0, 0.0, 1.0, 1.0];
+
+    pub const SENTINEL_PRESETS: [(&'static str, [f32; 4]); 4] = [
+        ("Magenta", Self::DEFAULT_SENTINEL),
+        ("Green", [0.0, 1.0, 0.0, 1.0]),
+        ("Cyan", [0.0, 1.0, 1.0, 1.0]),
+        ("Yellow", [1.0, 1.0, 0.0, 1.0]),
+    ];
+}
+
+impl Default for NonFiniteSanitizeOptions {
+    fn default() -> Self {
+        Self {
+            mode: NonFiniteSanitizeMode::Off,
+            sentinel: Self::DEFAULT_SENTINEL,
+        }
+    }
+}
+
+pub(crate) fn apply_non_finite_sanitize(plan: &mut RenderPlan, options: NonFiniteSanitizeOptions) {
+    if options.mode == NonFiniteSanitizeMode::Off {
+        return;
+    }
+
+    for spec in &mut plan.resources.render_pass_specs {
+        if options.mode == NonFiniteSanitizeMode::FinalComposite
+            && spec.target_texture != plan.scene_output_texture
+            && spec.resolve_target.as_ref() != Some(&plan.scene_output_texture)
+        {
+            continue;
+        }
+        match sanitize_fragment(&spec.shader_wgsl, options.sentinel) {
+            Some(shader_wgsl) => {
+                spec.shader_wgsl = shader_wgsl;
+                plan.resources
+                    .pass_non_finite_counter_by_name
+                    .insert(spec.name.clone(), non_finite_counter_name(&spec.name));
+            }
+            None => eprintln!(
+                "[sanitize] fragment entry not found; {} is not sanitized",
+                spec.name.as_str()
+            ),
+        }
+    }
+}
+
+fn non_finite_counter_name(pass_name: &ResourceName) -> ResourceName {
+    format!(
+        "{NON_FINITE_COUNTER_PREFIX}{}{NON_FINITE_COUNTER_SUFFIX}",
+        pass_name.as_str()
+    )
+    .into()
+}
+
+fn sanitize_fragment(source: &str, sentinel: [f32; 4]) -> Option<String> {
+    let wrapped =
+        wrap_generated_fragment_entry(source, INNER_FRAGMENT_NAME, &sanitize_body(sentinel))?;
+    Some(format!(
+        "@group(0) @binding({NON_FINITE_COUNTER_BINDING})\nvar<storage, read_write> {NON_FINITE_COUNTER_VAR}: atomic<u32>;\n\n{wrapped}"
+    ))
+}
+
+fn sanitize_body(sentinel: [f32; 4]) -> String {
+    let [r, g, b, a] = sentinel;
+    format!(
+        "    let color = {INNER_FRAGMENT_NAME}(in);\n    let exponent_mask = vec4u(0x7f800000u);\n    if (any((bitcast<vec4u>(color) & exponent_mask) == exponent_mask)) {{\n        atomicAdd(&{NON_FINITE_COUNTER_VAR}, 1u);\n        return vec4f({r:.4}, {g:.4}, {b:.4}, {a:.4});\n    }}\n    return color;\n"
+    )
+}
+
+/// Reads and resets the safe mode counters, returning the number of non-finite
+/// fragments each sanitized pass replaced since the previous call, sorted by
+/// pass name. Blocks on a small readback; `None` when it fails.
+pub fn take_non_finite_counts(shader_space: &ShaderSpace) -> Option<Vec<(String, u64)>> {
+    let mut counters: Vec<(String, wgpu::Buffer)> = {
+        let buffers = shader_space.buffers.lock().ok()?;
+        buffers
+            .iter()
+            .filter_map(|(name, fish)| {
+                let pass = name
+                    .as_str()
+                    .strip_prefix(NON_FINITE_COUNTER_PREFIX)?
+                    .strip_suffix(NON_FINITE_COUNTER_SUFFIX)?;
+                Some((
+                    pass.to_string(),
+                    wgpu::Buffer::clone(fish.wgpu_buffer.as_ref()?),
+                ))
+            })
+            .collect()
+    };
+    if counters.is_empty() {
+        return Some(Vec::new());
+    }
+    counters.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let word = std::mem::size_of::<u32>() as u64;
+    let size = counters.len() as u64 * word;
+    let device = &shader_space.device;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("sys.sanitize.non_finite.readback"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("sys.sanitize.non_finite.readback.encoder"),
+    });
+    for (index, (_, counter)) in counters.iter().enumerate() {
+        encoder.copy_buffer_to_buffer(counter, 0, &readback, index as u64 * word, word);
+        encoder.clear_buffer(counter, 0, None);
+    }
+    shader_space.queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = readback.slice(..);
+    let (tx, rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = tx.send(result);
+    });
+    let _ = device.poll(wgpu::PollType::wait_indefinitely());
+    rx.recv().ok()?.ok()?;
+    let counts = {
+        let mapped = slice.get_mapped_range();
+        counters
+            .into_iter()
+            .zip(mapped.chunks_exact(word as usize))
+            .map(|((pass, _), bytes)| {
+                let count = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                (pass, count as u64)
+            })
+            .collect()
+    };
+    readback.unmap();
+    Some(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anyhow::Result;
+
+    use super::{
+        NON_FINITE_COUNTER_PREFIX, NonFiniteSanitizeMode, NonFiniteSanitizeOptions,
+        apply_non_finite_sanitize, non_finite_counter_name, sanitize_fragment,
+    };
+    use crate::{
+        asset_store,
+        renderer::{
+            ShaderSpacePresentationMode,
+            render_plan::{
+                planner::RenderPlanner,
+                types::{PlanBuildOptions, PlanningGpuCaps, RenderPlan},
+            },
+        },
+    };
+
+    fn plan_case(case_name: &str) -> Result<RenderPlan> {
+        let archive = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/render/editor-examples")
+            .join(case_name)
+            .join("scene.nforge");
+        let (scene, store) = asset_store::load_from_nforge(&archive)?;
+        let store = (!scene.assets.is_empty()).then_some(&store);
+        RenderPlanner::new(PlanBuildOptions {
+            gpu_caps: PlanningGpuCaps::default(),
+            presentation_mode: ShaderSpacePresentationMode::UiSdrDisplayEncode,
+            debug_dump_wgsl_dir: None,
+            channel_debug: Default::default(),
+            dither_sdr_output: false,
+        })
+        .plan(&scene, store, None)
+    }
+
+    #[test]
+    fn sanitize_wrapper_is_valid_wgsl() {
+        let source = "struct VSOut { @builtin(position) position: vec4f };\n\n@fragment\nfn fs_main(in: VSOut) -> @location(0) vec4f {\n    return vec4f(in.position.x);\n}\n";
+        let wrapped =
+            sanitize_fragment(source, NonFiniteSanitizeOptions::DEFAULT_SENTINEL).expect("wrapped");
+        crate::renderer::validate_wgsl(&wrapped).expect("valid wgsl");
+        assert!(wrapped.contains("@group(0) @binding(4)\nvar<storage, read_write> sanitize_non_finite_count: atomic<u32>;"));
+        assert!(wrapped.contains("atomicAdd(&sanitize_non_finite_count, 1u);"));
+    }
+
+    #[test]
+    fn every_sanitized_pass_gets_its_own_counter() -> Result<()> {
+        let mut plan = plan_case("bloom")?;
+        apply_non_finite_sanitize(
+            &mut plan,
+            NonFiniteSanitizeOptions {
+                mode: NonFiniteSanitizeMode::EveryPass,
+                ..Default::default()
+            },
+        );
+
+        let counters = &plan.resources.pass_non_finite_counter_by_name;
+        assert!(counters.len() > 1, "bloom should sanitize several passes");
+        for spec in &plan.resources.render_pass_specs {
+            let Some(counter) = counters.get(&spec.name) else {
+                assert!(!spec.shader_wgsl.contains("atomicAdd"));
+                continue;
+            };
+            assert_eq!(*counter, non_finite_counter_name(&spec.name));
+            assert!(counter.as_str().starts_with(NON_FINITE_COUNTER_PREFIX));
+            assert!(
+                spec.shader_wgsl
+                    .contains("atomicAdd(&sanitize_non_finite_count, 1u);")
+            );
+        }
+        Ok(())
+    }
+}
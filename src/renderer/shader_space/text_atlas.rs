@@ -0,0 +1,365 @@
+//! Text node rasterization.
+//!
+//! A `Text` node is turned into an rgba8 image at plan time and then bound like
+//! any other image texture. The font comes from `fontAssetId` (asset store),
+//! `fontPath` (relative to the crate root) or the bundled MiSans Regular.
+//!
+//! Two encodings are produced:
+//! - `bitmap`: premultiplied white coverage.
+//! - `sdf`: signed distance to the glyph edge in every channel, `0.5` on the
+//!   edge and falling off linearly over `sdfSpread` pixels. The `Text` node
+//!   compiler turns it back into coverage with screen-space antialiasing, so
+//!   the label stays sharp under magnification.
+
+use std::path::{Path, PathBuf};
+
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use anyhow::{Context, Result, anyhow, bail};
+use image::{DynamicImage, RgbaImage};
+
+use crate::{asset_store::AssetStore, dsl::Node};
+
+const DEFAULT_FONT_PATH: &str = "assets/fonts/MiSans/MiSans-Regular.ttf";
+/// Largest atlas edge; bigger layouts are rejected rather than silently clipped.
+const MAX_ATLAS_SIZE: u32 = 8192;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TextEncoding {
+    Bitmap,
+    Sdf,
+}
+
+impl TextEncoding {
+    pub(crate) fn from_node(node: &Node) -> Result<Self> {
+        match node
+            .params
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .unwrap_or("sdf")
+        {
+            "bitmap" => Ok(Self::Bitmap),
+            "sdf" => Ok(Self::Sdf),
+            other => bail!("unsupported Text.mode: {other}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Debug)]
+struct TextLayoutParams {
+    text: String,
+    font_size: f32,
+    letter_spacing: f32,
+    line_height: f32,
+    align: TextAlign,
+    padding: u32,
+    encoding: TextEncoding,
+    sdf_spread: f32,
+}
+
+impl TextLayoutParams {
+    fn from_node(node: &Node) -> Result<Self> {
+        let f32_param = |key: &str, default: f32| {
+            node.params
+                .get(key)
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(default)
+        };
+        let align = match node
+            .params
+            .get("align")
+            .and_then(|v| v.as_str())
+            .unwrap_or("left")
+        {
+            "left" => TextAlign::Left,
+            "center" => TextAlign::Center,
+            "right" => TextAlign::Right,
+            other => bail!("unsupported Text.align: {other}"),
+        };
+        let encoding = TextEncoding::from_node(node)?;
+        let font_size = f32_param("fontSize", 64.0);
+        if font_size.is_nan() || font_size <= 0.0 {
+            bail!("Text node '{}': fontSize must be > 0", node.id);
+        }
+        let sdf_spread = f32_param("sdfSpread", (font_size / 8.0).max(2.0)).max(1.0);
+        let min_padding = match encoding {
+            TextEncoding::Bitmap => 1.0,
+            TextEncoding::Sdf => sdf_spread.ceil() + 1.0,
+        };
+        Ok(Self {
+            text: node
+                .params
+                .get("text")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            font_size,
+            letter_spacing: f32_param("letterSpacing", 0.0),
+            line_height: f32_param("lineHeight", 1.2).max(0.0),
+            align,
+            padding: f32_param("padding", 0.0).max(min_padding).ceil() as u32,
+            encoding,
+            sdf_spread,
+        })
+    }
+}
+
+fn load_font(node: &Node, asset_store: Option<&AssetStore>, rel_base: &Path) -> Result<FontArc> {
+    if let Some(asset_id) = node
+        .params
+        .get("fontAssetId")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+    {
+        let store = asset_store.ok_or_else(|| {
+            anyhow!(
+                "Text node '{}': has fontAssetId '{asset_id}' but no asset store provided",
+                node.id
+            )
+        })?;
+        let data = store.get(asset_id).ok_or_else(|| {
+            anyhow!(
+                "Text node '{}': font asset '{asset_id}' not found in asset store",
+                node.id
+            )
+        })?;
+        return FontArc::try_from_vec(data.bytes)
+            .map_err(|e| anyhow!("Text node '{}': invalid font asset: {e}", node.id));
+    }
+
+    let path = node
+        .params
+        .get("fontPath")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(DEFAULT_FONT_PATH);
+    let path = if Path::new(path).is_absolute() {
+        PathBuf::from(path)
+    } else {
+        rel_base.join(path)
+    };
+    let bytes = std::fs::read(&path)
+        .with_context(|| format!("Text node '{}': failed to read font {path:?}", node.id))?;
+    FontArc::try_from_vec(bytes)
+        .map_err(|e| anyhow!("Text node '{}': invalid font {path:?}: {e}", node.id))
+}
+
+/// Rasterizes a `Text` node into an rgba8 image (see module docs for the encoding).
+pub(crate) fn rasterize_text_node(
+    node: &Node,
+    asset_store: Option<&AssetStore>,
+    rel_base: &Path,
+) -> Result<DynamicImage> {
+    let params = TextLayoutParams::from_node(node)?;
+    let font = load_font(node, asset_store, rel_base)?;
+    let coverage =
+        rasterize_coverage(&font, &params).with_context(|| format!("Text node '{}'", node.id))?;
+    Ok(DynamicImage::ImageRgba8(encode(&coverage, &params)))
+}
+
+/// Single-channel coverage image, `width * height` values in `[0, 1]`, top row first.
+struct Coverage {
+    width: u32,
+    height: u32,
+    values: Vec<f32>,
+}
+
+fn rasterize_coverage(font: &FontArc, params: &TextLayoutParams) -> Result<Coverage> {
+    let scaled = font.as_scaled(PxScale::from(params.font_size));
+    let line_advance = (scaled.height() + scaled.line_gap()) * params.line_height.max(0.0);
+
+    // Lay out each line at the origin first so alignment can use the final widths.
+    let mut lines: Vec<(Vec<(ab_glyph::Glyph, f32)>, f32)> = Vec::new();
+    for line in params.text.split('\n') {
+        let mut caret = 0.0f32;
+        let mut glyphs = Vec::new();
+        let mut prev = None;
+        for ch in line.trim_end_matches('\r').chars() {
+            let id = scaled.glyph_id(ch);
+            if let Some(prev) = prev {
+                caret += scaled.kern(prev, id) + params.letter_spacing;
+            }
+            glyphs.push((id.with_scale(params.font_size), caret));
+            caret += scaled.h_advance(id);
+            prev = Some(id);
+        }
+        lines.push((glyphs, caret.max(0.0)));
+    }
+
+    let content_w = lines.iter().map(|(_, w)| *w).fold(0.0f32, f32::max);
+    let content_h = scaled.height() + line_advance * (lines.len().saturating_sub(1)) as f32;
+    let pad = params.padding as f32;
+    let width = (content_w + pad * 2.0).ceil().max(1.0) as u32;
+    let height = (content_h + pad * 2.0).ceil().max(1.0) as u32;
+    if width > MAX_ATLAS_SIZE || height > MAX_ATLAS_SIZE {
+        bail!("text atlas {width}x{height} exceeds {MAX_ATLAS_SIZE}px; reduce fontSize or text");
+    }
+
+    let mut values = vec![0.0f32; width as usize * height as usize];
+    for (line_index, (glyphs, line_w)) in lines.into_iter().enumerate() {
+        let x0 = pad
+            + match params.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (content_w - line_w) * 0.5,
+                TextAlign::Right => content_w - line_w,
+            };
+        let baseline = pad + scaled.ascent() + line_advance * line_index as f32;
+        for (mut glyph, x) in glyphs {
+            glyph.position = ab_glyph::point(x0 + x, baseline);
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, c| {
+                let x = bounds.min.x as i64 + gx as i64;
+                let y = bounds.min.y as i64 + gy as i64;
+                if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+                    return;
+                }
+                let slot = &mut values[y as usize * width as usize + x as usize];
+                *slot = (*slot + c).min(1.0);
+            });
+        }
+    }
+
+    Ok(Coverage {
+        width,
+        height,
+        values,
+    })
+}
+
+fn encode(coverage: &Coverage, params: &TextLayoutParams) -> RgbaImage {
+    // Rows stay top-first, the same layout decoded images are uploaded with.
+    let encoded: Vec<f32> = match params.encoding {
+        TextEncoding::Bitmap => coverage.values.clone(),
+        TextEncoding::Sdf => signed_distance(coverage)
+            .into_iter()
+            .map(|d| (0.5 - d / (2.0 * params.sdf_spread)).clamp(0.0, 1.0))
+            .collect(),
+    };
+    let mut image = RgbaImage::new(coverage.width, coverage.height);
+    for (pixel, v) in image.pixels_mut().zip(encoded) {
+        let b = (v * 255.0).round() as u8;
+        pixel.0 = [b, b, b, b];
+    }
+    image
+}
+
+/// Signed distance in pixels to the 50% coverage edge (negative inside).
+fn signed_distance(coverage: &Coverage) -> Vec<f32> {
+    let (w, h) = (coverage.width as usize, coverage.height as usize);
+    let inside: Vec<bool> = coverage.values.iter().map(|&c| c >= 0.5).collect();
+    let to_inside = distance_transform(w, h, |i| inside[i]);
+    let to_outside = distance_transform(w, h, |i| !inside[i]);
+    to_inside
+        .iter()
+        .zip(&to_outside)
+        .map(|(di, dout)| {
+            if *di == 0.0 {
+                // Inside texels: distance to the nearest outside texel, measured to its edge.
+                -(dout - 0.5)
+            } else {
+                di - 0.5
+            }
+        })
+        .collect()
+}
+
+/// Exact Euclidean distance (in pixels) from every texel to the nearest seed texel.
+fn distance_transform(w: usize, h: usize, is_seed: impl Fn(usize) -> bool) -> Vec<f32> {
+    const FAR: f64 = 1e20;
+    let mut grid: Vec<f64> = (0..w * h)
+        .map(|i| if is_seed(i) { 0.0 } else { FAR })
+        .collect();
+
+    let n = w.max(h);
+    let mut f = vec![0.0f64; n];
+    let mut d = vec![0.0f64; n];
+    let mut v = vec![0usize; n];
+    let mut z = vec![0.0f64; n + 1];
+
+    for x in 0..w {
+        for y in 0..h {
+            f[y] = grid[y * w + x];
+        }
+        edt_1d(&f[..h], &mut d[..h], &mut v, &mut z);
+        for y in 0..h {
+            grid[y * w + x] = d[y];
+        }
+    }
+    for y in 0..h {
+        f[..w].copy_from_slice(&grid[y * w..(y + 1) * w]);
+        edt_1d(&f[..w], &mut d[..w], &mut v, &mut z);
+        grid[y * w..(y + 1) * w].copy_from_slice(&d[..w]);
+    }
+    grid.into_iter().map(|d| d.sqrt() as f32).collect()
+}
+
+/// Felzenszwalb & Huttenlocher squared-distance transform of a sampled function.
+fn edt_1d(f: &[f64], d: &mut [f64], v: &mut [usize], z: &mut [f64]) {
+    if f.is_empty() {
+        return;
+    }
+    let intersect = |q: usize, p: usize| {
+        ((f[q] + (q * q) as f64) - (f[p] + (p * p) as f64)) / (2.0 * (q - p) as f64)
+    };
+    let mut k = 0usize;
+    v[0] = 0;
+    z[0] = f64::NEG_INFINITY;
+    z[1] = f64::INFINITY;
+    for q in 1..f.len() {
+        let mut s = intersect(q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersect(q, v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f64::INFINITY;
+    }
+    k = 0;
+    for (q, out) in d.iter_mut().enumerate() {
+        while z[k + 1] < q as f64 {
+            k += 1;
+        }
+        let dq = q as f64 - v[k] as f64;
+        *out = dq * dq + f[v[k]];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Coverage, TextEncoding, signed_distance};
+
+    #[test]
+    fn signed_distance_is_negative_inside_and_grows_outward() {
+        // 9x1 strip with the middle three texels covered.
+        let coverage = Coverage {
+            width: 9,
+            height: 1,
+            values: vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0],
+        };
+        let sdf = signed_distance(&coverage);
+        assert_eq!(sdf, vec![2.5, 1.5, 0.5, -0.5, -1.5, -0.5, 0.5, 1.5, 2.5]);
+    }
+
+    #[test]
+    fn mode_defaults_to_sdf() {
+        let node: crate::dsl::Node = serde_json::from_value(serde_json::json!({
+            "id": "label",
+            "type": "Text",
+            "text": "12"
+        }))
+        .unwrap();
+        assert_eq!(TextEncoding::from_node(&node).unwrap(), TextEncoding::Sdf);
+    }
+}
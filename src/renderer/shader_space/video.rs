@@ -0,0 +1,265 @@
+//! Video export for headless frame-range renders.
+//!
+//! Frames are piped as raw RGBA into an `ffmpeg` subprocess, which owns the
+//! container and codec. Set `NODE_FORGE_FFMPEG_BIN` to use a specific binary.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::thread::JoinHandle;
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::external_command::resolve_command_path;
+
+/// Container formats recognised by output file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VideoContainer {
+    Mp4,
+    WebM,
+}
+
+impl VideoContainer {
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "mp4" => Some(Self::Mp4),
+            "webm" => Some(Self::WebM),
+            _ => None,
+        }
+    }
+
+    fn default_codec(self) -> &'static str {
+        match self {
+            Self::Mp4 => "libx264",
+            Self::WebM => "libvpx-vp9",
+        }
+    }
+}
+
+/// Returns true when `path` names a video file (`.mp4`, `.webm`).
+pub fn is_video_output_path(path: &Path) -> bool {
+    VideoContainer::from_path(path).is_some()
+}
+
+/// Encoder settings for video export. `None` fields use the container defaults:
+/// H.264 for MP4, VP9 for WebM, and ffmpeg's default rate control.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VideoEncodeOptions {
+    /// ffmpeg encoder name, e.g. `libx264`, `libx265`, `libvpx-vp9`, `libaom-av1`.
+    pub codec: Option<String>,
+    /// Target bitrate in ffmpeg syntax, e.g. `8M` or `2500k`.
+    pub bitrate: Option<String>,
+}
+
+fn ffmpeg_args(
+    output_path: &Path,
+    container: VideoContainer,
+    size: [u32; 2],
+    fps: f32,
+    options: &VideoEncodeOptions,
+) -> Vec<String> {
+    let codec = options
+        .codec
+        .as_deref()
+        .unwrap_or(container.default_codec());
+    let mut args: Vec<String> = [
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-y",
+        "-f",
+        "rawvideo",
+        "-pix_fmt",
+        "rgba",
+        "-s",
+    ]
+    .map(String::from)
+    .into();
+    args.push(format!("{}x{}", size[0], size[1]));
+    args.extend(["-r".to_string(), fps.to_string()]);
+    args.extend(["-i", "-", "-an"].map(String::from));
+    args.extend(["-c:v".to_string(), codec.to_string()]);
+    if let Some(bitrate) = &options.bitrate {
+        args.extend(["-b:v".to_string(), bitrate.clone()]);
+    }
+    // Premultiplied RGBA without alpha is the frame over black; yuv420p needs
+    // even dimensions and is what players expect.
+    args.extend(
+        [
+            "-vf",
+            "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+            "-pix_fmt",
+            "yuv420p",
+        ]
+        .map(String::from),
+    );
+    if container == VideoContainer::Mp4 {
+        args.extend(["-movflags", "+faststart"].map(String::from));
+    }
+    args.push(output_path.display().to_string());
+    args
+}
+
+/// An `ffmpeg` process consuming tightly packed RGBA8 frames on stdin.
+pub(crate) struct VideoEncoder {
+    process: Child,
+    stdin: Option<ChildStdin>,
+    /// Collects ffmpeg's stderr as it is written, so a chatty encoder never
+    /// blocks on a full pipe while frames are still being fed to it.
+    stderr: Option<JoinHandle<String>>,
+    frame_len: usize,
+    output_path: PathBuf,
+}
+
+impl VideoEncoder {
+    pub(crate) fn spawn(
+        output_path: &Path,
+        size: [u32; 2],
+        fps: f32,
+        options: &VideoEncodeOptions,
+    ) -> Result<Self> {
+        let container = VideoContainer::from_path(output_path).ok_or_else(|| {
+            anyhow!(
+                "video export requires a .mp4 or .webm output, got {}",
+                output_path.display()
+            )
+        })?;
+        let ffmpeg = resolve_command_path("NODE_FORGE_FFMPEG_BIN", "ffmpeg");
+        let mut process = Command::new(&ffmpeg)
+            .args(ffmpeg_args(output_path, container, size, fps, options))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                anyhow!(
+                    "failed to start {} for video export (set NODE_FORGE_FFMPEG_BIN to override): {e}",
+                    ffmpeg.display()
+                )
+            })?;
+        let stdin = process.stdin.take();
+        let stderr = process.stderr.take().map(|mut pipe| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                let _ = pipe.read_to_string(&mut text);
+                text
+            })
+        });
+        Ok(Self {
+            process,
+            stdin,
+            stderr,
+            frame_len: size[0] as usize * size[1] as usize * 4,
+            output_path: output_path.to_path_buf(),
+        })
+    }
+
+    pub(crate) fn write_frame(&mut self, rgba: &[u8]) -> Result<()> {
+        if rgba.len() != self.frame_len {
+            bail!(
+                "video frame has {} bytes, expected {}",
+                rgba.len(),
+                self.frame_len
+            );
+        }
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| anyhow!("video encoder input already closed"))?;
+        if let Err(e) = stdin.write_all(rgba) {
+            // ffmpeg exited early; its stderr explains why.
+            self.stdin = None;
+            return Err(self
+                .wait_for_exit()
+                .err()
+                .unwrap_or_else(|| anyhow!("failed to write video frame: {e}")));
+        }
+        Ok(())
+    }
+
+    /// Closes the frame stream and waits for ffmpeg to finish the file.
+    pub(crate) fn finish(mut self) -> Result<()> {
+        self.stdin = None;
+        self.wait_for_exit()
+    }
+
+    fn wait_for_exit(&mut self) -> Result<()> {
+        let status = self
+            .process
+            .wait()
+            .map_err(|e| anyhow!("failed to wait for ffmpeg: {e}"))?;
+        let stderr = self
+            .stderr
+            .take()
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        if !status.success() {
+            bail!(
+                "ffmpeg failed to encode {} ({status}): {}",
+                self.output_path.display(),
+                stderr.trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Drop for VideoEncoder {
+    fn drop(&mut self) {
+        if self.stdin.take().is_some() {
+            // Dropped mid-stream after an error: don't leave ffmpeg running.
+            let _ = self.process.kill();
+            let _ = self.process.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ffmpeg_args_use_container_defaults_and_overrides() {
+        let args = ffmpeg_args(
+            Path::new("/tmp/out.mp4"),
+            VideoContainer::Mp4,
+            [641, 360],
+            24.0,
+            &VideoEncodeOptions::default(),
+        );
+        let joined = args.join(" ");
+        assert!(joined.contains("-s 641x360 -r 24 -i -"));
+        assert!(joined.contains("-c:v libx264"));
+        assert!(!joined.contains("-b:v"));
+        assert!(joined.contains("-movflags +faststart"));
+        assert_eq!(args.last().map(String::as_str), Some("/tmp/out.mp4"));
+
+        let args = ffmpeg_args(
+            Path::new("/tmp/out.webm"),
+            VideoContainer::WebM,
+            [64, 64],
+            30.0,
+            &VideoEncodeOptions {
+                codec: Some("libaom-av1".to_string()),
+                bitrate: Some("4M".to_string()),
+            },
+        );
+        let joined = args.join(" ");
+        assert!(joined.contains("-c:v libaom-av1 -b:v 4M"));
+        assert!(!joined.contains("-movflags"));
+    }
+
+    #[test]
+    fn video_container_from_extension() {
+        assert_eq!(
+            VideoContainer::from_path(Path::new("/tmp/a.MP4")),
+            Some(VideoContainer::Mp4)
+        );
+        assert_eq!(
+            VideoContainer::from_path(Path::new("/tmp/a.webm")),
+            Some(VideoContainer::WebM)
+        );
+        assert!(!is_video_output_path(Path::new("/tmp/a_{frame}.png")));
+    }
+}
@@ -141,6 +141,9 @@ pub struct BakedDataParseMeta {
     pub outputs_per_instance: u32,
     pub slot_by_output: HashMap<(String, String, String), u32>,
     pub pass_id: String,
+    /// Named per-instance attributes declared by the pass (`instanceAttributes`), mapped to
+    /// the vec4 slot of the DataParse output that feeds them.
+    pub attribute_slots: HashMap<String, u32>,
 }
 
 impl BakedDataParseMeta {
@@ -153,6 +156,10 @@ impl BakedDataParseMeta {
             ))
             .copied()
     }
+
+    pub fn slot_for_attribute(&self, name: &str) -> Option<u32> {
+        self.attribute_slots.get(name).copied()
+    }
 }
 
 /// Output specification for any pass node that produces a texture.
@@ -412,7 +419,7 @@ impl TypedExpr {
 }
 
 /// Context for compiling material expressions, tracking referenced resources.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct MaterialCompileContext {
     pub baked_data_parse: Option<Arc<HashMap<(String, String, String), Vec<BakedValue>>>>,
     pub baked_data_parse_meta: Option<Arc<BakedDataParseMeta>>,
@@ -423,10 +430,19 @@ pub struct MaterialCompileContext {
     /// in the future we may also forward it into the fragment stage.
     pub uses_instance_index: bool,
 
+    /// Set when the compiled shader reads `front_facing`; the fragment entry then declares
+    /// `@builtin(front_facing)` (see [`MaterialCompileContext::fragment_entry_params`]).
+    pub uses_front_facing: bool,
+
     /// Set when the compiled shader needs the world-position varying (vertex → fragment)
     /// and `params.camera_position` to compute a view direction vector.
     pub needs_view_vector: bool,
 
+    /// Set when the compiled shader calls `surfaceNormal(in)`. The shader builder defines it
+    /// from the interpolated vertex normal when the geometry has one, and from screen-space
+    /// derivatives of `local_px` otherwise.
+    pub needs_surface_normal: bool,
+
     /// Set when the compiled shader uses f16 types and requires `enable f16;` at the top.
     pub needs_f16: bool,
 
@@ -615,6 +631,15 @@ impl MaterialCompileContext {
     }
 
     /// Build the fragment body with generated readable statements before the final return.
+    /// Parameter list for `fs_main`, including the front-facing builtin when a node reads it.
+    pub fn fragment_entry_params(&self) -> &'static str {
+        if self.uses_front_facing {
+            "in: VSOut, @builtin(front_facing) front_facing: bool"
+        } else {
+            "in: VSOut"
+        }
+    }
+
     pub fn build_fragment_body(&self, return_expr: &str) -> String {
         // Clamp alpha to [0,1] coverage — HDR energy lives in RGB only.
         let stmts = if self.inline_stmts.is_empty() {
@@ -682,6 +707,35 @@ pub struct Params {
 
     // Camera world-space position (xyz), w = 0 padding for 16-byte alignment.
     pub camera_position: [f32; 4],
+
+    // Frame clock shared by every pass (Time node `delta` / `frame` outputs).
+    pub delta_time: f32,
+    pub frame: u32,
+    pub _pad1: [f32; 2],
+}
+
+/// Per-frame clock values written into [`Params`] before each render.
+///
+/// One value is shared by every pass of a frame so time-driven graphs never
+/// re-derive the clock per node and drift between passes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameTiming {
+    /// Scene time in seconds.
+    pub time_secs: f32,
+    /// Seconds elapsed since the previous frame.
+    pub delta_secs: f32,
+    /// Monotonic frame index; reset together with scene time.
+    pub frame: u32,
+}
+
+impl Params {
+    /// Copy of `self` with the frame clock fields set from `timing`.
+    pub fn with_frame_timing(mut self, timing: FrameTiming) -> Self {
+        self.time = timing.time_secs;
+        self.delta_time = timing.delta_secs;
+        self.frame = timing.frame;
+        self
+    }
 }
 
 /// Per-frame custom update configuration for specialized pass types.
@@ -725,7 +779,7 @@ pub struct WgslShaderBundle {
     pub vertex: String,
     /// A standalone fragment WGSL module (common + @fragment entry).
     pub fragment: String,
-    /// Optional compute WGSL module (common + @compute entry). Currently unused.
+    /// Optional compute WGSL module (common + @compute entry). Used by `ComputePass`.
     pub compute: Option<String>,
     /// A combined WGSL module containing all emitted entry points.
     pub module: String,
@@ -1,7 +1,19 @@
 //! WGSL validation using the naga library.
 
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+
 use anyhow::{Context, Result, anyhow};
 
+/// Upper bound on remembered module results; the cache is cleared when reached.
+const VALIDATION_CACHE_MAX_ENTRIES: usize = 4096;
+
+/// Validation outcome per WGSL source hash. Failures keep their message so a
+/// broken module that is rebuilt unchanged reports the same error cheaply.
+static VALIDATION_CACHE: OnceLock<Mutex<HashMap<u64, Result<(), String>>>> = OnceLock::new();
+
 /// Validate WGSL source code using naga's parser.
 ///
 /// # Arguments
@@ -35,6 +47,77 @@ pub fn validate_wgsl_with_context(source: &str, context: &str) -> Result<naga::M
     validate_wgsl(source).with_context(|| format!("{} generated invalid WGSL", context))
 }
 
+/// Parse and fully validate a WGSL module (types, uniformity, bindings).
+///
+/// `validate_wgsl` only parses; this also runs naga's `Validator`, which is
+/// what catches most generated-shader mistakes before pipeline creation.
+pub fn validate_wgsl_module(source: &str) -> Result<()> {
+    let module = validate_wgsl(source)?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|error| anyhow!("WGSL validation failed: {error:?}"))?;
+    Ok(())
+}
+
+/// Validates `source` through the cache. The flag is true when the result
+/// came from the cache rather than a fresh validation.
+fn validate_wgsl_module_through_cache(
+    source: &str,
+    skip_trusted: bool,
+) -> (Result<(), String>, bool) {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    let key = hasher.finish();
+    let cache = VALIDATION_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+
+    let cached = cache.lock().ok().and_then(|cache| cache.get(&key).cloned());
+    match cached {
+        Some(Ok(())) if skip_trusted => (Ok(()), true),
+        Some(Err(message)) => (Err(message), true),
+        _ => {
+            let result = validate_wgsl_module(source).map_err(|e| format!("{e:#}"));
+            if let Ok(mut cache) = cache.lock() {
+                if cache.len() >= VALIDATION_CACHE_MAX_ENTRIES {
+                    cache.clear();
+                }
+                cache.insert(key, result.clone());
+            }
+            (result, false)
+        }
+    }
+}
+
+/// [`validate_wgsl_module`] with results remembered by source hash.
+///
+/// Known failures are always reported. When `skip_trusted` is set, modules
+/// that already validated in this process are not validated again.
+pub fn validate_wgsl_module_cached(source: &str, context: &str, skip_trusted: bool) -> Result<()> {
+    validate_wgsl_module_through_cache(source, skip_trusted)
+        .0
+        .map_err(|message| anyhow!(message))
+        .with_context(|| format!("{} generated invalid WGSL", context))
+}
+
+/// Advisory form of [`validate_wgsl_module_cached`] for shader space builds.
+///
+/// The naga pinned here is older than the one inside wgpu and validates with
+/// every capability enabled, so it can both reject WGSL that wgpu accepts and
+/// accept WGSL the device cannot run. Failures are therefore only logged, once
+/// per module source; wgpu's own validation at pipeline creation decides
+/// whether the build fails. Returns true when a failure was logged.
+pub fn warn_invalid_wgsl_module_cached(source: &str, context: &str, skip_trusted: bool) -> bool {
+    match validate_wgsl_module_through_cache(source, skip_trusted) {
+        (Err(message), false) => {
+            eprintln!("[shader-validation] {context} generated WGSL naga rejects: {message}");
+            true
+        }
+        _ => false,
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum GlslShaderStage {
     Vertex,
@@ -140,6 +223,55 @@ fn fs_main() -> @location(0) vec4f {
         assert!(validate_wgsl(source).is_err());
     }
 
+    #[test]
+    fn full_validation_catches_what_parsing_accepts() {
+        // Parses fine, but a fragment output without a binding is rejected.
+        let source = r#"
+@fragment
+fn fs_main() -> vec4f {
+    return vec4f(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+        assert!(validate_wgsl(source).is_ok());
+        assert!(validate_wgsl_module(source).is_err());
+    }
+
+    #[test]
+    fn cached_validation_keeps_reporting_failures() {
+        let source = "@fragment\nfn fs_main() -> @location(0) vec4f { return 1; }\n";
+        for skip_trusted in [false, true] {
+            let err = validate_wgsl_module_cached(source, "cached pass", skip_trusted)
+                .expect_err("invalid module");
+            assert!(format!("{err:#}").contains("cached pass"));
+        }
+
+        let valid = "@fragment\nfn fs_main() -> @location(0) vec4f { return vec4f(0.25); }\n";
+        validate_wgsl_module_cached(valid, "cached pass", false).unwrap();
+        validate_wgsl_module_cached(valid, "cached pass", true).unwrap();
+    }
+
+    #[test]
+    fn advisory_validation_logs_each_failing_module_once() {
+        let source = "@fragment\nfn fs_main() -> @location(0) vec4f { return 2; }\n";
+        assert!(warn_invalid_wgsl_module_cached(
+            source,
+            "advisory pass",
+            false
+        ));
+        assert!(!warn_invalid_wgsl_module_cached(
+            source,
+            "advisory pass",
+            false
+        ));
+
+        let valid = "@fragment\nfn fs_main() -> @location(0) vec4f { return vec4f(0.5); }\n";
+        assert!(!warn_invalid_wgsl_module_cached(
+            valid,
+            "advisory pass",
+            false
+        ));
+    }
+
     #[test]
     fn test_validate_with_context() {
         let source = "invalid wgsl";
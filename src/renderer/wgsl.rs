@@ -5,16 +5,18 @@
 //! - Gaussian blur utilities for post-processing effects
 //! - Helper functions for formatting WGSL code
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use anyhow::{Result, anyhow, bail};
+use rayon::prelude::*;
 
 use crate::{
     dsl::{Node, SceneDSL, find_node, incoming_connection},
     renderer::{
-        node_compiler::compile_material_expr,
+        node_compiler::{compile_cache::compile_material_expr_cached, compile_material_expr},
         render_plan::{parse_kernel_source_js_like, resolve_geometry_for_render_pass},
-        scene_prep::prepare_scene,
+        scene_prep::{PreparedScene, prepare_scene},
         types::{
             GraphBindingKind, GraphFieldKind, GraphSchema, Kernel2D, MaterialCompileContext,
             TypedExpr, ValueType, WgslShaderBundle,
@@ -23,6 +25,7 @@ use crate::{
         wgsl_bloom::{
             BLOOM_MAX_MIPS, build_bloom_additive_combine_bundle, build_bloom_extract_bundle,
         },
+        wgsl_templates::{ChannelDebugMode, channel_debug::build_channel_debug_fragment_entry},
     },
 };
 
@@ -220,6 +223,10 @@ struct Params {{
     color: vec4f,
     camera: mat4x4f,
     camera_position: vec4f,
+
+    // Frame clock shared by all passes (Time node).
+    delta_time: f32,
+    frame: u32,
 }};
 
 
@@ -311,6 +318,24 @@ pub(crate) fn build_fullscreen_textured_bundle(fragment_body: String) -> WgslSha
     build_fullscreen_textured_bundle_with_instance_index(fragment_body, false)
 }
 
+/// Declare an extra `texture_2d<f32>`/`sampler` pair at `@group(1) @binding(binding)`
+/// and `binding + 1` in a [`build_fullscreen_textured_bundle`] module.
+pub(crate) fn with_extra_fullscreen_texture(
+    module: &str,
+    texture: &str,
+    sampler: &str,
+    binding: u32,
+) -> String {
+    let decl = format!(
+        "\n@group(1) @binding({binding})\nvar {texture}: texture_2d<f32>;\n@group(1) @binding({})\nvar {sampler}: sampler;\n",
+        binding + 1
+    );
+    match module.find("\n @vertex") {
+        Some(at) => format!("{}{decl}{}", &module[..at], &module[at..]),
+        None => format!("{decl}{module}"),
+    }
+}
+
 pub(crate) fn build_fullscreen_textured_bundle_with_instance_index(
     fragment_body: String,
     uses_instance_index: bool,
@@ -333,6 +358,10 @@ struct Params {
     color: vec4f,
     camera: mat4x4f,
     camera_position: vec4f,
+
+    // Frame clock shared by all passes (Time node).
+    delta_time: f32,
+    frame: u32,
 };
 
 
@@ -503,6 +532,12 @@ fn build_static_vertex_fragment_bundle(combined_wgsl: String) -> WgslShaderBundl
 impl MaterialCompileContext {
     // Extension method for generating WGSL binding declarations
     pub(crate) fn wgsl_decls(&self) -> String {
+        self.wgsl_decls_with_normals(false)
+    }
+
+    /// Like [`Self::wgsl_decls`], for shaders whose `VSOut` carries an interpolated
+    /// `normal` when `has_vertex_normals` is set.
+    pub(crate) fn wgsl_decls_with_normals(&self, has_vertex_normals: bool) -> String {
         let mut out = String::new();
 
         // Image texture bindings
@@ -546,10 +581,33 @@ impl MaterialCompileContext {
             }
         }
 
+        if self.needs_surface_normal {
+            out.push_str(surface_normal_wgsl_decl(has_vertex_normals));
+        }
+
         out
     }
 }
 
+fn surface_normal_wgsl_decl(has_vertex_normals: bool) -> &'static str {
+    if has_vertex_normals {
+        r#"
+fn surfaceNormal(in: VSOut) -> vec3f {
+    return normalize(in.normal);
+}
+"#
+    } else {
+        r#"
+// No vertex normals: reconstruct a flat normal from screen-space derivatives.
+fn surfaceNormal(in: VSOut) -> vec3f {
+    let n = cross(dpdx(in.local_px), dpdy(in.local_px));
+    let len = length(n);
+    return select(vec3f(0.0, 0.0, 1.0), n / max(len, 1e-12), len > 1e-12);
+}
+"#
+    }
+}
+
 pub(crate) fn merge_graph_input_kinds(
     material_ctx: &MaterialCompileContext,
     extra: &std::collections::BTreeMap<String, GraphFieldKind>,
@@ -653,11 +711,28 @@ pub fn build_blur_image_wgsl_bundle_with_graph_binding(
             node.node_type.as_str(),
             "RenderPass"
                 | "BloomNode"
+                | "BloomPass"
                 | "GuassianBlurPass"
                 | "Downsample"
                 | "Upsample"
                 | "GradientBlur"
                 | "MeshGradient"
+                | "ComputePass"
+                | "FeedbackPass"
+                | "DepthOfFieldPass"
+                | "ConvolutionPass"
+                | "EdgeDetect"
+                | "ChromaticAberration"
+                | "Vignette"
+                | "FilmGrain"
+                | "LensDistortion"
+                | "LUTPass"
+                | "Pixelate"
+                | "Posterize"
+                | "Dither"
+                | "DropShadow"
+                | "Displace"
+                | "AntiAliasPass"
                 | "Composite"
         )
     });
@@ -708,6 +783,10 @@ struct Params {
     color: vec4f,
     camera: mat4x4f,
     camera_position: vec4f,
+
+    // Frame clock shared by all passes (Time node).
+    delta_time: f32,
+    frame: u32,
 };
 
 @group(0) @binding(0)
@@ -754,10 +833,11 @@ fn vs_main(@location(0) position: vec3f, @location(1) uv: vec2f) -> VSOut {
     let fragment = format!(
         r#"
 @fragment
-fn fs_main(in: VSOut) -> @location(0) vec4f {{
+fn fs_main({}) -> @location(0) vec4f {{
 {}
 }}
 "#,
+        material_ctx.fragment_entry_params(),
         fragment_body
     );
 
@@ -814,6 +894,7 @@ pub fn build_pass_wgsl_bundle(
         None,
         false, // fullscreen_vertex_positioning
         false, // has_normals
+        ChannelDebugMode::Off,
     )
 }
 
@@ -844,6 +925,8 @@ pub(crate) fn build_pass_wgsl_bundle_with_graph_binding(
     fullscreen_vertex_positioning: bool,
     // When true, the vertex shader declares @location(6) normal: vec3f and passes it through VSOut.
     has_normals: bool,
+    // Replaces the material output with a coordinate visualization (global debug switch).
+    channel_debug: ChannelDebugMode,
 ) -> Result<WgslShaderBundle> {
     // If RenderPass.material is connected, compile the upstream subgraph into an expression.
     // Otherwise, fallback to constant color.
@@ -854,14 +937,12 @@ pub(crate) fn build_pass_wgsl_bundle_with_graph_binding(
     };
     let fragment_expr: TypedExpr =
         if let Some(conn) = incoming_connection(scene, pass_id, "material") {
-            let mut cache: HashMap<(String, String), TypedExpr> = HashMap::new();
-            compile_material_expr(
+            compile_material_expr_cached(
                 scene,
                 nodes_by_id,
                 &conn.from.node_id,
-                Some(&conn.from.port_id),
+                &conn.from.port_id,
                 &mut material_ctx,
-                &mut cache,
             )?
         } else {
             // Premultiply params.color on the shader side to match premultiplied blending defaults.
@@ -908,6 +989,10 @@ struct Params {
     color: vec4f,
     camera: mat4x4f,
     camera_position: vec4f,
+
+    // Frame clock shared by all passes (Time node).
+    delta_time: f32,
+    frame: u32,
 };
 
 @group(0) @binding(0)
@@ -955,7 +1040,7 @@ var<uniform> params: Params;
         );
     }
 
-    common.push_str(&material_ctx.wgsl_decls());
+    common.push_str(&material_ctx.wgsl_decls_with_normals(has_normals));
     common.push_str(&vertex_wgsl_decls);
 
     if material_ctx.needs_f16 {
@@ -1143,14 +1228,23 @@ var<uniform> params: Params;
 
     let vertex_entry = vertex_entry;
 
-    let fragment_entry = format!(
-        r#"
+    let entry_params = material_ctx.fragment_entry_params();
+    let fragment_entry = build_channel_debug_fragment_entry(
+        channel_debug,
+        &fragment_body,
+        has_normals,
+        material_ctx.uses_front_facing,
+    )
+    .unwrap_or_else(|| {
+        format!(
+            r#"
 @fragment
-fn fs_main(in: VSOut) -> @location(0) vec4f {{
+fn fs_main({entry_params}) -> @location(0) vec4f {{
 {fragment_body}
 }}
 "#
-    );
+        )
+    });
 
     let vertex = format!("{common}{vertex_entry}");
     let fragment = format!("{common}{fragment_entry}");
@@ -1184,7 +1278,6 @@ pub fn build_all_pass_wgsl_bundles_from_scene_with_assets(
 ) -> Result<Vec<(String, WgslShaderBundle)>> {
     let prepared = prepare_scene(scene)?;
     let nodes_by_id = &prepared.nodes_by_id;
-    let ids = &prepared.ids;
     let output_target_node = find_node(nodes_by_id, &prepared.output_texture_node_id)?;
     let render_target_size = [
         cpu_num_u32_min_1(
@@ -1203,8 +1296,6 @@ pub fn build_all_pass_wgsl_bundles_from_scene_with_assets(
         )? as f32,
     ];
 
-    let mut baked_data_parse = prepared.baked_data_parse.clone();
-
     let composite_layers_in_draw_order = prepared.composite_layers_in_draw_order.clone();
     let sampled_pass_ids = crate::renderer::render_plan::sampled_pass_node_ids_from_roots(
         &prepared.scene,
@@ -1212,382 +1303,615 @@ pub fn build_all_pass_wgsl_bundles_from_scene_with_assets(
         &composite_layers_in_draw_order,
     )?;
 
+    // Layers are independent (each only reads the prepared scene), so their
+    // bundles are generated in parallel and concatenated in draw order.
+    let per_layer = composite_layers_in_draw_order
+        .par_iter()
+        .map(|layer_id| {
+            build_layer_pass_wgsl_bundles(
+                &prepared,
+                render_target_size,
+                &sampled_pass_ids,
+                asset_store,
+                layer_id,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(per_layer.into_iter().flatten().collect())
+}
+
+/// Writes `{pass}.vertex.wgsl`, `{pass}.fragment.wgsl`, `{pass}.module.wgsl` and,
+/// for compute passes, `{pass}.compute.wgsl` for every pass of `scene` into `dir`.
+/// Returns the written paths.
+pub fn dump_pass_wgsl_bundles(
+    scene: &SceneDSL,
+    asset_store: Option<&crate::asset_store::AssetStore>,
+    dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let bundles = build_all_pass_wgsl_bundles_from_scene_with_assets(scene, asset_store)?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| anyhow!("failed to create wgsl dump dir {}: {e}", dir.display()))?;
+    let mut written = Vec::new();
+    for (pass_id, bundle) in &bundles {
+        let stages = [
+            ("vertex", Some(&bundle.vertex)),
+            ("fragment", Some(&bundle.fragment)),
+            ("module", Some(&bundle.module)),
+            ("compute", bundle.compute.as_ref()),
+        ];
+        for (stage, source) in stages {
+            let Some(source) = source else {
+                continue;
+            };
+            let path = dir.join(format!("{pass_id}.{stage}.wgsl"));
+            std::fs::write(&path, source)
+                .map_err(|e| anyhow!("failed to write {}: {e}", path.display()))?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+fn build_layer_pass_wgsl_bundles(
+    prepared: &PreparedScene,
+    render_target_size: [f32; 2],
+    sampled_pass_ids: &HashSet<String>,
+    asset_store: Option<&crate::asset_store::AssetStore>,
+    layer_id: &str,
+) -> Result<Vec<(String, WgslShaderBundle)>> {
+    let nodes_by_id = &prepared.nodes_by_id;
+    let ids = &prepared.ids;
+    let node = find_node(nodes_by_id, layer_id)?;
+
     let mut out: Vec<(String, WgslShaderBundle)> = Vec::new();
-    for layer_id in composite_layers_in_draw_order {
-        let node = find_node(nodes_by_id, &layer_id)?;
-        match node.node_type.as_str() {
-            "RenderPass" => {
-                let render_geo_node_id =
-                    incoming_connection(&prepared.scene, &layer_id, "geometry")
-                        .map(|c| c.from.node_id.clone())
-                        .ok_or_else(|| anyhow!("RenderPass.geometry missing for {layer_id}"))?;
-
-                let (
-                    _geometry_buffer,
-                    _geo_w,
-                    _geo_h,
-                    _geo_x,
-                    _geo_y,
-                    instance_count,
-                    _base_m,
-                    _instance_mats,
-                    _translate_expr,
-                    _vertex_inline_stmts,
-                    _vertex_wgsl_decls,
-                    _vertex_graph_input_kinds,
-                    _vertex_uses_instance_index,
-                    _rect_dyn,
-                    _normals_bytes,
-                ) = resolve_geometry_for_render_pass(
-                    &prepared.scene,
-                    nodes_by_id,
-                    ids,
-                    &render_geo_node_id,
-                    render_target_size,
-                    None,
-                    asset_store,
-                )?;
+    match node.node_type.as_str() {
+        "RenderPass" => {
+            let render_geo_node_id = incoming_connection(&prepared.scene, layer_id, "geometry")
+                .map(|c| c.from.node_id.clone())
+                .ok_or_else(|| anyhow!("RenderPass.geometry missing for {layer_id}"))?;
+
+            let (
+                _geometry_buffer,
+                _geo_w,
+                _geo_h,
+                _geo_x,
+                _geo_y,
+                instance_count,
+                _base_m,
+                _instance_mats,
+                _translate_expr,
+                _vertex_inline_stmts,
+                _vertex_wgsl_decls,
+                _vertex_graph_input_kinds,
+                _vertex_uses_instance_index,
+                _rect_dyn,
+                _normals_bytes,
+            ) = resolve_geometry_for_render_pass(
+                &prepared.scene,
+                nodes_by_id,
+                ids,
+                &render_geo_node_id,
+                render_target_size,
+                None,
+                asset_store,
+            )?;
 
-                let is_instanced = instance_count > 1;
-
-                baked_data_parse.extend(crate::renderer::scene_prep::bake_data_parse_nodes(
-                    nodes_by_id,
-                    &layer_id,
-                    instance_count,
-                )?);
-
-                baked_data_parse.extend(crate::renderer::scene_prep::bake_data_parse_nodes(
-                    nodes_by_id,
-                    "__global",
-                    instance_count,
-                )?);
-
-                let meta = {
-                    let mut slot_by_output: std::collections::HashMap<
-                        (String, String, String),
-                        u32,
-                    > = std::collections::HashMap::new();
-                    let mut keys: Vec<(String, String, String)> = baked_data_parse
-                        .keys()
-                        .filter(|(pass_id, _, _)| pass_id == &layer_id)
-                        .cloned()
-                        .collect();
-                    keys.sort();
-                    for (i, k) in keys.iter().enumerate() {
-                        slot_by_output.insert(k.clone(), i as u32);
-                    }
-                    std::sync::Arc::new(crate::renderer::types::BakedDataParseMeta {
-                        pass_id: layer_id.clone(),
-                        outputs_per_instance: keys.len() as u32,
-                        slot_by_output,
-                    })
-                };
-
-                let (
-                    _geometry_buffer_2,
-                    _geo_w_2,
-                    _geo_h_2,
-                    _geo_x_2,
-                    _geo_y_2,
-                    _instance_count_2,
-                    _base_m_2,
-                    _instance_mats_2,
-                    translate_expr,
-                    vertex_inline_stmts,
-                    vertex_wgsl_decls,
-                    vertex_graph_input_kinds,
-                    vertex_uses_instance_index,
-                    rect_dyn_2,
-                    normals_bytes_2,
-                ) = resolve_geometry_for_render_pass(
-                    &prepared.scene,
-                    nodes_by_id,
-                    ids,
-                    &render_geo_node_id,
-                    render_target_size,
-                    Some(&MaterialCompileContext {
-                        baked_data_parse: Some(std::sync::Arc::new(baked_data_parse.clone())),
-                        baked_data_parse_meta: Some(meta.clone()),
-                        ..Default::default()
-                    }),
-                    asset_store,
-                )?;
+            let is_instanced = instance_count > 1;
 
-                let bundle = build_pass_wgsl_bundle_with_graph_binding(
-                    &prepared.scene,
-                    nodes_by_id,
-                    Some(std::sync::Arc::new(baked_data_parse.clone())),
-                    Some(meta),
-                    &layer_id,
-                    is_instanced,
-                    translate_expr.map(|e| e.expr),
-                    vertex_inline_stmts,
-                    vertex_wgsl_decls,
-                    vertex_uses_instance_index,
-                    rect_dyn_2,
-                    vertex_graph_input_kinds,
-                    None,
-                    false,                     // fullscreen_vertex_positioning
-                    normals_bytes_2.is_some(), // has_normals
-                )?;
+            baked_data_parse.extend(crate::renderer::scene_prep::bake_data_parse_nodes(
+                nodes_by_id,
+                layer_id,
+                instance_count,
+            )?);
 
-                out.push((layer_id, bundle));
-            }
-            "Downsample" => {
-                // Downsample pass WGSL uses a 2D kernel authored in a connected Kernel node.
-                let kernel_node_id = incoming_connection(&prepared.scene, &layer_id, "kernel")
-                    .map(|c| c.from.node_id.clone())
-                    .ok_or_else(|| anyhow!("Downsample.kernel missing for {layer_id}"))?;
-                let kernel_node = find_node(nodes_by_id, &kernel_node_id)?;
-                if kernel_node.node_type != "Kernel" {
-                    bail!(
-                        "Downsample.kernel must come from Kernel node, got {} for {}",
-                        kernel_node.node_type,
-                        kernel_node_id
-                    );
+            baked_data_parse.extend(crate::renderer::scene_prep::bake_data_parse_nodes(
+                nodes_by_id,
+                "__global",
+                instance_count,
+            )?);
+
+            let meta = {
+                let mut slot_by_output: std::collections::HashMap<(String, String, String), u32> =
+                    std::collections::HashMap::new();
+                let mut keys: Vec<(String, String, String)> = baked_data_parse
+                    .keys()
+                    .filter(|(pass_id, _, _)| pass_id == layer_id)
+                    .cloned()
+                    .collect();
+                keys.sort();
+                for (i, k) in keys.iter().enumerate() {
+                    slot_by_output.insert(k.clone(), i as u32);
                 }
-                let kernel_src = kernel_node
-                    .params
-                    .get("source")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let kernel: Kernel2D = parse_kernel_source_js_like(kernel_src.as_str())?;
-
-                let pass_id = format!("sys.downsample.{layer_id}.pass");
-                let bundle = build_downsample_pass_wgsl_bundle(&kernel)?;
-                out.push((pass_id, bundle));
+                let attribute_slots = crate::renderer::scene_prep::resolve_instance_attributes(
+                    node,
+                    layer_id,
+                    &slot_by_output,
+                )?;
+                std::sync::Arc::new(crate::renderer::types::BakedDataParseMeta {
+                    pass_id: layer_id.to_string(),
+                    outputs_per_instance: keys.len() as u32,
+                    slot_by_output,
+                    attribute_slots,
+                })
+            };
+
+            let (
+                _geometry_buffer_2,
+                _geo_w_2,
+                _geo_h_2,
+                _geo_x_2,
+                _geo_y_2,
+                _instance_count_2,
+                _base_m_2,
+                _instance_mats_2,
+                translate_expr,
+                vertex_inline_stmts,
+                vertex_wgsl_decls,
+                vertex_graph_input_kinds,
+                vertex_uses_instance_index,
+                rect_dyn_2,
+                normals_bytes_2,
+            ) = resolve_geometry_for_render_pass(
+                &prepared.scene,
+                nodes_by_id,
+                ids,
+                &render_geo_node_id,
+                render_target_size,
+                Some(&MaterialCompileContext {
+                    baked_data_parse: Some(std::sync::Arc::new(baked_data_parse.clone())),
+                    baked_data_parse_meta: Some(meta.clone()),
+                    ..Default::default()
+                }),
+                asset_store,
+            )?;
+
+            let bundle = build_pass_wgsl_bundle_with_graph_binding(
+                &prepared.scene,
+                nodes_by_id,
+                Some(std::sync::Arc::new(baked_data_parse.clone())),
+                Some(meta),
+                layer_id,
+                is_instanced,
+                translate_expr.map(|e| e.expr),
+                vertex_inline_stmts,
+                vertex_wgsl_decls,
+                vertex_uses_instance_index,
+                rect_dyn_2,
+                vertex_graph_input_kinds,
+                None,
+                false,                     // fullscreen_vertex_positioning
+                normals_bytes_2.is_some(), // has_normals
+                ChannelDebugMode::Off,
+            )?;
+
+            out.push((layer_id.to_string(), bundle));
+        }
+        "Downsample" => {
+            // Downsample pass WGSL uses a 2D kernel authored in a connected Kernel node.
+            let kernel_node_id = incoming_connection(&prepared.scene, layer_id, "kernel")
+                .map(|c| c.from.node_id.clone())
+                .ok_or_else(|| anyhow!("Downsample.kernel missing for {layer_id}"))?;
+            let kernel_node = find_node(nodes_by_id, &kernel_node_id)?;
+            if kernel_node.node_type != "Kernel" {
+                bail!(
+                    "Downsample.kernel must come from Kernel node, got {} for {}",
+                    kernel_node.node_type,
+                    kernel_node_id
+                );
             }
-            "Upsample" => {
-                let pass_id = format!("sys.upsample.{layer_id}.pass");
-                let bundle = build_upsample_bilinear_bundle();
-                out.push((pass_id, bundle));
+            let kernel_src = kernel_node
+                .params
+                .get("source")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let kernel: Kernel2D = parse_kernel_source_js_like(kernel_src.as_str())?;
+
+            let pass_id = format!("sys.downsample.{layer_id}.pass");
+            let bundle = build_downsample_pass_wgsl_bundle(&kernel)?;
+            out.push((pass_id, bundle));
+        }
+        "Upsample" => {
+            let pass_id = format!("sys.upsample.{layer_id}.pass");
+            let bundle = build_upsample_bilinear_bundle();
+            out.push((pass_id, bundle));
+        }
+        "GuassianBlurPass" => {
+            use crate::renderer::render_plan::pass_assemblers::gaussian_blur::{
+                BlurKernelPlan, BlurKernelType,
+            };
+            // SceneDSL `radius` is authored as an analytic 1D cutoff radius in full-res pixels;
+            // `BlurKernelPlan` maps it to the selected kernel and MIP factor.
+            let radius_px =
+                cpu_num_f32_min_0(&prepared.scene, &prepared.nodes_by_id, node, "radius", 0.0)?;
+            let kernel_plan =
+                BlurKernelPlan::new(BlurKernelType::from_params(&node.params)?, radius_px);
+            let downsample_factor = kernel_plan.downsample_factor;
+            let extend_enabled = node
+                .params
+                .get("extend")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let is_sampled_output = sampled_pass_ids.contains(layer_id);
+            let skip_factor1_downsample = downsample_factor == 1;
+            let skip_factor1_upsample =
+                downsample_factor == 1 && !extend_enabled && is_sampled_output;
+
+            let downsample_steps: Vec<u32> = if downsample_factor == 16 {
+                vec![8, 2]
+            } else if skip_factor1_downsample {
+                Vec::new()
+            } else {
+                vec![downsample_factor]
+            };
+
+            // 0) Source image expression pass (renders `image` input to an intermediate texture).
+            let src_bundle = build_blur_image_wgsl_bundle(&prepared.scene, nodes_by_id, layer_id)?;
+            out.push((format!("sys.blur.{layer_id}.src.pass"), src_bundle));
+
+            for step in &downsample_steps {
+                let bundle = kernel_plan.downsample_bundle(*step)?;
+                out.push((format!("sys.blur.{layer_id}.ds.{step}.pass"), bundle));
             }
-            "GuassianBlurPass" => {
-                // SceneDSL `radius` is authored as an analytic 1D cutoff radius in full-res pixels,
-                // not as Gaussian sigma.
-                //
-                // We map radius -> sigma using the same cutoff epsilon (~0.002) that our packed
-                // 27-wide Gaussian kernel effectively uses when pruning tiny weights
-                // (see `gaussian_kernel_8`).
-                //
-                // k = sqrt(2*ln(1/eps)) with eps=0.002 -> k≈3.525494, so sigma = radius/k.
-                let radius_px =
-                    cpu_num_f32_min_0(&prepared.scene, &prepared.nodes_by_id, node, "radius", 0.0)?;
-                let sigma = radius_px / 3.525_494;
-                let (mip_level, sigma_p) = gaussian_mip_level_and_sigma_p(sigma);
-                let downsample_factor: u32 = 1 << mip_level;
-                let (kernel, offset, num) = gaussian_kernel_8(sigma_p.max(1e-6));
-                let tap_count = num.clamp(1, 8);
-                let extend_enabled = node
-                    .params
-                    .get("extend")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-                let is_sampled_output = sampled_pass_ids.contains(&layer_id);
-                let skip_factor1_downsample = downsample_factor == 1;
-                let skip_factor1_upsample =
-                    downsample_factor == 1 && !extend_enabled && is_sampled_output;
-
-                let downsample_steps: Vec<u32> = if downsample_factor == 16 {
-                    vec![8, 2]
-                } else if skip_factor1_downsample {
-                    Vec::new()
-                } else {
-                    vec![downsample_factor]
-                };
-
-                // 0) Source image expression pass (renders `image` input to an intermediate texture).
-                let src_bundle =
-                    build_blur_image_wgsl_bundle(&prepared.scene, nodes_by_id, &layer_id)?;
-                out.push((format!("sys.blur.{layer_id}.src.pass"), src_bundle));
-
-                for step in &downsample_steps {
-                    let bundle = build_downsample_bundle(*step)?;
-                    out.push((format!("sys.blur.{layer_id}.ds.{step}.pass"), bundle));
-                }
 
+            let upsample_bundle = kernel_plan.upsample_bundle();
+            out.push((
+                format!("sys.blur.{layer_id}.h.ds{downsample_factor}.pass"),
+                kernel_plan.horizontal,
+            ));
+            out.push((
+                format!("sys.blur.{layer_id}.v.ds{downsample_factor}.pass"),
+                kernel_plan.vertical,
+            ));
+            if !skip_factor1_upsample {
                 out.push((
-                    format!("sys.blur.{layer_id}.h.ds{downsample_factor}.pass"),
-                    build_horizontal_blur_bundle_with_tap_count(kernel, offset, tap_count),
-                ));
-                out.push((
-                    format!("sys.blur.{layer_id}.v.ds{downsample_factor}.pass"),
-                    build_vertical_blur_bundle_with_tap_count(kernel, offset, tap_count),
+                    format!("sys.blur.{layer_id}.upsample_bilinear.ds{downsample_factor}.pass"),
+                    upsample_bundle,
                 ));
-                if !skip_factor1_upsample {
-                    out.push((
-                        format!("sys.blur.{layer_id}.upsample_bilinear.ds{downsample_factor}.pass"),
-                        build_upsample_bilinear_bundle(),
-                    ));
-                }
             }
-            "GradientBlur" => {
-                use crate::renderer::wgsl_gradient_blur::*;
-
-                // Resolve source dimensions to compute padding.
-                let src_resolution = {
-                    let mut res = prepared.resolution;
-                    if let Some(conn) = incoming_connection(&prepared.scene, &layer_id, "source") {
-                        if let Some(src_node) = nodes_by_id.get(&conn.from.node_id) {
-                            if src_node.node_type == "ImageTexture" {
-                                if let Some(dims) =
-                                    crate::renderer::shader_space::image_node_dimensions(
-                                        src_node, None,
-                                    )
-                                {
-                                    res = dims;
-                                }
+        }
+        "GradientBlur" => {
+            use crate::renderer::wgsl_gradient_blur::*;
+
+            // Resolve source dimensions to compute padding.
+            let src_resolution = {
+                let mut res = prepared.resolution;
+                if let Some(conn) = incoming_connection(&prepared.scene, layer_id, "source") {
+                    if let Some(src_node) = nodes_by_id.get(&conn.from.node_id) {
+                        if src_node.node_type == "ImageTexture" {
+                            if let Some(dims) =
+                                crate::renderer::shader_space::image_node_dimensions(src_node, None)
+                            {
+                                res = dims;
                             }
                         }
                     }
-                    res
-                };
-                let [padded_w, padded_h] =
-                    gradient_blur_padded_size(src_resolution[0], src_resolution[1]);
-                let src_w = src_resolution[0] as f32;
-                let src_h = src_resolution[1] as f32;
-                let pad_w = padded_w as f32;
-                let pad_h = padded_h as f32;
-                let pad_offset = [(pad_w - src_w) * 0.5, (pad_h - src_h) * 0.5];
-
-                // 0) Source pass
-                let src_bundle = build_gradient_blur_source_wgsl_bundle(
-                    &prepared.scene,
-                    nodes_by_id,
-                    &layer_id,
-                )?;
-                out.push((format!("sys.gb.{layer_id}.src.pass"), src_bundle));
-
-                // 1) Pad pass
-                let pad_bundle = build_gradient_blur_pad_wgsl_bundle(src_w, src_h, pad_w, pad_h);
-                out.push((format!("sys.gb.{layer_id}.pad.pass"), pad_bundle));
-
-                // 2) Mip chain (6 downsample passes)
-                let mip_pass_ids: Vec<String> = (0..GB_MIP_LEVELS)
-                    .map(|i| {
-                        if i == 0 {
-                            format!("sys.gb.{layer_id}.pad")
-                        } else {
-                            format!("sys.gb.{layer_id}.mip{i}")
-                        }
-                    })
-                    .collect();
-
-                for i in 1..GB_MIP_LEVELS {
-                    let ds_bundle =
-                        build_downsample_pass_wgsl_bundle(&gradient_blur_cross_kernel())?;
-                    out.push((format!("sys.gb.{layer_id}.mip{i}.pass"), ds_bundle));
                 }
+                res
+            };
+            let [padded_w, padded_h] =
+                gradient_blur_padded_size(src_resolution[0], src_resolution[1]);
+            let src_w = src_resolution[0] as f32;
+            let src_h = src_resolution[1] as f32;
+            let pad_w = padded_w as f32;
+            let pad_h = padded_h as f32;
+            let pad_offset = [(pad_w - src_w) * 0.5, (pad_h - src_h) * 0.5];
+
+            // 0) Source pass
+            let src_bundle =
+                build_gradient_blur_source_wgsl_bundle(&prepared.scene, nodes_by_id, layer_id)?;
+            out.push((format!("sys.gb.{layer_id}.src.pass"), src_bundle));
+
+            // 1) Pad pass
+            let pad_bundle = build_gradient_blur_pad_wgsl_bundle(src_w, src_h, pad_w, pad_h);
+            out.push((format!("sys.gb.{layer_id}.pad.pass"), pad_bundle));
+
+            // 2) Mip chain (6 downsample passes)
+            let mip_pass_ids: Vec<String> = (0..GB_MIP_LEVELS)
+                .map(|i| {
+                    if i == 0 {
+                        format!("sys.gb.{layer_id}.pad")
+                    } else {
+                        format!("sys.gb.{layer_id}.mip{i}")
+                    }
+                })
+                .collect();
 
-                // 3) Final composite pass
-                let composite_bundle = build_gradient_blur_composite_wgsl_bundle(
-                    &prepared.scene,
-                    nodes_by_id,
-                    &layer_id,
-                    &mip_pass_ids,
-                    [pad_w, pad_h],
-                    pad_offset,
-                )?;
-                out.push((format!("sys.gb.{layer_id}.final.pass"), composite_bundle));
+            for i in 1..GB_MIP_LEVELS {
+                let ds_bundle = build_downsample_pass_wgsl_bundle(&gradient_blur_cross_kernel())?;
+                out.push((format!("sys.gb.{layer_id}.mip{i}.pass"), ds_bundle));
             }
-            "BloomNode" => {
-                let parse_num = |key: &str, fallback: f32| {
-                    node.params
-                        .get(key)
-                        .and_then(|v| {
-                            v.as_f64()
-                                .map(|x| x as f32)
-                                .or_else(|| v.as_i64().map(|x| x as f32))
-                                .or_else(|| v.as_u64().map(|x| x as f32))
-                        })
-                        .unwrap_or(fallback)
-                };
-                let threshold = parse_num("threshold", 0.5).clamp(0.0, 1.0);
-                let smoothness = parse_num("smoothness", 0.5).clamp(0.0, 1.0);
-                let strength = parse_num("strength", 1.0).clamp(0.0, 1.0);
-                let saturation = parse_num("saturation", 1.0).clamp(0.0, 1.0);
-                let size = parse_num("size", 0.5).clamp(0.0, 1.0);
-                let smooth_width_px = (1.0 - smoothness) * 40.0;
-                let radius_px = size * 6.0;
-                let sigma = radius_px / 3.525_494;
-                let (_mip_level, sigma_p) = gaussian_mip_level_and_sigma_p(sigma);
-                let (kernel, offset, num) = gaussian_kernel_8(sigma_p.max(1e-6));
-                let tap_count = num.clamp(1, 8);
-
-                let tint = node
+
+            // 3) Final composite pass
+            let composite_bundle = build_gradient_blur_composite_wgsl_bundle(
+                &prepared.scene,
+                nodes_by_id,
+                layer_id,
+                &mip_pass_ids,
+                [pad_w, pad_h],
+                pad_offset,
+            )?;
+            out.push((format!("sys.gb.{layer_id}.final.pass"), composite_bundle));
+        }
+        "BloomNode" | "BloomPass" => {
+            let parse_num = |key: &str, fallback: f32| {
+                Ok(node
                     .params
-                    .get("tint")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| {
-                        [
-                            arr.first().and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
-                            arr.get(1).and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
-                            arr.get(2).and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
-                            arr.get(3).and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
-                        ]
+                    .get(key)
+                    .and_then(|v| {
+                        v.as_f64()
+                            .map(|x| x as f32)
+                            .or_else(|| v.as_i64().map(|x| x as f32))
+                            .or_else(|| v.as_u64().map(|x| x as f32))
                     })
-                    .unwrap_or([1.0, 1.0, 1.0, 1.0]);
+                    .unwrap_or(fallback))
+            };
+            let crate::renderer::render_plan::pass_assemblers::bloom::BloomSettings {
+                threshold,
+                smooth_width_px,
+                strength,
+                saturation,
+                radius_px,
+                ..
+            } = crate::renderer::render_plan::pass_assemblers::bloom::bloom_settings(
+                &node.node_type,
+                parse_num,
+            )?;
+            let sigma = radius_px / 3.525_494;
+            let (_mip_level, sigma_p) = gaussian_mip_level_and_sigma_p(sigma);
+            let (kernel, offset, num) = gaussian_kernel_8(sigma_p.max(1e-6));
+            let tap_count = num.clamp(1, 8);
+
+            let tint = node
+                .params
+                .get("tint")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    [
+                        arr.first().and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
+                        arr.get(1).and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
+                        arr.get(2).and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
+                        arr.get(3).and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
+                    ]
+                })
+                .unwrap_or([1.0, 1.0, 1.0, 1.0]);
+
+            out.push((
+                format!("sys.bloom.{layer_id}.extract.pass"),
+                build_bloom_extract_bundle(threshold, smooth_width_px, strength, saturation, tint),
+            ));
 
+            for level in 1..=BLOOM_MAX_MIPS {
                 out.push((
-                    format!("sys.bloom.{layer_id}.extract.pass"),
-                    build_bloom_extract_bundle(
-                        threshold,
-                        smooth_width_px,
-                        strength,
-                        saturation,
-                        tint,
-                    ),
+                    format!("sys.bloom.{layer_id}.mip{level}.down.pass"),
+                    build_downsample_bundle(2)?,
                 ));
+            }
 
-                for level in 1..=BLOOM_MAX_MIPS {
-                    out.push((
-                        format!("sys.bloom.{layer_id}.mip{level}.down.pass"),
-                        build_downsample_bundle(2)?,
-                    ));
-                }
-
-                for level in (1..=BLOOM_MAX_MIPS).rev() {
-                    out.push((
-                        format!("sys.bloom.{layer_id}.lvl{level}.h.pass"),
-                        build_horizontal_blur_bundle_with_tap_count(kernel, offset, tap_count),
-                    ));
-                    out.push((
-                        format!("sys.bloom.{layer_id}.lvl{level}.v.pass"),
-                        build_vertical_blur_bundle_with_tap_count(kernel, offset, tap_count),
-                    ));
+            for level in (1..=BLOOM_MAX_MIPS).rev() {
+                out.push((
+                    format!("sys.bloom.{layer_id}.lvl{level}.h.pass"),
+                    build_horizontal_blur_bundle_with_tap_count(kernel, offset, tap_count),
+                ));
+                out.push((
+                    format!("sys.bloom.{layer_id}.lvl{level}.v.pass"),
+                    build_vertical_blur_bundle_with_tap_count(kernel, offset, tap_count),
+                ));
+                out.push((
+                    format!("sys.bloom.{layer_id}.lvl{level}.up.pass"),
+                    build_upsample_bilinear_bundle(),
+                ));
+                if level > 1 {
                     out.push((
-                        format!("sys.bloom.{layer_id}.lvl{level}.up.pass"),
-                        build_upsample_bilinear_bundle(),
+                        format!("sys.bloom.{layer_id}.lvl{level}.add.pass"),
+                        build_bloom_additive_combine_bundle(),
                     ));
-                    if level > 1 {
-                        out.push((
-                            format!("sys.bloom.{layer_id}.lvl{level}.add.pass"),
-                            build_bloom_additive_combine_bundle(),
-                        ));
-                    }
                 }
+            }
+            out.push((
+                format!("sys.bloom.{layer_id}.out.pass"),
+                build_fullscreen_textured_bundle(
+                    "return textureSample(src_tex, src_samp, in.uv);".to_string(),
+                ),
+            ));
+        }
+        "IntelligentLight" => {
+            let wgsl = crate::renderer::render_plan::pass_assemblers::intelligent_light::build_intelligent_light_wgsl(node);
+            let bundle = build_static_vertex_fragment_bundle(wgsl);
+            out.push((format!("sys.ilight.{layer_id}.pass"), bundle));
+        }
+        "MeshGradient" => {
+            let wgsl = crate::renderer::render_plan::pass_assemblers::mesh_gradient::build_mesh_gradient_wgsl();
+            let bundle = build_static_vertex_fragment_bundle(wgsl);
+            out.push((format!("sys.mesh_gradient.{layer_id}.pass"), bundle));
+        }
+        "ComputePass" => {
+            use crate::renderer::render_plan::pass_assemblers::compute;
+            let bundle = compute::build_compute_pass_wgsl_bundle(
+                &prepared.scene,
+                nodes_by_id,
+                layer_id,
+                compute::parse_compute_storage_format(&node.params)?,
+                compute::parse_compute_workgroup_size(&node.params)?,
+                None,
+            )?;
+            out.push((format!("sys.compute.{layer_id}.pass"), bundle));
+        }
+        "FeedbackPass" => {
+            use crate::renderer::render_plan::pass_assemblers::feedback;
+            let decay = crate::renderer::utils::cpu_num_f32(
+                &prepared.scene,
+                nodes_by_id,
+                node,
+                "decay",
+                feedback::DEFAULT_DECAY,
+            )?
+            .max(0.0);
+            let wgsl = feedback::build_feedback_wgsl(
+                node,
+                decay,
+                incoming_connection(&prepared.scene, layer_id, "pass").is_some(),
+            );
+            let bundle = build_static_vertex_fragment_bundle(wgsl);
+            out.push((format!("sys.feedback.{layer_id}.pass"), bundle));
+        }
+        "ConvolutionPass" => {
+            use crate::renderer::render_plan::pass_assemblers::convolution;
+            let kernel = convolution::convolution_kernel(&prepared.scene, nodes_by_id, layer_id)?;
+            let (scale, bias) = convolution::convolution_scale_and_bias(
+                &prepared.scene,
+                nodes_by_id,
+                node,
+                &kernel,
+            )?;
+            for (stage, wgsl) in convolution::build_convolution_stages(&kernel, scale, bias) {
                 out.push((
-                    format!("sys.bloom.{layer_id}.out.pass"),
-                    build_fullscreen_textured_bundle(
-                        "return textureSample(src_tex, src_samp, in.uv);".to_string(),
-                    ),
+                    format!("sys.conv.{layer_id}.{stage}.pass"),
+                    build_static_vertex_fragment_bundle(wgsl),
                 ));
             }
-            "IntelligentLight" => {
-                let wgsl = crate::renderer::render_plan::pass_assemblers::intelligent_light::build_intelligent_light_wgsl(node);
-                let bundle = build_static_vertex_fragment_bundle(wgsl);
-                out.push((format!("sys.ilight.{layer_id}.pass"), bundle));
+        }
+        "DepthOfFieldPass" => {
+            use crate::renderer::render_plan::pass_assemblers::dof;
+            let settings = dof::dof_settings(&prepared.scene, nodes_by_id, node)?;
+            let has_focus_texture =
+                incoming_connection(&prepared.scene, layer_id, "focus").is_some();
+            for (stage, wgsl) in [
+                (
+                    "coc",
+                    dof::build_dof_coc_wgsl(node, &settings, has_focus_texture),
+                ),
+                ("gather", dof::build_dof_gather_wgsl(&settings)),
+                ("composite", dof::build_dof_composite_wgsl(&settings)),
+            ] {
+                out.push((
+                    format!("sys.dof.{layer_id}.{stage}.pass"),
+                    build_static_vertex_fragment_bundle(wgsl),
+                ));
             }
-            "MeshGradient" => {
-                let wgsl = crate::renderer::render_plan::pass_assemblers::mesh_gradient::build_mesh_gradient_wgsl();
-                let bundle = build_static_vertex_fragment_bundle(wgsl);
-                out.push((format!("sys.mesh_gradient.{layer_id}.pass"), bundle));
+        }
+        "EdgeDetect" => {
+            use crate::renderer::render_plan::pass_assemblers::edge_detect;
+            let settings = edge_detect::edge_detect_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.edge.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(edge_detect::build_edge_detect_wgsl(&settings)),
+            ));
+        }
+        "ChromaticAberration" => {
+            use crate::renderer::render_plan::pass_assemblers::finishing;
+            let settings =
+                finishing::chromatic_aberration_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.chroma.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(finishing::build_chromatic_aberration_wgsl(
+                    &settings,
+                )),
+            ));
+        }
+        "Vignette" => {
+            use crate::renderer::render_plan::pass_assemblers::finishing;
+            let settings = finishing::vignette_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.vignette.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(finishing::build_vignette_wgsl(&settings)),
+            ));
+        }
+        "FilmGrain" => {
+            use crate::renderer::render_plan::pass_assemblers::finishing;
+            let settings = finishing::film_grain_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.grain.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(finishing::build_film_grain_wgsl(&settings)),
+            ));
+        }
+        "LensDistortion" => {
+            use crate::renderer::render_plan::pass_assemblers::lens_distortion;
+            let settings =
+                lens_distortion::lens_distortion_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.lens.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(lens_distortion::build_lens_distortion_wgsl(
+                    &settings,
+                )),
+            ));
+        }
+        "LUTPass" => {
+            use crate::renderer::render_plan::pass_assemblers::lut;
+            let settings = lut::lut_settings(&prepared.scene, nodes_by_id, node)?;
+            let cube = lut::load_lut_asset(asset_store, node)?;
+            out.push((
+                format!("sys.lut.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(lut::build_lut_wgsl(&cube, &settings)),
+            ));
+        }
+        "Pixelate" => {
+            use crate::renderer::render_plan::pass_assemblers::stylize;
+            let settings = stylize::pixelate_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.pixelate.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(stylize::build_pixelate_wgsl(&settings)),
+            ));
+        }
+        "Posterize" => {
+            use crate::renderer::render_plan::pass_assemblers::stylize;
+            let settings = stylize::posterize_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.posterize.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(stylize::build_posterize_wgsl(&settings)),
+            ));
+        }
+        "Dither" => {
+            use crate::renderer::render_plan::pass_assemblers::stylize;
+            let settings = stylize::dither_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.dither.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(stylize::build_dither_wgsl(&settings)),
+            ));
+        }
+        "DropShadow" => {
+            use crate::renderer::render_plan::pass_assemblers::drop_shadow;
+            let settings = drop_shadow::drop_shadow_settings(&prepared.scene, nodes_by_id, node)?;
+            for (stage, wgsl) in [
+                (
+                    "h",
+                    drop_shadow::build_drop_shadow_blur_wgsl(&settings, true, settings.offset_px),
+                ),
+                (
+                    "v",
+                    drop_shadow::build_drop_shadow_blur_wgsl(&settings, false, [0.0, 0.0]),
+                ),
+                (
+                    "composite",
+                    drop_shadow::build_drop_shadow_composite_wgsl(&settings),
+                ),
+            ] {
+                out.push((
+                    format!("sys.shadow.{layer_id}.{stage}.pass"),
+                    build_static_vertex_fragment_bundle(wgsl),
+                ));
             }
-            other => bail!(
-                "Composite layer must be RenderPass, BloomNode, Downsample, Upsample, GuassianBlurPass, GradientBlur, IntelligentLight, or MeshGradient, got {other} for {layer_id}"
-            ),
         }
+        "Displace" => {
+            use crate::renderer::render_plan::pass_assemblers::displace;
+            let settings = displace::displace_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.displace.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(displace::build_displace_wgsl(&settings)),
+            ));
+        }
+        "AntiAliasPass" => {
+            use crate::renderer::render_plan::pass_assemblers::anti_alias;
+            let settings = anti_alias::anti_alias_settings(&prepared.scene, nodes_by_id, node)?;
+            out.push((
+                format!("sys.aa.{layer_id}.pass"),
+                build_static_vertex_fragment_bundle(anti_alias::build_anti_alias_wgsl(&settings)),
+            ));
+        }
+        other => bail!(
+            "Composite layer must be RenderPass, BloomNode, BloomPass, Downsample, Upsample, GuassianBlurPass, GradientBlur, IntelligentLight, MeshGradient, ComputePass, FeedbackPass, DepthOfFieldPass, ConvolutionPass, EdgeDetect, ChromaticAberration, Vignette, FilmGrain, LensDistortion, LUTPass, Pixelate, Posterize, Dither, DropShadow, Displace, or AntiAliasPass, got {other} for {layer_id}"
+        ),
     }
 
     Ok(out)
@@ -1810,6 +2134,84 @@ pub fn build_vertical_blur_bundle_with_tap_count(
     build_fullscreen_textured_bundle(body)
 }
 
+/// Build a box blur shader bundle averaging `2 * radius_px + 1` texels along one axis.
+pub fn build_box_blur_bundle(radius_px: u32, horizontal: bool) -> WgslShaderBundle {
+    let axis = if horizontal {
+        "vec2f(f32(i), 0.0)"
+    } else {
+        "vec2f(0.0, f32(i))"
+    };
+    let body = format!(
+        r#"
+ let original = vec2f(textureDimensions(src_tex));
+ let xy = in.uv * original;
+ let radius: i32 = {radius_px};
+ var color = vec4f(0.0);
+ for (var i: i32 = -radius; i <= radius; i = i + 1) {{
+     let uv = (xy + {axis}) / original;
+     color = color + textureSampleLevel(src_tex, src_samp, uv, 0.0);
+ }}
+ return color / f32(2 * radius + 1);
+"#
+    );
+    build_fullscreen_textured_bundle(body)
+}
+
+/// Build one Kawase blur iteration: four diagonal bilinear taps `offset_texels` away.
+pub fn build_kawase_blur_bundle(offset_texels: f32) -> WgslShaderBundle {
+    let body = format!(
+        r#"
+ let texel = 1.0 / vec2f(textureDimensions(src_tex));
+ let d = texel * f32({offset});
+ var color = textureSampleLevel(src_tex, src_samp, in.uv + vec2f(d.x, d.y), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(-d.x, d.y), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(d.x, -d.y), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(-d.x, -d.y), 0.0);
+ return color * 0.25;
+"#,
+        offset = fmt_f32(offset_texels.max(0.0)),
+    );
+    build_fullscreen_textured_bundle(body)
+}
+
+/// Build a dual-Kawase downsample bundle: the center plus four diagonal taps half a
+/// destination texel away, so cost stays at five taps regardless of `factor`.
+pub fn build_kawase_downsample_bundle(factor: u32) -> Result<WgslShaderBundle> {
+    if !matches!(factor, 2 | 4 | 8) {
+        bail!("GuassianBlurPass: unsupported Kawase downsample factor {factor}");
+    }
+    let body = r#"
+ let d = 0.5 / params.target_size;
+ var color = textureSampleLevel(src_tex, src_samp, in.uv, 0.0) * 4.0;
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(d.x, d.y), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(-d.x, d.y), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(d.x, -d.y), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(-d.x, -d.y), 0.0);
+ return color * 0.125;
+"#
+    .to_string();
+    Ok(build_fullscreen_textured_bundle(body))
+}
+
+/// Build a dual-Kawase upsample bundle (8-tap tent around the source texel).
+pub fn build_kawase_upsample_bundle() -> WgslShaderBundle {
+    let body = r#"
+ let t = 1.0 / vec2f(textureDimensions(src_tex));
+ var color = vec4f(0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(-t.x, 0.0), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(t.x, 0.0), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(0.0, -t.y), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + vec2f(0.0, t.y), 0.0);
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + t * vec2f(-0.5, 0.5), 0.0) * 2.0;
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + t * vec2f(0.5, 0.5), 0.0) * 2.0;
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + t * vec2f(-0.5, -0.5), 0.0) * 2.0;
+ color = color + textureSampleLevel(src_tex, src_samp, in.uv + t * vec2f(0.5, -0.5), 0.0) * 2.0;
+ return color / 12.0;
+ "#
+    .to_string();
+    build_fullscreen_textured_bundle(body)
+}
+
 /// Build a bilinear upsample shader bundle.
 pub fn build_upsample_bilinear_bundle() -> WgslShaderBundle {
     let body = r#"
@@ -1941,8 +2343,9 @@ fn fs_main(in: VSOut) -> @location(0) vec4f {
 #[cfg(test)]
 mod tests {
     use super::{
-        ERROR_SHADER_WGSL, build_horizontal_blur_bundle,
-        build_horizontal_blur_bundle_with_tap_count, build_vertical_blur_bundle,
+        ERROR_SHADER_WGSL, build_box_blur_bundle, build_horizontal_blur_bundle,
+        build_horizontal_blur_bundle_with_tap_count, build_kawase_blur_bundle,
+        build_kawase_downsample_bundle, build_kawase_upsample_bundle, build_vertical_blur_bundle,
         build_vertical_blur_bundle_with_tap_count,
     };
     use crate::renderer::validation::validate_wgsl;
@@ -1989,6 +2392,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn box_and_kawase_blur_bundles_validate() {
+        let mut modules = vec![
+            build_box_blur_bundle(0, true).module,
+            build_box_blur_bundle(12, false).module,
+            build_kawase_blur_bundle(1.5).module,
+            build_kawase_upsample_bundle().module,
+        ];
+        for factor in [2, 4, 8] {
+            modules.push(build_kawase_downsample_bundle(factor).unwrap().module);
+        }
+        for module in modules {
+            if let Err(e) = validate_wgsl(&module) {
+                panic!("blur WGSL must validate: {e:#}\n{module}");
+            }
+        }
+        assert!(
+            build_box_blur_bundle(12, true)
+                .module
+                .contains("let radius: i32 = 12;")
+        );
+        assert!(build_kawase_downsample_bundle(16).is_err());
+    }
+
     #[test]
     fn error_shader_validates_and_is_not_legacy_magenta() {
         if let Err(e) = validate_wgsl(ERROR_SHADER_WGSL) {
@@ -11,7 +11,7 @@ pub fn build_bloom_extract_bundle(
 ) -> WgslShaderBundle {
     let threshold = threshold.clamp(0.0, 1.0);
     let smooth_width_px = smooth_width_px.max(0.0);
-    let strength = strength.clamp(0.0, 1.0);
+    let strength = strength.max(0.0);
     let saturation = saturation.clamp(0.0, 1.0);
     let tint = [
         tint[0].clamp(0.0, 1.0),
@@ -69,11 +69,28 @@ pub fn build_gradient_blur_source_wgsl_bundle_with_graph_binding(
             node.node_type.as_str(),
             "RenderPass"
                 | "BloomNode"
+                | "BloomPass"
                 | "GuassianBlurPass"
                 | "Downsample"
                 | "Upsample"
                 | "GradientBlur"
                 | "MeshGradient"
+                | "ComputePass"
+                | "FeedbackPass"
+                | "DepthOfFieldPass"
+                | "ConvolutionPass"
+                | "EdgeDetect"
+                | "ChromaticAberration"
+                | "Vignette"
+                | "FilmGrain"
+                | "LensDistortion"
+                | "LUTPass"
+                | "Pixelate"
+                | "Posterize"
+                | "Dither"
+                | "DropShadow"
+                | "Displace"
+                | "AntiAliasPass"
                 | "Composite"
         )
     });
@@ -115,10 +132,11 @@ pub fn build_gradient_blur_source_wgsl_bundle_with_graph_binding(
     common.push_str(&material_ctx.wgsl_decls());
 
     let vertex = FULLSCREEN_VERTEX;
+    let entry_params = material_ctx.fragment_entry_params();
     let fragment = format!(
         r#"
 @fragment
-fn fs_main(in: VSOut) -> @location(0) vec4f {{
+fn fs_main({entry_params}) -> @location(0) vec4f {{
 {fragment_body}
 }}
 "#
@@ -249,11 +267,28 @@ pub fn build_gradient_blur_composite_wgsl_bundle_with_graph_binding(
         mask_upstream.node_type.as_str(),
         "RenderPass"
             | "BloomNode"
+            | "BloomPass"
             | "GuassianBlurPass"
             | "Downsample"
             | "Upsample"
             | "GradientBlur"
             | "MeshGradient"
+            | "ComputePass"
+            | "FeedbackPass"
+            | "DepthOfFieldPass"
+            | "ConvolutionPass"
+            | "EdgeDetect"
+            | "ChromaticAberration"
+            | "Vignette"
+            | "FilmGrain"
+            | "LensDistortion"
+            | "LUTPass"
+            | "Pixelate"
+            | "Posterize"
+            | "Dither"
+            | "DropShadow"
+            | "Displace"
+            | "AntiAliasPass"
             | "Composite"
     ) {
         bail!(
@@ -462,6 +497,10 @@ struct Params {
     color: vec4f,
     camera: mat4x4f,
     camera_position: vec4f,
+
+    // Frame clock shared by all passes (Time node).
+    delta_time: f32,
+    frame: u32,
 };
 
 @group(0) @binding(0)
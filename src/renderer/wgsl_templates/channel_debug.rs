@@ -0,0 +1,132 @@
+//! Global "channel debug" fragment entries for draw passes.
+//!
+//! The material body is kept as a helper function and still evaluated so the
+//! pass keeps every binding it declares; only the returned colour changes.
+
+/// Coordinate channel written instead of the material output of every draw pass.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ChannelDebugMode {
+    #[default]
+    Off,
+    /// `fract(in.uv)` in red/green.
+    Uv,
+    /// GeoFragcoord normalized by geometry size, with a 32px grid in blue.
+    GeoFragcoord,
+    /// Screen-space derivatives of GeoFragcoord; 1px per pixel maps to 0.5.
+    Derivatives,
+    /// Vertex normals when present, otherwise the face normal from derivatives.
+    Normals,
+}
+
+impl ChannelDebugMode {
+    pub const ALL: [Self; 5] = [
+        Self::Off,
+        Self::Uv,
+        Self::GeoFragcoord,
+        Self::Derivatives,
+        Self::Normals,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Uv => "UV",
+            Self::GeoFragcoord => "GeoFragcoord",
+            Self::Derivatives => "Derivatives",
+            Self::Normals => "Normals",
+        }
+    }
+}
+
+/// Builds the `fs_main` entry for `mode`, or `None` when channel debug is off.
+pub(crate) fn build_channel_debug_fragment_entry(
+    mode: ChannelDebugMode,
+    fragment_body: &str,
+    has_normals: bool,
+    uses_front_facing: bool,
+) -> Option<String> {
+    let debug_color = match mode {
+        ChannelDebugMode::Off => return None,
+        ChannelDebugMode::Uv => "vec4f(fract(in.uv), 0.0, 1.0)".to_string(),
+        ChannelDebugMode::GeoFragcoord => "vec4f(\n        clamp(in.local_px.xy / max(in.geo_size_px, vec2f(1.0)), vec2f(0.0), vec2f(1.0)),\n        select(0.0, 1.0, any(fract(in.local_px.xy / 32.0) > vec2f(0.94))),\n        1.0,\n    )".to_string(),
+        ChannelDebugMode::Derivatives => {
+            "vec4f(clamp(fwidth(in.local_px.xy) * 0.5, vec2f(0.0), vec2f(1.0)), 0.0, 1.0)"
+                .to_string()
+        }
+        ChannelDebugMode::Normals => {
+            let normal = if has_normals {
+                "normalize(in.normal)"
+            } else {
+                "normalize(cross(dpdx(in.local_px), dpdy(in.local_px)))"
+            };
+            format!("vec4f({normal} * 0.5 + vec3f(0.5), 1.0)")
+        }
+    };
+
+    // The material helper sees the same builtins as a regular `fs_main`.
+    let (material_params, main_params, call_args) = if uses_front_facing {
+        (
+            "in: VSOut, front_facing: bool",
+            "in: VSOut, @builtin(front_facing) front_facing: bool",
+            "in, front_facing",
+        )
+    } else {
+        ("in: VSOut", "in: VSOut", "in")
+    };
+
+    // Derivatives are taken before the material helper runs, which may discard.
+    Some(format!(
+        r#"
+fn fs_material({material_params}) -> vec4f {{
+{fragment_body}
+}}
+
+@fragment
+fn fs_main({main_params}) -> @location(0) vec4f {{
+    let debug_color = {debug_color};
+    _ = fs_material({call_args});
+    return debug_color;
+}}
+"#
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelDebugMode, build_channel_debug_fragment_entry};
+
+    const COMMON: &str = "struct VSOut {\n    @builtin(position) position: vec4f,\n    @location(0) uv: vec2f,\n    @location(2) local_px: vec3f,\n    @location(3) geo_size_px: vec2f,\n};\n";
+
+    #[test]
+    fn off_keeps_material_entry() {
+        assert!(
+            build_channel_debug_fragment_entry(
+                ChannelDebugMode::Off,
+                "return vec4f(1.0);",
+                false,
+                false
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn every_mode_produces_valid_wgsl() {
+        for mode in ChannelDebugMode::ALL {
+            for uses_front_facing in [false, true] {
+                let body = if uses_front_facing {
+                    "    return vec4f(select(0.0, 1.0, front_facing));"
+                } else {
+                    "    return vec4f(1.0);"
+                };
+                let Some(entry) =
+                    build_channel_debug_fragment_entry(mode, body, false, uses_front_facing)
+                else {
+                    continue;
+                };
+                crate::renderer::validate_wgsl(&format!("{COMMON}{entry}"))
+                    .unwrap_or_else(|e| panic!("{mode:?}: {e:#}"));
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Mip atlas for ImageTexture `mipmapFilter`.
+//!
+//! Fiber textures carry a single mip level, so an ImageTexture that asks for
+//! mip filtering gets its chain packed into one 2D "atlas" texture instead:
+//! a prepass per level box-filters the previous level into place, and the
+//! material samples the atlas through [`mip_atlas_sampling_wgsl`], which picks
+//! the LOD from screen-space derivatives and applies the address mode itself.
+
+/// Atlas layout helpers shared by the generation prepasses and the sampler.
+pub const MIP_ATLAS_LAYOUT_WGSL: &str = r#"
+// Mip atlas layout: level 0 (base W x H) at the origin, levels 1.. stacked
+// top-down in a column at x = W. The atlas is (W + ceil(W / 2)) x H, so the
+// base size is recoverable from the atlas size alone.
+fn nf_mip_atlas_base_size(t: texture_2d<f32>) -> vec2u {
+    let dims = textureDimensions(t);
+    return vec2u(dims.x * 2u / 3u, dims.y);
+}
+
+// Levels stop at the first 1-texel dimension so every level fits the column.
+fn nf_mip_atlas_level_count(base: vec2u) -> u32 {
+    return firstLeadingBit(max(min(base.x, base.y), 1u)) + 1u;
+}
+
+// Texel rect of `level` as (origin, size).
+fn nf_mip_atlas_level_rect(base: vec2u, level: u32) -> vec4u {
+    if (level == 0u) {
+        return vec4u(0u, 0u, base);
+    }
+    var y = 0u;
+    for (var j = 1u; j < level; j = j + 1u) {
+        y = y + (base.y >> j);
+    }
+    return vec4u(base.x, y, base.x >> level, base.y >> level);
+}
+"#;
+
+/// Stage-independent sampling helpers; requires [`MIP_ATLAS_LAYOUT_WGSL`].
+const MIP_ATLAS_SAMPLE_WGSL: &str = r#"
+// `mode`: 0 clamp-to-edge, 1 repeat, 2 mirror-repeat.
+fn nf_mip_atlas_address(uv: vec2f, mode: u32) -> vec2f {
+    if (mode == 1u) {
+        return fract(uv);
+    }
+    if (mode == 2u) {
+        return 1.0 - abs(1.0 - (uv - 2.0 * floor(uv * 0.5)));
+    }
+    return clamp(uv, vec2f(0.0), vec2f(1.0));
+}
+
+fn nf_mip_atlas_sample_level(t: texture_2d<f32>, s: sampler, uv: vec2f, base: vec2u, level: u32) -> vec4f {
+    let rect = vec4f(nf_mip_atlas_level_rect(base, level));
+    // Keep the filter footprint inside the level so neighbours never bleed in.
+    let px = clamp(rect.xy + uv * rect.zw, rect.xy + vec2f(0.5), rect.xy + rect.zw - vec2f(0.5));
+    return textureSampleLevel(t, s, px / vec2f(textureDimensions(t)), 0.0);
+}
+
+fn nf_mip_atlas_sample_lod(t: texture_2d<f32>, s: sampler, uv: vec2f, lod: f32, mode: u32, trilinear: bool) -> vec4f {
+    let base = nf_mip_atlas_base_size(t);
+    let max_level = nf_mip_atlas_level_count(base) - 1u;
+    let level_lod = clamp(lod, 0.0, f32(max_level));
+    let wrapped = nf_mip_atlas_address(uv, mode);
+    if (!trilinear) {
+        return nf_mip_atlas_sample_level(t, s, wrapped, base, u32(round(level_lod)));
+    }
+    let lo = u32(floor(level_lod));
+    let hi = min(lo + 1u, max_level);
+    let a = nf_mip_atlas_sample_level(t, s, wrapped, base, lo);
+    let b = nf_mip_atlas_sample_level(t, s, wrapped, base, hi);
+    return mix(a, b, level_lod - f32(lo));
+}
+"#;
+
+const MIP_ATLAS_SAMPLE_FRAGMENT_WGSL: &str = r#"
+// LOD from the screen-space footprint of the unwrapped uv, so repeat seams do
+// not spike it.
+fn nf_mip_atlas_sample(t: texture_2d<f32>, s: sampler, uv: vec2f, mode: u32, trilinear: bool) -> vec4f {
+    let texel = uv * vec2f(nf_mip_atlas_base_size(t));
+    let dx = dpdx(texel);
+    let dy = dpdy(texel);
+    let lod = 0.5 * log2(max(max(dot(dx, dx), dot(dy, dy)), 1e-8));
+    return nf_mip_atlas_sample_lod(t, s, uv, lod, mode, trilinear);
+}
+"#;
+
+const MIP_ATLAS_SAMPLE_COMPUTE_WGSL: &str = r#"
+// Compute stages have no derivatives; they read the base level.
+fn nf_mip_atlas_sample(t: texture_2d<f32>, s: sampler, uv: vec2f, mode: u32, trilinear: bool) -> vec4f {
+    return nf_mip_atlas_sample_lod(t, s, uv, 0.0, mode, trilinear);
+}
+"#;
+
+/// Helpers behind the `nf_mip_atlas_sample(t, s, uv, mode, trilinear)` call emitted for a
+/// mip-filtered ImageTexture. `mode` is 0 clamp-to-edge, 1 repeat, 2 mirror-repeat.
+pub fn mip_atlas_sampling_wgsl(compute: bool) -> String {
+    let entry = if compute {
+        MIP_ATLAS_SAMPLE_COMPUTE_WGSL
+    } else {
+        MIP_ATLAS_SAMPLE_FRAGMENT_WGSL
+    };
+    format!("{MIP_ATLAS_LAYOUT_WGSL}{MIP_ATLAS_SAMPLE_WGSL}{entry}")
+}
+
+/// Number of levels packed for a `width` x `height` image.
+pub fn mip_atlas_level_count(width: u32, height: u32) -> u32 {
+    32 - width.min(height).max(1).leading_zeros()
+}
+
+/// Atlas texture size for a `width` x `height` image.
+pub fn mip_atlas_size(width: u32, height: u32) -> [u32; 2] {
+    [width + width.div_ceil(2), height]
+}
+
+/// Prepass that writes atlas `level` (0 seeds the base) and copies every other
+/// texel from its source.
+///
+/// The source is the base image for level 0 and the previous atlas otherwise;
+/// the pass binds the nearest sampler, so every read is an exact texel.
+pub fn build_image_mip_atlas_wgsl(level: u32) -> String {
+    format!(
+        r#"{layout}
+struct Params {{
+    target_size: vec2f,
+    geo_size: vec2f,
+    center: vec2f,
+
+    geo_translate: vec2f,
+    geo_scale: vec2f,
+
+    time: f32,
+    _pad0: f32,
+
+    color: vec4f,
+    camera: mat4x4f,
+    camera_position: vec4f,
+}};
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+
+struct VSOut {{
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+}};
+
+@group(1) @binding(0)
+var src_tex: texture_2d<f32>;
+
+@group(1) @binding(1)
+var src_samp: sampler;
+
+const LEVEL: u32 = {level}u;
+
+@vertex
+fn vs_main(
+    @location(0) position: vec3f,
+    @location(1) uv: vec2f,
+) -> VSOut {{
+    var out: VSOut;
+    out.uv = uv;
+    let p_px = params.center + position.xy;
+    out.position = params.camera * vec4f(p_px, position.z, 1.0);
+    return out;
+}}
+
+// Exact texel read through the pass's nearest sampler.
+fn src_texel(p: vec2u) -> vec4f {{
+    let dims = vec2f(textureDimensions(src_tex));
+    return textureSampleLevel(src_tex, src_samp, (vec2f(p) + vec2f(0.5)) / dims, 0.0);
+}}
+
+@fragment
+fn fs_main(in: VSOut) -> @location(0) vec4f {{
+    let p = vec2u(in.position.xy);
+    let base = vec2u(u32(params.target_size.x) * 2u / 3u, u32(params.target_size.y));
+    if (LEVEL == 0u) {{
+        // Seed the atlas from the base image.
+        if (all(p < base)) {{
+            return src_texel(p);
+        }}
+        return vec4f(0.0);
+    }}
+    let rect = nf_mip_atlas_level_rect(base, LEVEL);
+    if (any(p < rect.xy) || any(p >= rect.xy + rect.zw)) {{
+        return src_texel(p);
+    }}
+    // 2x2 box filter of the previous level.
+    let parent = nf_mip_atlas_level_rect(base, LEVEL - 1u);
+    let q = (p - rect.xy) * 2u;
+    let last = parent.zw - vec2u(1u);
+    let a = src_texel(parent.xy + min(q, last));
+    let b = src_texel(parent.xy + min(q + vec2u(1u, 0u), last));
+    let c = src_texel(parent.xy + min(q + vec2u(0u, 1u), last));
+    let d = src_texel(parent.xy + min(q + vec2u(1u, 1u), last));
+    return 0.25 * (a + b + c + d);
+}}
+"#,
+        layout = MIP_ATLAS_LAYOUT_WGSL,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atlas_size_recovers_base_and_fits_every_level() {
+        for (w, h) in [(1, 1), (2, 7), (5, 3), (64, 64), (640, 360), (1023, 17)] {
+            let [aw, ah] = mip_atlas_size(w, h);
+            // Mirrors `nf_mip_atlas_base_size`.
+            assert_eq!((aw * 2 / 3, ah), (w, h), "{w}x{h}");
+
+            let levels = mip_atlas_level_count(w, h);
+            let column_height: u32 = (1..levels).map(|level| h >> level).sum();
+            assert!(column_height <= h, "{w}x{h}");
+            assert!((1..levels).all(|level| w >> level >= 1 && h >> level >= 1));
+            assert!(w >> 1 <= aw - w, "{w}x{h}");
+        }
+        assert_eq!(mip_atlas_level_count(640, 360), 9);
+    }
+
+    #[test]
+    fn atlas_shaders_validate() {
+        for level in [0, 1, 4] {
+            crate::renderer::validate_wgsl(&build_image_mip_atlas_wgsl(level)).unwrap();
+        }
+        let fragment = format!(
+            "{}\n\
+             @group(1) @binding(0) var tex: texture_2d<f32>;\n\
+             @group(1) @binding(1) var samp: sampler;\n\
+             @fragment fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {{\n\
+                 return nf_mip_atlas_sample(tex, samp, uv, 2u, true);\n\
+             }}\n",
+            mip_atlas_sampling_wgsl(false)
+        );
+        crate::renderer::validate_wgsl(&fragment).unwrap();
+        let compute = format!(
+            "{}\n\
+             @group(1) @binding(0) var tex: texture_2d<f32>;\n\
+             @group(1) @binding(1) var samp: sampler;\n\
+             @group(0) @binding(1) var out_tex: texture_storage_2d<rgba8unorm, write>;\n\
+             @compute @workgroup_size(8, 8) fn cs_main(@builtin(global_invocation_id) id: vec3u) {{\n\
+                 textureStore(out_tex, id.xy, nf_mip_atlas_sample(tex, samp, vec2f(id.xy) / 64.0, 1u, false));\n\
+             }}\n",
+            mip_atlas_sampling_wgsl(true)
+        );
+        crate::renderer::validate_wgsl(&compute).unwrap();
+    }
+}
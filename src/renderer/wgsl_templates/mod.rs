@@ -1,8 +1,12 @@
+pub mod channel_debug;
 pub mod color_ops;
 pub mod fullscreen;
+pub mod mip_atlas;
 pub mod present;
 
+pub use channel_debug::ChannelDebugMode;
 pub use color_ops::build_image_premultiply_wgsl;
 pub use fullscreen::build_fullscreen_sampled_bundle;
+pub use mip_atlas::build_image_mip_atlas_wgsl;
 pub use present::build_hdr_gamma_encode_wgsl;
 pub use present::build_srgb_display_encode_wgsl;
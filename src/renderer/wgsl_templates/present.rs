@@ -89,13 +89,26 @@ fn fs_main(in: VSOut) -> @location(0) vec4f {{\n\
     )
 }
 
-pub fn build_srgb_display_encode_wgsl(tex_var: &str, samp_var: &str) -> String {
+/// Build the SDR display-encode shader (linear → clamped sRGB into `Rgba8Unorm`).
+///
+/// With `dither` set, triangular-PDF noise of ±1 LSB is added after encoding
+/// so smooth gradients quantize to 8 bits without visible banding. The noise
+/// is a hash of the pixel position, so it is stable across frames.
+pub fn build_srgb_display_encode_wgsl(tex_var: &str, samp_var: &str, dither: bool) -> String {
     // Convert linear scene output -> sRGB-encoded bytes for display paths that treat the
     // framebuffer as linear (common in UI renderers). The source texture is assumed to be an
     // sRGB texture; sampling returns linear floats.
     //
     // Keep alpha linear (do NOT gamma-correct alpha).
     //
+    let (dither_fns, encode) = if dither {
+        (
+            SRGB_DITHER_WGSL,
+            "dither_8bit(linear_to_srgb(c.xyz), in.position.xy)",
+        )
+    } else {
+        ("", "linear_to_srgb(c.xyz)")
+    };
     format!(
         "\
 struct Params {{\n\
@@ -146,6 +159,7 @@ fn linear_to_srgb(rgb: vec3f) -> vec3f {{\n\
         linear_to_srgb_channel(rgb.z),\n\
     );\n\
 }}\n\
+{dither_fns}\
 \n\
 @vertex\n\
 fn vs_main(\n\
@@ -171,7 +185,44 @@ fn vs_main(\n\
 @fragment\n\
 fn fs_main(in: VSOut) -> @location(0) vec4f {{\n\
     let c = textureSample({tex_var}, {samp_var}, in.uv);\n\
-    return vec4f(linear_to_srgb(c.xyz), saturate(c.w));
+    return vec4f({encode}, saturate(c.w));
 }}\n"
     )
 }
+
+const SRGB_DITHER_WGSL: &str = "
+fn dither_pcg3d(v_in: vec3u) -> vec3u {
+    var v = v_in * 1664525u + 1013904223u;
+    v.x = v.x + v.y * v.z;
+    v.y = v.y + v.z * v.x;
+    v.z = v.z + v.x * v.y;
+    v = v ^ (v >> vec3u(16u));
+    v.x = v.x + v.y * v.z;
+    v.y = v.y + v.z * v.x;
+    v.z = v.z + v.x * v.y;
+    return v;
+}
+
+// Triangular-PDF dither: the sum of two uniform samples spans +-1 LSB.
+fn dither_8bit(srgb: vec3f, frag_xy: vec2f) -> vec3f {
+    let p = vec2u(frag_xy);
+    let a = vec3f(dither_pcg3d(vec3u(p, 0u))) * (1.0 / 4294967295.0);
+    let b = vec3f(dither_pcg3d(vec3u(p, 1u))) * (1.0 / 4294967295.0);
+    return srgb + (a + b - vec3f(1.0)) * (1.0 / 255.0);
+}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::build_srgb_display_encode_wgsl;
+
+    #[test]
+    fn srgb_encode_validates_with_and_without_dither() {
+        let plain = build_srgb_display_encode_wgsl("src_tex", "src_samp", false);
+        let dithered = build_srgb_display_encode_wgsl("src_tex", "src_samp", true);
+        assert!(!plain.contains("dither_8bit"));
+        assert!(dithered.contains("dither_8bit(linear_to_srgb(c.xyz), in.position.xy)"));
+        crate::renderer::validate_wgsl(&plain).unwrap();
+        crate::renderer::validate_wgsl(&dithered).unwrap();
+    }
+}
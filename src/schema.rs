@@ -325,6 +325,14 @@ pub fn validate_scene_against(scene: &SceneDSL, scheme: &NodeScheme) -> Result<(
             errors.push(msg);
         }
 
+        if let Err(msg) = validate_compute_pass_params(n) {
+            errors.push(msg);
+        }
+
+        if let Err(msg) = validate_blur_pass_params(n) {
+            errors.push(msg);
+        }
+
         if let Err(msg) = validate_camera_and_mat4_params(n) {
             errors.push(msg);
         }
@@ -393,9 +401,71 @@ fn validate_render_pass_params(node: &Node) -> std::result::Result<(), String> {
         }
     }
 
+    if let Some(value) = node.params.get("stencilWrite") {
+        let valid = value.is_boolean() || value.as_u64().is_some_and(|v| (1..=255).contains(&v));
+        if !valid {
+            return Err(format!(
+                "invalid RenderPass.stencilWrite for '{}': expected boolean or integer 1..=255, got {}",
+                node.id, value
+            ));
+        }
+    }
+
+    if let Some(value) = node.params.get("stencilTest") {
+        if !value.is_string() {
+            return Err(format!(
+                "invalid RenderPass.stencilTest for '{}': expected string, got {}",
+                node.id, value
+            ));
+        }
+    }
+
+    if let Some(value) = node.params.get("stencilRef") {
+        if !value.as_u64().is_some_and(|v| (1..=255).contains(&v)) {
+            return Err(format!(
+                "invalid RenderPass.stencilRef for '{}': expected integer 1..=255, got {}",
+                node.id, value
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_compute_pass_params(node: &Node) -> std::result::Result<(), String> {
+    use crate::renderer::render_plan::pass_assemblers::compute::{
+        parse_compute_storage_format, parse_compute_workgroup_size,
+    };
+
+    if node.node_type != "ComputePass" {
+        return Ok(());
+    }
+    parse_compute_workgroup_size(&node.params)
+        .map_err(|e| format!("invalid ComputePass '{}': {e}", node.id))?;
+    parse_compute_storage_format(&node.params)
+        .map_err(|e| format!("invalid ComputePass '{}': {e}", node.id))?;
+    if let Some(value) = node.params.get("source")
+        && !value.is_string()
+    {
+        return Err(format!(
+            "invalid ComputePass.source for '{}': expected string, got {}",
+            node.id, value
+        ));
+    }
     Ok(())
 }
 
+fn validate_blur_pass_params(node: &Node) -> std::result::Result<(), String> {
+    use crate::renderer::render_plan::pass_assemblers::gaussian_blur::BlurKernelType;
+
+    if node.node_type != "GuassianBlurPass" {
+        return Ok(());
+    }
+    BlurKernelType::from_params(&node.params)
+        .map(|_| ())
+        .map_err(|e| format!("invalid GuassianBlurPass '{}': {e}", node.id))
+}
+
 fn render_pass_depth_test_enabled(node: &Node, scheme: &NodeScheme) -> bool {
     if node.node_type != "RenderPass" {
         return false;
@@ -623,6 +693,57 @@ fn validate_camera_and_mat4_params(node: &Node) -> std::result::Result<(), Strin
             }
             Ok(())
         }
+        "Camera" => {
+            validate_camera_node_geometry(node)?;
+            let projection = node
+                .params
+                .get("projection")
+                .and_then(|v| v.as_str())
+                .unwrap_or("perspective")
+                .trim()
+                .to_ascii_lowercase();
+            if !matches!(
+                projection.as_str(),
+                "perspective" | "orthographic" | "ortho"
+            ) {
+                return Err(format!(
+                    "{}.projection must be 'perspective' or 'orthographic', got '{}'",
+                    node.id, projection
+                ));
+            }
+            if let Some(fov) = parse_scalar_if_present(node, "fov")? {
+                if !(fov > 0.0 && fov < 180.0) {
+                    return Err(format!(
+                        "{}.fov must be > 0 and < 180 degrees, got {}",
+                        node.id, fov
+                    ));
+                }
+            }
+            if let Some(height) = parse_scalar_if_present(node, "orthoHeight")? {
+                if !(height > 0.0) {
+                    return Err(format!(
+                        "{}.orthoHeight must be > 0, got {}",
+                        node.id, height
+                    ));
+                }
+            }
+            let near = parse_scalar_if_present(node, "near")?;
+            let far = parse_scalar_if_present(node, "far")?;
+            if let Some(near) = near {
+                if !(near > 0.0) {
+                    return Err(format!("{}.near must be > 0, got {}", node.id, near));
+                }
+            }
+            if let (Some(near), Some(far)) = (near, far) {
+                if !(far > near) {
+                    return Err(format!(
+                        "{}.far must be > near (near={}, far={})",
+                        node.id, near, far
+                    ));
+                }
+            }
+            Ok(())
+        }
         "OrthographicCamera" => {
             validate_camera_node_geometry(node)?;
             let left = parse_scalar_if_present(node, "left")?;
@@ -753,13 +874,18 @@ fn validate_connection(
     fn inferred_result_output_node_type(node_type: &str) -> bool {
         matches!(
             node_type,
-            "MathAdd"
+            "Math"
+                | "MathAdd"
                 | "MathSubtract"
                 | "MathMultiply"
                 | "MathDivide"
                 | "MathPower"
                 | "MathClamp"
                 | "MathMax"
+                | "MapRange"
+                | "VectorRotate"
+                | "VectorTransform"
+                | "Switch"
                 | "Sin"
                 | "Cos"
                 | "Tan"
@@ -853,8 +979,8 @@ fn validate_connection(
             Some(pass_ty) => Cow::Borrowed(pass_ty),
             None => Cow::Owned(PortTypeSpec::One("pass".to_string())),
         }
-    } else if to_node.node_type == "MathClosure" {
-        // MathClosure inputs are instance-defined (node.inputs in the DSL export).
+    } else if matches!(to_node.node_type.as_str(), "MathClosure" | "CustomWGSL") {
+        // MathClosure/CustomWGSL inputs are instance-defined (node.inputs in the DSL export).
         if let Some(spec) = math_closure_input_port_type(to_node, &c.to.port_id) {
             Cow::Owned(spec)
         } else {
@@ -0,0 +1,249 @@
+//! Golden-baseline maintenance for the render tests.
+//!
+//! Render cases keep their expected output at `<case>/expected/baseline.{png,exr}`.
+//! When a change legitimately alters that output, [`bless_baseline`] copies a
+//! fresh headless render over the baseline and records how far it moved from
+//! the previous one in a `<baseline>.bless.json` sidecar, so reviewers can see
+//! the size of the change without diffing images by hand.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Per-channel difference below which EXR pixels count as equal; matches the
+/// tolerance the render tests compare with.
+const EXR_CHANNEL_EPSILON: f32 = 1.0 / 512.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BlessOutcome {
+    /// No baseline existed; the render became the baseline.
+    Created,
+    /// The baseline differed and was replaced.
+    Updated,
+    /// The render matches the baseline; nothing was written.
+    Unchanged,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineDiffStats {
+    pub previous_size: [u32; 2],
+    pub size: [u32; 2],
+    /// Pixels with any differing channel; `None` when the sizes differ.
+    pub mismatched_pixels: Option<u64>,
+    /// Largest channel difference, in 8-bit steps for PNG and linear units
+    /// for EXR; `None` when the sizes differ.
+    pub max_channel_delta: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlessRecord {
+    pub baseline: PathBuf,
+    pub outcome: BlessOutcome,
+    /// Difference from the previous baseline; `None` when there was none.
+    pub diff: Option<BaselineDiffStats>,
+}
+
+/// Baseline a scene's render is compared against:
+/// `<scene dir>/expected/baseline.<ext>`.
+pub fn scene_baseline_path(scene_path: &Path, extension: &str) -> PathBuf {
+    scene_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("expected")
+        .join(format!("baseline.{extension}"))
+}
+
+/// Sidecar JSON written next to a blessed baseline.
+pub fn bless_sidecar_path(baseline: &Path) -> PathBuf {
+    let mut name = baseline.file_name().unwrap_or_default().to_os_string();
+    name.push(".bless.json");
+    baseline.with_file_name(name)
+}
+
+fn is_exr(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exr"))
+}
+
+/// Compares two images of the same kind (both PNG or both EXR).
+pub fn baseline_diff_stats(previous: &Path, rendered: &Path) -> Result<BaselineDiffStats> {
+    let open = |path: &Path| {
+        image::open(path).with_context(|| format!("failed to open image {}", path.display()))
+    };
+    let (previous_img, rendered_img) = (open(previous)?, open(rendered)?);
+    let previous_size = [previous_img.width(), previous_img.height()];
+    let size = [rendered_img.width(), rendered_img.height()];
+    if previous_size != size {
+        return Ok(BaselineDiffStats {
+            previous_size,
+            size,
+            mismatched_pixels: None,
+            max_channel_delta: None,
+        });
+    }
+
+    let mut mismatched: u64 = 0;
+    let mut max_delta: f64 = 0.0;
+    if is_exr(rendered) {
+        let (a, b) = (previous_img.to_rgba32f(), rendered_img.to_rgba32f());
+        for (pa, pb) in a.pixels().zip(b.pixels()) {
+            let delta = (0..4)
+                .map(|c| (pa.0[c] - pb.0[c]).abs())
+                .fold(0.0f32, f32::max);
+            if delta > EXR_CHANNEL_EPSILON {
+                mismatched += 1;
+                max_delta = max_delta.max(delta as f64);
+            }
+        }
+    } else {
+        let (a, b) = (previous_img.to_rgba8(), rendered_img.to_rgba8());
+        for (pa, pb) in a.pixels().zip(b.pixels()) {
+            let delta = (0..4).map(|c| pa.0[c].abs_diff(pb.0[c])).max().unwrap_or(0);
+            if delta != 0 {
+                mismatched += 1;
+                max_delta = max_delta.max(delta as f64);
+            }
+        }
+    }
+    Ok(BaselineDiffStats {
+        previous_size,
+        size,
+        mismatched_pixels: Some(mismatched),
+        max_channel_delta: Some(max_delta),
+    })
+}
+
+/// Makes `rendered` the new `baseline`, recording the diff against the old one
+/// in [`bless_sidecar_path`]. A render identical to the baseline leaves both
+/// files untouched.
+pub fn bless_baseline(rendered: &Path, baseline: &Path) -> Result<BlessRecord> {
+    if !rendered.is_file() {
+        bail!("rendered output {} does not exist", rendered.display());
+    }
+    if is_exr(rendered) != is_exr(baseline) {
+        bail!(
+            "cannot bless {} as {}: PNG and EXR baselines are not interchangeable",
+            rendered.display(),
+            baseline.display()
+        );
+    }
+
+    let diff = if baseline.is_file() {
+        Some(baseline_diff_stats(baseline, rendered)?)
+    } else {
+        None
+    };
+    let outcome = match &diff {
+        None => BlessOutcome::Created,
+        Some(stats) if stats.mismatched_pixels == Some(0) => BlessOutcome::Unchanged,
+        Some(_) => BlessOutcome::Updated,
+    };
+    let record = BlessRecord {
+        baseline: baseline.to_path_buf(),
+        outcome,
+        diff,
+    };
+    if outcome == BlessOutcome::Unchanged {
+        return Ok(record);
+    }
+
+    if let Some(parent) = baseline.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::copy(rendered, baseline).with_context(|| {
+        format!(
+            "failed to copy {} to {}",
+            rendered.display(),
+            baseline.display()
+        )
+    })?;
+    let sidecar = bless_sidecar_path(baseline);
+    let json = serde_json::to_string_pretty(&record)?;
+    std::fs::write(&sidecar, json + "\n")
+        .with_context(|| format!("failed to write {}", sidecar.display()))?;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{BlessOutcome, bless_baseline, bless_sidecar_path, scene_baseline_path};
+
+    fn temp_root(label: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "node-forge-bless-{label}-{nonce}-{}",
+            std::process::id()
+        ))
+    }
+
+    fn write_png(path: &Path, pixels: &[[u8; 4]]) {
+        let mut img = image::RgbaImage::new(pixels.len() as u32, 1);
+        for (x, p) in pixels.iter().enumerate() {
+            img.put_pixel(x as u32, 0, image::Rgba(*p));
+        }
+        img.save(path).unwrap();
+    }
+
+    #[test]
+    fn baseline_path_sits_under_expected() {
+        let scene = Path::new("cases/bloom/scene.nforge");
+        assert_eq!(
+            scene_baseline_path(scene, "exr"),
+            Path::new("cases/bloom/expected/baseline.exr")
+        );
+        assert_eq!(
+            bless_sidecar_path(Path::new("expected/baseline.png")),
+            Path::new("expected/baseline.png.bless.json")
+        );
+    }
+
+    #[test]
+    fn bless_creates_updates_and_skips_unchanged() {
+        let root = temp_root("cycle");
+        fs::create_dir_all(&root).unwrap();
+        let rendered = root.join("out.png");
+        let baseline = root.join("expected").join("baseline.png");
+
+        write_png(&rendered, &[[10, 20, 30, 255], [0, 0, 0, 255]]);
+        let created = bless_baseline(&rendered, &baseline).unwrap();
+        assert_eq!(created.outcome, BlessOutcome::Created);
+        assert!(created.diff.is_none());
+        assert!(baseline.is_file());
+
+        let unchanged = bless_baseline(&rendered, &baseline).unwrap();
+        assert_eq!(unchanged.outcome, BlessOutcome::Unchanged);
+
+        write_png(&rendered, &[[10, 20, 30, 255], [0, 7, 0, 255]]);
+        let updated = bless_baseline(&rendered, &baseline).unwrap();
+        assert_eq!(updated.outcome, BlessOutcome::Updated);
+        let diff = updated.diff.as_ref().unwrap();
+        assert_eq!(diff.mismatched_pixels, Some(1));
+        assert_eq!(diff.max_channel_delta, Some(7.0));
+
+        let sidecar = fs::read_to_string(bless_sidecar_path(&baseline)).unwrap();
+        assert!(sidecar.contains("\"outcome\": \"updated\""));
+        assert!(sidecar.contains("\"mismatchedPixels\": 1"));
+        assert_eq!(
+            image::open(&baseline).unwrap().to_rgba8().get_pixel(1, 0).0,
+            [0, 7, 0, 255]
+        );
+
+        assert!(bless_baseline(&rendered, &root.join("baseline.exr")).is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
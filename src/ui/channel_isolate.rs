@@ -0,0 +1,295 @@
+use rust_wgpu_fiber::eframe::wgpu;
+
+use crate::app::DisplayChannel;
+
+/// Checkerboard square size behind the alpha view, in source pixels.
+const CHECKER_SIZE_PX: u32 = 8;
+
+/// Re-presents one channel of the display texture. Colour channels become
+/// grayscale; alpha composites the (premultiplied) image over a
+/// checkerboard.
+const COMPUTE_SHADER_SRC: &str = r#"
+struct ChannelParams {
+    size: vec2<u32>,
+    // 0 = rgb, 1 = r, 2 = g, 3 = b, 4 = alpha
+    channel: u32,
+    checker_size: u32,
+};
+
+@group(0) @binding(0)
+var source_tex: texture_2d<f32>;
+
+@group(0) @binding(1)
+var out_tex: texture_storage_2d<rgba8unorm, write>;
+
+@group(0) @binding(2)
+var<uniform> params: ChannelParams;
+
+@compute @workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= params.size.x || gid.y >= params.size.y) {
+        return;
+    }
+
+    let src = clamp(textureLoad(source_tex, vec2<i32>(gid.xy), 0), vec4<f32>(0.0), vec4<f32>(1.0));
+    var rgb = src.rgb;
+    switch params.channel {
+        case 1u: {
+            rgb = vec3<f32>(src.r);
+        }
+        case 2u: {
+            rgb = vec3<f32>(src.g);
+        }
+        case 3u: {
+            rgb = vec3<f32>(src.b);
+        }
+        case 4u: {
+            let cell = (gid.x / params.checker_size + gid.y / params.checker_size) % 2u;
+            let checker = select(0.8, 0.55, cell == 1u);
+            rgb = clamp(src.rgb + vec3<f32>(checker) * (1.0 - src.a), vec3<f32>(0.0), vec3<f32>(1.0));
+        }
+        default: {}
+    }
+
+    textureStore(out_tex, vec2<i32>(gid.xy), vec4<f32>(rgb, 1.0));
+}
+"#;
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ChannelParams {
+    size: [u32; 2],
+    channel: u32,
+    checker_size: u32,
+}
+
+pub struct ChannelIsolateRenderer {
+    compute_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    params_buffer: wgpu::Buffer,
+    output_texture: wgpu::Texture,
+    output_view: wgpu::TextureView,
+    output_size: [u32; 2],
+}
+
+impl ChannelIsolateRenderer {
+    pub fn new(device: &wgpu::Device, output_size: [u32; 2]) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("sys.preview.channel.compute"),
+            source: wgpu::ShaderSource::Wgsl(COMPUTE_SHADER_SRC.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sys.preview.channel.bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("sys.preview.channel.layout"),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("sys.preview.channel.pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sys.preview.channel.params"),
+            size: std::mem::size_of::<ChannelParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let (output_texture, output_view, output_size) =
+            Self::create_output_texture(device, output_size);
+
+        Self {
+            compute_pipeline,
+            bind_group_layout,
+            params_buffer,
+            output_texture,
+            output_view,
+            output_size,
+        }
+    }
+
+    fn create_output_texture(
+        device: &wgpu::Device,
+        output_size: [u32; 2],
+    ) -> (wgpu::Texture, wgpu::TextureView, [u32; 2]) {
+        let output_size = [output_size[0].max(1), output_size[1].max(1)];
+        let output_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("sys.preview.channel.output"),
+            size: wgpu::Extent3d {
+                width: output_size[0],
+                height: output_size[1],
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+            view_formats: &[],
+        });
+        let output_view = output_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (output_texture, output_view, output_size)
+    }
+
+    fn ensure_output_size(&mut self, device: &wgpu::Device, output_size: [u32; 2]) {
+        let target = [output_size[0].max(1), output_size[1].max(1)];
+        if self.output_size == target {
+            return;
+        }
+        let (output_texture, output_view, output_size) =
+            Self::create_output_texture(device, output_size);
+        self.output_texture = output_texture;
+        self.output_view = output_view;
+        self.output_size = output_size;
+    }
+
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        source_view: &wgpu::TextureView,
+        source_size: [u32; 2],
+        channel: DisplayChannel,
+    ) {
+        self.ensure_output_size(device, source_size);
+
+        let params = ChannelParams {
+            size: self.output_size,
+            channel: match channel {
+                DisplayChannel::Rgb => 0,
+                DisplayChannel::Red => 1,
+                DisplayChannel::Green => 2,
+                DisplayChannel::Blue => 3,
+                DisplayChannel::Alpha => 4,
+            },
+            checker_size: CHECKER_SIZE_PX,
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sys.preview.channel.bg"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&self.output_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &self.params_buffer,
+                        offset: 0,
+                        size: None,
+                    }),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("sys.preview.channel.encoder"),
+        });
+        {
+            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("sys.preview.channel.pass"),
+                timestamp_writes: None,
+            });
+            cpass.set_pipeline(&self.compute_pipeline);
+            cpass.set_bind_group(0, &bind_group, &[]);
+            let group_x = self.output_size[0].div_ceil(16);
+            let group_y = self.output_size[1].div_ceil(16);
+            cpass.dispatch_workgroups(group_x, group_y, 1);
+        }
+
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    pub fn output_view(&self) -> &wgpu::TextureView {
+        &self.output_view
+    }
+
+    pub fn output_texture(&self) -> &wgpu::Texture {
+        &self.output_texture
+    }
+
+    pub fn output_size(&self) -> [u32; 2] {
+        self.output_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CHECKER_SIZE_PX;
+
+    fn composite_over_checker(rgba: [f32; 4], x: u32, y: u32) -> [f32; 3] {
+        let cell = (x / CHECKER_SIZE_PX + y / CHECKER_SIZE_PX) % 2;
+        let checker = if cell == 1 { 0.55 } else { 0.8 };
+        [
+            (rgba[0] + checker * (1.0 - rgba[3])).clamp(0.0, 1.0),
+            (rgba[1] + checker * (1.0 - rgba[3])).clamp(0.0, 1.0),
+            (rgba[2] + checker * (1.0 - rgba[3])).clamp(0.0, 1.0),
+        ]
+    }
+
+    #[test]
+    fn transparent_pixels_show_the_checkerboard() {
+        assert_eq!(composite_over_checker([0.0; 4], 0, 0), [0.8; 3]);
+        assert_eq!(
+            composite_over_checker([0.0; 4], CHECKER_SIZE_PX, 0),
+            [0.55; 3]
+        );
+    }
+
+    #[test]
+    fn opaque_pixels_hide_the_checkerboard() {
+        assert_eq!(
+            composite_over_checker([0.25, 0.5, 0.75, 1.0], CHECKER_SIZE_PX, 0),
+            [0.25, 0.5, 0.75]
+        );
+    }
+}
@@ -1,10 +1,33 @@
 use rust_wgpu_fiber::eframe::wgpu;
 
+use crate::app::{ClippingMode, ClippingSettings, ClippingStyle};
+
+/// Diagonal stripe period of the zebra overlay, in source pixels.
+pub const ZEBRA_PERIOD_PX: u32 = 16;
+/// How fast the zebra stripes crawl, in source pixels per second.
+pub const ZEBRA_SPEED_PX_PER_SEC: f64 = 16.0;
+
+/// Stripe offset at time `now`. It only changes once per source pixel, so
+/// the overlay is recomputed at `ZEBRA_SPEED_PX_PER_SEC` rather than every
+/// frame.
+pub fn zebra_phase(now: f64) -> u32 {
+    if !now.is_finite() || now <= 0.0 {
+        return 0;
+    }
+    ((now * ZEBRA_SPEED_PX_PER_SEC) as u64 % ZEBRA_PERIOD_PX as u64) as u32
+}
+
 const COMPUTE_SHADER_SRC: &str = r#"
 struct ClipParams {
     size: vec2<u32>,
     shadow_threshold: f32,
     highlight_threshold: f32,
+    // 0 = per channel, 1 = luminance
+    mode: u32,
+    // 0 = map, 1 = zebra, 2 = both
+    style: u32,
+    zebra_phase: u32,
+    zebra_period: u32,
 };
 
 @group(0) @binding(0)
@@ -23,11 +46,16 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     }
 
     let rgb = textureLoad(source_tex, vec2<i32>(gid.xy), 0).rgb;
-    let max_rgb = max(max(rgb.r, rgb.g), rgb.b);
-    let min_rgb = min(min(rgb.r, rgb.g), rgb.b);
+    var high_value = max(max(rgb.r, rgb.g), rgb.b);
+    var low_value = min(min(rgb.r, rgb.g), rgb.b);
+    if (params.mode == 1u) {
+        let luma = dot(rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+        high_value = luma;
+        low_value = luma;
+    }
 
-    let h = smoothstep(params.highlight_threshold - 0.02, params.highlight_threshold, max_rgb);
-    let s = 1.0 - smoothstep(params.shadow_threshold, params.shadow_threshold + 0.02, min_rgb);
+    let h = smoothstep(params.highlight_threshold - 0.02, params.highlight_threshold, high_value);
+    let s = 1.0 - smoothstep(params.shadow_threshold, params.shadow_threshold + 0.02, low_value);
 
     var out_rgb = vec3<f32>(0.0, 0.0, 0.0);
     var out_a = 0.0;
@@ -43,6 +71,22 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         out_a = s * 0.78;
     }
 
+    if (params.style != 0u && out_a > 0.0) {
+        // Highlights stripe down-right, shadows down-left, so the two read
+        // apart even without the tint.
+        var diagonal = gid.x + gid.y;
+        if (h <= 0.001) {
+            diagonal = gid.x + params.size.y - gid.y;
+        }
+        let on_stripe = (diagonal + params.zebra_phase) % params.zebra_period
+            < params.zebra_period / 2u;
+        if (on_stripe) {
+            out_a = 0.95;
+        } else if (params.style == 1u) {
+            out_a = 0.0;
+        }
+    }
+
     textureStore(out_tex, vec2<i32>(gid.xy), vec4<f32>(out_rgb, out_a));
 }
 "#;
@@ -53,6 +97,10 @@ struct ClipParams {
     size: [u32; 2],
     shadow_threshold: f32,
     highlight_threshold: f32,
+    mode: u32,
+    style: u32,
+    zebra_phase: u32,
+    zebra_period: u32,
 }
 
 pub struct ClippingMapRenderer {
@@ -184,15 +232,26 @@ impl ClippingMapRenderer {
         queue: &wgpu::Queue,
         source_view: &wgpu::TextureView,
         source_size: [u32; 2],
-        shadow_threshold: f32,
-        highlight_threshold: f32,
+        settings: &ClippingSettings,
+        zebra_phase: u32,
     ) {
         self.ensure_output_size(device, source_size);
 
         let params = ClipParams {
             size: self.output_size,
-            shadow_threshold: shadow_threshold.clamp(0.0, 1.0),
-            highlight_threshold: highlight_threshold.clamp(0.0, 1.0),
+            shadow_threshold: settings.shadow_threshold.clamp(0.0, 1.0),
+            highlight_threshold: settings.highlight_threshold.clamp(0.0, 1.0),
+            mode: match settings.mode {
+                ClippingMode::PerChannel => 0,
+                ClippingMode::Luminance => 1,
+            },
+            style: match settings.style {
+                ClippingStyle::Map => 0,
+                ClippingStyle::Zebra => 1,
+                ClippingStyle::Both => 2,
+            },
+            zebra_phase: zebra_phase % ZEBRA_PERIOD_PX,
+            zebra_period: ZEBRA_PERIOD_PX,
         };
         queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
 
@@ -252,6 +311,8 @@ impl ClippingMapRenderer {
 
 #[cfg(test)]
 mod tests {
+    use super::{ZEBRA_PERIOD_PX, zebra_phase};
+
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     enum ClipClass {
         None,
@@ -263,8 +324,17 @@ mod tests {
     fn classify(rgb: [f32; 3], shadow: f32, highlight: f32) -> ClipClass {
         let max_rgb = rgb[0].max(rgb[1]).max(rgb[2]);
         let min_rgb = rgb[0].min(rgb[1]).min(rgb[2]);
-        let highlight_hit = max_rgb >= highlight;
-        let shadow_hit = min_rgb <= shadow;
+        classify_values(max_rgb, min_rgb, shadow, highlight)
+    }
+
+    fn classify_luminance(rgb: [f32; 3], shadow: f32, highlight: f32) -> ClipClass {
+        let luma = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+        classify_values(luma, luma, shadow, highlight)
+    }
+
+    fn classify_values(high: f32, low: f32, shadow: f32, highlight: f32) -> ClipClass {
+        let highlight_hit = high >= highlight;
+        let shadow_hit = low <= shadow;
 
         match (highlight_hit, shadow_hit) {
             (true, true) => ClipClass::Both,
@@ -293,4 +363,26 @@ mod tests {
     fn classify_none() {
         assert_eq!(classify([0.3, 0.4, 0.5], 0.02, 0.98), ClipClass::None);
     }
+
+    #[test]
+    fn luminance_mode_ignores_a_single_clipped_channel() {
+        assert_eq!(classify([1.0, 0.5, 0.4], 0.02, 0.98), ClipClass::Highlight);
+        assert_eq!(
+            classify_luminance([1.0, 0.5, 0.4], 0.02, 0.98),
+            ClipClass::None
+        );
+        assert_eq!(
+            classify_luminance([1.0, 1.0, 1.0], 0.02, 0.98),
+            ClipClass::Highlight
+        );
+    }
+
+    #[test]
+    fn zebra_phase_wraps_within_one_period() {
+        assert_eq!(zebra_phase(0.0), 0);
+        assert_eq!(zebra_phase(f64::NAN), 0);
+        assert_eq!(zebra_phase(0.5), 8);
+        assert_eq!(zebra_phase(1.0), 0);
+        assert!((0..1000).all(|i| zebra_phase(i as f64 * 0.37) < ZEBRA_PERIOD_PX));
+    }
 }
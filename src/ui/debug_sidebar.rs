@@ -1,11 +1,17 @@
 use rust_wgpu_fiber::{eframe::egui, shader_space::PassCaptureMode};
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::hash::Hash;
 
 use crate::android_reference::AndroidReferenceStatus;
 use crate::app::{
-    AnalysisTab, ClippingSettings, DiffMetricMode, DiffStats, QualifierChannel, QualifierSettings,
-    RefImageMode, ResourcePoolInfo, StateControlSelection, TestMode, display_metrics,
+    AnalysisTab, ClippingMode, ClippingSettings, ClippingStyle, DiffColorSpace, DiffMetricMode,
+    DiffStats, DisplayChannel, DisplayTransform, FalseColorSettings, OnionSkinMode,
+    QualifierChannel, QualifierSettings, RefImageMode, ResourcePoolInfo, StateControlSelection,
+    TestMode, ViewTransformSettings, display_metrics,
+};
+use crate::renderer::{
+    ChannelDebugMode, NonFiniteSanitizeMode, NonFiniteSanitizeOptions, PassDebugOverlay,
 };
 
 use super::button::{
@@ -194,6 +200,117 @@ fn mode_options() -> [RadioButtonOption<'static, RefImageMode>; 2] {
     ]
 }
 
+fn onion_skin_mode_options() -> [RadioButtonOption<'static, OnionSkinMode>; 3] {
+    [
+        RadioButtonOption {
+            value: OnionSkinMode::Off,
+            label: "Off",
+        },
+        RadioButtonOption {
+            value: OnionSkinMode::Blend,
+            label: "Blend",
+        },
+        RadioButtonOption {
+            value: OnionSkinMode::Flicker,
+            label: "Flicker",
+        },
+    ]
+}
+
+fn channel_debug_mode_options() -> [RadioButtonOption<'static, ChannelDebugMode>; 5] {
+    [
+        RadioButtonOption {
+            value: ChannelDebugMode::Off,
+            label: "Off",
+        },
+        RadioButtonOption {
+            value: ChannelDebugMode::Uv,
+            label: "UV",
+        },
+        RadioButtonOption {
+            value: ChannelDebugMode::GeoFragcoord,
+            label: "Geo",
+        },
+        RadioButtonOption {
+            value: ChannelDebugMode::Derivatives,
+            label: "dXY",
+        },
+        RadioButtonOption {
+            value: ChannelDebugMode::Normals,
+            label: "Normal",
+        },
+    ]
+}
+
+fn clipping_mode_options() -> [RadioButtonOption<'static, ClippingMode>; 2] {
+    [
+        RadioButtonOption {
+            value: ClippingMode::PerChannel,
+            label: "RGB",
+        },
+        RadioButtonOption {
+            value: ClippingMode::Luminance,
+            label: "Luma",
+        },
+    ]
+}
+
+fn clipping_style_options() -> [RadioButtonOption<'static, ClippingStyle>; 3] {
+    [
+        RadioButtonOption {
+            value: ClippingStyle::Map,
+            label: "Map",
+        },
+        RadioButtonOption {
+            value: ClippingStyle::Zebra,
+            label: "Zebra",
+        },
+        RadioButtonOption {
+            value: ClippingStyle::Both,
+            label: "Both",
+        },
+    ]
+}
+
+fn display_channel_options() -> [RadioButtonOption<'static, DisplayChannel>; 5] {
+    DisplayChannel::ALL.map(|channel| RadioButtonOption {
+        value: channel,
+        label: channel.short_label(),
+    })
+}
+
+fn display_transform_options() -> [RadioButtonOption<'static, DisplayTransform>; 3] {
+    [
+        DisplayTransform::Srgb,
+        DisplayTransform::Pq,
+        DisplayTransform::Hlg,
+    ]
+    .map(|transform| RadioButtonOption {
+        value: transform,
+        label: transform.label(),
+    })
+}
+
+fn off_on_options() -> [RadioButtonOption<'static, bool>; 2] {
+    [
+        RadioButtonOption {
+            value: false,
+            label: "Off",
+        },
+        RadioButtonOption {
+            value: true,
+            label: "On",
+        },
+    ]
+}
+
+fn non_finite_sanitize_mode_options() -> [RadioButtonOption<'static, NonFiniteSanitizeMode>; 3] {
+    NonFiniteSanitizeMode::ALL.map(|mode| RadioButtonOption {
+        value: mode,
+        label: mode.label(),
+    })
+}
+
 fn diff_metric_options() -> [RadioButtonOption<'static, DiffMetricMode>; 5] {
     [
         RadioButtonOption {
@@ -219,6 +336,13 @@ fn diff_metric_options() -> [RadioButtonOption<'static, DiffMetricMode>; 5] {
     ]
 }
 
+fn diff_color_space_options() -> [RadioButtonOption<'static, DiffColorSpace>; 2] {
+    [DiffColorSpace::Linear, DiffColorSpace::Srgb].map(|space| RadioButtonOption {
+        value: space,
+        label: space.label(),
+    })
+}
+
 fn analysis_tab_options() -> [RadioButtonOption<'static, AnalysisTab>; 3] {
     [
         RadioButtonOption {
@@ -286,6 +410,11 @@ pub enum SidebarAction {
     OpenPassDebug(String),
     /// Open a pass-specific design window.
     OpenPassDesign(PassDesignTarget),
+    /// Set or clear the debug visualization for one render pass.
+    SetPassDebugOverlay {
+        pass_name: String,
+        overlay: Option<PassDebugOverlay>,
+    },
     /// Clear the preview (user clicked a non-texture node).
     ClearPreview,
     /// Update reference overlay opacity.
@@ -302,6 +431,8 @@ pub enum SidebarAction {
     StopAndroidReference,
     /// Set current diff metric mode.
     SetDiffMetricMode(DiffMetricMode),
+    /// Set the space diff operands are compared in.
+    SetDiffColorSpace(DiffColorSpace),
     /// Switch current analysis tab.
     SetAnalysisTab(AnalysisTab),
     /// Enable/disable clipping overlay.
@@ -310,6 +441,10 @@ pub enum SidebarAction {
     SetClippingShadowThreshold(f32),
     /// Set clipping highlight threshold.
     SetClippingHighlightThreshold(f32),
+    /// Compare clipping thresholds per channel or against luminance.
+    SetClippingMode(ClippingMode),
+    /// Show clipping as a tint, zebra stripes or both.
+    SetClippingStyle(ClippingStyle),
     /// Enable/disable qualifier overlay.
     SetQualifierEnabled(bool),
     /// Set the qualifier range for a single channel.
@@ -328,6 +463,28 @@ pub enum SidebarAction {
     SetMatrixLabelsVisible(bool),
     /// Set the target display PPI used for physical-size preview.
     SetDisplayPpi(f32),
+    /// Switch how the previous scene version is shown over the current output.
+    SetOnionSkinMode(OnionSkinMode),
+    /// Set the A/B flip rate used by flicker onion skinning.
+    SetOnionSkinFlickerHz(f32),
+    /// Replace every draw pass's output with a coordinate visualization.
+    SetChannelDebugMode(ChannelDebugMode),
+    /// Configure the NaN/Inf sanitize stage (safe mode).
+    SetNonFiniteSanitize(NonFiniteSanitizeOptions),
+    /// Render pass ids so canvas clicks report the pass under the cursor.
+    SetPickIds(bool),
+    /// Present a single channel of the canvas texture.
+    SetDisplayChannel(DisplayChannel),
+    /// Set the viewer exposure, in stops.
+    SetViewExposure(f32),
+    /// Set the viewer gamma.
+    SetViewGamma(f32),
+    /// Preview the output through an sRGB, PQ or HLG transform.
+    SetDisplayTransform(DisplayTransform),
+    /// Show the output as false-color luminance bands.
+    SetFalseColorEnabled(bool),
+    /// Move one false-color band edge, in stops from middle grey.
+    SetFalseColorEdge { index: usize, stops: f32 },
 }
 
 /// Hover state from the timeline panel.
@@ -350,6 +507,7 @@ pub struct ReferenceSidebarState {
     pub mode: RefImageMode,
     pub opacity: f32,
     pub diff_metric_mode: DiffMetricMode,
+    pub diff_color_space: DiffColorSpace,
     pub diff_stats: Option<DiffStats>,
 }
 
@@ -373,6 +531,15 @@ pub struct TestModeSidebarState<'a> {
 #[derive(Clone, Copy, Debug)]
 pub struct DisplaySidebarState {
     pub ppi: f32,
+    pub onion_skin_mode: OnionSkinMode,
+    pub onion_skin_flicker_hz: f32,
+    pub channel_debug_mode: ChannelDebugMode,
+    pub non_finite_sanitize: NonFiniteSanitizeOptions,
+    pub pick_ids: bool,
+    pub false_color_enabled: bool,
+    pub false_color: FalseColorSettings,
+    pub display_channel: DisplayChannel,
+    pub view_transform: ViewTransformSettings,
 }
 
 pub struct PassCaptureSidebarState {
@@ -419,6 +586,7 @@ pub fn show_in_rect(
     test_mode_state: TestModeSidebarState<'_>,
     pass_capture_state: PassCaptureSidebarState,
     tree_nodes: &[FileTreeNode],
+    pass_debug_overlays: &HashMap<String, PassDebugOverlay>,
     file_tree_state: &mut FileTreeState,
 ) -> SidebarResult {
     if ui_sidebar_factor <= 0.0 {
@@ -525,6 +693,7 @@ pub fn show_in_rect(
                                     ui,
                                     pass_capture_state,
                                     tree_nodes,
+                                    pass_debug_overlays,
                                     file_tree_state,
                                     &mut sidebar_action,
                                 );
@@ -702,7 +871,274 @@ fn show_display_section(
                 });
             });
         });
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        let row_action = RefCell::new(None);
+        sidebar_grid_row(ui, |row| {
+            row.place(1, 2, |ui| {
+                sidebar_group_cell(ui, "Onion Skin", |ui| {
+                    let mut mode = display.onion_skin_mode;
+                    if radio_button_group::radio_button_group(
+                        ui,
+                        "ui.debug_sidebar.display.onion_skin",
+                        &mut mode,
+                        &onion_skin_mode_options(),
+                    ) && mode != display.onion_skin_mode
+                    {
+                        *row_action.borrow_mut() = Some(SidebarAction::SetOnionSkinMode(mode));
+                    }
+                });
+            });
+            if display.onion_skin_mode == OnionSkinMode::Flicker {
+                row.place(3, 2, |ui| {
+                    sidebar_group_cell(ui, "Rate", |ui| {
+                        let mut hz = display.onion_skin_flicker_hz;
+                        let changed = slider_with_value(
+                            ui,
+                            "ui.debug_sidebar.display.onion_skin_hz",
+                            &mut hz,
+                            OnionSkinMode::MIN_FLICKER_HZ,
+                            OnionSkinMode::MAX_FLICKER_HZ,
+                            Some(&|v| format!("{v:.1} Hz")),
+                        );
+                        if changed {
+                            *row_action.borrow_mut() =
+                                Some(SidebarAction::SetOnionSkinFlickerHz(hz));
+                        }
+                    });
+                });
+            }
+        });
+        if let Some(action) = row_action.into_inner() {
+            *sidebar_action = Some(action);
+        }
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        sidebar_grid_row(ui, |row| {
+            row.place(1, 4, |ui| {
+                sidebar_group_cell(ui, "Channel Debug", |ui| {
+                    let mut mode = display.channel_debug_mode;
+                    if radio_button_group::radio_button_group(
+                        ui,
+                        "ui.debug_sidebar.display.channel_debug",
+                        &mut mode,
+                        &channel_debug_mode_options(),
+                    ) && mode != display.channel_debug_mode
+                    {
+                        *sidebar_action = Some(SidebarAction::SetChannelDebugMode(mode));
+                    }
+                });
+            });
+        });
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        sidebar_grid_row(ui, |row| {
+            row.place(1, 2, |ui| {
+                sidebar_group_cell(ui, "Pick", |ui| {
+                    let mut enabled = display.pick_ids;
+                    if radio_button_group::radio_button_group(
+                        ui,
+                        "ui.debug_sidebar.display.pick_ids",
+                        &mut enabled,
+                        &off_on_options(),
+                    ) && enabled != display.pick_ids
+                    {
+                        *sidebar_action = Some(SidebarAction::SetPickIds(enabled));
+                    }
+                });
+            });
+        });
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        sidebar_grid_row(ui, |row| {
+            row.place(1, 4, |ui| {
+                sidebar_group_cell(ui, "Channel", |ui| {
+                    let mut channel = display.display_channel;
+                    if radio_button_group::radio_button_group(
+                        ui,
+                        "ui.debug_sidebar.display.channel",
+                        &mut channel,
+                        &display_channel_options(),
+                    ) && channel != display.display_channel
+                    {
+                        *sidebar_action = Some(SidebarAction::SetDisplayChannel(channel));
+                    }
+                });
+            });
+        });
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        let view = display.view_transform;
+        let row_action = RefCell::new(None);
+        sidebar_grid_row(ui, |row| {
+            row.place(1, 2, |ui| {
+                sidebar_group_cell(ui, "Exposure", |ui| {
+                    let mut stops = view.exposure_stops;
+                    let formatter = |v: f32| format!("{v:+.1} EV");
+                    let changed = slider_with_editable_value(
+                        ui,
+                        "ui.debug_sidebar.display.exposure",
+                        &mut stops,
+                        ViewTransformSettings::MIN_EXPOSURE_STOPS,
+                        ViewTransformSettings::MAX_EXPOSURE_STOPS,
+                        0.1,
+                        Some(&formatter),
+                    );
+                    if changed {
+                        *row_action.borrow_mut() = Some(SidebarAction::SetViewExposure(stops));
+                    }
+                });
+            });
+            row.place(3, 2, |ui| {
+                sidebar_group_cell(ui, "Gamma", |ui| {
+                    let mut gamma = view.gamma;
+                    let formatter = |v: f32| format!("{v:.2}");
+                    let changed = slider_with_editable_value(
+                        ui,
+                        "ui.debug_sidebar.display.gamma",
+                        &mut gamma,
+                        ViewTransformSettings::MIN_GAMMA,
+                        ViewTransformSettings::MAX_GAMMA,
+                        0.01,
+                        Some(&formatter),
+                    );
+                    if changed {
+                        *row_action.borrow_mut() = Some(SidebarAction::SetViewGamma(gamma));
+                    }
+                });
+            });
+        });
+        if let Some(action) = row_action.into_inner() {
+            *sidebar_action = Some(action);
+        }
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        sidebar_grid_row(ui, |row| {
+            row.place(1, 4, |ui| {
+                sidebar_group_cell(ui, "Transform", |ui| {
+                    let mut transform = view.transform;
+                    if radio_button_group::radio_button_group(
+                        ui,
+                        "ui.debug_sidebar.display.transform",
+                        &mut transform,
+                        &display_transform_options(),
+                    ) && transform != view.transform
+                    {
+                        *sidebar_action = Some(SidebarAction::SetDisplayTransform(transform));
+                    }
+                });
+            });
+        });
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        show_false_color_rows(ui, display, sidebar_action);
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        let sanitize = display.non_finite_sanitize;
+        let row_action = RefCell::new(None);
+        sidebar_grid_row(ui, |row| {
+            row.place(1, 2, |ui| {
+                sidebar_group_cell(ui, "Safe Mode", |ui| {
+                    let mut mode = sanitize.mode;
+                    if radio_button_group::radio_button_group(
+                        ui,
+                        "ui.debug_sidebar.display.safe_mode",
+                        &mut mode,
+                        &non_finite_sanitize_mode_options(),
+                    ) && mode != sanitize.mode
+                    {
+                        *row_action.borrow_mut() = Some(SidebarAction::SetNonFiniteSanitize(
+                            NonFiniteSanitizeOptions { mode, ..sanitize },
+                        ));
+                    }
+                });
+            });
+            if sanitize.mode != NonFiniteSanitizeMode::Off {
+                row.place(3, 2, |ui| {
+                    sidebar_group_cell(ui, "Sentinel", |ui| {
+                        let presets = NonFiniteSanitizeOptions::SENTINEL_PRESETS;
+                        let current = presets
+                            .iter()
+                            .position(|(_, color)| *color == sanitize.sentinel)
+                            .unwrap_or(usize::MAX);
+                        let options: Vec<RadioButtonOption<'static, usize>> = presets
+                            .iter()
+                            .enumerate()
+                            .map(|(index, (label, _))| RadioButtonOption {
+                                value: index,
+                                label: *label,
+                            })
+                            .collect();
+                        let mut selected = current;
+                        if radio_button_group::radio_button_group(
+                            ui,
+                            "ui.debug_sidebar.display.safe_mode_sentinel",
+                            &mut selected,
+                            &options,
+                        ) && selected != current
+                            && let Some((_, sentinel)) = presets.get(selected)
+                        {
+                            *row_action.borrow_mut() = Some(SidebarAction::SetNonFiniteSanitize(
+                                NonFiniteSanitizeOptions {
+                                    sentinel: *sentinel,
+                                    ..sanitize
+                                },
+                            ));
+                        }
+                    });
+                });
+            }
+        });
+        if let Some(action) = row_action.into_inner() {
+            *sidebar_action = Some(action);
+        }
+    });
+}
+
+fn show_false_color_rows(
+    ui: &mut egui::Ui,
+    display: DisplaySidebarState,
+    sidebar_action: &mut Option<SidebarAction>,
+) {
+    sidebar_grid_row(ui, |row| {
+        row.place(1, 2, |ui| {
+            sidebar_group_cell(ui, "False Color", |ui| {
+                let mut enabled = display.false_color_enabled;
+                if radio_button_group::radio_button_group(
+                    ui,
+                    "ui.debug_sidebar.display.false_color",
+                    &mut enabled,
+                    &off_on_options(),
+                ) && enabled != display.false_color_enabled
+                {
+                    *sidebar_action = Some(SidebarAction::SetFalseColorEnabled(enabled));
+                }
+            });
+        });
     });
+    if !display.false_color_enabled {
+        return;
+    }
+    for first in (0..FalseColorSettings::EDGE_COUNT).step_by(2) {
+        ui.add_space(SIDEBAR_GRID_ROW_GAP);
+        let row_action = RefCell::new(None);
+        sidebar_grid_row(ui, |row| {
+            for index in first..(first + 2).min(FalseColorSettings::EDGE_COUNT) {
+                row.place(1 + (index - first) * 2, 2, |ui| {
+                    sidebar_group_cell(ui, FalseColorSettings::EDGE_LABELS[index], |ui| {
+                        let mut stops = display.false_color.edges[index];
+                        let changed = slider_with_value(
+                            ui,
+                            ("ui.debug_sidebar.display.false_color_edge", index),
+                            &mut stops,
+                            FalseColorSettings::MIN_STOPS,
+                            FalseColorSettings::MAX_STOPS,
+                            Some(&|v| format!("{v:+.1} st")),
+                        );
+                        if changed {
+                            *row_action.borrow_mut() =
+                                Some(SidebarAction::SetFalseColorEdge { index, stops });
+                        }
+                    });
+                });
+            }
+        });
+        if let Some(action) = row_action.into_inner() {
+            *sidebar_action = Some(action);
+        }
+    }
 }
 
 fn show_ref_section(
@@ -717,6 +1153,7 @@ fn show_ref_section(
         mode: RefImageMode::Overlay,
         opacity: 0.5,
         diff_metric_mode: DiffMetricMode::default(),
+        diff_color_space: DiffColorSpace::default(),
         diff_stats: None,
     });
     let ref_action = RefCell::new(None);
@@ -899,6 +1336,26 @@ fn show_ref_section(
                         }
                     }
                 });
+                if reference_state.mode == RefImageMode::Diff {
+                    ui.add_space(SIDEBAR_GRID_ROW_GAP);
+                    sidebar_grid_row(ui, |row| {
+                        row.place(3, 2, |ui| {
+                            sidebar_group_cell(ui, "Space", |ui| {
+                                let mut space = reference_state.diff_color_space;
+                                if radio_button_group::radio_button_group(
+                                    ui,
+                                    "ui.debug_sidebar.ref.space",
+                                    &mut space,
+                                    &diff_color_space_options(),
+                                ) && space != reference_state.diff_color_space
+                                {
+                                    *row_action.borrow_mut() =
+                                        Some(SidebarAction::SetDiffColorSpace(space));
+                                }
+                            });
+                        });
+                    });
+                }
             });
             if let Some(action) = row_action.into_inner() {
                 *ref_action.borrow_mut() = Some(action);
@@ -993,6 +1450,39 @@ fn show_clip_section(
                         });
                     });
                 });
+                ui.add_space(SIDEBAR_GRID_ROW_GAP);
+                sidebar_grid_row(ui, |row| {
+                    row.place(1, 2, |ui| {
+                        sidebar_group_cell(ui, "Mode", |ui| {
+                            let mut mode = analysis.clipping.mode;
+                            if radio_button_group::radio_button_group(
+                                ui,
+                                "ui.debug_sidebar.clip.mode",
+                                &mut mode,
+                                &clipping_mode_options(),
+                            ) && mode != analysis.clipping.mode
+                            {
+                                *row_action.borrow_mut() =
+                                    Some(SidebarAction::SetClippingMode(mode));
+                            }
+                        });
+                    });
+                    row.place(3, 2, |ui| {
+                        sidebar_group_cell(ui, "Style", |ui| {
+                            let mut style = analysis.clipping.style;
+                            if radio_button_group::radio_button_group(
+                                ui,
+                                "ui.debug_sidebar.clip.style",
+                                &mut style,
+                                &clipping_style_options(),
+                            ) && style != analysis.clipping.style
+                            {
+                                *row_action.borrow_mut() =
+                                    Some(SidebarAction::SetClippingStyle(style));
+                            }
+                        });
+                    });
+                });
             });
             if let Some(action) = row_action.into_inner() {
                 *clip_action.borrow_mut() = Some(action);
@@ -1319,6 +1809,7 @@ fn show_resource_tree_section(
     ui: &mut egui::Ui,
     pass_capture: PassCaptureSidebarState,
     tree_nodes: &[FileTreeNode],
+    pass_debug_overlays: &HashMap<String, PassDebugOverlay>,
     file_tree_state: &mut FileTreeState,
     sidebar_action: &mut Option<SidebarAction>,
 ) {
@@ -1349,7 +1840,12 @@ fn show_resource_tree_section(
             .id_salt("ui.debug_sidebar.resource_tree.scroll_x")
             .auto_shrink([false, false])
             .show(ui, |ui| {
-                super::file_tree_widget::show_file_tree(ui, tree_nodes, file_tree_state)
+                super::file_tree_widget::show_file_tree(
+                    ui,
+                    tree_nodes,
+                    pass_debug_overlays,
+                    file_tree_state,
+                )
             })
             .inner;
 
@@ -1362,6 +1858,11 @@ fn show_resource_tree_section(
             return;
         }
 
+        if let Some((pass_name, overlay)) = tree_response.set_pass_debug_overlay {
+            *sidebar_action = Some(SidebarAction::SetPassDebugOverlay { pass_name, overlay });
+            return;
+        }
+
         if let Some(target) = tree_response.open_pass_design {
             *sidebar_action = Some(SidebarAction::OpenPassDesign(target));
             return;
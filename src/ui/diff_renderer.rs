@@ -1,6 +1,6 @@
 use rust_wgpu_fiber::eframe::wgpu;
 
-use crate::app::{DiffMetricMode, DiffStats, RefImageMode};
+use crate::app::{DiffColorSpace, DiffMetricMode, DiffStats, RefImageMode};
 
 const WORKGROUP_SIZE_X: u32 = 16;
 const WORKGROUP_SIZE_Y: u32 = 16;
@@ -25,7 +25,10 @@ struct DiffParams {
     groups_x: u32,
     groups_y: u32,
     overlay_opacity: f32,
-    _padding: vec4<u32>,
+    color_space: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
 };
 
 @group(0) @binding(0)
@@ -88,6 +91,27 @@ fn metric_diff_rgba(render_rgba: vec4<f32>, ref_rgba: vec4<f32>, mode: u32) -> v
     return diff_rgba;
 }
 
+// Textures load as linear; space 1 re-encodes RGB with the sRGB transfer
+// function (unclamped, so HDR values stay comparable). Alpha is untouched.
+fn linear_to_srgb_channel(x: f32) -> f32 {
+    if (x <= 0.0031308) {
+        return 12.92 * x;
+    }
+    return 1.055 * pow(x, 1.0 / 2.4) - 0.055;
+}
+
+fn to_compare_space(rgba: vec4<f32>, space: u32) -> vec4<f32> {
+    if (space == 0u) {
+        return rgba;
+    }
+    return vec4<f32>(
+        linear_to_srgb_channel(rgba.x),
+        linear_to_srgb_channel(rgba.y),
+        linear_to_srgb_channel(rgba.z),
+        rgba.w
+    );
+}
+
 fn compose_overlay(render_rgba: vec4<f32>, ref_rgba: vec4<f32>, opacity: f32) -> vec4<f32> {
     let mix = clamp(opacity, 0.0, 1.0);
     return ref_rgba * mix + render_rgba * (1.0 - mix);
@@ -153,7 +177,11 @@ fn main(
                 display_rgba = overlay_rgba;
                 analysis_rgba = overlay_rgba;
             } else {
-                let metric_rgba = metric_diff_rgba(render_rgba, ref_rgba, params.metric_mode);
+                let metric_rgba = metric_diff_rgba(
+                    to_compare_space(render_rgba, params.color_space),
+                    to_compare_space(ref_rgba, params.color_space),
+                    params.metric_mode
+                );
                 display_rgba = metric_rgba;
                 analysis_rgba = metric_rgba;
             }
@@ -284,7 +312,8 @@ struct DiffParams {
     groups_x: u32,
     groups_y: u32,
     overlay_opacity: f32,
-    _padding: [u32; 4],
+    color_space: u32,
+    _padding: [u32; 3],
 }
 
 #[repr(C)]
@@ -712,6 +741,7 @@ impl DiffRenderer {
         compare_mode: RefImageMode,
         overlay_opacity: f32,
         metric_mode: DiffMetricMode,
+        color_space: DiffColorSpace,
         clamp_output: bool,
         collect_stats: bool,
     ) -> Option<DiffStats> {
@@ -759,7 +789,8 @@ impl DiffRenderer {
             groups_x: group_x,
             groups_y: group_y,
             overlay_opacity: overlay_opacity.clamp(0.0, 1.0),
-            _padding: [0, 0, 0, 0],
+            color_space: color_space.shader_code(),
+            _padding: [0, 0, 0],
         };
         queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
         queue.write_buffer(&self.histogram_buffer, 0, &self.histogram_clear_bytes);
@@ -939,7 +970,7 @@ mod tests {
         DiffRenderer, HIST_BIN_COUNT, HIST_INTERIOR_START_BIN, HIST_OVERFLOW_BIN, HIST_ZERO_BIN,
         select_diff_output_format,
     };
-    use crate::app::{DiffMetricMode, RefImageMode};
+    use crate::app::{DiffColorSpace, DiffMetricMode, RefImageMode};
     use rust_wgpu_fiber::eframe::wgpu;
 
     fn map_ref_xy(
@@ -1206,4 +1237,32 @@ mod tests {
             wgpu::TextureFormat::Rgba8Unorm
         );
     }
+
+    #[test]
+    fn diff_params_match_wgsl_uniform_layout() {
+        assert_eq!(std::mem::size_of::<super::DiffParams>(), 64);
+        for format in [
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::TextureFormat::Rgba16Float,
+        ] {
+            crate::renderer::validate_wgsl_module(&super::diff_compute_shader_source(format))
+                .unwrap_or_else(|e| panic!("{format:?}: {e:#}"));
+        }
+    }
+
+    #[test]
+    fn srgb_color_space_compares_encoded_rgb_and_keeps_alpha() {
+        let render = [0.18, 0.0, 1.0, 0.5];
+        let reference = [0.0, 0.0, 1.0, 0.25];
+        let linear = cpu_metric_diff_rgba(render, reference, DiffMetricMode::AE);
+        let srgb = cpu_metric_diff_rgba(
+            DiffColorSpace::Srgb.from_linear_rgba(render),
+            DiffColorSpace::Srgb.from_linear_rgba(reference),
+            DiffMetricMode::AE,
+        );
+        assert!((linear[0] - 0.18).abs() < 1e-6);
+        assert!((srgb[0] - 0.4614).abs() < 1e-3);
+        assert_eq!(srgb[2], 0.0);
+        assert_eq!(srgb[3], linear[3]);
+    }
 }
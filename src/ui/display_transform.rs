@@ -0,0 +1,327 @@
+use rust_wgpu_fiber::eframe::wgpu;
+
+use crate::app::{DisplayTransform, ViewTransformSettings};
+
+/// Viewer transform for the canvas: scale by the exposure, encode with the
+/// chosen transfer, apply the viewer gamma to the signal and decode it with
+/// the sRGB EOTF for the UI surface. sRGB therefore shows [0, 1] after
+/// exposure, while PQ and HLG show their encoded signal, which fits the
+/// whole HDR range on screen the way a log view does. The output is
+/// Rgba16Float so dark values do not band.
+const COMPUTE_SHADER_SRC: &str = r#"
+struct ViewParams {
+    size: vec2<u32>,
+    // 0 = sRGB, 1 = PQ, 2 = HLG
+    transform: u32,
+    _pad: u32,
+    exposure_scale: f32,
+    inv_gamma: f32,
+    _pad2: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var source_tex: texture_2d<f32>;
+
+@group(0) @binding(1)
+var out_tex: texture_storage_2d<rgba16float, write>;
+
+@group(0) @binding(2)
+var<uniform> params: ViewParams;
+
+fn srgb_oetf(x: vec3<f32>) -> vec3<f32> {
+    let c = clamp(x, vec3<f32>(0.0), vec3<f32>(1.0));
+    return select(1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055, c * 12.92, c <= vec3<f32>(0.0031308));
+}
+
+fn srgb_eotf(v: vec3<f32>) -> vec3<f32> {
+    let c = clamp(v, vec3<f32>(0.0), vec3<f32>(1.0));
+    return select(pow((c + 0.055) / 1.055, vec3<f32>(2.4)), c / 12.92, c <= vec3<f32>(0.04045));
+}
+
+// 1.0 is 203-nit reference white (BT.2408).
+fn pq_oetf(x: vec3<f32>) -> vec3<f32> {
+    let y = clamp(x * (203.0 / 10000.0), vec3<f32>(0.0), vec3<f32>(1.0));
+    let ym = pow(y, vec3<f32>(0.1593017578125));
+    return pow((0.8359375 + 18.8515625 * ym) / (1.0 + 18.6875 * ym), vec3<f32>(78.84375));
+}
+
+// 1.0 lands on the 75% reference-white signal (BT.2408).
+fn hlg_oetf(x: vec3<f32>) -> vec3<f32> {
+    let e = clamp(x * 0.2647, vec3<f32>(0.0), vec3<f32>(1.0));
+    let log_branch = 0.17883277 * log(max(12.0 * e - 0.28466892, vec3<f32>(1e-6))) + 0.55991073;
+    return select(log_branch, sqrt(3.0 * e), e <= vec3<f32>(1.0 / 12.0));
+}
+
+@compute @workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= params.size.x || gid.y >= params.size.y) {
+        return;
+    }
+
+    let src = textureLoad(source_tex, vec2<i32>(gid.xy), 0);
+    let linear = max(src.rgb, vec3<f32>(0.0)) * params.exposure_scale;
+    var signal = srgb_oetf(linear);
+    if (params.transform == 1u) {
+        signal = pq_oetf(linear);
+    } else if (params.transform == 2u) {
+        signal = hlg_oetf(linear);
+    }
+    signal = pow(signal, vec3<f32>(params.inv_gamma));
+
+    textureStore(
+        out_tex,
+        vec2<i32>(gid.xy),
+        vec4<f32>(srgb_eotf(signal), clamp(src.a, 0.0, 1.0))
+    );
+}
+"#;
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ViewParams {
+    size: [u32; 2],
+    transform: u32,
+    _pad: u32,
+    exposure_scale: f32,
+    inv_gamma: f32,
+    _pad2: [f32; 2],
+}
+
+pub struct DisplayTransformRenderer {
+    compute_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    params_buffer: wgpu::Buffer,
+    output_texture: wgpu::Texture,
+    output_view: wgpu::TextureView,
+    output_size: [u32; 2],
+}
+
+impl DisplayTransformRenderer {
+    pub fn new(device: &wgpu::Device, output_size: [u32; 2]) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("sys.preview.view_transform.compute"),
+            source: wgpu::ShaderSource::Wgsl(COMPUTE_SHADER_SRC.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sys.preview.view_transform.bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: wgpu::TextureFormat::Rgba16Float,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("sys.preview.view_transform.layout"),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("sys.preview.view_transform.pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sys.preview.view_transform.params"),
+            size: std::mem::size_of::<ViewParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let (output_texture, output_view, output_size) =
+            Self::create_output_texture(device, output_size);
+
+        Self {
+            compute_pipeline,
+            bind_group_layout,
+            params_buffer,
+            output_texture,
+            output_view,
+            output_size,
+        }
+    }
+
+    fn create_output_texture(
+        device: &wgpu::Device,
+        output_size: [u32; 2],
+    ) -> (wgpu::Texture, wgpu::TextureView, [u32; 2]) {
+        let output_size = [output_size[0].max(1), output_size[1].max(1)];
+        let output_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("sys.preview.view_transform.output"),
+            size: wgpu::Extent3d {
+                width: output_size[0],
+                height: output_size[1],
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+            view_formats: &[],
+        });
+        let output_view = output_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (output_texture, output_view, output_size)
+    }
+
+    fn ensure_output_size(&mut self, device: &wgpu::Device, output_size: [u32; 2]) {
+        let target = [output_size[0].max(1), output_size[1].max(1)];
+        if self.output_size == target {
+            return;
+        }
+        let (output_texture, output_view, output_size) =
+            Self::create_output_texture(device, output_size);
+        self.output_texture = output_texture;
+        self.output_view = output_view;
+        self.output_size = output_size;
+    }
+
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        source_view: &wgpu::TextureView,
+        source_size: [u32; 2],
+        settings: &ViewTransformSettings,
+    ) {
+        self.ensure_output_size(device, source_size);
+
+        let exposure_stops = settings.exposure_stops.clamp(
+            ViewTransformSettings::MIN_EXPOSURE_STOPS,
+            ViewTransformSettings::MAX_EXPOSURE_STOPS,
+        );
+        let gamma = settings.gamma.clamp(
+            ViewTransformSettings::MIN_GAMMA,
+            ViewTransformSettings::MAX_GAMMA,
+        );
+        let params = ViewParams {
+            size: self.output_size,
+            transform: match settings.transform {
+                DisplayTransform::Srgb => 0,
+                DisplayTransform::Pq => 1,
+                DisplayTransform::Hlg => 2,
+            },
+            _pad: 0,
+            exposure_scale: exposure_stops.exp2(),
+            inv_gamma: 1.0 / gamma,
+            _pad2: [0.0; 2],
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sys.preview.view_transform.bg"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&self.output_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &self.params_buffer,
+                        offset: 0,
+                        size: None,
+                    }),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("sys.preview.view_transform.encoder"),
+        });
+        {
+            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("sys.preview.view_transform.pass"),
+                timestamp_writes: None,
+            });
+            cpass.set_pipeline(&self.compute_pipeline);
+            cpass.set_bind_group(0, &bind_group, &[]);
+            let group_x = self.output_size[0].div_ceil(16);
+            let group_y = self.output_size[1].div_ceil(16);
+            cpass.dispatch_workgroups(group_x, group_y, 1);
+        }
+
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    pub fn output_view(&self) -> &wgpu::TextureView {
+        &self.output_view
+    }
+
+    pub fn output_texture(&self) -> &wgpu::Texture {
+        &self.output_texture
+    }
+
+    pub fn output_size(&self) -> [u32; 2] {
+        self.output_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn pq_oetf(x: f32) -> f32 {
+        let y = (x * (203.0 / 10000.0)).clamp(0.0, 1.0);
+        let ym = y.powf(0.159_301_76);
+        ((0.835_937_5 + 18.851_563 * ym) / (1.0 + 18.6875 * ym)).powf(78.84375)
+    }
+
+    fn hlg_oetf(x: f32) -> f32 {
+        let e = (x * 0.2647).clamp(0.0, 1.0);
+        if e <= 1.0 / 12.0 {
+            (3.0 * e).sqrt()
+        } else {
+            0.178_832_77 * (12.0 * e - 0.284_668_92).ln() + 0.559_910_7
+        }
+    }
+
+    #[test]
+    fn reference_white_hits_the_bt2408_signal_levels() {
+        assert!((pq_oetf(1.0) - 0.58).abs() < 0.01);
+        assert!((hlg_oetf(1.0) - 0.75).abs() < 0.01);
+    }
+
+    #[test]
+    fn pq_keeps_overbright_values_apart() {
+        assert!(pq_oetf(4.0) > pq_oetf(2.0));
+        assert!(pq_oetf(16.0) < 1.0);
+        assert!(pq_oetf(0.0) < 1e-5);
+    }
+}
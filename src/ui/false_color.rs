@@ -0,0 +1,291 @@
+use rust_wgpu_fiber::eframe::wgpu;
+
+use crate::app::FalseColorSettings;
+
+/// Maps Rec.709 luminance, in stops from 18% grey, to the usual false-color
+/// bands: purple crushed, blue under, grey, green middle grey, grey, yellow
+/// bright and red above the clip edge. The grey bands keep the luminance so
+/// the image stays readable between the marked ranges.
+const COMPUTE_SHADER_SRC: &str = r#"
+struct FalseColorParams {
+    size: vec2<u32>,
+    _pad: vec2<u32>,
+    edges_lo: vec4<f32>,
+    edges_hi: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var source_tex: texture_2d<f32>;
+
+@group(0) @binding(1)
+var out_tex: texture_storage_2d<rgba8unorm, write>;
+
+@group(0) @binding(2)
+var<uniform> params: FalseColorParams;
+
+@compute @workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= params.size.x || gid.y >= params.size.y) {
+        return;
+    }
+
+    let src = textureLoad(source_tex, vec2<i32>(gid.xy), 0);
+    let luma = max(dot(src.rgb, vec3<f32>(0.2126, 0.7152, 0.0722)), 0.0);
+    let stops = log2(max(luma, 1e-6) / 0.18);
+    let grey = vec3<f32>(clamp(luma, 0.0, 1.0));
+
+    var rgb = vec3<f32>(1.0, 0.0, 0.0);
+    if (stops < params.edges_lo.x) {
+        rgb = vec3<f32>(0.5, 0.0, 0.75);
+    } else if (stops < params.edges_lo.y) {
+        rgb = vec3<f32>(0.0, 0.35, 1.0);
+    } else if (stops < params.edges_lo.z) {
+        rgb = grey;
+    } else if (stops < params.edges_lo.w) {
+        rgb = vec3<f32>(0.2, 0.8, 0.2);
+    } else if (stops < params.edges_hi.x) {
+        rgb = grey;
+    } else if (stops < params.edges_hi.y) {
+        rgb = vec3<f32>(1.0, 0.9, 0.0);
+    }
+
+    textureStore(out_tex, vec2<i32>(gid.xy), vec4<f32>(rgb, clamp(src.a, 0.0, 1.0)));
+}
+"#;
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct FalseColorParams {
+    size: [u32; 2],
+    _pad: [u32; 2],
+    edges: [f32; 8],
+}
+
+pub struct FalseColorRenderer {
+    compute_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    params_buffer: wgpu::Buffer,
+    output_texture: wgpu::Texture,
+    output_view: wgpu::TextureView,
+    output_size: [u32; 2],
+}
+
+impl FalseColorRenderer {
+    pub fn new(device: &wgpu::Device, output_size: [u32; 2]) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("sys.preview.false_color.compute"),
+            source: wgpu::ShaderSource::Wgsl(COMPUTE_SHADER_SRC.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sys.preview.false_color.bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("sys.preview.false_color.layout"),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("sys.preview.false_color.pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sys.preview.false_color.params"),
+            size: std::mem::size_of::<FalseColorParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let (output_texture, output_view, output_size) =
+            Self::create_output_texture(device, output_size);
+
+        Self {
+            compute_pipeline,
+            bind_group_layout,
+            params_buffer,
+            output_texture,
+            output_view,
+            output_size,
+        }
+    }
+
+    fn create_output_texture(
+        device: &wgpu::Device,
+        output_size: [u32; 2],
+    ) -> (wgpu::Texture, wgpu::TextureView, [u32; 2]) {
+        let output_size = [output_size[0].max(1), output_size[1].max(1)];
+        let output_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("sys.preview.false_color.output"),
+            size: wgpu::Extent3d {
+                width: output_size[0],
+                height: output_size[1],
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+            view_formats: &[],
+        });
+        let output_view = output_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (output_texture, output_view, output_size)
+    }
+
+    fn ensure_output_size(&mut self, device: &wgpu::Device, output_size: [u32; 2]) {
+        let target = [output_size[0].max(1), output_size[1].max(1)];
+        if self.output_size == target {
+            return;
+        }
+        let (output_texture, output_view, output_size) =
+            Self::create_output_texture(device, output_size);
+        self.output_texture = output_texture;
+        self.output_view = output_view;
+        self.output_size = output_size;
+    }
+
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        source_view: &wgpu::TextureView,
+        source_size: [u32; 2],
+        settings: &FalseColorSettings,
+    ) {
+        self.ensure_output_size(device, source_size);
+
+        let mut edges = [0.0; 8];
+        edges[..FalseColorSettings::EDGE_COUNT].copy_from_slice(&settings.edges);
+        let params = FalseColorParams {
+            size: self.output_size,
+            _pad: [0; 2],
+            edges,
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sys.preview.false_color.bg"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&self.output_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &self.params_buffer,
+                        offset: 0,
+                        size: None,
+                    }),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("sys.preview.false_color.encoder"),
+        });
+        {
+            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("sys.preview.false_color.pass"),
+                timestamp_writes: None,
+            });
+            cpass.set_pipeline(&self.compute_pipeline);
+            cpass.set_bind_group(0, &bind_group, &[]);
+            let group_x = self.output_size[0].div_ceil(16);
+            let group_y = self.output_size[1].div_ceil(16);
+            cpass.dispatch_workgroups(group_x, group_y, 1);
+        }
+
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    pub fn output_view(&self) -> &wgpu::TextureView {
+        &self.output_view
+    }
+
+    pub fn output_texture(&self) -> &wgpu::Texture {
+        &self.output_texture
+    }
+
+    pub fn output_size(&self) -> [u32; 2] {
+        self.output_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::FalseColorSettings;
+
+    fn band_index(luma: f32, settings: &FalseColorSettings) -> usize {
+        let stops = (luma.max(1e-6) / 0.18).log2();
+        settings
+            .edges
+            .iter()
+            .take_while(|edge| stops >= **edge)
+            .count()
+    }
+
+    #[test]
+    fn middle_grey_lands_in_the_green_band() {
+        assert_eq!(band_index(0.18, &FalseColorSettings::default()), 3);
+    }
+
+    #[test]
+    fn linear_white_and_above_is_clipped() {
+        let settings = FalseColorSettings::default();
+        assert_eq!(band_index(1.0, &settings), 6);
+        assert_eq!(band_index(4.0, &settings), 6);
+        assert_eq!(band_index(0.9, &settings), 5);
+    }
+
+    #[test]
+    fn black_is_crushed() {
+        let settings = FalseColorSettings::default();
+        assert_eq!(band_index(0.0, &settings), 0);
+        assert_eq!(band_index(0.18 / 32.0, &settings), 1);
+    }
+}
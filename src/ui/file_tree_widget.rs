@@ -3,10 +3,13 @@
 //! All rendering is done with `egui::Painter` to give pixel-precise control over
 //! row highlight, chevron, icon, label, and detail text.
 
+use std::collections::HashMap;
+
 use rust_wgpu_fiber::eframe::egui::{self, Color32, Pos2, Rect, Vec2};
 
 use super::design_tokens::{self, TextRole};
 use super::resource_tree::{FileTreeNode, NodeKind, PassDesignTarget, TreeIcon};
+use crate::renderer::{PassDebugOverlay, pass_debug_color};
 
 // ---------------------------------------------------------------------------
 // Style constants
@@ -50,6 +53,8 @@ pub struct FileTreeResponse {
     pub copied_texture_name: Option<String>,
     pub open_pass_debug: Option<String>,
     pub open_pass_design: Option<PassDesignTarget>,
+    /// Pass name and the overlay to apply (`None` clears it).
+    pub set_pass_debug_overlay: Option<(String, Option<PassDebugOverlay>)>,
 }
 
 struct VisibleTreeEntry {
@@ -66,6 +71,7 @@ struct VisibleTreeEntry {
 pub fn show_file_tree(
     ui: &mut egui::Ui,
     nodes: &[FileTreeNode],
+    pass_debug_overlays: &HashMap<String, PassDebugOverlay>,
     state: &mut FileTreeState,
 ) -> FileTreeResponse {
     let mut response = FileTreeResponse {
@@ -73,6 +79,7 @@ pub fn show_file_tree(
         copied_texture_name: None,
         open_pass_debug: None,
         open_pass_design: None,
+        set_pass_debug_overlay: None,
     };
     let root_path = ui.id().with("file_tree_root");
     let mut visible_entries: Vec<VisibleTreeEntry> = Vec::new();
@@ -83,6 +90,7 @@ pub fn show_file_tree(
             node,
             0,
             None,
+            pass_debug_overlays,
             state,
             &mut response,
             &mut visible_entries,
@@ -232,6 +240,7 @@ fn draw_node(
     node: &FileTreeNode,
     depth: usize,
     parent_id: Option<&str>,
+    pass_debug_overlays: &HashMap<String, PassDebugOverlay>,
     state: &mut FileTreeState,
     response: &mut FileTreeResponse,
     visible_entries: &mut Vec<VisibleTreeEntry>,
@@ -273,6 +282,11 @@ fn draw_node(
     let row_id = node_path.with("row");
     let row_response = ui.interact(row_rect, row_id, egui::Sense::click());
 
+    let active_overlay = match &node.kind {
+        NodeKind::Pass { pass_name, .. } => pass_debug_overlays.get(pass_name).copied(),
+        _ => None,
+    };
+
     match &node.kind {
         NodeKind::Texture { texture_name } => {
             row_response.context_menu(|ui| {
@@ -309,6 +323,26 @@ fn draw_node(
                     });
                     ui.close();
                 }
+                ui.separator();
+                ui.menu_button("Debug Overlay", |ui| {
+                    for overlay in PassDebugOverlay::ALL {
+                        if ui
+                            .radio(active_overlay == Some(overlay), overlay.label())
+                            .clicked()
+                        {
+                            response.set_pass_debug_overlay =
+                                Some((pass_name.clone(), Some(overlay)));
+                            ui.close();
+                        }
+                    }
+                    if ui
+                        .add_enabled(active_overlay.is_some(), egui::Button::new("Off"))
+                        .clicked()
+                    {
+                        response.set_pass_debug_overlay = Some((pass_name.clone(), None));
+                        ui.close();
+                    }
+                });
             });
         }
         _ => {}
@@ -432,6 +466,23 @@ fn draw_node(
     } else {
         draw_icon(&painter, cx, cy, resolved_icon);
     }
+    if active_overlay.is_some()
+        && let NodeKind::Pass { pass_name, .. } = &node.kind
+    {
+        let [r, g, b] = pass_debug_color(pass_name);
+        painter.circle_stroke(
+            Pos2::new(cx + ICON_SIZE * 0.5, cy),
+            ICON_SIZE * 0.5,
+            egui::Stroke::new(
+                1.5_f32,
+                Color32::from_rgb(
+                    (r * 255.0).round() as u8,
+                    (g * 255.0).round() as u8,
+                    (b * 255.0).round() as u8,
+                ),
+            ),
+        );
+    }
     cx += ICON_SIZE + GAP_ICON_LABEL;
 
     // --- Label ---
@@ -468,6 +519,7 @@ fn draw_node(
                         child,
                         depth + 1,
                         Some(node.id.as_str()),
+                        pass_debug_overlays,
                         state,
                         response,
                         visible_entries,
@@ -241,6 +241,18 @@ struct RenderParams {
     _pad0: [u32; 2],
 }
 
+/// How the 256 bins per channel of the last [`HistogramRenderer::update`]
+/// map to values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HistogramRange {
+    /// The source had values outside [0, 1]: bins are uniform in
+    /// `sign(v) * ln(1 + |v| / 0.001)` between `min_value` and `max_value`.
+    /// Otherwise bins are uniform over [0, 1].
+    pub hdr: bool,
+    pub min_value: f32,
+    pub max_value: f32,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum HistogramMode {
     Sdr = 0,
@@ -659,7 +671,7 @@ impl HistogramRenderer {
         queue: &wgpu::Queue,
         source_view: &wgpu::TextureView,
         source_size: [u32; 2],
-    ) {
+    ) -> HistogramRange {
         let stats_init = [u32::MAX, 0, 0, 0];
         queue.write_buffer(&self.stats_buffer, 0, bytemuck::cast_slice(&stats_init));
 
@@ -874,6 +886,25 @@ impl HistogramRenderer {
         }
 
         queue.submit(std::iter::once(render_encoder.finish()));
+
+        if mode == HistogramMode::Hdr {
+            HistogramRange {
+                hdr: true,
+                min_value: symmetric_log_inverse(min_log),
+                max_value: symmetric_log_inverse(min_log + diff_log),
+            }
+        } else {
+            HistogramRange {
+                hdr: false,
+                min_value: 0.0,
+                max_value: 1.0,
+            }
+        }
+    }
+
+    /// Raw counts of the last update: 256 red, then green, then blue bins.
+    pub fn read_bins(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Vec<u32>> {
+        read_buffer_words(device, queue, &self.histogram_buffer, HISTOGRAM_WORD_COUNT)
     }
 
     pub fn output_view(&self) -> &wgpu::TextureView {
@@ -889,6 +920,35 @@ impl HistogramRenderer {
     }
 }
 
+/// Copies `word_count` u32s out of a `COPY_SRC` storage buffer, for scope
+/// data requested over WS rather than drawn in the sidebar.
+pub(crate) fn read_buffer_words(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    source: &wgpu::Buffer,
+    word_count: usize,
+) -> Option<Vec<u32>> {
+    let size = (word_count * std::mem::size_of::<u32>()) as u64;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("sys.scope.bins.readback"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("sys.scope.bins.readback.encoder"),
+    });
+    encoder.copy_buffer_to_buffer(source, 0, &readback, 0, size);
+    queue.submit(std::iter::once(encoder.finish()));
+    let bytes = HistogramRenderer::map_readback_buffer(device, &readback, size)?;
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|word| u32::from_ne_bytes([word[0], word[1], word[2], word[3]]))
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,11 +1,14 @@
 pub mod animation_manager;
 pub mod button;
+pub mod channel_isolate;
 pub mod clipping_map;
 pub mod color_popover;
 pub mod components;
 pub mod debug_sidebar;
 pub mod design_tokens;
 pub mod diff_renderer;
+pub mod display_transform;
+pub mod false_color;
 pub mod file_tree_widget;
 pub mod hdr_clamp;
 pub mod histogram;
@@ -1,8 +1,8 @@
 use rust_wgpu_fiber::eframe::wgpu;
 
-const X_BINS: u32 = 512;
-const Y_BINS: u32 = 256;
-const PLANES: u32 = 3;
+pub(crate) const X_BINS: u32 = 512;
+pub(crate) const Y_BINS: u32 = 256;
+pub(crate) const PLANES: u32 = 3;
 #[cfg(test)]
 const PLANE_WORD_COUNT: usize = (X_BINS * Y_BINS) as usize;
 const TOTAL_WORD_COUNT: usize = (X_BINS * Y_BINS * PLANES) as usize;
@@ -140,7 +140,9 @@ impl ParadeRenderer {
         let bins_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("sys.scope.parade.buffer"),
             size: (std::mem::size_of::<u32>() * TOTAL_WORD_COUNT) as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
@@ -404,6 +406,13 @@ impl ParadeRenderer {
     pub fn parade_output_texture(&self) -> &wgpu::Texture {
         &self.parade_output_texture
     }
+
+    /// Raw counts of the last update: `PLANES` planes (R, G, B), each
+    /// `X_BINS` image columns of `Y_BINS` value bins, top down: bin 0 holds
+    /// value 1.0 and bin 255 value 0.
+    pub fn read_bins(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Vec<u32>> {
+        super::histogram::read_buffer_words(device, queue, &self.bins_buffer, TOTAL_WORD_COUNT)
+    }
 }
 
 #[cfg(test)]
@@ -822,6 +822,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let labels = pass_display_labels_by_pass(&scene);
@@ -855,6 +856,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let sources = pass_source_metadata_by_pass(&scene);
@@ -891,6 +893,7 @@ mod tests {
             assets: HashMap::new(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
 
         let sources = pass_source_metadata_by_pass(&scene);
@@ -1,6 +1,6 @@
 use rust_wgpu_fiber::eframe::wgpu;
 
-const VECTORSCOPE_BINS: u32 = 256;
+pub(crate) const VECTORSCOPE_BINS: u32 = 256;
 const VECTORSCOPE_WORD_COUNT: usize = (VECTORSCOPE_BINS * VECTORSCOPE_BINS) as usize;
 const VECTORSCOPE_BYTE_COUNT: usize = VECTORSCOPE_WORD_COUNT * std::mem::size_of::<u32>();
 const VECTORSCOPE_OUTPUT_SIZE: [u32; 2] = [512, 512];
@@ -140,7 +140,9 @@ impl VectorscopeRenderer {
         let bins_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("sys.scope.vectorscope.buffer"),
             size: (std::mem::size_of::<u32>() * VECTORSCOPE_WORD_COUNT) as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
@@ -404,6 +406,17 @@ impl VectorscopeRenderer {
     pub fn output_texture(&self) -> &wgpu::Texture {
         &self.output_texture
     }
+
+    /// Raw counts of the last update: `VECTORSCOPE_BINS` rows of Cr by
+    /// `VECTORSCOPE_BINS` columns of Cb, both mapped from [-0.5, 0.5].
+    pub fn read_bins(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Vec<u32>> {
+        super::histogram::read_buffer_words(
+            device,
+            queue,
+            &self.bins_buffer,
+            VECTORSCOPE_WORD_COUNT,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,220 @@
+//! `get_analysis`: the sidebar scopes' raw data (histogram, parade and
+//! vectorscope bins, reference diff stats), so editors can draw their own
+//! scopes instead of screenshotting the sidebar.
+//!
+//! Histogram and diff stats reply with JSON `analysis_result` messages. Parade
+//! and vectorscope bins are large and reply with a binary `analysis_result`
+//! message whose payload is the counts as little-endian u32, laid out by the
+//! header's `shape`: parade is `[channel, column, value]` with values top
+//! down (bin 0 is 1.0), vectorscope is `[1, Cr, Cb]` with both chroma axes
+//! mapped from [-0.5, 0.5].
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisKind {
+    Histogram,
+    Parade,
+    Vectorscope,
+    DiffStats,
+}
+
+/// `get_analysis` payload.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AnalysisRequest {
+    pub kind: AnalysisKind,
+    /// Texture to analyse; defaults to the displayed output. Ignored for
+    /// `diff_stats`, which reports the current reference comparison.
+    #[serde(default)]
+    pub texture: Option<String>,
+}
+
+impl AnalysisRequest {
+    pub(super) fn parse(payload: Option<Value>) -> Result<Self> {
+        let payload = payload.context("get_analysis missing payload")?;
+        Ok(serde_json::from_value(payload)?)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HistogramPayload<'a> {
+    kind: AnalysisKind,
+    texture: &'a str,
+    /// `sdr`: bins are uniform over [0, 1]. `hdr`: bins are uniform in
+    /// `sign(v) * ln(1 + |v| / 0.001)` between `minValue` and `maxValue`.
+    mode: &'static str,
+    min_value: f32,
+    max_value: f32,
+    bin_count: usize,
+    r: &'a [u32],
+    g: &'a [u32],
+    b: &'a [u32],
+}
+
+/// Answers a histogram `get_analysis` with per-channel counts. `bins` holds
+/// the red, green and blue bins back to back.
+pub fn broadcast_histogram_analysis(
+    hub: &WsHub,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    texture: &str,
+    range: crate::ui::histogram::HistogramRange,
+    bins: &[u32],
+) {
+    let bin_count = bins.len() / 3;
+    let message = WSMessage {
+        msg_type: "analysis_result".to_string(),
+        timestamp: now_millis(),
+        request_id,
+        payload: Some(HistogramPayload {
+            kind: AnalysisKind::Histogram,
+            texture,
+            mode: if range.hdr { "hdr" } else { "sdr" },
+            min_value: range.min_value,
+            max_value: range.max_value,
+            bin_count,
+            r: &bins[..bin_count],
+            g: &bins[bin_count..bin_count * 2],
+            b: &bins[bin_count * 2..bin_count * 3],
+        }),
+    };
+    if let Ok(text) = serde_json::to_string(&message) {
+        hub.reply(client_id, text);
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScopeBinsHeader<'a> {
+    #[serde(rename = "type")]
+    frame_type: &'static str,
+    request_id: Option<String>,
+    kind: AnalysisKind,
+    texture: &'a str,
+    /// Bin grid dimensions, outermost first.
+    shape: [u32; 3],
+    dtype: &'static str,
+    timestamp: u64,
+}
+
+/// Answers a parade or vectorscope `get_analysis` with a binary message;
+/// `shape` describes the layout of `bins`, outermost dimension first.
+pub fn broadcast_scope_bins(
+    hub: &WsHub,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    kind: AnalysisKind,
+    texture: &str,
+    shape: [u32; 3],
+    bins: &[u32],
+) -> Result<()> {
+    let payload: Vec<u8> = bins.iter().flat_map(|count| count.to_le_bytes()).collect();
+    let frame = binary_message(
+        &ScopeBinsHeader {
+            frame_type: "analysis_result",
+            request_id,
+            kind,
+            texture,
+            shape,
+            dtype: "u32le",
+            timestamp: now_millis(),
+        },
+        &payload,
+    )?;
+    hub.reply_binary(client_id, frame);
+    Ok(())
+}
+
+/// Reference diff statistics as shown in the sidebar.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffStatsAnalysis {
+    pub metric: String,
+    pub color_space: String,
+    pub min: f32,
+    pub max: f32,
+    pub avg: f32,
+    pub rms: f32,
+    pub p95_abs: f32,
+    pub sample_count: u64,
+    pub non_finite_count: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiffStatsPayload<'a> {
+    kind: AnalysisKind,
+    #[serde(flatten)]
+    stats: &'a DiffStatsAnalysis,
+}
+
+pub fn broadcast_diff_stats_analysis(
+    hub: &WsHub,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    stats: &DiffStatsAnalysis,
+) {
+    let message = WSMessage {
+        msg_type: "analysis_result".to_string(),
+        timestamp: now_millis(),
+        request_id,
+        payload: Some(DiffStatsPayload {
+            kind: AnalysisKind::DiffStats,
+            stats,
+        }),
+    };
+    if let Ok(text) = serde_json::to_string(&message) {
+        hub.reply(client_id, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_parses_kind_and_optional_texture() {
+        let request =
+            AnalysisRequest::parse(Some(serde_json::json!({ "kind": "diff_stats" }))).unwrap();
+        assert_eq!(request.kind, AnalysisKind::DiffStats);
+        assert_eq!(request.texture, None);
+
+        let request = AnalysisRequest::parse(Some(
+            serde_json::json!({ "kind": "parade", "texture": "blur.tex" }),
+        ))
+        .unwrap();
+        assert_eq!(request.texture.as_deref(), Some("blur.tex"));
+
+        assert!(AnalysisRequest::parse(Some(serde_json::json!({ "kind": "waveform" }))).is_err());
+        assert!(AnalysisRequest::parse(None).is_err());
+    }
+
+    #[test]
+    fn scope_bins_payload_is_little_endian_u32() {
+        let hub = WsHub::default();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        hub.register_client(tx);
+        broadcast_scope_bins(
+            &hub,
+            Some("r1".to_string()),
+            None,
+            AnalysisKind::Vectorscope,
+            "out",
+            [1, 2, 1],
+            &[1, 0x0102_0304],
+        )
+        .unwrap();
+
+        let Ok(Message::Binary(frame)) = rx.try_recv() else {
+            panic!("expected a binary message");
+        };
+        let (frame_type, header, payload) = parse_binary_frame_header(&frame).unwrap();
+        assert_eq!(frame_type, "analysis_result");
+        assert_eq!(header["kind"], "vectorscope");
+        assert_eq!(header["shape"], serde_json::json!([1, 2, 1]));
+        assert_eq!(payload, [1, 0, 0, 0, 4, 3, 2, 1]);
+    }
+}
@@ -3,9 +3,11 @@ use std::collections::{HashMap, HashSet};
 use anyhow::{Context, Result, anyhow, bail};
 use tungstenite::Message;
 
-use crate::asset_store::{AssetData, AssetStore};
+use crate::asset_store::{AssetData, AssetStore, AssetSummary};
 use crate::protocol::{WSMessage, now_millis};
 
+use super::ClientSink;
+
 // ---------------------------------------------------------------------------
 // Constants
 // ---------------------------------------------------------------------------
@@ -119,6 +121,29 @@ pub(super) struct AssetUploadEndPayload {
     pub(super) asset_id: String,
 }
 
+/// Header of an `upload_asset` binary frame, whose payload is the whole asset.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub(super) struct UploadAssetHeader {
+    #[serde(rename = "assetId")]
+    pub(super) asset_id: String,
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    #[serde(rename = "originalName", default)]
+    original_name: String,
+    #[serde(rename = "requestId", default)]
+    pub(super) request_id: Option<String>,
+}
+
+impl UploadAssetHeader {
+    pub(super) fn into_asset_data(self, bytes: Vec<u8>) -> AssetData {
+        AssetData {
+            bytes,
+            mime_type: self.mime_type,
+            original_name: self.original_name,
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Deserialize)]
 struct AssetUploadChunkHeader {
     #[serde(rename = "type")]
@@ -481,6 +506,16 @@ impl AssetTransferState {
         }
     }
 
+    /// A whole asset arrived in one `upload_asset` frame, superseding any
+    /// chunked upload of the same id.
+    pub(super) fn on_asset_uploaded(&mut self, asset_id: &str, now_ms: u64) {
+        self.uploads.remove(asset_id);
+        self.entries
+            .entry(asset_id.to_string())
+            .or_default()
+            .mark_ready(now_ms);
+    }
+
     pub(super) fn on_asset_removed(&mut self, asset_id: &str) {
         self.entries.remove(asset_id);
         self.uploads.remove(asset_id);
@@ -495,7 +530,7 @@ impl AssetTransferState {
 /// `[header_len: u32 BE][JSON header (UTF-8)][raw chunk bytes]`
 /// where the JSON header `type` is `asset_upload_chunk`.
 pub(super) fn handle_binary_asset_upload(
-    ws: &mut tungstenite::WebSocket<std::net::TcpStream>,
+    ws: &mut dyn ClientSink,
     data: &[u8],
     transfer_state: &mut AssetTransferState,
     asset_store: &AssetStore,
@@ -550,7 +585,7 @@ pub(super) fn handle_binary_asset_upload(
 }
 
 pub(super) fn request_missing_assets(
-    ws: &mut tungstenite::WebSocket<std::net::TcpStream>,
+    ws: &mut dyn ClientSink,
     transfer_state: &mut AssetTransferState,
     asset_store: &AssetStore,
     referenced_ids: &[String],
@@ -570,7 +605,7 @@ pub(super) fn request_missing_assets(
 }
 
 /// Send an `asset_request` message to the editor for a list of missing asset IDs.
-fn send_asset_request(ws: &mut tungstenite::WebSocket<std::net::TcpStream>, asset_ids: &[String]) {
+fn send_asset_request(ws: &mut dyn ClientSink, asset_ids: &[String]) {
     #[derive(serde::Serialize)]
     struct AssetRequestPayload {
         #[serde(rename = "assetIds")]
@@ -591,10 +626,7 @@ fn send_asset_request(ws: &mut tungstenite::WebSocket<std::net::TcpStream>, asse
     }
 }
 
-pub(super) fn send_asset_upload_ack(
-    ws: &mut tungstenite::WebSocket<std::net::TcpStream>,
-    asset_id: &str,
-) {
+pub(super) fn send_asset_upload_ack(ws: &mut dyn ClientSink, asset_id: &str) {
     #[derive(serde::Serialize)]
     struct AssetUploadAckPayload {
         #[serde(rename = "assetId")]
@@ -615,8 +647,33 @@ pub(super) fn send_asset_upload_ack(
     }
 }
 
+/// Answers `list_assets` with the metadata of every asset in the store.
+pub(super) fn send_asset_list(
+    ws: &mut dyn ClientSink,
+    request_id: Option<String>,
+    asset_store: &AssetStore,
+) {
+    #[derive(serde::Serialize)]
+    struct AssetListPayload {
+        assets: Vec<AssetSummary>,
+    }
+
+    let list = WSMessage {
+        msg_type: "asset_list".to_string(),
+        timestamp: now_millis(),
+        request_id,
+        payload: Some(AssetListPayload {
+            assets: asset_store.summaries(),
+        }),
+    };
+
+    if let Ok(text) = serde_json::to_string(&list) {
+        let _ = ws.send(Message::Text(text));
+    }
+}
+
 pub(super) fn send_asset_upload_nack(
-    ws: &mut tungstenite::WebSocket<std::net::TcpStream>,
+    ws: &mut dyn ClientSink,
     asset_id: &str,
     missing_chunks: &[u32],
     reason: &str,
@@ -655,6 +712,42 @@ mod tests {
     use super::*;
     use crate::asset_store::AssetStore;
 
+    #[test]
+    fn single_frame_upload_supersedes_chunked_upload() {
+        let mut state = AssetTransferState::default();
+        state
+            .on_upload_start(
+                AssetUploadStartPayload {
+                    asset_id: "lut".to_string(),
+                    mime_type: "image/png".to_string(),
+                    original_name: "lut.png".to_string(),
+                    size: 6,
+                    chunk_size: 4,
+                    total_chunks: 2,
+                },
+                100,
+            )
+            .unwrap();
+
+        let header: UploadAssetHeader = serde_json::from_value(serde_json::json!({
+            "type": "upload_asset",
+            "assetId": "lut",
+            "mimeType": "image/png"
+        }))
+        .unwrap();
+        state.on_asset_uploaded(&header.asset_id, 120);
+        assert!(!state.uploads.contains_key("lut"));
+        assert!(matches!(
+            state.on_upload_end("lut", 130),
+            UploadFinalizeResult::NotStarted
+        ));
+
+        let data = header.into_asset_data(vec![1, 2, 3]);
+        assert_eq!(data.mime_type, "image/png");
+        assert_eq!(data.original_name, "");
+        assert_eq!(data.bytes, [1, 2, 3]);
+    }
+
     #[test]
     fn asset_transfer_state_reassembles_chunks_and_completes() {
         let mut state = AssetTransferState::default();
@@ -0,0 +1,241 @@
+//! Optional connection gating for the WS server (`--ws-token`,
+//! `--ws-allow-origin`). The UI server listens on every interface, so without
+//! this anyone on the LAN can replace the scene.
+//!
+//! A client presents the token either as `?token=<secret>` on the connect URL
+//! or, when the query is not an option, as the first message:
+//! `{"type":"auth","payload":{"token":"<secret>"}}`. Tokens are compared
+//! verbatim, so pick a URL-safe secret. The origin allow-list only applies to
+//! browser clients; connections without an `Origin` header rely on the token.
+
+use std::time::Duration;
+
+use tungstenite::{
+    WebSocket,
+    handshake::server::{ErrorResponse, Request, Response},
+    http::{HeaderValue, StatusCode},
+};
+
+use super::*;
+
+/// How long a client that did not send the token in the query has to send
+/// its `auth` message.
+const AUTH_MESSAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default)]
+pub struct WsAccessPolicy {
+    /// Shared secret every client must present; `None` leaves the server open.
+    pub token: Option<String>,
+    /// Accepted `Origin` header values; empty accepts any origin.
+    pub allowed_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct AuthPayload {
+    token: String,
+}
+
+/// Handshake verdict for a connection that passed the origin check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HandshakeAccess {
+    Granted,
+    /// The token was not in the query; the first message must carry it.
+    AwaitingToken,
+}
+
+impl WsAccessPolicy {
+    fn token_matches(&self, presented: &str) -> bool {
+        self.token
+            .as_deref()
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), presented.as_bytes()))
+    }
+
+    /// Checks the upgrade request's `Origin` header and `token` query
+    /// parameter.
+    pub(super) fn check_handshake(
+        &self,
+        request: &Request,
+    ) -> std::result::Result<HandshakeAccess, (StatusCode, String)> {
+        if !self.allowed_origins.is_empty()
+            && let Some(origin) = request.headers().get("origin")
+        {
+            let origin = origin.to_str().unwrap_or_default();
+            if !self.allowed_origins.iter().any(|allowed| allowed == origin) {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    format!("origin {origin:?} not allowed"),
+                ));
+            }
+        }
+        if self.token.is_none() {
+            return Ok(HandshakeAccess::Granted);
+        }
+        let query_token = request.uri().query().and_then(|query| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("token="))
+        });
+        match query_token {
+            Some(token) if self.token_matches(token) => Ok(HandshakeAccess::Granted),
+            Some(_) => Err((StatusCode::UNAUTHORIZED, "invalid token".to_string())),
+            None => Ok(HandshakeAccess::AwaitingToken),
+        }
+    }
+
+    /// Checks the first message of a connection that did not authenticate in
+    /// the query.
+    fn check_auth_message(&self, text: &str) -> std::result::Result<(), &'static str> {
+        let msg: WSMessage<Value> =
+            serde_json::from_str(text).map_err(|_| "expected an auth message")?;
+        if msg.msg_type != "auth" {
+            return Err("expected an auth message before any other request");
+        }
+        let payload = msg
+            .payload
+            .and_then(|payload| serde_json::from_value::<AuthPayload>(payload).ok())
+            .ok_or("auth message missing token")?;
+        if self.token_matches(&payload.token) {
+            Ok(())
+        } else {
+            Err("invalid token")
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Upgrades `stream`, rejecting the handshake when the origin or query token
+/// is refused, and negotiates payload compression.
+pub(super) fn accept_with_policy(
+    stream: std::net::TcpStream,
+    policy: &WsAccessPolicy,
+) -> Result<(
+    WebSocket<std::net::TcpStream>,
+    HandshakeAccess,
+    WsCompression,
+)> {
+    let mut access = HandshakeAccess::Granted;
+    let mut compression = WsCompression::None;
+    let check = |request: &Request, mut response: Response| match policy.check_handshake(request) {
+        Ok(verdict) => {
+            access = verdict;
+            compression = WsCompression::negotiate(request.uri().query());
+            if compression == WsCompression::Gzip {
+                response
+                    .headers_mut()
+                    .insert(COMPRESSION_HEADER, HeaderValue::from_static("gzip"));
+            }
+            Ok(response)
+        }
+        Err((status, reason)) => {
+            let mut rejection = ErrorResponse::new(Some(reason));
+            *rejection.status_mut() = status;
+            Err(rejection)
+        }
+    };
+    let ws = tungstenite::accept_hdr(stream, check)
+        .map_err(|e| anyhow::anyhow!("websocket handshake failed: {e}"))?;
+    Ok((ws, access, compression))
+}
+
+/// Waits for the `auth` message on a still-blocking socket. Replies with an
+/// `UNAUTHORIZED` error and closes the connection when it is missing or wrong.
+pub(super) fn authenticate_first_message(
+    ws: &mut WebSocket<std::net::TcpStream>,
+    policy: &WsAccessPolicy,
+) -> Result<()> {
+    ws.get_mut()
+        .set_read_timeout(Some(AUTH_MESSAGE_TIMEOUT))
+        .context("failed to set auth read timeout")?;
+    let verdict = match ws.read() {
+        Ok(Message::Text(text)) => policy.check_auth_message(&text),
+        Ok(_) => Err("expected an auth message"),
+        Err(WsError::Io(ref io))
+            if matches!(
+                io.kind(),
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+            ) =>
+        {
+            Err("timed out waiting for auth message")
+        }
+        Err(e) => return Err(e).context("websocket read failed during auth"),
+    };
+    ws.get_mut()
+        .set_read_timeout(None)
+        .context("failed to clear auth read timeout")?;
+    if let Err(reason) = verdict {
+        send_error(ws, None, "UNAUTHORIZED", reason);
+        let _ = ws.close(None);
+        let _ = ws.flush();
+        anyhow::bail!("client rejected: {reason}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(uri: &str, origin: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(origin) = origin {
+            builder = builder.header("Origin", origin);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn handshake_checks_origin_and_query_token() {
+        let policy = WsAccessPolicy {
+            token: Some("s3cret".to_string()),
+            allowed_origins: vec!["http://localhost:5173".to_string()],
+        };
+        assert_eq!(
+            policy.check_handshake(&request("/?token=s3cret", Some("http://localhost:5173"))),
+            Ok(HandshakeAccess::Granted)
+        );
+        assert_eq!(
+            policy.check_handshake(&request("/", None)),
+            Ok(HandshakeAccess::AwaitingToken)
+        );
+        assert_eq!(
+            policy
+                .check_handshake(&request("/?token=nope", None))
+                .unwrap_err()
+                .0,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            policy
+                .check_handshake(&request("/?token=s3cret", Some("http://evil.example")))
+                .unwrap_err()
+                .0,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            WsAccessPolicy::default().check_handshake(&request("/", Some("http://evil.example"))),
+            Ok(HandshakeAccess::Granted)
+        );
+    }
+
+    #[test]
+    fn auth_message_must_come_first_and_match() {
+        let policy = WsAccessPolicy {
+            token: Some("s3cret".to_string()),
+            allowed_origins: Vec::new(),
+        };
+        let auth = |token: &str| {
+            serde_json::json!({ "type": "auth", "timestamp": 0, "payload": { "token": token } })
+                .to_string()
+        };
+        assert!(policy.check_auth_message(&auth("s3cret")).is_ok());
+        assert!(policy.check_auth_message(&auth("s3cre")).is_err());
+        assert!(
+            policy
+                .check_auth_message(r#"{"type":"scene_request","timestamp":0}"#)
+                .is_err()
+        );
+    }
+}
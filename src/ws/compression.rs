@@ -0,0 +1,179 @@
+//! Gzip-wrapped messages for large payloads. tungstenite has no
+//! permessage-deflate, so compression is an explicit frame type instead.
+//!
+//! A client opts in at connect time with `?compression=gzip`; the handshake
+//! response then carries `x-node-forge-compression: gzip` and the client's
+//! `client_connected` notice reports `compression: "gzip"`.
+//!
+//! Either side may send a binary message with the header
+//! `{"type":"compressed","encoding":"gzip","content":"text"|"binary"}`
+//! followed by the gzip stream of the original message: the JSON text of a
+//! text message, or the complete bytes of a binary message. Clients may always
+//! send compressed messages; the server only compresses large text messages
+//! and raw RGBA frames for clients that opted in. Already-encoded PNG/JPEG
+//! payloads are sent as they are.
+
+use std::io::{Read, Write};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+use super::*;
+
+/// Response header confirming the negotiated compression.
+pub(super) const COMPRESSION_HEADER: &str = "x-node-forge-compression";
+
+/// Outbound messages smaller than this are not worth compressing.
+const COMPRESS_MIN_BYTES: usize = 64 * 1024;
+
+/// Upper bound for a decompressed inbound message, against gzip bombs.
+const MAX_DECOMPRESSED_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsCompression {
+    #[default]
+    None,
+    Gzip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CompressedContent {
+    Text,
+    Binary,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct CompressedHeader {
+    #[serde(rename = "type")]
+    frame_type: String,
+    encoding: WsCompression,
+    content: CompressedContent,
+}
+
+impl WsCompression {
+    /// Reads the client's `compression` query parameter.
+    pub(super) fn negotiate(query: Option<&str>) -> Self {
+        let requested = query.and_then(|query| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("compression="))
+        });
+        match requested {
+            Some("gzip") => Self::Gzip,
+            _ => Self::None,
+        }
+    }
+
+    /// Wraps `message` in a compressed frame when this client opted in and
+    /// the payload is large and compressible.
+    pub(super) fn encode_outbound(self, message: Message) -> Message {
+        if self != Self::Gzip {
+            return message;
+        }
+        let wrapped = match &message {
+            Message::Text(text) if text.len() >= COMPRESS_MIN_BYTES => {
+                compressed_message(CompressedContent::Text, text.as_bytes()).ok()
+            }
+            Message::Binary(bytes) if bytes.len() >= COMPRESS_MIN_BYTES && is_raw_frame(bytes) => {
+                compressed_message(CompressedContent::Binary, bytes).ok()
+            }
+            _ => None,
+        };
+        wrapped.map(Message::Binary).unwrap_or(message)
+    }
+}
+
+/// Raw RGBA stream frames compress well; encoded images do not.
+fn is_raw_frame(bytes: &[u8]) -> bool {
+    parse_binary_frame_header(bytes).is_some_and(|(frame_type, header, _)| {
+        frame_type == "frame" && header["encoding"] == "rgba"
+    })
+}
+
+fn compressed_message(content: CompressedContent, bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(bytes.len() / 4), Compression::fast());
+    encoder.write_all(bytes)?;
+    let payload = encoder.finish()?;
+    binary_message(
+        &CompressedHeader {
+            frame_type: "compressed".to_string(),
+            encoding: WsCompression::Gzip,
+            content,
+        },
+        &payload,
+    )
+}
+
+/// Unwraps an inbound `compressed` frame into the message it carries.
+pub(super) fn decode_compressed_frame(header: Value, payload: &[u8]) -> Result<Message> {
+    let header: CompressedHeader =
+        serde_json::from_value(header).context("invalid compressed frame header")?;
+    if header.encoding != WsCompression::Gzip {
+        anyhow::bail!(
+            "unsupported compressed frame encoding: {:?}",
+            header.encoding
+        );
+    }
+    let mut decoded = Vec::new();
+    GzDecoder::new(payload)
+        .take(MAX_DECOMPRESSED_BYTES + 1)
+        .read_to_end(&mut decoded)
+        .context("invalid gzip payload")?;
+    if decoded.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        anyhow::bail!("compressed message exceeds {MAX_DECOMPRESSED_BYTES} bytes when inflated");
+    }
+    Ok(match header.content {
+        CompressedContent::Text => {
+            Message::Text(String::from_utf8(decoded).context("compressed text is not UTF-8")?)
+        }
+        CompressedContent::Binary => Message::Binary(decoded),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_from_query() {
+        assert_eq!(
+            WsCompression::negotiate(Some("token=a&compression=gzip")),
+            WsCompression::Gzip
+        );
+        assert_eq!(
+            WsCompression::negotiate(Some("compression=br")),
+            WsCompression::None
+        );
+        assert_eq!(WsCompression::negotiate(None), WsCompression::None);
+    }
+
+    #[test]
+    fn large_text_round_trips_and_small_text_passes_through() {
+        let text = format!(
+            r#"{{"type":"scene_update","pad":"{}"}}"#,
+            "a".repeat(COMPRESS_MIN_BYTES)
+        );
+        let Message::Binary(frame) =
+            WsCompression::Gzip.encode_outbound(Message::Text(text.clone()))
+        else {
+            panic!("expected a compressed binary frame");
+        };
+        assert!(frame.len() < text.len() / 10);
+        let (frame_type, header, payload) = parse_binary_frame_header(&frame).unwrap();
+        assert_eq!(frame_type, "compressed");
+        assert_eq!(
+            decode_compressed_frame(header, payload).unwrap(),
+            Message::Text(text.clone())
+        );
+
+        assert_eq!(
+            WsCompression::None.encode_outbound(Message::Text(text.clone())),
+            Message::Text(text)
+        );
+        assert_eq!(
+            WsCompression::Gzip.encode_outbound(Message::Text("{}".to_string())),
+            Message::Text("{}".to_string())
+        );
+    }
+}
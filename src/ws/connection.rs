@@ -0,0 +1,247 @@
+//! Connection lifecycle: server options, protocol-level keepalive and the
+//! `client_connected` / `client_disconnected` notices.
+//!
+//! The JSON `ping` broadcast lets editors tell a dead server from an idle one.
+//! In the other direction the server sends WebSocket ping frames, which
+//! browsers answer on their own, and drops clients that have been silent for
+//! longer than the client timeout, so a vanished peer does not leave a writer
+//! loop running forever.
+
+use super::*;
+
+const DEFAULT_CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Ping frames per timeout window, so a couple of lost pongs are tolerated.
+const PINGS_PER_TIMEOUT: u32 = 3;
+
+#[derive(Debug, Clone)]
+pub struct WsServerOptions {
+    pub access: WsAccessPolicy,
+    /// Drop a client after this long without any inbound frame; `None` keeps
+    /// silent clients connected.
+    pub client_timeout: Option<Duration>,
+    /// Logs every inbound message (`--record-ws`).
+    pub record: Option<WsRecorder>,
+    /// Recording to feed back through the server once it listens
+    /// (`--replay-ws`).
+    pub replay: Option<std::path::PathBuf>,
+    /// Serve a single client over stdin/stdout instead of listening
+    /// (`--stdio`).
+    pub stdio: bool,
+}
+
+impl Default for WsServerOptions {
+    fn default() -> Self {
+        Self {
+            access: WsAccessPolicy::default(),
+            client_timeout: Some(DEFAULT_CLIENT_TIMEOUT),
+            record: None,
+            replay: None,
+            stdio: false,
+        }
+    }
+}
+
+impl WsServerOptions {
+    fn ping_interval(&self) -> Option<Duration> {
+        self.client_timeout
+            .map(|timeout| timeout / PINGS_PER_TIMEOUT)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum KeepaliveAction {
+    Idle,
+    Ping,
+    TimedOut,
+}
+
+/// Per-client liveness tracking.
+pub(super) struct Keepalive {
+    timeout: Option<Duration>,
+    ping_interval: Option<Duration>,
+    last_inbound: Instant,
+    last_ping: Instant,
+}
+
+impl Keepalive {
+    pub(super) fn new(options: &WsServerOptions, now: Instant) -> Self {
+        Self {
+            timeout: options.client_timeout,
+            ping_interval: options.ping_interval(),
+            last_inbound: now,
+            last_ping: now,
+        }
+    }
+
+    /// Any inbound frame, including pongs, proves the client is alive.
+    pub(super) fn observe_inbound(&mut self, now: Instant) {
+        self.last_inbound = now;
+    }
+
+    pub(super) fn poll(&mut self, now: Instant) -> KeepaliveAction {
+        if self
+            .timeout
+            .is_some_and(|timeout| now.duration_since(self.last_inbound) >= timeout)
+        {
+            return KeepaliveAction::TimedOut;
+        }
+        if self
+            .ping_interval
+            .is_some_and(|interval| now.duration_since(self.last_ping) >= interval)
+        {
+            self.last_ping = now;
+            return KeepaliveAction::Ping;
+        }
+        KeepaliveAction::Idle
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum DisconnectReason {
+    Closed,
+    TimedOut,
+    Error,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientConnectedPayload {
+    client_id: u64,
+    client_count: usize,
+    /// Interval of server ping frames; absent when keepalive is off.
+    ping_interval_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    /// Compression negotiated by the connecting client.
+    compression: WsCompression,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientDisconnectedPayload {
+    client_id: u64,
+    client_count: usize,
+    reason: DisconnectReason,
+}
+
+fn broadcast_notice<T: serde::Serialize>(hub: &WsHub, msg_type: &str, payload: T) {
+    let message = WSMessage {
+        msg_type: msg_type.to_string(),
+        timestamp: now_millis(),
+        request_id: None,
+        payload: Some(payload),
+    };
+    if let Ok(text) = serde_json::to_string(&message) {
+        hub.broadcast(text);
+    }
+}
+
+/// Announces a registered client to everyone, including the client itself,
+/// which learns its id and the keepalive settings this way.
+pub(super) fn broadcast_client_connected(
+    hub: &WsHub,
+    client_id: u64,
+    options: &WsServerOptions,
+    compression: WsCompression,
+) {
+    broadcast_notice(
+        hub,
+        "client_connected",
+        ClientConnectedPayload {
+            client_id,
+            client_count: hub.client_count(),
+            ping_interval_ms: options
+                .ping_interval()
+                .map(|interval| interval.as_millis() as u64),
+            timeout_ms: options
+                .client_timeout
+                .map(|timeout| timeout.as_millis() as u64),
+            compression,
+        },
+    );
+}
+
+/// Drops the client's session and tells the remaining clients.
+pub(super) fn end_client_session(hub: &WsHub, client_id: u64, reason: DisconnectReason) {
+    hub.unregister_client(client_id);
+    broadcast_notice(
+        hub,
+        "client_disconnected",
+        ClientDisconnectedPayload {
+            client_id,
+            client_count: hub.client_count(),
+            reason,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keepalive_pings_within_timeout_and_expires_silent_clients() {
+        let start = Instant::now();
+        let options = WsServerOptions {
+            client_timeout: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+        let mut keepalive = Keepalive::new(&options, start);
+
+        assert_eq!(
+            keepalive.poll(start + Duration::from_secs(5)),
+            KeepaliveAction::Idle
+        );
+        assert_eq!(
+            keepalive.poll(start + Duration::from_secs(10)),
+            KeepaliveAction::Ping
+        );
+        assert_eq!(
+            keepalive.poll(start + Duration::from_secs(11)),
+            KeepaliveAction::Idle
+        );
+
+        keepalive.observe_inbound(start + Duration::from_secs(20));
+        assert_ne!(
+            keepalive.poll(start + Duration::from_secs(45)),
+            KeepaliveAction::TimedOut
+        );
+        assert_eq!(
+            keepalive.poll(start + Duration::from_secs(50)),
+            KeepaliveAction::TimedOut
+        );
+
+        let mut disabled = Keepalive::new(
+            &WsServerOptions {
+                client_timeout: None,
+                ..Default::default()
+            },
+            start,
+        );
+        assert_eq!(
+            disabled.poll(start + Duration::from_secs(3600)),
+            KeepaliveAction::Idle
+        );
+    }
+
+    #[test]
+    fn disconnect_notice_reports_remaining_clients() {
+        let hub = WsHub::default();
+        let (tx_a, _rx_a) = crossbeam_channel::unbounded();
+        let (tx_b, rx_b) = crossbeam_channel::unbounded();
+        let a = hub.register_client(tx_a);
+        hub.register_client(tx_b);
+
+        end_client_session(&hub, a, DisconnectReason::TimedOut);
+
+        let Ok(Message::Text(text)) = rx_b.try_recv() else {
+            panic!("expected a text notice");
+        };
+        let notice: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(notice["type"], "client_disconnected");
+        assert_eq!(notice["payload"]["clientId"], a);
+        assert_eq!(notice["payload"]["clientCount"], 1);
+        assert_eq!(notice["payload"]["reason"], "timed_out");
+    }
+}
@@ -4,7 +4,7 @@ use super::debug_artifacts::{
 use super::*;
 
 pub(super) fn handle_text_message(
-    ws: &mut tungstenite::WebSocket<std::net::TcpStream>,
+    ws: &mut dyn ClientSink,
     text: &str,
     scene_tx: &Sender<SceneUpdate>,
     scene_drop_rx: &Receiver<SceneUpdate>,
@@ -13,6 +13,7 @@ pub(super) fn handle_text_message(
     asset_store: &AssetStore,
     transfer_state: &mut AssetTransferState,
     debug_artifact_transfer_state: &mut DebugArtifactTransferState,
+    client: &ClientContext<'_>,
     ui_wake: Option<&UiWakeCallback>,
 ) -> Result<()> {
     let receive_started_at = Instant::now();
@@ -29,6 +30,7 @@ pub(super) fn handle_text_message(
                 SceneUpdate::ParseError {
                     message,
                     request_id: None,
+                    client_id: Some(client.id),
                 },
                 ui_wake,
             );
@@ -97,7 +99,11 @@ pub(super) fn handle_text_message(
             }
         }
         "scene_request" => {
-            let scene = last_good.lock().ok().and_then(|g| g.clone());
+            // Prefer this client's own scene; fall back to what is on screen.
+            let scene = client
+                .hub
+                .session_last_good(client.id)
+                .or_else(|| last_good.lock().ok().and_then(|g| g.clone()));
             if let Some(scene) = scene {
                 let resp = WSMessage {
                     msg_type: "scene_update".to_string(),
@@ -107,9 +113,11 @@ pub(super) fn handle_text_message(
                         scene,
                         functions:
                             crate::state_machine::mutation_function::installed_document_functions(),
+                        features: Vec::new(),
                     })?),
                 };
-                let _ = ws.send(Message::Text(serde_json::to_string(&resp)?));
+                let text = serde_json::to_string(&resp)?;
+                let _ = ws.send(client.compression.encode_outbound(Message::Text(text)));
             } else {
                 send_error(ws, msg.request_id, "VALIDATION_ERROR", "no last-good scene");
             }
@@ -126,6 +134,7 @@ pub(super) fn handle_text_message(
                         SceneUpdate::ParseError {
                             message,
                             request_id: msg.request_id,
+                            client_id: Some(client.id),
                         },
                         ui_wake,
                     );
@@ -149,6 +158,7 @@ pub(super) fn handle_text_message(
                         SceneUpdate::ParseError {
                             message,
                             request_id: msg.request_id,
+                            client_id: Some(client.id),
                         },
                         ui_wake,
                     );
@@ -160,6 +170,22 @@ pub(super) fn handle_text_message(
 
             dsl::materialize_scene_node_labels_from_raw_json(&mut scene, &raw_scene);
 
+            if let Err(e) = dsl::apply_feature_overrides(&mut scene, &runtime_payload.features) {
+                let message = format!("invalid feature overrides: {e:#}");
+                send_error(ws, msg.request_id.clone(), "PARSE_ERROR", &message);
+                send_scene_update(
+                    scene_tx,
+                    scene_drop_rx,
+                    SceneUpdate::ParseError {
+                        message,
+                        request_id: msg.request_id,
+                        client_id: Some(client.id),
+                    },
+                    ui_wake,
+                );
+                return Ok(());
+            }
+
             if let Err(error) = crate::state_machine::mutation_function::install_document_functions(
                 runtime_payload.functions,
             ) {
@@ -171,6 +197,7 @@ pub(super) fn handle_text_message(
                     SceneUpdate::ParseError {
                         message,
                         request_id: msg.request_id,
+                        client_id: Some(client.id),
                     },
                     ui_wake,
                 );
@@ -187,6 +214,7 @@ pub(super) fn handle_text_message(
                     SceneUpdate::ParseError {
                         message,
                         request_id: msg.request_id,
+                        client_id: Some(client.id),
                     },
                     ui_wake,
                 );
@@ -214,9 +242,11 @@ pub(super) fn handle_text_message(
                     SceneUpdate::Parsed {
                         scene,
                         request_id: msg.request_id,
+                        client_id: Some(client.id),
                         source: ParsedSceneSource::SceneUpdate,
                         perf_trace: create_scene_perf_trace(
                             &perf_request_id,
+                            Some(client.id),
                             perf_client_sent_at_ms,
                             server_received_at_ms,
                             message_bytes,
@@ -319,8 +349,10 @@ pub(super) fn handle_text_message(
                         SceneUpdate::UniformDelta {
                             updated_nodes: delta.nodes.updated.clone(),
                             request_id: msg.request_id,
+                            client_id: Some(client.id),
                             perf_trace: create_scene_perf_trace(
                                 &perf_request_id,
+                                Some(client.id),
                                 perf_client_sent_at_ms,
                                 server_received_at_ms,
                                 message_bytes,
@@ -356,9 +388,11 @@ pub(super) fn handle_text_message(
                     SceneUpdate::Parsed {
                         scene,
                         request_id: msg.request_id,
+                        client_id: Some(client.id),
                         source: ParsedSceneSource::SceneDelta,
                         perf_trace: create_scene_perf_trace(
                             &perf_request_id,
+                            Some(client.id),
                             perf_client_sent_at_ms,
                             server_received_at_ms,
                             message_bytes,
@@ -369,19 +403,117 @@ pub(super) fn handle_text_message(
                 );
             }
         }
-        "asset_remove" => {
-            if let Some(payload) = msg.payload {
-                if let Some(asset_id) = payload.get("assetId").and_then(|v| v.as_str()) {
-                    asset_store.remove(asset_id);
-                    transfer_state.on_asset_removed(asset_id);
-                    // Also remove from scene cache assets if present.
-                    if let Ok(mut guard) = scene_cache.lock() {
-                        if let Some(cache) = guard.as_mut() {
-                            cache.assets.remove(asset_id);
-                        }
-                    }
+        "param_update" => {
+            let updates = match msg.payload.context("missing payload").and_then(|payload| {
+                serde_json::from_value::<ParamUpdatePayload>(payload).map_err(anyhow::Error::from)
+            }) {
+                Ok(payload) => payload.into_updates(),
+                Err(e) => {
+                    send_error(
+                        ws,
+                        msg.request_id,
+                        "PARSE_ERROR",
+                        &format!("invalid param_update payload: {e:#}"),
+                    );
+                    return Ok(());
                 }
+            };
+
+            let Ok(mut guard) = scene_cache.lock() else {
+                return Ok(());
+            };
+            let Some(cache) = guard.as_mut() else {
+                send_error(
+                    ws,
+                    msg.request_id,
+                    "RESYNC_REQUIRED",
+                    "received param_update before scene_update",
+                );
+                send_scene_resync_request(ws, "missing_baseline_scene_update");
+                return Ok(());
+            };
+            let delta = match param_updates_to_delta(cache, &updates) {
+                Ok(Some(delta)) => delta,
+                Ok(None) => return Ok(()),
+                Err(e) => {
+                    send_error(ws, msg.request_id, "VALIDATION_ERROR", &format!("{e:#}"));
+                    return Ok(());
+                }
+            };
+            // Only params packed into graph uniforms can skip the rebuild;
+            // anything else has to go through scene_delta.
+            if !delta_updates_only_uniform_values(cache, &delta) {
+                send_error(
+                    ws,
+                    msg.request_id,
+                    "VALIDATION_ERROR",
+                    "param_update only accepts graph-bound input values; send a scene_delta instead",
+                );
+                return Ok(());
             }
+            apply_scene_delta(cache, &delta);
+            drop(guard);
+
+            send_scene_update(
+                scene_tx,
+                scene_drop_rx,
+                SceneUpdate::UniformDelta {
+                    updated_nodes: delta.nodes.updated,
+                    request_id: msg.request_id,
+                    client_id: Some(client.id),
+                    perf_trace: create_scene_perf_trace(
+                        &perf_request_id,
+                        Some(client.id),
+                        perf_client_sent_at_ms,
+                        server_received_at_ms,
+                        message_bytes,
+                        receive_started_at,
+                    ),
+                },
+                ui_wake,
+            );
+        }
+        "asset_remove" | "delete_asset" => {
+            let asset_id = msg
+                .payload
+                .as_ref()
+                .and_then(|payload| payload.get("assetId"))
+                .and_then(|v| v.as_str());
+            let Some(asset_id) = asset_id else {
+                // asset_remove has always been fire-and-forget.
+                if msg.msg_type == "delete_asset" {
+                    send_error(
+                        ws,
+                        msg.request_id,
+                        "PARSE_ERROR",
+                        "delete_asset missing assetId",
+                    );
+                }
+                return Ok(());
+            };
+            let deleted = asset_store.remove(asset_id).is_some();
+            transfer_state.on_asset_removed(asset_id);
+            // Also remove from scene cache assets if present.
+            if let Ok(mut guard) = scene_cache.lock() {
+                if let Some(cache) = guard.as_mut() {
+                    cache.assets.remove(asset_id);
+                }
+            }
+            if msg.msg_type == "delete_asset" {
+                let resp = WSMessage {
+                    msg_type: "asset_deleted".to_string(),
+                    timestamp: now_millis(),
+                    request_id: msg.request_id,
+                    payload: Some(serde_json::json!({
+                        "assetId": asset_id,
+                        "deleted": deleted,
+                    })),
+                };
+                let _ = ws.send(Message::Text(serde_json::to_string(&resp)?));
+            }
+        }
+        "list_assets" => {
+            send_asset_list(ws, msg.request_id, asset_store);
         }
         "asset_upload_start" => {
             let payload = match msg.payload {
@@ -583,6 +715,190 @@ pub(super) fn handle_text_message(
                 ui_wake,
             );
         }
+        "subscribe_frames" => {
+            let options = match msg.payload {
+                None => Ok(FrameStreamOptions::default()),
+                Some(payload) => serde_json::from_value::<FrameStreamOptions>(payload)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|options| options.validate().map(|()| options)),
+            };
+            match options {
+                Ok(options) => {
+                    client
+                        .hub
+                        .subscribe_frames(client.id, options, client.mailbox.clone());
+                    // Render and send the first frame without waiting for input.
+                    if let Some(wake) = ui_wake {
+                        wake();
+                    }
+                }
+                Err(e) => send_error(
+                    ws,
+                    msg.request_id,
+                    "PARSE_ERROR",
+                    &format!("invalid subscribe_frames payload: {e:#}"),
+                ),
+            }
+        }
+        "unsubscribe_frames" => {
+            client.hub.unsubscribe_frames(client.id);
+        }
+        "render_to_bytes" => {
+            let payload = match RenderToBytesPayload::parse(msg.payload) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    send_error(
+                        ws,
+                        msg.request_id,
+                        "PARSE_ERROR",
+                        &format!("invalid render_to_bytes payload: {e:#}"),
+                    );
+                    return Ok(());
+                }
+            };
+            // Renders run on this client's thread and never block the app, but
+            // unlike scene_update they cannot wait for a later asset upload.
+            if !asset_ids_ready(payload.scene().assets.keys(), asset_store) {
+                send_error(
+                    ws,
+                    msg.request_id,
+                    "ASSET_NOT_FOUND",
+                    "render_to_bytes scene references assets that have not been uploaded",
+                );
+                return Ok(());
+            }
+            let request_id = msg.request_id.clone();
+            let rendered = render_to_bytes(
+                &payload,
+                asset_store,
+                msg.request_id.clone(),
+                &mut |progress| {
+                    if let Some(text) = render_progress_text(request_id.clone(), &progress) {
+                        let _ = ws.send(Message::Text(text));
+                    }
+                },
+            );
+            match rendered {
+                Ok(frame) => {
+                    let _ = ws.send(Message::Binary(frame));
+                }
+                Err(e) => send_error(ws, msg.request_id, "RENDER_FAILED", &format!("{e:#}")),
+            }
+        }
+        "sample_pixel" | "read_region" => {
+            let kind = if msg.msg_type == "sample_pixel" {
+                PixelReadKind::SamplePixel
+            } else {
+                PixelReadKind::ReadRegion
+            };
+            match PixelReadRequest::parse(kind, msg.payload) {
+                Ok(request) => send_scene_update(
+                    scene_tx,
+                    scene_drop_rx,
+                    SceneUpdate::PixelRead {
+                        request,
+                        request_id: msg.request_id,
+                        client_id: Some(client.id),
+                    },
+                    ui_wake,
+                ),
+                Err(e) => send_error(
+                    ws,
+                    msg.request_id,
+                    "PARSE_ERROR",
+                    &format!("invalid {} payload: {e:#}", msg.msg_type),
+                ),
+            }
+        }
+        "validate_scene" => {
+            let payload = match msg.payload.context("missing payload").and_then(|payload| {
+                serde_json::from_value::<ValidateScenePayload>(payload).map_err(anyhow::Error::from)
+            }) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    send_error(
+                        ws,
+                        msg.request_id,
+                        "PARSE_ERROR",
+                        &format!("invalid validate_scene payload: {e:#}"),
+                    );
+                    return Ok(());
+                }
+            };
+            let mut scene = payload.scene;
+            // Device-free and bounded by codegen, so it runs on this client's
+            // thread without going through the app.
+            let report = match prepare_request_scene(&mut scene, &payload.features) {
+                Ok(()) => crate::renderer::validate_scene(&scene, Some(asset_store)),
+                Err(e) => crate::renderer::SceneValidationReport::load_failure(format!("{e:#}")),
+            };
+            let resp = WSMessage {
+                msg_type: "validate_scene_result".to_string(),
+                timestamp: now_millis(),
+                request_id: msg.request_id,
+                payload: Some(report),
+            };
+            let _ = ws.send(Message::Text(serde_json::to_string(&resp)?));
+        }
+        "get_resources" => {
+            send_scene_update(
+                scene_tx,
+                scene_drop_rx,
+                SceneUpdate::ResourceQuery {
+                    query: ResourceQuery::Resources,
+                    request_id: msg.request_id,
+                    client_id: Some(client.id),
+                },
+                ui_wake,
+            );
+        }
+        "preview_texture" => match PreviewTextureRequest::parse(msg.payload) {
+            Ok(request) => send_scene_update(
+                scene_tx,
+                scene_drop_rx,
+                SceneUpdate::ResourceQuery {
+                    query: ResourceQuery::PreviewTexture(request),
+                    request_id: msg.request_id,
+                    client_id: Some(client.id),
+                },
+                ui_wake,
+            ),
+            Err(e) => send_error(
+                ws,
+                msg.request_id,
+                "PARSE_ERROR",
+                &format!("invalid preview_texture payload: {e:#}"),
+            ),
+        },
+        "get_analysis" => match AnalysisRequest::parse(msg.payload) {
+            Ok(request) => send_scene_update(
+                scene_tx,
+                scene_drop_rx,
+                SceneUpdate::Analysis {
+                    request,
+                    request_id: msg.request_id,
+                    client_id: Some(client.id),
+                },
+                ui_wake,
+            ),
+            Err(e) => send_error(
+                ws,
+                msg.request_id,
+                "PARSE_ERROR",
+                &format!("invalid get_analysis payload: {e:#}"),
+            ),
+        },
+        "render_stats_request" => {
+            send_scene_update(
+                scene_tx,
+                scene_drop_rx,
+                SceneUpdate::RenderStatsRequest {
+                    request_id: msg.request_id,
+                    client_id: Some(client.id),
+                },
+                ui_wake,
+            );
+        }
         "debug_artifact_upload_start" => {
             let payload = match msg.payload {
                 Some(p) => p,
@@ -682,6 +998,16 @@ pub(super) fn handle_text_message(
     Ok(())
 }
 
+/// The `scene_update` preprocessing for requests that carry their own scene:
+/// node labels, feature overrides and default params.
+pub(super) fn prepare_request_scene(scene: &mut SceneDSL, features: &[String]) -> Result<()> {
+    let raw_scene = serde_json::to_value(&*scene)?;
+    dsl::materialize_scene_node_labels_from_raw_json(scene, &raw_scene);
+    dsl::apply_feature_overrides(scene, features).context("invalid feature overrides")?;
+    dsl::normalize_scene_defaults(scene).context("failed to apply default params")?;
+    Ok(())
+}
+
 fn asset_ids_ready<'a>(
     asset_ids: impl IntoIterator<Item = &'a String>,
     asset_store: &AssetStore,
@@ -703,6 +1029,61 @@ fn scene_after_asset_upload(
     Some(materialize_scene_dsl(cache))
 }
 
+/// Stores the asset carried by an `upload_asset` binary frame and re-renders
+/// a cached scene that was waiting for it.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn handle_upload_asset_frame(
+    ws: &mut dyn ClientSink,
+    header: Value,
+    bytes: &[u8],
+    scene_tx: &Sender<SceneUpdate>,
+    scene_drop_rx: &Receiver<SceneUpdate>,
+    scene_cache: &Arc<Mutex<Option<SceneCache>>>,
+    asset_store: &AssetStore,
+    transfer_state: &mut AssetTransferState,
+    ui_wake: Option<&UiWakeCallback>,
+) {
+    let header: UploadAssetHeader = match serde_json::from_value(header) {
+        Ok(header) => header,
+        Err(e) => {
+            send_error(
+                ws,
+                None,
+                "PARSE_ERROR",
+                &format!("invalid upload_asset header: {e}"),
+            );
+            return;
+        }
+    };
+    if header.asset_id.is_empty() {
+        send_error(
+            ws,
+            header.request_id,
+            "PARSE_ERROR",
+            "upload_asset missing assetId",
+        );
+        return;
+    }
+
+    let asset_id = header.asset_id.clone();
+    transfer_state.on_asset_uploaded(&asset_id, now_millis());
+    asset_store.insert_or_replace(asset_id.clone(), header.into_asset_data(bytes.to_vec()));
+    send_asset_upload_ack(ws, &asset_id);
+    eprintln!(
+        r#"{{"event":"asset_transfer_completed","assetId":"{}","bytes":{}}}"#,
+        asset_id,
+        bytes.len()
+    );
+    trigger_rerender_for_asset(
+        &asset_id,
+        scene_cache,
+        asset_store,
+        scene_tx,
+        scene_drop_rx,
+        ui_wake,
+    );
+}
+
 fn trigger_rerender_for_asset(
     asset_id: &str,
     scene_cache: &Arc<Mutex<Option<SceneCache>>>,
@@ -724,6 +1105,7 @@ fn trigger_rerender_for_asset(
             SceneUpdate::Parsed {
                 scene,
                 request_id: None,
+                client_id: None,
                 source: ParsedSceneSource::SceneUpdate,
                 perf_trace: None,
             },
@@ -769,6 +1151,7 @@ mod asset_scene_tests {
             assets,
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         SceneCache::from_scene_update(&scene)
     }
@@ -808,7 +1191,7 @@ mod asset_scene_tests {
 }
 
 pub(super) fn send_error(
-    ws: &mut tungstenite::WebSocket<std::net::TcpStream>,
+    ws: &mut dyn ClientSink,
     request_id: Option<String>,
     code: &str,
     message: &str,
@@ -843,12 +1226,62 @@ fn send_scene_update(
                 while scene_drop_rx.try_recv().is_ok() {}
                 scene_tx.try_send(update).is_ok()
             }
-            SceneUpdate::UniformDelta { .. } => {
-                // Uniform-only updates are cheap and high-frequency.
-                // If the channel is full, prefer keeping the in-flight message
-                // (often a full Parsed scene) and drop this delta.
-                false
-            }
+            SceneUpdate::UniformDelta {
+                updated_nodes,
+                request_id,
+                client_id,
+                perf_trace,
+            } => match scene_drop_rx.try_recv() {
+                // Deltas may touch different params, so fold this one into a
+                // queued delta instead of dropping it.
+                Ok(SceneUpdate::UniformDelta {
+                    updated_nodes: mut queued_nodes,
+                    ..
+                }) => {
+                    merge_uniform_delta_nodes(&mut queued_nodes, updated_nodes);
+                    scene_tx
+                        .try_send(SceneUpdate::UniformDelta {
+                            updated_nodes: queued_nodes,
+                            request_id,
+                            client_id,
+                            perf_trace,
+                        })
+                        .is_ok()
+                }
+                // A queued full scene predates this delta; patch it so the
+                // change is not lost behind it.
+                Ok(SceneUpdate::Parsed {
+                    mut scene,
+                    request_id: queued_request_id,
+                    client_id: queued_client_id,
+                    source,
+                    perf_trace: queued_perf_trace,
+                }) => {
+                    patch_scene_node_params(&mut scene, &updated_nodes);
+                    scene_tx
+                        .try_send(SceneUpdate::Parsed {
+                            scene,
+                            request_id: queued_request_id,
+                            client_id: queued_client_id,
+                            source,
+                            perf_trace: queued_perf_trace,
+                        })
+                        .is_ok()
+                }
+                // Keep other in-flight messages and drop this delta.
+                Ok(queued) => {
+                    let _ = scene_tx.try_send(queued);
+                    false
+                }
+                Err(_) => scene_tx
+                    .try_send(SceneUpdate::UniformDelta {
+                        updated_nodes,
+                        request_id,
+                        client_id,
+                        perf_trace,
+                    })
+                    .is_ok(),
+            },
             SceneUpdate::ParseError { .. } => {
                 // Channel is full; keep the existing message rather than
                 // replacing it. A future update will replace it naturally.
@@ -856,7 +1289,11 @@ fn send_scene_update(
             }
             SceneUpdate::DebugArtifactUpsert { .. }
             | SceneUpdate::DebugArtifactBinaryUpsert { .. }
-            | SceneUpdate::DebugArtifactDelete { .. } => {
+            | SceneUpdate::DebugArtifactDelete { .. }
+            | SceneUpdate::RenderStatsRequest { .. }
+            | SceneUpdate::PixelRead { .. }
+            | SceneUpdate::ResourceQuery { .. }
+            | SceneUpdate::Analysis { .. } => {
                 // Artifact messages are side-channel state for the debug UI.
                 // Keep an in-flight scene update if one exists; the editor can
                 // answer a later request if this update is skipped.
@@ -871,3 +1308,68 @@ fn send_scene_update(
         wake();
     }
 }
+
+#[cfg(test)]
+mod request_routing_tests {
+    use super::*;
+
+    struct NullSink;
+
+    impl ClientSink for NullSink {
+        fn send(&mut self, _message: Message) -> tungstenite::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn send_as_client(hub: &WsHub, client_id: u64, text: &str) -> SceneUpdate {
+        let (scene_tx, scene_rx) = crossbeam_channel::unbounded();
+        let mailbox = FrameMailbox::default();
+        handle_text_message(
+            &mut NullSink,
+            text,
+            &scene_tx,
+            &scene_rx,
+            &Arc::new(Mutex::new(None)),
+            &Arc::new(Mutex::new(None)),
+            &AssetStore::new(),
+            &mut AssetTransferState::default(),
+            &mut DebugArtifactTransferState::default(),
+            &ClientContext {
+                id: client_id,
+                compression: WsCompression::None,
+                hub,
+                mailbox: &mailbox,
+            },
+            None,
+        )
+        .unwrap();
+        scene_rx.try_recv().unwrap()
+    }
+
+    #[test]
+    fn same_request_id_from_two_clients_replies_to_each_sender() {
+        let hub = WsHub::default();
+        let (tx_a, rx_a) = crossbeam_channel::unbounded();
+        let (tx_b, rx_b) = crossbeam_channel::unbounded();
+        let a = hub.register_client(tx_a);
+        let b = hub.register_client(tx_b);
+        let text = r#"{"type":"render_stats_request","timestamp":0,"requestId":"1"}"#;
+
+        for (client_id, rx) in [(a, &rx_a), (b, &rx_b)] {
+            let SceneUpdate::RenderStatsRequest {
+                request_id,
+                client_id: sender,
+            } = send_as_client(&hub, client_id, text)
+            else {
+                panic!("expected a render stats request");
+            };
+            assert_eq!(request_id.as_deref(), Some("1"));
+            assert_eq!(sender, Some(client_id));
+
+            broadcast_render_stats(&hub, request_id, sender, None, None, 0);
+            assert_eq!(rx.try_iter().count(), 1);
+        }
+        assert!(rx_a.try_recv().is_err());
+        assert!(rx_b.try_recv().is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! Live preview streaming: `subscribe_frames` makes the server push the
+//! rendered output to that client as binary `frame` messages.
+//!
+//! Each subscriber has a single-frame mailbox drained by its client loop. A
+//! frame that has not been sent yet is replaced by the next one, so a slow
+//! client sees fewer frames instead of an ever-growing queue.
+
+use std::io::Cursor;
+
+use anyhow::bail;
+
+use super::*;
+
+const MAX_STREAM_FPS: f32 = 60.0;
+const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameStreamEncoding {
+    /// Tightly packed sRGB-encoded RGBA8, as copied to the clipboard.
+    Rgba,
+    Png,
+    /// Flattened over black.
+    Jpeg,
+}
+
+/// `subscribe_frames` payload.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FrameStreamOptions {
+    #[serde(default = "default_stream_fps")]
+    pub fps: f32,
+    /// Output size relative to the render, in (0, 1].
+    #[serde(default = "default_stream_scale")]
+    pub scale: f32,
+    #[serde(default = "default_stream_encoding")]
+    pub encoding: FrameStreamEncoding,
+    /// JPEG quality, 1-100.
+    #[serde(default)]
+    pub quality: Option<u8>,
+}
+
+fn default_stream_fps() -> f32 {
+    15.0
+}
+
+fn default_stream_scale() -> f32 {
+    1.0
+}
+
+fn default_stream_encoding() -> FrameStreamEncoding {
+    FrameStreamEncoding::Jpeg
+}
+
+impl Default for FrameStreamOptions {
+    fn default() -> Self {
+        Self {
+            fps: default_stream_fps(),
+            scale: default_stream_scale(),
+            encoding: default_stream_encoding(),
+            quality: None,
+        }
+    }
+}
+
+impl FrameStreamOptions {
+    pub(super) fn validate(&self) -> Result<()> {
+        if !(self.fps > 0.0 && self.fps <= MAX_STREAM_FPS) {
+            bail!("fps must be in (0, {MAX_STREAM_FPS}], got {}", self.fps);
+        }
+        if !(self.scale > 0.0 && self.scale <= 1.0) {
+            bail!("scale must be in (0, 1], got {}", self.scale);
+        }
+        if let Some(quality) = self.quality
+            && !(1..=100).contains(&quality)
+        {
+            bail!("quality must be in 1..=100, got {quality}");
+        }
+        Ok(())
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / f64::from(self.fps))
+    }
+}
+
+pub(super) type FrameMailbox = Arc<Mutex<Option<Vec<u8>>>>;
+
+struct FrameStream {
+    options: FrameStreamOptions,
+    mailbox: FrameMailbox,
+    last_sent_at: Option<Instant>,
+    /// The render changed since the last frame was sent.
+    stale: bool,
+    frames_sent: u64,
+}
+
+/// Frame subscriptions keyed by client id.
+#[derive(Default)]
+pub(super) struct FrameStreams {
+    streams: HashMap<u64, FrameStream>,
+}
+
+impl FrameStreams {
+    pub(super) fn subscribe(
+        &mut self,
+        client_id: u64,
+        options: FrameStreamOptions,
+        mailbox: FrameMailbox,
+    ) {
+        self.streams.insert(
+            client_id,
+            FrameStream {
+                options,
+                mailbox,
+                last_sent_at: None,
+                stale: true,
+                frames_sent: 0,
+            },
+        );
+    }
+
+    pub(super) fn unsubscribe(&mut self, client_id: u64) {
+        self.streams.remove(&client_id);
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    pub(super) fn mark_stale(&mut self) {
+        for stream in self.streams.values_mut() {
+            stream.stale = true;
+        }
+    }
+
+    /// Streams owed a frame at `now`: the render changed and the stream's
+    /// frame interval has elapsed.
+    pub(super) fn due(&self, now: Instant) -> Vec<(u64, FrameStreamOptions)> {
+        self.streams
+            .iter()
+            .filter(|(_, stream)| {
+                stream.stale
+                    && stream
+                        .last_sent_at
+                        .is_none_or(|sent| now.duration_since(sent) >= stream.options.interval())
+            })
+            .map(|(id, stream)| (*id, stream.options))
+            .collect()
+    }
+
+    /// Time until the next stale stream becomes due, so the caller can
+    /// schedule a repaint for a change that arrived inside the interval.
+    pub(super) fn next_due_in(&self, now: Instant) -> Option<Duration> {
+        self.streams
+            .values()
+            .filter(|stream| stream.stale)
+            .map(|stream| match stream.last_sent_at {
+                Some(sent) => (sent + stream.options.interval()).saturating_duration_since(now),
+                None => Duration::ZERO,
+            })
+            .min()
+    }
+
+    /// Encodes a frame for `client_id` (given its next frame index) and puts it
+    /// in the subscriber's mailbox, replacing any frame not sent yet.
+    pub(super) fn publish(
+        &mut self,
+        client_id: u64,
+        now: Instant,
+        encode: impl FnOnce(u64) -> Option<Vec<u8>>,
+    ) {
+        let Some(stream) = self.streams.get_mut(&client_id) else {
+            return;
+        };
+        stream.stale = false;
+        stream.last_sent_at = Some(now);
+        let Some(frame) = encode(stream.frames_sent) else {
+            return;
+        };
+        stream.frames_sent += 1;
+        if let Ok(mut slot) = stream.mailbox.lock() {
+            *slot = Some(frame);
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FrameHeader {
+    #[serde(rename = "type")]
+    frame_type: &'static str,
+    frame_index: u64,
+    width: u32,
+    height: u32,
+    encoding: FrameStreamEncoding,
+    timestamp: u64,
+}
+
+/// Scales and encodes one RGBA8 readback into a binary `frame` message.
+pub fn encode_stream_frame(
+    rgba: Vec<u8>,
+    size: [u32; 2],
+    options: &FrameStreamOptions,
+    frame_index: u64,
+) -> Result<Vec<u8>> {
+    let image = image::RgbaImage::from_raw(size[0], size[1], rgba)
+        .context("frame readback has an unexpected size")?;
+    let width = ((size[0] as f32 * options.scale).round() as u32).max(1);
+    let height = ((size[1] as f32 * options.scale).round() as u32).max(1);
+    let image = if [width, height] == size {
+        image
+    } else {
+        image::imageops::resize(&image, width, height, image::imageops::FilterType::Triangle)
+    };
+
+    let payload = encode_rgba_image(image, options.encoding, options.quality)?;
+    binary_message(
+        &FrameHeader {
+            frame_type: "frame",
+            frame_index,
+            width,
+            height,
+            encoding: options.encoding,
+            timestamp: now_millis(),
+        },
+        &payload,
+    )
+}
+
+/// Encodes an sRGB RGBA8 image; `quality` only applies to JPEG.
+pub(super) fn encode_rgba_image(
+    image: image::RgbaImage,
+    encoding: FrameStreamEncoding,
+    quality: Option<u8>,
+) -> Result<Vec<u8>> {
+    Ok(match encoding {
+        FrameStreamEncoding::Rgba => image.into_raw(),
+        FrameStreamEncoding::Png => {
+            let mut out = Cursor::new(Vec::new());
+            image
+                .write_to(&mut out, image::ImageFormat::Png)
+                .context("failed to encode png")?;
+            out.into_inner()
+        }
+        FrameStreamEncoding::Jpeg => {
+            let rgb = image::DynamicImage::ImageRgba8(image).to_rgb8();
+            let mut out = Vec::new();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(
+                &mut out,
+                quality.unwrap_or(DEFAULT_JPEG_QUALITY),
+            )
+            .encode_image(&rgb)
+            .context("failed to encode jpeg")?;
+            out
+        }
+    })
+}
+
+/// Binary WS message layout shared with debug artifact chunks: a big-endian
+/// u32 header length, the JSON header, then the payload bytes.
+pub(super) fn binary_message(header: &impl serde::Serialize, payload: &[u8]) -> Result<Vec<u8>> {
+    let header = serde_json::to_vec(header)?;
+    let header_len = u32::try_from(header.len()).context("binary message header too large")?;
+    let mut message = Vec::with_capacity(4 + header.len() + payload.len());
+    message.extend_from_slice(&header_len.to_be_bytes());
+    message.extend_from_slice(&header);
+    message.extend_from_slice(payload);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscribe_payload_defaults_and_validation() {
+        let options: FrameStreamOptions =
+            serde_json::from_value(serde_json::json!({ "encoding": "png", "scale": 0.5 })).unwrap();
+        assert_eq!(options.fps, 15.0);
+        assert_eq!(options.encoding, FrameStreamEncoding::Png);
+        assert!(options.validate().is_ok());
+
+        let bad = FrameStreamOptions {
+            scale: 1.5,
+            ..Default::default()
+        };
+        assert!(bad.validate().is_err());
+        let bad = FrameStreamOptions {
+            fps: 0.0,
+            ..Default::default()
+        };
+        assert!(bad.validate().is_err());
+        assert!(
+            serde_json::from_value::<FrameStreamOptions>(serde_json::json!({ "fmt": "png" }))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn encoded_frame_carries_scaled_size_in_header() {
+        let options = FrameStreamOptions {
+            scale: 0.5,
+            encoding: FrameStreamEncoding::Rgba,
+            ..Default::default()
+        };
+        let frame = encode_stream_frame(vec![255; 4 * 4 * 4], [4, 4], &options, 7).unwrap();
+        let (frame_type, header, payload) = parse_binary_frame_header(&frame).unwrap();
+        assert_eq!(frame_type, "frame");
+        assert_eq!(header["frameIndex"], 7);
+        assert_eq!(header["width"], 2);
+        assert_eq!(header["encoding"], "rgba");
+        assert_eq!(payload.len(), 2 * 2 * 4);
+    }
+
+    #[test]
+    fn streams_are_due_only_when_stale_and_interval_elapsed() {
+        let mut streams = FrameStreams::default();
+        let mailbox = FrameMailbox::default();
+        let options = FrameStreamOptions {
+            fps: 10.0,
+            ..Default::default()
+        };
+        streams.subscribe(1, options, mailbox.clone());
+        let t0 = Instant::now();
+        assert_eq!(streams.due(t0).len(), 1);
+
+        streams.publish(1, t0, |index| Some(vec![index as u8]));
+        assert_eq!(mailbox.lock().unwrap().as_deref(), Some(&[0u8][..]));
+        assert!(streams.due(t0).is_empty());
+
+        streams.mark_stale();
+        assert!(streams.due(t0 + Duration::from_millis(50)).is_empty());
+        assert_eq!(
+            streams.next_due_in(t0 + Duration::from_millis(50)),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(streams.due(t0 + Duration::from_millis(100)).len(), 1);
+
+        streams.unsubscribe(1);
+        assert!(streams.is_empty());
+    }
+}
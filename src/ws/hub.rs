@@ -1,11 +1,30 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
 use crossbeam_channel::Sender;
 use tungstenite::Message;
 
+use super::frame_stream::{FrameMailbox, FrameStreamOptions, FrameStreams};
+use crate::dsl::SceneDSL;
+
+/// One connected editor.
+struct ClientSession {
+    id: u64,
+    sender: Sender<Message>,
+    /// The last scene from this client that the renderer accepted.
+    last_good: Option<SceneDSL>,
+}
+
 #[derive(Clone, Default)]
 pub struct WsHub {
-    clients: Arc<Mutex<Vec<Sender<Message>>>>,
+    clients: Arc<Mutex<Vec<ClientSession>>>,
+    next_client_id: Arc<AtomicU64>,
+    frame_streams: Arc<Mutex<FrameStreams>>,
 }
 
 impl WsHub {
@@ -28,12 +47,180 @@ impl WsHub {
         let Ok(mut clients) = self.clients.lock() else {
             return;
         };
-        clients.retain(|sender| sender.send(message.clone()).is_ok());
+        clients.retain(|client| client.sender.send(message.clone()).is_ok());
+    }
+
+    /// Sends a reply to the client a request arrived from. Request ids are
+    /// chosen by clients and only unique per connection, so replies are
+    /// addressed by client id. Messages without a sender (headless renders,
+    /// internal errors) are broadcast instead.
+    pub fn reply(&self, client_id: Option<u64>, text: String) {
+        self.reply_message(client_id, Message::Text(text));
+    }
+
+    pub fn reply_binary(&self, client_id: Option<u64>, bytes: Vec<u8>) {
+        self.reply_message(client_id, Message::Binary(bytes));
+    }
+
+    fn reply_message(&self, client_id: Option<u64>, message: Message) {
+        match client_id {
+            Some(client_id) => {
+                self.send_to(client_id, message);
+            }
+            None => self.broadcast_message(message),
+        }
+    }
+
+    /// Queues `message` for one client; false if it is no longer connected.
+    pub fn send_to(&self, client_id: u64, message: Message) -> bool {
+        let Ok(mut clients) = self.clients.lock() else {
+            return false;
+        };
+        let Some(index) = clients.iter().position(|client| client.id == client_id) else {
+            return false;
+        };
+        if clients[index].sender.send(message).is_ok() {
+            return true;
+        }
+        clients.remove(index);
+        false
+    }
+
+    pub(super) fn register_client(&self, sender: Sender<Message>) -> u64 {
+        let id = self.next_client_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.push(ClientSession {
+                id,
+                sender,
+                last_good: None,
+            });
+        }
+        id
     }
 
-    pub(super) fn register_client(&self, sender: Sender<Message>) {
+    /// Drops a disconnected client's session and frame stream.
+    pub(super) fn unregister_client(&self, client_id: u64) {
         if let Ok(mut clients) = self.clients.lock() {
-            clients.push(sender);
+            clients.retain(|client| client.id != client_id);
+        }
+        self.unsubscribe_frames(client_id);
+    }
+
+    /// Stores `scene` as the last-good scene of the client that sent it.
+    /// Scenes without a sender only update the shared last-good scene the app
+    /// keeps.
+    pub fn set_session_last_good(&self, client_id: Option<u64>, scene: &SceneDSL) {
+        let Some(client_id) = client_id else {
+            return;
+        };
+        if let Ok(mut clients) = self.clients.lock()
+            && let Some(client) = clients.iter_mut().find(|client| client.id == client_id)
+        {
+            client.last_good = Some(scene.clone());
+        }
+    }
+
+    pub fn session_last_good(&self, client_id: u64) -> Option<SceneDSL> {
+        let clients = self.clients.lock().ok()?;
+        clients
+            .iter()
+            .find(|client| client.id == client_id)
+            .and_then(|client| client.last_good.clone())
+    }
+
+    pub(super) fn subscribe_frames(
+        &self,
+        client_id: u64,
+        options: FrameStreamOptions,
+        mailbox: FrameMailbox,
+    ) {
+        if let Ok(mut streams) = self.frame_streams.lock() {
+            streams.subscribe(client_id, options, mailbox);
         }
     }
+
+    pub(super) fn unsubscribe_frames(&self, client_id: u64) {
+        if let Ok(mut streams) = self.frame_streams.lock() {
+            streams.unsubscribe(client_id);
+        }
+    }
+
+    pub fn has_frame_subscribers(&self) -> bool {
+        self.frame_streams
+            .lock()
+            .map(|streams| !streams.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// Marks every frame stream as owing a frame of the new render.
+    pub fn mark_frame_streams_stale(&self) {
+        if let Ok(mut streams) = self.frame_streams.lock() {
+            streams.mark_stale();
+        }
+    }
+
+    /// Subscribers owed a frame at `now`, with their stream options.
+    pub fn due_frame_streams(&self, now: Instant) -> Vec<(u64, FrameStreamOptions)> {
+        self.frame_streams
+            .lock()
+            .map(|streams| streams.due(now))
+            .unwrap_or_default()
+    }
+
+    pub fn next_frame_stream_due_in(&self, now: Instant) -> Option<Duration> {
+        self.frame_streams
+            .lock()
+            .ok()
+            .and_then(|streams| streams.next_due_in(now))
+    }
+
+    /// Encodes and queues a frame for `client_id`; `encode` receives the
+    /// stream's next frame index and returns `None` to skip this frame.
+    pub fn publish_frame(
+        &self,
+        client_id: u64,
+        now: Instant,
+        encode: impl FnOnce(u64) -> Option<Vec<u8>>,
+    ) {
+        if let Ok(mut streams) = self.frame_streams.lock() {
+            streams.publish(client_id, now, encode);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(message: Message) -> String {
+        match message {
+            Message::Text(text) => text,
+            other => panic!("expected text, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn replies_reach_only_the_requesting_client() {
+        let hub = WsHub::default();
+        let (tx_a, rx_a) = crossbeam_channel::unbounded();
+        let (tx_b, rx_b) = crossbeam_channel::unbounded();
+        let a = hub.register_client(tx_a);
+        let b = hub.register_client(tx_b);
+        assert_ne!(a, b);
+
+        hub.reply(Some(a), "for a".to_string());
+        assert_eq!(text(rx_a.try_recv().unwrap()), "for a");
+        assert!(rx_b.try_recv().is_err());
+
+        // Messages without a sender fall back to broadcast.
+        hub.reply(None, "shared".to_string());
+        assert_eq!(rx_a.try_iter().count(), 1);
+        assert_eq!(rx_b.try_iter().count(), 1);
+
+        // Replies to a disconnected client are dropped, not broadcast.
+        hub.unregister_client(a);
+        hub.reply(Some(a), "for a".to_string());
+        assert!(rx_b.try_recv().is_err());
+        assert_eq!(hub.client_count(), 1);
+    }
 }
@@ -1,14 +1,36 @@
+mod analysis;
 mod asset_transfer;
+mod auth;
+mod compression;
+mod connection;
 mod debug_artifacts;
 mod dispatch;
+mod frame_stream;
 mod hub;
+mod pixel_read;
+mod render_to_bytes;
+mod resource_query;
 mod scene_delta;
+mod session_log;
 mod shader_templates;
+mod stdio;
 
+pub use analysis::{
+    AnalysisKind, AnalysisRequest, DiffStatsAnalysis, broadcast_diff_stats_analysis,
+    broadcast_histogram_analysis, broadcast_scope_bins,
+};
 use asset_transfer::{
-    AssetTransferState, AssetUploadEndPayload, AssetUploadStartPayload, UploadFinalizeResult,
-    handle_binary_asset_upload, request_missing_assets, send_asset_upload_ack,
-    send_asset_upload_nack,
+    AssetTransferState, AssetUploadEndPayload, AssetUploadStartPayload, UploadAssetHeader,
+    UploadFinalizeResult, handle_binary_asset_upload, request_missing_assets, send_asset_list,
+    send_asset_upload_ack, send_asset_upload_nack,
+};
+pub use auth::WsAccessPolicy;
+use auth::{HandshakeAccess, accept_with_policy, authenticate_first_message};
+pub use compression::WsCompression;
+use compression::{COMPRESSION_HEADER, decode_compressed_frame};
+pub use connection::WsServerOptions;
+use connection::{
+    DisconnectReason, Keepalive, KeepaliveAction, broadcast_client_connected, end_client_session,
 };
 use debug_artifacts::{
     DebugArtifactTransferState, DebugArtifactUploadChunkHeader, parse_binary_frame_header,
@@ -18,14 +40,32 @@ pub use debug_artifacts::{
     broadcast_debug_artifact_binary_upload, broadcast_debug_artifact_delete,
     broadcast_debug_artifact_request, broadcast_debug_artifact_upsert,
 };
-use dispatch::{handle_text_message, send_error};
+use dispatch::{
+    handle_text_message, handle_upload_asset_frame, prepare_request_scene, send_error,
+};
+use frame_stream::{FrameMailbox, binary_message, encode_rgba_image};
+pub use frame_stream::{FrameStreamEncoding, FrameStreamOptions, encode_stream_frame};
 pub use hub::WsHub;
-use scene_delta::delta_updates_only_uniform_values;
+pub use pixel_read::{
+    PixelReadKind, PixelReadRequest, PixelRect, broadcast_pixel_read_result,
+};
+use render_to_bytes::{RenderToBytesPayload, render_to_bytes};
+pub use resource_query::{
+    PreviewTextureRequest, ResourceQuery, broadcast_resources, broadcast_texture_preview,
+};
+use scene_delta::{
+    delta_updates_only_uniform_values, merge_uniform_delta_nodes, param_updates_to_delta,
+    patch_scene_node_params,
+};
 pub use scene_delta::{
-    SceneCache, SceneCacheConnectionsById, SceneCacheNodesById, SceneDelta, SceneDeltaConnections,
-    SceneDeltaNodes, SceneOutputs, apply_scene_delta, apply_scene_update,
-    has_dangling_connection_references, materialize_scene_dsl, prune_invalid_connections,
+    ParamUpdate, ParamUpdatePayload, SceneCache, SceneCacheConnectionsById, SceneCacheNodesById,
+    SceneDelta, SceneDeltaConnections, SceneDeltaNodes, SceneOutputs, apply_scene_delta,
+    apply_scene_update, has_dangling_connection_references, materialize_scene_dsl,
+    prune_invalid_connections,
 };
+pub use session_log::WsRecorder;
+use session_log::spawn_ws_replay;
+use stdio::spawn_stdio_server;
 
 use std::{
     collections::HashMap,
@@ -38,7 +78,7 @@ use std::{
 use anyhow::{Context, Result};
 use crossbeam_channel::{Receiver, Sender};
 use serde_json::Value;
-use tungstenite::{Error as WsError, Message, accept};
+use tungstenite::{Error as WsError, Message};
 
 use crate::{
     asset_store::AssetStore,
@@ -51,12 +91,24 @@ use crate::{
     ui::resource_tree::ResourceSnapshot,
 };
 
+/// Where direct replies to the connection a message arrived on go: the
+/// client's WebSocket, or stdout in `--stdio` mode.
+pub(super) trait ClientSink {
+    fn send(&mut self, message: Message) -> tungstenite::Result<()>;
+}
+
+impl ClientSink for tungstenite::WebSocket<std::net::TcpStream> {
+    fn send(&mut self, message: Message) -> tungstenite::Result<()> {
+        tungstenite::WebSocket::send(self, message)
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 struct SceneResyncRequestPayload {
     reason: String,
 }
 
-fn send_scene_resync_request(ws: &mut tungstenite::WebSocket<std::net::TcpStream>, reason: &str) {
+fn send_scene_resync_request(ws: &mut dyn ClientSink, reason: &str) {
     let req = WSMessage {
         msg_type: "scene_resync_request".to_string(),
         timestamp: now_millis(),
@@ -90,6 +142,15 @@ fn spawn_server_ping_loop(hub: WsHub) {
     });
 }
 
+/// The connection a request arrived on: replies and frame streams are routed
+/// by its id.
+struct ClientContext<'a> {
+    id: u64,
+    compression: WsCompression,
+    hub: &'a WsHub,
+    mailbox: &'a FrameMailbox,
+}
+
 #[derive(Debug, Clone)]
 pub enum ParsedSceneSource {
     SceneUpdate,
@@ -101,17 +162,21 @@ pub enum SceneUpdate {
     Parsed {
         scene: SceneDSL,
         request_id: Option<String>,
+        /// Connection the request arrived on; replies go only to it.
+        client_id: Option<u64>,
         source: ParsedSceneSource,
         perf_trace: Option<ScenePerfTrace>,
     },
     UniformDelta {
         updated_nodes: Vec<Node>,
         request_id: Option<String>,
+        client_id: Option<u64>,
         perf_trace: Option<ScenePerfTrace>,
     },
     ParseError {
         message: String,
         request_id: Option<String>,
+        client_id: Option<u64>,
     },
     DebugArtifactUpsert {
         item: DebugArtifactItem,
@@ -124,6 +189,29 @@ pub enum SceneUpdate {
     DebugArtifactDelete {
         artifact_id: String,
     },
+    /// The editor asked for a `render_stats` report of the latest frame.
+    RenderStatsRequest {
+        request_id: Option<String>,
+        client_id: Option<u64>,
+    },
+    /// `sample_pixel` / `read_region`, answered from the last rendered frame.
+    PixelRead {
+        request: PixelReadRequest,
+        request_id: Option<String>,
+        client_id: Option<u64>,
+    },
+    /// `get_resources` / `preview_texture`, answered from the live shader space.
+    ResourceQuery {
+        query: ResourceQuery,
+        request_id: Option<String>,
+        client_id: Option<u64>,
+    },
+    /// `get_analysis`, answered by running the scope renderers on the output.
+    Analysis {
+        request: AnalysisRequest,
+        request_id: Option<String>,
+        client_id: Option<u64>,
+    },
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -131,11 +219,27 @@ pub enum SceneUpdate {
 struct RuntimeSceneUpdatePayload {
     scene: SceneDSL,
     functions: Vec<crate::state_machine::mutation_function::FunctionResource>,
+    /// Feature flag overrides (`name`, `!name`, `name=false`) applied on top of
+    /// the scene's `featureFlags`, e.g. to preview the mobile variant.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    features: Vec<String>,
+}
+
+/// `validate_scene` payload.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ValidateScenePayload {
+    scene: SceneDSL,
+    /// Feature flag overrides, as in `scene_update`.
+    #[serde(default)]
+    features: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ScenePerfTrace {
     pub trace_id: String,
+    /// Connection the traced update arrived on.
+    pub client_id: Option<u64>,
     pub client_sent_at_ms: u64,
     pub server_received_at_ms: u64,
     pub message_bytes: usize,
@@ -171,6 +275,7 @@ impl SceneUpdate {
 
 fn create_scene_perf_trace(
     request_id: &Option<String>,
+    client_id: Option<u64>,
     client_sent_at_ms: u64,
     server_received_at_ms: u64,
     message_bytes: usize,
@@ -182,6 +287,7 @@ fn create_scene_perf_trace(
         .to_string();
     Some(ScenePerfTrace {
         trace_id,
+        client_id,
         client_sent_at_ms,
         server_received_at_ms,
         message_bytes,
@@ -267,6 +373,128 @@ pub fn broadcast_pass_target_sizes(hub: &WsHub, snapshot: &ResourceSnapshot, sce
     }
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NonFiniteStatsPayload<'a> {
+    mode: String,
+    sentinel: [f32; 4],
+    non_finite_fragment_count: u64,
+    passes: Vec<NonFinitePassCount<'a>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NonFinitePassCount<'a> {
+    pass_name: &'a str,
+    count: u64,
+}
+
+/// Reports how many NaN/Inf fragments each sanitized pass replaced with the
+/// sentinel colour in the last rendered frame.
+pub fn broadcast_non_finite_stats(
+    hub: &WsHub,
+    options: crate::renderer::NonFiniteSanitizeOptions,
+    per_pass: &[(String, u64)],
+) {
+    let message = WSMessage {
+        msg_type: "non_finite_stats".to_string(),
+        timestamp: now_millis(),
+        request_id: None,
+        payload: Some(NonFiniteStatsPayload {
+            mode: format!("{:?}", options.mode),
+            sentinel: options.sentinel,
+            non_finite_fragment_count: per_pass.iter().map(|(_, count)| count).sum(),
+            passes: per_pass
+                .iter()
+                .map(|(pass_name, count)| NonFinitePassCount {
+                    pass_name,
+                    count: *count,
+                })
+                .collect(),
+        }),
+    };
+    if let Ok(text) = serde_json::to_string(&message) {
+        hub.broadcast(text);
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PickResultPayload<'a> {
+    x: u32,
+    y: u32,
+    target: Option<&'a crate::renderer::PickTarget>,
+}
+
+/// Reports the pass under a canvas click while GPU picking is on.
+pub fn broadcast_pick_result(
+    hub: &WsHub,
+    x: u32,
+    y: u32,
+    target: Option<&crate::renderer::PickTarget>,
+) {
+    let message = WSMessage {
+        msg_type: "pick_result".to_string(),
+        timestamp: now_millis(),
+        request_id: None,
+        payload: Some(PickResultPayload { x, y, target }),
+    };
+    if let Ok(text) = serde_json::to_string(&message) {
+        hub.broadcast(text);
+    }
+}
+
+/// Answers `render_stats_request` with the latest frame's per-pass CPU and GPU
+/// timings and the texture memory of the current shader space.
+pub fn broadcast_render_stats(
+    hub: &WsHub,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    profile: Option<&rust_wgpu_fiber::shader_space::RenderProfile>,
+    gpu_timing: Option<&crate::renderer::GpuFrameTiming>,
+    texture_bytes: u64,
+) {
+    let message = WSMessage {
+        msg_type: "render_stats".to_string(),
+        timestamp: now_millis(),
+        request_id,
+        payload: Some(crate::profile::render_stats_payload(
+            profile,
+            gpu_timing,
+            texture_bytes,
+        )),
+    };
+    if let Ok(text) = serde_json::to_string(&message) {
+        hub.reply(client_id, text);
+    }
+}
+
+fn render_progress_text(
+    request_id: Option<String>,
+    progress: &crate::renderer::RenderProgress,
+) -> Option<String> {
+    let message = WSMessage {
+        msg_type: "render_progress".to_string(),
+        timestamp: now_millis(),
+        request_id,
+        payload: Some(progress),
+    };
+    serde_json::to_string(&message).ok()
+}
+
+/// Reports a finished build step or pass while a scene rebuilds or renders,
+/// so editors can show progress for heavy scenes.
+pub fn broadcast_render_progress(
+    hub: &WsHub,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    progress: &crate::renderer::RenderProgress,
+) {
+    if let Some(text) = render_progress_text(request_id, progress) {
+        hub.reply(client_id, text);
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ScenePerfTracePayload {
@@ -290,6 +518,7 @@ pub fn broadcast_scene_perf_trace(
     update_kind: &str,
 ) {
     let end_to_end_ms = now_millis().saturating_sub(trace.client_sent_at_ms) as f64;
+    let client_id = trace.client_id;
     let message = WSMessage {
         msg_type: "perf_trace".to_string(),
         timestamp: now_millis(),
@@ -309,7 +538,7 @@ pub fn broadcast_scene_perf_trace(
         }),
     };
     if let Ok(text) = serde_json::to_string(&message) {
-        hub.broadcast(text);
+        hub.reply(client_id, text);
     }
 }
 
@@ -326,17 +555,37 @@ pub fn spawn_ws_server(
     hub: WsHub,
     last_good: Arc<Mutex<Option<SceneDSL>>>,
     asset_store: AssetStore,
+    options: WsServerOptions,
     ui_wake: Option<UiWakeCallback>,
 ) -> Result<thread::JoinHandle<()>> {
-    let scene_cache = Arc::new(Mutex::new(None::<SceneCache>));
+    if options.stdio {
+        return Ok(spawn_stdio_server(
+            scene_tx,
+            scene_drop_rx,
+            hub,
+            last_good,
+            asset_store,
+            options,
+            ui_wake,
+        ));
+    }
+
     let addr_str = addr.to_string();
     let server =
         TcpListener::bind(addr).with_context(|| format!("failed to bind ws server at {addr}"))?;
 
     // Editor-side heartbeat: server periodically emits {type:"ping"}.
     // (Client may reply with {type:"pong"}, which we accept as a no-op.)
+    // Client liveness is tracked per connection; see `connection`.
     spawn_server_ping_loop(hub.clone());
 
+    if let Some(path) = options.replay.clone() {
+        let local_addr = server
+            .local_addr()
+            .context("failed to read ws server address")?;
+        spawn_ws_replay(path, local_addr, options.access.token.clone(), hub.clone())?;
+    }
+
     Ok(thread::spawn(move || {
         if let Err(e) = run_ws_server(
             server,
@@ -345,20 +594,20 @@ pub fn spawn_ws_server(
             scene_drop_rx,
             hub.clone(),
             last_good,
-            scene_cache,
             asset_store,
+            options,
             ui_wake,
         ) {
-            report_internal_error(&hub, None, "WS_SERVER_FAILED", &format!("{e:#}"));
+            report_internal_error(&hub, "WS_SERVER_FAILED", &format!("{e:#}"));
         }
     }))
 }
 
-fn report_internal_error(hub: &WsHub, request_id: Option<String>, code: &str, message: &str) {
+fn report_internal_error(hub: &WsHub, code: &str, message: &str) {
     let err = WSMessage {
         msg_type: "error".to_string(),
         timestamp: now_millis(),
-        request_id,
+        request_id: None,
         payload: Some(ErrorPayload {
             code: code.to_string(),
             message: message.to_string(),
@@ -377,10 +626,11 @@ fn run_ws_server(
     scene_drop_rx: Receiver<SceneUpdate>,
     hub: WsHub,
     last_good: Arc<Mutex<Option<SceneDSL>>>,
-    scene_cache: Arc<Mutex<Option<SceneCache>>>,
     asset_store: AssetStore,
+    options: WsServerOptions,
     ui_wake: Option<UiWakeCallback>,
 ) -> Result<()> {
+    let options = Arc::new(options);
     // Treat server lifecycle logs as editor-facing diagnostics.
     let startup = WSMessage::<Value> {
         msg_type: "debug".to_string(),
@@ -398,7 +648,7 @@ fn run_ws_server(
         let stream = match stream {
             Ok(s) => s,
             Err(e) => {
-                report_internal_error(&hub, None, "WS_ACCEPT_FAILED", &format!("{e:#}"));
+                report_internal_error(&hub, "WS_ACCEPT_FAILED", &format!("{e:#}"));
                 continue;
             }
         };
@@ -407,8 +657,8 @@ fn run_ws_server(
         let scene_drop_rx = scene_drop_rx.clone();
         let hub = hub.clone();
         let last_good = last_good.clone();
-        let scene_cache = scene_cache.clone();
         let asset_store = asset_store.clone();
+        let options = options.clone();
         let ui_wake = ui_wake.clone();
 
         thread::spawn(move || {
@@ -418,11 +668,11 @@ fn run_ws_server(
                 scene_drop_rx,
                 hub.clone(),
                 last_good,
-                scene_cache,
                 asset_store,
+                &options,
                 ui_wake,
             ) {
-                report_internal_error(&hub, None, "WS_CLIENT_ENDED", &format!("{e:#}"));
+                report_internal_error(&hub, "WS_CLIENT_ENDED", &format!("{e:#}"));
             }
         });
     }
@@ -436,29 +686,67 @@ fn handle_client(
     scene_drop_rx: Receiver<SceneUpdate>,
     hub: WsHub,
     last_good: Arc<Mutex<Option<SceneDSL>>>,
-    scene_cache: Arc<Mutex<Option<SceneCache>>>,
     asset_store: AssetStore,
+    options: &WsServerOptions,
     ui_wake: Option<UiWakeCallback>,
 ) -> Result<()> {
     // Handshake is easier with a blocking socket, switch to non-blocking afterwards.
-    let mut ws = accept(stream).context("websocket handshake failed")?;
+    let (mut ws, handshake, compression) = accept_with_policy(stream, &options.access)?;
+    if handshake == HandshakeAccess::AwaitingToken {
+        // Authenticate before registering, so rejected clients never see
+        // broadcasts.
+        authenticate_first_message(&mut ws, &options.access)?;
+    }
     ws.get_mut()
         .set_nonblocking(true)
         .context("failed to set tcp non-blocking")?;
 
     let (client_tx, client_rx) = crossbeam_channel::unbounded::<Message>();
-    hub.register_client(client_tx);
+    let client_id = hub.register_client(client_tx);
+    broadcast_client_connected(&hub, client_id, options, compression);
+    // Each client diffs against its own baseline, so deltas from one editor
+    // never apply on top of another editor's scene.
+    let scene_cache = Arc::new(Mutex::new(None::<SceneCache>));
+    let frame_mailbox = FrameMailbox::default();
     let mut transfer_state = AssetTransferState::default();
     let mut debug_artifact_transfer_state = DebugArtifactTransferState::default();
+    let mut keepalive = Keepalive::new(options, Instant::now());
 
-    loop {
+    let reason = loop {
         // 1) flush outbound (validation errors etc)
         while let Ok(message) = client_rx.try_recv() {
-            let _ = ws.send(message);
+            let _ = ws.send(compression.encode_outbound(message));
+        }
+        let frame = frame_mailbox.lock().ok().and_then(|mut slot| slot.take());
+        if let Some(frame) = frame {
+            let _ = ws.send(compression.encode_outbound(Message::Binary(frame)));
         }
 
         // 2) read inbound
-        match ws.read() {
+        let inbound = ws.read();
+        if let Ok(message) = &inbound {
+            keepalive.observe_inbound(Instant::now());
+            if let Some(recorder) = &options.record {
+                recorder.record(client_id, message);
+            }
+        }
+        let inbound = match inbound {
+            Ok(Message::Binary(data)) => {
+                let inflated = parse_binary_frame_header(&data)
+                    .filter(|(frame_type, _, _)| frame_type == "compressed")
+                    .map(|(_, header, payload)| decode_compressed_frame(header, payload));
+                match inflated {
+                    None => Ok(Message::Binary(data)),
+                    Some(Ok(message)) => Ok(message),
+                    Some(Err(error)) => {
+                        send_error(&mut ws, None, "PARSE_ERROR", &format!("{error:#}"));
+                        continue;
+                    }
+                }
+            }
+            other => other,
+        };
+        match inbound {
             Ok(Message::Text(text)) => {
                 if let Err(e) = handle_text_message(
                     &mut ws,
@@ -470,62 +758,122 @@ fn handle_client(
                     &asset_store,
                     &mut transfer_state,
                     &mut debug_artifact_transfer_state,
+                    &ClientContext {
+                        id: client_id,
+                        compression,
+                        hub: &hub,
+                        mailbox: &frame_mailbox,
+                    },
                     ui_wake.as_ref(),
                 ) {
                     report_internal_error(
                         &hub,
-                        None,
                         "WS_HANDLE_MESSAGE_FAILED",
                         &format!("{e:#}"),
                     );
                 }
             }
-            Ok(Message::Binary(data)) => {
-                if let Some((frame_type, header_value, chunk_payload)) =
-                    parse_binary_frame_header(&data)
-                    && frame_type == "debug_artifact_upload_chunk"
-                {
-                    match serde_json::from_value::<DebugArtifactUploadChunkHeader>(header_value) {
-                        Ok(header) => {
-                            if let Err(error) =
-                                debug_artifact_transfer_state.chunk(header, chunk_payload)
-                            {
-                                send_error(
-                                    &mut ws,
-                                    None,
-                                    "DEBUG_ARTIFACT_UPLOAD_CHUNK_INVALID",
-                                    &format!("{error:#}"),
-                                );
-                            }
-                        }
-                        Err(error) => {
-                            send_error(
-                                &mut ws,
-                                None,
-                                "PARSE_ERROR",
-                                &format!("invalid debug artifact chunk header: {error}"),
-                            );
-                        }
-                    }
-                } else {
-                    handle_binary_asset_upload(&mut ws, &data, &mut transfer_state, &asset_store);
-                }
-            }
+            Ok(Message::Binary(data)) => handle_binary_message(
+                &mut ws,
+                &data,
+                &scene_tx,
+                &scene_drop_rx,
+                &scene_cache,
+                &asset_store,
+                &mut transfer_state,
+                &mut debug_artifact_transfer_state,
+                ui_wake.as_ref(),
+            ),
             Ok(Message::Ping(payload)) => {
                 let _ = ws.send(Message::Pong(payload));
             }
             Ok(Message::Pong(_)) => {}
             Ok(Message::Frame(_)) => {}
-            Ok(Message::Close(_)) => break,
+            Ok(Message::Close(_)) => break DisconnectReason::Closed,
             Err(WsError::Io(ref io)) if io.kind() == std::io::ErrorKind::WouldBlock => {
                 // nothing to read
             }
-            Err(WsError::AlreadyClosed) | Err(WsError::ConnectionClosed) => break,
-            Err(e) => return Err(e).context("websocket read failed"),
+            Err(WsError::AlreadyClosed) | Err(WsError::ConnectionClosed) => {
+                break DisconnectReason::Closed;
+            }
+            Err(e) => {
+                end_client_session(&hub, client_id, DisconnectReason::Error);
+                return Err(e).context("websocket read failed");
+            }
+        }
+
+        // 3) keepalive
+        match keepalive.poll(Instant::now()) {
+            KeepaliveAction::Idle => {}
+            KeepaliveAction::Ping => {
+                let _ = ws.send(Message::Ping(Vec::new()));
+            }
+            KeepaliveAction::TimedOut => {
+                let _ = ws.close(None);
+                let _ = ws.flush();
+                break DisconnectReason::TimedOut;
+            }
         }
 
         thread::sleep(Duration::from_millis(5));
-    }
+    };
 
+    end_client_session(&hub, client_id, reason);
     Ok(())
 }
+
+/// Dispatches a binary message: debug artifact chunks, `upload_asset` frames
+/// and legacy chunked asset uploads.
+#[allow(clippy::too_many_arguments)]
+fn handle_binary_message(
+    ws: &mut dyn ClientSink,
+    data: &[u8],
+    scene_tx: &Sender<SceneUpdate>,
+    scene_drop_rx: &Receiver<SceneUpdate>,
+    scene_cache: &Arc<Mutex<Option<SceneCache>>>,
+    asset_store: &AssetStore,
+    transfer_state: &mut AssetTransferState,
+    debug_artifact_transfer_state: &mut DebugArtifactTransferState,
+    ui_wake: Option<&UiWakeCallback>,
+) {
+    if let Some((frame_type, header_value, chunk_payload)) = parse_binary_frame_header(data)
+        && frame_type == "debug_artifact_upload_chunk"
+    {
+        match serde_json::from_value::<DebugArtifactUploadChunkHeader>(header_value) {
+            Ok(header) => {
+                if let Err(error) = debug_artifact_transfer_state.chunk(header, chunk_payload) {
+                    send_error(
+                        ws,
+                        None,
+                        "DEBUG_ARTIFACT_UPLOAD_CHUNK_INVALID",
+                        &format!("{error:#}"),
+                    );
+                }
+            }
+            Err(error) => {
+                send_error(
+                    ws,
+                    None,
+                    "PARSE_ERROR",
+                    &format!("invalid debug artifact chunk header: {error}"),
+                );
+            }
+        }
+    } else if let Some((frame_type, header_value, asset_bytes)) = parse_binary_frame_header(data)
+        && frame_type == "upload_asset"
+    {
+        handle_upload_asset_frame(
+            ws,
+            header_value,
+            asset_bytes,
+            scene_tx,
+            scene_drop_rx,
+            scene_cache,
+            asset_store,
+            transfer_state,
+            ui_wake,
+        );
+    } else {
+        handle_binary_asset_upload(ws, data, transfer_state, asset_store);
+    }
+}
@@ -0,0 +1,191 @@
+//! `sample_pixel` / `read_region`: float RGBA readback of the rendered output
+//! or a named intermediate texture, for editor eyedroppers.
+
+use anyhow::bail;
+
+use super::*;
+
+/// Largest region `read_region` returns, so a stray request cannot stall the
+/// render thread on a full-frame float readback serialized as JSON.
+pub const MAX_READ_REGION_PIXELS: u64 = 256 * 256;
+
+/// Texel rectangle with its origin at the top-left of the texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelReadKind {
+    SamplePixel,
+    ReadRegion,
+}
+
+/// A parsed `sample_pixel` or `read_region` request. `texture: None` reads
+/// the scene output (the Composite result before display encoding).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelReadRequest {
+    pub kind: PixelReadKind,
+    pub rect: PixelRect,
+    pub texture: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SamplePixelPayload {
+    x: u32,
+    y: u32,
+    #[serde(default)]
+    texture: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReadRegionPayload {
+    rect: PixelRect,
+    #[serde(default)]
+    texture: Option<String>,
+}
+
+impl PixelReadRequest {
+    pub(super) fn parse(kind: PixelReadKind, payload: Option<Value>) -> Result<Self> {
+        let payload = payload.context("missing payload")?;
+        let request = match kind {
+            PixelReadKind::SamplePixel => {
+                let payload: SamplePixelPayload = serde_json::from_value(payload)?;
+                Self {
+                    kind,
+                    rect: PixelRect {
+                        x: payload.x,
+                        y: payload.y,
+                        width: 1,
+                        height: 1,
+                    },
+                    texture: payload.texture,
+                }
+            }
+            PixelReadKind::ReadRegion => {
+                let payload: ReadRegionPayload = serde_json::from_value(payload)?;
+                Self {
+                    kind,
+                    rect: payload.rect,
+                    texture: payload.texture,
+                }
+            }
+        };
+        let pixels = u64::from(request.rect.width) * u64::from(request.rect.height);
+        if pixels == 0 {
+            bail!("rect must not be empty");
+        }
+        if pixels > MAX_READ_REGION_PIXELS {
+            bail!("rect covers {pixels} pixels, at most {MAX_READ_REGION_PIXELS} are allowed");
+        }
+        Ok(request)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SamplePixelResultPayload<'a> {
+    texture: &'a str,
+    x: u32,
+    y: u32,
+    rgba: [f32; 4],
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadRegionResultPayload<'a> {
+    texture: &'a str,
+    rect: PixelRect,
+    /// Row-major RGBA, four floats per pixel.
+    pixels: Vec<f32>,
+}
+
+/// Answers a pixel read with `sample_pixel_result` or `read_region_result`.
+/// `pixels` holds one RGBA value per texel of `request.rect`, row-major.
+pub fn broadcast_pixel_read_result(
+    hub: &WsHub,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    request: &PixelReadRequest,
+    texture: &str,
+    pixels: &[[f32; 4]],
+) {
+    let rect = request.rect;
+    let (msg_type, payload) = match request.kind {
+        PixelReadKind::SamplePixel => (
+            "sample_pixel_result",
+            serde_json::to_value(SamplePixelResultPayload {
+                texture,
+                x: rect.x,
+                y: rect.y,
+                rgba: pixels.first().copied().unwrap_or_default(),
+            }),
+        ),
+        PixelReadKind::ReadRegion => (
+            "read_region_result",
+            serde_json::to_value(ReadRegionResultPayload {
+                texture,
+                rect,
+                pixels: pixels.iter().flatten().copied().collect(),
+            }),
+        ),
+    };
+    let Ok(payload) = payload else {
+        return;
+    };
+    let message = WSMessage {
+        msg_type: msg_type.to_string(),
+        timestamp: now_millis(),
+        request_id,
+        payload: Some(payload),
+    };
+    if let Ok(text) = serde_json::to_string(&message) {
+        hub.reply(client_id, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_pixel_is_a_one_pixel_rect() {
+        let request = PixelReadRequest::parse(
+            PixelReadKind::SamplePixel,
+            Some(serde_json::json!({ "x": 3, "y": 4, "texture": "pass_blur" })),
+        )
+        .unwrap();
+        assert_eq!(
+            request.rect,
+            PixelRect {
+                x: 3,
+                y: 4,
+                width: 1,
+                height: 1
+            }
+        );
+        assert_eq!(request.texture.as_deref(), Some("pass_blur"));
+    }
+
+    #[test]
+    fn read_region_rejects_empty_and_oversized_rects() {
+        let parse = |width: u32, height: u32| {
+            PixelReadRequest::parse(
+                PixelReadKind::ReadRegion,
+                Some(serde_json::json!({
+                    "rect": { "x": 0, "y": 0, "width": width, "height": height }
+                })),
+            )
+        };
+        assert!(parse(16, 16).is_ok());
+        assert!(parse(0, 16).is_err());
+        assert!(parse(1024, 1024).is_err());
+        assert!(PixelReadRequest::parse(PixelReadKind::ReadRegion, None).is_err());
+    }
+}
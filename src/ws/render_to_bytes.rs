@@ -0,0 +1,115 @@
+//! `render_to_bytes`: render a SceneDSL on a private headless device and reply
+//! with the encoded image as a binary message, so tooling gets pixels without
+//! sharing a filesystem with the server.
+
+use super::*;
+
+/// `render_to_bytes` payload.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct RenderToBytesPayload {
+    scene: SceneDSL,
+    #[serde(default = "default_render_encoding")]
+    encoding: FrameStreamEncoding,
+    /// JPEG quality, 1-100.
+    #[serde(default)]
+    quality: Option<u8>,
+    /// Feature flag overrides, as in `scene_update`.
+    #[serde(default)]
+    features: Vec<String>,
+    /// Pin the frame clock and planner caps, as `--deterministic` does.
+    #[serde(default)]
+    deterministic: bool,
+}
+
+fn default_render_encoding() -> FrameStreamEncoding {
+    FrameStreamEncoding::Png
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RenderToBytesHeader {
+    #[serde(rename = "type")]
+    frame_type: &'static str,
+    request_id: Option<String>,
+    width: u32,
+    height: u32,
+    encoding: FrameStreamEncoding,
+    byte_length: usize,
+    render_ms: f64,
+    timestamp: u64,
+}
+
+impl RenderToBytesPayload {
+    pub(super) fn parse(payload: Option<Value>) -> Result<Self> {
+        let payload = payload.context("render_to_bytes missing payload")?;
+        let mut parsed: Self = serde_json::from_value(payload)?;
+        if let Some(quality) = parsed.quality
+            && !(1..=100).contains(&quality)
+        {
+            anyhow::bail!("quality must be in 1..=100, got {quality}");
+        }
+        prepare_request_scene(&mut parsed.scene, &parsed.features)?;
+        Ok(parsed)
+    }
+
+    pub(super) fn scene(&self) -> &SceneDSL {
+        &self.scene
+    }
+}
+
+/// Render the payload's scene and build the `render_to_bytes_result` binary
+/// message for `request_id`, reporting each build step and pass on the way.
+pub(super) fn render_to_bytes(
+    payload: &RenderToBytesPayload,
+    asset_store: &AssetStore,
+    request_id: Option<String>,
+    on_progress: &mut dyn FnMut(crate::renderer::RenderProgress),
+) -> Result<Vec<u8>> {
+    let started_at = Instant::now();
+    let image = crate::renderer::HeadlessSession::with_deterministic(payload.deterministic)?
+        .render_scene_to_rgba8_with_progress(&payload.scene, Some(asset_store), on_progress)?;
+    let render_ms = started_at.elapsed().as_secs_f64() * 1000.0;
+    let (width, height) = image.dimensions();
+    let bytes = encode_rgba_image(image, payload.encoding, payload.quality)?;
+    binary_message(
+        &RenderToBytesHeader {
+            frame_type: "render_to_bytes_result",
+            request_id,
+            width,
+            height,
+            encoding: payload.encoding,
+            byte_length: bytes.len(),
+            render_ms,
+            timestamp: now_millis(),
+        },
+        &bytes,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_defaults_to_png_and_rejects_bad_quality() {
+        let scene = serde_json::json!({
+            "version": "1.0",
+            "metadata": { "name": "t", "created": "", "modified": "" },
+            "nodes": [],
+            "connections": [],
+            "outputs": {}
+        });
+        let parsed: RenderToBytesPayload =
+            serde_json::from_value(serde_json::json!({ "scene": scene })).unwrap();
+        assert_eq!(parsed.encoding, FrameStreamEncoding::Png);
+        assert!(!parsed.deterministic);
+
+        let err = RenderToBytesPayload::parse(Some(
+            serde_json::json!({ "scene": scene, "encoding": "jpeg", "quality": 0 }),
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("quality"));
+        assert!(RenderToBytesPayload::parse(None).is_err());
+    }
+}
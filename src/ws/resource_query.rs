@@ -0,0 +1,344 @@
+//! `get_resources` / `preview_texture`: the debug sidebar's resource
+//! introspection (textures, passes, draw order, intermediate previews) for
+//! editors that do not run the native UI.
+
+use std::collections::BTreeMap;
+
+use super::*;
+
+const DEFAULT_PREVIEW_MAX_SIZE: u32 = 256;
+const MAX_PREVIEW_SIZE: u32 = 2048;
+
+/// `preview_texture` payload.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PreviewTextureRequest {
+    pub name: String,
+    /// Longest side of the thumbnail; smaller textures are not upscaled.
+    #[serde(default = "default_preview_max_size")]
+    pub max_size: u32,
+    #[serde(default = "default_preview_encoding")]
+    pub encoding: FrameStreamEncoding,
+    /// JPEG quality, 1-100.
+    #[serde(default)]
+    pub quality: Option<u8>,
+}
+
+fn default_preview_max_size() -> u32 {
+    DEFAULT_PREVIEW_MAX_SIZE
+}
+
+fn default_preview_encoding() -> FrameStreamEncoding {
+    FrameStreamEncoding::Png
+}
+
+impl PreviewTextureRequest {
+    pub(super) fn parse(payload: Option<Value>) -> Result<Self> {
+        let payload = payload.context("preview_texture missing payload")?;
+        let request: Self = serde_json::from_value(payload)?;
+        if !(1..=MAX_PREVIEW_SIZE).contains(&request.max_size) {
+            anyhow::bail!(
+                "maxSize must be in 1..={MAX_PREVIEW_SIZE}, got {}",
+                request.max_size
+            );
+        }
+        if let Some(quality) = request.quality
+            && !(1..=100).contains(&quality)
+        {
+            anyhow::bail!("quality must be in 1..=100, got {quality}");
+        }
+        Ok(request)
+    }
+
+    /// Thumbnail size for a `source` texture: aspect kept, longest side at
+    /// most `max_size`.
+    pub fn preview_size(&self, source: [u32; 2]) -> [u32; 2] {
+        let longest = source[0].max(source[1]);
+        if longest <= self.max_size {
+            return source;
+        }
+        let scale = self.max_size as f64 / longest as f64;
+        [
+            ((source[0] as f64 * scale).round() as u32).max(1),
+            ((source[1] as f64 * scale).round() as u32).max(1),
+        ]
+    }
+}
+
+/// A resource introspection request answered by the app.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceQuery {
+    Resources,
+    PreviewTexture(PreviewTextureRequest),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TextureEntry {
+    name: String,
+    size: Option<[u32; 2]>,
+    format: Option<String>,
+    /// Passes writing this texture, in draw order.
+    written_by: Vec<String>,
+    sampled_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PassEntry<'a> {
+    name: &'a str,
+    display_label: Option<&'a str>,
+    source_node_id: Option<&'a str>,
+    source_node_type: Option<&'a str>,
+    order_index: usize,
+    target_texture: Option<&'a str>,
+    target_size: Option<[u32; 2]>,
+    target_format: Option<&'a str>,
+    is_compute: bool,
+    sampled_textures: &'a [String],
+    instance_count: u32,
+    vertex_count: u32,
+    workgroup_count: u32,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferEntry<'a> {
+    name: &'a str,
+    size: u64,
+    usage: &'a str,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourcesPayload<'a> {
+    final_output_texture: Option<&'a str>,
+    textures: Vec<TextureEntry>,
+    /// Sorted by draw order.
+    passes: Vec<PassEntry<'a>>,
+    buffers: Vec<BufferEntry<'a>>,
+    samplers: Vec<&'a str>,
+}
+
+fn texture_entry<'m>(
+    textures: &'m mut BTreeMap<String, TextureEntry>,
+    name: &str,
+    texture_info: &impl Fn(&str) -> Option<([u32; 2], String)>,
+) -> &'m mut TextureEntry {
+    textures.entry(name.to_string()).or_insert_with(|| {
+        let (size, format) = texture_info(name).unzip();
+        TextureEntry {
+            name: name.to_string(),
+            size,
+            format,
+            written_by: Vec::new(),
+            sampled_by: Vec::new(),
+        }
+    })
+}
+
+fn resources_payload<'a>(
+    snapshot: &'a ResourceSnapshot,
+    texture_info: impl Fn(&str) -> Option<([u32; 2], String)>,
+) -> ResourcesPayload<'a> {
+    let mut passes: Vec<&crate::ui::resource_tree::PassInfo> = snapshot.passes.iter().collect();
+    passes.sort_by_key(|pass| pass.order_index);
+
+    let mut textures: BTreeMap<String, TextureEntry> = BTreeMap::new();
+    for pass in &passes {
+        if let Some(target) = pass.target_texture.as_deref() {
+            texture_entry(&mut textures, target, &texture_info)
+                .written_by
+                .push(pass.name.clone());
+        }
+        for sampled in &pass.sampled_textures {
+            texture_entry(&mut textures, sampled, &texture_info)
+                .sampled_by
+                .push(pass.name.clone());
+        }
+    }
+    if let Some(output) = snapshot.final_output_texture.as_deref() {
+        texture_entry(&mut textures, output, &texture_info);
+    }
+
+    ResourcesPayload {
+        final_output_texture: snapshot.final_output_texture.as_deref(),
+        textures: textures.into_values().collect(),
+        passes: passes
+            .into_iter()
+            .map(|pass| PassEntry {
+                name: &pass.name,
+                display_label: pass.display_label.as_deref(),
+                source_node_id: pass.source_node_id.as_deref(),
+                source_node_type: pass.source_node_type.as_deref(),
+                order_index: pass.order_index,
+                target_texture: pass.target_texture.as_deref(),
+                target_size: pass.target_size.map(|(w, h)| [w, h]),
+                target_format: pass.target_format.as_deref(),
+                is_compute: pass.is_compute,
+                sampled_textures: &pass.sampled_textures,
+                instance_count: pass.instance_count,
+                vertex_count: pass.vertex_count,
+                workgroup_count: pass.workgroup_count,
+            })
+            .collect(),
+        buffers: snapshot
+            .buffers
+            .iter()
+            .map(|buffer| BufferEntry {
+                name: &buffer.name,
+                size: buffer.size,
+                usage: &buffer.usage_label,
+            })
+            .collect(),
+        samplers: snapshot
+            .samplers
+            .iter()
+            .map(|sampler| sampler.name.as_str())
+            .collect(),
+    }
+}
+
+/// Answers `get_resources` with the sidebar's resource snapshot. Texture sizes
+/// and formats come from `texture_info`, which also covers textures that are
+/// only sampled (assets, history buffers).
+pub fn broadcast_resources(
+    hub: &WsHub,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    snapshot: &ResourceSnapshot,
+    texture_info: impl Fn(&str) -> Option<([u32; 2], String)>,
+) {
+    let message = WSMessage {
+        msg_type: "resources".to_string(),
+        timestamp: now_millis(),
+        request_id,
+        payload: Some(resources_payload(snapshot, texture_info)),
+    };
+    if let Ok(text) = serde_json::to_string(&message) {
+        hub.reply(client_id, text);
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PreviewTextureHeader<'a> {
+    #[serde(rename = "type")]
+    frame_type: &'static str,
+    request_id: Option<String>,
+    name: &'a str,
+    width: u32,
+    height: u32,
+    source_width: u32,
+    source_height: u32,
+    encoding: FrameStreamEncoding,
+    timestamp: u64,
+}
+
+/// Answers `preview_texture` with a binary `preview_texture_result` message.
+/// `image` is the display-encoded texture at `source_size`; it is downscaled
+/// here to the requested thumbnail size.
+pub fn broadcast_texture_preview(
+    hub: &WsHub,
+    request_id: Option<String>,
+    client_id: Option<u64>,
+    request: &PreviewTextureRequest,
+    image: image::RgbaImage,
+) -> Result<()> {
+    let source_size = [image.width(), image.height()];
+    let [width, height] = request.preview_size(source_size);
+    let image = if [width, height] == source_size {
+        image
+    } else {
+        image::imageops::resize(&image, width, height, image::imageops::FilterType::Triangle)
+    };
+    let bytes = encode_rgba_image(image, request.encoding, request.quality)?;
+    let frame = binary_message(
+        &PreviewTextureHeader {
+            frame_type: "preview_texture_result",
+            request_id,
+            name: &request.name,
+            width,
+            height,
+            source_width: source_size[0],
+            source_height: source_size[1],
+            encoding: request.encoding,
+            timestamp: now_millis(),
+        },
+        &bytes,
+    )?;
+    hub.reply_binary(client_id, frame);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::resource_tree::PassInfo;
+
+    fn pass(name: &str, order_index: usize, target: &str, sampled: &[&str]) -> PassInfo {
+        PassInfo {
+            name: name.to_string(),
+            display_label: None,
+            source_node_id: None,
+            source_node_type: None,
+            order_index,
+            target_texture: Some(target.to_string()),
+            target_size: Some((64, 32)),
+            target_format: Some("Rgba16Float".to_string()),
+            is_compute: false,
+            sampled_textures: sampled.iter().map(|name| name.to_string()).collect(),
+            instance_count: 1,
+            vertex_count: 6,
+            workgroup_count: 0,
+        }
+    }
+
+    #[test]
+    fn resources_payload_orders_passes_and_indexes_textures() {
+        let snapshot = ResourceSnapshot {
+            passes: vec![
+                pass("blur_y", 1, "out", &["blur_x.tex"]),
+                pass("blur_x", 0, "blur_x.tex", &["asset.photo"]),
+            ],
+            final_output_texture: Some("out".to_string()),
+            ..Default::default()
+        };
+        let payload = serde_json::to_value(resources_payload(&snapshot, |name| {
+            (name == "asset.photo").then(|| ([128, 128], "Rgba8Unorm".to_string()))
+        }))
+        .unwrap();
+
+        assert_eq!(payload["passes"][0]["name"], "blur_x");
+        assert_eq!(payload["passes"][1]["orderIndex"], 1);
+        assert_eq!(
+            payload["passes"][1]["targetSize"],
+            serde_json::json!([64, 32])
+        );
+        let textures = payload["textures"].as_array().unwrap();
+        let names: Vec<&str> = textures
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["asset.photo", "blur_x.tex", "out"]);
+        assert_eq!(textures[0]["size"], serde_json::json!([128, 128]));
+        assert_eq!(textures[1]["writtenBy"], serde_json::json!(["blur_x"]));
+        assert_eq!(textures[1]["sampledBy"], serde_json::json!(["blur_y"]));
+    }
+
+    #[test]
+    fn preview_request_defaults_and_fits_longest_side() {
+        let request =
+            PreviewTextureRequest::parse(Some(serde_json::json!({ "name": "out" }))).unwrap();
+        assert_eq!(request.max_size, DEFAULT_PREVIEW_MAX_SIZE);
+        assert_eq!(request.encoding, FrameStreamEncoding::Png);
+        assert_eq!(request.preview_size([1024, 512]), [256, 128]);
+        assert_eq!(request.preview_size([100, 40]), [100, 40]);
+
+        assert!(
+            PreviewTextureRequest::parse(Some(serde_json::json!({ "name": "out", "maxSize": 0 })))
+                .is_err()
+        );
+        assert!(PreviewTextureRequest::parse(None).is_err());
+    }
+}
@@ -1,5 +1,6 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
+use anyhow::{Result, anyhow};
 use serde_json::Value;
 
 use crate::dsl::{Connection, GroupDSL, Metadata, Node, SceneDSL};
@@ -62,6 +63,7 @@ pub struct SceneCache {
     pub assets: HashMap<String, crate::dsl::AssetEntry>,
     pub state_machine: Option<crate::state_machine::types::StateMachine>,
     pub debug_artifacts: Option<crate::dsl::DebugArtifacts>,
+    pub feature_flags: BTreeMap<String, bool>,
 }
 
 impl SceneCache {
@@ -76,6 +78,7 @@ impl SceneCache {
             assets: scene.assets.clone(),
             state_machine: scene.state_machine.clone(),
             debug_artifacts: scene.debug_artifacts.clone(),
+            feature_flags: scene.feature_flags.clone(),
         };
         apply_scene_update(&mut cache, scene);
         cache
@@ -89,6 +92,7 @@ pub fn apply_scene_update(cache: &mut SceneCache, scene: &SceneDSL) {
     cache.assets = scene.assets.clone();
     cache.state_machine = scene.state_machine.clone();
     cache.debug_artifacts = scene.debug_artifacts.clone();
+    cache.feature_flags = scene.feature_flags.clone();
 
     cache.nodes_by_id.clear();
     for node in &scene.nodes {
@@ -309,6 +313,108 @@ pub(crate) fn delta_updates_only_uniform_values(cache: &SceneCache, delta: &Scen
     true
 }
 
+/// One `{nodeId, param, value}` entry of a `param_update` message.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ParamUpdate {
+    pub node_id: String,
+    pub param: String,
+    pub value: Value,
+}
+
+/// `param_update` payload: a single update or an `updates` batch applied as
+/// one uniform delta.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(untagged)]
+pub enum ParamUpdatePayload {
+    Batch { updates: Vec<ParamUpdate> },
+    Single(ParamUpdate),
+}
+
+impl ParamUpdatePayload {
+    pub fn into_updates(self) -> Vec<ParamUpdate> {
+        match self {
+            Self::Batch { updates } => updates,
+            Self::Single(update) => vec![update],
+        }
+    }
+}
+
+/// Express param updates as a delta that only touches node params, grouped per
+/// node and carrying just the changed keys. Updates that match the cached
+/// value are dropped, so an unchanged batch yields `None`.
+pub(crate) fn param_updates_to_delta(
+    cache: &SceneCache,
+    updates: &[ParamUpdate],
+) -> Result<Option<SceneDelta>> {
+    let mut updated: Vec<Node> = Vec::new();
+    for update in updates {
+        let cached = cache
+            .nodes_by_id
+            .get(&update.node_id)
+            .ok_or_else(|| anyhow!("param_update references missing node '{}'", update.node_id))?;
+        if cached.params.get(&update.param) == Some(&update.value) {
+            continue;
+        }
+        let index = match updated.iter().position(|node| node.id == update.node_id) {
+            Some(index) => index,
+            None => {
+                updated.push(Node {
+                    params: HashMap::new(),
+                    ..cached.clone()
+                });
+                updated.len() - 1
+            }
+        };
+        updated[index]
+            .params
+            .insert(update.param.clone(), update.value.clone());
+    }
+    if updated.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(SceneDelta {
+        version: cache.version.clone(),
+        nodes: SceneDeltaNodes {
+            added: Vec::new(),
+            updated,
+            removed: Vec::new(),
+        },
+        connections: SceneDeltaConnections {
+            added: Vec::new(),
+            updated: Vec::new(),
+            removed: Vec::new(),
+        },
+        outputs: None,
+        groups: None,
+        state_machine: None,
+        debug_artifacts: None,
+        assets_added: None,
+        assets_removed: None,
+    }))
+}
+
+/// Apply a uniform delta's params to a full scene still queued ahead of it,
+/// so the delta is not lost when the queued scene replaces it.
+pub(crate) fn patch_scene_node_params(scene: &mut SceneDSL, updated_nodes: &[Node]) {
+    for updated in updated_nodes {
+        if let Some(node) = scene.nodes.iter_mut().find(|node| node.id == updated.id) {
+            node.params.extend(updated.params.clone());
+        }
+    }
+}
+
+/// Fold a uniform delta into one still queued ahead of it; params from
+/// `later` win.
+pub(crate) fn merge_uniform_delta_nodes(earlier: &mut Vec<Node>, later: Vec<Node>) {
+    for node in later {
+        match earlier.iter_mut().find(|existing| existing.id == node.id) {
+            Some(existing) => existing.params.extend(node.params),
+            None => earlier.push(node),
+        }
+    }
+}
+
 fn is_geometry_allocation_sink(node_type: &str, port_id: &str) -> bool {
     matches!(
         (node_type, port_id),
@@ -388,6 +494,7 @@ pub fn materialize_scene_dsl(cache: &SceneCache) -> SceneDSL {
         assets: cache.assets.clone(),
         state_machine: cache.state_machine.clone(),
         debug_artifacts: cache.debug_artifacts.clone(),
+        feature_flags: cache.feature_flags.clone(),
     }
 }
 
@@ -441,9 +548,71 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         }
     }
 
+    #[test]
+    fn param_updates_become_uniform_only_deltas() {
+        let cache = SceneCache::from_scene_update(&base_scene());
+        let payload: ParamUpdatePayload = serde_json::from_value(json!({
+            "updates": [
+                {"nodeId": "FloatInput_1", "param": "value", "value": 0.9},
+                {"nodeId": "FloatInput_1", "param": "min", "value": 0.0}
+            ]
+        }))
+        .unwrap();
+        let delta = param_updates_to_delta(&cache, &payload.into_updates())
+            .unwrap()
+            .expect("value changed");
+        assert_eq!(delta.nodes.updated.len(), 1);
+        assert_eq!(
+            delta.nodes.updated[0].params,
+            HashMap::from([("value".to_string(), json!(0.9))])
+        );
+        assert!(delta_updates_only_uniform_values(&cache, &delta));
+
+        let single: ParamUpdatePayload = serde_json::from_value(
+            json!({"nodeId": "FloatInput_1", "param": "min", "value": -1.0}),
+        )
+        .unwrap();
+        let delta = param_updates_to_delta(&cache, &single.into_updates())
+            .unwrap()
+            .unwrap();
+        assert!(!delta_updates_only_uniform_values(&cache, &delta));
+
+        let missing = ParamUpdate {
+            node_id: "Nope".to_string(),
+            param: "value".to_string(),
+            value: json!(1.0),
+        };
+        assert!(param_updates_to_delta(&cache, &[missing]).is_err());
+    }
+
+    #[test]
+    fn merged_uniform_deltas_keep_latest_params() {
+        let mut earlier = vec![node("a", "FloatInput", json!({"value": 1.0}))];
+        merge_uniform_delta_nodes(
+            &mut earlier,
+            vec![
+                node("a", "FloatInput", json!({"value": 2.0})),
+                node("b", "ColorInput", json!({"value": [1.0, 0.0, 0.0, 1.0]})),
+            ],
+        );
+        assert_eq!(earlier.len(), 2);
+        assert_eq!(earlier[0].params["value"], json!(2.0));
+
+        let mut scene = base_scene();
+        patch_scene_node_params(&mut scene, &earlier);
+        assert_eq!(scene.nodes[0].params["value"], json!(0.5));
+        patch_scene_node_params(
+            &mut scene,
+            &[node("FloatInput_1", "FloatInput", json!({"value": 0.25}))],
+        );
+        assert_eq!(scene.nodes[0].params["value"], json!(0.25));
+        assert_eq!(scene.nodes[0].params["min"], json!(0.0));
+    }
+
     #[test]
     fn scene_cache_materialize_preserves_debug_artifacts() {
         let mut scene = base_scene();
@@ -815,6 +984,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let cache = SceneCache::from_scene_update(&scene);
         let delta = SceneDelta {
@@ -935,6 +1105,7 @@ mod tests {
             assets: Default::default(),
             state_machine: None,
             debug_artifacts: None,
+            feature_flags: Default::default(),
         };
         let cache = SceneCache::from_scene_update(&scene);
         let delta = SceneDelta {
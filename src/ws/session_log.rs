@@ -0,0 +1,259 @@
+//! `--record-ws` / `--replay-ws`: capture every inbound WS message and feed a
+//! capture back through the server, to reproduce editor-triggered failures
+//! without the editor attached.
+//!
+//! A recording is NDJSON, one inbound message per line:
+//! `{"atMs":1234,"clientId":0,"text":"{...}"}`, or `"binary":"<base64>"` for
+//! binary messages. `auth` messages are not recorded. Replay connects to the
+//! local server like an editor would, one connection per recorded client,
+//! and resends each message at its recorded offset, so the messages take
+//! exactly the path they took originally (dispatch, scene cache, scene-update
+//! channel).
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    net::{SocketAddr, TcpStream},
+    path::{Path, PathBuf},
+};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+use super::*;
+
+/// How long replay waits for the server to accept its first connection.
+const REPLAY_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedMessage {
+    /// Milliseconds since recording started.
+    at_ms: u64,
+    client_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    binary: Option<String>,
+}
+
+impl RecordedMessage {
+    fn message(&self) -> Result<Message> {
+        match (&self.text, &self.binary) {
+            (Some(text), None) => Ok(Message::Text(text.clone())),
+            (None, Some(binary)) => Ok(Message::Binary(
+                BASE64
+                    .decode(binary)
+                    .context("invalid base64 in recorded binary message")?,
+            )),
+            _ => anyhow::bail!("recorded message needs exactly one of text or binary"),
+        }
+    }
+}
+
+/// Appends inbound messages to a recording file. Each line is flushed as it
+/// is written, so the recording survives a crash it is meant to reproduce.
+#[derive(Clone)]
+pub struct WsRecorder {
+    started_at: Instant,
+    out: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl std::fmt::Debug for WsRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WsRecorder").finish_non_exhaustive()
+    }
+}
+
+impl WsRecorder {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create ws recording {}", path.display()))?;
+        Ok(Self {
+            started_at: Instant::now(),
+            out: Arc::new(Mutex::new(BufWriter::new(file))),
+        })
+    }
+
+    pub(super) fn record(&self, client_id: u64, message: &Message) {
+        let (text, binary) = match message {
+            Message::Text(text) => (Some(text.clone()), None),
+            Message::Binary(bytes) => (None, Some(BASE64.encode(bytes))),
+            _ => return,
+        };
+        let entry = RecordedMessage {
+            at_ms: self.started_at.elapsed().as_millis() as u64,
+            client_id,
+            text,
+            binary,
+        };
+        let Ok(line) = serde_json::to_string(&entry) else {
+            return;
+        };
+        if let Ok(mut out) = self.out.lock() {
+            let _ = writeln!(out, "{line}");
+            let _ = out.flush();
+        }
+    }
+}
+
+fn read_recording(path: &Path) -> Result<Vec<RecordedMessage>> {
+    let file = File::open(path)
+        .with_context(|| format!("failed to open ws recording {}", path.display()))?;
+    let mut messages = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: RecordedMessage = serde_json::from_str(&line).with_context(|| {
+            format!("{}:{}: invalid recorded message", path.display(), index + 1)
+        })?;
+        message
+            .message()
+            .with_context(|| format!("{}:{}", path.display(), index + 1))?;
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+fn replay_url(addr: SocketAddr, token: Option<&str>) -> String {
+    let host = if addr.ip().is_unspecified() {
+        format!("127.0.0.1:{}", addr.port())
+    } else {
+        addr.to_string()
+    };
+    match token {
+        Some(token) => format!("ws://{host}/?token={token}"),
+        None => format!("ws://{host}/"),
+    }
+}
+
+type ReplayConnection = tungstenite::WebSocket<TcpStream>;
+
+fn connect_replay_client(url: &str, addr: SocketAddr) -> Result<ReplayConnection> {
+    let deadline = Instant::now() + REPLAY_CONNECT_TIMEOUT;
+    let stream = loop {
+        match TcpStream::connect(addr) {
+            Ok(stream) => break stream,
+            Err(_) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(e).context("replay could not reach the ws server"),
+        }
+    };
+    let (ws, _) = tungstenite::client(url, stream)
+        .map_err(|e| anyhow::anyhow!("replay websocket handshake failed: {e}"))?;
+    // Replies are drained between sends; a short timeout keeps that cheap.
+    ws.get_ref()
+        .set_read_timeout(Some(Duration::from_millis(1)))
+        .context("failed to set replay read timeout")?;
+    Ok(ws)
+}
+
+/// Reads and discards whatever the server sent, answering pings.
+fn drain_replies(connections: &mut HashMap<u64, ReplayConnection>) {
+    for ws in connections.values_mut() {
+        while ws.read().is_ok() {}
+    }
+}
+
+/// Replays `path` against the server listening on `addr` on a background
+/// thread. Connections stay open after the last message so the renderer
+/// keeps the replayed state.
+pub(super) fn spawn_ws_replay(
+    path: PathBuf,
+    addr: SocketAddr,
+    token: Option<String>,
+    hub: WsHub,
+) -> Result<()> {
+    let recording = read_recording(&path)?;
+    let url = replay_url(addr, token.as_deref());
+    thread::spawn(move || {
+        let started_at = Instant::now();
+        let mut connections: HashMap<u64, ReplayConnection> = HashMap::new();
+        eprintln!(
+            "[ws-replay] replaying {} messages from {}",
+            recording.len(),
+            path.display()
+        );
+        for entry in &recording {
+            let due = started_at + Duration::from_millis(entry.at_ms);
+            while Instant::now() < due {
+                drain_replies(&mut connections);
+                thread::sleep(
+                    due.saturating_duration_since(Instant::now())
+                        .min(Duration::from_millis(10)),
+                );
+            }
+            if !connections.contains_key(&entry.client_id) {
+                match connect_replay_client(&url, addr) {
+                    Ok(ws) => {
+                        connections.insert(entry.client_id, ws);
+                    }
+                    Err(e) => {
+                        report_internal_error(&hub, "WS_REPLAY_FAILED", &format!("{e:#}"));
+                        return;
+                    }
+                }
+            }
+            let (Some(ws), Ok(message)) = (connections.get_mut(&entry.client_id), entry.message())
+            else {
+                continue;
+            };
+            if let Err(e) = ws.send(message) {
+                report_internal_error(
+                    &hub,
+                    "WS_REPLAY_FAILED",
+                    &format!("replay send failed at {} ms: {e}", entry.at_ms),
+                );
+                return;
+            }
+        }
+        eprintln!("[ws-replay] finished");
+        loop {
+            drain_replies(&mut connections);
+            thread::sleep(Duration::from_millis(50));
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_round_trips_text_and_binary() {
+        let path = std::env::temp_dir().join(format!(
+            "nf-ws-recording-{}-{}.ndjson",
+            std::process::id(),
+            now_millis()
+        ));
+        let recorder = WsRecorder::create(&path).unwrap();
+        recorder.record(3, &Message::Text(r#"{"type":"scene_request"}"#.to_string()));
+        recorder.record(3, &Message::Ping(vec![1]));
+        recorder.record(4, &Message::Binary(vec![0, 1, 2, 255]));
+
+        let recording = read_recording(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(recording.len(), 2);
+        assert_eq!(recording[0].client_id, 3);
+        assert_eq!(
+            recording[0].message().unwrap(),
+            Message::Text(r#"{"type":"scene_request"}"#.to_string())
+        );
+        assert_eq!(
+            recording[1].message().unwrap(),
+            Message::Binary(vec![0, 1, 2, 255])
+        );
+        assert!(recording[1].at_ms >= recording[0].at_ms);
+    }
+
+    #[test]
+    fn replay_url_targets_loopback_for_wildcard_binds() {
+        let addr: SocketAddr = "0.0.0.0:8080".parse().unwrap();
+        assert_eq!(replay_url(addr, None), "ws://127.0.0.1:8080/");
+        assert_eq!(
+            replay_url(addr, Some("s3cret")),
+            "ws://127.0.0.1:8080/?token=s3cret"
+        );
+    }
+}
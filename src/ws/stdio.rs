@@ -0,0 +1,209 @@
+//! `--stdio`: serve a single client over stdin/stdout instead of a TCP port,
+//! so editors and build tools can embed the server as a subprocess.
+//!
+//! Both directions are NDJSON using the WS message schema: every stdin line is
+//! one request, every stdout line one message (replies, broadcasts, frames).
+//! Binary messages travel as `{"binary":"<base64>"}` holding exactly the bytes
+//! of the WS binary message, so the usual header parsing applies after
+//! decoding. Logs stay on stderr. Closing stdin shuts the server down.
+
+use std::io::{BufRead, Write};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BinaryLine {
+    binary: String,
+}
+
+/// The stdout line for `message`; control frames have no line.
+fn encode_line(message: &Message) -> Option<String> {
+    match message {
+        // Outbound text is always compact JSON, so it never spans lines.
+        Message::Text(text) => Some(text.clone()),
+        Message::Binary(bytes) => serde_json::to_string(&BinaryLine {
+            binary: BASE64.encode(bytes),
+        })
+        .ok(),
+        _ => None,
+    }
+}
+
+fn decode_line(line: &str) -> Result<Message> {
+    match serde_json::from_str::<BinaryLine>(line) {
+        Ok(BinaryLine { binary }) => Ok(Message::Binary(
+            BASE64
+                .decode(binary)
+                .context("invalid base64 in binary line")?,
+        )),
+        Err(_) => Ok(Message::Text(line.to_string())),
+    }
+}
+
+/// Writes one message per line. Locking stdout per line keeps lines from the
+/// reader and writer threads from interleaving.
+struct StdoutSink;
+
+impl ClientSink for StdoutSink {
+    fn send(&mut self, message: Message) -> tungstenite::Result<()> {
+        let Some(line) = encode_line(&message) else {
+            return Ok(());
+        };
+        let mut out = std::io::stdout().lock();
+        writeln!(out, "{line}")?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Registers the stdio client with `hub` and serves it on background
+/// threads. The returned handle belongs to the stdin reader, which exits the
+/// process once stdin closes and the writer has flushed everything queued.
+pub(super) fn spawn_stdio_server(
+    scene_tx: Sender<SceneUpdate>,
+    scene_drop_rx: Receiver<SceneUpdate>,
+    hub: WsHub,
+    last_good: Arc<Mutex<Option<SceneDSL>>>,
+    asset_store: AssetStore,
+    options: WsServerOptions,
+    ui_wake: Option<UiWakeCallback>,
+) -> thread::JoinHandle<()> {
+    let (client_tx, client_rx) = crossbeam_channel::unbounded::<Message>();
+    let client_id = hub.register_client(client_tx);
+    let frame_mailbox = FrameMailbox::default();
+
+    let writer_mailbox = frame_mailbox.clone();
+    // Runs until the hub drops the client's sender; crossbeam hands out every
+    // queued message before reporting the disconnect.
+    let writer = thread::spawn(move || {
+        let mut sink = StdoutSink;
+        loop {
+            let disconnected = match client_rx.recv_timeout(Duration::from_millis(5)) {
+                Ok(message) => {
+                    let _ = sink.send(message);
+                    false
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => false,
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => true,
+            };
+            let frame = writer_mailbox.lock().ok().and_then(|mut slot| slot.take());
+            if let Some(frame) = frame {
+                let _ = sink.send(Message::Binary(frame));
+            }
+            if disconnected {
+                return;
+            }
+        }
+    });
+
+    // A pipe has no keepalive; report that instead of the TCP settings.
+    let notice_options = WsServerOptions {
+        client_timeout: None,
+        ..WsServerOptions::default()
+    };
+    broadcast_client_connected(&hub, client_id, &notice_options, WsCompression::None);
+
+    thread::spawn(move || {
+        let mut sink = StdoutSink;
+        let scene_cache = Arc::new(Mutex::new(None::<SceneCache>));
+        let mut transfer_state = AssetTransferState::default();
+        let mut debug_artifact_transfer_state = DebugArtifactTransferState::default();
+        let client = ClientContext {
+            id: client_id,
+            compression: WsCompression::None,
+            hub: &hub,
+            mailbox: &frame_mailbox,
+        };
+
+        for line in std::io::stdin().lock().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    eprintln!("[stdio] failed to read stdin: {e}");
+                    break;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message = match decode_line(&line) {
+                Ok(message) => message,
+                Err(e) => {
+                    send_error(&mut sink, None, "PARSE_ERROR", &format!("{e:#}"));
+                    continue;
+                }
+            };
+            if let Some(recorder) = &options.record {
+                recorder.record(client_id, &message);
+            }
+            match message {
+                Message::Text(text) => {
+                    if let Err(e) = handle_text_message(
+                        &mut sink,
+                        &text,
+                        &scene_tx,
+                        &scene_drop_rx,
+                        &last_good,
+                        &scene_cache,
+                        &asset_store,
+                        &mut transfer_state,
+                        &mut debug_artifact_transfer_state,
+                        &client,
+                        ui_wake.as_ref(),
+                    ) {
+                        report_internal_error(&hub, "WS_HANDLE_MESSAGE_FAILED", &format!("{e:#}"));
+                    }
+                }
+                Message::Binary(data) => handle_binary_message(
+                    &mut sink,
+                    &data,
+                    &scene_tx,
+                    &scene_drop_rx,
+                    &scene_cache,
+                    &asset_store,
+                    &mut transfer_state,
+                    &mut debug_artifact_transfer_state,
+                    ui_wake.as_ref(),
+                ),
+                _ => {}
+            }
+        }
+
+        // Unregistering drops the client's sender, so the writer stops once
+        // it has written everything already queued.
+        end_client_session(&hub, client_id, DisconnectReason::Closed);
+        eprintln!("[stdio] stdin closed, exiting");
+        if writer.join().is_err() {
+            eprintln!("[stdio] writer thread panicked");
+        }
+        std::process::exit(0);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_round_trip_text_and_binary() {
+        let text = r#"{"type":"scene_request","timestamp":0}"#;
+        assert_eq!(
+            encode_line(&Message::Text(text.to_string())).as_deref(),
+            Some(text)
+        );
+        assert_eq!(decode_line(text).unwrap(), Message::Text(text.to_string()));
+
+        let line = encode_line(&Message::Binary(vec![0, 1, 2, 255])).unwrap();
+        assert_eq!(line, r#"{"binary":"AAEC/w=="}"#);
+        assert_eq!(
+            decode_line(&line).unwrap(),
+            Message::Binary(vec![0, 1, 2, 255])
+        );
+
+        assert!(decode_line(r#"{"binary":"not base64!"}"#).is_err());
+        assert_eq!(encode_line(&Message::Ping(vec![1])), None);
+    }
+}
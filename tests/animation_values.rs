@@ -390,6 +390,7 @@ fn sticky_override_test_scene() -> dsl::SceneDSL {
             viewport: None,
         }),
         debug_artifacts: None,
+        feature_flags: Default::default(),
     }
 }
 
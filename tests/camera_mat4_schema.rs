@@ -37,6 +37,7 @@ fn scene(nodes: Vec<Node>, connections: Vec<Connection>) -> SceneDSL {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     }
 }
 
@@ -61,7 +62,7 @@ fn scheme_exposes_mat4_port_type_contract() {
 fn scheme_exposes_camera_nodes_and_camera_inputs() {
     let scheme = load_default_scheme().expect("load default scheme");
 
-    for node_type in ["PerspectiveCamera", "OrthographicCamera"] {
+    for node_type in ["PerspectiveCamera", "OrthographicCamera", "Camera"] {
         let node_scheme = scheme
             .nodes
             .get(node_type)
@@ -133,6 +134,19 @@ fn validation_rejects_invalid_camera_node_params() {
     assert!(msg.contains("cam.near"));
 }
 
+#[test]
+fn validation_rejects_invalid_unified_camera_params() {
+    let scheme = load_default_scheme().expect("load default scheme");
+    let scene = scene(
+        vec![node("cam", "Camera", json!({"projection": "fisheye"}))],
+        vec![],
+    );
+
+    let err = validate_scene_against(&scene, &scheme).expect_err("unknown projection should fail");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("cam.projection"));
+}
+
 #[test]
 fn validation_rejects_non_mat4_connection_to_camera_input() {
     let scheme = load_default_scheme().expect("load default scheme");
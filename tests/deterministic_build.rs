@@ -0,0 +1,85 @@
+use node_forge_render_server::{dsl, renderer};
+use rust_wgpu_fiber::HeadlessRenderer;
+use rust_wgpu_fiber::eframe::wgpu;
+use serde_json::json;
+
+mod support;
+
+/// `graph-rectangle` with an animated FilmGrain between its output pass and
+/// the Screen.
+fn animated_grain_scene() -> dsl::SceneDSL {
+    let mut scene = support::load_render_case_scene("graph-rectangle");
+    let screen_id = scene
+        .nodes
+        .iter()
+        .find(|node| node.node_type == "Screen")
+        .map(|node| node.id.clone())
+        .expect("graph-rectangle has a Screen");
+    let screen_input = scene
+        .connections
+        .iter_mut()
+        .find(|c| c.to.node_id == screen_id && c.to.port_id == "pass")
+        .expect("Screen.pass is connected");
+    let source_id = screen_input.from.node_id.clone();
+    let source_port = screen_input.from.port_id.clone();
+    screen_input.from.node_id = "grain".to_string();
+    screen_input.from.port_id = "pass".to_string();
+
+    scene.nodes.push(
+        serde_json::from_value(json!({
+            "id": "grain",
+            "type": "FilmGrain",
+            "params": { "animated": true, "seed": 7 }
+        }))
+        .expect("FilmGrain node should deserialize"),
+    );
+    scene.connections.push(
+        serde_json::from_value(json!({
+            "id": "e_grain_source",
+            "from": { "nodeId": source_id, "portId": source_port },
+            "to": { "nodeId": "grain", "portId": "pass" }
+        }))
+        .expect("grain connection should deserialize"),
+    );
+    scene
+}
+
+#[test]
+fn deterministic_build_pins_frame_dependent_params() {
+    let headless = match HeadlessRenderer::new(renderer::deterministic_renderer_config()) {
+        Ok(renderer) => renderer,
+        Err(err) => {
+            eprintln!("No adapter available for deterministic build test: {err:?}");
+            return;
+        }
+    };
+    if headless.adapter.get_info().backend == wgpu::Backend::Noop {
+        eprintln!("Native GPU unavailable; skipping deterministic build test");
+        return;
+    }
+    assert_eq!(headless.device.limits(), wgpu::Limits::default());
+    assert_eq!(headless.device.features(), wgpu::Features::empty());
+
+    let build = renderer::ShaderSpaceBuilder::new(headless.device.clone(), headless.queue.clone())
+        .with_adapter(headless.adapter.clone())
+        .with_options(renderer::ShaderSpaceBuildOptions {
+            deterministic: true,
+            ..Default::default()
+        })
+        .build(&animated_grain_scene())
+        .expect("deterministic build");
+
+    let grain_modules: Vec<&str> = build
+        .pass_debug_sources
+        .values()
+        .map(|source| source.module_source.as_str())
+        .filter(|module| module.contains("params.frame *"))
+        .collect();
+    assert!(!grain_modules.is_empty(), "film grain pass should be built");
+    for module in grain_modules {
+        assert!(
+            module.contains("let seed = 7u + params.frame * 0u;"),
+            "film grain must not reseed from the frame clock:\n{module}"
+        );
+    }
+}
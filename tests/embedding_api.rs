@@ -0,0 +1,65 @@
+use node_forge_render_server::api::{ParamUpdate, RenderServer};
+use rust_wgpu_fiber::eframe::wgpu;
+use rust_wgpu_fiber::{HeadlessRenderer, HeadlessRendererConfig};
+
+mod support;
+
+fn headless_renderer() -> Option<HeadlessRenderer> {
+    HeadlessRenderer::new(HeadlessRendererConfig::default())
+        .ok()
+        .filter(|renderer| renderer.adapter.get_info().backend != wgpu::Backend::Noop)
+}
+
+#[test]
+fn render_server_renders_and_reapplies_params_on_a_caller_device() {
+    let Some(headless) = headless_renderer() else {
+        eprintln!("No adapter available; skipping embedding api test.");
+        return;
+    };
+    let scene = support::load_render_case_scene("graph-rectangle");
+    let mut server = RenderServer::new(headless.device.clone(), headless.queue.clone())
+        .with_adapter(headless.adapter.clone());
+
+    assert!(
+        server
+            .update_params(&[])
+            .unwrap_err()
+            .to_string()
+            .contains("call render_scene first")
+    );
+
+    let image = server.render_scene(&scene).expect("render_scene");
+    assert!(image.width() > 0 && image.height() > 0);
+
+    let (node_id, param, value) = scene
+        .nodes
+        .iter()
+        .find_map(|node| {
+            node.params
+                .iter()
+                .next()
+                .map(|(param, value)| (node.id.clone(), param.clone(), value.clone()))
+        })
+        .expect("scene has a node with params");
+    let rerendered = server
+        .update_params(&[ParamUpdate {
+            node_id,
+            param,
+            value,
+        }])
+        .expect("update_params");
+    assert_eq!(rerendered.dimensions(), image.dimensions());
+    assert_eq!(rerendered.as_raw(), image.as_raw());
+
+    let err = server
+        .update_params(&[ParamUpdate {
+            node_id: "missing".to_string(),
+            param: "value".to_string(),
+            value: serde_json::json!(1.0),
+        }])
+        .unwrap_err();
+    assert!(err.to_string().contains("missing node 'missing'"));
+
+    let bundles = server.build_wgsl(&scene).expect("build_wgsl");
+    assert!(!bundles.is_empty());
+}
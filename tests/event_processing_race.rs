@@ -114,6 +114,7 @@ fn scene_with_event_transition(event_name: &str) -> SceneDSL {
         assets: HashMap::new(),
         state_machine: Some(sm),
         debug_artifacts: None,
+        feature_flags: Default::default(),
     }
 }
 
@@ -231,6 +232,7 @@ fn scene_with_press_release_transitions() -> SceneDSL {
         assets: HashMap::new(),
         state_machine: Some(sm),
         debug_artifacts: None,
+        feature_flags: Default::default(),
     }
 }
 
@@ -28,6 +28,7 @@ fn file_render_target_applies_scheme_defaults() {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     };
 
     normalize_scene_defaults(&mut scene).unwrap();
@@ -15,6 +15,10 @@ struct Params {
     color: vec4f,
     camera: mat4x4f,
     camera_position: vec4f,
+
+    // Frame clock shared by all passes (Time node).
+    delta_time: f32,
+    frame: u32,
 };
 
 
@@ -15,6 +15,10 @@ struct Params {
     color: vec4f,
     camera: mat4x4f,
     camera_position: vec4f,
+
+    // Frame clock shared by all passes (Time node).
+    delta_time: f32,
+    frame: u32,
 };
 
 @group(0) @binding(0)
@@ -808,7 +812,7 @@ fn fs_main(in: VSOut) -> @location(0) vec4f {
         let f = smoothstep(0.0, 0.015, math_closure_out_589e41e9);
         let l = math_closure_out_958b420d;
         let selection = show_thumb_out;
-        let lumin_edge = clamp(dot((pass_texture).rgb, vec3f(0.2126, 0.7152, 0.0722)), 0.0, 1.0);
+        let lumin_edge = dot((pass_texture).rgb, vec3f(0.2126, 0.7152, 0.0722));
         var output: vec4f;
         output = mc_math_closure_3faa7d04_(in.uv, c_edge, e, f, l, selection, lumin_edge);
         math_closure_out = output;
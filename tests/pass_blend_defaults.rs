@@ -77,6 +77,7 @@ fn normalization_merges_premul_blend_defaults_for_pass_nodes() {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     };
 
     normalize_scene_defaults(&mut scene).expect("normalize scene defaults");
@@ -180,6 +181,7 @@ fn normalization_merges_input_defaults_before_default_params() {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     };
 
     normalize_scene_defaults(&mut scene).expect("normalize scene defaults");
@@ -211,6 +213,7 @@ fn resolve_input_uses_port_default_when_param_missing() {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     };
     let nodes_by_id: HashMap<String, Node> = scene
         .nodes
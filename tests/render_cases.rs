@@ -1,10 +1,12 @@
 use std::path::{Path, PathBuf};
 
 use node_forge_render_server::asset_store::AssetStore;
+use node_forge_render_server::color;
 use node_forge_render_server::dsl;
 use node_forge_render_server::renderer;
 use node_forge_render_server::renderer::validation;
 use node_forge_render_server::state_machine::evenly_spaced_samples;
+use node_forge_render_server::testing;
 use rust_wgpu_fiber::{HeadlessRenderer, HeadlessRendererConfig};
 
 #[derive(Clone, Debug)]
@@ -136,21 +138,11 @@ fn resize_nearest_rgba8(src: &image::RgbaImage, w: u32, h: u32) -> image::RgbaIm
 }
 
 fn srgb_u8_to_linear_f32(x: u8) -> f32 {
-    let s = (x as f32) / 255.0;
-    if s <= 0.04045 {
-        s / 12.92
-    } else {
-        ((s + 0.055) / 1.055).powf(2.4)
-    }
+    color::srgb_to_linear((x as f32) / 255.0)
 }
 
 fn linear_f32_to_srgb_u8(x: f32) -> u8 {
-    let x = x.clamp(0.0, 1.0);
-    let s = if x <= 0.0031308 {
-        12.92 * x
-    } else {
-        1.055 * x.powf(1.0 / 2.4) - 0.055
-    };
+    let s = color::linear_to_srgb(x.clamp(0.0, 1.0));
     (s * 255.0).round().clamp(0.0, 255.0) as u8
 }
 
@@ -873,10 +865,32 @@ fn run_case(case: &Case) {
         out_result.display()
     );
 
+    // BLESS_BASELINES=1: the render is the new truth for static cases with a
+    // baseline; copy it over and record the diff instead of comparing.
+    let bless_baselines = std::env::var("BLESS_BASELINES").is_ok_and(|v| v != "0");
+    let blessed = match case.baseline_png {
+        Some(baseline_rel) if bless_baselines && !uses_time => {
+            let ext = if output_is_hdr { "exr" } else { "png" };
+            let baseline = expected_dir.join(baseline_rel).with_extension(ext);
+            let record = testing::bless_baseline(&out_result, &baseline)
+                .unwrap_or_else(|e| panic!("case {}: failed to bless baseline: {e:#}", case.name));
+            eprintln!(
+                "case {}: blessed {} ({:?})",
+                case.name,
+                baseline.display(),
+                record.outcome
+            );
+            true
+        }
+        _ => false,
+    };
+
     // Resolve the baseline path for the initial render:
     //   animated scenes → baseline_0.{ext}
     //   static  scenes → baseline.{ext}  (from case.baseline_png, typically "baseline.png")
-    let initial_baseline = if uses_time {
+    let initial_baseline = if blessed {
+        None
+    } else if uses_time {
         let ext = if output_is_hdr { "exr" } else { "png" };
         let p = expected_dir.join(format!("baseline_0.{ext}"));
         if p.exists() { Some(p) } else { None }
@@ -29,6 +29,7 @@ fn make_render_pass_scene(msaa_sample_count: serde_json::Value) -> SceneDSL {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     }
 }
 
@@ -63,6 +64,7 @@ fn scene(nodes: Vec<Node>, connections: Vec<Connection>) -> SceneDSL {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     }
 }
 
@@ -109,6 +111,7 @@ fn render_pass_msaa_default_is_applied_by_normalization() {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     };
 
     normalize_scene_defaults(&mut scene).expect("normalize scene defaults");
@@ -180,6 +183,32 @@ fn render_pass_depth_test_validation_rejects_non_boolean() {
     assert!(msg.contains("expected boolean"));
 }
 
+#[test]
+fn render_pass_stencil_validation_rejects_out_of_range_values() {
+    let scheme = load_default_scheme().expect("load default scheme");
+    for (params, field) in [
+        (json!({"stencilWrite": 0}), "RenderPass.stencilWrite"),
+        (json!({"stencilWrite": "yes"}), "RenderPass.stencilWrite"),
+        (json!({"stencilTest": 1}), "RenderPass.stencilTest"),
+        (json!({"stencilRef": 300}), "RenderPass.stencilRef"),
+    ] {
+        let scene = scene(vec![node("rp", "RenderPass", params.clone())], vec![]);
+        let err = validate_scene_against(&scene, &scheme)
+            .expect_err("invalid stencil params should fail");
+        assert!(format!("{err:#}").contains(field), "{params}");
+    }
+
+    let scene = scene(
+        vec![node(
+            "rp",
+            "RenderPass",
+            json!({"stencilWrite": 2, "stencilTest": "off", "stencilRef": 2}),
+        )],
+        vec![],
+    );
+    validate_scene_against(&scene, &scheme).expect("valid stencil params");
+}
+
 #[test]
 fn render_pass_depth_output_rejected_when_depth_test_is_false() {
     let scheme = load_default_scheme().expect("load default scheme");
@@ -63,6 +63,7 @@ fn base_scene() -> SceneDSL {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     }
 }
 
@@ -112,6 +113,7 @@ fn scene_update_replaces_cache() {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     };
 
     apply_scene_update(&mut cache, &scene2);
@@ -355,6 +357,7 @@ fn scene_delta_updates_intelligent_light_params() {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     };
     let mut cache = SceneCache::from_scene_update(&scene);
 
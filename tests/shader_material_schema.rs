@@ -78,6 +78,7 @@ fn scene(shader_input: NodePort, to_port: &str) -> SceneDSL {
         assets: Default::default(),
         state_machine: None,
         debug_artifacts: None,
+        feature_flags: Default::default(),
     }
 }
 